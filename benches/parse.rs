@@ -2,7 +2,7 @@
 
 extern crate test;
 
-use orgize::Org;
+use orgize::{elements::ElementType, Org, ParseConfig};
 use test::Bencher;
 
 #[bench]
@@ -28,3 +28,44 @@ fn org_faq(b: &mut Bencher) {
         Org::parse(include_str!("org-faq.org"));
     })
 }
+
+// a log-style file, with a logbook drawer and a table in each entry
+fn journal() -> String {
+    let mut text = String::new();
+    for day in 1..=28 {
+        for hour in 8..18 {
+            text += &format!(
+                "* DONE Entry {0} {1}\n\
+                 CLOSED: [2024-02-{0:02} Fri {1:02}:30]\n\
+                 :LOGBOOK:\n\
+                 - State \"DONE\"       from \"TODO\"       [2024-02-{0:02} Fri {1:02}:30]\n\
+                 CLOCK: [2024-02-{0:02} Fri {1:02}:00]--[2024-02-{0:02} Fri {1:02}:30] =>  0:30\n\
+                 :END:\n\
+                 Some *notes* about [[https://example.com][the entry]].\n\
+                 | a | b |\n|---+---|\n| 1 | 2 |\n",
+                day, hour
+            );
+        }
+    }
+    text
+}
+
+#[bench]
+fn journal_full(b: &mut Bencher) {
+    let text = journal();
+    b.iter(|| {
+        Org::parse(&text);
+    })
+}
+
+#[bench]
+fn journal_skipping(b: &mut Bencher) {
+    let text = journal();
+    let config = ParseConfig {
+        skip_elements: [ElementType::Drawer, ElementType::Table].into(),
+        ..Default::default()
+    };
+    b.iter(|| {
+        Org::parse_custom(&text, &config);
+    })
+}
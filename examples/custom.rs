@@ -5,8 +5,8 @@ use std::io::{Error as IOError, Write};
 use std::result::Result;
 use std::string::FromUtf8Error;
 
-use orgize::export::{DefaultHtmlHandler, HtmlHandler};
-use orgize::{Element, Org};
+use orgize::export::{DefaultHtmlHandler, ExportError, HtmlHandler};
+use orgize::{indextree::NodeId, Element, Org};
 use slugify::slugify;
 
 #[derive(Debug)]
@@ -29,10 +29,23 @@ impl From<FromUtf8Error> for MyError {
     }
 }
 
+// errors returned by exporters are wrapped in ExportError, which also
+// tells the element being written
+impl From<ExportError<MyError>> for MyError {
+    fn from(err: ExportError<MyError>) -> Self {
+        err.into_inner()
+    }
+}
+
 #[derive(Default)]
 struct MyHtmlHandler(DefaultHtmlHandler);
 
 impl HtmlHandler<MyError> for MyHtmlHandler {
+    // lets the default handler resolve links
+    fn prepare(&mut self, org: &Org) {
+        self.0.prepare(org);
+    }
+
     fn start<W: Write>(&mut self, mut w: W, element: &Element) -> Result<(), MyError> {
         if let Element::Title(title) = element {
             if title.level > 6 {
@@ -60,6 +73,31 @@ impl HtmlHandler<MyError> for MyHtmlHandler {
         }
         Ok(())
     }
+
+    // lets the default handler look up state of nodes, e.g. footnote sections
+    fn start_node<W: Write>(
+        &mut self,
+        w: W,
+        node: NodeId,
+        element: &Element,
+    ) -> Result<(), MyError> {
+        match element {
+            Element::Title(_) => self.start(w, element),
+            _ => Ok(self.0.start_node(w, node, element)?),
+        }
+    }
+
+    fn end_node<W: Write>(&mut self, w: W, node: NodeId, element: &Element) -> Result<(), MyError> {
+        match element {
+            Element::Title(_) => self.end(w, element),
+            _ => Ok(self.0.end_node(w, node, element)?),
+        }
+    }
+
+    // lets the default handler write footnote definitions
+    fn trailing_nodes(&mut self, org: &Org, root: NodeId) -> Vec<NodeId> {
+        self.0.trailing_nodes(org, root)
+    }
 }
 
 fn main() -> Result<(), MyError> {
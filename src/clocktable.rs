@@ -0,0 +1,496 @@
+use indextree::NodeId;
+use std::error::Error;
+use std::fmt;
+
+use crate::{
+    duration::OrgDuration,
+    elements::{Clock, Datetime, Element, HeaderArgs},
+    parsers::{parse_container, Container, OwnedArena},
+    Headline, Org,
+};
+
+/// Parameters of `Org::write_clocktable`
+///
+/// ```rust
+/// use orgize::{ClocktableBlock, ClocktableParams, ClocktableScope};
+///
+/// let params = ClocktableParams::parse(":scope subtree :maxlevel 2 :block 2024-03");
+///
+/// assert_eq!(params.scope, ClocktableScope::Subtree);
+/// assert_eq!(params.maxlevel, 2);
+/// assert_eq!(params.block, Some(ClocktableBlock::Month(2024, 3)));
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClocktableParams {
+    /// Deepest level of listed headlines, `:maxlevel`, default is `3`
+    ///
+    /// Clocks of deeper headlines are still counted in their ancestors.
+    pub maxlevel: usize,
+    /// Headlines to report, `:scope`, default is the whole file
+    pub scope: ClocktableScope,
+    /// Time range to report, `:block`, default is `None`, which reports
+    /// every closed clock
+    pub block: Option<ClocktableBlock>,
+    /// Tags match, `:tags`, e.g. `work-meeting|urgent`, default is `None`
+    ///
+    /// Alternatives are separated by `|`, and tags in an alternative are
+    /// required with `+` or excluded with `-`. Tags are inherited.
+    pub tags: Option<String>,
+    /// Current time, used by relative blocks like `today`, and written in
+    /// the caption of the table, default is `None`
+    pub now: Option<Datetime<'static>>,
+}
+
+impl Default for ClocktableParams {
+    fn default() -> Self {
+        ClocktableParams {
+            maxlevel: 3,
+            scope: ClocktableScope::File,
+            block: None,
+            tags: None,
+            now: None,
+        }
+    }
+}
+
+impl ClocktableParams {
+    /// Parses parameters of a clocktable, e.g. `:scope file :maxlevel 2` of
+    /// `#+BEGIN: clocktable :scope file :maxlevel 2`, see
+    /// `DynBlock::parameters`.
+    ///
+    /// Unknown parameters and values are ignored.
+    pub fn parse(input: &str) -> ClocktableParams {
+        ClocktableParams::from_args(&HeaderArgs::parse(input))
+    }
+
+    /// Likes `parse`, but reads already parsed parameters.
+    pub fn from_args(args: &HeaderArgs) -> ClocktableParams {
+        let mut params = ClocktableParams::default();
+        if let Some(maxlevel) = args.get("maxlevel").and_then(|s| s.parse().ok()) {
+            params.maxlevel = maxlevel;
+        }
+        if let Some(scope) = args.get("scope") {
+            params.scope = match scope {
+                "subtree" => ClocktableScope::Subtree,
+                _ => ClocktableScope::File,
+            };
+        }
+        params.block = args.get("block").and_then(ClocktableBlock::parse);
+        params.tags = args
+            .get("tags")
+            .map(|tags| tags.trim_matches('"'))
+            .filter(|tags| !tags.is_empty() && *tags != "nil")
+            .map(String::from);
+        params
+    }
+}
+
+/// Scope of a clocktable, see `ClocktableParams::scope`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClocktableScope {
+    /// Every headline of the document, `:scope file`
+    File,
+    /// The headline containing the clocktable and its descendants,
+    /// `:scope subtree`
+    Subtree,
+}
+
+/// Time range of a clocktable, see `ClocktableParams::block`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClocktableBlock {
+    /// `:block today`
+    Today,
+    /// `:block yesterday`
+    Yesterday,
+    /// `:block thisweek`, weeks start on Monday
+    ThisWeek,
+    /// `:block lastweek`
+    LastWeek,
+    /// `:block thismonth`
+    ThisMonth,
+    /// `:block lastmonth`
+    LastMonth,
+    /// `:block thisyear`
+    ThisYear,
+    /// A day, e.g. `:block 2024-03-08`
+    Day(u16, u8, u8),
+    /// A month, e.g. `:block 2024-03`
+    Month(u16, u8),
+    /// A year, e.g. `:block 2024`
+    Year(u16),
+}
+
+impl ClocktableBlock {
+    /// Parses a `:block` value, returns `None` if it's unknown.
+    pub fn parse(input: &str) -> Option<ClocktableBlock> {
+        let block = match input.trim() {
+            "today" => ClocktableBlock::Today,
+            "yesterday" => ClocktableBlock::Yesterday,
+            "thisweek" => ClocktableBlock::ThisWeek,
+            "lastweek" => ClocktableBlock::LastWeek,
+            "thismonth" => ClocktableBlock::ThisMonth,
+            "lastmonth" => ClocktableBlock::LastMonth,
+            "thisyear" => ClocktableBlock::ThisYear,
+            date => {
+                let parts = date
+                    .split('-')
+                    .map(|part| part.parse::<u16>().ok())
+                    .collect::<Option<Vec<_>>>()?;
+                match parts[..] {
+                    [year] => ClocktableBlock::Year(year),
+                    [year, month] if (1..=12).contains(&month) => {
+                        ClocktableBlock::Month(year, month as u8)
+                    }
+                    [year, month, day] if (1..=12).contains(&month) && (1..=31).contains(&day) => {
+                        ClocktableBlock::Day(year, month as u8, day as u8)
+                    }
+                    _ => return None,
+                }
+            }
+        };
+        Some(block)
+    }
+
+    // returns the first day and the day after the last day, as day numbers,
+    // or `None` if the block is relative and `now` is missing
+    fn range(self, now: Option<&Datetime>) -> Option<(i64, i64)> {
+        let range = match self {
+            ClocktableBlock::Day(year, month, day) => {
+                let day = day_number(year, month, day);
+                (day, day + 1)
+            }
+            ClocktableBlock::Month(year, month) => month_range(year, month),
+            ClocktableBlock::Year(year) => (day_number(year, 1, 1), day_number(year + 1, 1, 1)),
+            relative => {
+                let now = now?;
+                let today = day_number(now.year, now.month, now.day);
+                match relative {
+                    ClocktableBlock::Today => (today, today + 1),
+                    ClocktableBlock::Yesterday => (today - 1, today),
+                    ClocktableBlock::ThisWeek | ClocktableBlock::LastWeek => {
+                        let monday = today - now.weekday()? as i64;
+                        if relative == ClocktableBlock::ThisWeek {
+                            (monday, monday + 7)
+                        } else {
+                            (monday - 7, monday)
+                        }
+                    }
+                    ClocktableBlock::ThisMonth => month_range(now.year, now.month),
+                    ClocktableBlock::LastMonth if now.month == 1 => month_range(now.year - 1, 12),
+                    ClocktableBlock::LastMonth => month_range(now.year, now.month - 1),
+                    _ => ClocktableBlock::Year(now.year).range(None)?,
+                }
+            }
+        };
+        Some(range)
+    }
+}
+
+/// Error returned by `Org::write_clocktable`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ClocktableError {
+    /// The node isn't a dynamic block
+    NotADynamicBlock,
+    /// The block is relative, but `ClocktableParams::now` is missing or
+    /// isn't a valid date
+    MissingNow,
+}
+
+impl fmt::Display for ClocktableError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ClocktableError::NotADynamicBlock => write!(f, "clocktable must be a dynamic block"),
+            ClocktableError::MissingNow => write!(f, "relative clocktable block needs a date"),
+        }
+    }
+}
+
+impl Error for ClocktableError {}
+
+impl Org<'_> {
+    /// Replaces the contents of dynamic block `node` with a clock report,
+    /// like `org-clock-report` in Emacs.
+    ///
+    /// The report is a table of clocked headlines and their durations,
+    /// including their descendants. Each level has its own time column, and
+    /// the first row is the total time.
+    ///
+    /// ```rust
+    /// use orgize::{ClocktableParams, Element, Org};
+    ///
+    /// let mut org = Org::parse(
+    ///     "#+BEGIN: clocktable :maxlevel 2\n#+END:\n\
+    ///      * a\n\
+    ///      CLOCK: [2024-03-04 Mon 09:00]--[2024-03-04 Mon 10:30] =>  1:30\n\
+    ///      ** b\n\
+    ///      CLOCK: [2024-03-05 Tue 09:00]--[2024-03-05 Tue 09:45] =>  0:45\n",
+    /// );
+    /// let block = org
+    ///     .document()
+    ///     .section_node()
+    ///     .unwrap()
+    ///     .children(org.arena())
+    ///     .next()
+    ///     .unwrap();
+    /// let params = match &org[block] {
+    ///     Element::DynBlock(block) => ClocktableParams::from_args(&block.parameters()),
+    ///     _ => unreachable!(),
+    /// };
+    /// org.write_clocktable(block, &params).unwrap();
+    ///
+    /// let mut writer = Vec::new();
+    /// org.write_org(&mut writer).unwrap();
+    /// assert!(String::from_utf8(writer).unwrap().starts_with(
+    ///     "#+BEGIN: clocktable :maxlevel 2\n\
+    ///      | Headline     | Time   |      |\n\
+    ///      |--------------+--------+------|\n\
+    ///      | *Total time* | *2:15* |      |\n\
+    ///      |--------------+--------+------|\n\
+    ///      | a            | 2:15   |      |\n\
+    ///      | \\_  b        |        | 0:45 |\n\
+    ///      #+END:\n"
+    /// ));
+    /// ```
+    pub fn write_clocktable(
+        &mut self,
+        node: NodeId,
+        params: &ClocktableParams,
+    ) -> Result<(), ClocktableError> {
+        if !matches!(self[node], Element::DynBlock(_)) {
+            return Err(ClocktableError::NotADynamicBlock);
+        }
+        let range = match params.block {
+            Some(block) => Some(
+                block
+                    .range(params.now.as_ref())
+                    .ok_or(ClocktableError::MissingNow)?,
+            ),
+            None => None,
+        };
+
+        let scope = match params.scope {
+            ClocktableScope::Subtree => {
+                node.ancestors(&self.arena)
+                    .find_map(|node| match self[node] {
+                        Element::Headline { level } => Some(Headline::from_node(node, level, self)),
+                        _ => None,
+                    })
+            }
+            ClocktableScope::File => None,
+        };
+        let headlines: Vec<Headline> = match scope {
+            Some(headline) => std::iter::once(headline)
+                .chain(headline.descendants(self))
+                .collect(),
+            None => self.headlines().collect(),
+        };
+
+        let own: Vec<u64> = headlines
+            .iter()
+            .map(|&headline| {
+                let matched = params
+                    .tags
+                    .as_deref()
+                    .map_or(true, |tags| tags_match(self, headline, tags));
+                match headline.section_node() {
+                    Some(section) if matched => section
+                        .descendants(&self.arena)
+                        .filter_map(|node| match &self[node] {
+                            Element::Clock(clock) => clock_minutes(clock, range),
+                            _ => None,
+                        })
+                        .fold(0, u64::saturating_add),
+                    _ => 0,
+                }
+            })
+            .collect();
+
+        let mut rows = Vec::new();
+        for (i, headline) in headlines.iter().enumerate() {
+            let level = headline.level();
+            // the headline and its descendants, which are the following
+            // headlines of greater levels
+            let minutes = own[i]
+                + headlines[i + 1..]
+                    .iter()
+                    .zip(&own[i + 1..])
+                    .take_while(|(h, _)| h.level() > level)
+                    .map(|(_, minutes)| minutes)
+                    .sum::<u64>();
+            if minutes > 0 && level <= params.maxlevel {
+                let title = headline.title(self).raw.replace('|', "\\vert{}");
+                rows.push((level, title, minutes));
+            }
+        }
+        let total = own.iter().sum();
+
+        let content = clocktable(params, total, &rows);
+        self.set_dyn_block_content(node, &content);
+
+        Ok(())
+    }
+
+    // replaces children of dynamic block `node` with parsed `content`
+    pub(crate) fn set_dyn_block_content(&mut self, node: NodeId, content: &str) {
+        let config = self.config().clone();
+
+        let children: Vec<_> = node.children(&self.arena).collect();
+        for child in children {
+            child.detach(&mut self.arena);
+        }
+        if let Element::DynBlock(block) = &mut self[node] {
+            block.pre_blank = 0;
+        }
+        parse_container(
+            &mut OwnedArena::new(&mut self.arena),
+            Container::Block { node, content },
+            &config,
+        );
+
+        self.tree_changed();
+    }
+}
+
+// writes the table, rows are levels, titles and durations
+fn clocktable(params: &ClocktableParams, total: u64, rows: &[(usize, String, u64)]) -> String {
+    let columns = rows.iter().map(|(level, ..)| *level).max().unwrap_or(1);
+    let duration = |minutes| OrgDuration::from_minutes(minutes).to_string();
+
+    let mut table: Vec<Option<Vec<String>>> = Vec::new();
+    let mut row = vec!["Headline".to_string(), "Time".to_string()];
+    row.resize(columns + 1, String::new());
+    table.push(Some(row));
+    table.push(None);
+    let mut row = vec!["*Total time*".to_string(), format!("*{}*", duration(total))];
+    row.resize(columns + 1, String::new());
+    table.push(Some(row));
+    if !rows.is_empty() {
+        table.push(None);
+    }
+    for (level, title, minutes) in rows {
+        let mut row = vec![String::new(); columns + 1];
+        row[0] = if *level == 1 {
+            title.clone()
+        } else {
+            format!("\\_{}{}", " ".repeat(2 * (level - 1)), title)
+        };
+        row[*level] = duration(*minutes);
+        table.push(Some(row));
+    }
+
+    let mut output = String::new();
+    if let Some(now) = &params.now {
+        output += &format!("#+CAPTION: Clock summary at [{}]\n", now);
+    }
+    output += &aligned_table(&table);
+    output
+}
+
+// writes an aligned table, `None` rows are horizontal rules
+pub(crate) fn aligned_table(table: &[Option<Vec<String>>]) -> String {
+    let columns = table.iter().flatten().map(Vec::len).max().unwrap_or(0);
+    let mut widths = vec![1; columns];
+    for row in table.iter().flatten() {
+        for (width, cell) in widths.iter_mut().zip(row) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let mut output = String::new();
+    for row in table {
+        match row {
+            Some(row) => {
+                for (width, cell) in widths.iter().zip(row) {
+                    let padding = width - cell.chars().count();
+                    output += &format!("| {}{} ", cell, " ".repeat(padding));
+                }
+                output += "|\n";
+            }
+            None => {
+                let rules: Vec<_> = widths.iter().map(|width| "-".repeat(width + 2)).collect();
+                output += &format!("|{}|\n", rules.join("+"));
+            }
+        }
+    }
+    output
+}
+
+// returns minutes of a closed clock in `range`, the whole clock if there's
+// no range
+fn clock_minutes(clock: &Clock, range: Option<(i64, i64)>) -> Option<u64> {
+    let (start, end, duration) = match clock {
+        Clock::Closed {
+            start,
+            end,
+            duration,
+            ..
+        } => (start, end, duration),
+        Clock::Running { .. } => return None,
+    };
+    let (first, last) = match range {
+        Some(range) => range,
+        None => return Some(duration.minutes()),
+    };
+
+    let minutes = |datetime: &Datetime| {
+        day_number(datetime.year, datetime.month, datetime.day) * 1440
+            + i64::from(datetime.hour.unwrap_or(0)) * 60
+            + i64::from(datetime.minute.unwrap_or(0))
+    };
+    let start = minutes(start).max(first * 1440);
+    let end = minutes(end).min(last * 1440);
+    Some((end - start).max(0) as u64)
+}
+
+fn tags_match(org: &Org, headline: Headline, pattern: &str) -> bool {
+    let tags: Vec<&str> = std::iter::once(headline)
+        .chain(headline.ancestors(org))
+        .flat_map(|headline| headline.title(org).tags.iter().map(|tag| &**tag))
+        .collect();
+
+    pattern.split('|').any(|alternative| {
+        let mut rest = alternative.trim();
+        while !rest.is_empty() {
+            let (required, tail) = match rest.as_bytes()[0] {
+                b'+' => (true, &rest[1..]),
+                b'-' => (false, &rest[1..]),
+                _ => (true, rest),
+            };
+            let end = tail.find(['+', '-']).unwrap_or(tail.len());
+            let tag = tail[..end].trim_matches(':');
+            if !tag.is_empty() && tags.contains(&tag) != required {
+                return false;
+            }
+            rest = &tail[end..];
+        }
+        true
+    })
+}
+
+// days since 1970-01-01 of a proleptic gregorian date
+fn day_number(year: u16, month: u8, day: u8) -> i64 {
+    let (year, month, day) = (i64::from(year), i64::from(month), i64::from(day));
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+fn month_range(year: u16, month: u8) -> (i64, i64) {
+    let next = if month == 12 {
+        day_number(year + 1, 1, 1)
+    } else {
+        day_number(year, month + 1, 1)
+    };
+    (day_number(year, month, 1), next)
+}
+
+#[test]
+fn day_numbers() {
+    assert_eq!(day_number(1970, 1, 1), 0);
+    assert_eq!(day_number(2000, 3, 1), 11017);
+    assert_eq!(day_number(2024, 3, 1) - day_number(2024, 2, 1), 29);
+    assert_eq!(month_range(2023, 12), (19692, 19723));
+}
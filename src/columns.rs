@@ -0,0 +1,565 @@
+use indextree::NodeId;
+use std::collections::HashMap;
+use std::error::Error;
+use std::fmt;
+
+use crate::{
+    clocktable::aligned_table,
+    duration::OrgDuration,
+    elements::{Element, HeaderArgs},
+    Headline, Org,
+};
+
+const DEFAULT_COLUMNS: &str = "%25ITEM %TODO %3PRIORITY %TAGS";
+
+/// Column of a column view, one `%` entry of `#+COLUMNS`
+///
+/// ```rust
+/// use orgize::{ColumnSpec, ColumnSummary};
+///
+/// let specs = ColumnSpec::parse_all("%25ITEM %TODO %Effort(Estimate){:}");
+///
+/// assert_eq!(specs.len(), 3);
+/// assert_eq!(specs[0].width, Some(25));
+/// assert_eq!(specs[0].property, "ITEM");
+/// assert_eq!(specs[2].title.as_deref(), Some("Estimate"));
+/// assert_eq!(specs[2].summary, Some(ColumnSummary::Time));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ColumnSpec {
+    /// Width of the column, e.g. `25` of `%25ITEM`
+    pub width: Option<usize>,
+    /// Property of the column, e.g. `Effort`, or one of the special
+    /// properties `ITEM`, `TODO`, `PRIORITY`, `TAGS`, `SCHEDULED`,
+    /// `DEADLINE` and `CLOCKSUM`
+    pub property: String,
+    /// Title of the column, e.g. `Estimate` of `%Effort(Estimate)`
+    pub title: Option<String>,
+    /// Summary of the column, e.g. `{:}`
+    pub summary: Option<ColumnSummary>,
+}
+
+/// Summary operator of a column, see `ColumnSpec::summary`
+///
+/// A headline whose children have values in the column shows the summary
+/// of these values instead of its own.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ColumnSummary {
+    /// Sum of numbers, `{+}`
+    Sum,
+    /// Sum of durations, e.g. efforts, `{:}`
+    Time,
+    /// `[X]` if every child is checked, `[ ]` otherwise, `{X}`
+    Checkbox,
+    /// Number of checked children, e.g. `[1/3]`, `{X/}`
+    CheckboxCount,
+    /// Percentage of checked children, e.g. `[33%]`, `{X%}`
+    CheckboxPercent,
+    /// Smallest number, `{min}`
+    Min,
+    /// Largest number, `{max}`
+    Max,
+    /// Arithmetic mean of numbers, `{mean}`
+    Mean,
+}
+
+impl ColumnSummary {
+    /// Parses a summary operator, e.g. `X/` of `{X/}`, returns `None` if
+    /// it's unknown.
+    ///
+    /// A format after `;`, e.g. `+;%.1f`, is ignored.
+    pub fn parse(input: &str) -> Option<ColumnSummary> {
+        let operator = input.split(';').next().unwrap_or_default().trim();
+        let summary = match operator {
+            "+" => ColumnSummary::Sum,
+            ":" => ColumnSummary::Time,
+            "X" => ColumnSummary::Checkbox,
+            "X/" => ColumnSummary::CheckboxCount,
+            "X%" => ColumnSummary::CheckboxPercent,
+            "min" => ColumnSummary::Min,
+            "max" => ColumnSummary::Max,
+            "mean" => ColumnSummary::Mean,
+            _ => return None,
+        };
+        Some(summary)
+    }
+
+    // summarizes values of children, `None` if there isn't any usable one
+    fn summarize(self, values: &[&str]) -> Option<String> {
+        let numbers = || {
+            values
+                .iter()
+                .filter_map(|value| value.trim().parse::<f64>().ok())
+        };
+        let checked = || values.iter().filter(|value| is_checked(value)).count();
+
+        let summary = match self {
+            ColumnSummary::Sum => format_number(numbers().sum()),
+            ColumnSummary::Time => values
+                .iter()
+                .filter_map(|value| value.parse::<OrgDuration>().ok())
+                .sum::<OrgDuration>()
+                .to_string(),
+            ColumnSummary::Checkbox if checked() == values.len() => "[X]".into(),
+            ColumnSummary::Checkbox => "[ ]".into(),
+            ColumnSummary::CheckboxCount => format!("[{}/{}]", checked(), values.len()),
+            ColumnSummary::CheckboxPercent => {
+                format!("[{}%]", checked() * 100 / values.len())
+            }
+            ColumnSummary::Min => format_number(numbers().reduce(f64::min)?),
+            ColumnSummary::Max => format_number(numbers().reduce(f64::max)?),
+            ColumnSummary::Mean => {
+                let (sum, count) =
+                    numbers().fold((0., 0.), |(sum, count), n| (sum + n, count + 1.));
+                if count == 0. {
+                    return None;
+                }
+                format_number(sum / count)
+            }
+        };
+        Some(summary)
+    }
+}
+
+impl ColumnSpec {
+    /// Parses column specs of a `#+COLUMNS` value, e.g.
+    /// `%25ITEM %TODO %3PRIORITY %Effort(Est){:}`.
+    ///
+    /// Anything that isn't a spec is skipped.
+    pub fn parse_all(input: &str) -> Vec<ColumnSpec> {
+        let mut specs = Vec::new();
+        let mut rest = input;
+
+        while let Some(start) = rest.find('%') {
+            rest = &rest[start + 1..];
+
+            let digits = rest
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len());
+            let width = rest[..digits].parse().ok();
+            rest = &rest[digits..];
+
+            let end = rest
+                .find(|c: char| !c.is_alphanumeric() && c != '_' && c != '-')
+                .unwrap_or(rest.len());
+            if end == 0 {
+                continue;
+            }
+            let property = rest[..end].to_string();
+            rest = &rest[end..];
+
+            let mut title = None;
+            if let Some(tail) = rest.strip_prefix('(') {
+                if let Some(end) = tail.find(')') {
+                    title = Some(tail[..end].to_string());
+                    rest = &tail[end + 1..];
+                }
+            }
+            let mut summary = None;
+            if let Some(tail) = rest.strip_prefix('{') {
+                if let Some(end) = tail.find('}') {
+                    summary = ColumnSummary::parse(&tail[..end]);
+                    rest = &tail[end + 1..];
+                }
+            }
+
+            specs.push(ColumnSpec {
+                width,
+                property,
+                title,
+                summary,
+            });
+        }
+
+        specs
+    }
+
+    // value of this column of `headline`, without summary
+    fn value(&self, headline: Headline, org: &Org) -> Option<String> {
+        let title = headline.title(org);
+        let property = &*self.property;
+        let value = if property.eq_ignore_ascii_case("ITEM") {
+            title.raw.to_string()
+        } else if property.eq_ignore_ascii_case("TODO") {
+            title.keyword.as_deref()?.to_string()
+        } else if property.eq_ignore_ascii_case("PRIORITY") {
+            title.priority?.to_string()
+        } else if property.eq_ignore_ascii_case("TAGS") {
+            if title.tags.is_empty() {
+                return None;
+            }
+            format!(":{}:", title.tags.join(":"))
+        } else if property.eq_ignore_ascii_case("SCHEDULED") {
+            title.scheduled()?.to_string()
+        } else if property.eq_ignore_ascii_case("DEADLINE") {
+            title.deadline()?.to_string()
+        } else if property.eq_ignore_ascii_case("CLOCKSUM") {
+            let sum = headline.clock_sum(org);
+            if sum.minutes() == 0 {
+                return None;
+            }
+            sum.to_string()
+        } else {
+            title.properties.get(property)?.to_string()
+        };
+        Some(value)
+    }
+}
+
+/// Row of a column view, see `Org::column_view`
+#[derive(Debug, Clone)]
+pub struct ColumnRow {
+    /// Headline of the row
+    pub headline: Headline,
+    /// Value of each column, `None` if the headline has none
+    pub values: Vec<Option<String>>,
+}
+
+impl Org<'_> {
+    /// Returns column specs of the last `#+COLUMNS` keyword, or
+    /// `%25ITEM %TODO %3PRIORITY %TAGS` if there's none.
+    pub fn column_specs(&self) -> Vec<ColumnSpec> {
+        let columns = self
+            .keywords()
+            .filter(|keyword| keyword.key.eq_ignore_ascii_case("COLUMNS"))
+            .map(|keyword| keyword.value.trim())
+            .filter(|value| !value.is_empty())
+            .last()
+            .unwrap_or(DEFAULT_COLUMNS);
+        ColumnSpec::parse_all(columns)
+    }
+
+    /// Evaluates `specs` for every headline, like column view in Emacs.
+    ///
+    /// Rows are in document order. A column with a summary shows, for each
+    /// headline with children that have values, the summary of these
+    /// values, which are summaries themselves if the children have
+    /// children. `CLOCKSUM` already includes clocks of descendants, so its
+    /// summary is ignored.
+    ///
+    /// ```rust
+    /// use orgize::{ColumnSpec, Org};
+    ///
+    /// let org = Org::parse(
+    ///     "* Project\n\
+    ///      ** TODO a\n:PROPERTIES:\n:Effort: 0:30\n:END:\n\
+    ///      ** TODO b\n:PROPERTIES:\n:Effort: 1:15\n:END:\n",
+    /// );
+    /// let rows = org.column_view(&ColumnSpec::parse_all("%ITEM %TODO %Effort{:}"));
+    ///
+    /// assert_eq!(rows.len(), 3);
+    /// assert_eq!(rows[0].values, [Some("Project".into()), None, Some("1:45".into())]);
+    /// assert_eq!(rows[1].values[1].as_deref(), Some("TODO"));
+    /// ```
+    pub fn column_view(&self, specs: &[ColumnSpec]) -> Vec<ColumnRow> {
+        let headlines: Vec<Headline> = self.headlines().collect();
+        let index: HashMap<_, _> = headlines
+            .iter()
+            .enumerate()
+            .map(|(i, headline)| (headline.headline_node(), i))
+            .collect();
+
+        let mut rows: Vec<ColumnRow> = headlines
+            .iter()
+            .map(|&headline| ColumnRow {
+                headline,
+                values: specs
+                    .iter()
+                    .map(|spec| spec.value(headline, self))
+                    .collect(),
+            })
+            .collect();
+
+        // children come after their parent, so they're summarized first
+        for i in (0..rows.len()).rev() {
+            let children: Vec<usize> = headlines[i]
+                .children(self)
+                .filter_map(|child| index.get(&child.headline_node()).copied())
+                .collect();
+            for (column, spec) in specs.iter().enumerate() {
+                let summary = match spec.summary {
+                    Some(_) if spec.property.eq_ignore_ascii_case("CLOCKSUM") => continue,
+                    Some(summary) => summary,
+                    None => continue,
+                };
+                let values: Vec<&str> = children
+                    .iter()
+                    .filter_map(|&child| rows[child].values[column].as_deref())
+                    .collect();
+                if values.is_empty() {
+                    continue;
+                }
+                if let Some(value) = summary.summarize(&values) {
+                    rows[i].values[column] = Some(value);
+                }
+            }
+        }
+
+        rows
+    }
+}
+
+/// Parameters of `Org::write_columnview`
+///
+/// ```rust
+/// use orgize::{ColumnviewParams, ColumnviewScope};
+///
+/// let params = ColumnviewParams::parse(":id local :hlines 1 :indent t");
+///
+/// assert_eq!(params.id, ColumnviewScope::Local);
+/// assert_eq!(params.hlines, Some(1));
+/// assert!(params.indent);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ColumnviewParams {
+    /// Headlines to report, `:id`, default is the local subtree
+    pub id: ColumnviewScope,
+    /// Adds a horizontal rule before headlines of this level or less,
+    /// `:hlines`, default is `None`
+    ///
+    /// `:hlines t` adds one before every headline.
+    pub hlines: Option<usize>,
+    /// Indents the `ITEM` column by level, `:indent`, default is `false`
+    pub indent: bool,
+    /// Deepest level of listed headlines, `:maxlevel`, default is `None`
+    pub maxlevel: Option<usize>,
+    /// Column specs, `:format`, default is `None`, which uses
+    /// `Org::column_specs`
+    pub format: Option<String>,
+}
+
+impl ColumnviewParams {
+    /// Parses parameters of a column view, e.g. `:id local :hlines 1` of
+    /// `#+BEGIN: columnview :id local :hlines 1`, see
+    /// `DynBlock::parameters`.
+    ///
+    /// Unknown parameters and values are ignored.
+    pub fn parse(input: &str) -> ColumnviewParams {
+        ColumnviewParams::from_args(&HeaderArgs::parse(input))
+    }
+
+    /// Like `parse`, but reads already parsed parameters.
+    pub fn from_args(args: &HeaderArgs) -> ColumnviewParams {
+        let value = |name| {
+            args.get(name)
+                .map(|value| value.trim_matches('"'))
+                .filter(|value| !value.is_empty() && *value != "nil")
+        };
+        ColumnviewParams {
+            id: match value("id") {
+                Some("local") | None => ColumnviewScope::Local,
+                Some("global") => ColumnviewScope::File,
+                Some(id) => ColumnviewScope::Id(id.to_string()),
+            },
+            hlines: match value("hlines") {
+                Some("t") => Some(usize::MAX),
+                hlines => hlines.and_then(|s| s.parse().ok()),
+            },
+            indent: value("indent").is_some(),
+            maxlevel: value("maxlevel").and_then(|s| s.parse().ok()),
+            format: value("format").map(String::from),
+        }
+    }
+}
+
+/// Headlines of a column view, see `ColumnviewParams::id`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub enum ColumnviewScope {
+    /// The headline containing the block and its descendants, or the
+    /// whole file if there's none, `:id local`
+    #[default]
+    Local,
+    /// Every headline of the document, `:id global`
+    File,
+    /// The headline with this `ID` or `CUSTOM_ID` property and its
+    /// descendants, e.g. `:id "intro"`
+    Id(String),
+}
+
+/// Error returned by `Org::write_columnview`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ColumnviewError {
+    /// The node isn't a dynamic block
+    NotADynamicBlock,
+    /// No headline has the `:id` of the block
+    HeadlineNotFound(String),
+}
+
+impl fmt::Display for ColumnviewError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ColumnviewError::NotADynamicBlock => write!(f, "columnview must be a dynamic block"),
+            ColumnviewError::HeadlineNotFound(id) => write!(f, "no headline with id {:?}", id),
+        }
+    }
+}
+
+impl Error for ColumnviewError {}
+
+impl Org<'_> {
+    /// Replaces the contents of dynamic block `node` with a column view
+    /// table, like `org-dblock-write:columnview` in Emacs.
+    ///
+    /// The first row holds column titles, and each following row is a
+    /// headline of the scope, see `Org::column_view`. `|` in values is
+    /// written as `\vert{}`.
+    ///
+    /// ```rust
+    /// use orgize::{ColumnviewParams, Element, Org};
+    ///
+    /// let mut org = Org::parse(
+    ///     "#+COLUMNS: %ITEM(Task) %Effort{:}\n\
+    ///      * Project\n\
+    ///      #+BEGIN: columnview :id local :indent t\n#+END:\n\
+    ///      ** a\n:PROPERTIES:\n:Effort: 0:30\n:END:\n\
+    ///      ** b\n:PROPERTIES:\n:Effort: 1:15\n:END:\n",
+    /// );
+    /// let block = org
+    ///     .headlines()
+    ///     .next()
+    ///     .unwrap()
+    ///     .section_node()
+    ///     .unwrap()
+    ///     .children(org.arena())
+    ///     .next()
+    ///     .unwrap();
+    /// let params = match &org[block] {
+    ///     Element::DynBlock(block) => ColumnviewParams::from_args(&block.parameters()),
+    ///     _ => unreachable!(),
+    /// };
+    /// org.write_columnview(block, &params).unwrap();
+    ///
+    /// let mut writer = Vec::new();
+    /// org.write_org(&mut writer).unwrap();
+    /// assert!(String::from_utf8(writer).unwrap().contains(
+    ///     "#+BEGIN: columnview :id local :indent t\n\
+    ///      | Task    | Effort |\n\
+    ///      |---------+--------|\n\
+    ///      | Project | 1:45   |\n\
+    ///      | \\_  a   | 0:30   |\n\
+    ///      | \\_  b   | 1:15   |\n\
+    ///      #+END:\n"
+    /// ));
+    /// ```
+    pub fn write_columnview(
+        &mut self,
+        node: NodeId,
+        params: &ColumnviewParams,
+    ) -> Result<(), ColumnviewError> {
+        if !matches!(self[node], Element::DynBlock(_)) {
+            return Err(ColumnviewError::NotADynamicBlock);
+        }
+
+        let scope = match &params.id {
+            ColumnviewScope::Local => {
+                node.ancestors(&self.arena)
+                    .find_map(|node| match self[node] {
+                        Element::Headline { level } => Some(Headline::from_node(node, level, self)),
+                        _ => None,
+                    })
+            }
+            ColumnviewScope::File => None,
+            ColumnviewScope::Id(id) => Some(
+                self.headlines()
+                    .find(|headline| {
+                        let properties = &headline.title(self).properties;
+                        properties.get("ID") == Some(id) || properties.get("CUSTOM_ID") == Some(id)
+                    })
+                    .ok_or_else(|| ColumnviewError::HeadlineNotFound(id.clone()))?,
+            ),
+        };
+        let specs = match &params.format {
+            Some(format) => ColumnSpec::parse_all(format),
+            None => self.column_specs(),
+        };
+
+        let mut rows = self.column_view(&specs);
+        if let Some(scope) = scope {
+            let start = rows
+                .iter()
+                .position(|row| row.headline.headline_node() == scope.headline_node())
+                .unwrap_or(rows.len());
+            let end = rows[start..]
+                .iter()
+                .skip(1)
+                .position(|row| row.headline.level() <= scope.level())
+                .map_or(rows.len(), |end| start + 1 + end);
+            rows.truncate(end);
+            rows.drain(..start);
+        }
+        let top = rows.first().map_or(1, |row| row.headline.level());
+
+        let mut table = vec![
+            Some(
+                specs
+                    .iter()
+                    .map(|spec| spec.title.clone().unwrap_or_else(|| spec.property.clone()))
+                    .collect(),
+            ),
+            None,
+        ];
+        for row in rows {
+            let level = row.headline.level();
+            if params.maxlevel.is_some_and(|maxlevel| level > maxlevel) {
+                continue;
+            }
+            if params.hlines.is_some_and(|hlines| level <= hlines) && table.last() != Some(&None) {
+                table.push(None);
+            }
+            let cells = specs
+                .iter()
+                .zip(row.values)
+                .map(|(spec, value)| {
+                    let value = value.unwrap_or_default().replace('|', "\\vert{}");
+                    let depth = level.saturating_sub(top);
+                    if params.indent && depth > 0 && spec.property.eq_ignore_ascii_case("ITEM") {
+                        format!("\\_{}{}", " ".repeat(2 * depth), value)
+                    } else {
+                        value
+                    }
+                })
+                .collect();
+            table.push(Some(cells));
+        }
+
+        let content = aligned_table(&table);
+        self.set_dyn_block_content(node, &content);
+
+        Ok(())
+    }
+}
+
+// `[X]`, a complete count like `[2/2]`, or `[100%]`
+fn is_checked(value: &str) -> bool {
+    let inner = match value
+        .trim()
+        .strip_prefix('[')
+        .and_then(|s| s.strip_suffix(']'))
+    {
+        Some(inner) => inner,
+        None => return false,
+    };
+    match inner.split_once('/') {
+        Some((done, total)) => done == total && done.parse::<u64>().is_ok_and(|n| n > 0),
+        None => inner == "X" || inner == "100%",
+    }
+}
+
+fn format_number(number: f64) -> String {
+    if number.fract() == 0. && number.abs() < 1e15 {
+        format!("{}", number as i64)
+    } else {
+        format!("{}", number)
+    }
+}
+
+#[test]
+fn checked() {
+    assert!(is_checked("[X]"));
+    assert!(is_checked("[2/2]"));
+    assert!(is_checked("[100%]"));
+    assert!(!is_checked("[ ]"));
+    assert!(!is_checked("[0/0]"));
+    assert!(!is_checked("[1/2]"));
+    assert!(!is_checked("X"));
+}
@@ -1,18 +1,993 @@
+use std::any::Any;
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+use std::sync::Arc;
+
+use crate::elements::{CustomSource, ElementKind, ElementTypeSet};
+
 /// Parse configuration
-#[derive(Clone, Debug)]
+///
+/// Prefer creating it with [`ParseConfig::builder`], which validates the
+/// configuration, or with one of the presets. It can still be constructed
+/// literally.
+///
+/// ```rust
+/// use orgize::{ParseConfig, Strictness};
+///
+/// let config = ParseConfig::builder()
+///     .todo_sequence("TODO(t) NEXT(n) | DONE(d)")
+///     .strictness(Strictness::Warn)
+///     .build()
+///     .unwrap();
+///
+/// assert!(config.todo_keyword("NEXT").is_some());
+/// ```
+///
+/// [`ParseConfig::builder`]: #method.builder
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub struct ParseConfig {
-    /// Headline's todo keywords
+    /// Headline's todo keywords, not-done ones and done ones
+    ///
+    /// Keywords can have a fast-access key and logging markers, like
+    /// `WAIT(w@/!)`, see [`TodoKeyword`]. Headlines are matched against the
+    /// keyword name only. Overridden by `#+TODO:`, `#+SEQ_TODO:` and
+    /// `#+TYP_TODO:` keywords in the document.
+    ///
+    /// [`TodoKeyword`]: struct.TodoKeyword.html
     pub todo_keywords: (Vec<String>, Vec<String>),
+    /// How to handle malformed constructs, default is `Strictness::Lenient`
+    ///
+    /// See [`ParseError`] for the list of constructs considered as malformed.
+    ///
+    /// [`ParseError`]: enum.ParseError.html
+    pub strictness: Strictness,
+    /// Tolerates headline metadata in non-canonical arrangement, default is
+    /// `None`
+    ///
+    /// By default, like Emacs, the planning line must be right after the
+    /// headline, and the property drawer right after the headline or the
+    /// planning line. If it's `Some(n)`, up to `n` blank lines are allowed
+    /// before each of them, and the property drawer can also come before the
+    /// planning line.
+    pub lenient_headline_metadata: Option<usize>,
+    /// Parses legacy `[1]` footnote definitions at the beginning of a line,
+    /// like `[fn:1]`, default is `false`
+    pub legacy_footnote_definitions: bool,
+    /// Maximum number of stars of a headline, default is `None`, i.e.
+    /// unlimited like Emacs
+    ///
+    /// Lines with more stars, like a separator made of `*`, are parsed as
+    /// regular text.
+    pub max_headline_level: Option<usize>,
+    /// Options of `#+STARTUP:` keywords, default is `Startup::default()`
+    ///
+    /// Overridden by `#+STARTUP:` keywords in the document.
+    pub startup: Startup,
+    /// Which `:NAME:` lines start a drawer, default is `DrawerPolicy::Any`
+    pub drawer_names: DrawerPolicy,
+    /// Parser of custom elements, called with every special block and
+    /// keyword, default is `None`
+    ///
+    /// See [`CustomParser`].
+    ///
+    /// [`CustomParser`]: struct.CustomParser.html
+    pub custom_parser: Option<CustomParser>,
+    /// Rejects doubtful bold, italic, underline and strike-through objects,
+    /// default is `None`, which follows Org
+    ///
+    /// If it's `Some(n)`, their contents are at most `n` characters long,
+    /// and none of their lines ends with `:`, like the introduction of a
+    /// pasted list. It avoids stray markers, e.g. `*` used as a bullet or
+    /// a footnote marker in pasted markdown, pairing with another marker
+    /// many words later. Verbatim and code aren't affected.
+    pub conservative_emphasis: Option<usize>,
+    /// Kinds of elements which are recognized but not parsed, default is
+    /// empty
+    ///
+    /// Each of them becomes an `Element::Skipped` holding its source text,
+    /// and nothing inside it is parsed, e.g. timestamps in a `:LOGBOOK:`
+    /// drawer. It speeds up parsing large files when some elements are
+    /// never used, and org export still writes them unchanged.
+    ///
+    /// Blocks, clocks, comments, drawers, fixed-width lines, footnote
+    /// definitions and tables can be skipped, other kinds are parsed
+    /// anyway. Property drawers of headlines are part of their title, and
+    /// are always parsed.
+    pub skip_elements: ElementTypeSet,
+    /// Maximum length in bytes of a line parsed structurally, default is
+    /// `None`, i.e. unlimited
+    ///
+    /// Longer lines never start a headline or another element, and
+    /// paragraphs containing one are a single text object.
+    pub max_line_length: Option<usize>,
+    /// Maximum number of objects right inside a paragraph, a title, a table
+    /// cell or another object, default is `None`, i.e. unlimited
+    ///
+    /// The text after the last allowed object becomes a single text object.
+    pub max_objects: Option<usize>,
+    /// Maximum number of nodes in the arena, default is `None`, i.e.
+    /// unlimited
+    ///
+    /// Once the arena has this many nodes, contents of sections, blocks and
+    /// objects which aren't parsed yet become plain text, i.e. a paragraph
+    /// with a text object, or a text object. Headlines are still parsed, so
+    /// the arena can grow a little beyond the limit. It's also reported as
+    /// `ParseError::TooManyNodes` unless `strictness` is
+    /// `Strictness::Lenient`.
+    pub max_nodes: Option<usize>,
+}
+
+/// Parser of custom elements, see `ParseConfig::custom_parser`
+///
+/// It's called with every special block and keyword, except `#+CALL:`. If
+/// it returns a payload, the block or keyword becomes an `Element::Custom`
+/// holding it, otherwise it's parsed as usual.
+///
+/// ```rust
+/// use orgize::{
+///     elements::{CustomSource, Element},
+///     CustomParser, Org, ParseConfig,
+/// };
+///
+/// let config = ParseConfig {
+///     custom_parser: Some(CustomParser::new(|source| match source {
+///         CustomSource::Keyword { key, value, .. } if key.eq_ignore_ascii_case("ROAM_ALIAS") => {
+///             Some(Box::new(value.to_string()))
+///         }
+///         _ => None,
+///     })),
+///     ..Default::default()
+/// };
+///
+/// let org = Org::parse_custom("#+ROAM_ALIAS: notes\n#+TITLE: Notes\n", &config);
+/// let aliases: Vec<_> = org
+///     .arena()
+///     .iter()
+///     .filter_map(|node| match node.get() {
+///         Element::Custom(custom) => custom.payload::<String>(),
+///         _ => None,
+///     })
+///     .collect();
+///
+/// assert_eq!(aliases, ["notes"]);
+/// assert_eq!(org.keywords().count(), 1);
+/// ```
+#[derive(Clone)]
+pub struct CustomParser(Arc<CustomParserFn>);
+
+type CustomParserFn = dyn Fn(&CustomSource) -> Option<Box<dyn Any + Send + Sync>> + Send + Sync;
+
+impl CustomParser {
+    /// Wraps function `f` as a custom parser.
+    pub fn new<F>(f: F) -> Self
+    where
+        F: Fn(&CustomSource) -> Option<Box<dyn Any + Send + Sync>> + Send + Sync + 'static,
+    {
+        CustomParser(Arc::new(f))
+    }
+
+    pub(crate) fn parse(&self, source: &CustomSource) -> Option<Box<dyn Any + Send + Sync>> {
+        (self.0)(source)
+    }
+}
+
+impl fmt::Debug for CustomParser {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("CustomParser(..)")
+    }
+}
+
+// parsers are compared by identity
+impl PartialEq for CustomParser {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl Eq for CustomParser {}
+
+/// Options of `#+STARTUP:` keywords which affect parsing and editing
+///
+/// ```rust
+/// use orgize::{Org, TodoLog};
+///
+/// let org = Org::parse("#+STARTUP: odd lognotedone overview\n* a\n*** b\n");
+/// let startup = org.startup();
+///
+/// assert!(startup.odd_levels);
+/// assert_eq!(startup.log_done, Some(TodoLog::Note));
+/// assert_eq!(startup.other, ["overview"]);
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Startup {
+    /// Only odd headline levels are used, set by `odd` and unset by
+    /// `oddeven`, default is `false`
+    ///
+    /// Headline levels are reduced like Emacs, i.e. `*`, `***` and `*****`
+    /// are level 1, 2 and 3. Headlines are written with odd numbers of
+    /// stars.
+    pub odd_levels: bool,
+    /// What to record when a headline is done, see `Headline::complete`,
+    /// default is `Some(TodoLog::Timestamp)`
+    ///
+    /// Set by `logdone`, `lognotedone` and `nologdone`. `None` records
+    /// nothing, not even `CLOSED:`.
+    pub log_done: Option<TodoLog>,
+    /// Custom time formats are used for timestamps, set by `customtime`,
+    /// default is `false`
+    pub custom_time: bool,
+    /// Other words, e.g. folding options, kept as-is
+    pub other: Vec<String>,
+}
+
+impl Default for Startup {
+    fn default() -> Self {
+        Startup {
+            odd_levels: false,
+            log_done: Some(TodoLog::Timestamp),
+            custom_time: false,
+            other: Vec::new(),
+        }
+    }
+}
+
+impl Startup {
+    /// Applies the value of a `#+STARTUP:` keyword, later words win.
+    pub fn apply(&mut self, value: &str) {
+        for word in value.split_whitespace() {
+            match word {
+                "odd" => self.odd_levels = true,
+                "oddeven" => self.odd_levels = false,
+                "logdone" => self.log_done = Some(TodoLog::Timestamp),
+                "lognotedone" => self.log_done = Some(TodoLog::Note),
+                "nologdone" => self.log_done = None,
+                "customtime" => self.custom_time = true,
+                _ => self.other.push(word.to_string()),
+            }
+        }
+    }
+
+    /// Returns the level of a headline with `stars` stars.
+    pub fn level(&self, stars: usize) -> usize {
+        if self.odd_levels {
+            stars / 2 + 1
+        } else {
+            stars
+        }
+    }
+
+    /// Returns the number of stars of a headline at `level`.
+    pub fn stars(&self, level: usize) -> usize {
+        if self.odd_levels {
+            (level * 2).saturating_sub(1)
+        } else {
+            level
+        }
+    }
+}
+
+/// Handling of malformed constructs
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum Strictness {
+    /// Parses malformed constructs as paragraphs silently
+    #[default]
+    Lenient,
+    /// Parses malformed constructs as paragraphs, and collects a `ParseError`
+    /// for each of them, which can be retrieved by `Org::diagnostics`
+    Warn,
+    /// Like `Warn`, but `Org::try_parse_custom` returns the first `ParseError`
+    /// as an error
+    Strict,
+}
+
+/// Drawer names recognized by the parser, see `ParseConfig::drawer_names`
+///
+/// Names are compared case-insensitively. Property drawers of headlines are
+/// always recognized.
+///
+/// ```rust
+/// use orgize::{DrawerPolicy, Element, Event, Org, ParseConfig};
+///
+/// let config = ParseConfig {
+///     drawer_names: DrawerPolicy::OnlyKnown(vec!["LOGBOOK".into()]),
+///     ..Default::default()
+/// };
+///
+/// let org = Org::parse_custom(":0830:\nbreakfast\n:END:\n", &config);
+/// assert!(!org
+///     .iter()
+///     .any(|event| matches!(event, Event::Start(Element::Drawer(_)))));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum DrawerPolicy {
+    /// Any name made of word characters, hyphens and underscores, like Emacs
+    #[default]
+    Any,
+    /// Only the listed names
+    OnlyKnown(Vec<String>),
+    /// `PROPERTIES` and `LOGBOOK`, plus the listed names
+    KnownPlus(Vec<String>),
+}
+
+impl DrawerPolicy {
+    /// Returns `true` if `name` starts a drawer under this policy.
+    ///
+    /// ```rust
+    /// use orgize::DrawerPolicy;
+    ///
+    /// let policy = DrawerPolicy::KnownPlus(vec!["RESULTS".into()]);
+    ///
+    /// assert!(policy.allows("logbook"));
+    /// assert!(policy.allows("RESULTS"));
+    /// assert!(!policy.allows("NOTES"));
+    /// ```
+    pub fn allows(&self, name: &str) -> bool {
+        let listed = |names: &[String]| names.iter().any(|n| n.eq_ignore_ascii_case(name));
+
+        match self {
+            DrawerPolicy::Any => true,
+            DrawerPolicy::OnlyKnown(names) => listed(names),
+            DrawerPolicy::KnownPlus(names) => {
+                name.eq_ignore_ascii_case("PROPERTIES")
+                    || name.eq_ignore_ascii_case("LOGBOOK")
+                    || listed(names)
+            }
+        }
+    }
 }
 
 impl Default for ParseConfig {
     fn default() -> Self {
         ParseConfig {
             todo_keywords: (vec![String::from("TODO")], vec![String::from("DONE")]),
+            strictness: Strictness::Lenient,
+            lenient_headline_metadata: None,
+            legacy_footnote_definitions: false,
+            max_headline_level: None,
+            startup: Startup::default(),
+            drawer_names: DrawerPolicy::Any,
+            custom_parser: None,
+            conservative_emphasis: None,
+            skip_elements: ElementTypeSet::new(),
+            max_line_length: None,
+            max_objects: None,
+            max_nodes: None,
         }
     }
 }
 
+impl ParseConfig {
+    /// Creates a `ParseConfigBuilder` starting from the default configuration.
+    pub fn builder() -> ParseConfigBuilder {
+        ParseConfigBuilder {
+            config: ParseConfig::default(),
+        }
+    }
+
+    /// Emacs' default configuration, same as `ParseConfig::default()`.
+    pub fn emacs_defaults() -> Self {
+        ParseConfig::default()
+    }
+
+    /// Configuration with common "Getting Things Done" keywords:
+    /// `TODO(t) NEXT(n) WAIT(w@/!) | DONE(d!) CANCELLED(c@)`.
+    pub fn gtd() -> Self {
+        let mut config = ParseConfig::default();
+        config.set_todo_sequence("TODO(t) NEXT(n) WAIT(w@/!) | DONE(d!) CANCELLED(c@)");
+        config
+    }
+
+    /// Checks that every todo keyword is well-formed, that no keyword name
+    /// is used twice, and that `max_headline_level` isn't zero.
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        if self.max_headline_level == Some(0) {
+            return Err(ConfigError::ZeroHeadlineLevel);
+        }
+
+        let mut names = Vec::new();
+
+        for s in self
+            .todo_keywords
+            .0
+            .iter()
+            .chain(self.todo_keywords.1.iter())
+        {
+            let keyword = TodoKeyword::parse(s)
+                .ok_or_else(|| ConfigError::InvalidTodoKeyword { keyword: s.clone() })?;
+            if names.contains(&keyword.name) {
+                return Err(ConfigError::DuplicateTodoKeyword { name: keyword.name });
+            }
+            names.push(keyword.name);
+        }
+
+        Ok(())
+    }
+
+    /// Returns the todo keyword named `name`, if any.
+    ///
+    /// ```rust
+    /// use orgize::{ParseConfig, TodoKeyword, TodoLog};
+    ///
+    /// let mut config = ParseConfig::default();
+    /// config.set_todo_sequence("TODO(t!) WAIT(w@/!) | DONE(d)");
+    ///
+    /// let wait = config.todo_keyword("WAIT").unwrap();
+    /// assert_eq!(wait.key, Some('w'));
+    /// assert_eq!(wait.on_entry_log, Some(TodoLog::Note));
+    /// assert_eq!(wait.on_exit_log, Some(TodoLog::Timestamp));
+    /// assert!(config.todo_keyword("TODO(t!)").is_none());
+    /// ```
+    pub fn todo_keyword(&self, name: &str) -> Option<TodoKeyword> {
+        self.todo_keywords
+            .0
+            .iter()
+            .chain(self.todo_keywords.1.iter())
+            .filter_map(|s| TodoKeyword::parse(s))
+            .find(|keyword| keyword.name == name)
+    }
+
+    /// Replaces todo keywords with a sequence like `TODO(t!) | DONE(d)`.
+    ///
+    /// See `TodoKeyword::parse_sequence` for the syntax.
+    pub fn set_todo_sequence(&mut self, sequence: &str) {
+        let (todo, done) = TodoKeyword::parse_sequence(sequence);
+        self.todo_keywords = (
+            todo.iter().map(ToString::to_string).collect(),
+            done.iter().map(ToString::to_string).collect(),
+        );
+    }
+
+    /// Returns `true` if `name` is one of the done keywords.
+    ///
+    /// ```rust
+    /// use orgize::ParseConfig;
+    ///
+    /// let config = ParseConfig::gtd();
+    ///
+    /// assert!(config.is_done_keyword("CANCELLED"));
+    /// assert!(!config.is_done_keyword("WAIT"));
+    /// ```
+    pub fn is_done_keyword(&self, name: &str) -> bool {
+        self.todo_keywords.1.iter().any(|s| keyword_name(s) == name)
+    }
+
+    pub(crate) fn is_todo_keyword(&self, name: &str) -> bool {
+        self.todo_keywords
+            .0
+            .iter()
+            .chain(self.todo_keywords.1.iter())
+            .any(|s| keyword_name(s) == name)
+    }
+
+    /// Applies in-buffer settings of `text`, i.e. `#+TODO:` and `#+STARTUP:`
+    /// keywords. In-buffer todo keywords are added to the configured ones,
+    /// lines in source and example blocks are skipped.
+    pub(crate) fn with_buffer_settings(&self, text: &str) -> Cow<'_, ParseConfig> {
+        let mut todo = Vec::new();
+        let mut done = Vec::new();
+        let mut found = false;
+        let mut startup = None;
+        // end line of the verbatim block being skipped
+        let mut block_end: Option<&str> = None;
+
+        for line in text.lines() {
+            let line = line.trim_start();
+            if let Some(end) = block_end {
+                if line
+                    .get(..end.len())
+                    .is_some_and(|s| s.eq_ignore_ascii_case(end))
+                {
+                    block_end = None;
+                }
+                continue;
+            }
+            block_end = [
+                ("#+BEGIN_SRC", "#+END_SRC"),
+                ("#+BEGIN_EXAMPLE", "#+END_EXAMPLE"),
+            ]
+            .iter()
+            .find(|(begin, _)| {
+                line.get(..begin.len())
+                    .is_some_and(|s| s.eq_ignore_ascii_case(begin))
+            })
+            .map(|(_, end)| *end);
+            if block_end.is_some() {
+                continue;
+            }
+
+            if line
+                .get(.."#+STARTUP:".len())
+                .is_some_and(|s| s.eq_ignore_ascii_case("#+STARTUP:"))
+            {
+                startup
+                    .get_or_insert_with(|| self.startup.clone())
+                    .apply(&line["#+STARTUP:".len()..]);
+                continue;
+            }
+
+            let value = ["#+TODO:", "#+SEQ_TODO:", "#+TYP_TODO:"]
+                .iter()
+                .find(|prefix| {
+                    line.get(..prefix.len())
+                        .is_some_and(|s| s.eq_ignore_ascii_case(prefix))
+                })
+                .map(|prefix| &line[prefix.len()..]);
+
+            if let Some(value) = value {
+                let (t, d) = TodoKeyword::parse_sequence(value);
+                todo.extend(t.iter().map(ToString::to_string));
+                done.extend(d.iter().map(ToString::to_string));
+                found = true;
+            }
+        }
+
+        if !found && startup.is_none() {
+            return Cow::Borrowed(self);
+        }
+
+        let mut config = self.clone();
+        let (config_todo, config_done) = &mut config.todo_keywords;
+        for keyword in todo {
+            merge_keyword(config_todo, config_done, keyword);
+        }
+        for keyword in done {
+            merge_keyword(config_done, config_todo, keyword);
+        }
+        if let Some(startup) = startup {
+            config.startup = startup;
+        }
+        Cow::Owned(config)
+    }
+}
+
+/// Builder of `ParseConfig`, created by `ParseConfig::builder`
+#[derive(Clone, Debug)]
+pub struct ParseConfigBuilder {
+    config: ParseConfig,
+}
+
+impl ParseConfigBuilder {
+    /// Sets not-done and done todo keywords, which can have fast-access keys
+    /// and logging markers, e.g. `WAIT(w@/!)`.
+    pub fn todo_keywords<T, D>(mut self, todo: T, done: D) -> Self
+    where
+        T: IntoIterator,
+        T::Item: Into<String>,
+        D: IntoIterator,
+        D::Item: Into<String>,
+    {
+        self.config.todo_keywords = (
+            todo.into_iter().map(Into::into).collect(),
+            done.into_iter().map(Into::into).collect(),
+        );
+        self
+    }
+
+    /// Sets todo keywords from a sequence like `TODO(t!) | DONE(d)`, see
+    /// `TodoKeyword::parse_sequence`.
+    pub fn todo_sequence(mut self, sequence: &str) -> Self {
+        self.config.set_todo_sequence(sequence);
+        self
+    }
+
+    /// Sets how to handle malformed constructs.
+    pub fn strictness(mut self, strictness: Strictness) -> Self {
+        self.config.strictness = strictness;
+        self
+    }
+
+    /// Tolerates up to `blank_lines` blank lines before planning lines and
+    /// property drawers, and property drawers before planning lines, see
+    /// `ParseConfig::lenient_headline_metadata`.
+    pub fn lenient_headline_metadata(mut self, blank_lines: usize) -> Self {
+        self.config.lenient_headline_metadata = Some(blank_lines);
+        self
+    }
+
+    /// Parses legacy `[1]` footnote definitions, see
+    /// `ParseConfig::legacy_footnote_definitions`.
+    pub fn legacy_footnote_definitions(mut self, legacy: bool) -> Self {
+        self.config.legacy_footnote_definitions = legacy;
+        self
+    }
+
+    /// Sets the maximum number of stars of a headline, see
+    /// `ParseConfig::max_headline_level`.
+    pub fn max_headline_level(mut self, level: usize) -> Self {
+        self.config.max_headline_level = Some(level);
+        self
+    }
+
+    /// Sets which drawer names are recognized, see `ParseConfig::drawer_names`.
+    pub fn drawer_names(mut self, policy: DrawerPolicy) -> Self {
+        self.config.drawer_names = policy;
+        self
+    }
+
+    /// Limits emphasis contents to `max_chars` characters, see
+    /// `ParseConfig::conservative_emphasis`.
+    pub fn conservative_emphasis(mut self, max_chars: usize) -> Self {
+        self.config.conservative_emphasis = Some(max_chars);
+        self
+    }
+
+    /// Skips elements of `kind`, see `ParseConfig::skip_elements`.
+    pub fn skip_element(mut self, kind: ElementKind) -> Self {
+        self.config.skip_elements.insert(kind);
+        self
+    }
+
+    /// Sets the maximum length of a line parsed structurally, see
+    /// `ParseConfig::max_line_length`.
+    pub fn max_line_length(mut self, bytes: usize) -> Self {
+        self.config.max_line_length = Some(bytes);
+        self
+    }
+
+    /// Sets the maximum number of objects in a paragraph, see
+    /// `ParseConfig::max_objects`.
+    pub fn max_objects(mut self, objects: usize) -> Self {
+        self.config.max_objects = Some(objects);
+        self
+    }
+
+    /// Sets the maximum number of nodes in the arena, see
+    /// `ParseConfig::max_nodes`.
+    pub fn max_nodes(mut self, nodes: usize) -> Self {
+        self.config.max_nodes = Some(nodes);
+        self
+    }
+
+    /// Sets the parser of custom elements, see `ParseConfig::custom_parser`.
+    pub fn custom_parser(mut self, parser: CustomParser) -> Self {
+        self.config.custom_parser = Some(parser);
+        self
+    }
+
+    /// Validates and returns the configuration.
+    ///
+    /// ```rust
+    /// use orgize::{ConfigError, ParseConfig};
+    ///
+    /// assert_eq!(
+    ///     ParseConfig::builder()
+    ///         .todo_keywords(["TODO", "WAIT(w"], ["DONE"])
+    ///         .build(),
+    ///     Err(ConfigError::InvalidTodoKeyword {
+    ///         keyword: "WAIT(w".into()
+    ///     })
+    /// );
+    /// ```
+    pub fn build(self) -> Result<ParseConfig, ConfigError> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+/// Error returned by `ParseConfigBuilder::build`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    /// Todo keyword with an empty name or a malformed parenthesized part
+    InvalidTodoKeyword { keyword: String },
+    /// Todo keyword name used more than once
+    DuplicateTodoKeyword { name: String },
+    /// `max_headline_level` is `Some(0)`
+    ZeroHeadlineLevel,
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::InvalidTodoKeyword { keyword } => {
+                write!(f, "invalid todo keyword `{}`", keyword)
+            }
+            ConfigError::DuplicateTodoKeyword { name } => {
+                write!(f, "duplicated todo keyword `{}`", name)
+            }
+            ConfigError::ZeroHeadlineLevel => write!(f, "maximum headline level is zero"),
+        }
+    }
+}
+
+impl Error for ConfigError {}
+
+/// Todo keyword with fast-access key and logging markers, e.g. `WAIT(w@/!)`
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct TodoKeyword {
+    /// Keyword name, e.g. `WAIT`
+    pub name: String,
+    /// Fast-access key, e.g. `w`
+    pub key: Option<char>,
+    /// What to record when entering this state
+    pub on_entry_log: Option<TodoLog>,
+    /// What to record when leaving this state, if the target state doesn't
+    /// record anything itself
+    pub on_exit_log: Option<TodoLog>,
+}
+
+/// Logging marker of a todo keyword
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TodoLog {
+    /// `!`, records a timestamp
+    Timestamp,
+    /// `@`, records a timestamp with a note
+    Note,
+}
+
+impl TodoLog {
+    fn from_char(c: char) -> Option<TodoLog> {
+        match c {
+            '!' => Some(TodoLog::Timestamp),
+            '@' => Some(TodoLog::Note),
+            _ => None,
+        }
+    }
+
+    fn as_char(self) -> char {
+        match self {
+            TodoLog::Timestamp => '!',
+            TodoLog::Note => '@',
+        }
+    }
+}
+
+impl TodoKeyword {
+    /// Creates a keyword without fast-access key and logging markers.
+    pub fn new<S: Into<String>>(name: S) -> Self {
+        TodoKeyword {
+            name: name.into(),
+            key: None,
+            on_entry_log: None,
+            on_exit_log: None,
+        }
+    }
+
+    /// Parses a keyword like `TODO`, `TODO(t)`, `WAIT(w@/!)` or `DONE(/!)`.
+    ///
+    /// Returns `None` if the name is empty or the parenthesized part is
+    /// malformed.
+    pub fn parse(input: &str) -> Option<TodoKeyword> {
+        let (name, spec) = match input.find('(') {
+            Some(i) => (&input[..i], Some(input[i + 1..].strip_suffix(')')?)),
+            None => (input, None),
+        };
+
+        if name.is_empty() || name.contains(|c: char| c.is_whitespace() || c == ')') {
+            return None;
+        }
+
+        let mut keyword = TodoKeyword::new(name);
+
+        if let Some(spec) = spec {
+            let (entry, exit) = match spec.find('/') {
+                Some(i) => (&spec[..i], Some(&spec[i + 1..])),
+                None => (spec, None),
+            };
+
+            let mut chars = entry.chars();
+            match (chars.next(), chars.next(), chars.next()) {
+                (None, _, _) => (),
+                (Some(c), None, _) if TodoLog::from_char(c).is_some() => {
+                    keyword.on_entry_log = TodoLog::from_char(c);
+                }
+                (Some(key), log, None) if is_key(key) => {
+                    keyword.key = Some(key);
+                    if let Some(c) = log {
+                        keyword.on_entry_log = Some(TodoLog::from_char(c)?);
+                    }
+                }
+                _ => return None,
+            }
+
+            if let Some(exit) = exit {
+                let mut chars = exit.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => keyword.on_exit_log = Some(TodoLog::from_char(c)?),
+                    _ => return None,
+                }
+            }
+        }
+
+        Some(keyword)
+    }
+
+    /// Parses a keyword sequence like `TODO(t!) WAIT(w@/!) | DONE(d)`,
+    /// returning not-done keywords and done keywords.
+    ///
+    /// Without `|`, the last keyword is the only done keyword. Malformed
+    /// keywords are skipped.
+    ///
+    /// ```rust
+    /// use orgize::TodoKeyword;
+    ///
+    /// let (todo, done) = TodoKeyword::parse_sequence("TODO(t!) WAIT(w@/!) | DONE(d)");
+    ///
+    /// assert_eq!(todo[0].name, "TODO");
+    /// assert_eq!(todo[1].key, Some('w'));
+    /// assert_eq!(done[0].to_string(), "DONE(d)");
+    /// ```
+    pub fn parse_sequence(input: &str) -> (Vec<TodoKeyword>, Vec<TodoKeyword>) {
+        let words: Vec<_> = input.split_whitespace().collect();
+
+        match words.iter().position(|&word| word == "|") {
+            Some(i) => (
+                words[..i].iter().filter_map(|s| Self::parse(s)).collect(),
+                words[i + 1..]
+                    .iter()
+                    .filter(|&&s| s != "|")
+                    .filter_map(|s| Self::parse(s))
+                    .collect(),
+            ),
+            None => {
+                let mut todo: Vec<_> = words.iter().filter_map(|s| Self::parse(s)).collect();
+                let done = todo.pop().into_iter().collect();
+                (todo, done)
+            }
+        }
+    }
+}
+
+impl fmt::Display for TodoKeyword {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}", self.name)?;
+        if self.key.is_none() && self.on_entry_log.is_none() && self.on_exit_log.is_none() {
+            return Ok(());
+        }
+        write!(f, "(")?;
+        if let Some(key) = self.key {
+            write!(f, "{}", key)?;
+        }
+        if let Some(log) = self.on_entry_log {
+            write!(f, "{}", log.as_char())?;
+        }
+        if let Some(log) = self.on_exit_log {
+            write!(f, "/{}", log.as_char())?;
+        }
+        write!(f, ")")
+    }
+}
+
+fn is_key(c: char) -> bool {
+    !matches!(c, '!' | '@' | '/' | '(' | ')') && !c.is_whitespace()
+}
+
+// adds `keyword` to `list`, replacing the keyword of the same name in `list`
+// or `other`
+fn merge_keyword(list: &mut Vec<String>, other: &mut Vec<String>, keyword: String) {
+    other.retain(|s| keyword_name(s) != keyword_name(&keyword));
+    match list
+        .iter_mut()
+        .find(|s| keyword_name(s) == keyword_name(&keyword))
+    {
+        Some(s) => *s = keyword,
+        None => list.push(keyword),
+    }
+}
+
+pub(crate) fn keyword_name(s: &str) -> &str {
+    s.find('(').map(|i| &s[..i]).unwrap_or(s)
+}
+
 lazy_static::lazy_static! {
     pub static ref DEFAULT_CONFIG: ParseConfig = ParseConfig::default();
 }
+
+#[test]
+fn todo_keyword() {
+    for s in [
+        "TODO",
+        "TODO(t)",
+        "TODO(t!)",
+        "WAIT(w@/!)",
+        "DONE(/!)",
+        "NEXT(@)",
+    ] {
+        assert_eq!(TodoKeyword::parse(s).unwrap().to_string(), s);
+    }
+
+    assert_eq!(
+        TodoKeyword::parse("WAIT(w@/!)"),
+        Some(TodoKeyword {
+            name: "WAIT".into(),
+            key: Some('w'),
+            on_entry_log: Some(TodoLog::Note),
+            on_exit_log: Some(TodoLog::Timestamp),
+        })
+    );
+    assert_eq!(TodoKeyword::parse("TODO()"), Some(TodoKeyword::new("TODO")));
+
+    for s in [
+        "",
+        "(t)",
+        "TODO(t",
+        "TODO(tx)",
+        "TODO(t!!)",
+        "TODO(/)",
+        "TODO(t/!@)",
+    ] {
+        assert_eq!(TodoKeyword::parse(s), None, "{}", s);
+    }
+}
+
+#[test]
+fn todo_sequence() {
+    let (todo, done) = TodoKeyword::parse_sequence("TODO(t!) WAIT(w@/!) | DONE(d)");
+    assert_eq!(
+        todo.iter().map(ToString::to_string).collect::<Vec<_>>(),
+        ["TODO(t!)", "WAIT(w@/!)"]
+    );
+    assert_eq!(
+        done,
+        [TodoKeyword {
+            key: Some('d'),
+            ..TodoKeyword::new("DONE")
+        }]
+    );
+
+    let (todo, done) = TodoKeyword::parse_sequence("A B C");
+    assert_eq!(todo, [TodoKeyword::new("A"), TodoKeyword::new("B")]);
+    assert_eq!(done, [TodoKeyword::new("C")]);
+
+    assert_eq!(TodoKeyword::parse_sequence(""), (vec![], vec![]));
+}
+
+#[test]
+fn builder() {
+    assert_eq!(ParseConfig::builder().build().unwrap(), *DEFAULT_CONFIG);
+    assert_eq!(ParseConfig::emacs_defaults(), *DEFAULT_CONFIG);
+
+    let config = ParseConfig::builder()
+        .todo_sequence("TODO(t) NEXT(n) WAIT(w@/!) | DONE(d!) CANCELLED(c@)")
+        .build()
+        .unwrap();
+    assert_eq!(config, ParseConfig::gtd());
+
+    let config = ParseConfig::builder()
+        .strictness(Strictness::Strict)
+        .lenient_headline_metadata(1)
+        .legacy_footnote_definitions(true)
+        .build()
+        .unwrap();
+    assert_eq!(config.strictness, Strictness::Strict);
+    assert_eq!(config.lenient_headline_metadata, Some(1));
+    assert!(config.legacy_footnote_definitions);
+}
+
+#[test]
+fn builder_validation() {
+    assert_eq!(
+        ParseConfig::builder()
+            .todo_keywords(Vec::<String>::new(), vec![""])
+            .build(),
+        Err(ConfigError::InvalidTodoKeyword { keyword: "".into() })
+    );
+    assert_eq!(
+        ParseConfig::builder()
+            .todo_keywords(["TODO(t!!)"], ["DONE"])
+            .build(),
+        Err(ConfigError::InvalidTodoKeyword {
+            keyword: "TODO(t!!)".into()
+        })
+    );
+    assert_eq!(
+        ParseConfig::builder()
+            .todo_keywords(["TODO(t)", "WAIT"], ["TODO(d)"])
+            .build(),
+        Err(ConfigError::DuplicateTodoKeyword {
+            name: "TODO".into()
+        })
+    );
+    assert_eq!(
+        ParseConfig::builder().max_headline_level(0).build(),
+        Err(ConfigError::ZeroHeadlineLevel)
+    );
+    assert_eq!(
+        ParseConfig::builder()
+            .todo_keywords(["TODO"], ["DONE"])
+            .build()
+            .unwrap()
+            .todo_keywords,
+        (vec!["TODO".into()], vec!["DONE".into()])
+    );
+}
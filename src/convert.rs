@@ -0,0 +1,382 @@
+use indextree::{NodeEdge, NodeId};
+
+use crate::{
+    config::keyword_name,
+    elements::{
+        list::{bullet_shift, renumbered_bullet, shift_item_contents, shift_lines},
+        Checkbox, Element, ListItem, Title,
+    },
+    export::{DefaultOrgHandler, OrgHandler},
+    parsers::{parse_container, Container, OwnedArena},
+    validate::{ValidationError, ValidationResult},
+    Headline, Org,
+};
+
+/// Options of `ListItem::into_headline` and `Headline::into_list_item`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListConversion {
+    /// Converts nested items into deeper headlines too, instead of keeping
+    /// them as a list in the new section, default is `true`
+    pub nested_items: bool,
+    /// Converts checkboxes into todo keywords and back, default is `true`
+    ///
+    /// Checked items get the first done keyword, other items the first
+    /// todo keyword. Headlines with a done keyword get a checked checkbox,
+    /// those with a todo keyword an empty one.
+    pub checkboxes: bool,
+}
+
+impl Default for ListConversion {
+    fn default() -> Self {
+        ListConversion {
+            nested_items: true,
+            checkboxes: true,
+        }
+    }
+}
+
+impl ListItem<'_> {
+    /// Converts list item `node` into a headline at `level`, like `C-c *`
+    /// in Emacs, and returns the new headline.
+    ///
+    /// The first line of the item becomes the title, the rest of its
+    /// contents becomes the section. The headline is appended to the
+    /// children of the headline containing the item if it's deeper,
+    /// otherwise it's inserted after that headline and its subtree. The
+    /// item is removed from its list, which is renumbered if it's ordered.
+    ///
+    /// Returns an error, and changes nothing, if `node` isn't a list item or
+    /// if the headline can't be inserted at `level`.
+    ///
+    /// ```rust
+    /// use orgize::{elements::ListItem, ListConversion, Org};
+    ///
+    /// let mut org = Org::parse("* Tasks\n1. [ ] a\n   text\n2. [X] b\n");
+    /// let item = org.headlines().next().unwrap().section_node().unwrap();
+    /// let item = org.arena()[item].first_child().unwrap();
+    /// let item = org.arena()[item].first_child().unwrap();
+    ///
+    /// ListItem::into_headline(&mut org, item, 2, &ListConversion::default()).unwrap();
+    ///
+    /// let mut writer = Vec::new();
+    /// org.write_org(&mut writer).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(writer).unwrap(),
+    ///     "* Tasks\n1. [X] b\n** TODO a\ntext\n"
+    /// );
+    /// ```
+    pub fn into_headline(
+        org: &mut Org,
+        node: NodeId,
+        level: usize,
+        conversion: &ListConversion,
+    ) -> ValidationResult<Headline> {
+        if !matches!(org[node], Element::ListItem(_)) {
+            return Err(ValidationError::UnexpectedElement {
+                expected: "ListItem",
+                at: node,
+            });
+        }
+
+        let container = node.ancestors(&org.arena).find_map(|n| match org[n] {
+            Element::Headline { level } => Some(Headline::from_node(n, level, org)),
+            _ => None,
+        });
+        // the levels accepted by `append`, `insert_after` and `prepend`
+        let range = match container {
+            Some(container) if level > container.level() => {
+                let max = container
+                    .last_child(org)
+                    .map_or(usize::MAX, |hdl| hdl.level());
+                container.level() + 1..=max
+            }
+            Some(container) => {
+                let min = container
+                    .next(org)
+                    .map(|hdl| hdl.level())
+                    .or_else(|| container.parent(org).map(|hdl| hdl.level() + 1))
+                    .unwrap_or(1);
+                min..=container.level()
+            }
+            None => {
+                let min = org.document().first_child(org).map_or(1, |hdl| hdl.level());
+                min..=usize::MAX
+            }
+        };
+        if !range.contains(&level) {
+            return Err(ValidationError::HeadlineLevelMismatch { range, at: node });
+        }
+
+        let mut text = String::new();
+        write_headline(org, node, level, conversion, &mut text);
+        let placeholder = Headline::new(Title::default(), org);
+        let headline = placeholder.parse_replace(&text, false, org);
+        placeholder.headline_node().remove_subtree(&mut org.arena);
+        let headline = headline?;
+
+        let inserted = match container {
+            Some(container) if level > container.level() => container.append(headline, org),
+            Some(container) => container.insert_after(headline, org),
+            None => org.document().prepend(headline, org),
+        };
+        if let Err(err) = inserted {
+            headline.headline_node().remove_subtree(&mut org.arena);
+            return Err(err);
+        }
+
+        let list = org.arena[node].parent();
+        node.remove_subtree(&mut org.arena);
+        if let Some(list) = list {
+            if list.children(&org.arena).next().is_none() {
+                // so is a section left empty
+                let section = org.arena[list]
+                    .parent()
+                    .filter(|&n| matches!(org[n], Element::Section));
+                list.remove_subtree(&mut org.arena);
+                if let Some(section) = section {
+                    if section.children(&org.arena).next().is_none() {
+                        section.remove_subtree(&mut org.arena);
+                    }
+                }
+            } else {
+                renumber_list(org, list);
+            }
+        }
+
+        org.tree_changed();
+
+        Ok(headline)
+    }
+}
+
+impl Headline {
+    /// Converts this headline and its subheadlines into a list item, like
+    /// `C-c -` in Emacs, and returns the new list item.
+    ///
+    /// The title becomes the first line of the item, followed by the
+    /// planning, properties and section, and subheadlines become nested
+    /// items. Tags are kept at the end of the first line. The item is
+    /// appended to the section of the parent headline, or of the document,
+    /// joining its last list if it's a top-level unordered list.
+    ///
+    /// ```rust
+    /// use orgize::{ListConversion, Org};
+    ///
+    /// let mut org = Org::parse("* Tasks\n- [X] a\n** TODO b\ntext\n*** DONE c\n");
+    /// let b = org.headlines().nth(1).unwrap();
+    ///
+    /// b.into_list_item(&mut org, &ListConversion::default());
+    ///
+    /// let mut writer = Vec::new();
+    /// org.write_org(&mut writer).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(writer).unwrap(),
+    ///     "* Tasks\n- [X] a\n- [ ] b\n  text\n  - [X] c\n"
+    /// );
+    /// ```
+    pub fn into_list_item(self, org: &mut Org, conversion: &ListConversion) -> NodeId {
+        let mut text = String::new();
+        write_item(org, self, 0, conversion, &mut text);
+
+        let config = org.config.clone();
+        let doc_n = org.arena.new_node(Element::Document { pre_blank: 0 });
+        parse_container(
+            &mut OwnedArena::new(&mut org.arena),
+            Container::Document {
+                content: &text,
+                node: doc_n,
+            },
+            &config,
+        );
+        // the text is a single list in a section
+        let list = doc_n
+            .descendants(&org.arena)
+            .find(|&n| matches!(org[n], Element::List(_)))
+            .unwrap();
+        let item = org.arena[list].first_child().unwrap();
+
+        let section = match self.parent(org) {
+            Some(parent) => parent.section_node().unwrap_or_else(|| {
+                let section = org.arena.new_node(Element::Section);
+                parent.title_node().insert_after(section, &mut org.arena);
+                section
+            }),
+            None => org.document().section_node().unwrap_or_else(|| {
+                let section = org.arena.new_node(Element::Section);
+                org.root.prepend(section, &mut org.arena);
+                section
+            }),
+        };
+        self.headline_node().remove_subtree(&mut org.arena);
+
+        match org.arena[section].last_child() {
+            Some(last) if matches!(&org[last], Element::List(l) if l.indent == 0 && !l.ordered) => {
+                item.detach(&mut org.arena);
+                last.append(item, &mut org.arena);
+            }
+            _ => {
+                list.detach(&mut org.arena);
+                section.append(list, &mut org.arena);
+            }
+        }
+        // the temporary document is freed with whatever it still contains
+        doc_n.remove_subtree(&mut org.arena);
+
+        org.tree_changed();
+
+        item
+    }
+}
+
+// writes list item `node` as a headline at `level`
+fn write_headline(
+    org: &Org,
+    node: NodeId,
+    level: usize,
+    conversion: &ListConversion,
+    text: &mut String,
+) {
+    let item = match &org[node] {
+        Element::ListItem(item) => item,
+        _ => return,
+    };
+    let column = (item.indent + item.bullet.chars().count()) as isize;
+
+    text.push_str(&"*".repeat(org.startup().stars(level)));
+    let keywords = &org.config.todo_keywords;
+    let keyword = match item.checkbox {
+        Some(Checkbox::On) if conversion.checkboxes => keywords.1.first(),
+        Some(_) if conversion.checkboxes => keywords.0.first(),
+        _ => None,
+    };
+    if let Some(keyword) = keyword {
+        text.push(' ');
+        text.push_str(keyword_name(keyword));
+    }
+    text.push(' ');
+
+    let mut children = node.children(&org.arena).peekable();
+    match children.peek() {
+        Some(&first) if matches!(org[first], Element::Paragraph { .. }) => {
+            children.next();
+            let paragraph = to_org(org, first);
+            let (title, rest) = paragraph.split_once('\n').unwrap_or((&paragraph, ""));
+            text.push_str(title.trim_end());
+            text.push('\n');
+            text.push_str(&shift_lines(rest, true, -column));
+        }
+        _ => text.push('\n'),
+    }
+
+    let mut nested = Vec::new();
+    for child in children {
+        match org[child] {
+            Element::List(_) if conversion.nested_items => {
+                nested.extend(child.children(&org.arena));
+            }
+            _ => text.push_str(&shift_lines(&to_org(org, child), true, -column)),
+        }
+    }
+    for child in nested {
+        write_headline(org, child, level + 1, conversion, text);
+    }
+}
+
+// writes `headline` as a list item indented by `indent` spaces
+fn write_item(
+    org: &Org,
+    headline: Headline,
+    indent: usize,
+    conversion: &ListConversion,
+    text: &mut String,
+) {
+    let title = headline.title(org);
+    let column = indent + 2;
+
+    text.push_str(&" ".repeat(indent));
+    text.push_str("- ");
+    match title.keyword.as_deref() {
+        Some(keyword) if conversion.checkboxes => {
+            if org.config.is_done_keyword(keyword) {
+                text.push_str("[X] ");
+            } else {
+                text.push_str("[ ] ");
+            }
+        }
+        Some(keyword) => {
+            text.push_str(keyword);
+            text.push(' ');
+        }
+        None => (),
+    }
+    if let Some(priority) = title.priority {
+        text.push_str(&format!("[#{}] ", priority));
+    }
+    text.push_str(&title.raw);
+    if !title.tags.is_empty() {
+        text.push_str(" :");
+        for tag in &title.tags {
+            text.push_str(tag);
+            text.push(':');
+        }
+    }
+    text.push('\n');
+
+    // planning and properties follow the headline line
+    let metadata = to_org(org, headline.title_node());
+    let metadata = metadata.split_once('\n').map_or("", |(_, rest)| rest);
+    text.push_str(&indent_lines(metadata, column));
+    if let Some(section) = headline.section_node() {
+        text.push_str(&indent_lines(&to_org(org, section), column));
+    }
+
+    for child in headline.children(org) {
+        write_item(org, child, column, conversion, text);
+    }
+}
+
+// org text of `node` and its descendants
+fn to_org(org: &Org, node: NodeId) -> String {
+    let mut writer = Vec::new();
+    let mut handler = DefaultOrgHandler::default();
+    handler.prepare(org);
+    for edge in node.traverse(&org.arena) {
+        match edge {
+            NodeEdge::Start(n) => handler.start_node(&mut writer, n, &org[n]).unwrap(),
+            NodeEdge::End(n) => handler.end_node(&mut writer, n, &org[n]).unwrap(),
+        }
+    }
+    String::from_utf8_lossy(&writer).into_owned()
+}
+
+// indents non-blank lines of `text` by `indent` spaces
+fn indent_lines(text: &str, indent: usize) -> String {
+    let mut indented = String::with_capacity(text.len());
+    for line in text.split_inclusive('\n') {
+        if !line.trim().is_empty() {
+            indented.push_str(&" ".repeat(indent));
+        }
+        indented.push_str(line);
+    }
+    indented
+}
+
+// renumbers the ordered items of `list`, like `List::renumber`
+fn renumber_list(org: &mut Org, list: NodeId) {
+    let items: Vec<_> = list.children(&org.arena).collect();
+    let mut number = 1;
+    for node in items {
+        let shift = match &mut org[node] {
+            Element::ListItem(item) if item.ordered => {
+                let new = renumbered_bullet(item, &mut number, '-');
+                let shift = bullet_shift(&item.bullet, &new);
+                item.bullet = new.into();
+                shift
+            }
+            _ => continue,
+        };
+        if shift != 0 {
+            shift_item_contents(org, node, shift);
+        }
+    }
+}
@@ -0,0 +1,525 @@
+use std::error::Error;
+use std::fmt;
+use std::ops::Range;
+
+use nom::{
+    bytes::complete::{tag, tag_no_case, take_while1},
+    character::complete::{alpha1, space1},
+    sequence::{delimited, pair, preceded},
+    IResult,
+};
+
+use crate::config::{DrawerPolicy, ParseConfig, Strictness};
+use crate::elements::{
+    planning::{parse_entries, PlanningKeyword},
+    title::is_tag_line,
+    Timestamp,
+};
+use crate::parse::combinators::{eol, line};
+
+/// Malformed construct found while parsing
+///
+/// Only the following conditions are considered as malformed, everything else
+/// is parsed the same way regardless of `ParseConfig::strictness`:
+///
+/// + a `#+BEGIN_NAME` line without any `#+END_` line after it
+/// + a `#+BEGIN_NAME` line whose first following `#+END_` line has a different name
+/// + a `:NAME:` drawer line without any `:END:` line after it
+/// + a `#+BEGIN: name` line without any `#+END:` line after it
+/// + a line right after a headline starting with `SCHEDULED:`, `DEADLINE:` or
+///   `CLOSED:` which isn't a valid planning line
+/// + a planning keyword given more than once in a planning line, only the
+///   first one is used
+/// + an inactive timestamp after `SCHEDULED:` or `DEADLINE:`, which is used
+///   anyway
+/// + a `:NAME: value` line which isn't placed inside a property drawer
+/// + whitespace other than a space or a tab, e.g. a non-breaking space,
+///   after headline stars, list bullets, todo keywords or planning
+///   keywords, or around headline tags
+/// + a document with more nodes than `ParseConfig::max_nodes`, whose
+///   remaining contents are parsed as plain text
+///
+/// In lenient mode, all of them are parsed as paragraphs, except for planning
+/// lines with duplicated keywords or inactive timestamps, and headlines with
+/// unusual whitespace, which are parsed like Emacs: the keyword or the tags
+/// stay in the title.
+#[derive(Debug, Clone, PartialEq)]
+pub enum ParseError {
+    /// Block without any end line
+    UnclosedBlock {
+        /// Block name
+        name: String,
+        /// Byte offset of the begin line
+        offset: usize,
+    },
+    /// Block whose end line has a different name
+    MismatchedBlockEnd {
+        /// Name in the begin line
+        begin: String,
+        /// Name in the first end line
+        end: String,
+        /// Byte offset of the begin line
+        offset: usize,
+    },
+    /// Drawer without end line
+    UnclosedDrawer {
+        /// Drawer name
+        name: String,
+        /// Byte offset of the drawer's first line
+        offset: usize,
+    },
+    /// Dynamic block without `#+END:` line
+    UnclosedDynamicBlock {
+        /// Block name, e.g. `clocktable`
+        name: String,
+        /// Byte offset of the begin line
+        offset: usize,
+    },
+    /// Planning line which cannot be parsed
+    InvalidPlanning {
+        /// Byte offset of the planning line
+        offset: usize,
+    },
+    /// Planning keyword given more than once
+    DuplicatePlanningKeyword {
+        /// Keyword, e.g. `SCHEDULED:`
+        keyword: String,
+        /// Byte offset of the repeated keyword
+        offset: usize,
+    },
+    /// Inactive timestamp after `SCHEDULED:` or `DEADLINE:`
+    InactivePlanningTimestamp {
+        /// Keyword, e.g. `SCHEDULED:`
+        keyword: String,
+        /// Byte offset of the keyword
+        offset: usize,
+    },
+    /// Node property outside of a property drawer
+    PropertyOutsideDrawer {
+        /// Property name
+        name: String,
+        /// Byte offset of the property line
+        offset: usize,
+    },
+    /// Unicode whitespace where only a space or a tab is recognized, or a
+    /// zero-width character before a star, a keyword or a bullet, which is
+    /// almost always pasted by accident
+    UnicodeWhitespace {
+        /// The whitespace, e.g. `'\u{a0}'`
+        character: char,
+        /// Byte offset of the whitespace
+        offset: usize,
+    },
+    /// Contents left unparsed since the arena reached
+    /// `ParseConfig::max_nodes`, reported once
+    TooManyNodes {
+        /// The limit
+        limit: usize,
+        /// Byte offset of the first contents left unparsed
+        offset: usize,
+    },
+}
+
+impl ParseError {
+    /// Returns the byte offset of the line where this error occurs.
+    pub fn offset(&self) -> usize {
+        match self {
+            ParseError::UnclosedBlock { offset, .. }
+            | ParseError::MismatchedBlockEnd { offset, .. }
+            | ParseError::UnclosedDrawer { offset, .. }
+            | ParseError::UnclosedDynamicBlock { offset, .. }
+            | ParseError::InvalidPlanning { offset }
+            | ParseError::DuplicatePlanningKeyword { offset, .. }
+            | ParseError::InactivePlanningTimestamp { offset, .. }
+            | ParseError::PropertyOutsideDrawer { offset, .. }
+            | ParseError::UnicodeWhitespace { offset, .. }
+            | ParseError::TooManyNodes { offset, .. } => *offset,
+        }
+    }
+}
+
+impl fmt::Display for ParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ParseError::UnclosedBlock { name, offset } => {
+                write!(f, "unclosed block `{}` at {}", name, offset)
+            }
+            ParseError::MismatchedBlockEnd { begin, end, offset } => write!(
+                f,
+                "block `{}` at {} is ended by `#+END_{}`",
+                begin, offset, end
+            ),
+            ParseError::UnclosedDrawer { name, offset } => {
+                write!(f, "unclosed drawer `{}` at {}", name, offset)
+            }
+            ParseError::UnclosedDynamicBlock { name, offset } => {
+                write!(f, "unclosed dynamic block `{}` at {}", name, offset)
+            }
+            ParseError::InvalidPlanning { offset } => {
+                write!(f, "invalid planning line at {}", offset)
+            }
+            ParseError::DuplicatePlanningKeyword { keyword, offset } => {
+                write!(f, "duplicated planning keyword `{}` at {}", keyword, offset)
+            }
+            ParseError::InactivePlanningTimestamp { keyword, offset } => write!(
+                f,
+                "inactive timestamp after planning keyword `{}` at {}",
+                keyword, offset
+            ),
+            ParseError::PropertyOutsideDrawer { name, offset } => {
+                write!(f, "property `{}` outside of drawer at {}", name, offset)
+            }
+            ParseError::UnicodeWhitespace { character, offset } => write!(
+                f,
+                "unicode whitespace U+{:04X} at {}",
+                *character as u32, offset
+            ),
+            ParseError::TooManyNodes { limit, offset } => write!(
+                f,
+                "more than {} nodes, contents at {} left unparsed",
+                limit, offset
+            ),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// Collects `ParseError`s while parsing
+pub(crate) struct Diagnostics {
+    base: usize,
+    strictness: Strictness,
+    errors: Vec<ParseError>,
+    property_drawers: Vec<Range<usize>>,
+}
+
+impl Diagnostics {
+    /// Creates a collector, offsets are relative to the start of `text`.
+    pub fn new(text: &str, strictness: Strictness) -> Diagnostics {
+        Diagnostics {
+            base: text.as_ptr() as usize,
+            strictness,
+            errors: Vec::new(),
+            property_drawers: Vec::new(),
+        }
+    }
+
+    /// Creates a collector which ignores everything.
+    pub fn disabled() -> Diagnostics {
+        Diagnostics::new("", Strictness::Lenient)
+    }
+
+    fn enabled(&self) -> bool {
+        self.strictness != Strictness::Lenient
+    }
+
+    fn offset(&self, input: &str) -> usize {
+        input.as_ptr() as usize - self.base
+    }
+
+    fn push(&mut self, error: ParseError) {
+        // the same line can be checked more than once
+        if !self.errors.contains(&error) {
+            self.errors.push(error);
+        }
+    }
+
+    /// Returns collected errors, sorted by offset.
+    pub fn into_errors(mut self) -> Vec<ParseError> {
+        self.errors.sort_by_key(ParseError::offset);
+        self.errors
+    }
+
+    /// Called when `input` starts with `#` but no block can be parsed.
+    pub fn check_block(&mut self, input: &str) {
+        if !self.enabled() {
+            return;
+        }
+
+        let input = input.trim_start();
+        if let Ok((tail, name)) = block_begin(input) {
+            let end = tail
+                .lines()
+                .find_map(|line| block_end(line.trim()).ok().map(|(_, end)| end));
+            let offset = self.offset(input);
+            self.push(match end {
+                Some(end) => ParseError::MismatchedBlockEnd {
+                    begin: name.into(),
+                    end: end.into(),
+                    offset,
+                },
+                None => ParseError::UnclosedBlock {
+                    name: name.into(),
+                    offset,
+                },
+            });
+        }
+    }
+
+    /// Called when `input` is parsed as a `#+BEGIN:` keyword, i.e. no dynamic
+    /// block can be parsed.
+    pub fn check_dyn_block(&mut self, input: &str) {
+        if !self.enabled() {
+            return;
+        }
+
+        let input = input.trim_start();
+        if let Ok((_, name)) = dyn_block_begin(input) {
+            self.push(ParseError::UnclosedDynamicBlock {
+                name: name.into(),
+                offset: self.offset(input),
+            });
+        }
+    }
+
+    /// Called when `input` starts with `:` but no drawer can be parsed.
+    /// Names rejected by `policy` don't start drawers, so they are never
+    /// reported as unclosed.
+    pub fn check_drawer(&mut self, input: &str, policy: &DrawerPolicy) {
+        if !self.enabled() {
+            return;
+        }
+
+        let input = input.trim_start();
+        if let Ok((_, name)) = drawer_begin(input) {
+            if !name.eq_ignore_ascii_case("END") && policy.allows(name) {
+                self.push(ParseError::UnclosedDrawer {
+                    name: name.into(),
+                    offset: self.offset(input),
+                });
+            }
+        } else if let Ok((_, name)) = property(input) {
+            let offset = self.offset(input);
+            if !self.property_drawers.iter().any(|r| r.contains(&offset)) {
+                self.push(ParseError::PropertyOutsideDrawer {
+                    name: name.into(),
+                    offset,
+                });
+            }
+        }
+    }
+
+    /// Called for every drawer, so that properties inside drawers named
+    /// `PROPERTIES` are not reported.
+    pub fn add_drawer(&mut self, name: &str, content: &str) {
+        if self.enabled() && name.eq_ignore_ascii_case("PROPERTIES") {
+            let start = self.offset(content);
+            self.property_drawers.push(start..start + content.len());
+        }
+    }
+
+    /// Called with the text after a headline line which has no planning.
+    pub fn check_planning(&mut self, input: &str) {
+        if !self.enabled() {
+            return;
+        }
+
+        let first_line = line(input).map_or(input, |(_, line)| line).trim_start();
+        if ["SCHEDULED:", "DEADLINE:", "CLOSED:"]
+            .iter()
+            .any(|keyword| first_line.starts_with(keyword))
+        {
+            // more precise than an invalid line, since it's likely the only
+            // issue
+            match first_line
+                .char_indices()
+                .find(|&(_, c)| is_unicode_space(c))
+            {
+                Some((i, character)) => self.push(ParseError::UnicodeWhitespace {
+                    character,
+                    offset: self.offset(&first_line[i..]),
+                }),
+                None => self.push(ParseError::InvalidPlanning {
+                    offset: self.offset(first_line),
+                }),
+            }
+        }
+    }
+
+    /// Called with a headline line.
+    pub fn check_title(&mut self, input: &str, config: &ParseConfig) {
+        if !self.enabled() {
+            return;
+        }
+
+        let title = line(input).map_or(input, |(_, line)| line);
+        let title = title.trim_start_matches('*');
+
+        // a todo keyword followed by unicode whitespace
+        let word = title.trim_start_matches([' ', '\t']);
+        if let Some((i, c)) = word.char_indices().find(|&(_, c)| c.is_whitespace()) {
+            if is_unicode_space(c) && config.is_todo_keyword(&word[..i]) {
+                self.push_space(&word[i..], c);
+            }
+        }
+
+        // tags preceded or followed by unicode whitespace
+        let end = title.trim_end_matches(|c: char| c.is_ascii_whitespace());
+        let tags = end.trim_end_matches(char::is_whitespace);
+        if let Some((i, c)) = tags.char_indices().rfind(|&(_, c)| c.is_whitespace()) {
+            if is_unicode_space(c) && is_tag_line(&tags[i + c.len_utf8()..]) {
+                self.push_space(&tags[i..], c);
+            }
+        }
+        let trailing = &end[tags.len()..];
+        if let Some((i, c)) = trailing.char_indices().find(|&(_, c)| is_unicode_space(c)) {
+            if tags
+                .rsplit(char::is_whitespace)
+                .next()
+                .is_some_and(is_tag_line)
+            {
+                self.push_space(&trailing[i..], c);
+            }
+        }
+    }
+
+    /// Called when `input` starts with a star, a dash, a plus sign or a
+    /// digit, but isn't a list, to report stars or bullets followed by
+    /// unicode whitespace.
+    pub fn check_bullet(&mut self, input: &str) {
+        if !self.enabled() {
+            return;
+        }
+
+        let input = input.trim_start_matches([' ', '\t']);
+        let marker = match input.as_bytes().first() {
+            Some(b'*') => input.bytes().take_while(|&c| c == b'*').count(),
+            Some(b'-') | Some(b'+') => 1,
+            Some(c) if c.is_ascii_digit() => {
+                let digits = input.bytes().take_while(u8::is_ascii_digit).count();
+                match input.as_bytes().get(digits) {
+                    Some(b'.') | Some(b')') => digits + 1,
+                    _ => return,
+                }
+            }
+            _ => return,
+        };
+        if let Some(c) = input[marker..].chars().next() {
+            if is_unicode_space(c) {
+                self.push_space(&input[marker..], c);
+            }
+        }
+    }
+
+    /// Called when `input` doesn't start with an element, to report
+    /// zero-width characters hiding one, e.g. a headline or a keyword.
+    pub fn check_zero_width(&mut self, input: &str) {
+        if !self.enabled() {
+            return;
+        }
+
+        let input = input.trim_start_matches([' ', '\t']);
+        let structural = input.trim_start_matches(is_zero_width);
+        if structural.len() == input.len() {
+            return;
+        }
+        if let Some('*' | '#' | ':' | '-' | '+' | '|' | '0'..='9') = structural.chars().next() {
+            self.push_space(input, input.chars().next().unwrap());
+        }
+    }
+
+    fn push_space(&mut self, input: &str, character: char) {
+        self.push(ParseError::UnicodeWhitespace {
+            character,
+            offset: self.offset(input),
+        });
+    }
+
+    /// Called with contents left unparsed since the arena reached `limit`
+    /// nodes.
+    pub fn check_node_limit(&mut self, input: &str, limit: usize) {
+        if !self.enabled() {
+            return;
+        }
+
+        // keeps the first contents in the document, which aren't always
+        // the first ones left unparsed
+        let offset = self.offset(input);
+        for error in &mut self.errors {
+            if let ParseError::TooManyNodes { offset: first, .. } = error {
+                *first = offset.min(*first);
+                return;
+            }
+        }
+        self.push(ParseError::TooManyNodes { limit, offset });
+    }
+
+    /// Called with the planning line right after a headline.
+    pub fn check_planning_keywords(&mut self, input: &str) {
+        if !self.enabled() {
+            return;
+        }
+
+        let first_line = line(input).map_or(input, |(_, line)| line);
+        let base = self.offset(first_line);
+        let mut seen = Vec::new();
+        for (keyword, offset, timestamp) in parse_entries(first_line).unwrap_or_default() {
+            let offset = base + offset;
+            if seen.contains(&keyword) {
+                self.push(ParseError::DuplicatePlanningKeyword {
+                    keyword: keyword.as_str().into(),
+                    offset,
+                });
+                continue;
+            }
+            seen.push(keyword);
+            if keyword != PlanningKeyword::Closed
+                && matches!(
+                    timestamp,
+                    Timestamp::Inactive { .. } | Timestamp::InactiveRange { .. }
+                )
+            {
+                self.push(ParseError::InactivePlanningTimestamp {
+                    keyword: keyword.as_str().into(),
+                    offset,
+                });
+            }
+        }
+    }
+}
+
+// whitespace which isn't recognized by the parser, unlike spaces, tabs and
+// line endings, including zero-width characters
+fn is_unicode_space(c: char) -> bool {
+    (c.is_whitespace() && !c.is_ascii_whitespace()) || is_zero_width(c)
+}
+
+fn is_zero_width(c: char) -> bool {
+    matches!(
+        c,
+        '\u{200b}' | '\u{200c}' | '\u{200d}' | '\u{2060}' | '\u{feff}'
+    )
+}
+
+fn block_begin(input: &str) -> IResult<&str, &str, ()> {
+    let (input, name) = preceded(tag_no_case("#+BEGIN_"), alpha1)(input)?;
+    let (input, _) = line(input)?;
+    Ok((input, name))
+}
+
+fn block_end(input: &str) -> IResult<&str, &str, ()> {
+    preceded(tag_no_case("#+END_"), alpha1)(input)
+}
+
+fn dyn_block_begin(input: &str) -> IResult<&str, &str, ()> {
+    preceded(pair(tag_no_case("#+BEGIN:"), space1), alpha1)(input)
+}
+
+fn drawer_begin(input: &str) -> IResult<&str, &str, ()> {
+    let (input, name) = delimited(
+        tag(":"),
+        take_while1(|c: char| c.is_alphanumeric() || c == '-' || c == '_'),
+        tag(":"),
+    )(input)?;
+    let (input, _) = eol(input)?;
+    Ok((input, name))
+}
+
+fn property(input: &str) -> IResult<&str, &str, ()> {
+    let (input, name) = delimited(
+        tag(":"),
+        take_while1(|c: char| !c.is_whitespace() && c != ':'),
+        tag(":"),
+    )(input)?;
+    Ok((input, name.trim_end_matches('+')))
+}
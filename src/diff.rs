@@ -0,0 +1,461 @@
+use indextree::NodeId;
+use std::collections::{HashMap, HashSet};
+
+use crate::{
+    elements::{Timestamp, Title},
+    headline::node_contents,
+    Headline, Org,
+};
+
+/// Headline-level edit between two documents, see `diff`
+///
+/// `old` nodes belong to the old document and `new` nodes to the new one.
+#[derive(Debug, Clone, PartialEq)]
+pub enum TreeEdit {
+    /// Headline which only exists in the old document
+    Removed { old: NodeId },
+    /// Headline which only exists in the new document, at `index` among the
+    /// children of `parent`, or of the document if `parent` is `None`
+    Added {
+        new: NodeId,
+        parent: Option<NodeId>,
+        index: usize,
+    },
+    /// Headline which has a different parent, or is reordered among its
+    /// siblings, moved to `index` among the children of `parent`
+    Moved {
+        old: NodeId,
+        new: NodeId,
+        parent: Option<NodeId>,
+        index: usize,
+    },
+    /// Change of a headline existing in both documents
+    Changed {
+        old: NodeId,
+        new: NodeId,
+        change: HeadlineChange,
+    },
+}
+
+/// Change of a headline, see `TreeEdit::Changed`
+#[derive(Debug, Clone, PartialEq)]
+pub enum HeadlineChange {
+    /// Number of stars
+    Level { from: usize, to: usize },
+    /// Raw title text
+    Title { from: String, to: String },
+    /// Todo keyword
+    Keyword {
+        from: Option<String>,
+        to: Option<String>,
+    },
+    /// Priority cookie
+    Priority {
+        from: Option<char>,
+        to: Option<char>,
+    },
+    /// Tags
+    Tags { from: Vec<String>, to: Vec<String> },
+    /// Property of the property drawer, keys are compared case-insensitively
+    Property {
+        key: String,
+        from: Option<String>,
+        to: Option<String>,
+    },
+    /// `SCHEDULED`, `DEADLINE` or `CLOSED` timestamp
+    Planning {
+        keyword: &'static str,
+        from: Option<Box<Timestamp<'static>>>,
+        to: Option<Box<Timestamp<'static>>>,
+    },
+    /// Contents of the section, i.e. everything between the title and the
+    /// first child
+    Section,
+}
+
+/// Compares the outlines of two documents.
+///
+/// Headlines of `old` are matched with headlines of `new` in four passes,
+/// each pass only considers headlines not matched yet:
+///
+/// 1. same `ID` property,
+/// 2. same `CUSTOM_ID` property,
+/// 3. same title, whitespaces collapsed; a headline whose parent is matched
+///    with the other's parent is preferred,
+/// 4. similar title, i.e. at least half of their words are shared, among
+///    headlines whose parents are matched with each other, or are both
+///    top-level.
+///
+/// Unmatched headlines of `old` are `Removed` and unmatched headlines of
+/// `new` are `Added`. A matched headline is `Moved` if its parent isn't
+/// matched with the new parent, or it's out of order among its siblings,
+/// using the fewest moves. The remaining differences of matched headlines
+/// are reported as `Changed`, section contents are compared as org text.
+///
+/// Edits are ordered by document order: `Removed` ones of `old` first, then
+/// `Added`, `Moved` and `Changed` ones of `new`. Applying them in this order
+/// transforms the outline of `old` into the one of `new`.
+///
+/// ```rust
+/// use orgize::{diff, HeadlineChange, Org, TreeEdit};
+///
+/// let old = Org::parse("* TODO a\n* b\n");
+/// let new = Org::parse("* b\n* DONE a\n");
+///
+/// let edits = diff(&old, &new);
+/// assert_eq!(edits.len(), 2);
+/// assert!(matches!(edits[0], TreeEdit::Moved { index: 0, .. }));
+/// assert!(matches!(
+///     &edits[1],
+///     TreeEdit::Changed { change: HeadlineChange::Keyword { .. }, .. }
+/// ));
+/// ```
+pub fn diff(old: &Org, new: &Org) -> Vec<TreeEdit> {
+    let old_outline = Outline::new(old);
+    let new_outline = Outline::new(new);
+    let matches = match_headlines(&old_outline, &new_outline);
+    let matched: HashSet<NodeId> = matches.values().copied().collect();
+
+    let mut edits = Vec::new();
+
+    for entry in &old_outline.entries {
+        if !matched.contains(&entry.node) {
+            edits.push(TreeEdit::Removed { old: entry.node });
+        }
+    }
+
+    let moved = moved_headlines(&old_outline, &new_outline, &matches);
+
+    for entry in &new_outline.entries {
+        let old_node = match matches.get(&entry.node) {
+            Some(&node) => node,
+            None => {
+                edits.push(TreeEdit::Added {
+                    new: entry.node,
+                    parent: entry.parent,
+                    index: entry.index,
+                });
+                continue;
+            }
+        };
+
+        if moved.contains(&entry.node) {
+            edits.push(TreeEdit::Moved {
+                old: old_node,
+                new: entry.node,
+                parent: entry.parent,
+                index: entry.index,
+            });
+        }
+
+        let old_entry = &old_outline.entries[old_outline.positions[&old_node]];
+        for change in changes(old, old_entry, new, entry) {
+            edits.push(TreeEdit::Changed {
+                old: old_node,
+                new: entry.node,
+                change,
+            });
+        }
+    }
+
+    edits
+}
+
+// headlines of a document in document order
+struct Outline {
+    entries: Vec<Entry>,
+    // position of each headline in `entries`
+    positions: HashMap<NodeId, usize>,
+}
+
+struct Entry {
+    headline: Headline,
+    node: NodeId,
+    parent: Option<NodeId>,
+    // index among the children of `parent`
+    index: usize,
+    title: String,
+    id: Option<String>,
+    custom_id: Option<String>,
+}
+
+impl Outline {
+    fn new(org: &Org) -> Outline {
+        let mut entries = Vec::new();
+        let mut children = HashMap::new();
+
+        for headline in org.headlines() {
+            let title = headline.title(org);
+            let parent = headline.parent(org).map(Headline::headline_node);
+            let index = children.entry(parent).or_insert(0);
+            entries.push(Entry {
+                headline,
+                node: headline.headline_node(),
+                parent,
+                index: *index,
+                title: normalize(&title.raw),
+                id: property(title, "ID"),
+                custom_id: property(title, "CUSTOM_ID"),
+            });
+            *index += 1;
+        }
+
+        let positions = entries
+            .iter()
+            .enumerate()
+            .map(|(i, entry)| (entry.node, i))
+            .collect();
+
+        Outline { entries, positions }
+    }
+}
+
+fn property(title: &Title, key: &str) -> Option<String> {
+    title
+        .properties
+        .get(key)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+        .map(String::from)
+}
+
+// collapses whitespaces
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+// returns the matched old headline of each new headline
+fn match_headlines(old: &Outline, new: &Outline) -> HashMap<NodeId, NodeId> {
+    let mut matches = HashMap::new();
+    let mut used = HashSet::new();
+
+    let keys: [fn(&Entry) -> Option<&str>; 2] = [|e| e.id.as_deref(), |e| e.custom_id.as_deref()];
+    for key in keys.iter() {
+        let mut nodes = HashMap::new();
+        for entry in old.entries.iter().filter(|e| !used.contains(&e.node)) {
+            if let Some(key) = key(entry) {
+                nodes.entry(key).or_insert(entry.node);
+            }
+        }
+        for entry in &new.entries {
+            if matches.contains_key(&entry.node) {
+                continue;
+            }
+            if let Some(&node) = key(entry).and_then(|key| nodes.get(key)) {
+                if used.insert(node) {
+                    matches.insert(entry.node, node);
+                }
+            }
+        }
+    }
+
+    for entry in &new.entries {
+        if matches.contains_key(&entry.node) {
+            continue;
+        }
+        let candidates: Vec<_> = old
+            .entries
+            .iter()
+            .filter(|e| !used.contains(&e.node) && e.title == entry.title)
+            .collect();
+        let candidate = candidates
+            .iter()
+            .find(|e| same_parent(&matches, e, entry))
+            .or_else(|| candidates.first());
+        if let Some(candidate) = candidate {
+            used.insert(candidate.node);
+            matches.insert(entry.node, candidate.node);
+        }
+    }
+
+    for entry in &new.entries {
+        if matches.contains_key(&entry.node) {
+            continue;
+        }
+        let mut best: Option<(f64, NodeId)> = None;
+        for candidate in old
+            .entries
+            .iter()
+            .filter(|e| !used.contains(&e.node) && same_parent(&matches, e, entry))
+        {
+            let similarity = similarity(&candidate.title, &entry.title);
+            if similarity >= 0.5 && best.map_or(true, |(best, _)| similarity > best) {
+                best = Some((similarity, candidate.node));
+            }
+        }
+        if let Some((_, node)) = best {
+            used.insert(node);
+            matches.insert(entry.node, node);
+        }
+    }
+
+    matches
+}
+
+// whether the parent of `new` is matched with the parent of `old`, or both
+// are top-level
+fn same_parent(matches: &HashMap<NodeId, NodeId>, old: &Entry, new: &Entry) -> bool {
+    match new.parent {
+        Some(parent) => matches
+            .get(&parent)
+            .is_some_and(|&parent| old.parent == Some(parent)),
+        None => old.parent.is_none(),
+    }
+}
+
+// shared words over all words, case-insensitively
+fn similarity(a: &str, b: &str) -> f64 {
+    let a: HashSet<_> = a.split_whitespace().map(str::to_lowercase).collect();
+    let b: HashSet<_> = b.split_whitespace().map(str::to_lowercase).collect();
+    let all = a.union(&b).count();
+    if all == 0 {
+        return 0.0;
+    }
+    a.intersection(&b).count() as f64 / all as f64
+}
+
+// new headlines whose parent changed, or which are out of order among their
+// siblings
+fn moved_headlines(
+    old: &Outline,
+    new: &Outline,
+    matches: &HashMap<NodeId, NodeId>,
+) -> HashSet<NodeId> {
+    let mut moved = HashSet::new();
+    // headlines staying under the same parent, along with their old indices
+    let mut siblings: HashMap<Option<NodeId>, Vec<(NodeId, usize)>> = HashMap::new();
+
+    for entry in &new.entries {
+        let old_entry = match matches.get(&entry.node) {
+            Some(node) => &old.entries[old.positions[node]],
+            None => continue,
+        };
+        if same_parent(matches, old_entry, entry) {
+            siblings
+                .entry(entry.parent)
+                .or_default()
+                .push((entry.node, old_entry.index));
+        } else {
+            moved.insert(entry.node);
+        }
+    }
+
+    for siblings in siblings.values() {
+        let indices: Vec<_> = siblings.iter().map(|&(_, index)| index).collect();
+        let kept = longest_increasing(&indices);
+        moved.extend(
+            siblings
+                .iter()
+                .enumerate()
+                .filter(|(i, _)| !kept.contains(i))
+                .map(|(_, &(node, _))| node),
+        );
+    }
+
+    moved
+}
+
+// positions of a longest increasing subsequence of `values`
+fn longest_increasing(values: &[usize]) -> HashSet<usize> {
+    // position of the smallest last value of increasing subsequences of
+    // each length
+    let mut tails: Vec<usize> = Vec::new();
+    let mut previous = vec![None; values.len()];
+
+    for (i, &value) in values.iter().enumerate() {
+        let len = tails.partition_point(|&j| values[j] < value);
+        previous[i] = len.checked_sub(1).map(|len| tails[len]);
+        if len == tails.len() {
+            tails.push(i);
+        } else {
+            tails[len] = i;
+        }
+    }
+
+    let mut kept = HashSet::new();
+    let mut last = tails.last().copied();
+    while let Some(i) = last {
+        kept.insert(i);
+        last = previous[i];
+    }
+    kept
+}
+
+fn changes(old: &Org, old_entry: &Entry, new: &Org, new_entry: &Entry) -> Vec<HeadlineChange> {
+    let from = old_entry.headline.title(old);
+    let to = new_entry.headline.title(new);
+    let mut changes = Vec::new();
+
+    if from.level != to.level {
+        changes.push(HeadlineChange::Level {
+            from: from.level,
+            to: to.level,
+        });
+    }
+    if from.raw != to.raw {
+        changes.push(HeadlineChange::Title {
+            from: from.raw.to_string(),
+            to: to.raw.to_string(),
+        });
+    }
+    if from.keyword != to.keyword {
+        changes.push(HeadlineChange::Keyword {
+            from: from.keyword.as_deref().map(String::from),
+            to: to.keyword.as_deref().map(String::from),
+        });
+    }
+    if from.priority != to.priority {
+        changes.push(HeadlineChange::Priority {
+            from: from.priority,
+            to: to.priority,
+        });
+    }
+    if from.tags != to.tags {
+        changes.push(HeadlineChange::Tags {
+            from: from.tags.iter().map(|tag| tag.to_string()).collect(),
+            to: to.tags.iter().map(|tag| tag.to_string()).collect(),
+        });
+    }
+
+    let mut keys = HashSet::new();
+    for (key, _) in from.properties.iter().chain(to.properties.iter()) {
+        if !keys.insert(key.to_ascii_uppercase()) {
+            continue;
+        }
+        let (old_value, new_value) = (from.properties.get(key), to.properties.get(key));
+        if old_value != new_value {
+            changes.push(HeadlineChange::Property {
+                key: key.to_string(),
+                from: old_value.map(String::from),
+                to: new_value.map(String::from),
+            });
+        }
+    }
+
+    let planning = [
+        ("SCHEDULED", from.scheduled(), to.scheduled()),
+        ("DEADLINE", from.deadline(), to.deadline()),
+        ("CLOSED", from.closed(), to.closed()),
+    ];
+    for &(keyword, old_timestamp, new_timestamp) in planning.iter() {
+        if old_timestamp != new_timestamp {
+            changes.push(HeadlineChange::Planning {
+                keyword,
+                from: old_timestamp.map(|timestamp| Box::new(timestamp.clone().into_owned())),
+                to: new_timestamp.map(|timestamp| Box::new(timestamp.clone().into_owned())),
+            });
+        }
+    }
+
+    let section = |org: &Org, headline: Headline| {
+        headline
+            .section_node()
+            .map(|node| node_contents(node, org))
+            .unwrap_or_default()
+    };
+    if section(old, old_entry.headline) != section(new, new_entry.headline) {
+        changes.push(HeadlineChange::Section);
+    }
+
+    changes
+}
@@ -0,0 +1,366 @@
+//! Durations of clocks, efforts and clock tables.
+
+use std::error::Error;
+use std::fmt;
+use std::iter::Sum;
+use std::ops::Add;
+use std::str::FromStr;
+
+use indextree::NodeId;
+
+use crate::{elements::Element, formula::cell_text, Org};
+
+const UNITS: &[(&str, u64)] = &[
+    ("min", 1),
+    ("h", 60),
+    ("d", 60 * 24),
+    ("w", 60 * 24 * 7),
+    ("m", 60 * 24 * 30),
+    ("y", 60 * 24 * 365),
+];
+
+/// Duration, in whole minutes
+///
+/// It's parsed from `H:MM` and `H:MM:SS` strings, like the `=>` value of
+/// clocks, from unit strings like `2h 30min` or `0.5d`, and from mixed
+/// strings like `1d 2:30`. Units are `min`, `h`, `d`, `w`, `m` (30 days)
+/// and `y` (365 days), and a plain number is a number of minutes.
+///
+/// Adding durations saturates at `OrgDuration::MAX`, use `checked_add` to
+/// detect overflows.
+///
+/// ```rust
+/// use orgize::duration::OrgDuration;
+///
+/// let total: OrgDuration = ["1d 2:30", "0:45", "1.5h"]
+///     .iter()
+///     .map(|s| s.parse::<OrgDuration>().unwrap())
+///     .sum();
+///
+/// assert_eq!(total.minutes(), 1440 + 150 + 45 + 90);
+/// assert_eq!(total.to_string(), "28:45");
+/// assert_eq!(total.format_days(), "1d 4:45");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct OrgDuration {
+    minutes: u64,
+}
+
+/// Error returned by parsing an `OrgDuration`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DurationError {
+    /// The string isn't a duration
+    Invalid,
+    /// The duration is too large
+    Overflow,
+}
+
+impl fmt::Display for DurationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            DurationError::Invalid => write!(f, "invalid duration"),
+            DurationError::Overflow => write!(f, "duration is too large"),
+        }
+    }
+}
+
+impl Error for DurationError {}
+
+impl OrgDuration {
+    /// The largest duration.
+    pub const MAX: OrgDuration = OrgDuration { minutes: u64::MAX };
+
+    /// Constructs a duration of `minutes` minutes.
+    pub fn from_minutes(minutes: u64) -> OrgDuration {
+        OrgDuration { minutes }
+    }
+
+    /// Returns the number of minutes.
+    pub fn minutes(self) -> u64 {
+        self.minutes
+    }
+
+    /// Adds two durations, returns `None` if it overflows.
+    pub fn checked_add(self, other: OrgDuration) -> Option<OrgDuration> {
+        self.minutes
+            .checked_add(other.minutes)
+            .map(OrgDuration::from_minutes)
+    }
+
+    /// Formats the duration like `1d 2:30`, days are omitted if there's
+    /// none.
+    pub fn format_days(self) -> String {
+        let days = self.minutes / (60 * 24);
+        let rest = OrgDuration::from_minutes(self.minutes % (60 * 24));
+        if days == 0 {
+            rest.to_string()
+        } else {
+            format!("{}d {}", days, rest)
+        }
+    }
+}
+
+impl fmt::Display for OrgDuration {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}:{:02}", self.minutes / 60, self.minutes % 60)
+    }
+}
+
+impl FromStr for OrgDuration {
+    type Err = DurationError;
+
+    fn from_str(s: &str) -> Result<OrgDuration, DurationError> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Err(DurationError::Invalid);
+        }
+        if s.bytes().all(|c| c.is_ascii_digit()) {
+            return s
+                .parse()
+                .map(OrgDuration::from_minutes)
+                .map_err(|_| DurationError::Overflow);
+        }
+
+        let mut minutes = 0u64;
+        let mut rest = s;
+        while !rest.is_empty() {
+            let (value, rest_) = if rest.contains(':') && !rest.contains(char::is_whitespace) {
+                (parse_clock(rest)?, "")
+            } else {
+                parse_unit(rest)?
+            };
+            minutes = minutes.checked_add(value).ok_or(DurationError::Overflow)?;
+            rest = rest_.trim_start();
+        }
+
+        Ok(OrgDuration::from_minutes(minutes))
+    }
+}
+
+// parses `H:MM` or `H:MM:SS`, seconds are rounded to the nearest minute
+fn parse_clock(s: &str) -> Result<u64, DurationError> {
+    let mut parts = s.split(':');
+    let mut next = || -> Result<Option<u64>, DurationError> {
+        match parts.next() {
+            Some(part) if !part.is_empty() && part.bytes().all(|c| c.is_ascii_digit()) => {
+                part.parse().map(Some).map_err(|_| DurationError::Overflow)
+            }
+            Some(_) => Err(DurationError::Invalid),
+            None => Ok(None),
+        }
+    };
+    let hours = next()?.ok_or(DurationError::Invalid)?;
+    let minutes = next()?.ok_or(DurationError::Invalid)?;
+    let seconds = next()?.unwrap_or(0);
+    if next()?.is_some() {
+        return Err(DurationError::Invalid);
+    }
+
+    hours
+        .checked_mul(60)
+        .and_then(|m| m.checked_add(minutes))
+        .and_then(|m| m.checked_add(u64::from(seconds >= 30)))
+        .ok_or(DurationError::Overflow)
+}
+
+// parses a number followed by a unit, like `2h` or `1.5 d`
+fn parse_unit(s: &str) -> Result<(u64, &str), DurationError> {
+    let end = s
+        .find(|c: char| !c.is_ascii_digit() && c != '.')
+        .ok_or(DurationError::Invalid)?;
+    let (number, rest) = s.split_at(end);
+    let rest = rest.trim_start();
+    let len = rest
+        .find(|c: char| !c.is_ascii_alphabetic())
+        .unwrap_or(rest.len());
+    let (unit, rest) = rest.split_at(len);
+    let &(_, factor) = UNITS
+        .iter()
+        .find(|(name, _)| *name == unit)
+        .ok_or(DurationError::Invalid)?;
+
+    let value = if number.contains('.') {
+        let number: f64 = number.parse().map_err(|_| DurationError::Invalid)?;
+        let value = (number * factor as f64).round();
+        if value >= u64::MAX as f64 {
+            return Err(DurationError::Overflow);
+        }
+        value as u64
+    } else if number.is_empty() {
+        return Err(DurationError::Invalid);
+    } else {
+        number
+            .parse::<u64>()
+            .ok()
+            .and_then(|number| number.checked_mul(factor))
+            .ok_or(DurationError::Overflow)?
+    };
+
+    Ok((value, rest))
+}
+
+impl Add for OrgDuration {
+    type Output = OrgDuration;
+
+    fn add(self, other: OrgDuration) -> OrgDuration {
+        OrgDuration::from_minutes(self.minutes.saturating_add(other.minutes))
+    }
+}
+
+impl Sum for OrgDuration {
+    fn sum<I: Iterator<Item = OrgDuration>>(iter: I) -> OrgDuration {
+        iter.fold(OrgDuration::default(), Add::add)
+    }
+}
+
+impl<'a> Sum<&'a OrgDuration> for OrgDuration {
+    fn sum<I: Iterator<Item = &'a OrgDuration>>(iter: I) -> OrgDuration {
+        iter.copied().sum()
+    }
+}
+
+#[cfg(feature = "ser")]
+impl serde::Serialize for OrgDuration {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl Org<'_> {
+    /// Returns the total time of clocktable `node`, which is a
+    /// `#+BEGIN: clocktable` dynamic block.
+    ///
+    /// It's read from the `*Total time*` row of the block's table, so the
+    /// table is what was written when the block was last updated.
+    ///
+    /// ```rust
+    /// use orgize::{duration::OrgDuration, Element, Org};
+    ///
+    /// let org = Org::parse(
+    ///     "#+BEGIN: clocktable :scope file\n\
+    ///      | Headline     | Time   |\n\
+    ///      |--------------+--------|\n\
+    ///      | *Total time* | *1d 2:30* |\n\
+    ///      |--------------+--------|\n\
+    ///      | a            | 1d 2:30 |\n\
+    ///      #+END:\n",
+    /// );
+    /// let section = org.document().section_node().unwrap();
+    /// let block = section
+    ///     .children(org.arena())
+    ///     .find(|&node| matches!(org[node], Element::DynBlock(_)))
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     org.clocktable_total(block),
+    ///     Some(OrgDuration::from_minutes(26 * 60 + 30))
+    /// );
+    /// ```
+    pub fn clocktable_total(&self, node: NodeId) -> Option<OrgDuration> {
+        match &self[node] {
+            Element::DynBlock(block) if block.block_name.eq_ignore_ascii_case("clocktable") => (),
+            _ => return None,
+        }
+
+        let table = node
+            .descendants(&self.arena)
+            .find(|&node| matches!(self[node], Element::Table(_)))?;
+
+        table
+            .children(&self.arena)
+            .map(|row| {
+                row.children(&self.arena)
+                    .map(|cell| cell_text(self, cell))
+                    .collect::<Vec<_>>()
+            })
+            .find_map(|cells| {
+                let mut cells = cells.iter().map(|cell| cell.trim().trim_matches('*'));
+                cells.find(|cell| cell.eq_ignore_ascii_case("Total time"))?;
+                cells.find(|cell| !cell.is_empty())?.parse().ok()
+            })
+    }
+}
+
+#[test]
+fn parse() {
+    fn parse(s: &str) -> Result<u64, DurationError> {
+        s.parse::<OrgDuration>().map(OrgDuration::minutes)
+    }
+
+    assert_eq!(parse("1:30"), Ok(90));
+    assert_eq!(parse(" 12:05 "), Ok(725));
+    assert_eq!(parse("0:10:30"), Ok(11));
+    assert_eq!(parse("0:10:29"), Ok(10));
+    assert_eq!(parse("45"), Ok(45));
+    assert_eq!(parse("2h"), Ok(120));
+    assert_eq!(parse("2h 30min"), Ok(150));
+    assert_eq!(parse("2h30min"), Ok(150));
+    assert_eq!(parse("1.5 h"), Ok(90));
+    assert_eq!(parse("0.5d"), Ok(720));
+    assert_eq!(parse("1w 1d"), Ok(8 * 1440));
+    assert_eq!(parse("1m"), Ok(30 * 1440));
+    assert_eq!(parse("1y"), Ok(365 * 1440));
+    assert_eq!(parse("1d 2:30"), Ok(1440 + 150));
+    assert_eq!(parse("1d2:30"), Err(DurationError::Invalid));
+    assert_eq!(parse("1d 2:30 1h"), Err(DurationError::Invalid));
+
+    assert_eq!(parse(""), Err(DurationError::Invalid));
+    assert_eq!(parse("h"), Err(DurationError::Invalid));
+    assert_eq!(parse("2 hours"), Err(DurationError::Invalid));
+    assert_eq!(parse("1:"), Err(DurationError::Invalid));
+    assert_eq!(parse(":30"), Err(DurationError::Invalid));
+    assert_eq!(parse("1:2:3:4"), Err(DurationError::Invalid));
+
+    assert_eq!(parse("18446744073709551615"), Ok(u64::MAX));
+    assert_eq!(parse("18446744073709551616"), Err(DurationError::Overflow));
+    assert_eq!(parse("999999999999999999y"), Err(DurationError::Overflow));
+    assert_eq!(parse("1e30h"), Err(DurationError::Invalid));
+    assert_eq!(
+        parse("307445734561825860:15 1h"),
+        Err(DurationError::Invalid)
+    );
+    assert_eq!(
+        parse("300000000000000000y 1d"),
+        Err(DurationError::Overflow)
+    );
+}
+
+#[test]
+fn format() {
+    assert_eq!(OrgDuration::from_minutes(0).to_string(), "0:00");
+    assert_eq!(OrgDuration::from_minutes(65).to_string(), "1:05");
+    assert_eq!(OrgDuration::from_minutes(1590).to_string(), "26:30");
+    assert_eq!(OrgDuration::from_minutes(1590).format_days(), "1d 2:30");
+    assert_eq!(OrgDuration::from_minutes(150).format_days(), "2:30");
+    assert_eq!(OrgDuration::from_minutes(2880).format_days(), "2d 0:00");
+
+    for s in ["0:00", "1:05", "26:30", "1d 2:30", "2d 0:00"] {
+        let duration: OrgDuration = s.parse().unwrap();
+        assert!(duration.to_string() == s || duration.format_days() == s);
+    }
+}
+
+#[test]
+fn sum() {
+    let durations: Vec<OrgDuration> = ["1d 2:30", "0:45", "2h"]
+        .iter()
+        .map(|s| s.parse().unwrap())
+        .collect();
+    assert_eq!(
+        durations.iter().sum::<OrgDuration>(),
+        OrgDuration::from_minutes(1440 + 150 + 45 + 120)
+    );
+    assert_eq!(
+        Vec::<OrgDuration>::new().into_iter().sum::<OrgDuration>(),
+        OrgDuration::default()
+    );
+
+    let one = OrgDuration::from_minutes(1);
+    assert_eq!(OrgDuration::MAX + one, OrgDuration::MAX);
+    assert_eq!(OrgDuration::MAX.checked_add(one), None);
+    assert_eq!(
+        [OrgDuration::MAX, one, one].iter().sum::<OrgDuration>(),
+        OrgDuration::MAX
+    );
+    assert_eq!(one.checked_add(one), Some(OrgDuration::from_minutes(2)));
+}
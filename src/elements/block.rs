@@ -7,7 +7,7 @@ use nom::{
     IResult,
 };
 
-use crate::elements::Element;
+use crate::elements::{Element, ElementKind};
 use crate::parse::combinators::{blank_lines_count, line, lines_till};
 
 /// Special Block Element
@@ -146,6 +146,7 @@ impl CommentBlock<'_> {
 #[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(feature = "ser", derive(serde::Serialize))]
 pub struct ExampleBlock<'a> {
+    /// Switches, e.g. `-n -r`
     #[cfg_attr(feature = "ser", serde(skip_serializing_if = "Option::is_none"))]
     pub data: Option<Cow<'a, str>>,
     ///  Block contents
@@ -163,6 +164,11 @@ impl ExampleBlock<'_> {
             post_blank: self.post_blank,
         }
     }
+
+    /// Returns switches of this block, see `Switches`.
+    pub fn switches(&self) -> Switches<'_> {
+        Switches::parse(self.data.as_deref().unwrap_or_default())
+    }
 }
 
 /// Export Block Element
@@ -197,7 +203,12 @@ pub struct SourceBlock<'a> {
     pub contents: Cow<'a, str>,
     /// Language of the code in the block
     pub language: Cow<'a, str>,
+    /// Switches after the language, e.g. `-n -l "(ref:%s)"`, see `Switches`
+    pub switches: Cow<'a, str>,
+    /// Header arguments after the switches, e.g. `:exports both`
     pub arguments: Cow<'a, str>,
+    /// Block indent, number of whitespaces before `#+BEGIN_SRC`
+    pub indent: usize,
     /// Numbers of blank lines between last block's line and next non-blank line
     /// or buffer's end
     pub post_blank: usize,
@@ -207,17 +218,162 @@ impl SourceBlock<'_> {
     pub fn into_owned(self) -> SourceBlock<'static> {
         SourceBlock {
             language: self.language.into_owned().into(),
+            switches: self.switches.into_owned().into(),
             arguments: self.arguments.into_owned().into(),
             contents: self.contents.into_owned().into(),
+            indent: self.indent,
             post_blank: self.post_blank,
         }
     }
 
-    // TODO: fn number_lines() -> Some(New) | Some(Continued) | None {  }
-    // TODO: fn preserve_indent() -> bool {  }
-    // TODO: fn use_labels() -> bool {  }
-    // TODO: fn label_fmt() -> Option<String> {  }
-    // TODO: fn retain_labels() -> bool {  }
+    /// Returns switches of this block, see `Switches`.
+    pub fn parse_switches(&self) -> Switches<'_> {
+        Switches::parse(&self.switches)
+    }
+}
+
+/// Switches of source and example blocks, e.g. `-n 10 -r -l "(ref:%s)"`
+///
+/// Each switch is a `-` or `+` followed by a letter, and an optional number
+/// or quoted value.
+///
+/// ```rust
+/// use orgize::elements::{NumberLines, Switches};
+///
+/// let switches = Switches::parse("-n 10 -r -l \"[ref %s]\" -i");
+///
+/// assert_eq!(switches.number_lines(), Some(NumberLines::New(10)));
+/// assert!(switches.preserve_indent());
+/// assert!(!switches.retain_labels());
+/// assert_eq!(switches.label_fmt(), Some("[ref %s]"));
+/// assert_eq!(switches.get('l'), Some(Some("\"[ref %s]\"")));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Switches<'a> {
+    switches: Vec<(&'a str, Option<&'a str>)>,
+}
+
+/// Line numbering of a block, see `Switches::number_lines`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NumberLines {
+    /// `-n`, numbering starts at the given number, default is `1`
+    New(usize),
+    /// `+n`, numbering continues from the previous numbered block, plus the
+    /// given number, default is `0`
+    Continued(usize),
+}
+
+impl<'a> Switches<'a> {
+    /// Parses switches, anything after them is ignored.
+    pub fn parse(input: &'a str) -> Switches<'a> {
+        let mut switches = Vec::new();
+        let mut rest = input;
+        while let Some((tail, switch)) = parse_switch(rest) {
+            switches.push(switch);
+            rest = tail;
+        }
+        Switches { switches }
+    }
+
+    /// Returns the value of switch `-name` or `+name`, `Some(None)` if it
+    /// has no value. Quoted values are returned with their quotes.
+    pub fn get(&self, name: char) -> Option<Option<&'a str>> {
+        self.switches
+            .iter()
+            .find(|(switch, _)| switch[1..].starts_with(name))
+            .map(|(_, value)| *value)
+    }
+
+    /// Returns `true` if there's no switch.
+    pub fn is_empty(&self) -> bool {
+        self.switches.is_empty()
+    }
+
+    /// Returns the line numbering given by `-n` or `+n`.
+    pub fn number_lines(&self) -> Option<NumberLines> {
+        self.switches.iter().find_map(|(switch, value)| {
+            let number = value.and_then(|value| value.parse().ok());
+            match *switch {
+                "-n" => Some(NumberLines::New(number.unwrap_or(1))),
+                "+n" => Some(NumberLines::Continued(number.unwrap_or(0))),
+                _ => None,
+            }
+        })
+    }
+
+    /// Returns `true` if indentation is preserved, i.e. `-i`.
+    pub fn preserve_indent(&self) -> bool {
+        self.get('i').is_some()
+    }
+
+    /// Returns `false` if code references are removed from the exported code,
+    /// i.e. `-r`.
+    pub fn retain_labels(&self) -> bool {
+        self.get('r').is_none()
+    }
+
+    /// Returns `false` if links to code references use line numbers
+    /// instead of labels, i.e. `-k`.
+    pub fn use_labels(&self) -> bool {
+        self.get('k').is_none()
+    }
+
+    /// Returns the label format of code references given by `-l`, without
+    /// quotes.
+    pub fn label_fmt(&self) -> Option<&'a str> {
+        let value = self.get('l')??;
+        Some(
+            value
+                .strip_prefix('"')
+                .and_then(|value| value.strip_suffix('"'))
+                .unwrap_or(value),
+        )
+    }
+}
+
+// parses a switch and its value, e.g. `-n 10` or `-l "(ref:%s)"`, along with
+// the following spaces
+fn parse_switch(input: &str) -> Option<(&str, (&str, Option<&str>))> {
+    let input = input.trim_start();
+    let mut chars = input.chars();
+    match (chars.next(), chars.next(), chars.next()) {
+        (Some('-' | '+'), Some(c), next)
+            if c.is_ascii_alphabetic() && next.map_or(true, char::is_whitespace) => {}
+        _ => return None,
+    }
+    let (switch, tail) = input.split_at(2);
+
+    let rest = tail.trim_start();
+    let value_len = if let Some(quoted) = rest.strip_prefix('"') {
+        quoted.find('"').map(|end| end + 2)
+    } else {
+        let digits = rest.len() - rest.trim_start_matches(|c: char| c.is_ascii_digit()).len();
+        Some(digits).filter(|&digits| {
+            digits > 0
+                && rest[digits..]
+                    .chars()
+                    .next()
+                    .map_or(true, char::is_whitespace)
+        })
+    };
+    match value_len {
+        Some(len) if tail.starts_with(char::is_whitespace) => {
+            Some((&rest[len..], (switch, Some(&rest[..len]))))
+        }
+        _ => Some((tail, (switch, None))),
+    }
+}
+
+// splits arguments of a source block after its language into switches and
+// header arguments
+fn split_switches(input: &str) -> (&str, &str) {
+    let input = input.trim();
+    let mut rest = input;
+    while let Some((tail, _)) = parse_switch(rest) {
+        rest = tail;
+    }
+    let switches = input[..input.len() - rest.len()].trim_end();
+    (switches, rest.trim_start())
 }
 
 #[derive(Debug)]
@@ -225,6 +381,7 @@ impl SourceBlock<'_> {
 pub(crate) struct RawBlock<'a> {
     pub name: &'a str,
     pub arguments: &'a str,
+    pub indent: usize,
 
     pub pre_blank: usize,
     pub contents: &'a str,
@@ -238,11 +395,31 @@ impl<'a> RawBlock<'a> {
         parse_internal(input).ok()
     }
 
+    /// Returns `true` if this block becomes a `SpecialBlock`.
+    pub fn is_special(&self) -> bool {
+        self.kind() == ElementKind::SpecialBlock
+    }
+
+    /// Returns the kind of the element this block becomes.
+    pub fn kind(&self) -> ElementKind {
+        match &*self.name.to_uppercase() {
+            "CENTER" => ElementKind::CenterBlock,
+            "QUOTE" => ElementKind::QuoteBlock,
+            "VERSE" => ElementKind::VerseBlock,
+            "COMMENT" => ElementKind::CommentBlock,
+            "EXAMPLE" => ElementKind::ExampleBlock,
+            "EXPORT" => ElementKind::ExportBlock,
+            "SRC" => ElementKind::SourceBlock,
+            _ => ElementKind::SpecialBlock,
+        }
+    }
+
     pub fn into_element(self) -> (Element<'a>, &'a str) {
         let RawBlock {
             name,
             contents,
             arguments,
+            indent,
             pre_blank,
             contents_without_blank_lines,
             post_blank,
@@ -292,21 +469,24 @@ impl<'a> RawBlock<'a> {
             }
             .into(),
             "SRC" => {
-                let (language, arguments) = match &arguments {
+                let (language, switches, arguments) = match &arguments {
                     Some(Cow::Borrowed(args)) => {
-                        let (language, arguments) =
-                            args.split_at(args.find(' ').unwrap_or_else(|| args.len()));
-                        (language.into(), arguments.into())
+                        let (language, rest) =
+                            args.split_at(args.find(char::is_whitespace).unwrap_or(args.len()));
+                        let (switches, arguments) = split_switches(rest);
+                        (language.into(), switches.into(), arguments.into())
                     }
-                    None => (Cow::Borrowed(""), Cow::Borrowed("")),
+                    None => (Cow::Borrowed(""), Cow::Borrowed(""), Cow::Borrowed("")),
                     _ => unreachable!(
                         "`parse_block_element` returns `Some(Cow::Borrowed)` or `None`"
                     ),
                 };
                 SourceBlock {
                     arguments,
+                    switches,
                     language,
                     contents: contents.into(),
+                    indent,
                     post_blank,
                 }
                 .into()
@@ -325,7 +505,7 @@ impl<'a> RawBlock<'a> {
 }
 
 fn parse_internal(input: &str) -> IResult<&str, RawBlock, ()> {
-    let (input, _) = space0(input)?;
+    let (input, indent) = space0(input)?;
     let (input, name) = preceded(tag_no_case("#+BEGIN_"), alpha1)(input)?;
     let (input, arguments) = line(input)?;
     let end_line = format!("#+END_{}", name);
@@ -339,6 +519,7 @@ fn parse_internal(input: &str) -> IResult<&str, RawBlock, ()> {
             name,
             contents,
             arguments: arguments.trim(),
+            indent: indent.len(),
             pre_blank,
             contents_without_blank_lines,
             post_blank,
@@ -361,7 +542,8 @@ fn parse() {
                 pre_blank: 0,
                 post_blank: 0,
                 name: "SRC".into(),
-                arguments: ""
+                arguments: "",
+                indent: 0,
             }
         ))
     );
@@ -379,7 +561,8 @@ fn parse() {
                 pre_blank: 0,
                 post_blank: 0,
                 name: "src".into(),
-                arguments: ""
+                arguments: "",
+                indent: 0,
             }
         ))
     );
@@ -400,9 +583,33 @@ console.log('Hello World!');
                 pre_blank: 0,
                 post_blank: 1,
                 name: "SRC".into(),
-                arguments: "javascript"
+                arguments: "javascript",
+                indent: 0,
             }
         ))
     );
     // TODO: more testing
 }
+
+#[test]
+fn switches() {
+    assert_eq!(split_switches(""), ("", ""));
+    assert_eq!(
+        split_switches(" -n 10 -r :exports both"),
+        ("-n 10 -r", ":exports both")
+    );
+    assert_eq!(split_switches(" :var x=-n"), ("", ":var x=-n"));
+    assert_eq!(
+        split_switches(" -l \"(ref: %s)\" -i\t:tangle yes"),
+        ("-l \"(ref: %s)\" -i", ":tangle yes")
+    );
+    // not a switch
+    assert_eq!(split_switches(" -nx"), ("", "-nx"));
+
+    let switches = Switches::parse("+n -k");
+    assert_eq!(switches.number_lines(), Some(NumberLines::Continued(0)));
+    assert!(!switches.use_labels());
+    assert!(switches.retain_labels());
+    assert_eq!(switches.label_fmt(), None);
+    assert!(Switches::parse("").is_empty());
+}
@@ -1,15 +1,8 @@
-use std::borrow::Cow;
-
-use nom::{
-    bytes::complete::tag,
-    character::complete::{char, digit1, space0},
-    combinator::recognize,
-    sequence::separated_pair,
-    IResult,
-};
+use nom::{bytes::complete::tag, character::complete::space0, IResult};
 
+use crate::duration::OrgDuration;
 use crate::elements::timestamp::{parse_timestamp, Datetime, Delay, Repeater, Timestamp};
-use crate::parse::combinators::{blank_lines_count, eol};
+use crate::parse::combinators::{blank_lines_count, duration, eol};
 
 /// Clock Element
 #[cfg_attr(test, derive(PartialEq))]
@@ -31,8 +24,8 @@ pub enum Clock<'a> {
         start_delay: Option<Delay>,
         #[cfg_attr(feature = "ser", serde(skip_serializing_if = "Option::is_none"))]
         end_delay: Option<Delay>,
-        /// Clock duration
-        duration: Cow<'a, str>,
+        /// Clock duration, the value after `=>`
+        duration: OrgDuration,
         /// Numbers of blank lines between the clock line and next non-blank
         /// line or buffer's end
         post_blank: usize,
@@ -74,7 +67,7 @@ impl Clock<'_> {
                 end_repeater,
                 start_delay,
                 end_delay,
-                duration: duration.into_owned().into(),
+                duration,
                 post_blank,
             },
             Clock::Running {
@@ -108,9 +101,9 @@ impl Clock<'_> {
     }
 
     /// Returns clock duration, or `None` if it's running.
-    pub fn duration(&self) -> Option<&str> {
+    pub fn duration(&self) -> Option<OrgDuration> {
         match self {
-            Clock::Closed { duration, .. } => Some(duration),
+            Clock::Closed { duration, .. } => Some(*duration),
             Clock::Running { .. } => None,
         }
     }
@@ -133,6 +126,7 @@ impl Clock<'_> {
                 end_repeater: *end_repeater,
                 start_delay: *start_delay,
                 end_delay: *end_delay,
+                raw: None,
             },
             Clock::Running {
                 start,
@@ -143,6 +137,7 @@ impl Clock<'_> {
                 start: start.clone(),
                 repeater: *repeater,
                 delay: *delay,
+                raw: None,
             },
         }
     }
@@ -162,11 +157,13 @@ fn parse_internal(input: &str) -> IResult<&str, Clock, ()> {
             end_repeater,
             start_delay,
             end_delay,
+            ..
         } => {
             let (input, _) = space0(input)?;
             let (input, _) = tag("=>")(input)?;
             let (input, _) = space0(input)?;
-            let (input, duration) = recognize(separated_pair(digit1, char(':'), digit1))(input)?;
+            let (input, duration) = duration(input)?;
+            let duration = duration.parse().map_err(|_| nom::Err::Error(()))?;
             let (input, _) = eol(input)?;
             let (input, blank) = blank_lines_count(input)?;
             Ok((
@@ -178,7 +175,7 @@ fn parse_internal(input: &str) -> IResult<&str, Clock, ()> {
                     end_repeater,
                     start_delay,
                     end_delay,
-                    duration: duration.into(),
+                    duration,
                     post_blank: blank,
                 },
             ))
@@ -187,6 +184,7 @@ fn parse_internal(input: &str) -> IResult<&str, Clock, ()> {
             start,
             repeater,
             delay,
+            ..
         } => {
             let (input, _) = eol(input)?;
             let (input, blank) = blank_lines_count(input)?;
@@ -252,7 +250,7 @@ fn parse() {
                 end_repeater: None,
                 start_delay: None,
                 end_delay: None,
-                duration: "1:00".into(),
+                duration: OrgDuration::from_minutes(60),
                 post_blank: 1,
             }
         ))
@@ -20,7 +20,11 @@ pub struct Cookie<'a> {
 
 impl Cookie<'_> {
     pub(crate) fn parse(input: &str) -> Option<(&str, Cookie)> {
-        parse_internal(input).ok()
+        // most brackets in text aren't cookies, e.g. `[see note]`
+        match input.as_bytes().get(1) {
+            Some(b'0'..=b'9' | b'/' | b'%') => parse_internal(input).ok(),
+            _ => None,
+        }
     }
 
     pub fn into_owned(self) -> Cookie<'static> {
@@ -28,6 +32,17 @@ impl Cookie<'_> {
             value: self.value.into_owned().into(),
         }
     }
+
+    /// Rewrites this cookie with `done` out of `total` items, keeping its
+    /// type, e.g. `[1/3]` or `[33%]`.
+    pub fn set_statistics(&mut self, done: usize, total: usize) {
+        self.value = if self.value.ends_with("%]") {
+            let percent = (done * 100).checked_div(total).unwrap_or(0);
+            format!("[{}%]", percent).into()
+        } else {
+            format!("[{}/{}]", done, total).into()
+        };
+    }
 }
 
 #[inline]
@@ -116,7 +131,31 @@ fn parse() {
     );
 
     assert!(Cookie::parse("[10% ]").is_none());
+    assert!(Cookie::parse("[see note]").is_none());
+    assert!(Cookie::parse("[2024]").is_none());
+    assert!(Cookie::parse("[TODO]").is_none());
+    assert!(Cookie::parse("[1]").is_none());
+    assert!(Cookie::parse("[2024-03-08 Fri]").is_none());
+    assert!(Cookie::parse("[1/2 ]").is_none());
+    assert!(Cookie::parse("[").is_none());
     assert!(Cookie::parse("[1//100]").is_none());
     assert!(Cookie::parse("[1\\100]").is_none());
     assert!(Cookie::parse("[10%%]").is_none());
 }
+
+#[test]
+fn set_statistics() {
+    let mut cookie = Cookie {
+        value: "[/]".into(),
+    };
+    cookie.set_statistics(1, 3);
+    assert_eq!(cookie.value, "[1/3]");
+
+    let mut cookie = Cookie {
+        value: "[%]".into(),
+    };
+    cookie.set_statistics(1, 3);
+    assert_eq!(cookie.value, "[33%]");
+    cookie.set_statistics(0, 0);
+    assert_eq!(cookie.value, "[0%]");
+}
@@ -0,0 +1,70 @@
+use std::any::Any;
+use std::borrow::Cow;
+use std::fmt;
+
+/// Custom Element, returned by `ParseConfig::custom_parser`
+///
+/// Html export skips it, and org export writes its source text as-is.
+#[cfg_attr(feature = "ser", derive(serde::Serialize))]
+pub struct Custom<'a> {
+    /// Block name, e.g. `roam` of `#+BEGIN_roam`, or keyword key
+    pub name: Cow<'a, str>,
+    /// Source text of this element, without blank lines after it
+    pub raw: Cow<'a, str>,
+    /// Value returned by the custom parser
+    #[cfg_attr(feature = "ser", serde(skip))]
+    pub payload: Box<dyn Any + Send + Sync>,
+    /// Numbers of blank lines between last element's line and next non-blank
+    /// line or buffer's end
+    pub post_blank: usize,
+}
+
+impl Custom<'_> {
+    /// Returns the payload if it's a `T`.
+    pub fn payload<T: Any>(&self) -> Option<&T> {
+        self.payload.downcast_ref()
+    }
+
+    pub fn into_owned(self) -> Custom<'static> {
+        Custom {
+            name: self.name.into_owned().into(),
+            raw: self.raw.into_owned().into(),
+            payload: self.payload,
+            post_blank: self.post_blank,
+        }
+    }
+}
+
+impl fmt::Debug for Custom<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Custom")
+            .field("name", &self.name)
+            .field("raw", &self.raw)
+            .field("post_blank", &self.post_blank)
+            .finish_non_exhaustive()
+    }
+}
+
+/// Source of an element given to `ParseConfig::custom_parser`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CustomSource<'a> {
+    /// Special block, i.e. a block other than center, quote, verse, comment,
+    /// example, export and source blocks
+    Block {
+        /// Block name, e.g. `roam` of `#+BEGIN_roam`
+        name: &'a str,
+        /// Rest of the begin line, trimmed, e.g. `a b` of `#+BEGIN_roam a b`
+        parameters: &'a str,
+        /// Lines between the begin and end lines, with their line endings
+        contents: &'a str,
+    },
+    /// Keyword, other than `#+CALL:`
+    Keyword {
+        /// Keyword key, e.g. `ROAM_ALIAS` of `#+ROAM_ALIAS: a`
+        key: &'a str,
+        /// Optional value, e.g. `b` of `#+CAPTION[b]: a`
+        optional: Option<&'a str>,
+        /// Keyword value, trimmed
+        value: &'a str,
+    },
+}
@@ -7,7 +7,10 @@ use nom::{
     IResult,
 };
 
-use crate::parse::combinators::{blank_lines_count, eol, lines_till};
+use crate::{
+    config::ParseConfig,
+    parse::combinators::{blank_lines_count, eol, lines_till},
+};
 
 /// Drawer Element
 #[derive(Debug, Default, Clone)]
@@ -25,8 +28,13 @@ pub struct Drawer<'a> {
 }
 
 impl Drawer<'_> {
-    pub(crate) fn parse(input: &str) -> Option<(&str, (Drawer, &str))> {
-        parse_drawer(input).ok()
+    pub(crate) fn parse<'a>(
+        input: &'a str,
+        config: &ParseConfig,
+    ) -> Option<(&'a str, (Drawer<'a>, &'a str))> {
+        parse_drawer(input)
+            .ok()
+            .filter(|(_, (drawer, _))| config.drawer_names.allows(&drawer.name))
     }
 
     pub fn into_owned(self) -> Drawer<'static> {
@@ -55,7 +63,7 @@ pub fn parse_drawer_without_blank(input: &str) -> IResult<&str, (Drawer, &str),
     let (input, _) = space0(input)?;
     let (input, name) = delimited(
         tag(":"),
-        take_while1(|c: char| c.is_ascii_alphabetic() || c == '-' || c == '_'),
+        take_while1(|c: char| c.is_alphanumeric() || c == '-' || c == '_'),
         tag(":"),
     )(input)?;
     let (input, _) = eol(input)?;
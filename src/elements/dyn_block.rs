@@ -6,6 +6,7 @@ use nom::{
     IResult,
 };
 
+use crate::elements::HeaderArgs;
 use crate::parse::combinators::{blank_lines_count, line, lines_till};
 
 /// Dynamic Block Element
@@ -31,6 +32,12 @@ impl DynBlock<'_> {
         parse_internal(input).ok()
     }
 
+    /// Parses block arguments as `:name value` parameters, e.g.
+    /// `:scope file` of `#+BEGIN: clocktable :scope file`.
+    pub fn parameters(&self) -> HeaderArgs<'_> {
+        HeaderArgs::parse(self.arguments.as_deref().unwrap_or_default())
+    }
+
     pub fn into_owned(self) -> DynBlock<'static> {
         DynBlock {
             block_name: self.block_name.into_owned().into(),
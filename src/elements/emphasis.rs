@@ -1,6 +1,7 @@
 use bytecount::count;
 use memchr::memchr_iter;
 
+use crate::config::ParseConfig;
 use crate::elements::Element;
 
 #[derive(Debug)]
@@ -11,7 +12,11 @@ pub(crate) struct Emphasis<'a> {
 }
 
 impl<'a> Emphasis<'a> {
-    pub fn parse(text: &str, marker: u8) -> Option<(&str, Emphasis)> {
+    pub fn parse<'b>(
+        text: &'b str,
+        marker: u8,
+        config: &ParseConfig,
+    ) -> Option<(&'b str, Emphasis<'b>)> {
         if text.len() < 3 {
             return None;
         }
@@ -28,6 +33,9 @@ impl<'a> Emphasis<'a> {
                 continue;
             } else if count(&bytes[1..i], b'\n') >= 2 {
                 break;
+            } else if !is_conservative(&text[1..i], marker, config) {
+                // longer contents aren't either
+                break;
             } else if validate_marker(i, text) {
                 return Some((
                     &text[i + 1..],
@@ -60,14 +68,28 @@ impl<'a> Emphasis<'a> {
     }
 }
 
+// see `ParseConfig::conservative_emphasis`
+fn is_conservative(contents: &str, marker: u8, config: &ParseConfig) -> bool {
+    match config.conservative_emphasis {
+        Some(max) if marker != b'=' && marker != b'~' => {
+            // a colon followed by a newline
+            let introduces_list = contents
+                .split('\n')
+                .rev()
+                .skip(1)
+                .any(|line| line.trim_end().ends_with(':'));
+            contents.chars().count() <= max && !introduces_list
+        }
+        _ => true,
+    }
+}
+
 fn validate_marker(pos: usize, text: &str) -> bool {
     if text.as_bytes()[pos - 1].is_ascii_whitespace() {
         false
     } else if let Some(&post) = text.as_bytes().get(pos + 1) {
-        match post {
-            b' ' | b'-' | b'.' | b',' | b':' | b'!' | b'?' | b'\'' | b'\n' | b')' | b'}' => true,
-            _ => false,
-        }
+        // like `org-emphasis-regexp-components`
+        b" \t\r\n-.,:;!?'\")}[".contains(&post)
     } else {
         true
     }
@@ -76,7 +98,7 @@ fn validate_marker(pos: usize, text: &str) -> bool {
 #[test]
 fn parse() {
     assert_eq!(
-        Emphasis::parse("*bold*", b'*'),
+        Emphasis::parse("*bold*", b'*', &ParseConfig::default()),
         Some((
             "",
             Emphasis {
@@ -86,7 +108,7 @@ fn parse() {
         ))
     );
     assert_eq!(
-        Emphasis::parse("*bo*ld*", b'*'),
+        Emphasis::parse("*bo*ld*", b'*', &ParseConfig::default()),
         Some((
             "",
             Emphasis {
@@ -96,7 +118,7 @@ fn parse() {
         ))
     );
     assert_eq!(
-        Emphasis::parse("*bo\nld*", b'*'),
+        Emphasis::parse("*bo\nld*", b'*', &ParseConfig::default()),
         Some((
             "",
             Emphasis {
@@ -105,9 +127,59 @@ fn parse() {
             }
         ))
     );
-    assert_eq!(Emphasis::parse("*bold*a", b'*'), None);
-    assert_eq!(Emphasis::parse("*bold*", b'/'), None);
-    assert_eq!(Emphasis::parse("*bold *", b'*'), None);
-    assert_eq!(Emphasis::parse("* bold*", b'*'), None);
-    assert_eq!(Emphasis::parse("*b\nol\nd*", b'*'), None);
+    assert_eq!(
+        Emphasis::parse("=x=\"", b'=', &ParseConfig::default()),
+        Some((
+            "\"",
+            Emphasis {
+                contents: "x",
+                marker: b'='
+            }
+        ))
+    );
+    assert_eq!(
+        Emphasis::parse("*bold*a", b'*', &ParseConfig::default()),
+        None
+    );
+    assert_eq!(
+        Emphasis::parse("*bold*", b'/', &ParseConfig::default()),
+        None
+    );
+    assert_eq!(
+        Emphasis::parse("*bold *", b'*', &ParseConfig::default()),
+        None
+    );
+    assert_eq!(
+        Emphasis::parse("* bold*", b'*', &ParseConfig::default()),
+        None
+    );
+    assert_eq!(
+        Emphasis::parse("*b\nol\nd*", b'*', &ParseConfig::default()),
+        None
+    );
+}
+
+#[test]
+fn conservative() {
+    let config = ParseConfig {
+        conservative_emphasis: Some(4),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        Emphasis::parse("*bold*", b'*', &config),
+        Some((
+            "",
+            Emphasis {
+                marker: b'*',
+                contents: "bold"
+            }
+        ))
+    );
+    assert_eq!(Emphasis::parse("*bolder*", b'*', &config), None);
+    assert_eq!(Emphasis::parse("*a:\nb*", b'*', &config), None);
+    assert_eq!(
+        Emphasis::parse("=bolder=", b'=', &config).map(|(_, e)| e.contents),
+        Some("bolder")
+    );
 }
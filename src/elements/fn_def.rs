@@ -1,11 +1,15 @@
 use std::borrow::Cow;
 
 use nom::{
-    bytes::complete::{tag, take_while1},
+    branch::alt,
+    bytes::complete::{tag, take_while, take_while1},
+    character::complete::space0,
+    combinator::{recognize, verify},
     sequence::delimited,
     IResult,
 };
 
+use crate::config::ParseConfig;
 use crate::parse::combinators::{blank_lines_count, line};
 
 /// Footnote Definition Element
@@ -13,7 +17,8 @@ use crate::parse::combinators::{blank_lines_count, line};
 #[cfg_attr(feature = "ser", derive(serde::Serialize))]
 #[derive(Debug, Default, Clone)]
 pub struct FnDef<'a> {
-    /// Footnote label, used for reference
+    /// Footnote label, used for reference, as written including surrounding
+    /// spaces
     pub label: Cow<'a, str>,
     /// Numbers of blank lines between last footnote definition's line and next
     /// non-blank line or buffer's end
@@ -21,8 +26,17 @@ pub struct FnDef<'a> {
 }
 
 impl FnDef<'_> {
-    pub(crate) fn parse(input: &str) -> Option<(&str, (FnDef, &str))> {
-        parse_internal(input).ok()
+    pub(crate) fn parse<'a>(
+        input: &'a str,
+        config: &ParseConfig,
+    ) -> Option<(&'a str, (FnDef<'a>, &'a str))> {
+        parse_internal(input, config.legacy_footnote_definitions).ok()
+    }
+
+    /// Returns the label used for matching references, see
+    /// `FnRef::normalized_label`.
+    pub fn normalized_label(&self) -> &str {
+        normalize_label(&self.label)
     }
 
     pub fn into_owned(self) -> FnDef<'static> {
@@ -33,13 +47,46 @@ impl FnDef<'_> {
     }
 }
 
-fn parse_internal(input: &str) -> IResult<&str, (FnDef, &str), ()> {
-    let (input, label) = delimited(
-        tag("[fn:"),
-        take_while1(|c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_'),
-        tag("]"),
-    )(input)?;
+// footnote label with optional surrounding spaces, e.g. ` note-1 `, which
+// are kept
+pub(crate) fn label(input: &str) -> IResult<&str, &str, ()> {
+    recognize(delimited(
+        space0,
+        take_while(|c: char| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.')),
+        space0,
+    ))(input)
+}
+
+// labels are compared case-sensitively like Emacs, but surrounding
+// whitespace is insignificant
+pub(crate) fn normalize_label(label: &str) -> &str {
+    label.trim()
+}
 
+fn parse_internal(input: &str, legacy: bool) -> IResult<&str, (FnDef<'_>, &str), ()> {
+    let (input, label) = if legacy {
+        alt((
+            delimited(
+                tag("[fn:"),
+                verify(label, |s: &str| !normalize_label(s).is_empty()),
+                tag("]"),
+            ),
+            // legacy `[1]` definition
+            delimited(
+                tag("["),
+                take_while1(|c: char| c.is_ascii_digit()),
+                tag("]"),
+            ),
+        ))(input)?
+    } else {
+        delimited(
+            tag("[fn:"),
+            verify(label, |s: &str| !normalize_label(s).is_empty()),
+            tag("]"),
+        )(input)?
+    };
+
+    let (input, _) = space0(input)?;
     let (input, content) = line(input)?;
 
     let (input, post_blank) = blank_lines_count(input)?;
@@ -58,8 +105,10 @@ fn parse_internal(input: &str) -> IResult<&str, (FnDef, &str), ()> {
 
 #[test]
 fn parse() {
+    let config = &ParseConfig::default();
+
     assert_eq!(
-        FnDef::parse("[fn:1] https://orgmode.org"),
+        FnDef::parse("[fn:1] https://orgmode.org", config),
         Some((
             "",
             (
@@ -67,12 +116,12 @@ fn parse() {
                     label: "1".into(),
                     post_blank: 0
                 },
-                " https://orgmode.org"
+                "https://orgmode.org"
             )
         ))
     );
     assert_eq!(
-        FnDef::parse("[fn:word_1] https://orgmode.org"),
+        FnDef::parse("[fn:word_1] https://orgmode.org", config),
         Some((
             "",
             (
@@ -80,12 +129,12 @@ fn parse() {
                     label: "word_1".into(),
                     post_blank: 0,
                 },
-                " https://orgmode.org"
+                "https://orgmode.org"
             )
         ))
     );
     assert_eq!(
-        FnDef::parse("[fn:WORD-1] https://orgmode.org"),
+        FnDef::parse("[fn:WORD-1] https://orgmode.org", config),
         Some((
             "",
             (
@@ -93,12 +142,12 @@ fn parse() {
                     label: "WORD-1".into(),
                     post_blank: 0,
                 },
-                " https://orgmode.org"
+                "https://orgmode.org"
             )
         ))
     );
     assert_eq!(
-        FnDef::parse("[fn:WORD]"),
+        FnDef::parse("[fn:WORD]", config),
         Some((
             "",
             (
@@ -111,7 +160,52 @@ fn parse() {
         ))
     );
 
-    assert!(FnDef::parse("[fn:] https://orgmode.org").is_none());
-    assert!(FnDef::parse("[fn:wor d] https://orgmode.org").is_none());
-    assert!(FnDef::parse("[fn:WORD https://orgmode.org").is_none());
+    assert_eq!(
+        FnDef::parse("[fn: note.1_a ] text", config),
+        Some((
+            "",
+            (
+                FnDef {
+                    label: " note.1_a ".into(),
+                    post_blank: 0,
+                },
+                "text"
+            )
+        ))
+    );
+
+    assert!(FnDef::parse("[fn:] https://orgmode.org", config).is_none());
+    assert!(FnDef::parse("[fn: ] https://orgmode.org", config).is_none());
+    assert!(FnDef::parse("[fn:wor d] https://orgmode.org", config).is_none());
+    assert!(FnDef::parse("[fn:WORD https://orgmode.org", config).is_none());
+}
+
+#[test]
+fn parse_legacy() {
+    let legacy = &ParseConfig {
+        legacy_footnote_definitions: true,
+        ..Default::default()
+    };
+
+    assert!(FnDef::parse("[1] https://orgmode.org", &ParseConfig::default()).is_none());
+    assert_eq!(
+        FnDef::parse("[1] https://orgmode.org", legacy),
+        Some((
+            "",
+            (
+                FnDef {
+                    label: "1".into(),
+                    post_blank: 0,
+                },
+                "https://orgmode.org"
+            )
+        ))
+    );
+    assert_eq!(
+        FnDef::parse("[fn:note] text", legacy).map(|(_, (fn_def, _))| fn_def.label),
+        Some("note".into())
+    );
+
+    assert!(FnDef::parse("[a] text", legacy).is_none());
+    assert!(FnDef::parse("[] text", legacy).is_none());
 }
@@ -2,19 +2,21 @@ use std::borrow::Cow;
 
 use memchr::memchr2_iter;
 use nom::{
-    bytes::complete::{tag, take_while},
+    bytes::complete::tag,
     combinator::opt,
     error::{make_error, ErrorKind},
     sequence::preceded,
     Err, IResult,
 };
 
+use crate::elements::fn_def::{label, normalize_label};
+
 /// Footnote Reference Element
 #[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(feature = "ser", derive(serde::Serialize))]
 #[derive(Debug, Clone)]
 pub struct FnRef<'a> {
-    /// Footnote label
+    /// Footnote label, as written including surrounding spaces
     pub label: Cow<'a, str>,
     #[cfg_attr(feature = "ser", serde(skip_serializing_if = "Option::is_none"))]
     pub definition: Option<Cow<'a, str>>,
@@ -25,6 +27,15 @@ impl FnRef<'_> {
         parse_internal(input).ok()
     }
 
+    /// Returns the label used for matching this reference against
+    /// definitions, with surrounding whitespace trimmed.
+    ///
+    /// Labels are case-sensitive, `[fn:Note]` and `[fn:note]` are different
+    /// footnotes.
+    pub fn normalized_label(&self) -> &str {
+        normalize_label(&self.label)
+    }
+
     pub fn into_owned(self) -> FnRef<'static> {
         FnRef {
             label: self.label.into_owned().into(),
@@ -36,8 +47,7 @@ impl FnRef<'_> {
 #[inline]
 fn parse_internal(input: &str) -> IResult<&str, FnRef, ()> {
     let (input, _) = tag("[fn:")(input)?;
-    let (input, label) =
-        take_while(|c: char| c.is_ascii_alphanumeric() || c == '-' || c == '_')(input)?;
+    let (input, label) = label(input)?;
     let (input, definition) = opt(preceded(tag(":"), balanced_brackets))(input)?;
     let (input, _) = tag("]")(input)?;
 
@@ -107,5 +117,16 @@ fn parse() {
         ))
     );
 
+    assert_eq!(
+        FnRef::parse("[fn: v1.2_a ]"),
+        Some((
+            "",
+            FnRef {
+                label: " v1.2_a ".into(),
+                definition: None
+            },
+        ))
+    );
+
     assert!(FnRef::parse("[fn::[]").is_none());
 }
@@ -0,0 +1,237 @@
+use crate::elements::{BabelCall, InlineCall, InlineSrc, SourceBlock};
+
+/// Header Arguments
+///
+/// Header arguments of source blocks, inline source blocks and babel calls,
+/// e.g. `:exports both :results output`.
+///
+/// ```rust
+/// use orgize::elements::HeaderArgs;
+///
+/// let args = HeaderArgs::parse(":var x=1 y=2 :exports both");
+///
+/// assert_eq!(args.get("var"), Some("x=1 y=2"));
+/// assert_eq!(args.get(":exports"), Some("both"));
+/// assert_eq!(args.get("results"), None);
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct HeaderArgs<'a> {
+    args: Vec<(&'a str, &'a str)>,
+}
+
+impl<'a> HeaderArgs<'a> {
+    pub fn parse(input: &'a str) -> HeaderArgs<'a> {
+        let mut args = HeaderArgs::default();
+        args.push_str(input);
+        args
+    }
+
+    fn push_str(&mut self, input: &'a str) {
+        let mut rest = input.trim();
+        // ignores anything before the first argument
+        while !rest.is_empty() && !rest.starts_with(':') {
+            rest = skip_word(rest);
+        }
+        while !rest.is_empty() {
+            let name_end = rest.find(char::is_whitespace).unwrap_or(rest.len());
+            let (name, mut value) = rest.split_at(name_end);
+            let mut value_end = 0;
+            let start = value;
+            value = value.trim_start();
+            while !value.is_empty() && !value.starts_with(':') {
+                value = skip_word(value);
+                value_end = start.len() - value.len();
+            }
+            self.args.push((&name[1..], start[..value_end].trim()));
+            rest = value;
+        }
+    }
+
+    /// Returns the value of argument `name`, with or without the leading
+    /// colon. The last one wins if the argument is given multiple times.
+    pub fn get(&self, name: &str) -> Option<&'a str> {
+        let name = name.strip_prefix(':').unwrap_or(name);
+        self.args
+            .iter()
+            .rev()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| *value)
+    }
+
+    /// Returns an iterator of arguments names, without the leading colon, and
+    /// values.
+    pub fn iter(&self) -> impl Iterator<Item = (&'a str, &'a str)> + '_ {
+        self.args.iter().copied()
+    }
+
+    /// Returns the value of `:exports` argument, `None` if it's missing or
+    /// unknown.
+    pub fn exports(&self) -> Option<Exports> {
+        match self.get("exports")?.to_ascii_lowercase().as_str() {
+            "code" => Some(Exports::Code),
+            "results" => Some(Exports::Results),
+            "both" => Some(Exports::Both),
+            "none" => Some(Exports::None),
+            _ => None,
+        }
+    }
+}
+
+// skips a word and following whitespaces
+fn skip_word(input: &str) -> &str {
+    let end = input.find(char::is_whitespace).unwrap_or(input.len());
+    input[end..].trim_start()
+}
+
+/// Value of `:exports` header argument
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Exports {
+    /// Exports code only
+    Code,
+    /// Exports results only
+    Results,
+    /// Exports both code and results
+    Both,
+    /// Exports nothing
+    None,
+}
+
+impl Exports {
+    /// Returns `true` if the code should be exported.
+    pub fn code(self) -> bool {
+        matches!(self, Exports::Code | Exports::Both)
+    }
+
+    /// Returns `true` if the results should be exported.
+    pub fn results(self) -> bool {
+        matches!(self, Exports::Results | Exports::Both)
+    }
+}
+
+impl SourceBlock<'_> {
+    /// Returns header arguments of this block.
+    pub fn header_args(&self) -> HeaderArgs<'_> {
+        HeaderArgs::parse(&self.arguments)
+    }
+
+    /// Returns how this block is exported, default is `Exports::Code`.
+    pub fn exports(&self) -> Exports {
+        self.header_args().exports().unwrap_or(Exports::Code)
+    }
+}
+
+impl InlineSrc<'_> {
+    /// Returns header arguments of this inline source block.
+    pub fn header_args(&self) -> HeaderArgs<'_> {
+        HeaderArgs::parse(self.options.as_deref().unwrap_or_default())
+    }
+
+    /// Returns how this inline source block is exported, default is
+    /// `Exports::Code`.
+    pub fn exports(&self) -> Exports {
+        self.header_args().exports().unwrap_or(Exports::Code)
+    }
+}
+
+impl BabelCall<'_> {
+    /// Returns header arguments of this babel call, including both inside
+    /// and end header arguments, e.g. `#+CALL: name[:inside](x=1)[:end] :end`.
+    pub fn header_args(&self) -> HeaderArgs<'_> {
+        let mut args = HeaderArgs::default();
+        let value = &*self.value;
+        let rest = match value.find(['[', '(']) {
+            Some(i) => &value[i..],
+            None => return args,
+        };
+        let rest = match rest.strip_prefix('[') {
+            Some(rest) => {
+                let end = rest.find(']').unwrap_or(rest.len());
+                args.push_str(&rest[..end]);
+                rest.get(end + 1..).unwrap_or_default()
+            }
+            None => rest,
+        };
+        let rest = match rest.strip_prefix('(') {
+            Some(rest) => rest
+                .find(')')
+                .map(|end| &rest[end + 1..])
+                .unwrap_or_default(),
+            None => rest,
+        };
+        let rest = match rest.strip_prefix('[') {
+            Some(rest) => {
+                let end = rest.find(']').unwrap_or(rest.len());
+                args.push_str(&rest[..end]);
+                rest.get(end + 1..).unwrap_or_default()
+            }
+            None => rest,
+        };
+        args.push_str(rest);
+        args
+    }
+
+    /// Returns how this babel call is exported, default is
+    /// `Exports::Results`.
+    pub fn exports(&self) -> Exports {
+        self.header_args().exports().unwrap_or(Exports::Results)
+    }
+}
+
+impl InlineCall<'_> {
+    /// Returns header arguments of this inline babel call, including both
+    /// inside and end header arguments.
+    pub fn header_args(&self) -> HeaderArgs<'_> {
+        let mut args = HeaderArgs::default();
+        if let Some(header) = &self.inside_header {
+            args.push_str(header);
+        }
+        if let Some(header) = &self.end_header {
+            args.push_str(header);
+        }
+        args
+    }
+
+    /// Returns how this inline babel call is exported, default is
+    /// `Exports::Results`.
+    pub fn exports(&self) -> Exports {
+        self.header_args().exports().unwrap_or(Exports::Results)
+    }
+}
+
+#[test]
+fn parse() {
+    let args = HeaderArgs::parse(" :results output   :exports both :noweb");
+    assert_eq!(
+        args.iter().collect::<Vec<_>>(),
+        vec![("results", "output"), ("exports", "both"), ("noweb", "")]
+    );
+    assert_eq!(args.exports(), Some(Exports::Both));
+
+    let args = HeaderArgs::parse("-n :exports none :exports results");
+    assert_eq!(args.get("exports"), Some("results"));
+
+    assert_eq!(HeaderArgs::parse(""), HeaderArgs::default());
+}
+
+#[test]
+fn babel_call() {
+    let call = BabelCall {
+        value: "square[:results output](x=4)[:exports none] :exports both".into(),
+        post_blank: 0,
+    };
+    assert_eq!(
+        call.header_args().iter().collect::<Vec<_>>(),
+        vec![
+            ("results", "output"),
+            ("exports", "none"),
+            ("exports", "both")
+        ]
+    );
+    assert_eq!(call.exports(), Exports::Both);
+
+    let call = BabelCall {
+        value: "square(x=4)".into(),
+        post_blank: 0,
+    };
+    assert_eq!(call.exports(), Exports::Results);
+}
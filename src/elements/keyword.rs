@@ -8,8 +8,13 @@ use nom::{
     IResult,
 };
 
+use indextree::{Arena, NodeEdge, NodeId};
+
+use crate::config::ParseConfig;
 use crate::elements::Element;
 use crate::parse::combinators::{blank_lines_count, line};
+use crate::parsers::{parse_container, Container};
+use crate::Event;
 
 /// Keyword Element
 #[cfg_attr(test, derive(PartialEq))]
@@ -18,6 +23,8 @@ use crate::parse::combinators::{blank_lines_count, line};
 pub struct Keyword<'a> {
     /// Keyword name
     pub key: Cow<'a, str>,
+    /// Keyword optional value, e.g. the short caption in
+    /// `#+CAPTION[short]: long`
     #[cfg_attr(feature = "ser", serde(skip_serializing_if = "Option::is_none"))]
     pub optional: Option<Cow<'a, str>>,
     /// Keyword value
@@ -28,6 +35,39 @@ pub struct Keyword<'a> {
 }
 
 impl Keyword<'_> {
+    /// Parses keyword value into objects, e.g. emphasis, links and entities.
+    ///
+    /// Only values of `CAPTION`, `TITLE`, `AUTHOR` and `DATE` keywords
+    /// contain objects, others are a single text object, see
+    /// `ObjectContext`.
+    ///
+    /// ```rust
+    /// use orgize::{elements::Keyword, Element, Event};
+    ///
+    /// let keyword = Keyword {
+    ///     key: "CAPTION".into(),
+    ///     optional: None,
+    ///     value: "A *bold* caption".into(),
+    ///     post_blank: 0,
+    /// };
+    ///
+    /// let objects = keyword.value_objects();
+    /// assert!(objects
+    ///     .iter()
+    ///     .any(|event| matches!(event, Event::Start(Element::Bold))));
+    /// ```
+    pub fn value_objects(&self) -> Objects<'_> {
+        Objects::parse_in(&self.value, ObjectContext::of_keyword(&self.key))
+    }
+
+    /// Parses keyword optional value into objects, if any.
+    pub fn optional_objects(&self) -> Option<Objects<'_>> {
+        let context = ObjectContext::of_keyword(&self.key);
+        self.optional
+            .as_deref()
+            .map(|optional| Objects::parse_in(optional, context))
+    }
+
     pub fn into_owned(self) -> Keyword<'static> {
         Keyword {
             key: self.key.into_owned().into(),
@@ -38,6 +78,144 @@ impl Keyword<'_> {
     }
 }
 
+/// Objects parsed from a keyword value, see `Keyword::value_objects`
+#[derive(Debug)]
+pub struct Objects<'a> {
+    arena: Arena<Element<'a>>,
+    root: NodeId,
+}
+
+impl<'a> Objects<'a> {
+    /// Parses a string into objects.
+    ///
+    /// Useful for values joined from multiple keywords, e.g. `#+TITLE:`
+    /// returned by `Org::metadata`.
+    pub fn parse(text: &'a str) -> Objects<'a> {
+        Objects::parse_in(text, ObjectContext::Paragraph)
+    }
+
+    /// Parses a string found in `context` into objects, or a single text
+    /// object if the context doesn't contain objects.
+    ///
+    /// ```rust
+    /// use orgize::elements::{ObjectContext, Objects};
+    /// use orgize::{Element, Event};
+    ///
+    /// let has_link = |context| {
+    ///     Objects::parse_in("see [[https://orgmode.org]]", context)
+    ///         .iter()
+    ///         .any(|event| matches!(event, Event::Start(Element::Link(_))))
+    /// };
+    /// assert!(has_link(ObjectContext::ParsedKeyword));
+    /// assert!(!has_link(ObjectContext::PropertyValue));
+    /// ```
+    pub fn parse_in(text: &'a str, context: ObjectContext) -> Objects<'a> {
+        let mut arena = Arena::new();
+        let root = arena.new_node(Element::Paragraph { post_blank: 0 });
+        if context.contains_objects() {
+            parse_container(
+                &mut arena,
+                Container::Inline {
+                    content: text,
+                    node: root,
+                },
+                &ParseConfig::default(),
+            );
+        } else if !text.is_empty() {
+            root.append(
+                arena.new_node(Element::Text { value: text.into() }),
+                &mut arena,
+            );
+        }
+        Objects { arena, root }
+    }
+
+    /// Returns an iterator of `Event`s over the objects.
+    pub fn iter<'b>(&'b self) -> impl Iterator<Item = Event<'a, 'b>> + 'b {
+        self.root
+            .traverse(&self.arena)
+            .filter_map(move |edge| match edge {
+                NodeEdge::Start(node) if node != self.root => {
+                    Some(Event::Start(self.arena[node].get()))
+                }
+                NodeEdge::End(node) if node != self.root => {
+                    Some(Event::End(self.arena[node].get()))
+                }
+                _ => None,
+            })
+    }
+}
+
+/// Contexts of text in a document, and whether it contains objects
+///
+/// The parser parses objects, e.g. emphasis, links and timestamps, in text
+/// of contexts which contain them, like the Org syntax does, and nowhere
+/// else:
+///
+/// | Context                                   | Objects |
+/// |-------------------------------------------|---------|
+/// | Paragraphs, including those in list items | yes     |
+/// | Headline titles                           | yes     |
+/// | Table cells                               | yes     |
+/// | `CAPTION`, `TITLE`, `AUTHOR` and `DATE`   | yes     |
+/// | Values of other keywords                  | no      |
+/// | Headline tags                             | no      |
+/// | Property values                           | no      |
+/// | Parameters of blocks                      | no      |
+///
+/// Text of contexts without objects is kept as-is, e.g. a link in a
+/// property value is a string, and `Objects::parse_in` leaves it as a
+/// single text object.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ObjectContext {
+    /// Paragraphs, including those in list items and greater blocks
+    Paragraph,
+    /// Raw text of headline titles, without keywords, priorities and tags
+    Title,
+    /// Table cells
+    TableCell,
+    /// Values of `CAPTION`, `TITLE`, `AUTHOR` and `DATE` keywords
+    ParsedKeyword,
+    /// Values of other keywords, e.g. `#+OPTIONS:`
+    Keyword,
+    /// Headline tags
+    Tags,
+    /// Values of properties in property drawers and `#+PROPERTY:` keywords
+    PropertyValue,
+    /// Parameters of blocks, e.g. the language and header arguments of
+    /// source blocks
+    BlockParameters,
+}
+
+impl ObjectContext {
+    /// Returns `true` if text in this context contains objects.
+    pub fn contains_objects(self) -> bool {
+        match self {
+            ObjectContext::Paragraph
+            | ObjectContext::Title
+            | ObjectContext::TableCell
+            | ObjectContext::ParsedKeyword => true,
+            ObjectContext::Keyword
+            | ObjectContext::Tags
+            | ObjectContext::PropertyValue
+            | ObjectContext::BlockParameters => false,
+        }
+    }
+
+    /// Returns the context of the value of keyword `key`.
+    pub fn of_keyword(key: &str) -> ObjectContext {
+        const PARSED: [&str; 4] = ["CAPTION", "TITLE", "AUTHOR", "DATE"];
+
+        if PARSED.iter().any(|parsed| key.eq_ignore_ascii_case(parsed)) {
+            ObjectContext::ParsedKeyword
+        } else if key.eq_ignore_ascii_case("PROPERTY") {
+            ObjectContext::PropertyValue
+        } else {
+            ObjectContext::Keyword
+        }
+    }
+}
+
 /// Babel Call Element
 #[derive(Debug, Clone)]
 #[cfg_attr(test, derive(PartialEq))]
@@ -0,0 +1,260 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+/// Kind of an `Element`, without its data, see `Element::kind`
+///
+/// Its name is the one org-element uses, e.g. `src-block`, and kinds
+/// without an org-element counterpart are named after their variant in
+/// kebab-case, e.g. `title`. It's written by `Display` and is the `type` of
+/// serialized elements. `FromStr` reads it back, and also accepts
+/// `property-drawer` for drawers and variant names in kebab-case, e.g.
+/// `source-block`.
+///
+/// ```rust
+/// use orgize::elements::ElementKind;
+///
+/// assert_eq!(ElementKind::SourceBlock.to_string(), "src-block");
+/// assert_eq!("src-block".parse(), Ok(ElementKind::SourceBlock));
+/// assert_eq!("source-block".parse(), Ok(ElementKind::SourceBlock));
+/// assert_eq!("property-drawer".parse(), Ok(ElementKind::Drawer));
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+#[non_exhaustive]
+pub enum ElementKind {
+    SpecialBlock,
+    QuoteBlock,
+    CenterBlock,
+    VerseBlock,
+    CommentBlock,
+    ExampleBlock,
+    ExportBlock,
+    SourceBlock,
+    BabelCall,
+    Section,
+    Clock,
+    Cookie,
+    RadioTarget,
+    Drawer,
+    Document,
+    DynBlock,
+    FnDef,
+    FnRef,
+    Headline,
+    InlineCall,
+    InlineSrc,
+    Keyword,
+    Link,
+    List,
+    ListItem,
+    Macros,
+    Snippet,
+    Text,
+    Paragraph,
+    Rule,
+    Timestamp,
+    Target,
+    Bold,
+    Strike,
+    Italic,
+    Underline,
+    Verbatim,
+    Code,
+    Comment,
+    FixedWidth,
+    Title,
+    Table,
+    TableRow,
+    TableCell,
+    Custom,
+    Skipped,
+}
+
+impl ElementKind {
+    /// Every kind, in the order of `Element` variants.
+    pub const ALL: &'static [ElementKind] = &[
+        ElementKind::SpecialBlock,
+        ElementKind::QuoteBlock,
+        ElementKind::CenterBlock,
+        ElementKind::VerseBlock,
+        ElementKind::CommentBlock,
+        ElementKind::ExampleBlock,
+        ElementKind::ExportBlock,
+        ElementKind::SourceBlock,
+        ElementKind::BabelCall,
+        ElementKind::Section,
+        ElementKind::Clock,
+        ElementKind::Cookie,
+        ElementKind::RadioTarget,
+        ElementKind::Drawer,
+        ElementKind::Document,
+        ElementKind::DynBlock,
+        ElementKind::FnDef,
+        ElementKind::FnRef,
+        ElementKind::Headline,
+        ElementKind::InlineCall,
+        ElementKind::InlineSrc,
+        ElementKind::Keyword,
+        ElementKind::Link,
+        ElementKind::List,
+        ElementKind::ListItem,
+        ElementKind::Macros,
+        ElementKind::Snippet,
+        ElementKind::Text,
+        ElementKind::Paragraph,
+        ElementKind::Rule,
+        ElementKind::Timestamp,
+        ElementKind::Target,
+        ElementKind::Bold,
+        ElementKind::Strike,
+        ElementKind::Italic,
+        ElementKind::Underline,
+        ElementKind::Verbatim,
+        ElementKind::Code,
+        ElementKind::Comment,
+        ElementKind::FixedWidth,
+        ElementKind::Title,
+        ElementKind::Table,
+        ElementKind::TableRow,
+        ElementKind::TableCell,
+        ElementKind::Custom,
+        ElementKind::Skipped,
+    ];
+
+    /// Returns the name of this kind, e.g. `"src-block"`.
+    pub fn name(self) -> &'static str {
+        match self {
+            ElementKind::SpecialBlock => "special-block",
+            ElementKind::QuoteBlock => "quote-block",
+            ElementKind::CenterBlock => "center-block",
+            ElementKind::VerseBlock => "verse-block",
+            ElementKind::CommentBlock => "comment-block",
+            ElementKind::ExampleBlock => "example-block",
+            ElementKind::ExportBlock => "export-block",
+            ElementKind::SourceBlock => "src-block",
+            ElementKind::BabelCall => "babel-call",
+            ElementKind::Section => "section",
+            ElementKind::Clock => "clock",
+            ElementKind::Cookie => "statistics-cookie",
+            ElementKind::RadioTarget => "radio-target",
+            ElementKind::Drawer => "drawer",
+            ElementKind::Document => "org-data",
+            ElementKind::DynBlock => "dynamic-block",
+            ElementKind::FnDef => "footnote-definition",
+            ElementKind::FnRef => "footnote-reference",
+            ElementKind::Headline => "headline",
+            ElementKind::InlineCall => "inline-babel-call",
+            ElementKind::InlineSrc => "inline-src-block",
+            ElementKind::Keyword => "keyword",
+            ElementKind::Link => "link",
+            ElementKind::List => "plain-list",
+            ElementKind::ListItem => "item",
+            ElementKind::Macros => "macro",
+            ElementKind::Snippet => "export-snippet",
+            ElementKind::Text => "plain-text",
+            ElementKind::Paragraph => "paragraph",
+            ElementKind::Rule => "horizontal-rule",
+            ElementKind::Timestamp => "timestamp",
+            ElementKind::Target => "target",
+            ElementKind::Bold => "bold",
+            ElementKind::Strike => "strike-through",
+            ElementKind::Italic => "italic",
+            ElementKind::Underline => "underline",
+            ElementKind::Verbatim => "verbatim",
+            ElementKind::Code => "code",
+            ElementKind::Comment => "comment",
+            ElementKind::FixedWidth => "fixed-width",
+            ElementKind::Title => "title",
+            ElementKind::Table => "table",
+            ElementKind::TableRow => "table-row",
+            ElementKind::TableCell => "table-cell",
+            ElementKind::Custom => "custom",
+            ElementKind::Skipped => "skipped",
+        }
+    }
+
+    /// Returns `true` if elements of this kind are objects, see
+    /// `Element::is_object`.
+    pub fn is_object(self) -> bool {
+        matches!(
+            self,
+            ElementKind::Bold
+                | ElementKind::Code
+                | ElementKind::Cookie
+                | ElementKind::FnRef
+                | ElementKind::InlineCall
+                | ElementKind::InlineSrc
+                | ElementKind::Italic
+                | ElementKind::Link
+                | ElementKind::Macros
+                | ElementKind::RadioTarget
+                | ElementKind::Snippet
+                | ElementKind::Strike
+                | ElementKind::TableCell
+                | ElementKind::Target
+                | ElementKind::Text
+                | ElementKind::Timestamp
+                | ElementKind::Underline
+                | ElementKind::Verbatim
+        )
+    }
+}
+
+#[cfg(feature = "ser")]
+impl serde::Serialize for ElementKind {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.name())
+    }
+}
+
+impl fmt::Display for ElementKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// Error returned by parsing an unknown `ElementKind`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ElementKindError;
+
+impl fmt::Display for ElementKindError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "unknown element kind")
+    }
+}
+
+impl Error for ElementKindError {}
+
+impl FromStr for ElementKind {
+    type Err = ElementKindError;
+
+    fn from_str(s: &str) -> Result<ElementKind, ElementKindError> {
+        // variant names in kebab-case which differ from org-element names
+        let kind = match s {
+            "property-drawer" => ElementKind::Drawer,
+            "source-block" => ElementKind::SourceBlock,
+            "dyn-block" => ElementKind::DynBlock,
+            "fn-def" => ElementKind::FnDef,
+            "fn-ref" => ElementKind::FnRef,
+            "inline-call" => ElementKind::InlineCall,
+            "inline-src" => ElementKind::InlineSrc,
+            "macros" => ElementKind::Macros,
+            "snippet" => ElementKind::Snippet,
+            "cookie" => ElementKind::Cookie,
+            "rule" => ElementKind::Rule,
+            "strike" => ElementKind::Strike,
+            "text" => ElementKind::Text,
+            "list" => ElementKind::List,
+            "list-item" => ElementKind::ListItem,
+            "document" => ElementKind::Document,
+            _ => {
+                return ElementKind::ALL
+                    .iter()
+                    .copied()
+                    .find(|kind| kind.name() == s)
+                    .ok_or(ElementKindError)
+            }
+        };
+        Ok(kind)
+    }
+}
@@ -24,6 +24,102 @@ impl Link<'_> {
         parse_internal(input).ok()
     }
 
+    /// Returns the link type, like `file` of `file:notes.org` and `https` of
+    /// `https://example.com`, or `None` for internal links and plain paths.
+    ///
+    /// ```rust
+    /// use orgize::elements::Link;
+    ///
+    /// let link = Link {
+    ///     path: "https://example.com".into(),
+    ///     desc: None,
+    /// };
+    /// assert_eq!(link.protocol(), Some("https"));
+    /// ```
+    pub fn protocol(&self) -> Option<&str> {
+        if has_scheme(&self.path) {
+            self.path.split(':').next()
+        } else {
+            None
+        }
+    }
+
+    /// Returns the path of a file link without the search option, or `None`
+    /// if it isn't a file link.
+    ///
+    /// File links are `file:` links and plain paths starting with `/`, `./`,
+    /// `../` or `~/`.
+    ///
+    /// ```rust
+    /// use orgize::elements::Link;
+    ///
+    /// let link = Link {
+    ///     path: "file:notes/todo.org::*Inbox".into(),
+    ///     desc: None,
+    /// };
+    /// assert_eq!(link.file_path(), Some("notes/todo.org"));
+    /// assert_eq!(link.search_option(), Some("*Inbox"));
+    /// ```
+    pub fn file_path(&self) -> Option<&str> {
+        let file = self.file_link()?;
+        Some(file.find("::").map(|i| &file[..i]).unwrap_or(file))
+    }
+
+    /// Returns the search option of a file link, i.e. the part after `::`.
+    pub fn search_option(&self) -> Option<&str> {
+        let file = self.file_link()?;
+        file.find("::").map(|i| &file[i + 2..])
+    }
+
+    /// Returns the typed search option of this link.
+    ///
+    /// It's the search option of a file link, or the path of an internal
+    /// link like `[[*Heading]]`, `[[#custom-id]]` or `[[target]]`. Other
+    /// links have none.
+    ///
+    /// ```rust
+    /// use orgize::elements::{Link, SearchOption};
+    ///
+    /// let link = Link {
+    ///     path: "*Installation".into(),
+    ///     desc: None,
+    /// };
+    /// assert_eq!(link.search(), Some(SearchOption::Heading("Installation")));
+    ///
+    /// let link = Link {
+    ///     path: "file:notes.org::/TODO|FIXME/".into(),
+    ///     desc: None,
+    /// };
+    /// assert_eq!(link.search(), Some(SearchOption::Regex("TODO|FIXME")));
+    /// ```
+    pub fn search(&self) -> Option<SearchOption<'_>> {
+        if self.file_link().is_some() {
+            self.search_option().map(SearchOption::parse)
+        } else if has_scheme(&self.path) || self.path.starts_with('(') {
+            // external links and code references
+            None
+        } else if let Some(title) = self.path.strip_prefix('*') {
+            Some(SearchOption::Heading(title))
+        } else if let Some(id) = self.path.strip_prefix('#') {
+            Some(SearchOption::CustomId(id))
+        } else {
+            Some(SearchOption::Fuzzy(&self.path))
+        }
+    }
+
+    fn file_link(&self) -> Option<&str> {
+        if let Some(file) = self.path.strip_prefix("file:") {
+            Some(file)
+        } else if ["/", "./", "../", "~/"]
+            .iter()
+            .any(|prefix| self.path.starts_with(prefix))
+        {
+            Some(&self.path)
+        } else {
+            None
+        }
+    }
+
     pub fn into_owned(self) -> Link<'static> {
         Link {
             path: self.path.into_owned().into(),
@@ -32,6 +128,66 @@ impl Link<'_> {
     }
 }
 
+/// Search option of a link, see `Link::search`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchOption<'a> {
+    /// `*Heading`, matches headline titles
+    Heading(&'a str),
+    /// `#custom-id`, matches `CUSTOM_ID` properties
+    CustomId(&'a str),
+    /// `/regex/`, a regular expression searched in the file
+    Regex(&'a str),
+    /// `255`, a line number
+    LineNumber(usize),
+    /// Any other text, matches `<<targets>>` and `<<<radio targets>>>`,
+    /// `#+NAME:` keywords and headline titles in that order
+    Fuzzy(&'a str),
+}
+
+impl<'a> SearchOption<'a> {
+    /// Parses the search option of a file link, i.e. the part after `::`.
+    ///
+    /// ```rust
+    /// use orgize::elements::SearchOption;
+    ///
+    /// assert_eq!(SearchOption::parse("*Intro"), SearchOption::Heading("Intro"));
+    /// assert_eq!(SearchOption::parse("#intro"), SearchOption::CustomId("intro"));
+    /// assert_eq!(SearchOption::parse("/intro/"), SearchOption::Regex("intro"));
+    /// assert_eq!(SearchOption::parse("12"), SearchOption::LineNumber(12));
+    /// assert_eq!(SearchOption::parse("intro"), SearchOption::Fuzzy("intro"));
+    /// ```
+    pub fn parse(option: &'a str) -> SearchOption<'a> {
+        if let Some(title) = option.strip_prefix('*') {
+            SearchOption::Heading(title)
+        } else if let Some(id) = option.strip_prefix('#') {
+            SearchOption::CustomId(id)
+        } else if let Some(regex) = option
+            .strip_prefix('/')
+            .and_then(|option| option.strip_suffix('/'))
+        {
+            SearchOption::Regex(regex)
+        } else if let Ok(line) = option.parse() {
+            SearchOption::LineNumber(line)
+        } else {
+            SearchOption::Fuzzy(option)
+        }
+    }
+}
+
+// e.g. `https:` or `mailto:`
+pub(crate) fn has_scheme(path: &str) -> bool {
+    match path.find(':') {
+        Some(i) => {
+            let scheme = &path[..i];
+            scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+                && scheme
+                    .chars()
+                    .all(|c| c.is_ascii_alphanumeric() || c == '+' || c == '-' || c == '.')
+        }
+        None => false,
+    }
+}
+
 #[inline]
 fn parse_internal(input: &str) -> IResult<&str, Link, ()> {
     let (input, path) = delimited(
@@ -1,7 +1,7 @@
 use std::borrow::Cow;
-use std::iter::once;
 
-use memchr::{memchr, memchr_iter};
+use indextree::{NodeEdge, NodeId};
+use memchr::memchr;
 use nom::{
     branch::alt,
     bytes::complete::tag,
@@ -11,6 +11,9 @@ use nom::{
     IResult,
 };
 
+use crate::elements::Element;
+use crate::Org;
+
 /// Plain List Element
 #[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(feature = "ser", derive(serde::Serialize))]
@@ -25,6 +28,126 @@ pub struct List {
     pub post_blank: usize,
 }
 
+impl List {
+    /// Rewrites bullets of every list, like `List::renumber_with_bullet`
+    /// with `-` bullets.
+    pub fn renumber(org: &mut Org) {
+        List::renumber_with_bullet(org, '-');
+    }
+
+    /// Rewrites bullets of every list: ordered items are numbered
+    /// sequentially from 1 or from their `[@N]` counter, unordered items use
+    /// `bullet`.
+    ///
+    /// Contents of items are re-indented when the width of the bullet
+    /// changes, e.g. from `9. ` to `10. `.
+    ///
+    /// ```rust
+    /// use orgize::{elements::List, Org};
+    ///
+    /// let mut org = Org::parse("+ a\n3. b\n   text\n9. [@9] c\n1. d\n");
+    /// List::renumber(&mut org);
+    ///
+    /// let mut writer = Vec::new();
+    /// org.write_org(&mut writer).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(writer).unwrap(),
+    ///     "- a\n1. b\n   text\n9. [@9] c\n10. d\n"
+    /// );
+    /// ```
+    pub fn renumber_with_bullet(org: &mut Org, bullet: char) {
+        let lists: Vec<_> = org
+            .root
+            .descendants(&org.arena)
+            .filter(|&node| matches!(org[node], Element::List(_)))
+            .collect();
+
+        for list in lists {
+            let items: Vec<_> = list.children(&org.arena).collect();
+            let mut number = 1;
+            for node in items {
+                let shift = match &mut org[node] {
+                    Element::ListItem(item) => {
+                        let new = renumbered_bullet(item, &mut number, bullet);
+                        let shift = bullet_shift(&item.bullet, &new);
+                        item.bullet = new.into();
+                        shift
+                    }
+                    _ => continue,
+                };
+                if shift != 0 {
+                    shift_item_contents(org, node, shift);
+                }
+            }
+        }
+    }
+}
+
+// new bullet of `item`, `number` is the number of next ordered item
+pub(crate) fn renumbered_bullet(item: &ListItem, number: &mut usize, bullet: char) -> String {
+    if item.ordered {
+        let n = item.counter.unwrap_or(*number);
+        *number = n + 1;
+        format!("{}. ", n)
+    } else {
+        format!("{} ", bullet)
+    }
+}
+
+// change of the contents column when `old` bullet is replaced by `new`
+pub(crate) fn bullet_shift(old: &str, new: &str) -> isize {
+    new.chars().count() as isize - old.chars().count() as isize
+}
+
+// adds `shift` spaces to, or removes at most `-shift` spaces from, each
+// line of `text` but the first one, unless `first` is set
+pub(crate) fn shift_lines(text: &str, first: bool, shift: isize) -> String {
+    let mut shifted = String::with_capacity(text.len());
+    for (i, line) in text.split_inclusive('\n').enumerate() {
+        if i == 0 && !first {
+            shifted.push_str(line);
+        } else if shift > 0 {
+            for _ in 0..shift {
+                shifted.push(' ');
+            }
+            shifted.push_str(line);
+        } else {
+            let spaces = line.len() - line.trim_start_matches(' ').len();
+            shifted.push_str(&line[spaces.min(-shift as usize)..]);
+        }
+    }
+    shifted
+}
+
+pub(crate) fn shift_item_contents(org: &mut Org, item: NodeId, shift: isize) {
+    let nodes: Vec<_> = item.descendants(&org.arena).skip(1).collect();
+    for node in nodes {
+        // paragraphs start a line unless they follow a bullet
+        let first = org.arena[node].previous_sibling().is_none()
+            && org.arena[node].parent().is_some_and(|paragraph| {
+                matches!(org[paragraph], Element::Paragraph { .. })
+                    && (org.arena[paragraph].previous_sibling().is_some()
+                        || !matches!(
+                            org.arena[paragraph].parent().map(|parent| &org[parent]),
+                            Some(Element::ListItem(_))
+                        ))
+            });
+
+        match &mut org[node] {
+            Element::ListItem(item) => item.indent = shift_indent(item.indent, shift),
+            Element::List(list) => list.indent = shift_indent(list.indent, shift),
+            Element::Text { value } if first || value.contains('\n') => {
+                *value = Cow::Owned(shift_lines(value, first, shift));
+            }
+            _ => (),
+        }
+    }
+}
+
+pub(crate) fn shift_indent(indent: usize, shift: isize) -> usize {
+    (indent as isize + shift).max(0) as usize
+}
+
 /// List Item Element
 #[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(feature = "ser", derive(serde::Serialize))]
@@ -36,8 +159,20 @@ pub struct ListItem<'a> {
     pub indent: usize,
     /// List item type
     pub ordered: bool,
-    // TODO checkbox
-    // TODO counter
+    /// Counter set by `[@N]` after the bullet, the number of this item and
+    /// the start of the following ones
+    #[cfg_attr(feature = "ser", serde(skip_serializing_if = "Option::is_none"))]
+    pub counter: Option<usize>,
+    /// List item checkbox
+    #[cfg_attr(feature = "ser", serde(skip_serializing_if = "Option::is_none"))]
+    pub checkbox: Option<Checkbox>,
+    /// Space or tab separating the checkbox from item contents, a space is
+    /// written if it's `None`
+    #[cfg_attr(feature = "ser", serde(skip_serializing_if = "Option::is_none"))]
+    pub checkbox_separator: Option<char>,
+    /// Numbers of blank lines between last item's line and next item, the
+    /// blank lines after the last item belong to its list
+    pub post_blank: usize,
     // TODO tag
 }
 
@@ -52,8 +187,148 @@ impl ListItem<'_> {
             bullet: self.bullet.into_owned().into(),
             indent: self.indent,
             ordered: self.ordered,
+            counter: self.counter,
+            checkbox: self.checkbox,
+            checkbox_separator: self.checkbox_separator,
+            post_blank: self.post_blank,
         }
     }
+
+    /// Sets the checkbox of list item `node`, does nothing if `node` isn't
+    /// a list item.
+    ///
+    /// Statistics cookies aren't updated, call `ListItem::update_cookie`
+    /// afterwards.
+    pub fn set_checkbox(org: &mut Org, node: NodeId, checkbox: Option<Checkbox>) {
+        if let Element::ListItem(item) = &mut org[node] {
+            item.checkbox = checkbox;
+        }
+    }
+
+    /// Recounts checkboxes of every list item with a statistics cookie in its
+    /// first line, e.g. `- tasks [1/3]`, and rewrites the cookie.
+    ///
+    /// Direct child items with a checkbox are counted, an item is done if
+    /// its checkbox is checked.
+    ///
+    /// ```rust
+    /// use orgize::{elements::ListItem, Org};
+    ///
+    /// let mut org = Org::parse("- tasks [/]\n  - [X] a\n  - [ ] b\n");
+    /// ListItem::update_cookie(&mut org);
+    ///
+    /// let mut writer = Vec::new();
+    /// org.write_org(&mut writer).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(writer).unwrap(),
+    ///     "- tasks [1/2]\n  - [X] a\n  - [ ] b\n"
+    /// );
+    /// ```
+    pub fn update_cookie(org: &mut Org) {
+        let items: Vec<_> = org
+            .root
+            .descendants(&org.arena)
+            .filter(|&node| matches!(org[node], Element::ListItem(_)))
+            .collect();
+
+        for item in items {
+            let cookie = match first_line_cookie(org, item) {
+                Some(cookie) => cookie,
+                None => continue,
+            };
+
+            let (mut done, mut total) = (0, 0);
+            let children = item
+                .children(&org.arena)
+                .filter(|&list| matches!(org[list], Element::List(_)))
+                .flat_map(|list| list.children(&org.arena));
+            for node in children {
+                if let Element::ListItem(ListItem {
+                    checkbox: Some(checkbox),
+                    ..
+                }) = &org[node]
+                {
+                    total += 1;
+                    done += (*checkbox == Checkbox::On) as usize;
+                }
+            }
+
+            if let Element::Cookie(cookie) = &mut org[cookie] {
+                cookie.set_statistics(done, total);
+            }
+        }
+    }
+}
+
+/// Finds the statistics cookie in the first line of list item
+fn first_line_cookie(org: &Org, item: NodeId) -> Option<NodeId> {
+    let paragraph = item.children(&org.arena).next()?;
+    if !matches!(org[paragraph], Element::Paragraph { .. }) {
+        return None;
+    }
+    for edge in paragraph.traverse(&org.arena) {
+        if let NodeEdge::Start(node) = edge {
+            match &org[node] {
+                Element::Cookie(_) => return Some(node),
+                Element::Text { value } if value.contains('\n') => return None,
+                _ => (),
+            }
+        }
+    }
+    None
+}
+
+/// List Item Checkbox
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "ser", derive(serde::Serialize))]
+#[cfg_attr(feature = "ser", serde(rename_all = "kebab-case"))]
+pub enum Checkbox {
+    /// `[ ]`
+    Off,
+    /// `[X]`
+    On,
+    /// `[-]`, some of its children are checked
+    Trans,
+}
+
+impl Checkbox {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Checkbox::Off => "[ ]",
+            Checkbox::On => "[X]",
+            Checkbox::Trans => "[-]",
+        }
+    }
+
+    fn parse(input: &str) -> Option<(&str, (Checkbox, Option<char>))> {
+        let checkbox = match input.get(..3)? {
+            "[ ]" => Checkbox::Off,
+            "[X]" => Checkbox::On,
+            "[-]" => Checkbox::Trans,
+            _ => return None,
+        };
+        match input.as_bytes().get(3) {
+            None | Some(b'\n') => Some((&input[3..], (checkbox, None))),
+            Some(&c @ b' ') | Some(&c @ b'\t') => Some((&input[4..], (checkbox, Some(c as char)))),
+            _ => None,
+        }
+    }
+}
+
+// `[@N]` counter cookie
+fn parse_counter(input: &str) -> Option<(&str, usize)> {
+    let input = input.strip_prefix("[@")?;
+    let end = input.find(']')?;
+    let digits = &input[0..end];
+    if digits.is_empty() || !digits.bytes().all(|c| c.is_ascii_digit()) {
+        return None;
+    }
+    let counter = digits.parse().ok()?;
+    match input.as_bytes().get(end + 1) {
+        None | Some(b'\n') => Some((&input[end + 1..], counter)),
+        Some(b' ') | Some(b'\t') => Some((&input[end + 2..], counter)),
+        _ => None,
+    }
 }
 
 fn list_item(input: &str) -> IResult<&str, (ListItem, &str), ()> {
@@ -64,6 +339,14 @@ fn list_item(input: &str) -> IResult<&str, (ListItem, &str), ()> {
         tag("- "),
         terminated(digit1, tag(". ")),
     )))(input)?;
+    let (input, counter) = match parse_counter(input) {
+        Some((input, counter)) => (input, Some(counter)),
+        None => (input, None),
+    };
+    let (input, (checkbox, checkbox_separator)) = match Checkbox::parse(input) {
+        Some((input, (checkbox, separator))) => (input, (Some(checkbox), separator)),
+        None => (input, (None, None)),
+    };
     let (input, contents) = list_item_contents(input, indent);
     Ok((
         input,
@@ -72,6 +355,10 @@ fn list_item(input: &str) -> IResult<&str, (ListItem, &str), ()> {
                 bullet: bullet.into(),
                 indent,
                 ordered: bullet.starts_with(|c: char| c.is_ascii_digit()),
+                counter,
+                checkbox,
+                checkbox_separator,
+                post_blank: 0,
             },
             contents,
         ),
@@ -79,32 +366,27 @@ fn list_item(input: &str) -> IResult<&str, (ListItem, &str), ()> {
 }
 
 fn list_item_contents(input: &str, indent: usize) -> (&str, &str) {
-    let mut last_end = memchr(b'\n', input.as_bytes())
-        .map(|i| i + 1)
-        .unwrap_or_else(|| input.len());
-
-    for i in memchr_iter(b'\n', input.as_bytes())
-        .map(|i| i + 1)
-        .chain(once(input.len()))
-        .skip(1)
-    {
-        if input[last_end..i]
-            .as_bytes()
-            .iter()
-            .all(u8::is_ascii_whitespace)
-        {
-            let x = memchr(b'\n', &input[i..].as_bytes())
-                .map(|ii| i + ii + 1)
-                .unwrap_or_else(|| input.len());
+    let mut last_end = line_end(input, 0);
+    let mut blank_lines = 0;
+
+    while last_end < input.len() {
+        let i = line_end(input, last_end);
+        let line = &input[last_end..i];
 
-            // two consecutive empty lines
-            if input[i..x].as_bytes().iter().all(u8::is_ascii_whitespace) {
-                return (&input[x..], &input[0..x]);
+        if line.as_bytes().iter().all(u8::is_ascii_whitespace) {
+            blank_lines += 1;
+            last_end = i;
+
+            // two consecutive empty lines end the whole list
+            if blank_lines == 2 {
+                return (&input[last_end..], &input[0..last_end]);
             }
+
+            continue;
         }
 
         // line less or equally indented than the starting line
-        if input[last_end..i]
+        if line
             .as_bytes()
             .iter()
             .take(indent + 1)
@@ -113,12 +395,47 @@ fn list_item_contents(input: &str, indent: usize) -> (&str, &str) {
             return (&input[last_end..], &input[0..last_end]);
         }
 
-        last_end = i;
+        blank_lines = 0;
+
+        // indentation and blank lines inside a block don't end the item
+        last_end = block_end(input, last_end).unwrap_or(i);
     }
 
     ("", input)
 }
 
+fn line_end(input: &str, start: usize) -> usize {
+    memchr(b'\n', &input.as_bytes()[start..])
+        .map(|i| start + i + 1)
+        .unwrap_or_else(|| input.len())
+}
+
+// end of the block starting at `start`, if it is closed
+fn block_end(input: &str, start: usize) -> Option<usize> {
+    let line = input[start..line_end(input, start)].trim_start();
+    if !line.get(..8)?.eq_ignore_ascii_case("#+BEGIN_") {
+        return None;
+    }
+    let name = line[8..].split_whitespace().next()?;
+
+    let mut last_end = line_end(input, start);
+    while last_end < input.len() {
+        let i = line_end(input, last_end);
+        let line = input[last_end..i].trim();
+        if line.len() == name.len() + 6
+            && line
+                .get(..6)
+                .is_some_and(|s| s.eq_ignore_ascii_case("#+END_"))
+            && line.get(6..).is_some_and(|s| s.eq_ignore_ascii_case(name))
+        {
+            return Some(i);
+        }
+        last_end = i;
+    }
+
+    None
+}
+
 #[test]
 fn parse() {
     assert_eq!(
@@ -133,6 +450,10 @@ fn parse() {
                     bullet: "+ ".into(),
                     indent: 0,
                     ordered: false,
+                    counter: None,
+                    checkbox: None,
+                    checkbox_separator: None,
+                    post_blank: 0,
                 },
                 r#"item1
 "#
@@ -152,6 +473,10 @@ fn parse() {
                     bullet: "* ".into(),
                     indent: 0,
                     ordered: false,
+                    counter: None,
+                    checkbox: None,
+                    checkbox_separator: None,
+                    post_blank: 0,
                 },
                 r#"item1
 
@@ -173,6 +498,10 @@ fn parse() {
                     bullet: "* ".into(),
                     indent: 0,
                     ordered: false,
+                    counter: None,
+                    checkbox: None,
+                    checkbox_separator: None,
+                    post_blank: 0,
                 },
                 r#"item1
 
@@ -194,6 +523,10 @@ fn parse() {
                     bullet: "* ".into(),
                     indent: 0,
                     ordered: false,
+                    counter: None,
+                    checkbox: None,
+                    checkbox_separator: None,
+                    post_blank: 0,
                 },
                 r#"item1
 
@@ -214,6 +547,10 @@ fn parse() {
                     bullet: "+ ".into(),
                     indent: 0,
                     ordered: false,
+                    counter: None,
+                    checkbox: None,
+                    checkbox_separator: None,
+                    post_blank: 0,
                 },
                 r#"item1
   + item2
@@ -236,6 +573,10 @@ fn parse() {
                     bullet: "+ ".into(),
                     indent: 0,
                     ordered: false,
+                    counter: None,
+                    checkbox: None,
+                    checkbox_separator: None,
+                    post_blank: 0,
                 },
                 r#"item1
 
@@ -258,6 +599,10 @@ fn parse() {
                     bullet: "+ ".into(),
                     indent: 2,
                     ordered: false,
+                    counter: None,
+                    checkbox: None,
+                    checkbox_separator: None,
+                    post_blank: 0,
                 },
                 r#"item1
 
@@ -279,6 +624,10 @@ fn parse() {
                     bullet: "1. ".into(),
                     indent: 2,
                     ordered: true,
+                    counter: None,
+                    checkbox: None,
+                    checkbox_separator: None,
+                    post_blank: 0,
                 },
                 r#"item1
 "#
@@ -302,6 +651,10 @@ fn parse() {
                     bullet: "+ ".into(),
                     indent: 0,
                     ordered: false,
+                    counter: None,
+                    checkbox: None,
+                    checkbox_separator: None,
+                    post_blank: 0,
                 },
                 r#"1
 
@@ -314,3 +667,61 @@ fn parse() {
         ))
     );
 }
+
+#[test]
+fn checkbox() {
+    assert_eq!(
+        list_item("- [X] done\n"),
+        Ok((
+            "",
+            (
+                ListItem {
+                    bullet: "- ".into(),
+                    indent: 0,
+                    ordered: false,
+                    counter: None,
+                    checkbox: Some(Checkbox::On),
+                    checkbox_separator: Some(' '),
+                    post_blank: 0,
+                },
+                "done\n"
+            )
+        ))
+    );
+    assert_eq!(
+        list_item("1. [ ]\n"),
+        Ok((
+            "",
+            (
+                ListItem {
+                    bullet: "1. ".into(),
+                    indent: 0,
+                    ordered: true,
+                    counter: None,
+                    checkbox: Some(Checkbox::Off),
+                    checkbox_separator: None,
+                    post_blank: 0,
+                },
+                "\n"
+            )
+        ))
+    );
+    assert_eq!(
+        list_item("- [-]item\n"),
+        Ok((
+            "",
+            (
+                ListItem {
+                    bullet: "- ".into(),
+                    indent: 0,
+                    ordered: false,
+                    counter: None,
+                    checkbox: None,
+                    checkbox_separator: None,
+                    post_blank: 0,
+                },
+                "[-]item\n"
+            )
+        ))
+    );
+}
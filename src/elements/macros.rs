@@ -30,6 +30,51 @@ impl Macros<'_> {
             arguments: self.arguments.map(Into::into).map(Cow::Owned),
         }
     }
+
+    // arguments split at commas, except those escaped as `\,`
+    fn argument_list(&self) -> Vec<String> {
+        let mut arguments = vec![String::new()];
+        let mut chars = self
+            .arguments
+            .as_deref()
+            .unwrap_or_default()
+            .chars()
+            .peekable();
+        while let Some(c) = chars.next() {
+            match c {
+                '\\' if chars.peek() == Some(&',') => {
+                    chars.next();
+                    arguments.last_mut().unwrap().push(',');
+                }
+                ',' => arguments.push(String::new()),
+                c => arguments.last_mut().unwrap().push(c),
+            }
+        }
+        arguments
+    }
+
+    // `template`, a `#+MACRO:` definition, with `$1`, `$2`... replaced by
+    // arguments of this macro, missing ones are empty
+    pub(crate) fn expand(&self, template: &str) -> String {
+        let arguments = self.argument_list();
+        let mut expanded = String::new();
+        let mut rest = template;
+        while let Some(i) = rest.find('$') {
+            expanded.push_str(&rest[..i]);
+            let digits = rest[i + 1..]
+                .find(|c: char| !c.is_ascii_digit())
+                .unwrap_or(rest.len() - i - 1);
+            match rest[i + 1..i + 1 + digits].parse::<usize>() {
+                Ok(n) if n > 0 => {
+                    expanded.push_str(arguments.get(n - 1).map_or("", |argument| argument))
+                }
+                _ => expanded.push_str(&rest[i..i + 1 + digits]),
+            }
+            rest = &rest[i + 1 + digits..];
+        }
+        expanded.push_str(rest);
+        expanded
+    }
 }
 
 #[inline]
@@ -89,3 +134,15 @@ fn test() {
     assert!(Macros::parse("{{{poem(}}}").is_none());
     assert!(Macros::parse("{{{poem)}}}").is_none());
 }
+
+#[test]
+fn expand() {
+    let (_, macros) = Macros::parse("{{{poem(red,blue\\, green)}}}").unwrap();
+    assert_eq!(
+        macros.expand("roses are $1, violets are $2$3 for $$1"),
+        "roses are red, violets are blue, green for $red"
+    );
+
+    let (_, macros) = Macros::parse("{{{author}}}").unwrap();
+    assert_eq!(macros.expand("[$1] $0 $"), "[] $0 $");
+}
@@ -4,21 +4,25 @@ pub(crate) mod block;
 pub(crate) mod clock;
 pub(crate) mod comment;
 pub(crate) mod cookie;
+pub(crate) mod custom;
 pub(crate) mod drawer;
 pub(crate) mod dyn_block;
 pub(crate) mod emphasis;
 pub(crate) mod fixed_width;
 pub(crate) mod fn_def;
 pub(crate) mod fn_ref;
+pub(crate) mod header_args;
 pub(crate) mod inline_call;
 pub(crate) mod inline_src;
 pub(crate) mod keyword;
+pub(crate) mod kind;
 pub(crate) mod link;
 pub(crate) mod list;
 pub(crate) mod macros;
 pub(crate) mod planning;
 pub(crate) mod radio_target;
 pub(crate) mod rule;
+pub(crate) mod skipped;
 pub(crate) mod snippet;
 pub(crate) mod table;
 pub(crate) mod target;
@@ -27,34 +31,76 @@ pub(crate) mod title;
 
 pub use self::{
     block::{
-        CenterBlock, CommentBlock, ExampleBlock, ExportBlock, QuoteBlock, SourceBlock,
-        SpecialBlock, VerseBlock,
+        CenterBlock, CommentBlock, ExampleBlock, ExportBlock, NumberLines, QuoteBlock, SourceBlock,
+        SpecialBlock, Switches, VerseBlock,
     },
     clock::Clock,
     comment::Comment,
     cookie::Cookie,
+    custom::{Custom, CustomSource},
     drawer::Drawer,
     dyn_block::DynBlock,
     fixed_width::FixedWidth,
     fn_def::FnDef,
     fn_ref::FnRef,
+    header_args::{Exports, HeaderArgs},
     inline_call::InlineCall,
     inline_src::InlineSrc,
-    keyword::{BabelCall, Keyword},
-    link::Link,
-    list::{List, ListItem},
+    keyword::{BabelCall, Keyword, ObjectContext, Objects},
+    kind::{ElementKind, ElementKindError},
+    link::{Link, SearchOption},
+    list::{Checkbox, List, ListItem},
     macros::Macros,
     planning::Planning,
     rule::Rule,
+    skipped::{ElementTypeSet, Skipped},
     snippet::Snippet,
-    table::{Table, TableCell, TableRow},
+    table::{Table, TableAlignment, TableCell, TableColumn, TableRow},
     target::Target,
-    timestamp::{Datetime, Delay, DelayMark, Repeater, RepeaterMark, TimeUnit, Timestamp},
+    timestamp::{
+        Datetime, Delay, DelayMark, HabitStatus, Repeater, RepeaterMark, TimeUnit, Timestamp,
+        DAY_NAMES,
+    },
     title::{PropertiesMap, Title},
 };
 
 use std::borrow::Cow;
 
+// `post_blank` field of an element, works on `&Element` and `&mut Element`
+macro_rules! post_blank {
+    ($element:expr) => {
+        match $element {
+            Element::SpecialBlock(SpecialBlock { post_blank, .. })
+            | Element::QuoteBlock(QuoteBlock { post_blank, .. })
+            | Element::CenterBlock(CenterBlock { post_blank, .. })
+            | Element::VerseBlock(VerseBlock { post_blank, .. })
+            | Element::CommentBlock(CommentBlock { post_blank, .. })
+            | Element::ExampleBlock(ExampleBlock { post_blank, .. })
+            | Element::ExportBlock(ExportBlock { post_blank, .. })
+            | Element::SourceBlock(SourceBlock { post_blank, .. })
+            | Element::BabelCall(BabelCall { post_blank, .. })
+            | Element::Clock(Clock::Closed { post_blank, .. })
+            | Element::Clock(Clock::Running { post_blank, .. })
+            | Element::Drawer(Drawer { post_blank, .. })
+            | Element::DynBlock(DynBlock { post_blank, .. })
+            | Element::FnDef(FnDef { post_blank, .. })
+            | Element::Keyword(Keyword { post_blank, .. })
+            | Element::List(List { post_blank, .. })
+            | Element::ListItem(ListItem { post_blank, .. })
+            | Element::Paragraph { post_blank }
+            | Element::Rule(Rule { post_blank })
+            | Element::Comment(Comment { post_blank, .. })
+            | Element::FixedWidth(FixedWidth { post_blank, .. })
+            | Element::Title(Title { post_blank, .. })
+            | Element::Table(Table::Org { post_blank, .. })
+            | Element::Table(Table::TableEl { post_blank, .. })
+            | Element::Custom(Custom { post_blank, .. })
+            | Element::Skipped(Skipped { post_blank, .. }) => Some(post_blank),
+            _ => None,
+        }
+    };
+}
+
 /// Element Enum
 #[derive(Debug)]
 #[cfg_attr(feature = "ser", derive(serde::Serialize))]
@@ -67,46 +113,81 @@ pub enum Element<'a> {
     CommentBlock(CommentBlock<'a>),
     ExampleBlock(ExampleBlock<'a>),
     ExportBlock(ExportBlock<'a>),
+    #[cfg_attr(feature = "ser", serde(rename = "src-block"))]
     SourceBlock(SourceBlock<'a>),
     BabelCall(BabelCall<'a>),
     Section,
     Clock(Clock<'a>),
+    #[cfg_attr(feature = "ser", serde(rename = "statistics-cookie"))]
     Cookie(Cookie<'a>),
     RadioTarget,
     Drawer(Drawer<'a>),
-    Document { pre_blank: usize },
+    #[cfg_attr(feature = "ser", serde(rename = "org-data"))]
+    Document {
+        pre_blank: usize,
+    },
+    #[cfg_attr(feature = "ser", serde(rename = "dynamic-block"))]
     DynBlock(DynBlock<'a>),
+    #[cfg_attr(feature = "ser", serde(rename = "footnote-definition"))]
     FnDef(FnDef<'a>),
+    #[cfg_attr(feature = "ser", serde(rename = "footnote-reference"))]
     FnRef(FnRef<'a>),
-    Headline { level: usize },
+    Headline {
+        level: usize,
+    },
+    #[cfg_attr(feature = "ser", serde(rename = "inline-babel-call"))]
     InlineCall(InlineCall<'a>),
+    #[cfg_attr(feature = "ser", serde(rename = "inline-src-block"))]
     InlineSrc(InlineSrc<'a>),
     Keyword(Keyword<'a>),
     Link(Link<'a>),
+    #[cfg_attr(feature = "ser", serde(rename = "plain-list"))]
     List(List),
+    #[cfg_attr(feature = "ser", serde(rename = "item"))]
     ListItem(ListItem<'a>),
+    #[cfg_attr(feature = "ser", serde(rename = "macro"))]
     Macros(Macros<'a>),
+    #[cfg_attr(feature = "ser", serde(rename = "export-snippet"))]
     Snippet(Snippet<'a>),
-    Text { value: Cow<'a, str> },
-    Paragraph { post_blank: usize },
+    #[cfg_attr(feature = "ser", serde(rename = "plain-text"))]
+    Text {
+        value: Cow<'a, str>,
+    },
+    Paragraph {
+        post_blank: usize,
+    },
+    #[cfg_attr(feature = "ser", serde(rename = "horizontal-rule"))]
     Rule(Rule),
     Timestamp(Timestamp<'a>),
     Target(Target<'a>),
     Bold,
+    #[cfg_attr(feature = "ser", serde(rename = "strike-through"))]
     Strike,
     Italic,
     Underline,
-    Verbatim { value: Cow<'a, str> },
-    Code { value: Cow<'a, str> },
+    Verbatim {
+        value: Cow<'a, str>,
+    },
+    Code {
+        value: Cow<'a, str>,
+    },
     Comment(Comment<'a>),
     FixedWidth(FixedWidth<'a>),
     Title(Title<'a>),
     Table(Table<'a>),
     TableRow(TableRow),
     TableCell(TableCell),
+    Custom(Custom<'a>),
+    Skipped(Skipped<'a>),
 }
 
 impl Element<'_> {
+    /// Returns `true` if this is an object, which can only appear inside
+    /// paragraphs, titles, table cells and other objects.
+    pub fn is_object(&self) -> bool {
+        self.kind().is_object()
+    }
+
     pub fn is_container(&self) -> bool {
         match self {
             Element::SpecialBlock(_)
@@ -116,11 +197,14 @@ impl Element<'_> {
             | Element::Bold
             | Element::Document { .. }
             | Element::DynBlock(_)
+            | Element::Drawer(_)
+            | Element::FnDef(_)
             | Element::Headline { .. }
             | Element::Italic
             | Element::List(_)
             | Element::ListItem(_)
             | Element::Paragraph { .. }
+            | Element::RadioTarget
             | Element::Section
             | Element::Strike
             | Element::Underline
@@ -133,6 +217,125 @@ impl Element<'_> {
         }
     }
 
+    /// Returns the kind of this element.
+    ///
+    /// ```rust
+    /// use orgize::{elements::ElementKind, Element};
+    ///
+    /// assert_eq!(Element::Bold.kind(), ElementKind::Bold);
+    /// assert_eq!(Element::Bold.kind().to_string(), "bold");
+    /// ```
+    pub fn kind(&self) -> ElementKind {
+        match self {
+            Element::SpecialBlock(_) => ElementKind::SpecialBlock,
+            Element::QuoteBlock(_) => ElementKind::QuoteBlock,
+            Element::CenterBlock(_) => ElementKind::CenterBlock,
+            Element::VerseBlock(_) => ElementKind::VerseBlock,
+            Element::CommentBlock(_) => ElementKind::CommentBlock,
+            Element::ExampleBlock(_) => ElementKind::ExampleBlock,
+            Element::ExportBlock(_) => ElementKind::ExportBlock,
+            Element::SourceBlock(_) => ElementKind::SourceBlock,
+            Element::BabelCall(_) => ElementKind::BabelCall,
+            Element::Section => ElementKind::Section,
+            Element::Clock(_) => ElementKind::Clock,
+            Element::Cookie(_) => ElementKind::Cookie,
+            Element::RadioTarget => ElementKind::RadioTarget,
+            Element::Drawer(_) => ElementKind::Drawer,
+            Element::Document { .. } => ElementKind::Document,
+            Element::DynBlock(_) => ElementKind::DynBlock,
+            Element::FnDef(_) => ElementKind::FnDef,
+            Element::FnRef(_) => ElementKind::FnRef,
+            Element::Headline { .. } => ElementKind::Headline,
+            Element::InlineCall(_) => ElementKind::InlineCall,
+            Element::InlineSrc(_) => ElementKind::InlineSrc,
+            Element::Keyword(_) => ElementKind::Keyword,
+            Element::Link(_) => ElementKind::Link,
+            Element::List(_) => ElementKind::List,
+            Element::ListItem(_) => ElementKind::ListItem,
+            Element::Macros(_) => ElementKind::Macros,
+            Element::Snippet(_) => ElementKind::Snippet,
+            Element::Text { .. } => ElementKind::Text,
+            Element::Paragraph { .. } => ElementKind::Paragraph,
+            Element::Rule(_) => ElementKind::Rule,
+            Element::Timestamp(_) => ElementKind::Timestamp,
+            Element::Target(_) => ElementKind::Target,
+            Element::Bold => ElementKind::Bold,
+            Element::Strike => ElementKind::Strike,
+            Element::Italic => ElementKind::Italic,
+            Element::Underline => ElementKind::Underline,
+            Element::Verbatim { .. } => ElementKind::Verbatim,
+            Element::Code { .. } => ElementKind::Code,
+            Element::Comment(_) => ElementKind::Comment,
+            Element::FixedWidth(_) => ElementKind::FixedWidth,
+            Element::Title(_) => ElementKind::Title,
+            Element::Table(_) => ElementKind::Table,
+            Element::TableRow(_) => ElementKind::TableRow,
+            Element::TableCell(_) => ElementKind::TableCell,
+            Element::Custom(_) => ElementKind::Custom,
+            Element::Skipped(_) => ElementKind::Skipped,
+        }
+    }
+
+    /// Returns the name of this element, e.g. `"src-block"`, which is also
+    /// its `type` when serialized, see `ElementKind::name`.
+    pub fn name(&self) -> &'static str {
+        self.kind().name()
+    }
+
+    /// Returns the number of blank lines after this element.
+    ///
+    /// Every blank line belongs to exactly one element, the outermost one
+    /// ending right before it: the previous item for blank lines between
+    /// list items, the list for blank lines after its last item, and the
+    /// last element of a section for blank lines before the next headline.
+    /// Blank lines after a headline's title, planning and properties belong
+    /// to its title.
+    ///
+    /// It's always `0` for objects, documents, headlines, sections and table
+    /// rows. Blank lines right after the begin line of blocks, drawers and
+    /// the document are their `pre_blank`.
+    ///
+    /// ```rust
+    /// use orgize::{Event, Org};
+    ///
+    /// let org = Org::parse("- a\n\n- b\n\n\ntext\n");
+    ///
+    /// let post_blanks: Vec<_> = org
+    ///     .iter()
+    ///     .filter_map(|event| match event {
+    ///         Event::Start(element) if !element.is_object() => {
+    ///             Some((element.name(), element.post_blank()))
+    ///         }
+    ///         _ => None,
+    ///     })
+    ///     .collect();
+    ///
+    /// assert_eq!(
+    ///     post_blanks,
+    ///     [
+    ///         ("org-data", 0),
+    ///         ("section", 0),
+    ///         ("plain-list", 2),
+    ///         ("item", 1),
+    ///         ("paragraph", 0),
+    ///         ("item", 0),
+    ///         ("paragraph", 0),
+    ///         ("paragraph", 0),
+    ///     ]
+    /// );
+    /// ```
+    pub fn post_blank(&self) -> usize {
+        post_blank!(self).copied().unwrap_or(0)
+    }
+
+    /// Sets the number of blank lines after this element, see
+    /// `Element::post_blank`. Does nothing if it's always `0`.
+    pub fn set_post_blank(&mut self, blank_lines: usize) {
+        if let Some(post_blank) = post_blank!(self) {
+            *post_blank = blank_lines;
+        }
+    }
+
     pub fn into_owned(self) -> Element<'static> {
         use Element::*;
 
@@ -187,6 +390,8 @@ impl Element<'_> {
             Table(e) => Table(e.into_owned()),
             TableRow(e) => TableRow(e),
             TableCell(e) => TableCell(e),
+            Custom(e) => Custom(e.into_owned()),
+            Skipped(e) => Skipped(e.into_owned()),
         }
     }
 }
@@ -3,9 +3,8 @@ use memchr::memchr;
 use crate::elements::{timestamp::parse_timestamp, Timestamp};
 
 /// Planning element
-#[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(feature = "ser", derive(serde::Serialize))]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Planning<'a> {
     /// Timestamp associated to deadline keyword
     #[cfg_attr(feature = "ser", serde(skip_serializing_if = "Option::is_none"))]
@@ -19,44 +18,34 @@ pub struct Planning<'a> {
 }
 
 impl Planning<'_> {
+    /// Parses the first line of `text` as a planning line.
+    ///
+    /// Keywords can appear in any order, only the first occurrence of each
+    /// keyword is kept.
     #[inline]
     pub(crate) fn parse(text: &str) -> Option<(&str, Planning)> {
-        let (mut deadline, mut scheduled, mut closed) = (None, None, None);
-        let (mut tail, off) = memchr(b'\n', text.as_bytes())
-            .map(|i| (text[..i].trim(), i + 1))
-            .unwrap_or_else(|| (text.trim(), text.len()));
+        let (line, off) = memchr(b'\n', text.as_bytes())
+            .map(|i| (&text[..i], i + 1))
+            .unwrap_or((text, text.len()));
 
-        while let Some(i) = memchr(b' ', tail.as_bytes()) {
-            let next = &tail[i + 1..].trim_start();
+        let mut planning = Planning {
+            deadline: None,
+            scheduled: None,
+            closed: None,
+        };
 
-            macro_rules! set_timestamp {
-                ($timestamp:expr) => {{
-                    let (new_tail, timestamp) = parse_timestamp(next).ok()?;
-                    $timestamp = Some(timestamp);
-                    tail = new_tail.trim_start();
-                }};
-            }
-
-            match &tail[..i] {
-                "DEADLINE:" if deadline.is_none() => set_timestamp!(deadline),
-                "SCHEDULED:" if scheduled.is_none() => set_timestamp!(scheduled),
-                "CLOSED:" if closed.is_none() => set_timestamp!(closed),
-                _ => return None,
+        for (keyword, _, timestamp) in parse_entries(line)? {
+            let slot = match keyword {
+                PlanningKeyword::Deadline => &mut planning.deadline,
+                PlanningKeyword::Scheduled => &mut planning.scheduled,
+                PlanningKeyword::Closed => &mut planning.closed,
+            };
+            if slot.is_none() {
+                *slot = Some(timestamp);
             }
         }
 
-        if deadline.is_none() && scheduled.is_none() && closed.is_none() {
-            None
-        } else {
-            Some((
-                &text[off..],
-                Planning {
-                    deadline,
-                    scheduled,
-                    closed,
-                },
-            ))
-        }
+        Some((&text[off..], planning))
     }
 
     pub fn into_owned(self) -> Planning<'static> {
@@ -68,6 +57,55 @@ impl Planning<'_> {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum PlanningKeyword {
+    Deadline,
+    Scheduled,
+    Closed,
+}
+
+impl PlanningKeyword {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            PlanningKeyword::Deadline => "DEADLINE:",
+            PlanningKeyword::Scheduled => "SCHEDULED:",
+            PlanningKeyword::Closed => "CLOSED:",
+        }
+    }
+}
+
+/// Splits a planning line into keywords, their offsets in `line` and
+/// timestamps. Returns `None` if it isn't a planning line.
+pub(crate) fn parse_entries<'a>(
+    line: &'a str,
+) -> Option<Vec<(PlanningKeyword, usize, Timestamp<'a>)>> {
+    // like Emacs, entries are separated by ascii whitespace only
+    let trim = |s: &'a str| s.trim_start_matches(|c: char| c.is_ascii_whitespace());
+    let mut entries = Vec::new();
+    let mut rest = trim(line);
+
+    while !rest.is_empty() {
+        let keyword = [
+            PlanningKeyword::Deadline,
+            PlanningKeyword::Scheduled,
+            PlanningKeyword::Closed,
+        ]
+        .iter()
+        .copied()
+        .find(|keyword| rest.starts_with(keyword.as_str()))?;
+        let offset = line.len() - rest.len();
+        let (tail, timestamp) = parse_timestamp(trim(&rest[keyword.as_str().len()..])).ok()?;
+        entries.push((keyword, offset, timestamp));
+        rest = trim(tail);
+    }
+
+    if entries.is_empty() {
+        None
+    } else {
+        Some(entries)
+    }
+}
+
 #[test]
 fn prase() {
     use crate::elements::Datetime;
@@ -87,7 +125,8 @@ fn prase() {
                         minute: None
                     },
                     repeater: None,
-                    delay: None
+                    delay: None,
+                    raw: None,
                 }),
                 deadline: None,
                 closed: None,
@@ -0,0 +1,90 @@
+use std::borrow::Cow;
+use std::iter::FromIterator;
+
+use crate::elements::ElementKind;
+
+/// Set of element kinds, see `ParseConfig::skip_elements`
+///
+/// ```rust
+/// use orgize::elements::{ElementKind, ElementTypeSet};
+///
+/// let set = ElementTypeSet::new()
+///     .with(ElementKind::Drawer)
+///     .with(ElementKind::Clock);
+///
+/// assert!(set.contains(ElementKind::Drawer));
+/// assert!(!set.contains(ElementKind::Table));
+/// assert_eq!(set, [ElementKind::Clock, ElementKind::Drawer].into());
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ElementTypeSet(u64);
+
+// every kind has a bit
+const _: () = assert!(ElementKind::ALL.len() <= 64);
+
+impl ElementTypeSet {
+    /// Creates an empty set.
+    pub fn new() -> Self {
+        ElementTypeSet(0)
+    }
+
+    /// Returns this set with `kind` added.
+    pub fn with(mut self, kind: ElementKind) -> Self {
+        self.insert(kind);
+        self
+    }
+
+    pub fn insert(&mut self, kind: ElementKind) {
+        self.0 |= 1 << kind as u64;
+    }
+
+    pub fn remove(&mut self, kind: ElementKind) {
+        self.0 &= !(1 << kind as u64);
+    }
+
+    pub fn contains(self, kind: ElementKind) -> bool {
+        self.0 & (1 << kind as u64) != 0
+    }
+
+    pub fn is_empty(self) -> bool {
+        self.0 == 0
+    }
+}
+
+impl FromIterator<ElementKind> for ElementTypeSet {
+    fn from_iter<I: IntoIterator<Item = ElementKind>>(iter: I) -> Self {
+        iter.into_iter()
+            .fold(ElementTypeSet::new(), ElementTypeSet::with)
+    }
+}
+
+impl<const N: usize> From<[ElementKind; N]> for ElementTypeSet {
+    fn from(kinds: [ElementKind; N]) -> Self {
+        kinds.iter().copied().collect()
+    }
+}
+
+/// Element skipped by the parser, see `ParseConfig::skip_elements`
+///
+/// Html export skips it, and org export writes its source text as-is.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "ser", derive(serde::Serialize))]
+pub struct Skipped<'a> {
+    /// Kind of the skipped element
+    pub kind: ElementKind,
+    /// Source text of this element, without blank lines after it
+    pub raw: Cow<'a, str>,
+    /// Numbers of blank lines between last element's line and next non-blank
+    /// line or buffer's end
+    pub post_blank: usize,
+}
+
+impl Skipped<'_> {
+    pub fn into_owned(self) -> Skipped<'static> {
+        Skipped {
+            kind: self.kind,
+            raw: self.raw.into_owned().into(),
+            post_blank: self.post_blank,
+        }
+    }
+}
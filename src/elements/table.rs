@@ -16,12 +16,22 @@ pub enum Table<'a> {
     /// "org" type table
     #[cfg_attr(feature = "ser", serde(rename = "org"))]
     Org {
+        /// Formulas of `#+TBLFM:` lines, one line for each of them
         #[cfg_attr(feature = "ser", serde(skip_serializing_if = "Option::is_none"))]
         tblfm: Option<Cow<'a, str>>,
         /// Numbers of blank lines between last table's line and next non-blank
         /// line or buffer's end
         post_blank: usize,
         has_header: bool,
+        /// Column alignments, widths and groups, specified by special rows
+        ///
+        /// Special rows are not part of the table's children, it's empty if
+        /// the table has no special row.
+        #[cfg_attr(feature = "ser", serde(skip_serializing_if = "Vec::is_empty"))]
+        columns: Vec<TableColumn>,
+        /// Source text of table rows, `None` if it was created programmatically
+        #[cfg_attr(feature = "ser", serde(skip))]
+        raw: Option<Cow<'a, str>>,
     },
     /// "table.el" type table
     #[cfg_attr(feature = "ser", serde(rename = "table.el"))]
@@ -77,10 +87,14 @@ impl Table<'_> {
                 tblfm,
                 post_blank,
                 has_header,
+                columns,
+                raw,
             } => Table::Org {
                 tblfm: tblfm.map(Into::into).map(Cow::Owned),
                 post_blank,
                 has_header,
+                columns,
+                raw: raw.map(|s| s.into_owned().into()),
             },
             Table::TableEl { value, post_blank } => Table::TableEl {
                 value: value.into_owned().into(),
@@ -90,6 +104,144 @@ impl Table<'_> {
     }
 }
 
+/// Table Column
+///
+/// # Syntax
+///
+/// ```text
+/// | <l> | <r10> |  <c>  | <- alignment and width cookies
+/// |  /  |   <   |   >   | <- column groups, first field must be "/"
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+#[cfg_attr(feature = "ser", derive(serde::Serialize))]
+pub struct TableColumn {
+    /// Column alignment
+    #[cfg_attr(feature = "ser", serde(skip_serializing_if = "Option::is_none"))]
+    pub alignment: Option<TableAlignment>,
+    /// Column width
+    #[cfg_attr(feature = "ser", serde(skip_serializing_if = "Option::is_none"))]
+    pub width: Option<usize>,
+    /// This column starts a column group
+    pub group_start: bool,
+    /// This column ends a column group
+    pub group_end: bool,
+}
+
+impl TableColumn {
+    /// Returns the alignment and width cookie of this column, e.g. `<r10>`.
+    pub fn cookie(&self) -> Option<String> {
+        if self.alignment.is_none() && self.width.is_none() {
+            return None;
+        }
+        let mut cookie = String::from("<");
+        match self.alignment {
+            Some(TableAlignment::Left) => cookie.push('l'),
+            Some(TableAlignment::Center) => cookie.push('c'),
+            Some(TableAlignment::Right) => cookie.push('r'),
+            None => (),
+        }
+        if let Some(width) = self.width {
+            cookie.push_str(&width.to_string());
+        }
+        cookie.push('>');
+        Some(cookie)
+    }
+
+    /// Returns the column group marker of this column, e.g. `<>`.
+    pub fn group_marker(&self) -> &'static str {
+        match (self.group_start, self.group_end) {
+            (true, true) => "<>",
+            (true, false) => "<",
+            (false, true) => ">",
+            (false, false) => "",
+        }
+    }
+}
+
+/// Table Column Alignment
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "ser", derive(serde::Serialize))]
+#[cfg_attr(feature = "ser", serde(rename_all = "kebab-case"))]
+pub enum TableAlignment {
+    Left,
+    Center,
+    Right,
+}
+
+impl TableAlignment {
+    /// Returns the class name used by org-mode html exporter.
+    pub fn as_class(self) -> &'static str {
+        match self {
+            TableAlignment::Left => "org-left",
+            TableAlignment::Center => "org-center",
+            TableAlignment::Right => "org-right",
+        }
+    }
+}
+
+/// Parses an alignment or width cookie, e.g. `<c>`, `<10>` or `<r10>`
+fn parse_cookie(field: &str) -> Option<(Option<TableAlignment>, Option<usize>)> {
+    let field = field.strip_prefix('<')?.strip_suffix('>')?;
+    let (alignment, width) = match field.as_bytes().first()? {
+        b'l' => (Some(TableAlignment::Left), &field[1..]),
+        b'c' => (Some(TableAlignment::Center), &field[1..]),
+        b'r' => (Some(TableAlignment::Right), &field[1..]),
+        _ => (None, field),
+    };
+    if width.is_empty() {
+        Some((alignment, None))
+    } else if width.bytes().all(|c| c.is_ascii_digit()) {
+        Some((alignment, Some(width.parse().ok()?)))
+    } else {
+        None
+    }
+}
+
+/// Applies a special row to `columns`, returns `false` if `fields` isn't
+/// a special row.
+///
+/// Special rows are column group rows, whose first field is `/`, and rows
+/// containing only alignment or width cookies.
+pub(crate) fn parse_special_row(fields: &[&str], columns: &mut Vec<TableColumn>) -> bool {
+    let is_group_row = fields.first() == Some(&"/");
+
+    if !is_group_row
+        && (fields.iter().all(|field| field.is_empty())
+            || fields
+                .iter()
+                .any(|field| !field.is_empty() && parse_cookie(field).is_none()))
+    {
+        return false;
+    }
+
+    if columns.len() < fields.len() {
+        columns.resize_with(fields.len(), Default::default);
+    }
+
+    for (field, column) in fields
+        .iter()
+        .zip(columns.iter_mut())
+        .skip(is_group_row as usize)
+    {
+        match *field {
+            "<" => column.group_start = true,
+            ">" => column.group_end = true,
+            "<>" => {
+                column.group_start = true;
+                column.group_end = true;
+            }
+            _ => {
+                if let Some((alignment, width)) = parse_cookie(field) {
+                    column.alignment = alignment.or(column.alignment);
+                    column.width = width.or(column.width);
+                }
+            }
+        }
+    }
+
+    true
+}
+
 /// Table Row Element
 ///
 /// # Syntax
@@ -130,6 +282,9 @@ pub enum TableRow {
 }
 
 /// Table Cell Element
+///
+/// Cell contents are parsed as objects, and the alignment comes from the
+/// column's alignment cookie.
 #[derive(Debug, Clone)]
 #[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(feature = "ser", derive(serde::Serialize))]
@@ -137,9 +292,24 @@ pub enum TableRow {
 #[cfg_attr(feature = "ser", serde(rename_all = "kebab-case"))]
 pub enum TableCell {
     /// Header cell
-    Header,
+    Header {
+        #[cfg_attr(feature = "ser", serde(skip_serializing_if = "Option::is_none"))]
+        alignment: Option<TableAlignment>,
+    },
     /// Body cell, or standard cell
-    Body,
+    Body {
+        #[cfg_attr(feature = "ser", serde(skip_serializing_if = "Option::is_none"))]
+        alignment: Option<TableAlignment>,
+    },
+}
+
+impl TableCell {
+    /// Returns the alignment of this cell.
+    pub fn alignment(&self) -> Option<TableAlignment> {
+        match self {
+            TableCell::Header { alignment } | TableCell::Body { alignment } => *alignment,
+        }
+    }
 }
 
 #[test]
@@ -167,3 +337,47 @@ fn parse_table_el_() {
     assert!(Table::parse_table_el("").is_none());
     assert!(Table::parse_table_el("+----|---").is_none());
 }
+
+#[test]
+fn parse_special_row_() {
+    let mut columns = vec![];
+    assert!(!parse_special_row(&["a", "<r>"], &mut columns));
+    assert!(!parse_special_row(&["", ""], &mut columns));
+    assert!(!parse_special_row(&["<>"], &mut columns));
+    assert!(columns.is_empty());
+
+    assert!(parse_special_row(
+        &["<l>", "", "<r10>", "<5>"],
+        &mut columns
+    ));
+    assert!(parse_special_row(&["/", "<", ">", "<>"], &mut columns));
+    assert_eq!(
+        columns,
+        vec![
+            TableColumn {
+                alignment: Some(TableAlignment::Left),
+                ..Default::default()
+            },
+            TableColumn {
+                group_start: true,
+                ..Default::default()
+            },
+            TableColumn {
+                alignment: Some(TableAlignment::Right),
+                width: Some(10),
+                group_end: true,
+                ..Default::default()
+            },
+            TableColumn {
+                width: Some(5),
+                group_start: true,
+                group_end: true,
+                ..Default::default()
+            },
+        ]
+    );
+    assert_eq!(columns[2].cookie(), Some("<r10>".into()));
+    assert_eq!(columns[3].cookie(), Some("<5>".into()));
+    assert_eq!(columns[1].cookie(), None);
+    assert_eq!(columns[3].group_marker(), "<>");
+}
@@ -6,15 +6,14 @@ use nom::{
     branch::{alt, permutation},
     bytes::complete::{tag, take, take_until, take_while1, take_while_m_n},
     character::complete::{char, digit1, space0, space1},
-    combinator::{all_consuming, map, map_res, opt, value, verify},
+    combinator::{all_consuming, consumed, map, map_res, opt, value, verify},
     sequence::{delimited, preceded, separated_pair},
     IResult,
 };
 
 /// Datetime Struct
-#[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(feature = "ser", derive(serde::Serialize))]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct Datetime<'a> {
     pub year: u16,
     pub month: u8,
@@ -42,6 +41,9 @@ pub struct Repeater {
     pub mark: RepeaterMark,
     pub value: usize,
     pub unit: TimeUnit,
+    /// Maximum interval of a habit, e.g. `4d` of `.+2d/4d`
+    #[cfg_attr(feature = "ser", serde(skip_serializing_if = "Option::is_none"))]
+    pub max: Option<(usize, TimeUnit)>,
 }
 
 #[cfg_attr(feature = "ser", derive(serde::Serialize))]
@@ -67,6 +69,19 @@ pub enum DelayMark {
     First,
 }
 
+/// Status of a habit on a given day, see `Timestamp::habit_status`
+#[cfg_attr(feature = "ser", derive(serde::Serialize))]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum HabitStatus {
+    /// Before the scheduled date
+    NotDue,
+    /// On or after the scheduled date, but within the maximum interval
+    Due,
+    /// After the maximum interval, or after the scheduled date if there's
+    /// no maximum interval
+    Overdue,
+}
+
 impl AsRef<str> for RepeaterMark {
     fn as_ref(&self) -> &str {
         match self {
@@ -178,7 +193,11 @@ impl TryFrom<String> for TimeUnit {
 
 impl fmt::Display for Repeater {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
-        write!(f, "{}{}{}", self.mark, self.value, self.unit)
+        write!(f, "{}{}{}", self.mark, self.value, self.unit)?;
+        if let Some((value, unit)) = self.max {
+            write!(f, "/{}{}", value, unit)?;
+        }
+        Ok(())
     }
 }
 
@@ -266,8 +285,10 @@ impl Datetime<'_> {
 
 #[cfg(feature = "chrono")]
 mod chrono {
-    use super::Datetime;
+    use super::{Datetime, HabitStatus, Repeater, RepeaterMark, TimeUnit, Timestamp};
+    use crate::duration::OrgDuration;
     use chrono::*;
+    use std::convert::TryFrom;
 
     impl Into<NaiveDate> for Datetime<'_> {
         fn into(self) -> NaiveDate {
@@ -320,10 +341,367 @@ mod chrono {
             DateTime::from_utc(self.into(), Utc)
         }
     }
+
+    impl Datetime<'_> {
+        /// Converts this datetime into a `NaiveDate`.
+        ///
+        /// Returns `None` if the date doesn't exist, e.g. `2021-02-30`.
+        pub fn to_naive_date(&self) -> Option<NaiveDate> {
+            NaiveDate::from_ymd_opt(self.year.into(), self.month.into(), self.day.into())
+        }
+
+        /// Converts this datetime into a `NaiveDateTime`, a missing time is treated
+        /// as midnight.
+        ///
+        /// Returns `None` if the date or the time doesn't exist.
+        pub fn to_naive_datetime(&self) -> Option<NaiveDateTime> {
+            let time = NaiveTime::from_hms_opt(
+                self.hour.unwrap_or_default().into(),
+                self.minute.unwrap_or_default().into(),
+                0,
+            )?;
+            Some(NaiveDateTime::new(self.to_naive_date()?, time))
+        }
+
+        /// Creates a datetime without time from a `NaiveDate`, the day name is
+        /// generated from the date.
+        pub fn from_naive_date(date: NaiveDate) -> Datetime<'static> {
+            Datetime {
+                year: date.year() as u16,
+                month: date.month() as u8,
+                day: date.day() as u8,
+                dayname: date.weekday().to_string().into(),
+                hour: None,
+                minute: None,
+            }
+        }
+
+        /// Creates a datetime from a `NaiveDateTime`, the day name is generated
+        /// from the date and seconds are dropped.
+        pub fn from_naive_datetime(datetime: NaiveDateTime) -> Datetime<'static> {
+            Datetime {
+                hour: Some(datetime.hour() as u8),
+                minute: Some(datetime.minute() as u8),
+                ..Datetime::from_naive_date(datetime.date())
+            }
+        }
+    }
+
+    impl Timestamp<'_> {
+        /// Creates an active or inactive timestamp without time from a `NaiveDate`.
+        ///
+        /// ```rust
+        /// use chrono::NaiveDate;
+        /// use orgize::elements::Timestamp;
+        ///
+        /// let date = NaiveDate::from_ymd_opt(2024, 3, 5).unwrap();
+        /// assert_eq!(Timestamp::from_date(date, true).to_string(), "<2024-03-05 Tue>");
+        /// assert_eq!(Timestamp::from_date(date, false).to_string(), "[2024-03-05 Tue]");
+        /// ```
+        pub fn from_date(date: NaiveDate, active: bool) -> Timestamp<'static> {
+            Timestamp::from_start(Datetime::from_naive_date(date), active)
+        }
+
+        /// Creates an active or inactive timestamp from a `NaiveDateTime`.
+        ///
+        /// ```rust
+        /// use chrono::NaiveDate;
+        /// use orgize::elements::Timestamp;
+        ///
+        /// let datetime = NaiveDate::from_ymd_opt(2024, 3, 5)
+        ///     .unwrap()
+        ///     .and_hms_opt(14, 0, 0)
+        ///     .unwrap();
+        /// assert_eq!(
+        ///     Timestamp::from_datetime(datetime, true).to_string(),
+        ///     "<2024-03-05 Tue 14:00>"
+        /// );
+        /// ```
+        pub fn from_datetime(datetime: NaiveDateTime, active: bool) -> Timestamp<'static> {
+            Timestamp::from_start(Datetime::from_naive_datetime(datetime), active)
+        }
+
+        fn from_start(start: Datetime<'static>, active: bool) -> Timestamp<'static> {
+            if active {
+                Timestamp::Active {
+                    start,
+                    repeater: None,
+                    delay: None,
+                    raw: None,
+                }
+            } else {
+                Timestamp::Inactive {
+                    start,
+                    repeater: None,
+                    delay: None,
+                    raw: None,
+                }
+            }
+        }
+
+        /// Returns the start of this timestamp as a `NaiveDateTime`.
+        ///
+        /// Returns `None` for diary timestamps or if the date doesn't exist.
+        pub fn start_datetime(&self) -> Option<NaiveDateTime> {
+            self.start_parts()?.0.to_naive_datetime()
+        }
+
+        /// Returns the start date of this timestamp.
+        ///
+        /// Returns `None` for diary timestamps or if the date doesn't exist.
+        pub fn start_date(&self) -> Option<NaiveDate> {
+            self.start_parts()?.0.to_naive_date()
+        }
+
+        /// Returns the end of this timestamp as a `NaiveDateTime`.
+        ///
+        /// An end time earlier than the start time on the same date, like
+        /// `<2024-01-01 Mon 23:30-01:00>`, is on the next day.
+        ///
+        /// Returns `None` if this timestamp isn't a range or if the date doesn't exist.
+        pub fn end_datetime(&self) -> Option<NaiveDateTime> {
+            match self {
+                Timestamp::ActiveRange { start, end, .. }
+                | Timestamp::InactiveRange { start, end, .. } => {
+                    let end_datetime = end.to_naive_datetime()?;
+                    match start.to_naive_datetime() {
+                        Some(start)
+                            if start > end_datetime && start.date() == end_datetime.date() =>
+                        {
+                            end_datetime.checked_add_signed(Duration::days(1))
+                        }
+                        _ => Some(end_datetime),
+                    }
+                }
+                _ => None,
+            }
+        }
+
+        /// Returns the duration of this timestamp range, see `end_datetime`.
+        ///
+        /// Returns `None` if this timestamp isn't a range, or its dates don't
+        /// exist, or it ends before its start.
+        ///
+        /// ```rust
+        /// use orgize::elements::Timestamp;
+        /// use std::convert::TryFrom;
+        ///
+        /// let ts = Timestamp::try_from("<2024-01-01 Mon 23:30-1:00>").unwrap();
+        /// assert_eq!(ts.duration().unwrap().to_string(), "1:30");
+        /// // the range is kept as written
+        /// assert_eq!(ts.to_string(), "<2024-01-01 Mon 23:30-01:00>");
+        /// ```
+        pub fn duration(&self) -> Option<OrgDuration> {
+            let minutes = (self.end_datetime()? - self.start_datetime()?).num_minutes();
+            u64::try_from(minutes).ok().map(OrgDuration::from_minutes)
+        }
+
+        /// Returns the end date of this timestamp.
+        ///
+        /// Returns `None` if this timestamp isn't a range or if the date doesn't exist.
+        pub fn end_date(&self) -> Option<NaiveDate> {
+            match self {
+                Timestamp::ActiveRange { end, .. } | Timestamp::InactiveRange { end, .. } => {
+                    end.to_naive_date()
+                }
+                _ => None,
+            }
+        }
+
+        /// Returns the date this timestamp moves to when its repeated task is
+        /// marked as done on `date`.
+        ///
+        /// + `+`: shifts the start date by one interval.
+        /// + `++`: shifts the start date by as many intervals as needed to land
+        ///   after `date`.
+        /// + `.+`: shifts `date` by one interval.
+        ///
+        /// Returns `None` if this timestamp has no repeater, or the start date
+        /// doesn't exist, or the result overflows. Date math is done on naive
+        /// dates, so daylight saving time never moves the result, and adding
+        /// months clamps to the end of the month.
+        ///
+        /// ```rust
+        /// use chrono::NaiveDate;
+        /// use orgize::elements::Timestamp;
+        /// use std::convert::TryFrom;
+        ///
+        /// let ts = Timestamp::try_from("<2021-01-04 Mon ++1w>").unwrap();
+        /// assert_eq!(
+        ///     ts.next_after(NaiveDate::from_ymd_opt(2021, 2, 3).unwrap()),
+        ///     NaiveDate::from_ymd_opt(2021, 2, 8)
+        /// );
+        /// ```
+        pub fn next_after(&self, date: NaiveDate) -> Option<NaiveDate> {
+            let (start, repeater) = self.start_parts()?;
+            let repeater = repeater?;
+            let start = start.to_naive_datetime()?;
+
+            match repeater.mark {
+                RepeaterMark::Cumulate => {
+                    add_interval(start, repeater.value, repeater.unit).map(|d| d.date())
+                }
+                RepeaterMark::CatchUp => {
+                    if repeater.value == 0 {
+                        return None;
+                    }
+                    let mut next = add_interval(start, repeater.value, repeater.unit)?;
+                    while next.date() <= date {
+                        next = add_interval(next, repeater.value, repeater.unit)?;
+                    }
+                    Some(next.date())
+                }
+                RepeaterMark::Restart => add_interval(
+                    NaiveDateTime::new(date, start.time()),
+                    repeater.value,
+                    repeater.unit,
+                )
+                .map(|d| d.date()),
+            }
+        }
+
+        /// Returns the status of a habit scheduled with this timestamp on
+        /// `today`, e.g. `SCHEDULED: <2024-01-01 Mon .+2d/4d>`, like
+        /// `org-habit` in Emacs.
+        ///
+        /// A habit is due from its scheduled date to the scheduled date plus
+        /// the difference between the maximum and the minimum interval. The
+        /// scheduled date itself moves when the habit is done, see
+        /// `next_after`. Months and years count as 30 and 365 days.
+        ///
+        /// Returns `None` if this timestamp has no repeater, or the start date
+        /// doesn't exist.
+        ///
+        /// ```rust
+        /// use chrono::NaiveDate;
+        /// use orgize::elements::{HabitStatus, Timestamp};
+        /// use std::convert::TryFrom;
+        ///
+        /// let ts = Timestamp::try_from("<2024-01-01 Mon .+2d/4d>").unwrap();
+        /// let status = |day| ts.habit_status(NaiveDate::from_ymd_opt(2024, 1, day).unwrap());
+        ///
+        /// assert_eq!(status(1), Some(HabitStatus::Due));
+        /// assert_eq!(status(3), Some(HabitStatus::Due));
+        /// assert_eq!(status(4), Some(HabitStatus::Overdue));
+        /// ```
+        pub fn habit_status(&self, today: NaiveDate) -> Option<HabitStatus> {
+            let (start, repeater) = self.start_parts()?;
+            let repeater = repeater?;
+            let scheduled = start.to_naive_date()?;
+
+            let days = |value: usize, unit: TimeUnit| -> i64 {
+                let days = match unit {
+                    TimeUnit::Hour => return value as i64 / 24,
+                    TimeUnit::Day => 1,
+                    TimeUnit::Week => 7,
+                    TimeUnit::Month => 30,
+                    TimeUnit::Year => 365,
+                };
+                (value as i64).saturating_mul(days)
+            };
+            let window = repeater.max.map_or(0, |(value, unit)| {
+                (days(value, unit) - days(repeater.value, repeater.unit)).max(0)
+            });
+            let deadline = scheduled
+                .checked_add_signed(Duration::days(window.min(36500)))
+                .unwrap_or(NaiveDate::MAX);
+
+            Some(if today < scheduled {
+                HabitStatus::NotDue
+            } else if today <= deadline {
+                HabitStatus::Due
+            } else {
+                HabitStatus::Overdue
+            })
+        }
+
+        /// Moves this timestamp to the date returned by `next_after(date)`,
+        /// keeping its time, repeater and delay. The end of a range is moved
+        /// by the same number of days.
+        ///
+        /// Returns `false` and leaves this timestamp unchanged if it doesn't
+        /// repeat.
+        pub(crate) fn repeat_after(&mut self, date: NaiveDate) -> bool {
+            let (start, next) = match (self.start_date(), self.next_after(date)) {
+                (Some(start), Some(next)) => (start, next),
+                _ => return false,
+            };
+            let days = next - start;
+
+            let shift = |datetime: &mut Datetime| {
+                if let Some(date) = datetime
+                    .to_naive_date()
+                    .and_then(|date| date.checked_add_signed(days))
+                {
+                    *datetime = Datetime {
+                        hour: datetime.hour,
+                        minute: datetime.minute,
+                        ..Datetime::from_naive_date(date)
+                    };
+                }
+            };
+
+            match self {
+                Timestamp::Active { start, raw, .. } | Timestamp::Inactive { start, raw, .. } => {
+                    shift(start);
+                    *raw = None;
+                }
+                Timestamp::ActiveRange {
+                    start, end, raw, ..
+                }
+                | Timestamp::InactiveRange {
+                    start, end, raw, ..
+                } => {
+                    shift(start);
+                    shift(end);
+                    *raw = None;
+                }
+                Timestamp::Diary { .. } => return false,
+            }
+
+            true
+        }
+
+        fn start_parts(&self) -> Option<(&Datetime<'_>, Option<Repeater>)> {
+            match self {
+                Timestamp::Active {
+                    start, repeater, ..
+                }
+                | Timestamp::Inactive {
+                    start, repeater, ..
+                } => Some((start, *repeater)),
+                Timestamp::ActiveRange {
+                    start,
+                    start_repeater,
+                    ..
+                }
+                | Timestamp::InactiveRange {
+                    start,
+                    start_repeater,
+                    ..
+                } => Some((start, *start_repeater)),
+                Timestamp::Diary { .. } => None,
+            }
+        }
+    }
+
+    fn add_interval(
+        datetime: NaiveDateTime,
+        value: usize,
+        unit: TimeUnit,
+    ) -> Option<NaiveDateTime> {
+        let value = u32::try_from(value).ok()?;
+        match unit {
+            TimeUnit::Hour => datetime.checked_add_signed(Duration::hours(value.into())),
+            TimeUnit::Day => datetime.checked_add_days(Days::new(value.into())),
+            TimeUnit::Week => datetime.checked_add_days(Days::new(u64::from(value) * 7)),
+            TimeUnit::Month => datetime.checked_add_months(Months::new(value)),
+            TimeUnit::Year => datetime.checked_add_months(Months::new(value.checked_mul(12)?)),
+        }
+    }
 }
 
 /// Timestamp Object
-#[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(feature = "ser", derive(serde::Serialize))]
 #[cfg_attr(feature = "ser", serde(rename_all = "kebab-case"))]
 #[cfg_attr(feature = "ser", serde(tag = "timestamp_type"))]
@@ -335,6 +713,9 @@ pub enum Timestamp<'a> {
         repeater: Option<Repeater>,
         #[cfg_attr(feature = "ser", serde(skip_serializing_if = "Option::is_none"))]
         delay: Option<Delay>,
+        /// Source text of this timestamp, `None` if it was created programmatically
+        #[cfg_attr(feature = "ser", serde(skip))]
+        raw: Option<Cow<'a, str>>,
     },
     Inactive {
         start: Datetime<'a>,
@@ -342,6 +723,9 @@ pub enum Timestamp<'a> {
         repeater: Option<Repeater>,
         #[cfg_attr(feature = "ser", serde(skip_serializing_if = "Option::is_none"))]
         delay: Option<Delay>,
+        /// Source text of this timestamp, `None` if it was created programmatically
+        #[cfg_attr(feature = "ser", serde(skip))]
+        raw: Option<Cow<'a, str>>,
     },
     ActiveRange {
         start: Datetime<'a>,
@@ -354,6 +738,9 @@ pub enum Timestamp<'a> {
         start_delay: Option<Delay>,
         #[cfg_attr(feature = "ser", serde(skip_serializing_if = "Option::is_none"))]
         end_delay: Option<Delay>,
+        /// Source text of this timestamp, `None` if it was created programmatically
+        #[cfg_attr(feature = "ser", serde(skip))]
+        raw: Option<Cow<'a, str>>,
     },
     InactiveRange {
         start: Datetime<'a>,
@@ -366,6 +753,9 @@ pub enum Timestamp<'a> {
         start_delay: Option<Delay>,
         #[cfg_attr(feature = "ser", serde(skip_serializing_if = "Option::is_none"))]
         end_delay: Option<Delay>,
+        /// Source text of this timestamp, `None` if it was created programmatically
+        #[cfg_attr(feature = "ser", serde(skip))]
+        raw: Option<Cow<'a, str>>,
     },
     Diary {
         value: Cow<'a, str>,
@@ -433,6 +823,7 @@ impl fmt::Display for Timestamp<'_> {
                 start,
                 repeater,
                 delay,
+                ..
             } => {
                 write!(f, "<{}", start)?;
                 write_parts(f, repeater, delay)?;
@@ -442,6 +833,7 @@ impl fmt::Display for Timestamp<'_> {
                 start,
                 repeater,
                 delay,
+                ..
             } => {
                 write!(f, "[{}", start)?;
                 write_parts(f, repeater, delay)?;
@@ -454,6 +846,7 @@ impl fmt::Display for Timestamp<'_> {
                 start_delay,
                 end_repeater,
                 end_delay,
+                ..
             } => {
                 write_range(
                     f,
@@ -474,6 +867,7 @@ impl fmt::Display for Timestamp<'_> {
                 start_delay,
                 end_repeater,
                 end_delay,
+                ..
             } => {
                 write_range(
                     f,
@@ -500,19 +894,23 @@ impl Timestamp<'_> {
                 start,
                 repeater,
                 delay,
+                raw,
             } => Timestamp::Active {
                 start: start.into_owned(),
                 repeater,
                 delay,
+                raw: raw.map(|s| s.into_owned().into()),
             },
             Timestamp::Inactive {
                 start,
                 repeater,
                 delay,
+                raw,
             } => Timestamp::Inactive {
                 start: start.into_owned(),
                 repeater,
                 delay,
+                raw: raw.map(|s| s.into_owned().into()),
             },
             Timestamp::ActiveRange {
                 start,
@@ -521,6 +919,7 @@ impl Timestamp<'_> {
                 end_repeater,
                 start_delay,
                 end_delay,
+                raw,
             } => Timestamp::ActiveRange {
                 start: start.into_owned(),
                 end: end.into_owned(),
@@ -528,6 +927,7 @@ impl Timestamp<'_> {
                 end_repeater,
                 start_delay,
                 end_delay,
+                raw: raw.map(|s| s.into_owned().into()),
             },
             Timestamp::InactiveRange {
                 start,
@@ -536,6 +936,7 @@ impl Timestamp<'_> {
                 end_repeater,
                 start_delay,
                 end_delay,
+                raw,
             } => Timestamp::InactiveRange {
                 start: start.into_owned(),
                 end: end.into_owned(),
@@ -543,6 +944,7 @@ impl Timestamp<'_> {
                 end_repeater,
                 start_delay,
                 end_delay,
+                raw: raw.map(|s| s.into_owned().into()),
             },
             Timestamp::Diary { value } => Timestamp::Diary {
                 value: value.into_owned().into(),
@@ -551,6 +953,208 @@ impl Timestamp<'_> {
     }
 }
 
+impl PartialEq for Timestamp<'_> {
+    // source text is ignored when comparing timestamps
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (
+                Timestamp::Active {
+                    start: s1,
+                    repeater: r1,
+                    delay: d1,
+                    ..
+                },
+                Timestamp::Active {
+                    start: s2,
+                    repeater: r2,
+                    delay: d2,
+                    ..
+                },
+            )
+            | (
+                Timestamp::Inactive {
+                    start: s1,
+                    repeater: r1,
+                    delay: d1,
+                    ..
+                },
+                Timestamp::Inactive {
+                    start: s2,
+                    repeater: r2,
+                    delay: d2,
+                    ..
+                },
+            ) => s1 == s2 && r1 == r2 && d1 == d2,
+            (
+                Timestamp::ActiveRange {
+                    start: s1,
+                    end: e1,
+                    start_repeater: sr1,
+                    end_repeater: er1,
+                    start_delay: sd1,
+                    end_delay: ed1,
+                    ..
+                },
+                Timestamp::ActiveRange {
+                    start: s2,
+                    end: e2,
+                    start_repeater: sr2,
+                    end_repeater: er2,
+                    start_delay: sd2,
+                    end_delay: ed2,
+                    ..
+                },
+            )
+            | (
+                Timestamp::InactiveRange {
+                    start: s1,
+                    end: e1,
+                    start_repeater: sr1,
+                    end_repeater: er1,
+                    start_delay: sd1,
+                    end_delay: ed1,
+                    ..
+                },
+                Timestamp::InactiveRange {
+                    start: s2,
+                    end: e2,
+                    start_repeater: sr2,
+                    end_repeater: er2,
+                    start_delay: sd2,
+                    end_delay: ed2,
+                    ..
+                },
+            ) => s1 == s2 && e1 == e2 && sr1 == sr2 && er1 == er2 && sd1 == sd2 && ed1 == ed2,
+            (Timestamp::Diary { value: v1 }, Timestamp::Diary { value: v2 }) => v1 == v2,
+            _ => false,
+        }
+    }
+}
+
+/// English abbreviated day names, starting from Monday
+pub const DAY_NAMES: [&str; 7] = ["Mon", "Tue", "Wed", "Thu", "Fri", "Sat", "Sun"];
+
+impl Datetime<'_> {
+    /// Returns the day of the week of this datetime, from `0` (Monday) to
+    /// `6` (Sunday), or `None` if the date doesn't exist.
+    ///
+    /// ```rust
+    /// use orgize::elements::Datetime;
+    /// use std::convert::TryFrom;
+    ///
+    /// assert_eq!(Datetime::try_from("2024-03-05").unwrap().weekday(), Some(1));
+    /// assert_eq!(Datetime::try_from("2023-02-29").unwrap().weekday(), None);
+    /// ```
+    pub fn weekday(&self) -> Option<usize> {
+        const OFFSETS: [usize; 12] = [0, 3, 2, 5, 0, 3, 5, 1, 4, 6, 2, 4];
+
+        let (year, month, day) = (self.year as usize, self.month as usize, self.day as usize);
+        let leap = year % 4 == 0 && (year % 100 != 0 || year % 400 == 0);
+        let days_in_month = match month {
+            2 if leap => 29,
+            2 => 28,
+            4 | 6 | 9 | 11 => 30,
+            1..=12 => 31,
+            _ => return None,
+        };
+        if day == 0 || day > days_in_month {
+            return None;
+        }
+
+        // Sakamoto's method, 0 is Sunday
+        let year = if month < 3 { year + 399 } else { year + 400 };
+        let weekday = (year + year / 4 - year / 100 + year / 400 + OFFSETS[month - 1] + day) % 7;
+
+        Some((weekday + 6) % 7)
+    }
+}
+
+impl Timestamp<'_> {
+    /// Returns the source text of this timestamp, or `None` if it was created
+    /// programmatically, or it was modified after parsing, or it's a diary timestamp.
+    ///
+    /// ```rust
+    /// use orgize::elements::Timestamp;
+    /// use std::convert::TryFrom;
+    ///
+    /// let mut ts = Timestamp::try_from("<2024-3-5  14:00>").unwrap();
+    /// assert_eq!(ts.raw(), Some("<2024-3-5  14:00>"));
+    ///
+    /// if let Timestamp::Active { start, .. } = &mut ts {
+    ///     start.day = 6;
+    /// }
+    /// assert_eq!(ts.raw(), None);
+    /// ```
+    pub fn raw(&self) -> Option<&str> {
+        let raw = match self {
+            Timestamp::Active { raw, .. }
+            | Timestamp::Inactive { raw, .. }
+            | Timestamp::ActiveRange { raw, .. }
+            | Timestamp::InactiveRange { raw, .. } => raw.as_deref()?,
+            Timestamp::Diary { .. } => return None,
+        };
+
+        match parse_timestamp(raw) {
+            Ok(("", parsed)) if parsed == *self => Some(raw),
+            _ => None,
+        }
+    }
+
+    /// Renders this timestamp in canonical org syntax, with day names regenerated
+    /// from the dates in english.
+    ///
+    /// Date and time are zero-padded, and a range within one day is written
+    /// in the short `<2024-03-05 Tue 14:00-15:00>` form.
+    ///
+    /// ```rust
+    /// use orgize::elements::Timestamp;
+    /// use std::convert::TryFrom;
+    ///
+    /// assert_eq!(
+    ///     Timestamp::try_from("<2024-3-5 14:00>--<2024-3-5 15:00 +1w>")
+    ///         .unwrap()
+    ///         .to_org_string(),
+    ///     "<2024-03-05 Tue 14:00>--<2024-03-05 Tue 15:00 +1w>"
+    /// );
+    /// assert_eq!(
+    ///     Timestamp::try_from("[2024-03-05 Fri 14:00-15:00 +1w]")
+    ///         .unwrap()
+    ///         .to_org_string(),
+    ///     "[2024-03-05 Tue 14:00-15:00 +1w]"
+    /// );
+    /// ```
+    pub fn to_org_string(&self) -> String {
+        self.to_org_string_with_day_names(&DAY_NAMES)
+    }
+
+    /// Likes `to_org_string`, but uses the given day names, starting from Monday.
+    ///
+    /// Day names of dates that don't exist are kept unchanged.
+    pub fn to_org_string_with_day_names<S: AsRef<str>>(&self, day_names: &[S; 7]) -> String {
+        let mut timestamp = self.clone();
+
+        let regenerate = |datetime: &mut Datetime| {
+            if let Some(weekday) = datetime.weekday() {
+                datetime.dayname = day_names[weekday].as_ref().to_string().into();
+            }
+        };
+
+        match &mut timestamp {
+            Timestamp::Active { start, .. } | Timestamp::Inactive { start, .. } => {
+                regenerate(start);
+            }
+            Timestamp::ActiveRange { start, end, .. }
+            | Timestamp::InactiveRange { start, end, .. } => {
+                regenerate(start);
+                regenerate(end);
+            }
+            Timestamp::Diary { .. } => (),
+        }
+
+        timestamp.to_string()
+    }
+}
+
 impl<'a> TryFrom<&'a str> for Timestamp<'a> {
     type Error = ();
     fn try_from(s: &'a str) -> Result<Self, Self::Error> {
@@ -569,12 +1173,19 @@ impl<'a> TryFrom<String> for Timestamp<'static> {
     }
 }
 
+// `H:MM` or `HH:MM`, hours after 23 and minutes after 59 are rejected
 fn parse_time(input: &str) -> IResult<&str, (u8, u8), ()> {
-    let (input, hour) = map_res(take_while_m_n(1, 2, |c: char| c.is_ascii_digit()), |num| {
-        u8::from_str_radix(num, 10)
-    })(input)?;
+    let (input, hour) = verify(
+        map_res(take_while_m_n(1, 2, |c: char| c.is_ascii_digit()), |num| {
+            u8::from_str_radix(num, 10)
+        }),
+        |hour| *hour < 24,
+    )(input)?;
     let (input, _) = tag(":")(input)?;
-    let (input, minute) = map_res(take(2usize), |num| u8::from_str_radix(num, 10))(input)?;
+    let (input, minute) = verify(
+        map_res(take(2usize), |num| u8::from_str_radix(num, 10)),
+        |minute| *minute < 60,
+    )(input)?;
     Ok((input, (hour, minute)))
 }
 
@@ -612,7 +1223,16 @@ fn parse_interval(input: &str) -> IResult<&str, (usize, TimeUnit), ()> {
 fn parse_repeater(input: &str) -> IResult<&str, Repeater, ()> {
     let (input, mark) = parse_repeater_mark(input)?;
     let (input, (value, unit)) = parse_interval(input)?;
-    Ok((input, Repeater { mark, value, unit }))
+    let (input, max) = opt(preceded(char('/'), parse_interval))(input)?;
+    Ok((
+        input,
+        Repeater {
+            mark,
+            value,
+            unit,
+            max,
+        },
+    ))
 }
 
 fn parse_delay(input: &str) -> IResult<&str, Delay, ()> {
@@ -698,7 +1318,7 @@ fn parse_timestamp_parts(input: &str) -> IResult<&str, TimestampParts, ()> {
 }
 
 pub(crate) fn parse_timestamp<'a>(input: &'a str) -> IResult<&str, Timestamp<'a>, ()> {
-    alt((
+    let (input, (source, mut timestamp)) = consumed(alt((
         map(
             delimited(
                 tag("<"),
@@ -712,6 +1332,7 @@ pub(crate) fn parse_timestamp<'a>(input: &'a str) -> IResult<&str, Timestamp<'a>
                 start_repeater: start.repeater,
                 end_delay: end.delay,
                 end_repeater: end.repeater,
+                raw: None,
             },
         ),
         map(
@@ -727,6 +1348,7 @@ pub(crate) fn parse_timestamp<'a>(input: &'a str) -> IResult<&str, Timestamp<'a>
                 start_repeater: start.repeater,
                 end_delay: end.delay,
                 end_repeater: end.repeater,
+                raw: None,
             },
         ),
         map(
@@ -743,12 +1365,14 @@ pub(crate) fn parse_timestamp<'a>(input: &'a str) -> IResult<&str, Timestamp<'a>
                         end_repeater: parts.repeater,
                         start_delay: parts.delay,
                         end_delay: parts.delay,
+                        raw: None,
                     }
                 }
                 None => Timestamp::Active {
                     start: parts.datetime,
                     delay: parts.delay,
                     repeater: parts.repeater,
+                    raw: None,
                 },
             },
         ),
@@ -766,12 +1390,14 @@ pub(crate) fn parse_timestamp<'a>(input: &'a str) -> IResult<&str, Timestamp<'a>
                         end_repeater: parts.repeater,
                         start_delay: parts.delay,
                         end_delay: parts.delay,
+                        raw: None,
                     }
                 }
                 None => Timestamp::Inactive {
                     start: parts.datetime,
                     delay: parts.delay,
                     repeater: parts.repeater,
+                    raw: None,
                 },
             },
         ),
@@ -781,7 +1407,17 @@ pub(crate) fn parse_timestamp<'a>(input: &'a str) -> IResult<&str, Timestamp<'a>
                 value: diary.into(),
             },
         ),
-    ))(input)
+    )))(input)?;
+
+    match &mut timestamp {
+        Timestamp::Active { raw, .. }
+        | Timestamp::Inactive { raw, .. }
+        | Timestamp::ActiveRange { raw, .. }
+        | Timestamp::InactiveRange { raw, .. } => *raw = Some(source.into()),
+        Timestamp::Diary { .. } => (),
+    }
+
+    Ok((input, timestamp))
 }
 
 #[test]
@@ -797,6 +1433,7 @@ fn parse() {
         },
         repeater: None,
         delay: None,
+        raw: None,
     };
     assert_eq!(
         parse_timestamp("<2019-03-26 Fri 03:33>"),
@@ -824,6 +1461,7 @@ fn parse() {
                 },
                 repeater: None,
                 delay: None,
+                raw: None,
             },
         ))
     );
@@ -852,6 +1490,7 @@ fn parse() {
                 end_repeater: None,
                 start_delay: None,
                 end_delay: None,
+                raw: None,
             },
         ))
     );
@@ -886,6 +1525,7 @@ fn parse() {
                 end_repeater: None,
                 start_delay: None,
                 end_delay: None,
+                raw: None,
             },
         ))
     );
@@ -914,6 +1554,7 @@ fn parse() {
                 end_repeater: None,
                 start_delay: None,
                 end_delay: None,
+                raw: None,
             },
         ))
     );
@@ -942,6 +1583,7 @@ fn parse() {
                 end_repeater: None,
                 start_delay: None,
                 end_delay: None,
+                raw: None,
             },
         ))
     );
@@ -971,6 +1613,7 @@ fn parse() {
                 end_repeater: None,
                 start_delay: None,
                 end_delay: None,
+                raw: None,
             },
         ))
     );
@@ -979,6 +1622,7 @@ fn parse() {
         mark: RepeaterMark::Cumulate,
         value: 1,
         unit: TimeUnit::Week,
+        max: None,
     });
     let delay = Some(Delay {
         mark: DelayMark::First,
@@ -1006,6 +1650,7 @@ fn parse() {
         end_repeater: repeater,
         start_delay: delay,
         end_delay: delay,
+        raw: None,
     };
     assert_eq!(
         parse_timestamp("<2003-09-16 Tue 09:39-10:39 +1w --2d>"),
@@ -1020,6 +1665,7 @@ fn parse() {
         mark: RepeaterMark::Restart,
         value: 1,
         unit: TimeUnit::Year,
+        max: None,
     });
     let delay2 = Some(Delay {
         mark: DelayMark::All,
@@ -1051,6 +1697,7 @@ fn parse() {
                 end_repeater: repeater2,
                 start_delay: delay,
                 end_delay: delay2,
+                raw: None,
             },
         ))
     );
@@ -1136,6 +1783,11 @@ fn test_parse_time() {
     assert_eq!(parse_time("00:00"), Ok(("", (0, 0))));
     assert_eq!(parse_time("0:00"), Ok(("", (0, 0))));
     assert_eq!(parse_time("0:01"), Ok(("", (0, 1))));
+    assert_eq!(parse_time("23:59"), Ok(("", (23, 59))));
+
+    assert_eq!(parse_time("24:00").ok(), None);
+    assert_eq!(parse_time("25:00").ok(), None);
+    assert_eq!(parse_time("9:60").ok(), None);
 }
 
 #[test]
@@ -1170,6 +1822,7 @@ fn test_parse_repeater_and_delay() {
         mark: RepeaterMark::Cumulate,
         unit: TimeUnit::Day,
         value: 1,
+        max: None,
     };
     assert_eq!(
         parse_repeater_and_delay(" +1d"),
@@ -1338,6 +1991,7 @@ fn test_parse_timestamp_parts() {
         mark: RepeaterMark::CatchUp,
         value: 2,
         unit: TimeUnit::Week,
+        max: None,
     });
     parts.datetime.dayname = "Mon".into();
     parts.datetime.hour = Some(3);
@@ -1436,3 +2090,226 @@ fn test_format_timestamp() {
             .to_string()
     );
 }
+
+#[test]
+#[cfg(feature = "chrono")]
+fn test_chrono_conversion() {
+    use ::chrono::NaiveDate;
+
+    let ts = Timestamp::try_from("<2021-03-13 Sat 23:30>--<2021-03-15 Mon 01:10>").unwrap();
+    assert_eq!(
+        ts.start_datetime(),
+        NaiveDate::from_ymd_opt(2021, 3, 13).and_then(|d| d.and_hms_opt(23, 30, 0))
+    );
+    assert_eq!(
+        ts.end_datetime(),
+        NaiveDate::from_ymd_opt(2021, 3, 15).and_then(|d| d.and_hms_opt(1, 10, 0))
+    );
+    // crosses the daylight saving time switch of most timezones
+    assert_eq!(
+        ts.end_datetime().unwrap() - ts.start_datetime().unwrap(),
+        ::chrono::Duration::minutes(25 * 60 + 40)
+    );
+
+    let ts = Timestamp::try_from("[2021-02-30 Tue]").unwrap();
+    assert_eq!(ts.start_date(), None);
+    assert_eq!(ts.start_datetime(), None);
+    assert_eq!(ts.end_datetime(), None);
+
+    let ts = Timestamp::try_from("<%%(diary-float t 4 2)>").unwrap();
+    assert_eq!(ts.start_date(), None);
+
+    let date = NaiveDate::from_ymd_opt(2024, 2, 29).unwrap();
+    assert_eq!(
+        Timestamp::from_date(date, true).to_string(),
+        "<2024-02-29 Thu>"
+    );
+    assert_eq!(
+        Timestamp::from_datetime(date.and_hms_opt(7, 5, 59).unwrap(), false).to_string(),
+        "[2024-02-29 Thu 07:05]"
+    );
+    assert_eq!(Timestamp::from_date(date, true).start_date(), Some(date));
+}
+
+#[test]
+#[cfg(feature = "chrono")]
+fn test_chrono_next_after() {
+    use ::chrono::NaiveDate;
+
+    let date = |y, m, d| NaiveDate::from_ymd_opt(y, m, d).unwrap();
+    let next = |s: &str, today| Timestamp::try_from(s).unwrap().next_after(today);
+
+    assert_eq!(
+        next("<2021-01-04 Mon +1w>", date(2021, 2, 3)),
+        Some(date(2021, 1, 11))
+    );
+    assert_eq!(
+        next("<2021-01-04 Mon ++1w>", date(2021, 2, 3)),
+        Some(date(2021, 2, 8))
+    );
+    assert_eq!(
+        next("<2021-01-04 Mon .+1w>", date(2021, 2, 3)),
+        Some(date(2021, 2, 10))
+    );
+
+    // catching up lands strictly after today, even on a repeat day
+    assert_eq!(
+        next("<2021-01-04 Mon ++1w>", date(2021, 2, 8)),
+        Some(date(2021, 2, 15))
+    );
+    assert_eq!(
+        next("<2021-01-04 Mon ++1w>", date(2020, 1, 1)),
+        Some(date(2021, 1, 11))
+    );
+    assert_eq!(
+        next("<2021-01-04 Mon ++2d>", date(2021, 1, 9)),
+        Some(date(2021, 1, 10))
+    );
+
+    // naive dates aren't affected by daylight saving time
+    assert_eq!(
+        next("<2021-03-13 Sat +1d>", date(2021, 3, 13)),
+        Some(date(2021, 3, 14))
+    );
+    assert_eq!(
+        next("<2021-03-13 Sat 23:30 +1h>", date(2021, 3, 13)),
+        Some(date(2021, 3, 14))
+    );
+
+    assert_eq!(
+        next("<2021-01-31 Sun +1m>", date(2021, 1, 31)),
+        Some(date(2021, 2, 28))
+    );
+    assert_eq!(
+        next("<2020-02-29 Sat ++1y>", date(2022, 6, 1)),
+        Some(date(2023, 2, 28))
+    );
+
+    assert_eq!(next("<2021-01-04 Mon>", date(2021, 2, 3)), None);
+    assert_eq!(next("<2021-01-04 Mon ++0d>", date(2021, 2, 3)), None);
+}
+
+#[test]
+#[cfg(feature = "chrono")]
+fn test_chrono_regenerate_dayname() {
+    use crate::{Element, Org};
+
+    let mut org = Org::parse("* TODO task\nSCHEDULED: <2024-03-05 Wed>\n");
+    let headline = org.headlines().next().unwrap();
+    let title = headline.title_mut(&mut org);
+    let planning = title.planning.as_mut().unwrap();
+    let date = planning.scheduled.as_ref().unwrap().start_date().unwrap();
+    planning.scheduled = Some(Timestamp::from_date(date, true));
+
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        "* TODO task\nSCHEDULED: <2024-03-05 Tue>\n"
+    );
+
+    let org = Org::parse("[2024-03-05]");
+    let dates: Vec<_> = org
+        .iter()
+        .filter_map(|event| match event {
+            crate::Event::Start(Element::Timestamp(ts)) => ts.start_date(),
+            _ => None,
+        })
+        .map(|date| Timestamp::from_date(date, false).to_string())
+        .collect();
+    assert_eq!(dates, ["[2024-03-05 Tue]"]);
+}
+
+#[test]
+fn test_canonical_timestamp() {
+    let canonical = |s: &str| Timestamp::try_from(s).unwrap().to_org_string();
+
+    // ranges within one day
+    assert_eq!(
+        canonical("<2024-03-05 14:00-15:00 +1w>"),
+        "<2024-03-05 Tue 14:00-15:00 +1w>"
+    );
+    assert_eq!(
+        canonical("<2024-3-5 Tue 9:00>--<2024-03-05 Tue 9:30>"),
+        "<2024-03-05 Tue 09:00-09:30>"
+    );
+    // ranges across days
+    assert_eq!(
+        canonical("[2024-03-05 14:00]--[2024-03-06 Sun 1:00]"),
+        "[2024-03-05 Tue 14:00]--[2024-03-06 Wed 01:00]"
+    );
+    assert_eq!(
+        canonical("<2024-02-28>--<2024-03-01>"),
+        "<2024-02-28 Wed>--<2024-03-01 Fri>"
+    );
+    // missing day name
+    assert_eq!(canonical("[2000-01-01]"), "[2000-01-01 Sat]");
+    assert_eq!(
+        canonical("<1900-03-01 .+1d -2d>"),
+        "<1900-03-01 Thu .+1d -2d>"
+    );
+    // dates that don't exist keep their day names
+    assert_eq!(canonical("<2023-02-29 Foo>"), "<2023-02-29 Foo>");
+    assert_eq!(
+        canonical("<%%(diary-float t 4 2)>"),
+        "<%%(diary-float t 4 2)>"
+    );
+
+    let ts = Timestamp::try_from("<2024-03-05 14:00>").unwrap();
+    assert_eq!(
+        ts.to_org_string_with_day_names(&["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"]),
+        "<2024-03-05 Di 14:00>"
+    );
+}
+
+#[test]
+fn test_export_timestamp() {
+    use crate::{export::DefaultOrgHandler, Element, Org};
+
+    let mut org = Org::parse(
+        "* TODO task\nDEADLINE: <2024-3-5  Fri>\n\n<2024-03-05  14:00> [2024-03-05 14:00]--[2024-03-06]\n",
+    );
+
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        "* TODO task\nDEADLINE: <2024-3-5  Fri>\n\n<2024-03-05  14:00> [2024-03-05 14:00]--[2024-03-06]\n"
+    );
+
+    let nodes: Vec<_> = org
+        .arena()
+        .iter()
+        .filter(|node| matches!(node.get(), Element::Timestamp(_)))
+        .map(|node| org.arena().get_node_id(node).unwrap())
+        .collect();
+
+    if let Element::Timestamp(Timestamp::Active { start, .. }) = &mut org[nodes[0]] {
+        start.hour = Some(15);
+    }
+    let headline = org.headlines().next().unwrap();
+    if let Some(Timestamp::Active { start, .. }) = headline
+        .title_mut(&mut org)
+        .planning
+        .as_mut()
+        .and_then(|p| p.deadline.as_mut())
+    {
+        start.day = 6;
+    }
+
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        "* TODO task\nDEADLINE: <2024-03-06 Wed>\n\n<2024-03-05 Tue 15:00> [2024-03-05 14:00]--[2024-03-06]\n"
+    );
+
+    let mut writer = Vec::new();
+    let mut handler = DefaultOrgHandler::default();
+    handler.day_names = ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"].map(String::from);
+    org.write_org_custom(&mut writer, &mut handler).unwrap();
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        "* TODO task\nDEADLINE: <2024-03-06 Mi>\n\n<2024-03-05 Di 15:00> [2024-03-05 14:00]--[2024-03-06]\n"
+    );
+}
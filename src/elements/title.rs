@@ -5,12 +5,10 @@ use std::{borrow::Cow, iter::FromIterator};
 
 use memchr::memrchr2;
 use nom::{
-    branch::alt,
     bytes::complete::{tag, take_until, take_while},
-    character::complete::{anychar, line_ending, space1},
+    character::complete::{anychar, space0, space1},
     combinator::{map, opt, verify},
     error::{make_error, ErrorKind},
-    multi::fold_many0,
     sequence::{delimited, preceded},
     Err, IResult,
 };
@@ -26,7 +24,8 @@ use crate::{
 #[cfg_attr(feature = "ser", derive(serde::Serialize))]
 #[derive(Debug, Clone)]
 pub struct Title<'a> {
-    /// Headline level, number of stars
+    /// Headline level, number of stars, or the reduced level if only odd
+    /// levels are used, see `Startup::odd_levels`
     pub level: usize,
     /// Headline priority cookie
     #[cfg_attr(feature = "ser", serde(skip_serializing_if = "Option::is_none"))]
@@ -39,6 +38,12 @@ pub struct Title<'a> {
     pub keyword: Option<Cow<'a, str>>,
     /// Raw headline's text, without the stars and the tags
     pub raw: Cow<'a, str>,
+    /// Source text of the headline line, without the line ending
+    ///
+    /// Its spacing, e.g. tabs before the tags, is written back as-is unless
+    /// the level, keyword, priority, raw text or tags are modified.
+    #[cfg_attr(feature = "ser", serde(skip))]
+    pub raw_line: Option<Cow<'a, str>>,
     /// Planning element associated to this headline
     #[cfg_attr(feature = "ser", serde(skip_serializing_if = "Option::is_none"))]
     pub planning: Option<Box<Planning<'a>>>,
@@ -48,6 +53,12 @@ pub struct Title<'a> {
         serde(skip_serializing_if = "PropertiesMap::is_empty")
     )]
     pub properties: PropertiesMap<'a>,
+    /// Source text of planning line and property drawer if they're parsed in
+    /// non-canonical arrangement, see `ParseConfig::lenient_headline_metadata`
+    ///
+    /// It's written back as-is unless planning or properties are modified.
+    #[cfg_attr(feature = "ser", serde(skip))]
+    pub raw_metadata: Option<Cow<'a, str>>,
     /// Numbers of blank lines between last title's line and next non-blank line
     /// or buffer's end
     pub post_blank: usize,
@@ -61,9 +72,6 @@ impl Title<'_> {
         parse_title(input, config).ok()
     }
 
-    // TODO: fn is_quoted(&self) -> bool { }
-    // TODO: fn is_footnote_section(&self) -> bool { }
-
     /// Returns this headline's closed timestamp, or `None` if not set.
     pub fn closed(&self) -> Option<&Timestamp> {
         self.planning.as_ref().and_then(|p| p.closed.as_ref())
@@ -85,9 +93,32 @@ impl Title<'_> {
     }
 
     /// Returns `true` if this headline is commented
+    ///
+    /// ```rust
+    /// use orgize::Org;
+    ///
+    /// let org = Org::parse("* COMMENT Title\n* COMMENTé\n* COMMENT\n");
+    /// let commented: Vec<_> = org
+    ///     .headlines()
+    ///     .map(|headline| headline.title(&org).is_commented())
+    ///     .collect();
+    ///
+    /// assert_eq!(commented, [true, false, true]);
+    /// ```
     pub fn is_commented(&self) -> bool {
-        self.raw.starts_with("COMMENT")
-            && (self.raw.len() == 7 || self.raw[7..].starts_with(char::is_whitespace))
+        strip_word(&self.raw, "COMMENT").is_some()
+    }
+
+    /// Returns `true` if this headline is quoted, i.e. its title starts with
+    /// the `QUOTE` keyword
+    pub fn is_quoted(&self) -> bool {
+        strip_word(&self.raw, "QUOTE").is_some()
+    }
+
+    /// Returns `true` if this headline is the footnote section, i.e. its
+    /// title, without the `COMMENT` keyword, is `Footnotes`
+    pub fn is_footnote_section(&self) -> bool {
+        strip_word(&self.raw, "COMMENT").unwrap_or(&self.raw) == "Footnotes"
     }
 
     pub fn into_owned(self) -> Title<'static> {
@@ -101,8 +132,10 @@ impl Title<'_> {
                 .collect(),
             keyword: self.keyword.map(Into::into).map(Cow::Owned),
             raw: self.raw.into_owned().into(),
+            raw_line: self.raw_line.map(|s| s.into_owned().into()),
             planning: self.planning.map(|p| Box::new(p.into_owned())),
             properties: self.properties.into_owned(),
+            raw_metadata: self.raw_metadata.map(|s| s.into_owned().into()),
             post_blank: self.post_blank,
         }
     }
@@ -116,30 +149,108 @@ impl Default for Title<'_> {
             tags: Vec::new(),
             keyword: None,
             raw: Cow::Borrowed(""),
+            raw_line: None,
             planning: None,
             properties: PropertiesMap::new(),
+            raw_metadata: None,
             post_blank: 0,
         }
     }
 }
 
 /// Properties
+///
+/// Names of accumulating properties keep their trailing `+`, e.g. `VAR+`.
 #[derive(Default, Debug, Clone)]
-#[cfg_attr(test, derive(PartialEq))]
 #[cfg_attr(feature = "ser", derive(serde::Serialize))]
 pub struct PropertiesMap<'a> {
     pub pairs: Vec<(Cow<'a, str>, Cow<'a, str>)>,
+    // indentation of the drawer lines
+    #[cfg_attr(feature = "ser", serde(skip))]
+    pub(crate) indent: Cow<'a, str>,
+    // column of values after the indentation, if they are aligned
+    #[cfg_attr(feature = "ser", serde(skip))]
+    pub(crate) value_column: Option<usize>,
 }
 
 impl<'a> PropertiesMap<'a> {
     pub fn new() -> Self {
-        PropertiesMap { pairs: Vec::new() }
+        PropertiesMap {
+            pairs: Vec::new(),
+            indent: Cow::Borrowed(""),
+            value_column: None,
+        }
     }
 
     pub fn is_empty(&self) -> bool {
         self.pairs.is_empty()
     }
 
+    /// Returns the value of property `key`, case-insensitively.
+    ///
+    /// Accumulating `KEY+` properties are matched as well, see
+    /// `get_accumulated` for the value they add up to.
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.pairs
+            .iter()
+            .find(|(k, _)| is_property(k, key))
+            .map(|(_, v)| &**v)
+    }
+
+    /// Returns the value of property `key` along with accumulating `KEY+`
+    /// properties, joined with spaces, case-insensitively.
+    ///
+    /// ```rust
+    /// use orgize::Org;
+    ///
+    /// let org = Org::parse("* h\n:PROPERTIES:\n:VAR: x=1\n:VAR+: y=2\n:END:\n");
+    /// let title = org.headlines().next().unwrap().title(&org);
+    ///
+    /// assert_eq!(title.properties.get("VAR"), Some("x=1"));
+    /// assert_eq!(title.properties.get_accumulated("var").as_deref(), Some("x=1 y=2"));
+    /// ```
+    pub fn get_accumulated(&self, key: &str) -> Option<String> {
+        let mut values = self
+            .pairs
+            .iter()
+            .filter(|(k, _)| is_property(k, key))
+            .map(|(_, v)| &**v)
+            .peekable();
+        values.peek()?;
+        Some(values.collect::<Vec<_>>().join(" "))
+    }
+
+    /// Sets property `key` to `value`.
+    ///
+    /// The first property named `key` is updated in place, keys are compared
+    /// case-insensitively, or a new property is appended. Accumulating
+    /// `KEY+` properties are left untouched, use `remove` first to replace
+    /// them as well.
+    pub fn set<K, V>(&mut self, key: K, value: V)
+    where
+        K: Into<Cow<'a, str>>,
+        V: Into<Cow<'a, str>>,
+    {
+        let key = key.into();
+        let value = value.into();
+        match self
+            .pairs
+            .iter_mut()
+            .find(|(k, _)| k.eq_ignore_ascii_case(&key))
+        {
+            Some((_, old)) => *old = value,
+            None => self.pairs.push((key, value)),
+        }
+    }
+
+    /// Removes property `key`, along with accumulating `KEY+` properties,
+    /// case-insensitively. Returns `true` if any property was removed.
+    pub fn remove(&mut self, key: &str) -> bool {
+        let len = self.pairs.len();
+        self.pairs.retain(|(k, _)| !is_property(k, key));
+        self.pairs.len() != len
+    }
+
     pub fn iter(&self) -> impl Iterator<Item = &(Cow<'a, str>, Cow<'a, str>)> {
         self.pairs.iter()
     }
@@ -162,13 +273,33 @@ impl<'a> PropertiesMap<'a> {
     }
 
     pub fn into_owned(self) -> PropertiesMap<'static> {
-        self.pairs
-            .into_iter()
-            .map(|(k, v)| (k.into_owned().into(), v.into_owned().into()))
-            .collect()
+        PropertiesMap {
+            pairs: self
+                .pairs
+                .into_iter()
+                .map(|(k, v)| (k.into_owned().into(), v.into_owned().into()))
+                .collect(),
+            indent: self.indent.into_owned().into(),
+            value_column: self.value_column,
+        }
     }
 }
 
+// formatting of the drawer is ignored when comparing properties
+impl PartialEq for PropertiesMap<'_> {
+    fn eq(&self, other: &Self) -> bool {
+        self.pairs == other.pairs
+    }
+}
+
+// whether property `name` is `key` or accumulating `key+`
+fn is_property(name: &str, key: &str) -> bool {
+    let key = key.strip_suffix('+').unwrap_or(key);
+    name.strip_suffix('+')
+        .unwrap_or(name)
+        .eq_ignore_ascii_case(key)
+}
+
 impl<'a> FromIterator<(Cow<'a, str>, Cow<'a, str>)> for PropertiesMap<'a> {
     fn from_iter<T: IntoIterator<Item = (Cow<'a, str>, Cow<'a, str>)>>(iter: T) -> Self {
         let mut map = PropertiesMap::new();
@@ -177,44 +308,49 @@ impl<'a> FromIterator<(Cow<'a, str>, Cow<'a, str>)> for PropertiesMap<'a> {
     }
 }
 
-fn white_spaces_or_eol(input: &str) -> IResult<&str, &str, ()> {
-    alt((space1, line_ending))(input)
-}
-
 #[inline]
 fn parse_title<'a>(
     input: &'a str,
     config: &ParseConfig,
 ) -> IResult<&'a str, (Title<'a>, &'a str), ()> {
-    let (input, level) = map(take_while(|c: char| c == '*'), |s: &str| s.len())(input)?;
+    let (_, raw_line) = line(input)?;
+    let (input, stars) = map(take_while(|c: char| c == '*'), |s: &str| s.len())(input)?;
+
+    debug_assert!(stars > 0);
 
-    debug_assert!(level > 0);
+    let level = config.startup.level(stars);
 
     let (input, keyword) = opt(preceded(
         space1,
-        verify(one_word, |s: &str| {
-            config.todo_keywords.0.iter().any(|x| x == s)
-                || config.todo_keywords.1.iter().any(|x| x == s)
-        }),
+        verify(one_word, |s: &str| config.is_todo_keyword(s)),
     ))(input)?;
 
-    let (input, priority) = opt(delimited(
+    // like Emacs, the priority cookie can be followed by anything, e.g.
+    // `[#A]Title`
+    let (input, priority) = opt(preceded(
         space1,
         delimited(
             tag("[#"),
             verify(anychar, |c: &char| c.is_ascii_uppercase()),
             tag("]"),
         ),
-        white_spaces_or_eol,
     ))(input)?;
     let (input, tail) = line(input)?;
-    let tail = tail.trim();
-
-    // tags can be separated by space or \t
-    let (raw, tags) = memrchr2(b' ', b'\t', tail.as_bytes())
-        .map(|i| (tail[0..i].trim(), &tail[i + 1..]))
-        .filter(|(_, x)| is_tag_line(x))
-        .unwrap_or((tail, ""));
+    // like Emacs, only ascii whitespace separates tags, so that a
+    // non-breaking space before or after them keeps them in the title
+    let tail = tail.trim_matches(|c: char| c.is_ascii_whitespace());
+
+    // tags can be separated by space or \t, only the last group counts, and
+    // the title can be nothing but tags
+    let (raw, tags) = match memrchr2(b' ', b'\t', tail.as_bytes()) {
+        Some(i) => (tail[0..i].trim_end(), &tail[i + 1..]),
+        None => ("", tail),
+    };
+    let (raw, tags) = if is_tag_line(tags) {
+        (raw, tags)
+    } else {
+        (tail, "")
+    };
 
     let tags = tags
         .split(':')
@@ -222,11 +358,8 @@ fn parse_title<'a>(
         .map(Into::into)
         .collect();
 
-    let (input, planning) = Planning::parse(input)
-        .map(|(input, planning)| (input, Some(Box::new(planning))))
-        .unwrap_or((input, None));
-
-    let (input, properties) = opt(parse_properties_drawer)(input)?;
+    let (input, (planning, properties, raw_metadata)) =
+        parse_metadata(input, config.lenient_headline_metadata);
     let (input, post_blank) = blank_lines_count(input)?;
 
     Ok((
@@ -239,7 +372,9 @@ fn parse_title<'a>(
                 priority,
                 tags,
                 raw: raw.into(),
-                planning,
+                raw_line: Some(raw_line.into()),
+                planning: planning.map(Box::new),
+                raw_metadata: raw_metadata.map(Into::into),
                 post_blank,
             },
             raw,
@@ -247,7 +382,18 @@ fn parse_title<'a>(
     ))
 }
 
-fn is_tag_line(input: &str) -> bool {
+/// Strips `word` at the beginning of `raw`, if it's followed by a space, a
+/// tab or nothing, like Emacs.
+fn strip_word<'a>(raw: &'a str, word: &str) -> Option<&'a str> {
+    let rest = raw.strip_prefix(word)?;
+    if rest.is_empty() || rest.starts_with([' ', '\t']) {
+        Some(rest.trim_start_matches([' ', '\t']))
+    } else {
+        None
+    }
+}
+
+pub(crate) fn is_tag_line(input: &str) -> bool {
     input.len() > 2
         && input.starts_with(':')
         && input.ends_with(':')
@@ -256,32 +402,114 @@ fn is_tag_line(input: &str) -> bool {
         })
 }
 
+type Metadata<'a> = (
+    Option<Planning<'a>>,
+    Option<PropertiesMap<'a>>,
+    Option<&'a str>,
+);
+
+/// Parses planning line and property drawer after the title line, returns
+/// them along with their source text if they aren't in canonical arrangement.
+///
+/// Up to `max_blank_lines` blank lines are allowed before each of them, and
+/// they can come in either order, if it isn't `None`.
+pub(crate) fn parse_metadata(input: &str, max_blank_lines: Option<usize>) -> (&str, Metadata<'_>) {
+    let max_blank_lines = match max_blank_lines {
+        Some(max) => max,
+        None => {
+            let (input, planning) = Planning::parse(input)
+                .map(|(input, planning)| (input, Some(planning)))
+                .unwrap_or((input, None));
+            let (input, properties) = parse_properties_drawer(input)
+                .map(|(input, properties)| (input, Some(properties)))
+                .unwrap_or((input, None));
+            return (input, (planning, properties, None));
+        }
+    };
+
+    let (mut planning, mut properties) = (None, None);
+    let mut canonical = true;
+    let mut tail = input;
+
+    loop {
+        let (rest, blank_lines) = match blank_lines_count(tail) {
+            Ok((rest, blank_lines)) if blank_lines <= max_blank_lines => (rest, blank_lines),
+            _ => break,
+        };
+        if planning.is_none() {
+            if let Some((rest, parsed)) = Planning::parse(rest) {
+                canonical &= blank_lines == 0 && properties.is_none();
+                planning = Some(parsed);
+                tail = rest;
+                continue;
+            }
+        }
+        if properties.is_none() {
+            if let Ok((rest, parsed)) = parse_properties_drawer(rest) {
+                canonical &= blank_lines == 0;
+                properties = Some(parsed);
+                tail = rest;
+                continue;
+            }
+        }
+        break;
+    }
+
+    let raw = if canonical {
+        None
+    } else {
+        Some(&input[..input.len() - tail.len()])
+    };
+
+    (tail, (planning, properties, raw))
+}
+
 #[inline]
 fn parse_properties_drawer(input: &str) -> IResult<&str, PropertiesMap<'_>, ()> {
-    let (input, (drawer, content)) = parse_drawer_without_blank(input.trim_start())?;
+    let (input, (drawer, content)) = parse_drawer_without_blank(input)?;
     if drawer.name != "PROPERTIES" {
         return Err(Err::Error(make_error(input, ErrorKind::Tag)));
     }
-    let (_, map) = fold_many0(
-        parse_node_property,
-        PropertiesMap::new,
-        |mut acc: PropertiesMap, (name, value)| {
-            acc.pairs.push((name.into(), value.into()));
-            acc
-        },
-    )(content)?;
-    Ok((input, map))
+    Ok((input, parse_node_properties(content)))
+}
+
+// `:NAME: value` lines, stops at the first line in other forms
+pub(crate) fn parse_node_properties(content: &str) -> PropertiesMap<'_> {
+    let mut map = PropertiesMap::new();
+    // columns of non-empty values, and whether any of them is separated from
+    // its name by more than one space
+    let mut columns = Vec::new();
+    let mut padded = false;
+
+    let mut input = content;
+    while let Ok((rest, (indent, name, gap, value))) = parse_node_property(input) {
+        if map.pairs.is_empty() {
+            map.indent = indent.into();
+        }
+        if !value.is_empty() {
+            columns.push(name.chars().count() + 2 + gap);
+            padded |= gap > 1;
+        }
+        map.pairs.push((name.into(), value.into()));
+        input = rest;
+    }
+
+    if padded && columns.windows(2).all(|w| w[0] == w[1]) {
+        map.value_column = columns.first().copied();
+    }
+
+    map
 }
 
+// returns indentation, name, width of the spaces after the name and value
 #[inline]
-fn parse_node_property(input: &str) -> IResult<&str, (&str, &str), ()> {
+fn parse_node_property(input: &str) -> IResult<&str, (&str, &str, usize, &str), ()> {
     let (input, _) = blank_lines_count(input)?;
-    let input = input.trim_start();
-    let (input, name) = map(delimited(tag(":"), take_until(":"), tag(":")), |s: &str| {
-        s.trim_end_matches('+')
-    })(input)?;
+    let (input, indent) = space0(input)?;
+    let (input, name) = delimited(tag(":"), take_until(":"), tag(":"))(input)?;
     let (input, value) = line(input)?;
-    Ok((input, (name, value.trim())))
+    let gap = value.len() - value.trim_start().len();
+    Ok((input, (indent, name, gap, value.trim())))
 }
 
 #[test]
@@ -298,9 +526,11 @@ fn parse_title_() {
                     keyword: Some("DONE".into()),
                     priority: Some('A'),
                     raw: "COMMENT Title".into(),
+                    raw_line: Some("**** DONE [#A] COMMENT Title :tag:a2%:".into()),
                     tags: vec!["tag".into(), "a2%".into()],
                     planning: None,
                     properties: PropertiesMap::new(),
+                    raw_metadata: None,
                     post_blank: 0,
                 },
                 "COMMENT Title"
@@ -317,9 +547,11 @@ fn parse_title_() {
                     keyword: None,
                     priority: None,
                     raw: "ToDO [#A] COMMENT Title".into(),
+                    raw_line: Some("**** ToDO [#A] COMMENT Title".into()),
                     tags: vec![],
                     planning: None,
                     properties: PropertiesMap::new(),
+                    raw_metadata: None,
                     post_blank: 0,
                 },
                 "ToDO [#A] COMMENT Title"
@@ -336,9 +568,11 @@ fn parse_title_() {
                     keyword: None,
                     priority: None,
                     raw: "T0DO [#A] COMMENT Title".into(),
+                    raw_line: Some("**** T0DO [#A] COMMENT Title".into()),
                     tags: vec![],
                     planning: None,
                     properties: PropertiesMap::new(),
+                    raw_metadata: None,
                     post_blank: 0,
                 },
                 "T0DO [#A] COMMENT Title"
@@ -355,9 +589,11 @@ fn parse_title_() {
                     keyword: Some("DONE".into()),
                     priority: None,
                     raw: "[#1] COMMENT Title".into(),
+                    raw_line: Some("**** DONE [#1] COMMENT Title".into()),
                     tags: vec![],
                     planning: None,
                     properties: PropertiesMap::new(),
+                    raw_metadata: None,
                     post_blank: 0,
                 },
                 "[#1] COMMENT Title"
@@ -374,9 +610,11 @@ fn parse_title_() {
                     keyword: Some("DONE".into()),
                     priority: None,
                     raw: "[#a] COMMENT Title".into(),
+                    raw_line: Some("**** DONE [#a] COMMENT Title".into()),
                     tags: vec![],
                     planning: None,
                     properties: PropertiesMap::new(),
+                    raw_metadata: None,
                     post_blank: 0,
                 },
                 "[#a] COMMENT Title"
@@ -393,14 +631,16 @@ fn parse_title_() {
                 Title {
                     level: 2,
                     keyword: Some("DONE".into()),
-                    priority: None,
-                    raw: "[#B]::".into(),
+                    priority: Some('B'),
+                    raw: "::".into(),
+                    raw_line: Some("** DONE [#B]::".into()),
                     tags: vec![],
                     planning: None,
                     properties: PropertiesMap::new(),
+                    raw_metadata: None,
                     post_blank: 0,
                 },
-                "[#B]::"
+                "::"
             )
         ))
     );
@@ -415,9 +655,11 @@ fn parse_title_() {
                     keyword: None,
                     priority: None,
                     raw: "Title :tag:a2%".into(),
+                    raw_line: Some("**** Title :tag:a2%".into()),
                     tags: vec![],
                     planning: None,
                     properties: PropertiesMap::new(),
+                    raw_metadata: None,
                     post_blank: 0,
                 },
                 "Title :tag:a2%"
@@ -434,9 +676,11 @@ fn parse_title_() {
                     keyword: None,
                     priority: None,
                     raw: "Title tag:a2%:".into(),
+                    raw_line: Some("**** Title tag:a2%:".into()),
                     tags: vec![],
                     planning: None,
                     properties: PropertiesMap::new(),
+                    raw_metadata: None,
                     post_blank: 0,
                 },
                 "Title tag:a2%:"
@@ -460,9 +704,11 @@ fn parse_title_() {
                     keyword: None,
                     priority: None,
                     raw: "DONE Title".into(),
+                    raw_line: Some("**** DONE Title".into()),
                     tags: vec![],
                     planning: None,
                     properties: PropertiesMap::new(),
+                    raw_metadata: None,
                     post_blank: 0,
                 },
                 "DONE Title"
@@ -485,9 +731,11 @@ fn parse_title_() {
                     keyword: Some("TASK".into()),
                     priority: Some('A'),
                     raw: "Title".into(),
+                    raw_line: Some("**** TASK [#A] Title".into()),
                     tags: vec![],
                     planning: None,
                     properties: PropertiesMap::new(),
+                    raw_metadata: None,
                     post_blank: 0,
                 },
                 "Title"
@@ -496,6 +744,87 @@ fn parse_title_() {
     );
 }
 
+#[test]
+fn title_keywords() {
+    use crate::config::DEFAULT_CONFIG;
+
+    let title = |input| {
+        let (_, (title, _)) = parse_title(input, &DEFAULT_CONFIG).unwrap();
+        title
+    };
+
+    assert!(title("* COMMENT").is_commented());
+    assert!(title("* COMMENT\tTitle").is_commented());
+    assert!(!title("* COMMENTé").is_commented());
+    assert!(!title("* COMMENT中文").is_commented());
+    assert!(!title("* COMMENTS").is_commented());
+    assert!(!title("* comment Title").is_commented());
+    assert!(title("* QUOTE Title").is_quoted());
+    assert!(!title("* QUOTEé").is_quoted());
+    assert!(!title("* QUOTED").is_quoted());
+    assert!(title("* Footnotes").is_footnote_section());
+    assert!(title("* COMMENT Footnotes").is_footnote_section());
+    assert!(!title("* Footnotes é").is_footnote_section());
+    assert!(!title("* Footnotesé").is_footnote_section());
+    assert!(!title("* COMMENTFootnotes").is_footnote_section());
+}
+
+#[test]
+fn parse_title_priority() {
+    use crate::config::DEFAULT_CONFIG;
+
+    let priority = |input| {
+        let (_, (title, raw)) = parse_title(input, &DEFAULT_CONFIG).unwrap();
+        (title.keyword, title.priority, raw)
+    };
+
+    assert_eq!(
+        priority("** TODO [#A]Fix the thing"),
+        (Some("TODO".into()), Some('A'), "Fix the thing")
+    );
+    assert_eq!(
+        priority("** TODO [#A]"),
+        (Some("TODO".into()), Some('A'), "")
+    );
+    assert_eq!(priority("** [#B]"), (None, Some('B'), ""));
+    assert_eq!(priority("** [#C] Title"), (None, Some('C'), "Title"));
+    assert_eq!(priority("** [#C]Title"), (None, Some('C'), "Title"));
+    assert_eq!(priority("** [#c]Title"), (None, None, "[#c]Title"));
+    assert_eq!(priority("** Title [#A]"), (None, None, "Title [#A]"));
+}
+
+#[test]
+fn parse_title_tags() {
+    use crate::config::DEFAULT_CONFIG;
+
+    let tags = |input| {
+        let (_, (title, raw)) = parse_title(input, &DEFAULT_CONFIG).unwrap();
+        assert_eq!(title.raw, raw);
+        (raw, title.tags)
+    };
+
+    assert_eq!(
+        tags("* Read https://foo:8080/ :web:"),
+        ("Read https://foo:8080/", vec!["web".into()])
+    );
+    assert_eq!(
+        tags("* Read https://foo:8080/"),
+        ("Read https://foo:8080/", vec![])
+    );
+    assert_eq!(
+        tags("* Title  :a:b:   :c:  "),
+        ("Title  :a:b:", vec!["c".into()])
+    );
+    assert_eq!(
+        tags("* Title \t :a:b:"),
+        ("Title", vec!["a".into(), "b".into()])
+    );
+    assert_eq!(tags("* :a:b:"), ("", vec!["a".into(), "b".into()]));
+    assert_eq!(tags("* TODO :a:"), ("", vec!["a".into()]));
+    assert_eq!(tags("* :a:b"), (":a:b", vec![]));
+    assert_eq!(tags("* a::"), ("a::", vec![]));
+}
+
 #[test]
 fn parse_properties_drawer_() {
     assert_eq!(
@@ -0,0 +1,82 @@
+use std::borrow::Cow;
+
+use crate::elements::Element;
+
+/// Export Backend
+///
+/// Identifiers of an export backend, used for routing export snippets like
+/// `@@html:<br>@@` and export blocks like `#+BEGIN_EXPORT html`. Contents
+/// of snippets and blocks for this backend are written as-is, others are
+/// skipped. Identifiers are case-insensitive.
+///
+/// ```rust
+/// use orgize::{export::Backend, Org};
+///
+/// let org = Org::parse("@@html:<br>@@@@latex:\\\\@@@@tex:\\\\@@");
+/// let latex = Backend::latex().with("tex");
+///
+/// let raw: Vec<_> = org
+///     .arena()
+///     .iter()
+///     .filter_map(|node| latex.raw_contents(node.get()))
+///     .collect();
+///
+/// assert_eq!(raw, vec!["\\\\", "\\\\"]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Backend {
+    names: Vec<Cow<'static, str>>,
+}
+
+impl Backend {
+    pub fn new<I, S>(names: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<Cow<'static, str>>,
+    {
+        Backend {
+            names: names.into_iter().map(Into::into).collect(),
+        }
+    }
+
+    /// Html backend, accepts `html`.
+    pub fn html() -> Self {
+        Backend::new(["html"])
+    }
+
+    /// LaTeX backend, accepts `latex` and `beamer`.
+    pub fn latex() -> Self {
+        Backend::new(["latex", "beamer"])
+    }
+
+    /// Markdown backend, accepts `md` and `markdown`.
+    pub fn markdown() -> Self {
+        Backend::new(["md", "markdown"])
+    }
+
+    /// Accepts `name` as well.
+    pub fn with<S: Into<Cow<'static, str>>>(mut self, name: S) -> Self {
+        self.names.push(name.into());
+        self
+    }
+
+    /// Returns the accepted identifiers.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.names.iter().map(|name| &**name)
+    }
+
+    /// Returns `true` if `name` is one of the accepted identifiers.
+    pub fn accepts(&self, name: &str) -> bool {
+        self.names.iter().any(|n| n.eq_ignore_ascii_case(name))
+    }
+
+    /// Returns contents of `element` if it's an export snippet or export
+    /// block for this backend.
+    pub fn raw_contents<'b>(&self, element: &'b Element) -> Option<&'b str> {
+        match element {
+            Element::Snippet(snippet) if self.accepts(&snippet.name) => Some(&snippet.value),
+            Element::ExportBlock(block) if self.accepts(&block.data) => Some(&block.contents),
+            _ => None,
+        }
+    }
+}
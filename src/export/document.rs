@@ -0,0 +1,355 @@
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::io::{Error, Write};
+
+use indextree::NodeId;
+
+use crate::{
+    export::{
+        html::headline_ids, DefaultHtmlHandler, ExportError, HtmlConfig, HtmlEscape, HtmlHandler,
+        Translations,
+    },
+    DocumentDate, DocumentMeta, Org, TocEntry,
+};
+
+/// Options of `Org::write_html_document`
+#[derive(Debug, Clone)]
+pub struct HtmlDocumentConfig {
+    /// Document title, default is `None`, which uses `#+TITLE:`
+    pub title: Option<String>,
+    /// Value of `lang` attribute, default is `None`, which uses
+    /// `#+LANGUAGE:`, or `en` if it's not set
+    pub language: Option<String>,
+    /// Urls of stylesheets linked in `<head>`, default is empty
+    pub stylesheets: Vec<String>,
+    /// Contents of a `<style>` element in `<head>`, written as-is, default is
+    /// `None`
+    pub style: Option<String>,
+    /// Extra contents of `<head>`, written as-is, default is empty
+    pub head: String,
+    /// Id of the `<div>` wrapping contents of `<body>`, default is
+    /// `Some("content")`, `None` writes contents without the wrapper
+    pub container: Option<String>,
+    /// Writes the table of contents before the document, default is `false`
+    ///
+    /// Entries link to ids of headings, which `DefaultHtmlHandler` writes
+    /// with `HtmlConfig::heading_ids`.
+    pub toc: bool,
+    /// Boilerplate strings keyed by the language of the document, see
+    /// `language`, default is `Translations::builtin()`
+    pub translations: HashMap<String, Translations>,
+    /// Format of a `<div id="preamble">` before the contents, default is
+    /// `None`, see `postamble`
+    pub preamble: Option<String>,
+    /// Format of a `<div id="postamble">` after the contents, default is
+    /// `None`
+    ///
+    /// It's written as-is, except for `%a` replaced with `#+AUTHOR:`, `%d`
+    /// with `#+DATE:`, `%c` with `creator`, `%T` with `export_time` and
+    /// `%%` with `%`. Replacements are escaped, and other `%` signs are
+    /// kept, e.g. `<p>100% by %a</p>`.
+    pub postamble: Option<String>,
+    /// Replacement of `%c` in `preamble` and `postamble`, default is
+    /// `orgize` followed by its version
+    pub creator: String,
+    /// Replacement of `%T` in `preamble` and `postamble`, default is `None`,
+    /// which replaces it with nothing
+    ///
+    /// Orgize never reads the clock, callers supply the time in the format
+    /// of their choice.
+    pub export_time: Option<String>,
+}
+
+impl Default for HtmlDocumentConfig {
+    fn default() -> Self {
+        HtmlDocumentConfig {
+            title: None,
+            language: None,
+            stylesheets: Vec::new(),
+            style: None,
+            head: String::new(),
+            container: Some(String::from("content")),
+            toc: false,
+            translations: Translations::builtin(),
+            preamble: None,
+            postamble: None,
+            creator: format!("orgize {}", env!("CARGO_PKG_VERSION")),
+            export_time: None,
+        }
+    }
+}
+
+impl Org<'_> {
+    /// Writes an `Org` struct as a standalone html document.
+    ///
+    /// Title, author and description come from `#+TITLE:`, `#+SUBTITLE:`,
+    /// `#+AUTHOR:` and `#+DESCRIPTION:` keywords, see `Org::metadata`. The
+    /// title is written as the only `<h1>`, headings of headlines start at
+    /// `<h2>`.
+    ///
+    /// ```rust
+    /// use orgize::{export::HtmlDocumentConfig, Org};
+    ///
+    /// let org = Org::parse("#+TITLE: Notes\n#+LANGUAGE: fr\ntext\n");
+    ///
+    /// let mut writer = Vec::new();
+    /// org.write_html_document(&mut writer, &HtmlDocumentConfig::default())
+    ///     .unwrap();
+    /// let html = String::from_utf8(writer).unwrap();
+    ///
+    /// assert!(html.starts_with("<!DOCTYPE html>\n<html lang=\"fr\">"));
+    /// assert!(html.contains("<title>Notes</title>"));
+    /// ```
+    pub fn write_html_document<W>(
+        &self,
+        writer: W,
+        config: &HtmlDocumentConfig,
+    ) -> Result<(), ExportError<Error>>
+    where
+        W: Write,
+    {
+        let has_title = config.title.is_some() || self.metadata().title.is_some();
+        let mut handler = DefaultHtmlHandler::new(HtmlConfig {
+            heading_ids: config.toc,
+            heading_offset: usize::from(has_title),
+            ..Default::default()
+        });
+        self.write_html_document_custom(writer, &mut handler, config)
+    }
+
+    /// Like `write_html_document`, but writes the document with custom
+    /// `HtmlHandler`.
+    ///
+    /// Errors occurred outside of the contents, e.g. in `<head>`, are
+    /// reported on the document element. Use `HtmlConfig::heading_offset`
+    /// and `HtmlConfig::heading_ids` of `DefaultHtmlHandler` to write
+    /// headings like `write_html_document`.
+    pub fn write_html_document_custom<W, H, E>(
+        &self,
+        mut writer: W,
+        handler: &mut H,
+        config: &HtmlDocumentConfig,
+    ) -> Result<(), ExportError<E>>
+    where
+        W: Write,
+        E: From<Error>,
+        H: HtmlHandler<E>,
+    {
+        self.write_document_head(&mut writer, config)
+            .map_err(|err| self.export_error(self.root, E::from(err)))?;
+
+        self.write_html_custom(&mut writer, handler)?;
+
+        self.write_document_end(&mut writer, config)
+            .map_err(|err| self.export_error(self.root, E::from(err)))
+    }
+
+    pub(crate) fn write_document_head<W: Write>(
+        &self,
+        mut writer: W,
+        config: &HtmlDocumentConfig,
+    ) -> Result<(), Error> {
+        let meta = self.metadata();
+        let title = config.title.as_deref().or(meta.title.as_deref());
+        let language = config
+            .language
+            .as_deref()
+            .or(meta.language.as_deref())
+            .unwrap_or("en");
+
+        write!(
+            writer,
+            "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n",
+            HtmlEscape(language)
+        )?;
+        if let Some(title) = title {
+            writeln!(writer, "<title>{}</title>", HtmlEscape(title))?;
+        }
+        if let Some(author) = &meta.author {
+            writeln!(
+                writer,
+                "<meta name=\"author\" content=\"{}\">",
+                HtmlEscape(author)
+            )?;
+        }
+        if let Some(description) = &meta.description {
+            writeln!(
+                writer,
+                "<meta name=\"description\" content=\"{}\">",
+                HtmlEscape(description)
+            )?;
+        }
+        for href in &config.stylesheets {
+            writeln!(
+                writer,
+                "<link rel=\"stylesheet\" href=\"{}\">",
+                HtmlEscape(href)
+            )?;
+        }
+        if let Some(style) = &config.style {
+            writeln!(writer, "<style>{}</style>", style)?;
+        }
+        if !config.head.is_empty() {
+            writeln!(writer, "{}", config.head)?;
+        }
+        writeln!(writer, "</head>\n<body>")?;
+        if let Some(format) = &config.preamble {
+            write_status(&mut writer, "preamble", format, &meta, config)?;
+            writeln!(writer)?;
+        }
+
+        if let Some(id) = &config.container {
+            writeln!(writer, "<div id=\"{}\">", HtmlEscape(id))?;
+        }
+        if let Some(title) = title {
+            write!(writer, "<h1 class=\"title\">{}", HtmlEscape(title))?;
+            if let Some(subtitle) = &meta.subtitle {
+                write!(
+                    writer,
+                    "<br><span class=\"subtitle\">{}</span>",
+                    HtmlEscape(subtitle)
+                )?;
+            }
+            writeln!(writer, "</h1>")?;
+        }
+        if config.toc {
+            let heading = Translations::lookup(&config.translations, language)
+                .map_or("Table of Contents", |strings| &strings.table_of_contents);
+            let ids = headline_ids(self, &self.unexported());
+            write_toc(&mut writer, heading, &self.toc(), &ids)?;
+        }
+
+        Ok(())
+    }
+
+    pub(crate) fn write_document_end<W: Write>(
+        &self,
+        mut writer: W,
+        config: &HtmlDocumentConfig,
+    ) -> Result<(), Error> {
+        if config.container.is_some() {
+            write!(writer, "\n</div>")?;
+        }
+        if let Some(format) = &config.postamble {
+            writeln!(writer)?;
+            write_status(&mut writer, "postamble", format, &self.metadata(), config)?;
+        }
+        writeln!(writer, "\n</body>\n</html>")
+    }
+}
+
+// writes the preamble or postamble `format`, see
+// `HtmlDocumentConfig::postamble`
+fn write_status<W: Write>(
+    mut w: W,
+    id: &str,
+    format: &str,
+    meta: &DocumentMeta,
+    config: &HtmlDocumentConfig,
+) -> Result<(), Error> {
+    write!(w, "<div id=\"{}\" class=\"status\">", id)?;
+    for part in format_parts(format) {
+        let spec = match part {
+            FormatPart::Text(text) => {
+                write!(w, "{}", text)?;
+                continue;
+            }
+            FormatPart::Spec(spec) => spec,
+        };
+        let value = match spec {
+            'a' => meta.author.as_deref().map(Cow::Borrowed),
+            'd' => meta.date.as_ref().map(|date| match date {
+                DocumentDate::Timestamp(timestamp) => Cow::Owned(timestamp.to_string()),
+                DocumentDate::Raw(raw) => Cow::Borrowed(&**raw),
+            }),
+            'c' => Some(Cow::Borrowed(&*config.creator)),
+            'T' => config.export_time.as_deref().map(Cow::Borrowed),
+            _ => {
+                write!(w, "%{}", spec)?;
+                continue;
+            }
+        };
+        if let Some(value) = value {
+            write!(w, "{}", HtmlEscape(&value))?;
+        }
+    }
+    write!(w, "</div>")
+}
+
+// a part of a format with `%` sequences, e.g. `HtmlDocumentConfig::postamble`
+pub(crate) enum FormatPart<'f> {
+    Text(&'f str),
+    // `%` followed by a character other than `%`, as `%%` is text
+    Spec(char),
+}
+
+// splits `format` into text and `%` sequences
+pub(crate) fn format_parts(format: &str) -> Vec<FormatPart<'_>> {
+    let mut parts = Vec::new();
+    let mut chars = format.char_indices().peekable();
+    let mut start = 0;
+    while let Some((i, c)) = chars.next() {
+        let spec = match chars.peek() {
+            Some(&(_, spec)) if c == '%' => spec,
+            _ => continue,
+        };
+        parts.push(FormatPart::Text(&format[start..i]));
+        parts.push(match spec {
+            '%' => FormatPart::Text("%"),
+            _ => FormatPart::Spec(spec),
+        });
+        chars.next();
+        start = i + 1 + spec.len_utf8();
+    }
+    parts.push(FormatPart::Text(&format[start..]));
+    parts
+}
+
+fn write_toc<W: Write>(
+    mut w: W,
+    heading: &str,
+    toc: &[TocEntry],
+    ids: &HashMap<NodeId, String>,
+) -> Result<(), Error> {
+    if toc.is_empty() {
+        return Ok(());
+    }
+
+    write!(
+        w,
+        "<nav id=\"table-of-contents\"><h2>{}</h2>",
+        HtmlEscape(heading)
+    )?;
+
+    // levels of unclosed lists
+    let mut levels: Vec<usize> = Vec::new();
+    for entry in toc {
+        while levels.last().is_some_and(|&level| level > entry.level) {
+            write!(w, "</li></ul>")?;
+            levels.pop();
+        }
+        if levels.last() == Some(&entry.level) {
+            write!(w, "</li>")?;
+        } else {
+            write!(w, "<ul>")?;
+            levels.push(entry.level);
+        }
+        write!(w, "<li>")?;
+        let id = ids.get(&entry.headline.headline_node());
+        if let Some(id) = id {
+            write!(w, "<a href=\"#{}\">", HtmlEscape(id))?;
+        }
+        if let Some(number) = entry.number_string() {
+            write!(w, "{} ", number)?;
+        }
+        write!(w, "{}", HtmlEscape(&entry.title))?;
+        if id.is_some() {
+            write!(w, "</a>")?;
+        }
+    }
+    for _ in levels {
+        write!(w, "</li></ul>")?;
+    }
+
+    writeln!(w, "</nav>")
+}
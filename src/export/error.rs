@@ -0,0 +1,52 @@
+use std::error::Error;
+use std::fmt;
+use std::io;
+use std::ops::Range;
+
+use indextree::NodeId;
+
+use crate::elements::ElementKind;
+
+/// Error returned by exporters, the error of the writer or handler along
+/// with the element being written
+#[derive(Debug)]
+pub struct ExportError<E> {
+    /// Kind of the element, which `Display` writes by name
+    pub element: ElementKind,
+    /// Node of the element
+    pub node: NodeId,
+    /// Span of the element in the source text, see `Org::span`
+    pub span: Option<Range<usize>>,
+    /// Error returned by the writer or handler
+    pub error: E,
+}
+
+impl<E> ExportError<E> {
+    /// Returns the error of the writer or handler, dropping the context.
+    pub fn into_inner(self) -> E {
+        self.error
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for ExportError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "failed to export {}", self.element)?;
+        if let Some(span) = &self.span {
+            write!(f, " at {}..{}", span.start, span.end)?;
+        }
+        write!(f, ": {}", self.error)
+    }
+}
+
+impl<E: Error + 'static> Error for ExportError<E> {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.error)
+    }
+}
+
+impl From<ExportError<io::Error>> for io::Error {
+    fn from(err: ExportError<io::Error>) -> Self {
+        let message = err.to_string();
+        io::Error::new(err.error.kind(), message)
+    }
+}
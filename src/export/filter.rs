@@ -0,0 +1,190 @@
+use std::collections::HashMap;
+use std::io::{Error, Write};
+
+use indextree::NodeId;
+
+use crate::elements::{Element, ElementKind};
+use crate::export::{HtmlHandler, OrgHandler};
+use crate::Org;
+
+type ElementFilter = Box<dyn Fn(&Element, String) -> String>;
+type OutputFilter = Box<dyn Fn(String) -> String>;
+
+/// Filter Handler
+///
+/// Wraps an html or org handler and post-processes its output. Element
+/// filters receive an element and its rendering by the inner handler,
+/// including the rendering of its children, and return the new rendering.
+/// The output filter receives the whole document.
+///
+/// Elements are matched by `Element::name`, filters of nested elements are
+/// applied from the inside out.
+///
+/// ```rust
+/// use orgize::{
+///     export::{DefaultHtmlHandler, FilterHandler},
+///     Org,
+/// };
+///
+/// let mut handler = FilterHandler::new(DefaultHtmlHandler::default())
+///     .filter("link", |_, html| html.replacen("<a ", "<a target=\"_blank\" ", 1))
+///     .output_filter(|html| html.replace("<main>", "<main id=\"content\">"));
+///
+/// let mut writer = Vec::new();
+/// Org::parse("[[https://example.com][example]]")
+///     .write_html_custom(&mut writer, &mut handler)
+///     .unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(writer).unwrap(),
+///     "<main id=\"content\"><section><p><a target=\"_blank\" href=\"https://example.com\">example</a></p></section></main>"
+/// );
+/// ```
+pub struct FilterHandler<H> {
+    /// inner handler
+    pub inner: H,
+    filters: HashMap<&'static str, Vec<ElementFilter>>,
+    output_filter: Option<OutputFilter>,
+    // outputs of elements being filtered, innermost last
+    buffers: Vec<Vec<u8>>,
+}
+
+impl<H> FilterHandler<H> {
+    pub fn new(inner: H) -> Self {
+        FilterHandler {
+            inner,
+            filters: HashMap::new(),
+            output_filter: None,
+            buffers: Vec::new(),
+        }
+    }
+
+    /// Adds a filter for elements named `name`, e.g. `"src-block"`, or any
+    /// other name of their kind `ElementKind` parses, e.g. `"source-block"`.
+    ///
+    /// Filters of the same element are applied in the order they are added.
+    pub fn filter<F>(mut self, name: &'static str, filter: F) -> Self
+    where
+        F: Fn(&Element, String) -> String + 'static,
+    {
+        let name = name.parse().map_or(name, ElementKind::name);
+        self.filters.entry(name).or_default().push(Box::new(filter));
+        self
+    }
+
+    /// Sets the filter applied to the whole output, after element filters.
+    pub fn output_filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(String) -> String + 'static,
+    {
+        self.output_filter = Some(Box::new(filter));
+        self
+    }
+
+    fn is_filtered(&self, element: &Element) -> bool {
+        self.filters.contains_key(element.name())
+    }
+
+    fn is_output(&self, element: &Element) -> bool {
+        self.output_filter.is_some() && matches!(element, Element::Document { .. })
+    }
+
+    fn push_buffers(&mut self, element: &Element) {
+        if self.is_output(element) {
+            self.buffers.push(Vec::new());
+        }
+        if self.is_filtered(element) {
+            self.buffers.push(Vec::new());
+        }
+    }
+
+    fn pop_buffers<W: Write>(&mut self, mut w: W, element: &Element) -> Result<(), Error> {
+        if let Some(filters) = self.filters.get(element.name()) {
+            let buffer = self.buffers.pop().unwrap_or_default();
+            let output = filters.iter().fold(
+                String::from_utf8_lossy(&buffer).into_owned(),
+                |output, filter| filter(element, output),
+            );
+            match self.buffers.last_mut() {
+                Some(buffer) => buffer.write_all(output.as_bytes())?,
+                None => w.write_all(output.as_bytes())?,
+            }
+        }
+        if let (Some(filter), true) = (&self.output_filter, self.is_output(element)) {
+            let buffer = self.buffers.pop().unwrap_or_default();
+            let output = filter(String::from_utf8_lossy(&buffer).into_owned());
+            match self.buffers.last_mut() {
+                Some(buffer) => buffer.write_all(output.as_bytes())?,
+                None => w.write_all(output.as_bytes())?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<H: Default> Default for FilterHandler<H> {
+    fn default() -> Self {
+        FilterHandler::new(H::default())
+    }
+}
+
+macro_rules! impl_handler {
+    ($handler:ident { $($methods:tt)* }) => {
+        impl<E: From<Error>, H: $handler<E>> $handler<E> for FilterHandler<H> {
+            fn prepare(&mut self, org: &Org) {
+                self.inner.prepare(org);
+            }
+
+            fn start<W: Write>(&mut self, w: W, element: &Element) -> Result<(), E> {
+                self.push_buffers(element);
+                match self.buffers.last_mut() {
+                    Some(buffer) => self.inner.start(buffer, element),
+                    None => self.inner.start(w, element),
+                }
+            }
+
+            fn end<W: Write>(&mut self, mut w: W, element: &Element) -> Result<(), E> {
+                match self.buffers.last_mut() {
+                    Some(buffer) => self.inner.end(buffer, element)?,
+                    None => self.inner.end(&mut w, element)?,
+                }
+                Ok(self.pop_buffers(w, element)?)
+            }
+
+            fn start_node<W: Write>(
+                &mut self,
+                w: W,
+                node: NodeId,
+                element: &Element,
+            ) -> Result<(), E> {
+                self.push_buffers(element);
+                match self.buffers.last_mut() {
+                    Some(buffer) => self.inner.start_node(buffer, node, element),
+                    None => self.inner.start_node(w, node, element),
+                }
+            }
+
+            fn end_node<W: Write>(
+                &mut self,
+                mut w: W,
+                node: NodeId,
+                element: &Element,
+            ) -> Result<(), E> {
+                match self.buffers.last_mut() {
+                    Some(buffer) => self.inner.end_node(buffer, node, element)?,
+                    None => self.inner.end_node(&mut w, node, element)?,
+                }
+                Ok(self.pop_buffers(w, element)?)
+            }
+
+            $($methods)*
+        }
+    };
+}
+
+impl_handler!(HtmlHandler {
+    fn trailing_nodes(&mut self, org: &Org, root: NodeId) -> Vec<NodeId> {
+        self.inner.trailing_nodes(org, root)
+    }
+});
+impl_handler!(OrgHandler {});
@@ -0,0 +1,468 @@
+use std::io::{Error, Result as IOResult, Write};
+
+use indextree::NodeId;
+
+use crate::elements::{Element, Title};
+use crate::export::org::{DefaultOrgHandler, OrgHandler};
+use crate::Org;
+
+/// Format configuration
+#[derive(Clone, Debug)]
+pub struct FormatConfig {
+    /// Numbers of blank lines between top-level elements and before
+    /// headlines, default is 1
+    ///
+    /// Blank lines after keywords are kept as-is, so that affiliated
+    /// keywords like `#+NAME:` stay attached to the following element.
+    pub blank_lines: usize,
+    /// Column of headline tags, default is -77
+    ///
+    /// Like `org-tags-column`, a positive value aligns tags to the left at
+    /// that column, a negative value aligns tags to the right so that they
+    /// end at that column.
+    pub tags_column: isize,
+    /// Numbers of whitespaces per list level, default is 2
+    pub list_indent: usize,
+    /// Realigns org tables, default is `true`
+    pub align_tables: bool,
+}
+
+impl Default for FormatConfig {
+    fn default() -> Self {
+        FormatConfig {
+            blank_lines: 1,
+            tags_column: -77,
+            list_indent: 2,
+            align_tables: true,
+        }
+    }
+}
+
+/// Org Handler which normalizes the document
+///
+/// + single space after headline stars, keyword and priority
+/// + planning and property drawer right after the headline, indented to the
+///   headline's body column
+/// + list items indented by `FormatConfig::list_indent` per level, with
+///   continuation lines aligned to the item's contents
+/// + `FormatConfig::blank_lines` blank lines between top-level elements
+/// + tags aligned to `FormatConfig::tags_column`
+/// + table columns aligned if `FormatConfig::align_tables` is set
+/// + no trailing whitespace
+///
+/// Contents of source, example, export, comment and verse blocks are written
+/// untouched.
+pub struct FormatOrgHandler {
+    config: FormatConfig,
+    inner: DefaultOrgHandler,
+    state: LineState,
+    scopes: Vec<Scope>,
+    // nothing has been written since the last title
+    after_title: bool,
+}
+
+impl FormatOrgHandler {
+    pub fn new(config: FormatConfig) -> Self {
+        let mut inner = DefaultOrgHandler::default();
+        inner.align_tables = config.align_tables;
+        FormatOrgHandler {
+            config,
+            inner,
+            state: LineState::default(),
+            scopes: Vec::new(),
+            after_title: false,
+        }
+    }
+}
+
+impl Default for FormatOrgHandler {
+    fn default() -> Self {
+        FormatOrgHandler::new(FormatConfig::default())
+    }
+}
+
+enum ScopeKind {
+    Section { first: bool, after_keyword: bool },
+    List { column: usize },
+    ListItem { column: usize },
+    Other,
+}
+
+struct Scope {
+    kind: ScopeKind,
+    // indentation to restore when this scope ends
+    indent: usize,
+}
+
+impl OrgHandler<Error> for FormatOrgHandler {
+    fn prepare(&mut self, org: &Org) {
+        self.inner.prepare(org);
+    }
+
+    fn start<W: Write>(&mut self, w: W, element: &Element) -> IOResult<()> {
+        self.write_start(w, None, element)
+    }
+
+    fn end<W: Write>(&mut self, w: W, element: &Element) -> IOResult<()> {
+        self.write_end(w, element)
+    }
+
+    fn start_node<W: Write>(&mut self, w: W, node: NodeId, element: &Element) -> IOResult<()> {
+        self.write_start(w, Some(node), element)
+    }
+}
+
+impl FormatOrgHandler {
+    fn write_start<W: Write>(
+        &mut self,
+        w: W,
+        node: Option<NodeId>,
+        element: &Element,
+    ) -> IOResult<()> {
+        let mut w = LineWriter {
+            inner: w,
+            state: &mut self.state,
+        };
+
+        if let Some(Scope {
+            kind:
+                ScopeKind::Section {
+                    first,
+                    after_keyword,
+                },
+            ..
+        }) = self.scopes.last_mut()
+        {
+            if *first {
+                w.state.blank_lines(0);
+            } else if !*after_keyword {
+                w.state.blank_lines(self.config.blank_lines);
+            }
+            *first = false;
+            *after_keyword = matches!(element, Element::Keyword(_));
+        }
+
+        // keeps a blank line if the first line of section would be parsed
+        // as planning or property drawer otherwise
+        if std::mem::take(&mut self.after_title) {
+            match element {
+                Element::Section | Element::Paragraph { .. } => self.after_title = true,
+                Element::Text { value } if is_planning(value) => w.state.blank_lines(1),
+                Element::Drawer(drawer) if drawer.name.eq_ignore_ascii_case("PROPERTIES") => {
+                    w.state.blank_lines(1)
+                }
+                _ => (),
+            }
+        }
+
+        let indent = w.state.indent;
+        let kind = match element {
+            Element::Section => ScopeKind::Section {
+                first: true,
+                after_keyword: false,
+            },
+            Element::List(_) => {
+                let list_indent = self.config.list_indent;
+                let column = self
+                    .scopes
+                    .iter()
+                    .rev()
+                    .find_map(|scope| match scope.kind {
+                        ScopeKind::ListItem { column } => Some(column + list_indent),
+                        _ => None,
+                    })
+                    .unwrap_or(indent);
+                ScopeKind::List { column }
+            }
+            Element::ListItem(_) => {
+                let column = match self.scopes.last() {
+                    Some(Scope {
+                        kind: ScopeKind::List { column },
+                        ..
+                    }) => *column,
+                    _ => indent,
+                };
+                ScopeKind::ListItem { column }
+            }
+            _ => ScopeKind::Other,
+        };
+
+        match element {
+            Element::Headline { .. } => {
+                w.state.blank_lines(self.config.blank_lines);
+                w.state.indent = 0;
+            }
+            Element::ListItem(item) => {
+                if let ScopeKind::ListItem { column } = kind {
+                    w.state.indent = column;
+                    self.inner.write_start(&mut w, node, element)?;
+                    w.state.indent = column + item.bullet.chars().count();
+                }
+            }
+            Element::VerseBlock(_) => {
+                self.inner.write_start(&mut w, node, element)?;
+                w.state.raw = true;
+            }
+            Element::Title(title) => {
+                // source spacing of the headline line is never kept
+                let title = Title {
+                    raw_line: None,
+                    ..title.clone()
+                };
+                self.inner
+                    .write_start(&mut w, node, &Element::Title(title))?;
+            }
+            Element::SourceBlock(block) => {
+                write!(w, "#+BEGIN_SRC")?;
+                for part in [&block.language, &block.switches, &block.arguments] {
+                    if !part.is_empty() {
+                        write!(w, " {}", part)?;
+                    }
+                }
+                writeln!(w)?;
+                write_raw(&mut w, &block.contents)?;
+                writeln!(w, "#+END_SRC")?;
+                write_blank_lines(&mut w, block.post_blank)?;
+            }
+            Element::ExampleBlock(block) => {
+                write!(w, "#+BEGIN_EXAMPLE")?;
+                if let Some(data) = &block.data {
+                    write!(w, " {}", data)?;
+                }
+                writeln!(w)?;
+                write_raw(&mut w, &block.contents)?;
+                writeln!(w, "#+END_EXAMPLE")?;
+                write_blank_lines(&mut w, block.post_blank)?;
+            }
+            Element::CommentBlock(block) => {
+                write!(w, "#+BEGIN_COMMENT")?;
+                if let Some(data) = &block.data {
+                    write!(w, "{}", data)?;
+                }
+                writeln!(w)?;
+                write_raw(&mut w, &block.contents)?;
+                writeln!(w, "#+END_COMMENT")?;
+                write_blank_lines(&mut w, block.post_blank)?;
+            }
+            Element::ExportBlock(block) => {
+                writeln!(w, "#+BEGIN_EXPORT {}", block.data.trim())?;
+                write_raw(&mut w, &block.contents)?;
+                writeln!(w, "#+END_EXPORT")?;
+                write_blank_lines(&mut w, block.post_blank)?;
+            }
+            _ => self.inner.write_start(&mut w, node, element)?,
+        }
+
+        self.scopes.push(Scope { kind, indent });
+
+        Ok(())
+    }
+
+    fn write_end<W: Write>(&mut self, w: W, element: &Element) -> IOResult<()> {
+        let mut w = LineWriter {
+            inner: w,
+            state: &mut self.state,
+        };
+
+        match element {
+            Element::Document { .. } => w.state.finish(&mut w.inner)?,
+            Element::VerseBlock(_) => {
+                w.state.raw = false;
+                self.inner.end(&mut w, element)?;
+            }
+            Element::Title(title) => {
+                write_title_end(&self.config, &self.inner, &mut w, title)?;
+                self.after_title = true;
+            }
+            _ => self.inner.end(&mut w, element)?,
+        }
+
+        if let Some(scope) = self.scopes.pop() {
+            w.state.indent = scope.indent;
+        }
+
+        Ok(())
+    }
+}
+
+fn write_title_end<W: Write>(
+    config: &FormatConfig,
+    handler: &DefaultOrgHandler,
+    w: &mut LineWriter<W>,
+    title: &Title,
+) -> IOResult<()> {
+    if !title.tags.is_empty() {
+        let tags_width = title
+            .tags
+            .iter()
+            .map(|tag| tag.chars().count() + 1)
+            .sum::<usize>()
+            + 1;
+        let column = if config.tags_column < 0 {
+            config.tags_column.unsigned_abs().saturating_sub(tags_width)
+        } else {
+            config.tags_column as usize
+        };
+        let column = column.max(w.state.column + 1);
+        w.state.spaces = column - w.state.column;
+        write!(w, ":")?;
+        for tag in &title.tags {
+            write!(w, "{}:", tag)?;
+        }
+    }
+    writeln!(w)?;
+
+    w.state.indent = handler.stars(title.level) + 1;
+    if let Some(planning) = &title.planning {
+        handler.write_planning(&mut *w, planning)?;
+    }
+    if !title.properties.is_empty() {
+        writeln!(w, ":PROPERTIES:")?;
+        for (key, value) in title.properties.iter() {
+            writeln!(w, ":{}: {}", key, value)?;
+        }
+        writeln!(w, ":END:")?;
+    }
+    w.state.indent = 0;
+
+    Ok(())
+}
+
+fn is_planning(text: &str) -> bool {
+    let text = text.trim_start();
+    ["SCHEDULED:", "DEADLINE:", "CLOSED:"]
+        .iter()
+        .any(|keyword| text.starts_with(keyword))
+}
+
+fn write_raw<W: Write>(w: &mut LineWriter<W>, contents: &str) -> IOResult<()> {
+    w.state.raw = true;
+    write!(w, "{}", contents)?;
+    if !contents.is_empty() && !contents.ends_with('\n') {
+        writeln!(w)?;
+    }
+    w.state.raw = false;
+    Ok(())
+}
+
+fn write_blank_lines<W: Write>(mut w: W, count: usize) -> IOResult<()> {
+    for _ in 0..count {
+        writeln!(w)?;
+    }
+    Ok(())
+}
+
+/// Tracks the line being written
+///
+/// Whitespaces and newlines are buffered until the next visible character,
+/// so trailing whitespaces can be dropped and blank lines can be adjusted.
+#[derive(Default)]
+struct LineState {
+    // indentation of each new line
+    indent: usize,
+    // width of the current line
+    column: usize,
+    // whitespaces written since the last visible character
+    spaces: usize,
+    // newlines written since the last visible character
+    newlines: usize,
+    at_line_start: bool,
+    written: bool,
+    // writes everything as-is
+    raw: bool,
+}
+
+impl LineState {
+    fn blank_lines(&mut self, count: usize) {
+        if self.written {
+            self.newlines = count + 1;
+        }
+    }
+
+    fn finish<W: Write>(&mut self, mut w: W) -> IOResult<()> {
+        if self.written {
+            writeln!(w)?;
+        }
+        *self = LineState::default();
+        Ok(())
+    }
+
+    fn write_byte<W: Write>(&mut self, mut w: W, byte: u8) -> IOResult<()> {
+        if self.raw {
+            if self.at_line_start || !self.written {
+                self.write_newlines(&mut w)?;
+            }
+            w.write_all(&[byte])?;
+            self.written = true;
+            if byte == b'\n' {
+                self.at_line_start = true;
+                self.column = 0;
+            } else {
+                self.at_line_start = false;
+                self.column += is_char_boundary(byte) as usize;
+            }
+            self.spaces = 0;
+            return Ok(());
+        }
+
+        match byte {
+            b'\n' => {
+                self.spaces = 0;
+                self.newlines += 1;
+                self.column = 0;
+                self.at_line_start = true;
+            }
+            b' ' | b'\t' if self.at_line_start || !self.written => (),
+            b' ' | b'\t' => self.spaces += 1,
+            _ => {
+                if self.at_line_start || !self.written {
+                    self.write_newlines(&mut w)?;
+                    self.spaces = self.indent;
+                    self.column = 0;
+                }
+                for _ in 0..self.spaces {
+                    w.write_all(b" ")?;
+                }
+                w.write_all(&[byte])?;
+                self.column += self.spaces + is_char_boundary(byte) as usize;
+                self.spaces = 0;
+                self.at_line_start = false;
+                self.written = true;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_newlines<W: Write>(&mut self, mut w: W) -> IOResult<()> {
+        if self.written {
+            for _ in 0..self.newlines {
+                w.write_all(b"\n")?;
+            }
+        }
+        self.newlines = 0;
+        Ok(())
+    }
+}
+
+fn is_char_boundary(byte: u8) -> bool {
+    byte & 0xC0 != 0x80
+}
+
+struct LineWriter<'s, W: Write> {
+    inner: W,
+    state: &'s mut LineState,
+}
+
+impl<W: Write> Write for LineWriter<'_, W> {
+    fn write(&mut self, buf: &[u8]) -> IOResult<usize> {
+        for &byte in buf {
+            self.state.write_byte(&mut self.inner, byte)?;
+        }
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> IOResult<()> {
+        self.inner.flush()
+    }
+}
@@ -1,9 +1,19 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::fmt;
 use std::io::{Error, Result as IOResult, Write};
+use std::sync::Arc;
 
+use indextree::NodeId;
 use jetscii::{bytes, BytesConst};
 
-use crate::elements::{Element, Table, TableCell, TableRow, Timestamp};
+use crate::config::keyword_name;
+use crate::elements::{
+    link::has_scheme, Drawer, Element, Link, ObjectContext, Objects, Planning, PropertiesMap,
+    Table, TableCell, TableRow, Timestamp, Title,
+};
+use crate::export::{Backend, Translations};
+use crate::{Event, Headline, Org};
 
 /// A wrapper for escaping sensitive characters in html.
 ///
@@ -48,27 +58,1399 @@ impl<S: AsRef<str>> fmt::Display for HtmlEscape<S> {
     }
 }
 
+// names used by highlight.js and prism for language aliases of org
+fn highlight_language(language: &str) -> &str {
+    match language {
+        "elisp" | "emacs-lisp" => "lisp",
+        "sh" | "shell" => "bash",
+        "C" => "c",
+        "C++" | "cpp" => "cpp",
+        "js" => "javascript",
+        "R" => "r",
+        "sqlite" => "sql",
+        "ipython" | "jupyter-python" => "python",
+        _ => language,
+    }
+}
+
+// removes the leading whitespaces shared by all non-blank lines
+fn strip_indentation(contents: &str) -> Cow<'_, str> {
+    let indent = contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| &line[0..line.len() - line.trim_start().len()])
+        .fold(None, |common: Option<&str>, indent| match common {
+            Some(common) => {
+                let len = common
+                    .char_indices()
+                    .zip(indent.chars())
+                    .find(|((_, a), b)| a != b)
+                    .map(|((i, _), _)| i)
+                    .unwrap_or_else(|| common.len().min(indent.len()));
+                Some(&common[0..len])
+            }
+            None => Some(indent),
+        })
+        .unwrap_or_default();
+
+    if indent.is_empty() {
+        return Cow::Borrowed(contents);
+    }
+
+    let mut stripped = String::with_capacity(contents.len());
+    for line in contents.split_inclusive('\n') {
+        if let Some(line) = line.strip_prefix(indent) {
+            stripped.push_str(line);
+        } else {
+            // blank lines
+            stripped.push_str(line.trim_start_matches([' ', '\t']));
+        }
+    }
+    Cow::Owned(stripped)
+}
+
+// `:key value` pairs of `#+ATTR_HTML:`, e.g. `:cite https://orgmode.org :class quote`
+fn parse_attributes(value: &str) -> Vec<(String, String)> {
+    let mut attributes: Vec<(String, String)> = Vec::new();
+    for token in value.split_whitespace() {
+        match token.strip_prefix(':') {
+            Some(key) if !key.is_empty() => attributes.push((key.to_string(), String::new())),
+            _ => {
+                if let Some((_, value)) = attributes.last_mut() {
+                    if !value.is_empty() {
+                        value.push(' ');
+                    }
+                    value.push_str(token);
+                }
+            }
+        }
+    }
+    for (_, value) in &mut attributes {
+        if value.len() >= 2 && value.starts_with('"') && value.ends_with('"') {
+            *value = value[1..value.len() - 1].to_string();
+        }
+    }
+    attributes
+}
+
+// `class` and `style` of `attributes` are appended to the ones of `base`,
+// others replace them
+fn write_attributes<W: Write>(
+    mut w: W,
+    mut base: Vec<(String, String)>,
+    attributes: Vec<(String, String)>,
+) -> IOResult<()> {
+    for (key, value) in attributes {
+        match base.iter_mut().find(|(k, _)| *k == key) {
+            Some((_, existing)) if key == "class" => {
+                existing.push(' ');
+                existing.push_str(&value);
+            }
+            Some((_, existing)) if key == "style" => {
+                existing.push(';');
+                existing.push_str(&value);
+            }
+            Some((_, existing)) => *existing = value,
+            None => base.push((key, value)),
+        }
+    }
+    for (key, value) in base {
+        write!(w, " {}=\"{}\"", HtmlEscape(key), HtmlEscape(value))?;
+    }
+    Ok(())
+}
+
 pub trait HtmlHandler<E: From<Error>>: Default {
+    /// Called with the whole document before writing it, e.g. for resolving
+    /// links. Does nothing by default.
+    fn prepare(&mut self, _org: &Org) {}
     fn start<W: Write>(&mut self, w: W, element: &Element) -> Result<(), E>;
     fn end<W: Write>(&mut self, w: W, element: &Element) -> Result<(), E>;
+    /// Like `start`, called with the node of `element` when writing a
+    /// document, so that state computed in `prepare` can be looked up by
+    /// node. Calls `start` by default, handlers wrapping another one should
+    /// forward it.
+    fn start_node<W: Write>(&mut self, w: W, _node: NodeId, element: &Element) -> Result<(), E> {
+        self.start(w, element)
+    }
+    /// Like `end`, see `start_node`.
+    fn end_node<W: Write>(&mut self, w: W, _node: NodeId, element: &Element) -> Result<(), E> {
+        self.end(w, element)
+    }
+    /// Returns nodes written after the contents of `root` and before its
+    /// end, e.g. footnote definitions, which aren't written where they're
+    /// defined. They're written with `start_node` and `end_node` like other
+    /// nodes, then it's called again until it returns nothing. Returns
+    /// nothing by default, handlers wrapping another one should forward it.
+    fn trailing_nodes(&mut self, _org: &Org, _root: NodeId) -> Vec<NodeId> {
+        Vec::new()
+    }
+}
+
+/// Options of `DefaultHtmlHandler`
+#[derive(Debug, Clone)]
+pub struct HtmlConfig {
+    /// Renders property drawers of headlines as `<table class="properties">`,
+    /// default is `false`
+    pub properties: bool,
+    /// Renders `LOGBOOK` drawers and clock lines, default is `false`
+    pub logbook: bool,
+    /// Renders contents of other drawers inline, default is `true`
+    pub drawers: bool,
+    /// Renders planning lines of headlines, default is `true`
+    pub planning: bool,
+    /// Export snippets and blocks written as-is, default is
+    /// `Backend::html()`
+    pub backend: Backend,
+    /// Prefix of the class of `<code>` in source blocks and inline source,
+    /// default is `"language-"`, which is understood by highlight.js and
+    /// prism. Use `"src-"` for the class names of Emacs.
+    pub src_class_prefix: String,
+    /// Renders center blocks with `style="text-align:center"` instead of
+    /// `class="org-center"`, default is `false`
+    pub center_style: bool,
+    /// Rewriting of link destinations, e.g. for publishing, default is
+    /// `LinkConfig::default()`, which writes them as-is
+    pub links: LinkConfig,
+    /// Embedding of image links, default is `ImageConfig::default()`, which
+    /// writes them as links
+    pub images: ImageConfig,
+    /// Renders todo keywords of headlines as `<span class="todo TODO">`, or
+    /// `<span class="done DONE">` for done keywords, default is `false`
+    ///
+    /// Overridden by `todo:` of `#+OPTIONS:` keywords in the document.
+    pub todo_keywords: bool,
+    /// Renders priorities of headlines as `<span class="priority">`, default
+    /// is `false`
+    ///
+    /// Overridden by `pri:` of `#+OPTIONS:` keywords in the document.
+    pub priorities: bool,
+    /// Renders tags of headlines as `<span class="tag">`, with a nested
+    /// `<span>` for each tag, default is `false`
+    ///
+    /// Overridden by `tags:` of `#+OPTIONS:` keywords in the document.
+    pub tags: bool,
+    /// Converts special strings in text, i.e. `\-`, `--`, `---` and `...`,
+    /// into a soft hyphen, an en dash, an em dash and an ellipsis, default
+    /// is `false`
+    ///
+    /// Overridden by `-:` of `#+OPTIONS:` keywords in the document.
+    pub special_strings: bool,
+    /// How characters from special strings are written, default is
+    /// `EntityOutput::Utf8`
+    pub entity_output: EntityOutput,
+    /// Order of footnote definitions, which are written at the end of the
+    /// document, default is `FootnoteOrder::Reference`
+    pub footnote_order: FootnoteOrder,
+    /// Writes footnote definitions which are never referenced as well,
+    /// after the referenced ones in document order, default is `false`
+    pub unreferenced_footnotes: bool,
+    /// Boilerplate strings keyed by the language of the document, i.e.
+    /// `#+LANGUAGE:`, default is `Translations::builtin()`
+    ///
+    /// Documents in other languages use english strings.
+    pub translations: HashMap<String, Translations>,
+    /// Writes the containers and ids of the Emacs exporter, so that themes
+    /// written for it apply, default is `false`
+    ///
+    /// The document is wrapped in `<div id="content" class="content">`
+    /// instead of `<main>`, each headline in `<div id="outline-container-ID"
+    /// class="outline-N">` and the section of each headline in `<div
+    /// class="outline-text-N" id="text-ID">`, where `N` is the level of its
+    /// heading, i.e. `<hN id="ID">`, one more than the level of the
+    /// headline. `ID` is the `CUSTOM_ID` property of the headline, or else
+    /// made from its title like `slug`, and links to the headline point to
+    /// it. Sections before the first headline aren't wrapped.
+    pub ox_html_structure: bool,
+    /// Writes ids on all headings, not only on the ones linked to, default
+    /// is `false`
+    ///
+    /// Ids are made like the ones of `ox_html_structure`.
+    pub heading_ids: bool,
+    /// Added to levels of headlines to get levels of their headings, default
+    /// is `0`, which writes top-level headlines as `<h1>`
+    pub heading_offset: usize,
+}
+
+/// How `DefaultHtmlHandler` writes special characters, see
+/// `HtmlConfig::entity_output`
+///
+/// ```rust
+/// use orgize::export::{DefaultHtmlHandler, EntityOutput, HtmlConfig};
+/// use orgize::Org;
+///
+/// let org = Org::parse("#+OPTIONS: -:t\nWait... 1--2\n");
+/// let html = |entity_output| {
+///     let mut handler = DefaultHtmlHandler::new(HtmlConfig {
+///         entity_output,
+///         ..Default::default()
+///     });
+///     let mut writer = Vec::new();
+///     org.write_html_custom(&mut writer, &mut handler).unwrap();
+///     String::from_utf8(writer).unwrap()
+/// };
+///
+/// assert!(html(EntityOutput::Utf8).contains("Wait\u{2026} 1\u{2013}2"));
+/// assert!(html(EntityOutput::Named).contains("Wait&hellip; 1&ndash;2"));
+/// assert!(html(EntityOutput::Numeric).contains("Wait&#x2026; 1&#x2013;2"));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum EntityOutput {
+    /// Characters as-is, e.g. `—`
+    #[default]
+    Utf8,
+    /// Named entities, e.g. `&mdash;`
+    Named,
+    /// Numeric character references, e.g. `&#x2014;`
+    Numeric,
+}
+
+impl EntityOutput {
+    fn write(self, f: &mut fmt::Formatter, c: char, name: &str) -> fmt::Result {
+        match self {
+            EntityOutput::Utf8 => write!(f, "{}", c),
+            EntityOutput::Named => write!(f, "&{};", name),
+            EntityOutput::Numeric => write!(f, "&#x{:x};", c as u32),
+        }
+    }
+}
+
+/// Order of footnote definitions written by `DefaultHtmlHandler`, see
+/// `HtmlConfig::footnote_order`
+///
+/// Definitions are collected from the whole document, including the
+/// `* Footnotes` headline, which isn't written itself, and written in a
+/// `<div id="footnotes">` at the end of the document. Only definitions of
+/// footnotes referenced in the output are written, unless
+/// `HtmlConfig::unreferenced_footnotes` is set.
+///
+/// ```rust
+/// use orgize::export::{DefaultHtmlHandler, FootnoteOrder, HtmlConfig};
+/// use orgize::Org;
+///
+/// let org = Org::parse("a[fn:2] b[fn:1]\n\n[fn:1] One.\n\n[fn:2] Two.\n");
+/// let footnotes = |footnote_order| {
+///     let mut handler = DefaultHtmlHandler::new(HtmlConfig {
+///         footnote_order,
+///         ..Default::default()
+///     });
+///     let mut writer = Vec::new();
+///     org.write_html_custom(&mut writer, &mut handler).unwrap();
+///     let html = String::from_utf8(writer).unwrap();
+///     let section = &html[html.find("<div id=\"footnotes\">").unwrap()..];
+///     section.find("One.").unwrap() < section.find("Two.").unwrap()
+/// };
+///
+/// assert!(!footnotes(FootnoteOrder::Reference));
+/// assert!(footnotes(FootnoteOrder::Document));
+/// ```
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FootnoteOrder {
+    /// Order of the first reference to each footnote
+    #[default]
+    Reference,
+    /// Order of definitions in the document
+    Document,
+}
+
+// escapes `text` like `HtmlEscape`, and converts special strings in it
+struct SpecialStrings<'a>(&'a str, EntityOutput);
+
+impl fmt::Display for SpecialStrings<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        // special string, its character and the name of its entity
+        const STRINGS: [(&str, char, &str); 4] = [
+            ("\\-", '\u{ad}', "shy"),
+            ("---", '\u{2014}', "mdash"),
+            ("--", '\u{2013}', "ndash"),
+            ("...", '\u{2026}', "hellip"),
+        ];
+
+        let SpecialStrings(text, output) = *self;
+        let mut start = 0;
+        let mut pos = 0;
+        while pos < text.len() {
+            let rest = &text[pos..];
+            // dashes are converted only between two other characters
+            let is_text = |c: Option<char>| c.is_some_and(|c| c != '-');
+            let found = STRINGS.iter().find(|(s, ..)| {
+                rest.starts_with(s)
+                    && (!s.ends_with('-')
+                        || is_text(text[..pos].chars().next_back())
+                            && is_text(rest[s.len()..].chars().next()))
+            });
+            if let Some((s, c, name)) = found {
+                write!(f, "{}", HtmlEscape(&text[start..pos]))?;
+                output.write(f, *c, name)?;
+                pos += s.len();
+                start = pos;
+            } else {
+                pos += rest.chars().next().map_or(1, char::len_utf8);
+            }
+        }
+        write!(f, "{}", HtmlEscape(&text[start..]))
+    }
+}
+
+impl Default for HtmlConfig {
+    fn default() -> Self {
+        HtmlConfig {
+            properties: false,
+            logbook: false,
+            drawers: true,
+            planning: true,
+            backend: Backend::html(),
+            src_class_prefix: String::from("language-"),
+            center_style: false,
+            links: LinkConfig::default(),
+            images: ImageConfig::default(),
+            todo_keywords: false,
+            priorities: false,
+            tags: false,
+            special_strings: false,
+            entity_output: EntityOutput::Utf8,
+            footnote_order: FootnoteOrder::Reference,
+            unreferenced_footnotes: false,
+            translations: Translations::builtin(),
+            ox_html_structure: false,
+            heading_ids: false,
+            heading_offset: 0,
+        }
+    }
+}
+
+/// Rewriting of link destinations in html export, see `HtmlConfig::links`
+///
+/// File links, i.e. `file:` links and plain paths like `./notes.org`, are
+/// rewritten if `base` or `org_extension` is set:
+///
+/// + the `file:` prefix is removed,
+/// + relative paths are resolved against `base`,
+/// + `.org` extensions are replaced with `org_extension`,
+/// + `::*Heading` search options become `#heading` anchors, see `slug`, and
+///   `::#custom-id` ones become `#custom-id`. Other search options are
+///   dropped.
+///
+/// If `org_extension` is set, links to headlines of the current document,
+/// like `[[*Heading]]`, become `#heading` anchors as well. Links with a
+/// protocol, like `https:`, are left untouched.
+///
+/// Regardless of these options, `DefaultHtmlHandler` writes links which
+/// resolve to a headline of the current document, see `Org::resolve_search`,
+/// as `#heading` anchors.
+///
+/// ```rust
+/// use orgize::export::{DefaultHtmlHandler, HtmlConfig, LinkConfig};
+/// use orgize::Org;
+///
+/// let config = HtmlConfig {
+///     links: LinkConfig {
+///         base: "/static".into(),
+///         org_extension: Some("html".into()),
+///         ..Default::default()
+///     },
+///     ..Default::default()
+/// };
+///
+/// let mut writer = Vec::new();
+/// Org::parse("[[file:notes/todo.org::*Next steps][todo]] [[./cat.png]]")
+///     .write_html_custom(&mut writer, &mut DefaultHtmlHandler::new(config))
+///     .unwrap();
+/// assert_eq!(
+///     String::from_utf8(writer).unwrap(),
+///     "<main><section><p><a href=\"/static/notes/todo.html#next-steps\">todo</a> \
+///      <a href=\"/static/cat.png\">./cat.png</a></p></section></main>"
+/// );
+/// ```
+#[derive(Clone, Default)]
+pub struct LinkConfig {
+    /// Base url or path of relative file links, default is empty
+    pub base: String,
+    /// Extension of the files `.org` files are exported to, without the
+    /// leading dot, default is `None`, which keeps `.org`
+    pub org_extension: Option<String>,
+    /// Called with every link before the rules above, the returned
+    /// destination is written as-is, default is `None`
+    ///
+    /// Returns `None` to fall back to the rules above.
+    pub hook: Option<LinkHook>,
+}
+
+type LinkHook = Arc<dyn Fn(&Link) -> Option<String> + Send + Sync>;
+
+impl LinkConfig {
+    /// Sets `hook`.
+    ///
+    /// ```rust
+    /// use orgize::export::LinkConfig;
+    ///
+    /// let config = LinkConfig::default().with_hook(|link| {
+    ///     let id = link.path.strip_prefix("id:")?;
+    ///     Some(format!("/notes/{}.html", id))
+    /// });
+    /// ```
+    pub fn with_hook<F>(mut self, hook: F) -> Self
+    where
+        F: Fn(&Link) -> Option<String> + Send + Sync + 'static,
+    {
+        self.hook = Some(Arc::new(hook));
+        self
+    }
+
+    /// Returns the destination `link` is written with.
+    pub fn href<'b>(&self, link: &'b Link) -> Cow<'b, str> {
+        if let Some(href) = self.hook.as_ref().and_then(|hook| hook(link)) {
+            return Cow::Owned(href);
+        }
+
+        if self.base.is_empty() && self.org_extension.is_none() {
+            return Cow::Borrowed(&link.path);
+        }
+
+        let file = match link.file_path() {
+            Some(file) => file,
+            None if has_scheme(&link.path) => return Cow::Borrowed(&link.path),
+            None => {
+                return match link.path.strip_prefix('*') {
+                    Some(title) if self.org_extension.is_some() => {
+                        Cow::Owned(format!("#{}", slug(title)))
+                    }
+                    _ => Cow::Borrowed(&link.path),
+                };
+            }
+        };
+
+        let mut href = String::new();
+        if !file.starts_with('/') && !file.starts_with('~') && !self.base.is_empty() {
+            href.push_str(&self.base);
+            if !self.base.ends_with('/') {
+                href.push('/');
+            }
+            href.push_str(file.strip_prefix("./").unwrap_or(file));
+        } else {
+            href.push_str(file);
+        }
+
+        if let Some(extension) = &self.org_extension {
+            let len = href.len();
+            if len > 4
+                && href
+                    .get(len - 4..)
+                    .is_some_and(|ext| ext.eq_ignore_ascii_case(".org"))
+            {
+                href.truncate(len - 3);
+                href.push_str(extension);
+            }
+        }
+
+        match link.search_option() {
+            Some(option) if option.starts_with('*') => {
+                href.push('#');
+                href.push_str(&slug(&option[1..]));
+            }
+            Some(option) if option.starts_with('#') => href.push_str(option),
+            _ => (),
+        }
+
+        Cow::Owned(href)
+    }
+}
+
+impl fmt::Debug for LinkConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("LinkConfig")
+            .field("base", &self.base)
+            .field("org_extension", &self.org_extension)
+            .field("hook", &self.hook.as_ref().map(|_| ".."))
+            .finish()
+    }
+}
+
+/// Embedding of images in html export, see `HtmlConfig::images`
+///
+/// If `resolve` is set, image links, i.e. file links without description
+/// to `.png`, `.jpg`, `.gif`, `.svg` files and so on, are written as
+/// `<img>` elements. Their `src` is a `data:` URI of the image returned by
+/// `resolve`, or the link destination if it returns `None` or the image is
+/// larger than `max_size`.
+///
+/// ```rust
+/// use orgize::export::{DefaultHtmlHandler, HtmlConfig, ImageConfig};
+/// use orgize::Org;
+///
+/// let config = HtmlConfig {
+///     images: ImageConfig::default().with_resolver(|path| match path {
+///         "dot.gif" => Some((String::from("image/gif"), b"GIF89a".to_vec())),
+///         _ => None,
+///     }),
+///     ..Default::default()
+/// };
+///
+/// let mut writer = Vec::new();
+/// Org::parse("[[file:dot.gif]] [[./cat.png]]")
+///     .write_html_custom(&mut writer, &mut DefaultHtmlHandler::new(config))
+///     .unwrap();
+/// assert_eq!(
+///     String::from_utf8(writer).unwrap(),
+///     "<main><section><p><img src=\"data:image/gif;base64,R0lGODlh\" alt=\"dot.gif\"> \
+///      <img src=\"./cat.png\" alt=\"cat.png\"></p></section></main>"
+/// );
+/// ```
+#[derive(Clone)]
+pub struct ImageConfig {
+    /// Called with the path of every image link, returns its mime type and
+    /// contents, default is `None`
+    pub resolve: Option<ImageResolver>,
+    /// Size in bytes of the largest embedded image, default is 1 MiB
+    pub max_size: usize,
+}
+
+type ImageResolver = Arc<dyn Fn(&str) -> Option<(String, Vec<u8>)> + Send + Sync>;
+
+impl Default for ImageConfig {
+    fn default() -> Self {
+        ImageConfig {
+            resolve: None,
+            max_size: 1 << 20,
+        }
+    }
+}
+
+impl ImageConfig {
+    /// Sets `resolve`.
+    pub fn with_resolver<F>(mut self, resolve: F) -> Self
+    where
+        F: Fn(&str) -> Option<(String, Vec<u8>)> + Send + Sync + 'static,
+    {
+        self.resolve = Some(Arc::new(resolve));
+        self
+    }
+
+    /// Sets `resolve` to read images from the file system, paths are
+    /// relative to `base`.
+    ///
+    /// Only files under `base` are read: absolute paths, paths starting
+    /// with `~` and paths with `..` components aren't resolved, so those
+    /// images are linked instead. Use `with_resolver` to read other files.
+    ///
+    /// Mime types are guessed from file extensions.
+    #[cfg(feature = "fs")]
+    pub fn with_files<P>(self, base: P) -> Self
+    where
+        P: Into<std::path::PathBuf>,
+    {
+        use std::path::{Component, Path};
+
+        let base = base.into();
+        self.with_resolver(move |path| {
+            let mime = image_mime(path)?;
+            let inside = !path.starts_with('~')
+                && Path::new(path)
+                    .components()
+                    .all(|component| matches!(component, Component::Normal(_) | Component::CurDir));
+            if !inside {
+                return None;
+            }
+            let contents = std::fs::read(base.join(path)).ok()?;
+            Some((mime.to_string(), contents))
+        })
+    }
+
+    // `src` of image `link`, or `None` if it isn't an image link
+    fn src<'b>(&self, link: &'b Link, links: &LinkConfig) -> Option<Cow<'b, str>> {
+        let resolve = self.resolve.as_ref()?;
+        if link.desc.is_some() {
+            return None;
+        }
+        let path = link.file_path()?;
+        image_mime(path)?;
+
+        match resolve(path) {
+            Some((mime, contents)) if contents.len() <= self.max_size => Some(Cow::Owned(format!(
+                "data:{};base64,{}",
+                mime,
+                base64(&contents)
+            ))),
+            // `file:` isn't understood by browsers
+            _ => Some(match links.href(link) {
+                Cow::Borrowed(href) => Cow::Borrowed(href.strip_prefix("file:").unwrap_or(href)),
+                href => href,
+            }),
+        }
+    }
+}
+
+impl fmt::Debug for ImageConfig {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("ImageConfig")
+            .field("resolve", &self.resolve.as_ref().map(|_| ".."))
+            .field("max_size", &self.max_size)
+            .finish()
+    }
+}
+
+// mime type of an image file, guessed from its extension, like
+// `org-html-inline-image-rules`
+fn image_mime(path: &str) -> Option<&'static str> {
+    let (_, extension) = path.rsplit_once('.')?;
+    let mime = match &*extension.to_ascii_lowercase() {
+        "png" => "image/png",
+        "jpg" | "jpeg" => "image/jpeg",
+        "gif" => "image/gif",
+        "svg" => "image/svg+xml",
+        "webp" => "image/webp",
+        "bmp" => "image/bmp",
+        "tif" | "tiff" => "image/tiff",
+        "xbm" => "image/x-xbitmap",
+        "xpm" => "image/x-xpixmap",
+        "pbm" => "image/x-portable-bitmap",
+        "pgm" => "image/x-portable-graymap",
+        "ppm" => "image/x-portable-pixmap",
+        _ => return None,
+    };
+    Some(mime)
+}
+
+// whether paragraph `node` is a single link to an image, i.e. a figure
+fn is_image_paragraph(org: &Org, node: NodeId) -> bool {
+    let mut links = 0;
+    for child in node.children(&org.arena) {
+        match &org[child] {
+            Element::Link(link)
+                if link.desc.is_none() && link.file_path().and_then(image_mime).is_some() =>
+            {
+                links += 1
+            }
+            Element::Text { value } if value.trim().is_empty() => (),
+            _ => return false,
+        }
+    }
+    links == 1
+}
+
+// standard base64 with padding
+fn base64(bytes: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity((bytes.len() + 2) / 3 * 4);
+    for chunk in bytes.chunks(3) {
+        let n = (chunk[0] as u32) << 16
+            | (*chunk.get(1).unwrap_or(&0) as u32) << 8
+            | *chunk.get(2).unwrap_or(&0) as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 63) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+    encoded
+}
+
+/// Converts a headline title into an anchor, i.e. lowercase letters and
+/// digits, with other characters collapsed into `-`.
+///
+/// ```rust
+/// use orgize::export::slug;
+///
+/// assert_eq!(slug("Next steps: 2024!"), "next-steps-2024");
+/// ```
+pub fn slug(title: &str) -> String {
+    let mut slug = String::with_capacity(title.len());
+    for c in title.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    if slug.ends_with('-') {
+        slug.pop();
+    }
+    slug
+}
+
+// an id based on `name`, which isn't in `used` yet
+pub(crate) fn unique_id(name: &str, used: &mut HashSet<String>) -> String {
+    let base = match slug(name) {
+        slug if slug.is_empty() => String::from("target"),
+        slug => slug,
+    };
+    let mut id = base.clone();
+    let mut n = 1;
+    while !used.insert(id.clone()) {
+        n += 1;
+        id = format!("{}-{}", base, n);
+    }
+    id
+}
+
+// ids of targets and radio targets, unique in the whole document
+pub(crate) fn target_ids(org: &Org) -> HashMap<NodeId, String> {
+    let mut ids = HashMap::new();
+    let mut used = HashSet::new();
+    for node in org.root.descendants(&org.arena) {
+        let name = match &org[node] {
+            Element::Target(target) => Cow::Borrowed(&*target.target),
+            Element::RadioTarget => Cow::Owned(org.text_content(node)),
+            _ => continue,
+        };
+        ids.insert(node, unique_id(&name, &mut used));
+    }
+    ids
+}
+
+// class of the number of a captioned table, figure or listing
+fn number_class(org: &Org, node: NodeId) -> Option<&'static str> {
+    match &org[node] {
+        Element::Table(Table::Org { .. }) => Some("table-number"),
+        Element::Paragraph { .. } if is_image_paragraph(org, node) => Some("figure-number"),
+        Element::SourceBlock(_) => Some("listing-number"),
+        _ => None,
+    }
+}
+
+// ids of exported headlines, their `CUSTOM_ID` properties or else made from
+// their titles, which aren't used by targets or numbered elements
+pub(crate) fn headline_ids(org: &Org, unexported: &HashSet<NodeId>) -> HashMap<NodeId, String> {
+    let mut used: HashSet<_> = target_ids(org).into_values().collect();
+    for node in org.root.descendants(&org.arena) {
+        if unexported.contains(&node) || org.caption(node).is_none() {
+            continue;
+        }
+        if let (Some(_), Some(name)) = (number_class(org, node), org.name(node)) {
+            unique_id(name, &mut used);
+        }
+    }
+
+    let mut ids = HashMap::new();
+    for node in org.root.descendants(&org.arena) {
+        if let (Element::Headline { level }, false) = (&org[node], unexported.contains(&node)) {
+            let title = Headline::from_node(node, *level, org).title(org);
+            let id = match title.properties.get("CUSTOM_ID") {
+                Some(id) => {
+                    used.insert(id.to_string());
+                    id.to_string()
+                }
+                None => unique_id(&title.raw, &mut used),
+            };
+            ids.insert(node, id);
+        }
+    }
+    ids
+}
+
+// a class name for todo keyword or tag `name`, with characters other than
+// letters, digits, `-` and `_` replaced with `_`, like Emacs
+fn class_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
 }
 
 /// Default Html Handler
 #[derive(Default)]
-pub struct DefaultHtmlHandler;
+pub struct DefaultHtmlHandler {
+    config: HtmlConfig,
+    // depth inside a drawer which isn't rendered
+    hidden: usize,
+    // number of references to each footnote label written so far
+    fn_refs: HashMap<String, usize>,
+    // footnote labels in order of their first reference written so far
+    fn_order: Vec<String>,
+    // labels and nodes of footnote definitions, including references with
+    // inline definitions, in document order
+    fn_defs: Vec<(String, NodeId)>,
+    // labels given to anonymous footnotes, keyed by their references
+    fn_labels: HashMap<NodeId, String>,
+    // definitions returned by `trailing_nodes`, and their labels
+    fn_written: HashMap<NodeId, String>,
+    // whether `<div id="footnotes">` is open
+    fn_open: bool,
+    // footnote section headlines
+    fn_sections: HashSet<NodeId>,
+    // affiliated keywords of the next element
+    affiliated: Affiliated,
+    // captions of open quote and center blocks, and of numbered elements,
+    // which are wrapped in `<figure>` if they have one
+    figures: Vec<Option<(String, Option<Number>)>>,
+    // numbers of captioned tables, figures and listings
+    numbers: HashMap<NodeId, Number>,
+    // labels of numbered elements linked by name, e.g. `Table 1`
+    references: HashMap<String, String>,
+    // destinations of links resolved in the current document
+    anchors: HashMap<String, String>,
+    // ids of targets and radio targets
+    targets: HashMap<NodeId, String>,
+    // sections with nothing to render
+    empty_sections: HashSet<NodeId>,
+    // ids of headlines with `HtmlConfig::ox_html_structure`
+    headline_ids: HashMap<NodeId, String>,
+    // ids of headings which are targets of links, or of all headings with
+    // `HtmlConfig::heading_ids`, keyed by their titles
+    heading_ids: HashMap<NodeId, String>,
+    // levels of headings and ids of open headlines, with
+    // `HtmlConfig::ox_html_structure`
+    outlines: Vec<(usize, String)>,
+    // configuration after applying `#+OPTIONS:`
+    options: ExportOptions,
+    // boilerplate strings in the language of the document
+    strings: Translations,
+}
+
+#[derive(Default)]
+struct ExportOptions {
+    todo_keywords: bool,
+    priorities: bool,
+    tags: bool,
+    special_strings: bool,
+    done_keywords: Vec<String>,
+}
+
+#[derive(Clone)]
+struct Number {
+    // class of the label, e.g. `table-number`
+    class: &'static str,
+    // e.g. `Table 1`
+    label: String,
+    // id of elements with a `#+NAME:`
+    id: Option<String>,
+}
+
+#[derive(Default)]
+struct Affiliated {
+    // `#+CAPTION:`
+    caption: Option<String>,
+    // `#+ATTR_HTML:`
+    attributes: Vec<(String, String)>,
+}
+
+impl DefaultHtmlHandler {
+    pub fn new(config: HtmlConfig) -> Self {
+        DefaultHtmlHandler {
+            config,
+            hidden: 0,
+            fn_refs: HashMap::new(),
+            fn_order: Vec::new(),
+            fn_defs: Vec::new(),
+            fn_labels: HashMap::new(),
+            fn_written: HashMap::new(),
+            fn_open: false,
+            fn_sections: HashSet::new(),
+            affiliated: Affiliated::default(),
+            figures: Vec::new(),
+            numbers: HashMap::new(),
+            references: HashMap::new(),
+            anchors: HashMap::new(),
+            targets: HashMap::new(),
+            empty_sections: HashSet::new(),
+            headline_ids: HashMap::new(),
+            heading_ids: HashMap::new(),
+            outlines: Vec::new(),
+            options: ExportOptions::default(),
+            strings: Translations::default(),
+        }
+    }
+
+    fn start_figure<W: Write>(
+        &mut self,
+        mut w: W,
+        caption: Option<String>,
+        number: Option<&Number>,
+    ) -> IOResult<()> {
+        if caption.is_some() {
+            match number.and_then(|number| number.id.as_ref()) {
+                Some(id) => write!(w, "<figure id=\"{}\">", HtmlEscape(id))?,
+                None => write!(w, "<figure>")?,
+            }
+        }
+        self.figures
+            .push(caption.map(|caption| (caption, number.cloned())));
+        Ok(())
+    }
+
+    fn end_figure(&mut self, w: &mut dyn Write) -> IOResult<()> {
+        if let Some(Some((caption, number))) = self.figures.pop() {
+            write!(w, "<figcaption>")?;
+            self.write_caption(w, &caption, number.as_ref())?;
+            write!(w, "</figcaption></figure>")?;
+        }
+        Ok(())
+    }
+
+    // takes a trait object, since rendering the caption recurses into
+    // `start` and `end`
+    fn write_caption(
+        &mut self,
+        mut w: &mut dyn Write,
+        caption: &str,
+        number: Option<&Number>,
+    ) -> IOResult<()> {
+        if let Some(number) = number {
+            write!(
+                w,
+                "<span class=\"{}\">{}:</span> ",
+                number.class,
+                HtmlEscape(&number.label)
+            )?;
+        }
+        for event in Objects::parse_in(caption, ObjectContext::ParsedKeyword).iter() {
+            match event {
+                Event::Start(element) => self.start(&mut w, element)?,
+                Event::End(element) => self.end(&mut w, element)?,
+            }
+        }
+        Ok(())
+    }
+
+    fn is_hidden(&self, drawer: &Drawer) -> bool {
+        if drawer.name.eq_ignore_ascii_case("LOGBOOK") {
+            !self.config.logbook
+        } else if drawer.name.eq_ignore_ascii_case("PROPERTIES") {
+            !self.config.properties
+        } else {
+            !self.config.drawers
+        }
+    }
+
+    fn write_heading_prefix<W: Write>(&self, mut w: W, title: &Title) -> IOResult<()> {
+        if let (Some(keyword), true) = (&title.keyword, self.options.todo_keywords) {
+            let kind = if self.options.done_keywords.iter().any(|k| k == keyword) {
+                "done"
+            } else {
+                "todo"
+            };
+            write!(
+                w,
+                "<span class=\"{} {}\">{}</span> ",
+                kind,
+                class_name(keyword),
+                HtmlEscape(keyword)
+            )?;
+        }
+        if let (Some(priority), true) = (title.priority, self.options.priorities) {
+            write!(w, "<span class=\"priority\">[#{}]</span> ", priority)?;
+        }
+        Ok(())
+    }
+
+    fn write_heading_tags<W: Write>(&self, mut w: W, title: &Title) -> IOResult<()> {
+        if title.tags.is_empty() || !self.options.tags {
+            return Ok(());
+        }
+        write!(w, "&#xa0;&#xa0;&#xa0;<span class=\"tag\">")?;
+        for (i, tag) in title.tags.iter().enumerate() {
+            if i != 0 {
+                write!(w, "&#xa0;")?;
+            }
+            write!(
+                w,
+                "<span class=\"{}\">{}</span>",
+                class_name(tag),
+                HtmlEscape(tag)
+            )?;
+        }
+        write!(w, "</span>")
+    }
+
+    fn write_planning<W: Write>(&self, mut w: W, planning: &Planning) -> IOResult<()> {
+        write!(w, "<p class=\"planning\">")?;
+        let timestamps = [
+            ("DEADLINE:", &planning.deadline),
+            ("SCHEDULED:", &planning.scheduled),
+            ("CLOSED:", &planning.closed),
+        ];
+        let mut first = true;
+        for (keyword, timestamp) in timestamps.iter() {
+            if let Some(timestamp) = timestamp {
+                if !first {
+                    write!(w, " ")?;
+                }
+                first = false;
+                write!(
+                    w,
+                    "<span class=\"timestamp-wrapper\"><span class=\"timestamp-kwd\">{}</span> \
+                     <span class=\"timestamp\">{}</span></span>",
+                    keyword,
+                    HtmlEscape(timestamp.to_string())
+                )?;
+            }
+        }
+        write!(w, "</p>")
+    }
+
+    // starts footnote definition `node` written after the document, see
+    // `trailing_nodes`
+    fn start_fn_def<W: Write>(&mut self, mut w: W, label: &str) -> IOResult<()> {
+        if !self.fn_open {
+            write!(
+                w,
+                "<div id=\"footnotes\"><h2 class=\"footnotes\">{}</h2>",
+                HtmlEscape(&self.strings.footnotes)
+            )?;
+            self.fn_open = true;
+        }
+        write!(
+            w,
+            "<div class=\"footdef\"><sup><a id=\"fn.{0}\" class=\"footnum\" \
+             href=\"#fnr.{0}\" role=\"doc-backlink\">{0}</a></sup> \
+             <div class=\"footpara\" role=\"doc-footnote\">",
+            HtmlEscape(label)
+        )
+    }
+
+    fn write_properties<W: Write>(&self, mut w: W, properties: &PropertiesMap) -> IOResult<()> {
+        write!(w, "<table class=\"properties\">")?;
+        for (key, value) in properties.iter() {
+            write!(
+                w,
+                "<tr><th>{}</th><td>{}</td></tr>",
+                HtmlEscape(key),
+                HtmlEscape(value)
+            )?;
+        }
+        write!(w, "</table>")
+    }
+}
 
 impl HtmlHandler<Error> for DefaultHtmlHandler {
-    fn start<W: Write>(&mut self, mut w: W, element: &Element) -> IOResult<()> {
+    fn prepare(&mut self, org: &Org) {
+        self.anchors.clear();
+        self.targets.clear();
+        self.empty_sections.clear();
+        self.headline_ids.clear();
+        self.heading_ids.clear();
+        self.outlines.clear();
+
+        self.options = ExportOptions {
+            todo_keywords: self.config.todo_keywords,
+            priorities: self.config.priorities,
+            tags: self.config.tags,
+            special_strings: self.config.special_strings,
+            done_keywords: org
+                .config()
+                .todo_keywords
+                .1
+                .iter()
+                .map(|keyword| keyword_name(keyword).to_string())
+                .collect(),
+        };
+        for (key, value) in org.export_options() {
+            let enabled = value != "nil";
+            match key {
+                "todo" => self.options.todo_keywords = enabled,
+                "pri" => self.options.priorities = enabled,
+                "tags" => self.options.tags = enabled,
+                "-" => self.options.special_strings = enabled,
+                _ => (),
+            }
+        }
+
+        self.strings = org
+            .metadata()
+            .language
+            .and_then(|language| {
+                Translations::lookup(&self.config.translations, &language).cloned()
+            })
+            .unwrap_or_default();
+
+        let unexported = org.unexported();
+        self.fn_sections.clear();
+        for node in org.root.descendants(&org.arena) {
+            if let Element::Title(title) = &org[node] {
+                if title.is_footnote_section() {
+                    if let Some(headline) = org.arena[node].parent() {
+                        self.fn_sections.insert(headline);
+                    }
+                }
+            }
+            if let Element::Section = org[node] {
+                let empty = node.children(&org.arena).all(|child| {
+                    unexported.contains(&child)
+                        || match &org[child] {
+                            Element::Keyword(_)
+                            | Element::Comment(_)
+                            | Element::BabelCall(_)
+                            | Element::FnDef(_)
+                            | Element::Custom(_)
+                            | Element::Skipped(_) => true,
+                            Element::Drawer(drawer) => self.is_hidden(drawer),
+                            _ => false,
+                        }
+                });
+                if empty {
+                    self.empty_sections.insert(node);
+                }
+            }
+        }
+
+        let ids = target_ids(org);
+        for (&node, id) in &ids {
+            self.targets.insert(node, id.clone());
+        }
+
+        // captioned tables, figures and listings are numbered by kind
+        self.numbers.clear();
+        let mut used: HashSet<_> = ids.values().cloned().collect();
+        let mut counters = HashMap::new();
+        for node in org.root.descendants(&org.arena) {
+            if unexported.contains(&node) || org.caption(node).is_none() {
+                continue;
+            }
+            let (class, name) = match number_class(org, node) {
+                Some(class @ "table-number") => (class, &self.strings.table),
+                Some(class @ "figure-number") => (class, &self.strings.figure),
+                Some(class) => (class, &self.strings.listing),
+                None => continue,
+            };
+            let counter = counters.entry(class).or_insert(0);
+            *counter += 1;
+            self.numbers.insert(
+                node,
+                Number {
+                    class,
+                    label: format!("{} {}", name, counter),
+                    id: org.name(node).map(|name| unique_id(name, &mut used)),
+                },
+            );
+        }
+
+        let headline_ids = headline_ids(org, &unexported);
+        if self.config.ox_html_structure {
+            self.headline_ids = headline_ids.clone();
+        } else if self.config.heading_ids {
+            for (&headline, id) in &headline_ids {
+                if let Some(title) = org.arena[headline].first_child() {
+                    self.heading_ids.insert(title, id.clone());
+                }
+            }
+        }
+
+        self.references.clear();
+        for event in org.iter() {
+            let link = match event {
+                Event::Start(Element::Link(link)) if link.file_path().is_none() => link,
+                _ => continue,
+            };
+            let node = match link.search() {
+                None => continue,
+                Some(option) => match org.resolve_search(&option) {
+                    Some(node) => node,
+                    None => continue,
+                },
+            };
+            if let Some(number) = self.numbers.get(&node) {
+                self.references
+                    .insert(link.path.to_string(), number.label.clone());
+            }
+            let number_id = self
+                .numbers
+                .get(&node)
+                .and_then(|number| number.id.as_ref());
+            let anchor = match (&org[node], ids.get(&node).or(number_id)) {
+                (_, Some(id)) => format!("#{}", id),
+                (Element::Headline { .. }, _) => match headline_ids.get(&node) {
+                    Some(id) => {
+                        // the heading is the target of the link
+                        if let Some(title) = org.arena[node].first_child() {
+                            self.heading_ids.insert(title, id.clone());
+                        }
+                        format!("#{}", id)
+                    }
+                    None => continue,
+                },
+                _ => continue,
+            };
+            self.anchors.insert(link.path.to_string(), anchor);
+        }
+
+        // definitions are written after the contents of the document, see
+        // `trailing_nodes`, and anonymous footnotes are numbered after the
+        // labels in use
+        self.fn_defs.clear();
+        self.fn_labels.clear();
+        self.fn_written.clear();
+        self.fn_open = false;
+        let mut used: HashSet<&str> = HashSet::new();
+        let mut anonymous = Vec::new();
+        for node in org.root.descendants(&org.arena) {
+            match &org[node] {
+                Element::FnDef(fn_def) => {
+                    used.insert(fn_def.normalized_label());
+                }
+                Element::FnRef(fn_ref)
+                    if fn_ref.normalized_label().is_empty() && fn_ref.definition.is_some() =>
+                {
+                    anonymous.push(node);
+                }
+                Element::FnRef(fn_ref) => {
+                    used.insert(fn_ref.normalized_label());
+                }
+                _ => (),
+            }
+        }
+        let mut number = 0;
+        for node in anonymous {
+            let label = loop {
+                number += 1;
+                let label = number.to_string();
+                if !used.contains(&*label) {
+                    break label;
+                }
+            };
+            self.fn_labels.insert(node, label);
+        }
+        for node in org.root.descendants(&org.arena) {
+            if unexported.contains(&node) {
+                continue;
+            }
+            let label = match &org[node] {
+                Element::FnDef(fn_def) => fn_def.normalized_label(),
+                Element::FnRef(fn_ref) if fn_ref.definition.is_some() => {
+                    match self.fn_labels.get(&node) {
+                        Some(label) => label,
+                        None => fn_ref.normalized_label(),
+                    }
+                }
+                _ => continue,
+            };
+            if self.fn_defs.iter().all(|(l, _)| l != label) {
+                self.fn_defs.push((label.to_string(), node));
+            }
+        }
+        self.fn_refs.clear();
+        self.fn_order.clear();
+    }
+
+    fn trailing_nodes(&mut self, org: &Org, root: NodeId) -> Vec<NodeId> {
+        if !matches!(org[root], Element::Document { .. }) {
+            return Vec::new();
+        }
+
+        let definition = |label: &String| {
+            self.fn_defs
+                .iter()
+                .find(|(l, _)| l == label)
+                .map(|(_, node)| (label.clone(), *node))
+        };
+        let mut footnotes: Vec<(String, NodeId)> = match self.config.footnote_order {
+            FootnoteOrder::Reference => self.fn_order.iter().filter_map(definition).collect(),
+            FootnoteOrder::Document => self
+                .fn_defs
+                .iter()
+                .filter(|(label, _)| self.fn_refs.contains_key(label))
+                .cloned()
+                .collect(),
+        };
+        if self.config.unreferenced_footnotes {
+            footnotes.extend(
+                self.fn_defs
+                    .iter()
+                    .filter(|(label, _)| !self.fn_refs.contains_key(label))
+                    .cloned(),
+            );
+        }
+
+        // definitions referenced only inside other definitions are returned
+        // by the next call
+        footnotes.retain(|(_, node)| !self.fn_written.contains_key(node));
+        let nodes = footnotes.iter().map(|(_, node)| *node).collect();
+        self.fn_written
+            .extend(footnotes.into_iter().map(|(l, n)| (n, l)));
+        nodes
+    }
+
+    fn start<W: Write>(&mut self, w: W, element: &Element) -> IOResult<()> {
+        self.write_start(w, None, element)
+    }
+
+    fn end<W: Write>(&mut self, w: W, element: &Element) -> IOResult<()> {
+        self.write_end(w, None, element)
+    }
+
+    fn start_node<W: Write>(&mut self, w: W, node: NodeId, element: &Element) -> IOResult<()> {
+        self.write_start(w, Some(node), element)
+    }
+
+    fn end_node<W: Write>(&mut self, w: W, node: NodeId, element: &Element) -> IOResult<()> {
+        self.write_end(w, Some(node), element)
+    }
+}
+
+impl DefaultHtmlHandler {
+    // `node` is `None` for elements outside of the document, e.g. objects
+    // of captions, or if the handler is called through `start`
+    fn write_start<W: Write>(
+        &mut self,
+        mut w: W,
+        node: Option<NodeId>,
+        element: &Element,
+    ) -> IOResult<()> {
+        if self.hidden > 0 {
+            self.hidden += 1;
+            return Ok(());
+        }
+
+        let affiliated = match element {
+            Element::Keyword(_) => Affiliated::default(),
+            _ => std::mem::take(&mut self.affiliated),
+        };
+
         match element {
             // container elements
             Element::SpecialBlock(_) => (),
-            Element::QuoteBlock(_) => write!(w, "<blockquote>")?,
-            Element::CenterBlock(_) => write!(w, "<div class=\"center\">")?,
+            Element::QuoteBlock(_) => {
+                self.start_figure(&mut w, affiliated.caption, None)?;
+                write!(w, "<blockquote")?;
+                write_attributes(&mut w, Vec::new(), affiliated.attributes)?;
+                write!(w, ">")?;
+            }
+            Element::CenterBlock(_) => {
+                self.start_figure(&mut w, affiliated.caption, None)?;
+                let base = if self.config.center_style {
+                    ("style", "text-align:center")
+                } else {
+                    ("class", "org-center")
+                };
+                write!(w, "<div")?;
+                write_attributes(
+                    &mut w,
+                    vec![(base.0.to_string(), base.1.to_string())],
+                    affiliated.attributes,
+                )?;
+                write!(w, ">")?;
+            }
             Element::VerseBlock(_) => write!(w, "<p class=\"verse\">")?,
             Element::Bold => write!(w, "<b>")?,
+            Element::Document { .. } if self.config.ox_html_structure => {
+                write!(w, "<div id=\"content\" class=\"content\">")?
+            }
             Element::Document { .. } => write!(w, "<main>")?,
             Element::DynBlock(_dyn_block) => (),
-            Element::Headline { .. } => (),
+            Element::Headline { .. }
+                if node.is_some_and(|node| self.fn_sections.contains(&node)) =>
+            {
+                self.hidden = 1;
+            }
+            Element::Headline { level } => {
+                if let Some(id) = node.and_then(|node| self.headline_ids.get(&node)) {
+                    write!(
+                        w,
+                        "<div id=\"outline-container-{}\" class=\"outline-{}\">",
+                        HtmlEscape(id),
+                        level + 1 + self.config.heading_offset
+                    )?;
+                    self.outlines
+                        .push((level + 1 + self.config.heading_offset, id.clone()));
+                }
+            }
             Element::List(list) => {
                 if list.ordered {
                     write!(w, "<ol>")?;
@@ -77,8 +1459,33 @@ impl HtmlHandler<Error> for DefaultHtmlHandler {
                 }
             }
             Element::Italic => write!(w, "<i>")?,
-            Element::ListItem(_) => write!(w, "<li>")?,
-            Element::Paragraph { .. } => write!(w, "<p>")?,
+            Element::ListItem(list_item) => {
+                match list_item.counter {
+                    Some(counter) if list_item.ordered => write!(w, "<li value=\"{}\">", counter)?,
+                    _ => write!(w, "<li>")?,
+                }
+                if let Some(checkbox) = list_item.checkbox {
+                    write!(w, "<code>{}</code> ", checkbox.as_str())?;
+                }
+            }
+            Element::Paragraph { .. } => {
+                if let Some(number) = node.and_then(|node| self.numbers.get(&node)).cloned() {
+                    self.start_figure(&mut w, affiliated.caption, Some(&number))?;
+                }
+                write!(w, "<p>")?
+            }
+            Element::Section if node.is_some_and(|node| self.empty_sections.contains(&node)) => (),
+            Element::Section if self.config.ox_html_structure => {
+                // sections before the first headline aren't wrapped
+                if let Some((level, id)) = self.outlines.last() {
+                    write!(
+                        w,
+                        "<div class=\"outline-text-{}\" id=\"text-{}\">",
+                        level,
+                        HtmlEscape(id)
+                    )?;
+                }
+            }
             Element::Section => write!(w, "<section>")?,
             Element::Strike => write!(w, "<s>")?,
             Element::Underline => write!(w, "<u>")?,
@@ -87,53 +1494,125 @@ impl HtmlHandler<Error> for DefaultHtmlHandler {
             Element::ExampleBlock(block) => write!(
                 w,
                 "<pre class=\"example\">{}</pre>",
-                HtmlEscape(&block.contents)
+                HtmlEscape(strip_indentation(&block.contents))
             )?,
-            Element::ExportBlock(block) => {
-                if block.data.eq_ignore_ascii_case("HTML") {
-                    write!(w, "{}", block.contents)?
+            Element::ExportBlock(_) | Element::Snippet(_) => {
+                if let Some(raw) = self.config.backend.raw_contents(element) {
+                    write!(w, "{}", raw)?;
                 }
             }
             Element::SourceBlock(block) => {
+                let number = node.and_then(|node| self.numbers.get(&node)).cloned();
+                if number.is_some() {
+                    self.start_figure(&mut w, affiliated.caption, number.as_ref())?;
+                }
+                let contents = strip_indentation(&block.contents);
                 if block.language.is_empty() {
-                    write!(
-                        w,
-                        "<pre class=\"example\">{}</pre>",
-                        HtmlEscape(&block.contents)
-                    )?;
+                    write!(w, "<pre class=\"example\">{}</pre>", HtmlEscape(&contents))?;
                 } else {
                     write!(
                         w,
-                        "<div class=\"org-src-container\"><pre class=\"src src-{}\">{}</pre></div>",
-                        block.language,
-                        HtmlEscape(&block.contents)
+                        "<div class=\"org-src-container\"><pre><code class=\"{}{}\">{}</code></pre></div>",
+                        HtmlEscape(&self.config.src_class_prefix),
+                        HtmlEscape(highlight_language(&block.language)),
+                        HtmlEscape(&contents)
                     )?;
                 }
+                if number.is_some() {
+                    self.end_figure(&mut w)?;
+                }
             }
-            Element::BabelCall(_) => (),
+            Element::BabelCall(_) | Element::Custom(_) | Element::Skipped(_) => (),
             Element::InlineSrc(inline_src) => write!(
                 w,
-                "<code class=\"src src-{}\">{}</code>",
-                inline_src.lang,
+                "<code class=\"{}{}\">{}</code>",
+                HtmlEscape(&self.config.src_class_prefix),
+                HtmlEscape(highlight_language(&inline_src.lang)),
                 HtmlEscape(&inline_src.body)
             )?,
             Element::Code { value } => write!(w, "<code>{}</code>", HtmlEscape(value))?,
-            Element::FnRef(_fn_ref) => (),
+            Element::FnRef(fn_ref) => {
+                if let Some(label) = node.and_then(|node| self.fn_written.get(&node)).cloned() {
+                    // the inline definition, written after the document
+                    self.start_fn_def(&mut w, &label)?;
+                    if let Some(definition) = &fn_ref.definition {
+                        write!(w, "<p>{}</p>", HtmlEscape(definition.trim()))?;
+                    }
+                    return Ok(());
+                }
+                let label = match node.and_then(|node| self.fn_labels.get(&node)) {
+                    Some(label) => label.clone(),
+                    None => fn_ref.normalized_label().to_string(),
+                };
+                if !label.is_empty() {
+                    let count = self.fn_refs.entry(label.clone()).or_insert(0);
+                    *count += 1;
+                    if *count == 1 {
+                        self.fn_order.push(label.clone());
+                    }
+                    // like Emacs, only the first reference is the target of
+                    // the definition's back link
+                    let suffix = if *count == 1 {
+                        String::new()
+                    } else {
+                        format!(".{}", count)
+                    };
+                    write!(
+                        w,
+                        "<sup><a id=\"fnr.{0}{1}\" class=\"footref\" href=\"#fn.{0}\" \
+                         role=\"doc-backlink\">{0}</a></sup>",
+                        HtmlEscape(&label),
+                        suffix
+                    )?;
+                }
+            }
             Element::InlineCall(_) => (),
-            Element::Link(link) => write!(
-                w,
-                "<a href=\"{}\">{}</a>",
-                HtmlEscape(&link.path),
-                HtmlEscape(link.desc.as_ref().unwrap_or(&link.path)),
-            )?,
-            Element::Macros(_macros) => (),
-            Element::RadioTarget => (),
-            Element::Snippet(snippet) => {
-                if snippet.name.eq_ignore_ascii_case("HTML") {
-                    write!(w, "{}", snippet.value)?;
+            Element::Link(link) => {
+                let links = &self.config.links;
+                if let Some(src) = self.config.images.src(link, links) {
+                    let path = link.file_path().unwrap_or(&link.path);
+                    write!(
+                        w,
+                        "<img src=\"{}\" alt=\"{}\">",
+                        HtmlEscape(src),
+                        HtmlEscape(path.rsplit('/').next().unwrap_or(path))
+                    )?
+                } else {
+                    let href = match self.anchors.get(&*link.path) {
+                        // the hook still takes precedence
+                        Some(anchor)
+                            if links.hook.as_ref().and_then(|hook| hook(link)).is_none() =>
+                        {
+                            Cow::Borrowed(anchor.as_str())
+                        }
+                        _ => links.href(link),
+                    };
+                    // links to numbered elements show their label
+                    let text = match (&link.desc, self.references.get(&*link.path)) {
+                        (Some(desc), _) => &**desc,
+                        (None, Some(label)) => label.as_str(),
+                        (None, None) => &*link.path,
+                    };
+                    write!(
+                        w,
+                        "<a href=\"{}\">{}</a>",
+                        HtmlEscape(href),
+                        HtmlEscape(text)
+                    )?
                 }
             }
-            Element::Target(_target) => (),
+            Element::Macros(_macros) => (),
+            Element::RadioTarget => match node.and_then(|node| self.targets.get(&node)) {
+                Some(id) => write!(w, "<a id=\"{}\">", HtmlEscape(id))?,
+                None => write!(w, "<a>")?,
+            },
+            Element::Target(target) => match node.and_then(|node| self.targets.get(&node)) {
+                Some(id) => write!(w, "<a id=\"{}\"></a>", HtmlEscape(id))?,
+                None => write!(w, "<a id=\"{}\"></a>", HtmlEscape(slug(&target.target)))?,
+            },
+            Element::Text { value } if self.options.special_strings => {
+                write!(w, "{}", SpecialStrings(value, self.config.entity_output))?
+            }
             Element::Text { value } => write!(w, "{}", HtmlEscape(value))?,
             Element::Timestamp(timestamp) => {
                 write!(
@@ -162,24 +1641,115 @@ impl HtmlHandler<Error> for DefaultHtmlHandler {
                 write!(&mut w, "</span></span>")?;
             }
             Element::Verbatim { value } => write!(&mut w, "<code>{}</code>", HtmlEscape(value))?,
-            Element::FnDef(_fn_def) => (),
-            Element::Clock(_clock) => (),
+            Element::FnDef(_) => match node.and_then(|node| self.fn_written.get(&node)).cloned() {
+                Some(label) => self.start_fn_def(&mut w, &label)?,
+                // written after the document, see `trailing_nodes`
+                None => self.hidden = 1,
+            },
+            Element::Clock(clock) => {
+                if self.config.logbook {
+                    write!(
+                        w,
+                        "<p><span class=\"timestamp-wrapper\"><span class=\"timestamp-kwd\">CLOCK:</span> \
+                         <span class=\"timestamp\">{}</span></span>",
+                        HtmlEscape(clock.value().to_string())
+                    )?;
+                    if let Some(duration) = clock.duration() {
+                        write!(w, " ({})", duration)?;
+                    }
+                    write!(w, "</p>")?;
+                }
+            }
             Element::Comment(_) => (),
             Element::FixedWidth(fixed_width) => write!(
                 w,
                 "<pre class=\"example\">{}</pre>",
                 HtmlEscape(&fixed_width.value)
             )?,
-            Element::Keyword(_keyword) => (),
-            Element::Drawer(_drawer) => (),
+            Element::Keyword(keyword) => {
+                if keyword.key.eq_ignore_ascii_case("CAPTION") {
+                    // consecutive captions are joined with a space
+                    let value = keyword.value.trim();
+                    match &mut self.affiliated.caption {
+                        Some(caption) => {
+                            caption.push(' ');
+                            caption.push_str(value);
+                        }
+                        None => self.affiliated.caption = Some(value.to_string()),
+                    }
+                } else if keyword.key.eq_ignore_ascii_case("ATTR_HTML") {
+                    self.affiliated
+                        .attributes
+                        .extend(parse_attributes(&keyword.value));
+                }
+                // affiliated keywords are followed by the element directly
+                if keyword.post_blank > 0 {
+                    self.affiliated = Affiliated::default();
+                }
+            }
+            Element::Drawer(drawer) => {
+                if self.is_hidden(drawer) {
+                    self.hidden = 1;
+                }
+            }
             Element::Rule(_) => write!(w, "<hr>")?,
             Element::Cookie(cookie) => write!(w, "<code>{}</code>", cookie.value)?,
             Element::Title(title) => {
-                write!(w, "<h{}>", if title.level <= 6 { title.level } else { 6 })?;
+                let id = match self.outlines.last() {
+                    Some((_, id)) => Some(id),
+                    None => node.and_then(|node| self.heading_ids.get(&node)),
+                };
+                let level = match self.outlines.last() {
+                    Some((level, _)) => *level,
+                    None => title.level + self.config.heading_offset,
+                };
+                match id {
+                    Some(id) => write!(w, "<h{} id=\"{}\">", level.min(6), HtmlEscape(id))?,
+                    None => write!(w, "<h{}>", level.min(6))?,
+                }
+                self.write_heading_prefix(&mut w, title)?;
             }
             Element::Table(Table::TableEl { .. }) => (),
-            Element::Table(Table::Org { has_header, .. }) => {
-                write!(w, "<table>")?;
+            Element::Table(Table::Org {
+                has_header,
+                columns,
+                ..
+            }) => {
+                let number = node.and_then(|node| self.numbers.get(&node)).cloned();
+                match number.as_ref().and_then(|number| number.id.as_ref()) {
+                    Some(id) => write!(w, "<table id=\"{}\">", HtmlEscape(id))?,
+                    None => write!(w, "<table>")?,
+                }
+                if let (Some(number), Some(caption)) = (&number, affiliated.caption) {
+                    write!(w, "<caption>")?;
+                    self.write_caption(&mut w, &caption, Some(number))?;
+                    write!(w, "</caption>")?;
+                }
+                if columns.iter().any(|c| c.group_start || c.group_end) {
+                    let mut in_group = false;
+                    for column in columns {
+                        if column.group_start || !in_group {
+                            if in_group {
+                                write!(w, "</colgroup>")?;
+                            }
+                            write!(w, "<colgroup>")?;
+                            in_group = true;
+                        }
+                        match column.alignment {
+                            Some(alignment) => {
+                                write!(w, "<col class=\"{}\">", alignment.as_class())?
+                            }
+                            None => write!(w, "<col>")?,
+                        }
+                        if column.group_end {
+                            write!(w, "</colgroup>")?;
+                            in_group = false;
+                        }
+                    }
+                    if in_group {
+                        write!(w, "</colgroup>")?;
+                    }
+                }
                 if *has_header {
                     write!(w, "<thead>")?;
                 } else {
@@ -192,26 +1762,63 @@ impl HtmlHandler<Error> for DefaultHtmlHandler {
                 TableRow::Header => write!(w, "<tr>")?,
                 TableRow::HeaderRule => write!(w, "</thead><tbody>")?,
             },
-            Element::TableCell(cell) => match cell {
-                TableCell::Body => write!(w, "<td>")?,
-                TableCell::Header => write!(w, "<th>")?,
-            },
+            Element::TableCell(cell) => {
+                let tag = match cell {
+                    TableCell::Body { .. } => "td",
+                    TableCell::Header { .. } => "th",
+                };
+                match cell.alignment() {
+                    Some(alignment) => write!(w, "<{} class=\"{}\">", tag, alignment.as_class())?,
+                    None => write!(w, "<{}>", tag)?,
+                }
+            }
         }
 
         Ok(())
     }
 
-    fn end<W: Write>(&mut self, mut w: W, element: &Element) -> IOResult<()> {
+    fn write_end<W: Write>(
+        &mut self,
+        mut w: W,
+        node: Option<NodeId>,
+        element: &Element,
+    ) -> IOResult<()> {
+        if self.hidden > 0 {
+            self.hidden -= 1;
+            return Ok(());
+        }
+
         match element {
             // container elements
             Element::SpecialBlock(_) => (),
-            Element::QuoteBlock(_) => write!(w, "</blockquote>")?,
-            Element::CenterBlock(_) => write!(w, "</div>")?,
+            Element::QuoteBlock(_) => {
+                write!(w, "</blockquote>")?;
+                self.end_figure(&mut w)?;
+            }
+            Element::CenterBlock(_) => {
+                write!(w, "</div>")?;
+                self.end_figure(&mut w)?;
+            }
             Element::VerseBlock(_) => write!(w, "</p>")?,
             Element::Bold => write!(w, "</b>")?,
-            Element::Document { .. } => write!(w, "</main>")?,
+            Element::Document { .. } => {
+                if self.fn_open {
+                    write!(w, "</div>")?;
+                    self.fn_open = false;
+                }
+                if self.config.ox_html_structure {
+                    write!(w, "</div>")?;
+                } else {
+                    write!(w, "</main>")?;
+                }
+            }
             Element::DynBlock(_dyn_block) => (),
-            Element::Headline { .. } => (),
+            Element::Headline { .. } => {
+                if node.is_some_and(|node| self.headline_ids.contains_key(&node)) {
+                    write!(w, "</div>")?;
+                    self.outlines.pop();
+                }
+            }
             Element::List(list) => {
                 if list.ordered {
                     write!(w, "</ol>")?;
@@ -221,12 +1828,38 @@ impl HtmlHandler<Error> for DefaultHtmlHandler {
             }
             Element::Italic => write!(w, "</i>")?,
             Element::ListItem(_) => write!(w, "</li>")?,
-            Element::Paragraph { .. } => write!(w, "</p>")?,
+            Element::Paragraph { .. } => {
+                write!(w, "</p>")?;
+                if node.is_some_and(|node| self.numbers.contains_key(&node)) {
+                    self.end_figure(&mut w)?;
+                }
+            }
+            Element::RadioTarget => write!(w, "</a>")?,
+            Element::Section if node.is_some_and(|node| self.empty_sections.contains(&node)) => (),
+            Element::Section if self.config.ox_html_structure => {
+                if !self.outlines.is_empty() {
+                    write!(w, "</div>")?;
+                }
+            }
             Element::Section => write!(w, "</section>")?,
             Element::Strike => write!(w, "</s>")?,
             Element::Underline => write!(w, "</u>")?,
             Element::Title(title) => {
-                write!(w, "</h{}>", if title.level <= 6 { title.level } else { 6 })?
+                self.write_heading_tags(&mut w, title)?;
+                match self.outlines.last() {
+                    Some((level, _)) => write!(w, "</h{}>", (*level).min(6))?,
+                    None => write!(
+                        w,
+                        "</h{}>",
+                        (title.level + self.config.heading_offset).min(6)
+                    )?,
+                }
+                if let (Some(planning), true) = (&title.planning, self.config.planning) {
+                    self.write_planning(&mut w, planning)?;
+                }
+                if !title.properties.is_empty() && self.config.properties {
+                    self.write_properties(&mut w, &title.properties)?;
+                }
             }
             Element::Table(Table::TableEl { .. }) => (),
             Element::Table(Table::Org { .. }) => {
@@ -236,9 +1869,16 @@ impl HtmlHandler<Error> for DefaultHtmlHandler {
                 write!(w, "</tr>")?;
             }
             Element::TableCell(cell) => match cell {
-                TableCell::Body => write!(w, "</td>")?,
-                TableCell::Header => write!(w, "</th>")?,
+                TableCell::Body { .. } => write!(w, "</td>")?,
+                TableCell::Header { .. } => write!(w, "</th>")?,
             },
+            Element::FnDef(_) | Element::FnRef(_)
+                if node.is_some_and(|node| self.fn_written.contains_key(&node)) =>
+            {
+                write!(w, "</div></div>")?
+            }
+            Element::FnDef(_) => (),
+            Element::Drawer(_) => (),
             // non-container elements
             _ => debug_assert!(!element.is_container()),
         }
@@ -267,7 +1907,7 @@ mod syntect_handler {
     /// use orgize::Org;
     /// use orgize::export::{DefaultHtmlHandler, SyntectHtmlHandler};
     ///
-    /// let mut handler = SyntectHtmlHandler::new(DefaultHtmlHandler);
+    /// let mut handler = SyntectHtmlHandler::new(DefaultHtmlHandler::default());
     /// let org = Org::parse("src_rust{println!(\"Hello\")}");
     ///
     /// let mut vec = vec![];
@@ -292,7 +1932,7 @@ mod syntect_handler {
     ///     },
     ///     // specify theme
     ///     theme: String::from("Solarized (dark)"),
-    ///     inner: DefaultHtmlHandler,
+    ///     inner: DefaultHtmlHandler::default(),
     ///     ..Default::default()
     /// };
     ///
@@ -377,18 +2017,50 @@ mod syntect_handler {
                 Element::ExampleBlock(block) => write!(
                     w,
                     "<pre class=\"example\">{}</pre>",
-                    self.highlight(None, &block.contents)
+                    self.highlight(None, &strip_indentation(&block.contents))
                 )?,
                 _ => self.inner.start(w, element)?,
             }
             Ok(())
         }
 
+        fn prepare(&mut self, org: &Org) {
+            self.inner.prepare(org);
+        }
+
         fn end<W: Write>(&mut self, w: W, element: &Element) -> Result<(), E> {
             self.inner.end(w, element)
         }
+
+        fn start_node<W: Write>(&mut self, w: W, node: NodeId, element: &Element) -> Result<(), E> {
+            match element {
+                Element::InlineSrc(_)
+                | Element::SourceBlock(_)
+                | Element::FixedWidth(_)
+                | Element::ExampleBlock(_) => self.start(w, element),
+                _ => self.inner.start_node(w, node, element),
+            }
+        }
+
+        fn end_node<W: Write>(&mut self, w: W, node: NodeId, element: &Element) -> Result<(), E> {
+            self.inner.end_node(w, node, element)
+        }
+
+        fn trailing_nodes(&mut self, org: &Org, root: NodeId) -> Vec<NodeId> {
+            self.inner.trailing_nodes(org, root)
+        }
     }
 }
 
 #[cfg(feature = "syntect")]
 pub use syntect_handler::SyntectHtmlHandler;
+
+#[test]
+fn encode_base64() {
+    assert_eq!(base64(b""), "");
+    assert_eq!(base64(b"f"), "Zg==");
+    assert_eq!(base64(b"fo"), "Zm8=");
+    assert_eq!(base64(b"foo"), "Zm9v");
+    assert_eq!(base64(b"foobar"), "Zm9vYmFy");
+    assert_eq!(base64(&[0xff, 0xfe, 0x00]), "//4A");
+}
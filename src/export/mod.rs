@@ -1,9 +1,29 @@
 //! Export `Org` struct to various formats.
 
+mod backend;
+mod document;
+mod error;
+mod filter;
+mod formatter;
 mod html;
 mod org;
+mod pages;
+#[cfg(feature = "tokio")]
+mod stream;
+mod translations;
 
+pub use backend::Backend;
+pub use document::HtmlDocumentConfig;
+pub(crate) use document::{format_parts, FormatPart};
+pub use error::ExportError;
+pub use filter::FilterHandler;
+pub use formatter::{FormatConfig, FormatOrgHandler};
 #[cfg(feature = "syntect")]
 pub use html::SyntectHtmlHandler;
-pub use html::{DefaultHtmlHandler, HtmlEscape, HtmlHandler};
+pub use html::{
+    slug, DefaultHtmlHandler, EntityOutput, FootnoteOrder, HtmlConfig, HtmlEscape, HtmlHandler,
+    ImageConfig, LinkConfig,
+};
 pub use org::{DefaultOrgHandler, OrgHandler};
+pub use pages::Page;
+pub use translations::Translations;
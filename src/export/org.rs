@@ -1,40 +1,398 @@
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::io::{Error, Result as IOResult, Write};
 
-use crate::elements::{Clock, Element, Table};
+use indextree::NodeId;
+use unicode_width::UnicodeWidthStr;
 
+use crate::config::Startup;
+use crate::elements::{
+    list::{bullet_shift, renumbered_bullet, shift_indent, shift_lines},
+    table::parse_special_row,
+    title::parse_metadata,
+    Clock, Element, Planning, PropertiesMap, Table, TableAlignment, TableColumn, TableRow,
+    Timestamp, Title, DAY_NAMES,
+};
+use crate::export::Translations;
+use crate::Org;
+
+/// Writes elements as org format, used by `Org::write_org_custom`
+///
+/// Like `HtmlHandler`, a custom handler usually wraps `DefaultOrgHandler`,
+/// and delegates the elements it doesn't handle itself:
+///
+/// ```rust
+/// use std::io::{Error, Write};
+///
+/// use orgize::{
+///     elements::{Element, Title},
+///     export::{DefaultOrgHandler, OrgHandler},
+///     Org,
+/// };
+///
+/// #[derive(Default)]
+/// struct UppercaseKeywords(DefaultOrgHandler);
+///
+/// impl OrgHandler<Error> for UppercaseKeywords {
+///     fn prepare(&mut self, org: &Org) {
+///         self.0.prepare(org);
+///     }
+///
+///     fn start<W: Write>(&mut self, w: W, element: &Element) -> Result<(), Error> {
+///         match element {
+///             Element::Title(title) => {
+///                 let title = Title {
+///                     keyword: title.keyword.as_ref().map(|k| k.to_uppercase().into()),
+///                     ..title.clone()
+///                 };
+///                 self.0.start(w, &Element::Title(title))
+///             }
+///             _ => self.0.start(w, element),
+///         }
+///     }
+///
+///     fn end<W: Write>(&mut self, w: W, element: &Element) -> Result<(), Error> {
+///         self.0.end(w, element)
+///     }
+/// }
+/// ```
 pub trait OrgHandler<E: From<Error>>: Default {
+    /// Called with the whole document before writing it, e.g. for reading
+    /// its settings. Does nothing by default.
+    fn prepare(&mut self, _org: &Org) {}
     fn start<W: Write>(&mut self, w: W, element: &Element) -> Result<(), E>;
     fn end<W: Write>(&mut self, w: W, element: &Element) -> Result<(), E>;
+    /// Like `start`, called with the node of `element` when writing a
+    /// document. Calls `start` by default, handlers wrapping another one
+    /// should forward it.
+    fn start_node<W: Write>(&mut self, w: W, _node: NodeId, element: &Element) -> Result<(), E> {
+        self.start(w, element)
+    }
+    /// Like `end`, see `start_node`.
+    fn end_node<W: Write>(&mut self, w: W, _node: NodeId, element: &Element) -> Result<(), E> {
+        self.end(w, element)
+    }
 }
 
-#[derive(Default)]
-pub struct DefaultOrgHandler;
+/// Default Org Handler
+///
+/// Timestamps and tables that are untouched since parsing are written as their
+/// source text, others are rendered in canonical form with regenerated day names.
+/// Headline lines keep their source spacing unless they're modified, see
+/// `Title::raw_line`.
+pub struct DefaultOrgHandler {
+    /// Day names used when rendering timestamps, starting from Monday,
+    /// default is english
+    ///
+    /// Overridden by day names of the document language, i.e.
+    /// `#+LANGUAGE:`, if it's one of `translations`.
+    pub day_names: [String; 7],
+    /// Boilerplate strings keyed by the language of the document, default
+    /// is `Translations::builtin()`
+    pub translations: HashMap<String, Translations>,
+    /// Realigns org tables like `org-table-align`, default is `false`
+    ///
+    /// Columns are padded to their widest cell, rules are regenerated, and
+    /// cells wider than the column's `<N>` width cookie are truncated with
+    /// `=>`.
+    pub align_tables: bool,
+    /// Leaves out what Emacs leaves out when exporting to org, default is
+    /// `false`
+    ///
+    /// `COMMENT` headlines and headlines left out by `Org::export_tags`
+    /// are dropped along with their subtrees, so are comment lines and
+    /// comment blocks. Otherwise, everything is written back.
+    pub clean: bool,
+    /// Tags of subtrees dropped by `clean`, instead of the exclude tags of
+    /// `Org::export_tags`, default is `None`
+    pub exclude_tags: Option<Vec<String>>,
+    /// Drops `:ARCHIVE:` subtrees too when `clean` is set, default is `false`
+    pub exclude_archived: bool,
+    /// Rewrites list bullets like `List::renumber_with_bullet`, default is
+    /// `false`
+    pub renumber_lists: bool,
+    /// Bullet of unordered items when `renumber_lists` is set, default is
+    /// `-`
+    pub list_bullet: char,
+    /// Writes a byte order mark first if the parsed text starts with one,
+    /// default is `false`
+    pub keep_bom: bool,
+    // org table being written
+    table: Option<TableBuffer>,
+    // depth inside a subtree which isn't written
+    hidden: usize,
+    // titles of headlines left out by export tags
+    unselected: HashSet<NodeId>,
+    // numbers of the next items of open lists when renumbering
+    lists: Vec<usize>,
+    // shift of contents of open list items when renumbering
+    shifts: Vec<isize>,
+    // the last element is a list item
+    after_bullet: bool,
+    // the next text starts a line
+    line_start: bool,
+    // the last element is a footnote definition
+    after_fn_label: bool,
+    // `#+STARTUP:` options of the document being written
+    startup: Startup,
+    // content indentation of open list items
+    item_indents: Vec<usize>,
+    // day names of the language of the document being written
+    language_day_names: Option<[String; 7]>,
+    // source text after the raw text of the title being written, if its
+    // source line is kept
+    title_tail: Option<String>,
+    // the document being written starts with a byte order mark
+    bom: bool,
+}
+
+impl Default for DefaultOrgHandler {
+    fn default() -> Self {
+        DefaultOrgHandler {
+            day_names: DAY_NAMES.map(String::from),
+            translations: Translations::builtin(),
+            align_tables: false,
+            clean: false,
+            exclude_tags: None,
+            exclude_archived: false,
+            renumber_lists: false,
+            list_bullet: '-',
+            keep_bom: false,
+            table: None,
+            hidden: 0,
+            unselected: HashSet::new(),
+            lists: Vec::new(),
+            shifts: Vec::new(),
+            after_bullet: false,
+            line_start: false,
+            after_fn_label: false,
+            startup: Startup::default(),
+            item_indents: Vec::new(),
+            language_day_names: None,
+            title_tail: None,
+            bom: false,
+        }
+    }
+}
+
+impl DefaultOrgHandler {
+    /// Returns the number of stars of a headline at `level`, see
+    /// `Startup::odd_levels`.
+    pub(super) fn stars(&self, level: usize) -> usize {
+        self.startup.stars(level)
+    }
+
+    // splits the source line of `title` into the text before and after its
+    // raw text, if it still represents `title`
+    fn split_title_line<'t>(&self, title: &'t Title) -> Option<(&'t str, &'t str)> {
+        let line = title.raw_line.as_deref()?;
+
+        let rest = line.strip_prefix(&*"*".repeat(self.stars(title.level)))?;
+        if rest.starts_with('*') {
+            return None;
+        }
+        let mut rest = rest;
+        let words = title
+            .keyword
+            .as_deref()
+            .map(Cow::Borrowed)
+            .into_iter()
+            .chain(title.priority.map(|p| Cow::Owned(format!("[#{}]", p))));
+        for word in words {
+            let trimmed = rest.trim_start_matches([' ', '\t']);
+            if trimmed.len() == rest.len() {
+                return None;
+            }
+            rest = trimmed.strip_prefix(&*word)?;
+        }
+        let rest = rest.trim_start_matches([' ', '\t']);
+
+        let tail = rest.strip_prefix(&*title.raw)?;
+        let tags = tail.trim_matches([' ', '\t']);
+        let untouched = if title.tags.is_empty() {
+            tags.is_empty()
+        } else {
+            tags.starts_with(':')
+                && tags.ends_with(':')
+                && tags
+                    .split(':')
+                    .filter(|tag| !tag.is_empty())
+                    .eq(title.tags.iter().map(|tag| &**tag))
+        };
+        if untouched && (title.raw.is_empty() || tail.is_empty() || tail.starts_with([' ', '\t'])) {
+            Some((&line[0..line.len() - rest.len()], tail))
+        } else {
+            None
+        }
+    }
+
+    pub(super) fn write_timestamp<W: Write>(
+        &self,
+        mut w: W,
+        timestamp: &Timestamp,
+    ) -> IOResult<()> {
+        match timestamp.raw() {
+            Some(raw) => write!(w, "{}", raw),
+            None => write!(
+                w,
+                "{}",
+                timestamp.to_org_string_with_day_names(
+                    self.language_day_names.as_ref().unwrap_or(&self.day_names)
+                )
+            ),
+        }
+    }
+
+    pub(super) fn write_planning<W: Write>(&self, mut w: W, planning: &Planning) -> IOResult<()> {
+        if let Some(scheduled) = &planning.scheduled {
+            write!(&mut w, "SCHEDULED: ")?;
+            self.write_timestamp(&mut w, scheduled)?;
+        }
+        if let Some(deadline) = &planning.deadline {
+            if planning.scheduled.is_some() {
+                write!(&mut w, " ")?;
+            }
+            write!(&mut w, "DEADLINE: ")?;
+            self.write_timestamp(&mut w, deadline)?;
+        }
+        if let Some(closed) = &planning.closed {
+            if planning.scheduled.is_some() || planning.deadline.is_some() {
+                write!(&mut w, " ")?;
+            }
+            write!(&mut w, "CLOSED: ")?;
+            self.write_timestamp(&mut w, closed)?;
+        }
+        writeln!(&mut w)
+    }
+
+    // writes the indentation of contents of the innermost list item, where
+    // lines of elements inside the item start
+    fn write_indent<W: Write>(&self, mut w: W) -> IOResult<()> {
+        let indent = self.item_indents.last().copied().unwrap_or(0);
+        write!(w, "{:1$}", "", indent)
+    }
+
+    fn is_excluded(&self, node: Option<NodeId>, element: &Element) -> bool {
+        if !self.clean {
+            return false;
+        }
+
+        match element {
+            Element::Comment(_) | Element::CommentBlock(_) => true,
+            Element::Title(title) => {
+                title.is_commented()
+                    || (self.exclude_archived && title.is_archived())
+                    || node.is_some_and(|node| self.unselected.contains(&node))
+            }
+            _ => false,
+        }
+    }
+}
 
 impl OrgHandler<Error> for DefaultOrgHandler {
-    fn start<W: Write>(&mut self, mut w: W, element: &Element) -> IOResult<()> {
+    fn prepare(&mut self, org: &Org) {
+        self.startup = org.startup().clone();
+        self.bom = org.has_bom();
+        self.language_day_names = org.metadata().language.and_then(|language| {
+            Translations::lookup(&self.translations, &language)
+                .map(|strings| strings.day_names.clone())
+        });
+
+        let mut tags = org.export_tags();
+        if let Some(exclude) = &self.exclude_tags {
+            tags.exclude = exclude.iter().map(String::as_str).collect();
+        }
+        let unselected = org
+            .unselected_headlines(&tags)
+            .into_iter()
+            .filter_map(|headline| org.arena[headline].first_child())
+            .collect();
+        self.unselected = unselected;
+    }
+
+    fn start<W: Write>(&mut self, w: W, element: &Element) -> IOResult<()> {
+        self.write_start(w, None, element)
+    }
+
+    fn end<W: Write>(&mut self, w: W, element: &Element) -> IOResult<()> {
+        self.write_end(w, element)
+    }
+
+    fn start_node<W: Write>(&mut self, w: W, node: NodeId, element: &Element) -> IOResult<()> {
+        self.write_start(w, Some(node), element)
+    }
+}
+
+impl DefaultOrgHandler {
+    // `node` is `None` if the handler is called through `start`
+    pub(crate) fn write_start<W: Write>(
+        &mut self,
+        mut w: W,
+        node: Option<NodeId>,
+        element: &Element,
+    ) -> IOResult<()> {
+        if self.hidden > 0 {
+            self.hidden += 1;
+            return Ok(());
+        }
+
+        if self.is_excluded(node, element) {
+            // the title is the first child of its headline, hides both
+            if let Element::Title(_) = element {
+                self.hidden = 2;
+            }
+            return Ok(());
+        }
+
+        // table cells are buffered until the end of table
+        if let Some(mut table) = self.table.take() {
+            match element {
+                Element::TableRow(TableRow::Header) | Element::TableRow(TableRow::Body) => {
+                    table.rows.push(Row::Cells(Vec::new()))
+                }
+                Element::TableRow(_) => table.rows.push(Row::Rule),
+                Element::TableCell(_) => table.cell.clear(),
+                _ => self.write_start(&mut table.cell, node, element)?,
+            }
+            self.table = Some(table);
+            return Ok(());
+        }
+
+        let after_bullet = std::mem::replace(&mut self.after_bullet, false);
+        let line_start = std::mem::replace(&mut self.line_start, false);
+        self.after_fn_label = false;
+
         match element {
             // container elements
             Element::SpecialBlock(block) => {
+                self.write_indent(&mut w)?;
                 writeln!(w, "#+BEGIN_{}", block.name)?;
                 write_blank_lines(&mut w, block.pre_blank)?;
             }
             Element::QuoteBlock(block) => {
+                self.write_indent(&mut w)?;
                 writeln!(&mut w, "#+BEGIN_QUOTE")?;
                 write_blank_lines(&mut w, block.pre_blank)?;
             }
             Element::CenterBlock(block) => {
+                self.write_indent(&mut w)?;
                 writeln!(&mut w, "#+BEGIN_CENTER")?;
                 write_blank_lines(&mut w, block.pre_blank)?;
             }
             Element::VerseBlock(block) => {
+                self.write_indent(&mut w)?;
                 writeln!(&mut w, "#+BEGIN_VERSE")?;
                 write_blank_lines(&mut w, block.pre_blank)?;
             }
             Element::Bold => write!(w, "*")?,
             Element::Document { pre_blank } => {
+                if self.keep_bom && self.bom {
+                    write!(w, "\u{feff}")?;
+                }
                 write_blank_lines(w, *pre_blank)?;
             }
             Element::DynBlock(dyn_block) => {
+                self.write_indent(&mut w)?;
                 write!(&mut w, "#+BEGIN: {}", dyn_block.block_name)?;
                 if let Some(parameters) = &dyn_block.arguments {
                     write!(&mut w, " {}", parameters)?;
@@ -42,48 +400,92 @@ impl OrgHandler<Error> for DefaultOrgHandler {
                 write_blank_lines(&mut w, dyn_block.pre_blank + 1)?;
             }
             Element::Headline { .. } => (),
-            Element::List(_list) => (),
+            Element::List(_list) => {
+                if self.renumber_lists {
+                    self.lists.push(1);
+                }
+            }
             Element::Italic => write!(w, "/")?,
             Element::ListItem(list_item) => {
-                for _ in 0..list_item.indent {
+                let mut indent = list_item.indent;
+                let mut bullet = Cow::Borrowed(&*list_item.bullet);
+                if self.renumber_lists {
+                    let shift = self.shifts.last().copied().unwrap_or(0);
+                    indent = shift_indent(indent, shift);
+                    if let Some(number) = self.lists.last_mut() {
+                        bullet = Cow::Owned(renumbered_bullet(list_item, number, self.list_bullet));
+                    }
+                    self.shifts
+                        .push(shift + bullet_shift(&list_item.bullet, &bullet));
+                    self.after_bullet = true;
+                }
+                for _ in 0..indent {
                     write!(&mut w, " ")?;
                 }
-                write!(&mut w, "{}", list_item.bullet)?;
+                write!(&mut w, "{}", bullet)?;
+                self.item_indents.push(indent + bullet.chars().count());
+                if let Some(counter) = list_item.counter {
+                    write!(&mut w, "[@{}] ", counter)?;
+                }
+                if let Some(checkbox) = list_item.checkbox {
+                    let separator = list_item.checkbox_separator.unwrap_or(' ');
+                    write!(&mut w, "{}{}", checkbox.as_str(), separator)?;
+                }
             }
-            Element::Paragraph { .. } => (),
+            Element::Paragraph { .. } => self.line_start = !after_bullet,
             Element::Section => (),
             Element::Strike => write!(w, "+")?,
             Element::Underline => write!(w, "_")?,
             Element::Drawer(drawer) => {
+                self.write_indent(&mut w)?;
                 writeln!(&mut w, ":{}:", drawer.name)?;
                 write_blank_lines(&mut w, drawer.pre_blank)?;
             }
             // non-container elements
             Element::CommentBlock(block) => {
+                self.write_indent(&mut w)?;
                 writeln!(&mut w, "#+BEGIN_COMMENT")?;
                 write!(&mut w, "{}", block.contents)?;
+                self.write_indent(&mut w)?;
                 writeln!(&mut w, "#+END_COMMENT")?;
                 write_blank_lines(&mut w, block.post_blank)?;
             }
             Element::ExampleBlock(block) => {
-                writeln!(&mut w, "#+BEGIN_EXAMPLE")?;
+                self.write_indent(&mut w)?;
+                write!(&mut w, "#+BEGIN_EXAMPLE")?;
+                if let Some(data) = &block.data {
+                    write!(&mut w, " {}", data)?;
+                }
+                writeln!(&mut w)?;
                 write!(&mut w, "{}", block.contents)?;
+                self.write_indent(&mut w)?;
                 writeln!(&mut w, "#+END_EXAMPLE")?;
                 write_blank_lines(&mut w, block.post_blank)?;
             }
             Element::ExportBlock(block) => {
+                self.write_indent(&mut w)?;
                 writeln!(&mut w, "#+BEGIN_EXPORT {}", block.data)?;
                 write!(&mut w, "{}", block.contents)?;
+                self.write_indent(&mut w)?;
                 writeln!(&mut w, "#+END_EXPORT")?;
                 write_blank_lines(&mut w, block.post_blank)?;
             }
             Element::SourceBlock(block) => {
-                writeln!(&mut w, "#+BEGIN_SRC {}", block.language)?;
+                self.write_indent(&mut w)?;
+                write!(&mut w, "#+BEGIN_SRC")?;
+                for part in [&block.language, &block.switches, &block.arguments] {
+                    if !part.is_empty() {
+                        write!(&mut w, " {}", part)?;
+                    }
+                }
+                writeln!(&mut w)?;
                 write!(&mut w, "{}", block.contents)?;
+                self.write_indent(&mut w)?;
                 writeln!(&mut w, "#+END_SRC")?;
                 write_blank_lines(&mut w, block.post_blank)?;
             }
             Element::BabelCall(call) => {
+                self.write_indent(&mut w)?;
                 writeln!(&mut w, "#+CALL: {}", call.value)?;
                 write_blank_lines(w, call.post_blank)?;
             }
@@ -119,19 +521,32 @@ impl OrgHandler<Error> for DefaultOrgHandler {
                 }
                 write!(&mut w, "]")?;
             }
-            Element::Macros(_macros) => (),
-            Element::RadioTarget => (),
+            Element::Macros(macros) => {
+                write!(&mut w, "{{{{{{{}", macros.name)?;
+                if let Some(arguments) = &macros.arguments {
+                    write!(&mut w, "({})", arguments)?;
+                }
+                write!(&mut w, "}}}}}}")?;
+            }
+            Element::RadioTarget => write!(w, "<<<")?,
             Element::Snippet(snippet) => write!(w, "@@{}:{}@@", snippet.name, snippet.value)?,
-            Element::Target(_target) => (),
-            Element::Text { value } => write!(w, "{}", value)?,
+            Element::Target(target) => write!(w, "<<{}>>", target.target)?,
+            Element::Text { value } => match self.shifts.last() {
+                Some(&shift) if shift != 0 => {
+                    write!(w, "{}", shift_lines(value, line_start, shift))?
+                }
+                _ => write!(w, "{}", value)?,
+            },
             Element::Timestamp(timestamp) => {
-                write!(&mut w, "{}", timestamp)?;
+                self.write_timestamp(&mut w, timestamp)?;
             }
             Element::Verbatim { value } => write!(w, "={}=", value)?,
             Element::FnDef(fn_def) => {
-                write_blank_lines(w, fn_def.post_blank)?;
+                write!(w, "[fn:{}] ", fn_def.label)?;
+                self.after_fn_label = true;
             }
             Element::Clock(clock) => {
+                self.write_indent(&mut w)?;
                 write!(w, "CLOCK: ")?;
 
                 match clock {
@@ -155,13 +570,20 @@ impl OrgHandler<Error> for DefaultOrgHandler {
             }
             Element::Comment(comment) => {
                 write!(w, "{}", comment.value)?;
+                if !comment.value.ends_with('\n') {
+                    writeln!(w)?;
+                }
                 write_blank_lines(&mut w, comment.post_blank)?;
             }
             Element::FixedWidth(fixed_width) => {
                 write!(&mut w, "{}", fixed_width.value)?;
+                if !fixed_width.value.ends_with('\n') {
+                    writeln!(w)?;
+                }
                 write_blank_lines(&mut w, fixed_width.post_blank)?;
             }
             Element::Keyword(keyword) => {
+                self.write_indent(&mut w)?;
                 write!(&mut w, "#+{}", keyword.key)?;
                 if let Some(optional) = &keyword.optional {
                     write!(&mut w, "[{}]", optional)?;
@@ -170,114 +592,193 @@ impl OrgHandler<Error> for DefaultOrgHandler {
                 write_blank_lines(&mut w, keyword.post_blank)?;
             }
             Element::Rule(rule) => {
+                self.write_indent(&mut w)?;
                 writeln!(w, "-----")?;
                 write_blank_lines(&mut w, rule.post_blank)?;
             }
-            Element::Cookie(_cookie) => (),
-            Element::Title(title) => {
-                for _ in 0..title.level {
-                    write!(&mut w, "*")?;
+            Element::Custom(custom) => {
+                write!(&mut w, "{}", custom.raw)?;
+                if !custom.raw.ends_with('\n') {
+                    writeln!(w)?;
                 }
-                if let Some(keyword) = &title.keyword {
-                    write!(&mut w, " {}", keyword)?;
+                write_blank_lines(&mut w, custom.post_blank)?;
+            }
+            Element::Skipped(skipped) => {
+                write!(&mut w, "{}", skipped.raw)?;
+                if !skipped.raw.ends_with('\n') {
+                    writeln!(w)?;
                 }
-                if let Some(priority) = title.priority {
-                    write!(&mut w, " [#{}]", priority)?;
+                write_blank_lines(&mut w, skipped.post_blank)?;
+            }
+            Element::Cookie(cookie) => write!(w, "{}", cookie.value)?,
+            Element::Title(title) => match self.split_title_line(title) {
+                Some((head, tail)) => {
+                    write!(&mut w, "{}", head)?;
+                    self.title_tail = Some(tail.to_string());
                 }
-                write!(&mut w, " ")?;
+                None => {
+                    for _ in 0..self.stars(title.level) {
+                        write!(&mut w, "*")?;
+                    }
+                    if let Some(keyword) = &title.keyword {
+                        write!(&mut w, " {}", keyword)?;
+                    }
+                    if let Some(priority) = title.priority {
+                        write!(&mut w, " [#{}]", priority)?;
+                    }
+                    write!(&mut w, " ")?;
+                }
+            },
+            Element::Table(Table::TableEl { value, .. }) => write!(w, "{}", value)?,
+            Element::Table(Table::Org { columns, raw, .. }) => {
+                self.table = Some(TableBuffer {
+                    indent: self.item_indents.last().copied().unwrap_or(0),
+                    columns: columns.clone(),
+                    raw: raw.as_deref().map(Into::into),
+                    rows: Vec::new(),
+                    cell: Vec::new(),
+                });
             }
-            Element::Table(_) => (),
-            Element::TableRow(_) => (),
-            Element::TableCell(_) => (),
+            Element::TableRow(_) | Element::TableCell(_) => (),
         }
 
         Ok(())
     }
 
-    fn end<W: Write>(&mut self, mut w: W, element: &Element) -> IOResult<()> {
+    fn write_end<W: Write>(&mut self, mut w: W, element: &Element) -> IOResult<()> {
+        if self.hidden > 0 {
+            self.hidden -= 1;
+            return Ok(());
+        }
+
+        if let Some(mut table) = self.table.take() {
+            match element {
+                Element::Table(_) => table.write(&mut w, self.align_tables)?,
+                Element::TableRow(_) => (),
+                Element::TableCell(_) => {
+                    let cell = String::from_utf8_lossy(&table.cell).trim().to_string();
+                    if let Some(Row::Cells(cells)) = table.rows.last_mut() {
+                        cells.push(cell);
+                    }
+                }
+                _ => self.write_end(&mut table.cell, element)?,
+            }
+            if !matches!(element, Element::Table(_)) {
+                self.table = Some(table);
+                return Ok(());
+            }
+        }
+
         match element {
             // container elements
             Element::SpecialBlock(block) => {
+                self.write_indent(&mut w)?;
                 writeln!(&mut w, "#+END_{}", block.name)?;
                 write_blank_lines(&mut w, block.post_blank)?;
             }
             Element::QuoteBlock(block) => {
+                self.write_indent(&mut w)?;
                 writeln!(&mut w, "#+END_QUOTE")?;
                 write_blank_lines(&mut w, block.post_blank)?;
             }
             Element::CenterBlock(block) => {
+                self.write_indent(&mut w)?;
                 writeln!(&mut w, "#+END_CENTER")?;
                 write_blank_lines(&mut w, block.post_blank)?;
             }
             Element::VerseBlock(block) => {
+                self.write_indent(&mut w)?;
                 writeln!(&mut w, "#+END_VERSE")?;
                 write_blank_lines(&mut w, block.post_blank)?;
             }
             Element::Bold => write!(w, "*")?,
             Element::Document { .. } => (),
             Element::DynBlock(dyn_block) => {
+                self.write_indent(&mut w)?;
                 writeln!(w, "#+END:")?;
                 write_blank_lines(w, dyn_block.post_blank)?;
             }
             Element::Headline { .. } => (),
             Element::List(list) => {
+                if self.renumber_lists {
+                    self.lists.pop();
+                }
                 write_blank_lines(w, list.post_blank)?;
             }
             Element::Italic => write!(w, "/")?,
-            Element::ListItem(_) => (),
+            Element::ListItem(item) => {
+                if self.renumber_lists {
+                    self.shifts.pop();
+                }
+                self.item_indents.pop();
+                write_blank_lines(w, item.post_blank)?;
+            }
             Element::Paragraph { post_blank } => {
                 write_blank_lines(w, post_blank + 1)?;
             }
+            Element::RadioTarget => write!(w, ">>>")?,
             Element::Section => (),
             Element::Strike => write!(w, "+")?,
             Element::Underline => write!(w, "_")?,
             Element::Drawer(drawer) => {
+                self.write_indent(&mut w)?;
                 writeln!(&mut w, ":END:")?;
                 write_blank_lines(&mut w, drawer.post_blank)?;
             }
             Element::Title(title) => {
-                if !title.tags.is_empty() {
-                    write!(&mut w, " :")?;
-                    for tag in &title.tags {
-                        write!(&mut w, "{}:", tag)?;
+                match self.title_tail.take() {
+                    Some(tail) => write!(&mut w, "{}", tail)?,
+                    None if !title.tags.is_empty() => {
+                        // the space after stars is already written
+                        if !title.raw.is_empty() {
+                            write!(&mut w, " ")?;
+                        }
+                        write!(&mut w, ":")?;
+                        for tag in &title.tags {
+                            write!(&mut w, "{}:", tag)?;
+                        }
                     }
+                    None => (),
                 }
                 writeln!(&mut w)?;
-                if let Some(planning) = &title.planning {
-                    if let Some(scheduled) = &planning.scheduled {
-                        write!(&mut w, "SCHEDULED: {}", &scheduled)?;
-                    }
-                    if let Some(deadline) = &planning.deadline {
-                        if planning.scheduled.is_some() {
-                            write!(&mut w, " ")?;
+                match title.raw_metadata.as_deref() {
+                    Some(raw) if is_metadata_untouched(raw, title) => {
+                        write!(&mut w, "{}", raw)?;
+                        if !raw.ends_with('\n') {
+                            writeln!(&mut w)?;
                         }
-                        write!(&mut w, "DEADLINE: {}", &deadline)?;
                     }
-                    if let Some(closed) = &planning.closed {
-                        if planning.deadline.is_some() {
-                            write!(&mut w, " ")?;
+                    _ => {
+                        if let Some(planning) = &title.planning {
+                            self.write_planning(&mut w, planning)?;
+                        }
+                        if !title.properties.is_empty() {
+                            write_properties(&mut w, &title.properties)?;
                         }
-                        write!(&mut w, "CLOSED: {}", &closed)?;
-                    }
-                    writeln!(&mut w)?;
-                }
-                if !title.properties.is_empty() {
-                    writeln!(&mut w, ":PROPERTIES:")?;
-                    for (key, value) in title.properties.iter() {
-                        writeln!(&mut w, ":{}: {}", key, value)?;
                     }
-                    writeln!(&mut w, ":END:")?;
                 }
                 write_blank_lines(&mut w, title.post_blank)?;
             }
-            Element::Table(Table::Org { post_blank, .. }) => {
+            Element::Table(Table::Org {
+                tblfm, post_blank, ..
+            }) => {
+                for formulas in tblfm.iter().flat_map(|tblfm| tblfm.lines()) {
+                    self.write_indent(&mut w)?;
+                    writeln!(&mut w, "#+TBLFM: {}", formulas)?;
+                }
                 write_blank_lines(w, *post_blank)?;
             }
             Element::Table(Table::TableEl { post_blank, .. }) => {
                 write_blank_lines(w, *post_blank)?;
             }
-            Element::TableRow(_) => (),
-            Element::TableCell(_) => (),
+            Element::TableRow(_) | Element::TableCell(_) => (),
+            Element::FnDef(fn_def) => {
+                // ends the line of a definition without contents
+                if std::mem::take(&mut self.after_fn_label) {
+                    writeln!(w)?;
+                }
+                write_blank_lines(w, fn_def.post_blank)?;
+            }
             // non-container elements
             _ => debug_assert!(!element.is_container()),
         }
@@ -286,9 +787,343 @@ impl OrgHandler<Error> for DefaultOrgHandler {
     }
 }
 
+// writes the property drawer in its original indentation and alignment
+fn write_properties<W: Write>(mut w: W, properties: &PropertiesMap) -> Result<(), Error> {
+    let indent = &properties.indent;
+    writeln!(w, "{}:PROPERTIES:", indent)?;
+    for (key, value) in properties.iter() {
+        let width = key.chars().count() + 2;
+        let padding = match properties.value_column {
+            Some(column) if column > width && !value.is_empty() => column - width,
+            _ => 1,
+        };
+        writeln!(w, "{}:{}:{:4$}{}", indent, key, "", value, padding)?;
+    }
+    writeln!(w, "{}:END:", indent)
+}
+
 fn write_blank_lines<W: Write>(mut w: W, count: usize) -> Result<(), Error> {
     for _ in 0..count {
         writeln!(w)?;
     }
     Ok(())
 }
+
+// `raw` still represents planning and properties of `title`
+fn is_metadata_untouched(raw: &str, title: &Title) -> bool {
+    let (_, (planning, properties, _)) = parse_metadata(raw, Some(usize::MAX));
+    planning.as_ref() == title.planning.as_deref()
+        && properties.unwrap_or_default() == title.properties
+}
+
+/// Org table being written
+struct TableBuffer {
+    // indentation of rows
+    indent: usize,
+    columns: Vec<TableColumn>,
+    raw: Option<String>,
+    rows: Vec<Row>,
+    cell: Vec<u8>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Row {
+    Rule,
+    Cells(Vec<String>),
+}
+
+impl TableBuffer {
+    fn write<W: Write>(&self, mut w: W, align: bool) -> IOResult<()> {
+        if align {
+            return self.write_aligned(w);
+        }
+
+        let raw = self.raw.as_deref().map(parse_raw_table);
+        if let (Some(raw), Some(text)) = (&raw, &self.raw) {
+            if raw.rows == self.rows && raw.columns == self.columns {
+                write!(w, "{}", text)?;
+                if !text.ends_with('\n') {
+                    writeln!(w)?;
+                }
+                return Ok(());
+            }
+        }
+
+        // rules are written as-is while the rows between them are kept
+        let count = self.rows.iter().filter(|row| **row == Row::Rule).count();
+        let (leading, inner, trailing) = match &raw {
+            Some(raw) if raw.rules.len() == count => {
+                (raw.leading_rule, raw.rules.clone(), raw.trailing_rule)
+            }
+            _ => (None, Vec::new(), None),
+        };
+
+        if let Some(rule) = leading {
+            writeln!(w, "{:1$}{2}", "", self.indent, rule)?;
+        }
+        let mut inner = inner.into_iter();
+        for row in &self.rows_with_specials(raw.as_ref()) {
+            match row {
+                Row::Rule => {
+                    let rule = inner.next().unwrap_or("|-");
+                    writeln!(w, "{:1$}{2}", "", self.indent, rule)?
+                }
+                Row::Cells(cells) => {
+                    write!(w, "{:1$}|", "", self.indent)?;
+                    for cell in cells {
+                        write!(w, " {} |", cell)?;
+                    }
+                    writeln!(w)?;
+                }
+            }
+        }
+        if let Some(rule) = trailing {
+            writeln!(w, "{:1$}{2}", "", self.indent, rule)?;
+        }
+
+        Ok(())
+    }
+
+    fn write_aligned<W: Write>(&self, mut w: W) -> IOResult<()> {
+        let special_rows = self.special_rows();
+
+        let cells = |rows: &'_ [Row]| -> Vec<Vec<String>> {
+            rows.iter()
+                .filter_map(|row| match row {
+                    Row::Cells(cells) => Some(cells.clone()),
+                    Row::Rule => None,
+                })
+                .collect()
+        };
+        let data = cells(&self.rows);
+        let special = cells(&special_rows);
+
+        let len = data.iter().chain(&special).map(Vec::len).max().unwrap_or(0);
+
+        let mut widths = vec![1; len];
+        let mut alignments = vec![TableAlignment::Left; len];
+
+        for (i, (width, alignment)) in widths.iter_mut().zip(&mut alignments).enumerate() {
+            let column = self.columns.get(i);
+
+            let cells = || {
+                data.iter()
+                    .filter_map(|row| row.get(i))
+                    .filter(|c| !c.is_empty())
+            };
+
+            *width = cells().map(|cell| cell.width()).max().unwrap_or(1);
+            if let Some(max) = column.and_then(|column| column.width) {
+                *width = (*width).min(max.max(2));
+            }
+            for row in &special {
+                *width = (*width).max(row.get(i).map_or(0, |cell| cell.width()));
+            }
+
+            let numbers = cells().filter(|cell| is_number(cell)).count();
+            *alignment = match column.and_then(|column| column.alignment) {
+                Some(alignment) => alignment,
+                // like org-table-number-fraction
+                None if numbers * 2 > cells().count() => TableAlignment::Right,
+                None => TableAlignment::Left,
+            };
+        }
+
+        let raw = self.raw.as_deref().map(parse_raw_table);
+        for row in &self.rows_with_specials(raw.as_ref()) {
+            match row {
+                Row::Rule => {
+                    write!(w, "{:1$}|", "", self.indent)?;
+                    for (i, width) in widths.iter().enumerate() {
+                        if i > 0 {
+                            write!(w, "+")?;
+                        }
+                        write!(w, "{}", "-".repeat(width + 2))?;
+                    }
+                    writeln!(w, "|")?;
+                }
+                Row::Cells(cells) => {
+                    write!(w, "{:1$}|", "", self.indent)?;
+                    for (i, (&width, &alignment)) in widths.iter().zip(&alignments).enumerate() {
+                        let cell = cells.get(i).map_or("", |cell| cell.as_str());
+                        let cell = truncate(cell, width);
+                        let padding = width.saturating_sub(cell.width());
+                        let (left, right) = match alignment {
+                            TableAlignment::Left => (0, padding),
+                            TableAlignment::Right => (padding, 0),
+                            TableAlignment::Center => (padding / 2, padding - padding / 2),
+                        };
+                        write!(w, " {}{}{} |", " ".repeat(left), cell, " ".repeat(right))?;
+                    }
+                    writeln!(w)?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    // all rows, with special rows where they are in the source if columns
+    // are unchanged, or else at the top
+    fn rows_with_specials(&self, raw: Option<&RawTable>) -> Vec<Row> {
+        let specials = match raw {
+            Some(raw) if raw.columns == self.columns => &raw.specials,
+            _ => {
+                return self
+                    .special_rows()
+                    .into_iter()
+                    .chain(self.rows.clone())
+                    .collect()
+            }
+        };
+
+        let mut rows = Vec::new();
+        for (i, row) in self.rows.iter().enumerate() {
+            rows.extend(
+                specials
+                    .iter()
+                    .filter(|(position, _)| *position == i)
+                    .map(|(_, cells)| Row::Cells(cells.clone())),
+            );
+            rows.push(row.clone());
+        }
+        rows.extend(
+            specials
+                .iter()
+                .filter(|(position, _)| *position >= self.rows.len())
+                .map(|(_, cells)| Row::Cells(cells.clone())),
+        );
+        rows
+    }
+
+    // cookie row and column group row
+    fn special_rows(&self) -> Vec<Row> {
+        let mut rows = Vec::new();
+        if self.columns.iter().any(|column| column.cookie().is_some()) {
+            rows.push(Row::Cells(
+                self.columns
+                    .iter()
+                    .map(|column| column.cookie().unwrap_or_default())
+                    .collect(),
+            ));
+        }
+        if self
+            .columns
+            .iter()
+            .any(|column| column.group_start || column.group_end)
+        {
+            rows.push(Row::Cells(
+                std::iter::once(String::from("/"))
+                    .chain(
+                        self.columns
+                            .iter()
+                            .skip(1)
+                            .map(|column| column.group_marker().into()),
+                    )
+                    .collect(),
+            ));
+        }
+        rows
+    }
+}
+
+// table source text split into rows, just like the parser does
+struct RawTable<'r> {
+    rows: Vec<Row>,
+    columns: Vec<TableColumn>,
+    // cells of special rows, with the number of rows before them
+    specials: Vec<(usize, Vec<String>)>,
+    // rule before the first row, which isn't a row of the table
+    leading_rule: Option<&'r str>,
+    // rules between rows, in order
+    rules: Vec<&'r str>,
+    // rule after the last row, which isn't a row of the table either
+    trailing_rule: Option<&'r str>,
+}
+
+fn parse_raw_table(raw: &str) -> RawTable<'_> {
+    let mut table = RawTable {
+        rows: Vec::new(),
+        columns: Vec::new(),
+        specials: Vec::new(),
+        leading_rule: None,
+        rules: Vec::new(),
+        trailing_rule: None,
+    };
+
+    // `None` for special rows
+    let mut lines = Vec::new();
+    for line in raw.trim_end().lines().map(str::trim_start) {
+        let fields: Vec<_> = fields(line).collect();
+        if !line.starts_with("|-") && parse_special_row(&fields, &mut table.columns) {
+            let cells = fields.into_iter().map(Into::into).collect();
+            table.specials.push((lines.iter().flatten().count(), cells));
+            lines.push(None);
+        } else {
+            lines.push(Some(line));
+        }
+    }
+
+    let first = lines.iter().position(Option::is_some);
+    let last = lines.iter().rposition(Option::is_some);
+    let mut skipped = 0;
+    for (i, line) in lines.into_iter().enumerate() {
+        let line = match line {
+            Some(line) => line,
+            None => continue,
+        };
+        if line.starts_with("|-") {
+            let rule = line.trim_end();
+            if Some(i) == first {
+                table.leading_rule = Some(rule);
+                skipped = 1;
+                continue;
+            } else if Some(i) == last {
+                table.trailing_rule = Some(rule);
+                continue;
+            }
+            table.rules.push(rule);
+            table.rows.push(Row::Rule);
+        } else {
+            table
+                .rows
+                .push(Row::Cells(fields(line).map(Into::into).collect()));
+        }
+    }
+    // positions of special rows don't count the leading rule
+    for (position, _) in &mut table.specials {
+        *position = position.saturating_sub(skipped);
+    }
+
+    table
+}
+
+fn fields(line: &str) -> impl Iterator<Item = &str> {
+    line.split_terminator('|').skip(1).map(str::trim)
+}
+
+fn is_number(cell: &str) -> bool {
+    cell.bytes().any(|c| c.is_ascii_digit())
+        && cell
+            .bytes()
+            .all(|c| c.is_ascii_digit() || matches!(c, b'+' | b'-' | b'.' | b'e' | b'E' | b'%'))
+}
+
+// truncates cell wider than `width` with `=>`
+fn truncate(cell: &str, width: usize) -> String {
+    if cell.width() <= width {
+        return cell.into();
+    }
+    let mut truncated = String::new();
+    for c in cell.chars() {
+        if truncated.width() + c.to_string().width() > width - 2 {
+            break;
+        }
+        truncated.push(c);
+    }
+    for _ in truncated.width()..width - 2 {
+        truncated.push(' ');
+    }
+    truncated.push_str("=>");
+    truncated
+}
@@ -0,0 +1,248 @@
+use std::collections::{HashMap, HashSet};
+use std::io::{Error, Write};
+use std::sync::Arc;
+
+use indextree::NodeId;
+
+use crate::{
+    export::{
+        html::{headline_ids, target_ids, unique_id},
+        slug, ExportError, HtmlDocumentConfig, HtmlEscape, HtmlHandler, LinkConfig, Translations,
+    },
+    Element, Event, Headline, Org,
+};
+
+/// Html page written by `Org::export_pages`
+#[derive(Debug, Clone)]
+pub struct Page {
+    /// File name, e.g. `post.html`
+    pub file_name: String,
+    /// Page title
+    pub title: String,
+    /// Headline the page is exported from, `None` for the index page
+    pub headline: Option<Headline>,
+    /// Standalone html document of the page
+    pub html: String,
+}
+
+impl Org<'_> {
+    /// Splits the document at headlines of `level`, and exports each of them
+    /// as a standalone html document, see `Org::write_html_document`.
+    ///
+    /// + Each page is titled after its headline, and written with its
+    ///   section and subheadlines. Author, description and language come
+    ///   from the document, see `Org::metadata`.
+    /// + Page files are named after the `:EXPORT_FILE_NAME:` property, or the
+    ///   slug of the headline title, with a `.html` extension.
+    /// + Links to headlines, custom ids and targets of other pages, like
+    ///   `[[*Heading]]`, are rewritten to `file.html#anchor`, where `anchor`
+    ///   is the id `DefaultHtmlHandler` writes on the heading or target.
+    /// + The last page is `index.html`, which contains the zeroth section,
+    ///   the titles and sections of headlines outside of all pages, e.g.
+    ///   the ones shallower than `level`, and a list of links to all pages.
+    ///
+    /// `handler` is called with the `LinkConfig` rewriting links for every
+    /// page. `toc` of `config` is ignored.
+    ///
+    /// ```rust
+    /// use orgize::export::{DefaultHtmlHandler, HtmlConfig, HtmlDocumentConfig};
+    /// use orgize::Org;
+    ///
+    /// let org = Org::parse("#+TITLE: Blog\n* Hello\nSee [[*World]].\n* World\n");
+    ///
+    /// let pages = org
+    ///     .export_pages(1, &HtmlDocumentConfig::default(), |links| {
+    ///         DefaultHtmlHandler::new(HtmlConfig {
+    ///             links,
+    ///             ..Default::default()
+    ///         })
+    ///     })
+    ///     .unwrap();
+    ///
+    /// let files: Vec<_> = pages.iter().map(|page| page.file_name.as_str()).collect();
+    /// assert_eq!(files, ["hello.html", "world.html", "index.html"]);
+    /// assert!(pages[0].html.contains("<a href=\"world.html\">*World</a>"));
+    /// ```
+    pub fn export_pages<F, H, E>(
+        &self,
+        level: usize,
+        config: &HtmlDocumentConfig,
+        mut handler: F,
+    ) -> Result<Vec<Page>, ExportError<E>>
+    where
+        F: FnMut(LinkConfig) -> H,
+        E: From<Error>,
+        H: HtmlHandler<E>,
+    {
+        let mut used = HashSet::new();
+        used.insert(String::from("index"));
+        let headlines: Vec<(Headline, String)> = self
+            .headlines()
+            .filter(|hdl| hdl.level() == level)
+            .map(|hdl| {
+                let name = hdl
+                    .export_file_name(self)
+                    .map(String::from)
+                    .unwrap_or_else(|| slug(&hdl.title(self).raw));
+                (hdl, format!("{}.html", unique_id(&name, &mut used)))
+            })
+            .collect();
+
+        let files: HashMap<NodeId, &str> = headlines
+            .iter()
+            .map(|(hdl, file_name)| (hdl.headline_node(), file_name.as_str()))
+            .collect();
+        let hrefs = Arc::new(self.page_hrefs(&files));
+        let links = || {
+            let hrefs = hrefs.clone();
+            LinkConfig::default().with_hook(move |link| hrefs.get(&*link.path).cloned())
+        };
+        let config = HtmlDocumentConfig {
+            toc: false,
+            ..config.clone()
+        };
+        let root_error = |err: Error| self.export_error(self.root, E::from(err));
+
+        let mut pages = Vec::with_capacity(headlines.len() + 1);
+        for (hdl, file_name) in &headlines {
+            let title = hdl.title(self).raw.to_string();
+            let config = HtmlDocumentConfig {
+                title: Some(title.clone()),
+                ..config.clone()
+            };
+            let mut handler = handler(links());
+            let mut writer = Vec::new();
+
+            self.write_document_head(&mut writer, &config)
+                .map_err(root_error)?;
+            handler.prepare(self);
+            handler
+                .start_node(&mut writer, self.root, &self[self.root])
+                .map_err(|err| self.export_error(self.root, err))?;
+            for node in hdl.headline_node().children(&self.arena).skip(1) {
+                self.write_html_edges(node, &mut writer, &mut handler, false)?;
+            }
+            self.write_html_trailing(self.root, &mut writer, &mut handler)?;
+            handler
+                .end_node(&mut writer, self.root, &self[self.root])
+                .map_err(|err| self.export_error(self.root, err))?;
+            self.write_document_end(&mut writer, &config)
+                .map_err(root_error)?;
+
+            pages.push(Page {
+                file_name: file_name.clone(),
+                title,
+                headline: Some(*hdl),
+                html: String::from_utf8_lossy(&writer).into_owned(),
+            });
+        }
+
+        let mut handler = handler(links());
+        let mut writer = Vec::new();
+        self.write_document_head(&mut writer, &config)
+            .map_err(root_error)?;
+        handler.prepare(self);
+        handler
+            .start_node(&mut writer, self.root, &self[self.root])
+            .map_err(|err| self.export_error(self.root, err))?;
+        if let Some(node) = self.document().section_node() {
+            self.write_html_edges(node, &mut writer, &mut handler, false)?;
+        }
+        let unexported = self.unexported();
+        for hdl in self.headlines() {
+            if unexported.contains(&hdl.headline_node())
+                || page_file(&files, hdl.headline_node(), self).is_some()
+            {
+                continue;
+            }
+            self.write_html_edges(hdl.title_node(), &mut writer, &mut handler, false)?;
+            if let Some(node) = hdl.section_node() {
+                self.write_html_edges(node, &mut writer, &mut handler, false)?;
+            }
+        }
+        write_index(&mut writer, &pages).map_err(root_error)?;
+        self.write_html_trailing(self.root, &mut writer, &mut handler)?;
+        handler
+            .end_node(&mut writer, self.root, &self[self.root])
+            .map_err(|err| self.export_error(self.root, err))?;
+        self.write_document_end(&mut writer, &config)
+            .map_err(root_error)?;
+
+        pages.push(Page {
+            file_name: String::from("index.html"),
+            title: self.metadata().title.map(String::from).unwrap_or_else(|| {
+                config
+                    .language
+                    .clone()
+                    .or_else(|| self.metadata().language.map(String::from))
+                    .and_then(|language| Translations::lookup(&config.translations, &language))
+                    .map_or("Index", |strings| &strings.index)
+                    .to_string()
+            }),
+            headline: None,
+            html: String::from_utf8_lossy(&writer).into_owned(),
+        });
+
+        Ok(pages)
+    }
+
+    // destinations of links to headlines and targets, keyed by link paths,
+    // with `files` of pages keyed by their headlines
+    fn page_hrefs(&self, files: &HashMap<NodeId, &str>) -> HashMap<String, String> {
+        let ids = target_ids(self);
+        let headline_ids = headline_ids(self, &self.unexported());
+
+        let mut hrefs = HashMap::new();
+        for event in self.iter() {
+            let link = match event {
+                Event::Start(Element::Link(link)) if link.file_path().is_none() => link,
+                _ => continue,
+            };
+            let search = match link.search() {
+                Some(search) => search,
+                None => continue,
+            };
+            let node = match self.resolve_search(&search) {
+                Some(node) => node,
+                None => continue,
+            };
+            // anything outside of pages is written on the index
+            let file = page_file(files, node, self).unwrap_or("index.html");
+
+            let anchor = match &self[node] {
+                _ if files.contains_key(&node) => None,
+                Element::Headline { .. } => headline_ids.get(&node).cloned(),
+                _ => ids.get(&node).cloned(),
+            };
+            let href = match anchor {
+                Some(anchor) => format!("{}#{}", file, anchor),
+                None => file.to_string(),
+            };
+            hrefs.insert(link.path.to_string(), href);
+        }
+        hrefs
+    }
+}
+
+// file of the page containing `node`
+fn page_file<'f>(files: &HashMap<NodeId, &'f str>, node: NodeId, org: &Org) -> Option<&'f str> {
+    node.ancestors(&org.arena)
+        .find_map(|node| files.get(&node).copied())
+}
+
+fn write_index<W: Write>(mut w: W, pages: &[Page]) -> Result<(), Error> {
+    if pages.is_empty() {
+        return Ok(());
+    }
+
+    write!(w, "<nav><ul>")?;
+    for page in pages {
+        write!(
+            w,
+            "<li><a href=\"{}\">{}</a></li>",
+            HtmlEscape(&page.file_name),
+            HtmlEscape(&page.title)
+        )?;
+    }
+    write!(w, "</ul></nav>")
+}
@@ -0,0 +1,80 @@
+use std::io::Error;
+
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::{
+    export::{DefaultHtmlHandler, ExportError, HtmlHandler},
+    org::HtmlEdges,
+    Org,
+};
+
+impl Org<'_> {
+    /// Like `write_html`, but writes to an `AsyncWrite`.
+    ///
+    /// Requires the `tokio` feature.
+    pub async fn write_html_async<W>(&self, writer: W) -> Result<(), ExportError<Error>>
+    where
+        W: AsyncWrite + Unpin,
+    {
+        self.write_html_async_custom(writer, &mut DefaultHtmlHandler::default())
+            .await
+    }
+
+    /// Like `write_html_custom`, but writes to an `AsyncWrite`.
+    ///
+    /// The handler still writes each element synchronously, into a buffer
+    /// which is written to `writer` before the next element, so output is
+    /// streamed as the document is rendered. `writer` is flushed at the end.
+    ///
+    /// Requires the `tokio` feature.
+    ///
+    /// ```rust
+    /// use orgize::{export::DefaultHtmlHandler, Org};
+    ///
+    /// # tokio::runtime::Builder::new_current_thread()
+    /// #     .build()
+    /// #     .unwrap()
+    /// #     .block_on(async {
+    /// let mut writer = Vec::new();
+    /// Org::parse("* title\n*section*")
+    ///     .write_html_async_custom(&mut writer, &mut DefaultHtmlHandler::default())
+    ///     .await
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     String::from_utf8(writer).unwrap(),
+    ///     "<main><h1>title</h1><section><p><b>section</b></p></section></main>"
+    /// );
+    /// # });
+    /// ```
+    pub async fn write_html_async_custom<W, H, E>(
+        &self,
+        mut writer: W,
+        handler: &mut H,
+    ) -> Result<(), ExportError<E>>
+    where
+        W: AsyncWrite + Unpin,
+        E: From<Error>,
+        H: HtmlHandler<E>,
+    {
+        handler.prepare(self);
+
+        let mut buffer = Vec::new();
+        let mut edges = HtmlEdges::new(self, self.root, true);
+
+        while let Some(node) = edges.write_next(&mut buffer, handler)? {
+            if !buffer.is_empty() {
+                writer
+                    .write_all(&buffer)
+                    .await
+                    .map_err(|err| self.export_error(node, err.into()))?;
+                buffer.clear();
+            }
+        }
+
+        writer
+            .flush()
+            .await
+            .map_err(|err| self.export_error(self.root, err.into()))
+    }
+}
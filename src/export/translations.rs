@@ -0,0 +1,186 @@
+use std::collections::HashMap;
+
+use crate::elements::DAY_NAMES;
+
+/// Boilerplate strings written by the built-in exporters, see
+/// `HtmlConfig::translations`
+///
+/// Translations are looked up by the language of the document, i.e. the
+/// `#+LANGUAGE:` keyword, in a table which defaults to
+/// `Translations::builtin()`. Users can extend the table, or replace some of
+/// its entries.
+///
+/// ```rust
+/// use orgize::export::{DefaultHtmlHandler, HtmlConfig, Translations};
+/// use orgize::Org;
+///
+/// let mut config = HtmlConfig::default();
+/// config.translations.insert(
+///     String::from("eo"),
+///     Translations {
+///         footnotes: String::from("Piednotoj"),
+///         ..Default::default()
+///     },
+/// );
+///
+/// let mut writer = Vec::new();
+/// Org::parse("#+LANGUAGE: eo\nTeksto[fn:1]\n* Footnotes\n[fn:1] Noto.\n")
+///     .write_html_custom(&mut writer, &mut DefaultHtmlHandler::new(config))
+///     .unwrap();
+/// assert!(String::from_utf8(writer)
+///     .unwrap()
+///     .contains("<h2 class=\"footnotes\">Piednotoj</h2>"));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Translations {
+    /// Heading of the table of contents, default is `"Table of Contents"`
+    pub table_of_contents: String,
+    /// Heading of footnote definitions written at the end of the document,
+    /// default is `"Footnotes"`
+    pub footnotes: String,
+    /// Title of the index page written by `Org::export_pages`, default is
+    /// `"Index"`
+    pub index: String,
+    /// Day names of regenerated timestamps, starting from Monday, default
+    /// is english
+    pub day_names: [String; 7],
+    /// Label of numbered tables, e.g. `Table 1:`, default is `"Table"`
+    pub table: String,
+    /// Label of numbered images, default is `"Figure"`
+    pub figure: String,
+    /// Label of numbered source blocks, default is `"Listing"`
+    pub listing: String,
+}
+
+impl Default for Translations {
+    fn default() -> Self {
+        Translations {
+            table_of_contents: String::from("Table of Contents"),
+            footnotes: String::from("Footnotes"),
+            index: String::from("Index"),
+            day_names: DAY_NAMES.map(String::from),
+            table: String::from("Table"),
+            figure: String::from("Figure"),
+            listing: String::from("Listing"),
+        }
+    }
+}
+
+impl Translations {
+    /// Returns built-in translations, keyed by language code
+    ///
+    /// Contains `en`, `de`, `es`, `fr`, `it`, `ja`, `nl` and `pt`.
+    pub fn builtin() -> HashMap<String, Translations> {
+        // language, table of contents, footnotes, index, day names, and
+        // labels of tables, figures and listings
+        #[allow(clippy::type_complexity)]
+        const BUILTIN: [(&str, &str, &str, &str, [&str; 7], [&str; 3]); 7] = [
+            (
+                "de",
+                "Inhaltsverzeichnis",
+                "Fußnoten",
+                "Index",
+                ["Mo", "Di", "Mi", "Do", "Fr", "Sa", "So"],
+                ["Tabelle", "Abbildung", "Programmlisting"],
+            ),
+            (
+                "es",
+                "Índice",
+                "Notas al pie de página",
+                "Índice",
+                ["lun", "mar", "mié", "jue", "vie", "sáb", "dom"],
+                ["Tabla", "Figura", "Listado de programa"],
+            ),
+            (
+                "fr",
+                "Table des matières",
+                "Notes de bas de page",
+                "Index",
+                ["lun.", "mar.", "mer.", "jeu.", "ven.", "sam.", "dim."],
+                ["Tableau", "Figure", "Programme"],
+            ),
+            (
+                "it",
+                "Indice",
+                "Note a piè di pagina",
+                "Indice",
+                ["lun", "mar", "mer", "gio", "ven", "sab", "dom"],
+                ["Tabella", "Figura", "Listato"],
+            ),
+            (
+                "ja",
+                "目次",
+                "脚注",
+                "索引",
+                ["月", "火", "水", "木", "金", "土", "日"],
+                ["表", "図", "ソースコード"],
+            ),
+            (
+                "nl",
+                "Inhoudsopgave",
+                "Voetnoten",
+                "Index",
+                ["ma", "di", "wo", "do", "vr", "za", "zo"],
+                ["Tabel", "Figuur", "Programma"],
+            ),
+            (
+                "pt",
+                "Índice",
+                "Notas de Rodapé",
+                "Índice",
+                ["seg", "ter", "qua", "qui", "sex", "sáb", "dom"],
+                ["Tabela", "Figura", "Listagem"],
+            ),
+        ];
+
+        let mut table = HashMap::new();
+        table.insert(String::from("en"), Translations::default());
+        for (language, table_of_contents, footnotes, index, day_names, labels) in BUILTIN {
+            table.insert(
+                language.to_string(),
+                Translations {
+                    table_of_contents: table_of_contents.to_string(),
+                    footnotes: footnotes.to_string(),
+                    index: index.to_string(),
+                    day_names: day_names.map(String::from),
+                    table: labels[0].to_string(),
+                    figure: labels[1].to_string(),
+                    listing: labels[2].to_string(),
+                },
+            );
+        }
+        table
+    }
+
+    /// Returns translations of `language` in `table`, or of its primary
+    /// subtag, e.g. `de` for `de-AT`.
+    pub fn lookup<'t>(
+        table: &'t HashMap<String, Translations>,
+        language: &str,
+    ) -> Option<&'t Translations> {
+        let language = language.trim();
+        table.get(language).or_else(|| {
+            let (primary, _) = language.split_once(['-', '_'])?;
+            table.get(primary)
+        })
+    }
+}
+
+#[test]
+fn lookup() {
+    let table = Translations::builtin();
+
+    assert_eq!(
+        Translations::lookup(&table, "de").map(|t| t.footnotes.as_str()),
+        Some("Fußnoten")
+    );
+    assert_eq!(
+        Translations::lookup(&table, "de-AT").map(|t| t.footnotes.as_str()),
+        Some("Fußnoten")
+    );
+    assert_eq!(
+        Translations::lookup(&table, "pt_BR").map(|t| t.day_names[0].as_str()),
+        Some("seg")
+    );
+    assert_eq!(Translations::lookup(&table, "xx"), None);
+}
@@ -0,0 +1,982 @@
+use indextree::{NodeEdge, NodeId};
+use std::borrow::Cow;
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::error::Error;
+use std::fmt;
+
+use crate::{
+    elements::{Element, Table, TableCell, TableRow},
+    export::{DefaultOrgHandler, OrgHandler},
+    Org,
+};
+
+/// Error returned by `Table::recalculate`
+#[derive(Debug, Clone, PartialEq)]
+pub struct FormulaError {
+    /// The formula, e.g. `$3=$1+$2`
+    pub formula: String,
+    /// Row and column of the field being calculated, both start from 1
+    ///
+    /// It's `None` if the formula itself can't be used.
+    pub field: Option<(usize, usize)>,
+    /// Error kind
+    pub kind: FormulaErrorKind,
+}
+
+/// Kind of `FormulaError`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FormulaErrorKind {
+    /// Formula uses unsupported features, like Emacs Lisp forms, remote
+    /// references, hline references or unknown functions
+    Unsupported,
+    /// Formula can't be parsed
+    Syntax,
+    /// Referenced field isn't a number
+    NotANumber,
+    /// Referenced field is outside of the table
+    OutOfRange,
+    /// Division by zero
+    DivisionByZero,
+    /// Referenced constant or remote table isn't defined
+    Undefined,
+}
+
+impl fmt::Display for FormulaError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reason = match self.kind {
+            FormulaErrorKind::Unsupported => "unsupported formula",
+            FormulaErrorKind::Syntax => "invalid formula",
+            FormulaErrorKind::NotANumber => "referenced field is not a number",
+            FormulaErrorKind::OutOfRange => "reference out of range",
+            FormulaErrorKind::DivisionByZero => "division by zero",
+            FormulaErrorKind::Undefined => "undefined constant or table",
+        };
+        match self.field {
+            Some((row, column)) => {
+                write!(f, "{} `{}` at @{}${}", reason, self.formula, row, column)
+            }
+            None => write!(f, "{} `{}`", reason, self.formula),
+        }
+    }
+}
+
+impl Error for FormulaError {}
+
+impl Table<'_> {
+    /// Evaluates `#+TBLFM:` formulas of every org table in `org`, and writes
+    /// computed values back into the table cells.
+    ///
+    /// Supports column formulas (`$3=$1+$2`), field formulas (`@2$3=@2$1*2`),
+    /// relative references (`@-1`, `$+1`), first and last references
+    /// (`@<`, `$>`), ranges in `vsum`, `vmean`, `vmin`, `vmax` and `vcount`,
+    /// arithmetic operators `+ - * / ^`, and `%.2f` or `%d` format specifiers.
+    /// Rows are numbered without horizontal rules and special rows.
+    ///
+    /// `$name` is a constant of `#+CONSTANTS:`, see `Org::constants`, and
+    /// `remote(name, @2$3)` references fields of the table named `name` by
+    /// `#+NAME:`, as they were before recalculating.
+    ///
+    /// Column formulas are applied to all rows below the first horizontal
+    /// rule, or all rows if the table has none, then field formulas are
+    /// applied.
+    ///
+    /// Formulas which can't be evaluated leave their fields untouched, and
+    /// are reported in the returned vector.
+    ///
+    /// ```rust
+    /// use orgize::{elements::Table, Org};
+    ///
+    /// let mut org = Org::parse("| 1 | 2 |   |\n| 3 | 4 |   |\n#+TBLFM: $3=$1+$2\n");
+    ///
+    /// assert!(Table::recalculate(&mut org).is_empty());
+    ///
+    /// let mut writer = Vec::new();
+    /// org.write_org(&mut writer).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(writer).unwrap(),
+    ///     "| 1 | 2 | 3 |\n| 3 | 4 | 7 |\n#+TBLFM: $3=$1+$2\n"
+    /// );
+    /// ```
+    pub fn recalculate(org: &mut Org) -> Vec<FormulaError> {
+        let tables: Vec<_> = org
+            .root
+            .descendants(&org.arena)
+            .filter_map(|node| match &org[node] {
+                Element::Table(Table::Org {
+                    tblfm: Some(tblfm), ..
+                }) => Some((node, tblfm.to_string())),
+                _ => None,
+            })
+            .collect();
+
+        let env = Env {
+            constants: org.constants(),
+            tables: org
+                .root
+                .descendants(&org.arena)
+                .filter(|&node| matches!(org[node], Element::Table(Table::Org { .. })))
+                .filter_map(|node| Some((org.name(node)?.to_string(), Grid::new(org, node))))
+                .collect(),
+        };
+        let mut errors = Vec::new();
+
+        for (node, tblfm) in tables {
+            let mut grid = Grid::new(org, node);
+            grid.apply(&tblfm, &env, &mut errors);
+            grid.write_back(org);
+        }
+        org.tree_changed();
+
+        errors
+    }
+}
+
+impl Org<'_> {
+    /// Returns constants defined by all `#+CONSTANTS:` keywords, e.g.
+    /// `#+CONSTANTS: pi=3.14159 c=299792458`. A constant defined twice has
+    /// its last value.
+    ///
+    /// ```rust
+    /// use orgize::Org;
+    ///
+    /// let org = Org::parse("#+CONSTANTS: pi=3.14159 c=299792458\n#+CONSTANTS: c=3e8\n");
+    /// let constants = org.constants();
+    ///
+    /// assert_eq!(constants["pi"], "3.14159");
+    /// assert_eq!(constants["c"], "3e8");
+    /// ```
+    pub fn constants(&self) -> HashMap<String, String> {
+        let mut constants = HashMap::new();
+        for keyword in self.keywords() {
+            if keyword.key.eq_ignore_ascii_case("CONSTANTS") {
+                for (name, value) in keyword
+                    .value
+                    .split_whitespace()
+                    .filter_map(|pair| pair.split_once('='))
+                    .filter(|(name, _)| is_constant_name(name))
+                {
+                    constants.insert(name.to_string(), value.to_string());
+                }
+            }
+        }
+        constants
+    }
+}
+
+// like `[a-zA-Z][_a-zA-Z0-9]*`
+fn is_constant_name(name: &str) -> bool {
+    name.starts_with(|c: char| c.is_ascii_alphabetic())
+        && name.bytes().all(|c| c.is_ascii_alphanumeric() || c == b'_')
+}
+
+// constants and named tables shared by all formulas of a document
+struct Env {
+    constants: HashMap<String, String>,
+    tables: HashMap<String, Grid>,
+}
+
+struct Grid {
+    rows: Vec<NodeId>,
+    cells: Vec<Vec<Option<NodeId>>>,
+    values: Vec<Vec<String>>,
+    changed: Vec<(usize, usize)>,
+    // rows before the first horizontal rule
+    header_rows: usize,
+}
+
+impl Grid {
+    fn new(org: &Org, table: NodeId) -> Grid {
+        let rows: Vec<_> = table
+            .children(&org.arena)
+            .filter(|&row| {
+                matches!(
+                    org[row],
+                    Element::TableRow(TableRow::Header) | Element::TableRow(TableRow::Body)
+                )
+            })
+            .collect();
+
+        let header_rows = rows
+            .iter()
+            .filter(|&&row| matches!(org[row], Element::TableRow(TableRow::Header)))
+            .count();
+
+        let cells: Vec<Vec<_>> = rows
+            .iter()
+            .map(|row| row.children(&org.arena).map(Some).collect())
+            .collect();
+
+        let values = cells
+            .iter()
+            .map(|row| {
+                row.iter()
+                    .map(|cell| cell_text(org, cell.unwrap()))
+                    .collect()
+            })
+            .collect();
+
+        Grid {
+            rows,
+            cells,
+            values,
+            changed: Vec::new(),
+            header_rows,
+        }
+    }
+
+    fn columns(&self) -> usize {
+        self.values.iter().map(Vec::len).max().unwrap_or(0)
+    }
+
+    fn get(&self, row: usize, column: usize) -> &str {
+        self.values[row]
+            .get(column)
+            .map_or("", |value| value.trim())
+    }
+
+    fn set(&mut self, row: usize, column: usize, value: String) {
+        let values = &mut self.values[row];
+        if values.len() <= column {
+            values.resize(column + 1, String::new());
+            self.cells[row].resize(column + 1, None);
+        }
+        if values[column] != value {
+            values[column] = value;
+            self.changed.push((row, column));
+        }
+    }
+
+    fn apply(&mut self, tblfm: &str, env: &Env, errors: &mut Vec<FormulaError>) {
+        let mut column_formulas = Vec::new();
+        let mut field_formulas = Vec::new();
+
+        for formula in tblfm
+            .lines()
+            .flat_map(|line| line.split("::"))
+            .map(str::trim)
+            .filter(|f| !f.is_empty())
+        {
+            match parse_formula(formula) {
+                Ok((Target::Column(column), expr, format)) => {
+                    column_formulas.push((formula, column, expr, format))
+                }
+                Ok((Target::Field(row, column), expr, format)) => {
+                    field_formulas.push((formula, row, column, expr, format))
+                }
+                Err(kind) => errors.push(FormulaError {
+                    formula: formula.into(),
+                    field: None,
+                    kind,
+                }),
+            }
+        }
+
+        for (formula, column, expr, format) in column_formulas {
+            let column = match column.resolve_target(self.columns()) {
+                Some(column) => column,
+                None => {
+                    errors.push(FormulaError {
+                        formula: formula.into(),
+                        field: None,
+                        kind: FormulaErrorKind::OutOfRange,
+                    });
+                    continue;
+                }
+            };
+            for row in self.header_rows..self.rows.len() {
+                errors.extend(self.eval_field(formula, row, column, &expr, &format, env));
+            }
+        }
+
+        for (formula, row, column, expr, format) in field_formulas {
+            match (
+                row.resolve(0, self.rows.len()),
+                column.resolve_target(self.columns()),
+            ) {
+                (Some(row), Some(column)) => {
+                    errors.extend(self.eval_field(formula, row, column, &expr, &format, env))
+                }
+                _ => errors.push(FormulaError {
+                    formula: formula.into(),
+                    field: None,
+                    kind: FormulaErrorKind::OutOfRange,
+                }),
+            }
+        }
+    }
+
+    fn eval_field(
+        &mut self,
+        formula: &str,
+        row: usize,
+        column: usize,
+        expr: &Expr,
+        format: &Format,
+        env: &Env,
+    ) -> Option<FormulaError> {
+        match self
+            .eval(expr, row, column, env)
+            .and_then(|value| match value {
+                Value::Number(number) => format.apply(number),
+                Value::Range(_) => Err(FormulaErrorKind::Unsupported),
+            }) {
+            Ok(value) => {
+                self.set(row, column, value);
+                None
+            }
+            Err(kind) => Some(FormulaError {
+                formula: formula.into(),
+                field: Some((row + 1, column + 1)),
+                kind,
+            }),
+        }
+    }
+
+    fn resolve(
+        &self,
+        reference: &Reference,
+        row: usize,
+        column: usize,
+    ) -> Result<(usize, usize), FormulaErrorKind> {
+        let row = match &reference.row {
+            Some(position) => position.resolve(row, self.rows.len()),
+            None => Some(row),
+        };
+        let column = match &reference.column {
+            Some(position) => position.resolve(column, self.columns()),
+            None => Some(column),
+        };
+        match (row, column) {
+            (Some(row), Some(column)) => Ok((row, column)),
+            _ => Err(FormulaErrorKind::OutOfRange),
+        }
+    }
+
+    fn eval(
+        &self,
+        expr: &Expr,
+        row: usize,
+        column: usize,
+        env: &Env,
+    ) -> Result<Value, FormulaErrorKind> {
+        let number = |expr: &Expr| match self.eval(expr, row, column, env)? {
+            Value::Number(number) => Ok(number),
+            Value::Range(_) => Err(FormulaErrorKind::Unsupported),
+        };
+
+        Ok(Value::Number(match expr {
+            Expr::Number(number) => *number,
+            Expr::Reference(reference) => {
+                let (row, column) = self.resolve(reference, row, column)?;
+                let value = self.get(row, column);
+                if value.is_empty() {
+                    0.0
+                } else {
+                    parse_number(value).ok_or(FormulaErrorKind::NotANumber)?
+                }
+            }
+            Expr::Range(start, end) => {
+                let (row1, column1) = self.resolve(start, row, column)?;
+                let (row2, column2) = self.resolve(end, row, column)?;
+                let mut numbers = Vec::new();
+                for row in row1.min(row2)..=row1.max(row2) {
+                    for column in column1.min(column2)..=column1.max(column2) {
+                        // empty fields are suppressed in ranges
+                        let value = self.get(row, column);
+                        if !value.is_empty() {
+                            numbers.push(parse_number(value).ok_or(FormulaErrorKind::NotANumber)?);
+                        }
+                    }
+                }
+                return Ok(Value::Range(numbers));
+            }
+            Expr::Constant(name) => {
+                let value = env.constants.get(name).ok_or(FormulaErrorKind::Undefined)?;
+                parse_number(value).ok_or(FormulaErrorKind::NotANumber)?
+            }
+            Expr::Remote(name, expr) => {
+                let table = env.tables.get(name).ok_or(FormulaErrorKind::Undefined)?;
+                return table.eval(expr, row, column, env);
+            }
+            Expr::Negative(expr) => -number(expr)?,
+            Expr::Binary(op, left, right) => {
+                let (left, right) = (number(left)?, number(right)?);
+                match op {
+                    b'+' => left + right,
+                    b'-' => left - right,
+                    b'*' => left * right,
+                    b'/' if right == 0.0 => return Err(FormulaErrorKind::DivisionByZero),
+                    b'/' => left / right,
+                    _ => left.powf(right),
+                }
+            }
+            Expr::Call(function, arguments) => {
+                let mut numbers = Vec::new();
+                for argument in arguments {
+                    match self.eval(argument, row, column, env)? {
+                        Value::Number(number) => numbers.push(number),
+                        Value::Range(range) => numbers.extend(range),
+                    }
+                }
+                match function {
+                    Function::Sum => numbers.iter().sum(),
+                    Function::Mean if numbers.is_empty() => {
+                        return Err(FormulaErrorKind::DivisionByZero)
+                    }
+                    Function::Mean => numbers.iter().sum::<f64>() / numbers.len() as f64,
+                    Function::Min => numbers.iter().cloned().fold(f64::INFINITY, f64::min),
+                    Function::Max => numbers.iter().cloned().fold(f64::NEG_INFINITY, f64::max),
+                    Function::Count => numbers.len() as f64,
+                }
+            }
+        }))
+    }
+
+    fn write_back(mut self, org: &mut Org) {
+        for (row, column) in std::mem::take(&mut self.changed) {
+            let cell = match self.cells[row][column] {
+                Some(cell) => {
+                    let children: Vec<_> = cell.children(&org.arena).collect();
+                    for child in children {
+                        child.remove_subtree(&mut org.arena);
+                    }
+                    cell
+                }
+                None => self.append_cells(org, row, column),
+            };
+            let value = &self.values[row][column];
+            if !value.is_empty() {
+                let text = org.arena.new_node(Element::Text {
+                    value: Cow::Owned(value.clone()),
+                });
+                cell.append(text, &mut org.arena);
+            }
+        }
+    }
+
+    // appends empty cells to the row until `column`
+    fn append_cells(&mut self, org: &mut Org, row: usize, column: usize) -> NodeId {
+        let header = matches!(org[self.rows[row]], Element::TableRow(TableRow::Header));
+        for i in 0..=column {
+            if self.cells[row][i].is_none() {
+                let cell = org.arena.new_node(Element::TableCell(if header {
+                    TableCell::Header { alignment: None }
+                } else {
+                    TableCell::Body { alignment: None }
+                }));
+                self.rows[row].append(cell, &mut org.arena);
+                self.cells[row][i] = Some(cell);
+            }
+        }
+        self.cells[row][column].unwrap()
+    }
+}
+
+pub(crate) fn cell_text(org: &Org, cell: NodeId) -> String {
+    let mut handler = DefaultOrgHandler::default();
+    let mut writer = Vec::new();
+    for edge in cell.traverse(&org.arena) {
+        let _ = match edge {
+            NodeEdge::Start(node) if node != cell => {
+                handler.start_node(&mut writer, node, &org[node])
+            }
+            NodeEdge::End(node) if node != cell => handler.end_node(&mut writer, node, &org[node]),
+            _ => Ok(()),
+        };
+    }
+    String::from_utf8(writer).unwrap_or_default()
+}
+
+fn parse_number(value: &str) -> Option<f64> {
+    if value
+        .bytes()
+        .all(|c| c.is_ascii_digit() || matches!(c, b'.' | b'-' | b'+' | b'e' | b'E'))
+    {
+        value.parse().ok()
+    } else {
+        None
+    }
+}
+
+enum Value {
+    Number(f64),
+    Range(Vec<f64>),
+}
+
+enum Target {
+    Column(Position),
+    Field(Position, Position),
+}
+
+/// Row or column position in a reference
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Position {
+    /// `@2`, `$3`
+    Absolute(usize),
+    /// `@-1`, `$+2`, `@0`
+    Relative(isize),
+    /// `@<`, `$<<`
+    First(usize),
+    /// `@>`, `$>>`
+    Last(usize),
+}
+
+impl Position {
+    // fields after the last column can be assigned
+    fn resolve_target(&self, len: usize) -> Option<usize> {
+        match *self {
+            Position::Absolute(n) => self.resolve(0, len.max(n)),
+            _ => self.resolve(0, len),
+        }
+    }
+
+    // returns 0-based index
+    fn resolve(&self, current: usize, len: usize) -> Option<usize> {
+        let index = match *self {
+            Position::Absolute(n) => n.checked_sub(1)?,
+            Position::Relative(n) => (current as isize).checked_add(n)?.try_into().ok()?,
+            Position::First(n) => n - 1,
+            Position::Last(n) => len.checked_sub(n)?,
+        };
+        if index < len {
+            Some(index)
+        } else {
+            None
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Reference {
+    row: Option<Position>,
+    column: Option<Position>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Function {
+    Sum,
+    Mean,
+    Min,
+    Max,
+    Count,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Expr {
+    Number(f64),
+    Reference(Reference),
+    Range(Reference, Reference),
+    /// `$name`
+    Constant(String),
+    /// `remote(name, reference)`, with a reference or a range
+    Remote(String, Box<Expr>),
+    Negative(Box<Expr>),
+    Binary(u8, Box<Expr>, Box<Expr>),
+    Call(Function, Vec<Expr>),
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Format {
+    /// Calc's default format, 12 significant digits
+    Default,
+    /// `%.2f`
+    Fixed(usize),
+    /// `%d`
+    Integer,
+}
+
+impl Format {
+    fn parse(input: &str) -> Result<Format, FormulaErrorKind> {
+        let input = input.trim();
+        if input.is_empty() {
+            return Ok(Format::Default);
+        }
+        let spec = input
+            .strip_prefix('%')
+            .ok_or(FormulaErrorKind::Unsupported)?;
+        if spec == "d" {
+            Ok(Format::Integer)
+        } else if spec == "f" {
+            Ok(Format::Fixed(6))
+        } else if let Some(precision) = spec.strip_prefix('.').and_then(|s| s.strip_suffix('f')) {
+            precision
+                .parse()
+                .map(Format::Fixed)
+                .map_err(|_| FormulaErrorKind::Unsupported)
+        } else {
+            Err(FormulaErrorKind::Unsupported)
+        }
+    }
+
+    fn apply(&self, number: f64) -> Result<String, FormulaErrorKind> {
+        if !number.is_finite() {
+            return Err(FormulaErrorKind::DivisionByZero);
+        }
+        Ok(match self {
+            Format::Default => {
+                let rounded: f64 = format!("{:.11e}", number).parse().unwrap_or(number);
+                // avoids `-0`
+                format!("{}", rounded + 0.0)
+            }
+            Format::Fixed(precision) => format!("{:.*}", precision, number),
+            Format::Integer => format!("{}", number.round() + 0.0),
+        })
+    }
+}
+
+fn parse_formula(formula: &str) -> Result<(Target, Expr, Format), FormulaErrorKind> {
+    let (target, rhs) = formula.split_once('=').ok_or(FormulaErrorKind::Syntax)?;
+    let rhs = rhs.trim();
+
+    if rhs.starts_with('\'') {
+        return Err(FormulaErrorKind::Unsupported);
+    }
+
+    let target = {
+        let mut parser = Parser::new(target.trim());
+        let reference = parser.reference()?.ok_or(FormulaErrorKind::Syntax)?;
+        if !parser.is_end() {
+            return Err(FormulaErrorKind::Unsupported);
+        }
+        match reference {
+            Reference {
+                row: None,
+                column: Some(column),
+            } if is_absolute(column) => Target::Column(column),
+            Reference {
+                row: Some(row),
+                column: Some(column),
+            } if is_absolute(row) && is_absolute(column) => Target::Field(row, column),
+            _ => return Err(FormulaErrorKind::Unsupported),
+        }
+    };
+
+    let (expr, format) = match rhs.split_once(';') {
+        Some((expr, format)) => (expr, Format::parse(format)?),
+        None => (rhs, Format::Default),
+    };
+
+    let mut parser = Parser::new(expr);
+    let expr = parser.expr()?;
+    if !parser.is_end() {
+        return Err(FormulaErrorKind::Syntax);
+    }
+
+    Ok((target, expr, format))
+}
+
+fn is_absolute(position: Position) -> bool {
+    !matches!(position, Position::Relative(_))
+}
+
+struct Parser<'a> {
+    input: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn new(input: &'a str) -> Self {
+        Parser { input }
+    }
+
+    fn skip_whitespace(&mut self) {
+        self.input = self.input.trim_start();
+    }
+
+    fn is_end(&mut self) -> bool {
+        self.skip_whitespace();
+        self.input.is_empty()
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.skip_whitespace();
+        self.input.as_bytes().first().copied()
+    }
+
+    fn eat(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        match self.input.strip_prefix(token) {
+            Some(input) => {
+                self.input = input;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn take_while(&mut self, predicate: impl Fn(u8) -> bool) -> &'a str {
+        let end = self
+            .input
+            .bytes()
+            .position(|c| !predicate(c))
+            .unwrap_or(self.input.len());
+        let (taken, input) = self.input.split_at(end);
+        self.input = input;
+        taken
+    }
+
+    fn expr(&mut self) -> Result<Expr, FormulaErrorKind> {
+        let mut left = self.term()?;
+        while let Some(op @ (b'+' | b'-')) = self.peek() {
+            self.input = &self.input[1..];
+            left = Expr::Binary(op, Box::new(left), Box::new(self.term()?));
+        }
+        Ok(left)
+    }
+
+    fn term(&mut self) -> Result<Expr, FormulaErrorKind> {
+        let mut left = self.unary()?;
+        while let Some(op @ (b'*' | b'/')) = self.peek() {
+            self.input = &self.input[1..];
+            left = Expr::Binary(op, Box::new(left), Box::new(self.unary()?));
+        }
+        Ok(left)
+    }
+
+    fn unary(&mut self) -> Result<Expr, FormulaErrorKind> {
+        if self.eat("-") {
+            Ok(Expr::Negative(Box::new(self.unary()?)))
+        } else if self.eat("+") {
+            self.unary()
+        } else {
+            self.power()
+        }
+    }
+
+    fn power(&mut self) -> Result<Expr, FormulaErrorKind> {
+        let base = self.atom()?;
+        if self.eat("^") {
+            Ok(Expr::Binary(b'^', Box::new(base), Box::new(self.unary()?)))
+        } else {
+            Ok(base)
+        }
+    }
+
+    fn atom(&mut self) -> Result<Expr, FormulaErrorKind> {
+        match self.peek().ok_or(FormulaErrorKind::Syntax)? {
+            b'(' => {
+                self.input = &self.input[1..];
+                let expr = self.expr()?;
+                if self.eat(")") {
+                    Ok(expr)
+                } else {
+                    Err(FormulaErrorKind::Syntax)
+                }
+            }
+            b'0'..=b'9' | b'.' => {
+                let number = self.take_while(|c| c.is_ascii_digit() || c == b'.');
+                number
+                    .parse()
+                    .map(Expr::Number)
+                    .map_err(|_| FormulaErrorKind::Syntax)
+            }
+            b'$' if self.input[1..].starts_with(|c: char| c.is_ascii_alphabetic()) => {
+                self.input = &self.input[1..];
+                let name = self.take_while(|c| c.is_ascii_alphanumeric() || c == b'_');
+                // properties of the headline
+                if name.starts_with("PROP_") {
+                    return Err(FormulaErrorKind::Unsupported);
+                }
+                Ok(Expr::Constant(name.to_string()))
+            }
+            b'@' | b'$' => self.reference_or_range(),
+            c if c.is_ascii_alphabetic() => {
+                let function = match self.take_while(|c| c.is_ascii_alphanumeric()) {
+                    "remote" => return self.remote(),
+                    "vsum" => Function::Sum,
+                    "vmean" => Function::Mean,
+                    "vmin" => Function::Min,
+                    "vmax" => Function::Max,
+                    "vcount" => Function::Count,
+                    _ => return Err(FormulaErrorKind::Unsupported),
+                };
+                if !self.eat("(") {
+                    return Err(FormulaErrorKind::Syntax);
+                }
+                let mut arguments = vec![self.expr()?];
+                while self.eat(",") {
+                    arguments.push(self.expr()?);
+                }
+                if self.eat(")") {
+                    Ok(Expr::Call(function, arguments))
+                } else {
+                    Err(FormulaErrorKind::Syntax)
+                }
+            }
+            _ => Err(FormulaErrorKind::Syntax),
+        }
+    }
+
+    fn reference_or_range(&mut self) -> Result<Expr, FormulaErrorKind> {
+        let start = self.reference()?.ok_or(FormulaErrorKind::Syntax)?;
+        if self.eat("..") {
+            let end = self.reference()?.ok_or(FormulaErrorKind::Syntax)?;
+            Ok(Expr::Range(start, end))
+        } else {
+            Ok(Expr::Reference(start))
+        }
+    }
+
+    // arguments of `remote(name, reference)`
+    fn remote(&mut self) -> Result<Expr, FormulaErrorKind> {
+        if !self.eat("(") {
+            return Err(FormulaErrorKind::Syntax);
+        }
+        self.skip_whitespace();
+        let name = self.take_while(|c| c != b',' && c != b')').trim();
+        if name.is_empty() || !self.eat(",") {
+            return Err(FormulaErrorKind::Syntax);
+        }
+        self.skip_whitespace();
+        let expr = self.reference_or_range()?;
+        if self.eat(")") {
+            Ok(Expr::Remote(name.to_string(), Box::new(expr)))
+        } else {
+            Err(FormulaErrorKind::Syntax)
+        }
+    }
+
+    fn reference(&mut self) -> Result<Option<Reference>, FormulaErrorKind> {
+        let row = if self.eat("@") {
+            Some(self.position()?)
+        } else {
+            None
+        };
+        // no whitespace is allowed inside a reference
+        let column = match self.input.strip_prefix('$') {
+            Some(input) => {
+                self.input = input;
+                Some(self.position()?)
+            }
+            None => None,
+        };
+        if row.is_none() && column.is_none() {
+            Ok(None)
+        } else {
+            Ok(Some(Reference { row, column }))
+        }
+    }
+
+    fn position(&mut self) -> Result<Position, FormulaErrorKind> {
+        let bytes = self.input.as_bytes();
+        match bytes.first() {
+            Some(b'<') => Ok(Position::First(self.take_while(|c| c == b'<').len())),
+            Some(b'>') => Ok(Position::Last(self.take_while(|c| c == b'>').len())),
+            Some(sign @ (b'-' | b'+')) if matches!(bytes.get(1), Some(c) if c.is_ascii_digit()) => {
+                let negative = *sign == b'-';
+                self.input = &self.input[1..];
+                let n: isize = self
+                    .take_while(|c| c.is_ascii_digit())
+                    .parse()
+                    .map_err(|_| FormulaErrorKind::Syntax)?;
+                Ok(Position::Relative(if negative { -n } else { n }))
+            }
+            Some(b'0') if !matches!(bytes.get(1), Some(c) if c.is_ascii_digit()) => {
+                self.input = &self.input[1..];
+                Ok(Position::Relative(0))
+            }
+            Some(c) if c.is_ascii_digit() => self
+                .take_while(|c| c.is_ascii_digit())
+                .parse()
+                .map(Position::Absolute)
+                .map_err(|_| FormulaErrorKind::Syntax),
+            // hline references, named fields, etc.
+            _ => Err(FormulaErrorKind::Unsupported),
+        }
+    }
+}
+
+#[test]
+fn parse() {
+    use Position::*;
+
+    let reference = |row, column| Reference { row, column };
+
+    assert_eq!(
+        parse_formula("$3=$1+$2").map(|(_, expr, _)| expr),
+        Ok(Expr::Binary(
+            b'+',
+            Box::new(Expr::Reference(reference(None, Some(Absolute(1))))),
+            Box::new(Expr::Reference(reference(None, Some(Absolute(2)))))
+        ))
+    );
+    assert_eq!(
+        parse_formula("@>$< = vsum(@<<..@-1) ; %.2f").map(|(_, expr, format)| (expr, format)),
+        Ok((
+            Expr::Call(
+                Function::Sum,
+                vec![Expr::Range(
+                    reference(Some(First(2)), None),
+                    reference(Some(Relative(-1)), None)
+                )]
+            ),
+            Format::Fixed(2)
+        ))
+    );
+    assert_eq!(
+        parse_formula("$2=-$1^2*3").map(|(_, expr, _)| expr),
+        Ok(Expr::Binary(
+            b'*',
+            Box::new(Expr::Negative(Box::new(Expr::Binary(
+                b'^',
+                Box::new(Expr::Reference(reference(None, Some(Absolute(1))))),
+                Box::new(Expr::Number(2.0))
+            )))),
+            Box::new(Expr::Number(3.0))
+        ))
+    );
+
+    assert_eq!(
+        parse_formula("$2='(+ $1 1)").err(),
+        Some(FormulaErrorKind::Unsupported)
+    );
+    assert_eq!(
+        parse_formula("$2=remote(tbl, @1$1..@2$1) * $c").map(|(_, expr, _)| expr),
+        Ok(Expr::Binary(
+            b'*',
+            Box::new(Expr::Remote(
+                "tbl".into(),
+                Box::new(Expr::Range(
+                    reference(Some(Absolute(1)), Some(Absolute(1))),
+                    reference(Some(Absolute(2)), Some(Absolute(1)))
+                ))
+            )),
+            Box::new(Expr::Constant("c".into()))
+        ))
+    );
+    assert_eq!(
+        parse_formula("$2=$PROP_Effort").err(),
+        Some(FormulaErrorKind::Unsupported)
+    );
+    assert_eq!(
+        parse_formula("$2=vsum(@I..@II)").err(),
+        Some(FormulaErrorKind::Unsupported)
+    );
+    assert_eq!(
+        parse_formula("$-1=1").err(),
+        Some(FormulaErrorKind::Unsupported)
+    );
+    assert_eq!(
+        parse_formula("$2=($1+").err(),
+        Some(FormulaErrorKind::Syntax)
+    );
+    assert_eq!(parse_formula("$2").err(), Some(FormulaErrorKind::Syntax));
+}
+
+#[test]
+fn format() {
+    assert_eq!(Format::Default.apply(3.0), Ok("3".into()));
+    assert_eq!(Format::Default.apply(0.1 + 0.2), Ok("0.3".into()));
+    assert_eq!(
+        Format::Default.apply(10.0 / 3.0),
+        Ok("3.33333333333".into())
+    );
+    assert_eq!(Format::Default.apply(-0.0), Ok("0".into()));
+    assert_eq!(Format::Fixed(2).apply(0.6), Ok("0.60".into()));
+    assert_eq!(Format::Integer.apply(2.6), Ok("3".into()));
+    assert_eq!(
+        Format::parse("%.2f").and_then(|f| f.apply(1.0 / 3.0)),
+        Ok("0.33".into())
+    );
+    assert_eq!(Format::parse("N"), Err(FormulaErrorKind::Unsupported));
+}
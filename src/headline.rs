@@ -1,16 +1,27 @@
-use indextree::NodeId;
+use indextree::{NodeEdge, NodeId};
 use std::borrow::Cow;
+use std::collections::HashMap;
+use std::iter::{from_fn, successors};
 use std::ops::RangeInclusive;
 use std::usize;
 
 use crate::{
     config::ParseConfig,
-    elements::{Element, Title},
+    duration::OrgDuration,
+    elements::{title::parse_node_properties, Element, Keyword, PropertiesMap, Timestamp, Title},
+    export::{DefaultOrgHandler, OrgHandler},
     parsers::{parse_container, Container, OwnedArena},
+    tags::TagConflict,
     validate::{ValidationError, ValidationResult},
     Org,
 };
 
+#[cfg(feature = "chrono")]
+use crate::{
+    config::{keyword_name, TodoLog},
+    elements::{Drawer, Planning},
+};
+
 /// Represents the document in `Org` struct.
 ///
 /// Each `Org` struct only has one `Document`.
@@ -213,7 +224,7 @@ impl Document {
             ),
         }
 
-        org.debug_validate();
+        org.tree_changed();
     }
 
     /// Appends a new child to this document.
@@ -275,7 +286,7 @@ impl Document {
 
         self.doc_n.append(hdl.hdl_n, &mut org.arena);
 
-        org.debug_validate();
+        org.tree_changed();
 
         Ok(())
     }
@@ -341,12 +352,134 @@ impl Document {
             self.doc_n.prepend(hdl.hdl_n, &mut org.arena);
         }
 
-        org.debug_validate();
+        org.tree_changed();
 
         Ok(())
     }
 }
 
+/// Represents the section before the first headline in `Org` struct.
+///
+/// Each `Org` struct only has one `ZerothSection`, it's also available for
+/// documents without any content before the first headline.
+#[derive(Copy, Clone, Debug)]
+pub struct ZerothSection {
+    doc_n: NodeId,
+    sec_n: Option<NodeId>,
+}
+
+impl ZerothSection {
+    /// Returns the ID of the section element, or `None` if the document has
+    /// no content before the first headline.
+    pub fn section_node(self) -> Option<NodeId> {
+        self.sec_n
+    }
+
+    /// Returns an iterator of IDs of the elements in this section.
+    ///
+    /// ```rust
+    /// # use orgize::{elements::Element, Org};
+    /// #
+    /// let org = Org::parse("#+TITLE: t\n\ntext\n* h\n");
+    ///
+    /// let section = org.zeroth_section();
+    /// let elements: Vec<_> = section.elements(&org).map(|n| &org[n]).collect();
+    ///
+    /// assert_eq!(elements.len(), 2);
+    /// assert!(matches!(elements[0], Element::Keyword(_)));
+    /// assert!(matches!(elements[1], Element::Paragraph { .. }));
+    /// ```
+    pub fn elements<'b>(self, org: &'b Org) -> impl Iterator<Item = NodeId> + 'b {
+        self.sec_n
+            .into_iter()
+            .flat_map(move |sec_n| sec_n.children(&org.arena))
+    }
+
+    /// Returns an iterator of keywords in this section, including the ones
+    /// nested in other elements.
+    ///
+    /// ```rust
+    /// # use orgize::Org;
+    /// #
+    /// let org = Org::parse("#+TITLE: t\n#+AUTHOR: a\n* h\n#+TITLE: ignored\n");
+    ///
+    /// let keys: Vec<_> = org
+    ///     .zeroth_section()
+    ///     .keywords(&org)
+    ///     .map(|kw| &*kw.key)
+    ///     .collect();
+    ///
+    /// assert_eq!(keys, ["TITLE", "AUTHOR"]);
+    /// ```
+    pub fn keywords<'a: 'b, 'b>(self, org: &'b Org<'a>) -> impl Iterator<Item = &'b Keyword<'a>> {
+        self.sec_n
+            .into_iter()
+            .flat_map(move |sec_n| sec_n.descendants(&org.arena).skip(1))
+            .filter_map(move |node| match &org[node] {
+                Element::Keyword(kw) => Some(kw),
+                _ => None,
+            })
+    }
+
+    /// Returns the ID of the document property drawer, which is a
+    /// `:PROPERTIES:` drawer at the beginning of this section, optionally
+    /// preceded by comments.
+    pub fn property_drawer_node(self, org: &Org) -> Option<NodeId> {
+        self.elements(org)
+            .find(|&node| !matches!(org[node], Element::Comment(_)))
+            .filter(|&node| matches!(&org[node], Element::Drawer(drawer) if drawer.name == "PROPERTIES"))
+    }
+
+    /// Returns properties of the document property drawer, see
+    /// `property_drawer_node`.
+    ///
+    /// ```rust
+    /// # use orgize::Org;
+    /// #
+    /// let org = Org::parse("# comment\n:PROPERTIES:\n:ID: doc\n:END:\ntext\n");
+    ///
+    /// let properties = org.zeroth_section().properties(&org);
+    /// assert_eq!(properties.get("id"), Some("doc"));
+    /// ```
+    pub fn properties(self, org: &Org) -> PropertiesMap<'static> {
+        let drawer = match self.property_drawer_node(org) {
+            Some(drawer) => drawer,
+            None => return PropertiesMap::new(),
+        };
+
+        // contents are parsed as regular elements, so they are written back
+        // to text before looking up the properties
+        parse_node_properties(&node_contents(drawer, org)).into_owned()
+    }
+
+    /// Sets the content of this section. The section is created if it
+    /// doesn't exist.
+    ///
+    /// ```rust
+    /// # use orgize::Org;
+    /// #
+    /// let mut org = Org::parse("* h\n");
+    ///
+    /// let mut section = org.zeroth_section();
+    /// section.set_content("#+TITLE: new\n", &mut org);
+    ///
+    /// let mut writer = Vec::new();
+    /// org.write_org(&mut writer).unwrap();
+    /// assert_eq!(String::from_utf8(writer).unwrap(), "#+TITLE: new\n* h\n");
+    /// ```
+    pub fn set_content<'a, S>(&mut self, content: S, org: &mut Org<'a>)
+    where
+        S: Into<Cow<'a, str>>,
+    {
+        let mut document = Document {
+            doc_n: self.doc_n,
+            sec_n: self.sec_n,
+        };
+        document.set_section_content(content, org);
+        self.sec_n = document.sec_n;
+    }
+}
+
 /// Represents a headline in `Org` struct.
 ///
 /// Each `Org` has zero or more `Headline`s.
@@ -601,7 +734,7 @@ impl Headline {
 
         self.title_mut(org).raw = content;
 
-        org.debug_validate();
+        org.tree_changed();
     }
 
     /// Changes the section content of this headline.
@@ -669,7 +802,152 @@ impl Headline {
             ),
         }
 
-        org.debug_validate();
+        org.tree_changed();
+    }
+
+    /// Writes this headline and its subheadlines back to org text.
+    ///
+    /// Untouched elements are written as their source text when the org
+    /// writer keeps them, see `DefaultOrgHandler`.
+    ///
+    /// ```rust
+    /// # use orgize::Org;
+    /// #
+    /// let org = Org::parse("* h1\n** h1_1\ntext\n* h2\n");
+    /// let h1 = org.headlines().nth(0).unwrap();
+    ///
+    /// assert_eq!(h1.to_org_string(&org), "* h1\n** h1_1\ntext\n");
+    /// ```
+    pub fn to_org_string(self, org: &Org) -> String {
+        let mut writer = Vec::new();
+        let mut handler = DefaultOrgHandler::default();
+        handler.prepare(org);
+        for edge in self.hdl_n.traverse(&org.arena) {
+            match edge {
+                NodeEdge::Start(n) => handler.start_node(&mut writer, n, &org[n]).unwrap(),
+                NodeEdge::End(n) => handler.end_node(&mut writer, n, &org[n]).unwrap(),
+            }
+        }
+        String::from_utf8_lossy(&writer).into_owned()
+    }
+
+    /// Parses `text` as a subtree and puts it in place of this headline and
+    /// its subheadlines, returns the new headline.
+    ///
+    /// `text` must start with a headline line, and every other headline in
+    /// it must be deeper than the first one. The first headline's level must
+    /// fit in the position of this headline, i.e. the same range as
+    /// `Headline::set_level`. If it doesn't, the whole subtree is shifted to
+    /// the level of this headline when `adjust_level` is `true`, otherwise
+    /// an error is returned and nothing is changed. Nodes parsed from `text`
+    /// are removed from the arena on errors, including the ones the errors
+    /// point at.
+    ///
+    /// ```rust
+    /// # use orgize::Org;
+    /// #
+    /// let mut org = Org::parse("* h1\n** h1_1\n* h2\n");
+    ///
+    /// let h1_1 = org.headlines().nth(1).unwrap();
+    /// assert!(h1_1.parse_replace("* new\n", false, &mut org).is_err());
+    ///
+    /// let new = h1_1
+    ///     .parse_replace("* new\n** child\n", true, &mut org)
+    ///     .unwrap();
+    /// assert_eq!(new.level(), 2);
+    ///
+    /// let mut writer = Vec::new();
+    /// org.write_org(&mut writer).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(writer).unwrap(),
+    ///     "* h1\n** new\n*** child\n* h2\n"
+    /// );
+    /// ```
+    pub fn parse_replace(
+        self,
+        text: &str,
+        adjust_level: bool,
+        org: &mut Org,
+    ) -> ValidationResult<Headline> {
+        let config = org.config.clone();
+        let doc_n = org.arena.new_node(Element::Document { pre_blank: 0 });
+        parse_container(
+            &mut OwnedArena::new(&mut org.arena),
+            Container::Document {
+                content: text,
+                node: doc_n,
+            },
+            &config,
+        );
+
+        // the temporary document is freed with whatever it still contains
+        let parsed = self.parsed_headline(doc_n, adjust_level, org);
+        doc_n.remove_subtree(&mut org.arena);
+        let (hdl_n, lvl) = parsed?;
+
+        if !self.is_detached(org) {
+            self.hdl_n.insert_after(hdl_n, &mut org.arena);
+            self.hdl_n.detach(&mut org.arena);
+        }
+
+        org.tree_changed();
+
+        Ok(Headline::from_node(hdl_n, lvl, org))
+    }
+
+    // detaches the single headline parsed into `doc_n` by `parse_replace`,
+    // and returns it with its level
+    fn parsed_headline(
+        self,
+        doc_n: NodeId,
+        adjust_level: bool,
+        org: &mut Org,
+    ) -> ValidationResult<(NodeId, usize)> {
+        let mut children = doc_n.children(&org.arena);
+        let (hdl_n, lvl) = match children.next() {
+            Some(n) => match org[n] {
+                Element::Headline { level } => (n, level),
+                _ => {
+                    return Err(ValidationError::UnexpectedElement {
+                        expected: "Headline",
+                        at: n,
+                    })
+                }
+            },
+            None => return Err(ValidationError::ExpectedChildren { at: doc_n }),
+        };
+        // a following headline of the same level or shallower isn't a part
+        // of the subtree
+        if let Some(n) = children.next() {
+            return Err(ValidationError::HeadlineLevelMismatch {
+                range: lvl + 1..=usize::MAX,
+                at: n,
+            });
+        }
+
+        let range = if self.is_detached(org) {
+            1..=usize::MAX
+        } else {
+            let min = self
+                .next(org)
+                .map(|hdl| hdl.lvl)
+                .or_else(|| self.parent(org).map(|hdl| hdl.lvl + 1))
+                .unwrap_or(1);
+            let max = self.previous(org).map_or(usize::MAX, |hdl| hdl.lvl);
+            min..=max
+        };
+
+        let lvl = if range.contains(&lvl) {
+            lvl
+        } else if adjust_level {
+            shift_levels(hdl_n, self.lvl as isize - lvl as isize, org);
+            self.lvl
+        } else {
+            return Err(ValidationError::HeadlineLevelMismatch { range, at: hdl_n });
+        };
+
+        hdl_n.detach(&mut org.arena);
+        Ok((hdl_n, lvl))
     }
 
     /// Returns the parent of this headline, or `None` if it is detached or attached to the document.
@@ -708,6 +986,494 @@ impl Headline {
         })
     }
 
+    /// Returns the category of this headline, which is used for grouping
+    /// entries in agenda views.
+    ///
+    /// It's the value of `:CATEGORY:` property of this headline or its
+    /// nearest ancestor, or the last `#+CATEGORY:` keyword before the first
+    /// headline, or `default`, which is normally the file name.
+    ///
+    /// ```rust
+    /// # use orgize::Org;
+    /// #
+    /// let org = Org::parse(
+    ///     r#"#+CATEGORY: work
+    /// * h1
+    /// :PROPERTIES:
+    /// :CATEGORY: home
+    /// :END:
+    /// ** h1_1
+    /// * h2
+    /// "#,
+    /// );
+    ///
+    /// let h1_1 = org.headlines().nth(1).unwrap();
+    /// let h2 = org.headlines().nth(2).unwrap();
+    ///
+    /// assert_eq!(h1_1.category(&org, "todo"), "home");
+    /// assert_eq!(h2.category(&org, "todo"), "work");
+    /// ```
+    pub fn category<'b>(self, org: &'b Org, default: &'b str) -> Cow<'b, str> {
+        let mut headline = Some(self);
+        while let Some(hdl) = headline {
+            if let Some(category) = non_empty_property(hdl.title(org), "CATEGORY") {
+                return Cow::Borrowed(category);
+            }
+            headline = hdl.parent(org);
+        }
+        Cow::Borrowed(keyword_category(org).unwrap_or(default))
+    }
+
+    /// Returns `true` if this headline's todo keyword is a done keyword of
+    /// the configuration `org` was parsed with, see `Org::config`.
+    pub fn is_done(self, org: &Org) -> bool {
+        self.title(org)
+            .keyword
+            .as_ref()
+            .is_some_and(|keyword| org.config().is_done_keyword(keyword))
+    }
+
+    /// Returns `true` if this headline is a habit, i.e. its `STYLE` property
+    /// is `habit`. Its scheduled timestamp tells when it's due, see
+    /// `Timestamp::habit_status`.
+    pub fn is_habit(self, org: &Org) -> bool {
+        non_empty_property(self.title(org), "STYLE")
+            .is_some_and(|style| style.eq_ignore_ascii_case("habit"))
+    }
+
+    /// Replaces tags of this headline.
+    ///
+    /// Tags are set anyway, but if two or more tags of a mutually exclusive
+    /// group of `#+TAGS:` are given, the conflicts are returned, see
+    /// `Org::tag_definitions`.
+    ///
+    /// ```rust
+    /// # use orgize::Org;
+    /// #
+    /// let mut org = Org::parse("#+TAGS: { @work @home } laptop\n* h1\n");
+    ///
+    /// let h1 = org.headlines().next().unwrap();
+    /// let conflicts = h1.set_tags(vec!["@work", "laptop", "@home"], &mut org);
+    ///
+    /// assert_eq!(conflicts[0].tags, ["@work", "@home"]);
+    /// assert_eq!(h1.title(&org).tags, ["@work", "laptop", "@home"]);
+    /// ```
+    pub fn set_tags<'a, I, S>(self, tags: I, org: &mut Org<'a>) -> Vec<TagConflict>
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<Cow<'a, str>>,
+    {
+        let tags: Vec<Cow<'a, str>> = tags.into_iter().map(Into::into).collect();
+        let conflicts = org.tag_definitions().conflicts(&tags);
+        self.title_mut(org).tags = tags;
+        conflicts
+    }
+
+    /// Returns `true` if this headline has tag `tag`. Inherited tags aren't
+    /// included.
+    pub fn has_tag(self, tag: &str, org: &Org) -> bool {
+        self.title(org).tags.iter().any(|t| t == tag)
+    }
+
+    /// Likes `has_tag`, but also returns `true` if `tag` is a group tag of
+    /// `#+TAGS:` and this headline has one of its members, see
+    /// `Org::tags_in_group`.
+    ///
+    /// ```rust
+    /// # use orgize::Org;
+    /// #
+    /// let org = Org::parse(
+    ///     "#+TAGS: [ Project : ProjA ]\n\
+    ///      #+TAGS: [ ProjA : ProjA1 ]\n\
+    ///      * h1 :ProjA1:\n",
+    /// );
+    ///
+    /// let h1 = org.headlines().next().unwrap();
+    /// assert!(!h1.has_tag("Project", &org));
+    /// assert!(h1.has_tag_in_hierarchy("Project", &org));
+    /// ```
+    pub fn has_tag_in_hierarchy(self, tag: &str, org: &Org) -> bool {
+        self.has_tag(tag, org)
+            || org
+                .tag_definitions()
+                .members(tag)
+                .into_iter()
+                .any(|member| self.has_tag(member, org))
+    }
+
+    /// Returns the total duration of closed clocks in this headline and its
+    /// descendants, summed from their `=>` values.
+    ///
+    /// ```rust
+    /// # use orgize::Org;
+    /// #
+    /// let org = Org::parse(
+    ///     r#"
+    /// * h1
+    /// CLOCK: [2024-03-04 Mon 09:00]--[2024-03-04 Mon 10:30] =>  1:30
+    /// ** h1_1
+    /// :LOGBOOK:
+    /// CLOCK: [2024-03-05 Tue 09:00]--[2024-03-05 Tue 09:45] =>  0:45
+    /// CLOCK: [2024-03-06 Wed 09:00]
+    /// :END:
+    /// "#,
+    /// );
+    ///
+    /// let h1 = org.headlines().next().unwrap();
+    /// assert_eq!(h1.clock_sum(&org).to_string(), "2:15");
+    /// ```
+    pub fn clock_sum(self, org: &Org) -> OrgDuration {
+        self.hdl_n
+            .descendants(&org.arena)
+            .filter_map(|node| match &org[node] {
+                Element::Clock(clock) => clock.duration(),
+                _ => None,
+            })
+            .sum()
+    }
+
+    /// Returns the first active timestamp in the title of this headline, or
+    /// else in the first paragraph of its section, like the `TIMESTAMP`
+    /// special property in Emacs.
+    ///
+    /// Timestamps of the planning line aren't included, so a `DEADLINE:`
+    /// typed in the title, or in a paragraph because it isn't right below
+    /// the headline, is found here as a plain timestamp.
+    ///
+    /// ```rust
+    /// # use orgize::Org;
+    /// #
+    /// let org = Org::parse(
+    ///     "* Call Bob <2024-03-05 Tue>\n\
+    ///      * Report\n:PROPERTIES:\n:ID: r\n:END:\n\
+    ///      text\n\nDEADLINE: <2024-03-08 Fri>\n\
+    ///      * Review\nDEADLINE: <2024-03-09 Sat>\n\
+    ///      Due [2024-03-09 Sat] <2024-03-10 Sun>\n",
+    /// );
+    /// let timestamps: Vec<_> = org
+    ///     .headlines()
+    ///     .map(|hdl| hdl.first_active_timestamp(&org).map(|ts| ts.to_string()))
+    ///     .collect();
+    ///
+    /// assert_eq!(
+    ///     timestamps,
+    ///     [Some("<2024-03-05 Tue>".into()), None, Some("<2024-03-10 Sun>".into())]
+    /// );
+    /// ```
+    pub fn first_active_timestamp<'a: 'b, 'b>(self, org: &'b Org<'a>) -> Option<&'b Timestamp<'a>> {
+        let paragraph = self.section_node().and_then(|section| {
+            section
+                .children(&org.arena)
+                .find(|&node| matches!(org[node], Element::Paragraph { .. }))
+        });
+
+        std::iter::once(self.title_node())
+            .chain(paragraph)
+            .flat_map(|node| node.descendants(&org.arena))
+            .find_map(|node| match &org[node] {
+                Element::Timestamp(
+                    timestamp @ (Timestamp::Active { .. }
+                    | Timestamp::ActiveRange { .. }
+                    | Timestamp::Diary { .. }),
+                ) => Some(timestamp),
+                _ => None,
+            })
+    }
+
+    /// Marks this headline as done, like `org-todo` in Emacs.
+    ///
+    /// The todo keyword is set to the first done keyword of `Org::config`,
+    /// and `CLOSED:` is set to `now` unless `Startup::log_done` of
+    /// `Org::startup` is `None`. If the scheduled or deadline timestamp has
+    /// a repeater, the task repeats instead: timestamps with a repeater are
+    /// moved forward, the todo keyword is set back to a not-done one, and the
+    /// `LAST_REPEAT` property is set to `now`.
+    ///
+    /// In both cases, a state change entry is added to the `LOGBOOK` drawer
+    /// if the done keyword or the current keyword has a logging marker, see
+    /// `TodoKeyword`. Otherwise, if `Startup::log_done` is
+    /// `Some(TodoLog::Note)`, a closing note entry is added when the
+    /// headline is done. `now` is always written as an inactive timestamp.
+    /// If a section is created for the logbook, `section_node` of this
+    /// `Headline` is outdated, get it again from `org`.
+    ///
+    /// Does nothing if this headline is already done, or there's no done
+    /// keyword.
+    ///
+    /// ```rust
+    /// use orgize::{elements::Timestamp, Org};
+    /// use std::convert::TryFrom;
+    ///
+    /// let mut org = Org::parse("* TODO task\n");
+    /// let now = Timestamp::try_from("[2024-03-05 Tue 14:00]").unwrap();
+    ///
+    /// let task = org.headlines().next().unwrap();
+    /// task.complete(&now, &mut org);
+    ///
+    /// let mut writer = Vec::new();
+    /// org.write_org(&mut writer).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(writer).unwrap(),
+    ///     "* DONE task\nCLOSED: [2024-03-05 Tue 14:00]\n"
+    /// );
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn complete(self, now: &Timestamp, org: &mut Org) {
+        let config = org.config().clone();
+        let done = match config.todo_keywords.1.first() {
+            Some(done) => keyword_name(done).to_string(),
+            None => return,
+        };
+        let from = self.title(org).keyword.as_deref().map(String::from);
+        if from
+            .as_deref()
+            .is_some_and(|from| config.is_done_keyword(from))
+        {
+            return;
+        }
+
+        let log = config
+            .todo_keyword(&done)
+            .is_some_and(|keyword| keyword.on_entry_log.is_some())
+            || from
+                .as_deref()
+                .and_then(|from| config.todo_keyword(from))
+                .is_some_and(|keyword| keyword.on_exit_log.is_some());
+        let now = inactive(now);
+
+        let title = self.title_mut(org);
+        let mut repeated = false;
+        if let (Some(planning), Some(today)) = (&mut title.planning, now.start_date()) {
+            for timestamp in planning
+                .scheduled
+                .iter_mut()
+                .chain(planning.deadline.iter_mut())
+            {
+                repeated |= timestamp.repeat_after(today);
+            }
+        }
+
+        if repeated {
+            // goes back to the previous state, or the first not-done one
+            title.keyword = from
+                .clone()
+                .filter(|from| config.is_todo_keyword(from))
+                .or_else(|| {
+                    config
+                        .todo_keywords
+                        .0
+                        .first()
+                        .map(|todo| keyword_name(todo).to_string())
+                })
+                .map(Cow::Owned);
+
+            title.properties.set("LAST_REPEAT", now.to_string());
+        } else {
+            title.keyword = Some(Cow::Owned(done.clone()));
+        }
+
+        if !repeated && config.startup.log_done.is_some() {
+            title
+                .planning
+                .get_or_insert_with(|| {
+                    Box::new(Planning {
+                        deadline: None,
+                        scheduled: None,
+                        closed: None,
+                    })
+                })
+                .closed = Some(now.clone());
+        }
+
+        if log {
+            let entry = format!(
+                "- State {:<12} from {:<12} {}",
+                format!("\"{}\"", done),
+                from.map(|from| format!("\"{}\"", from)).unwrap_or_default(),
+                now
+            );
+            self.add_log_entry(&entry, org);
+        } else if !repeated && config.startup.log_done == Some(TodoLog::Note) {
+            self.add_log_entry(&format!("- CLOSING NOTE {}", now), org);
+        }
+    }
+
+    /// Reopens this headline if it's done: the todo keyword is set to the
+    /// first not-done keyword of `Org::config`, and `CLOSED:` is removed.
+    ///
+    /// ```rust
+    /// use orgize::Org;
+    ///
+    /// let mut org = Org::parse("* DONE task\nCLOSED: [2024-03-05 Tue 14:00]\n");
+    ///
+    /// let task = org.headlines().next().unwrap();
+    /// task.reopen(&mut org);
+    ///
+    /// let mut writer = Vec::new();
+    /// org.write_org(&mut writer).unwrap();
+    /// assert_eq!(String::from_utf8(writer).unwrap(), "* TODO task\n");
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn reopen(self, org: &mut Org) {
+        let config = org.config().clone();
+        let title = self.title_mut(org);
+        if !title
+            .keyword
+            .as_deref()
+            .is_some_and(|keyword| config.is_done_keyword(keyword))
+        {
+            return;
+        }
+
+        title.keyword = config
+            .todo_keywords
+            .0
+            .first()
+            .map(|todo| Cow::Owned(keyword_name(todo).to_string()));
+
+        if let Some(planning) = &mut title.planning {
+            planning.closed = None;
+        }
+        if title
+            .planning
+            .as_ref()
+            .is_some_and(|planning| planning.scheduled.is_none() && planning.deadline.is_none())
+        {
+            title.planning = None;
+        }
+    }
+
+    // adds `entry` to the beginning of the logbook drawer, which is created
+    // at the beginning of the section if it doesn't exist
+    #[cfg(feature = "chrono")]
+    fn add_log_entry(self, entry: &str, org: &mut Org) {
+        // the section follows the title, and `sec_n` may be outdated
+        let section = self
+            .ttl_n
+            .following_siblings(&org.arena)
+            .nth(1)
+            .filter(|&node| matches!(org[node], Element::Section));
+        let sec_n = match section {
+            Some(sec_n) => sec_n,
+            None => {
+                let sec_n = org.arena.new_node(Element::Section);
+                self.ttl_n.insert_after(sec_n, &mut org.arena);
+                sec_n
+            }
+        };
+
+        let logbook = sec_n.children(&org.arena).next().filter(|&node| {
+            matches!(&org[node], Element::Drawer(drawer) if drawer.name.eq_ignore_ascii_case("LOGBOOK"))
+        });
+
+        let (drawer, content) = match logbook {
+            Some(drawer) => {
+                let content = format!("{}\n{}", entry, node_contents(drawer, org));
+                let children: Vec<_> = drawer.children(&org.arena).collect();
+                for child in children {
+                    child.remove_subtree(&mut org.arena);
+                }
+                (drawer, content)
+            }
+            None => {
+                let drawer = org.arena.new_node(Element::Drawer(Drawer {
+                    name: "LOGBOOK".into(),
+                    pre_blank: 0,
+                    post_blank: 0,
+                }));
+                sec_n.prepend(drawer, &mut org.arena);
+                (drawer, format!("{}\n", entry))
+            }
+        };
+
+        let config = org.config().clone();
+        parse_container(
+            &mut OwnedArena::new(&mut org.arena),
+            Container::Block {
+                node: drawer,
+                content: &content,
+            },
+            &config,
+        );
+
+        org.tree_changed();
+    }
+
+    /// Sets property `key` of this headline to `value`, see
+    /// `PropertiesMap::set`.
+    ///
+    /// The property drawer keeps its ordering, indentation and alignment.
+    ///
+    /// ```rust
+    /// # use orgize::Org;
+    /// #
+    /// let mut org = Org::parse("* h\n:PROPERTIES:\n:ID:     a1\n:VAR:    x\n:VAR+:   y\n:END:\n");
+    ///
+    /// let h = org.headlines().next().unwrap();
+    /// h.set_property("var", "z", &mut org);
+    /// h.set_property("EFFORT", "1:00", &mut org);
+    ///
+    /// let mut writer = Vec::new();
+    /// org.write_org(&mut writer).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(writer).unwrap(),
+    ///     "* h\n:PROPERTIES:\n:ID:     a1\n:VAR:    z\n:VAR+:   y\n:EFFORT: 1:00\n:END:\n"
+    /// );
+    /// ```
+    pub fn set_property<'a, K, V>(self, key: K, value: V, org: &mut Org<'a>)
+    where
+        K: Into<Cow<'a, str>>,
+        V: Into<Cow<'a, str>>,
+    {
+        self.title_mut(org).properties.set(key, value);
+    }
+
+    /// Removes property `key` of this headline, along with accumulating
+    /// `KEY+` properties. Returns `true` if any property was removed.
+    pub fn remove_property(self, key: &str, org: &mut Org) -> bool {
+        self.title_mut(org).properties.remove(key)
+    }
+
+    /// Returns `true` if this headline or any of its ancestors has a
+    /// non-nil `:UNNUMBERED:` property, which excludes it from numbering.
+    ///
+    /// ```rust
+    /// # use orgize::Org;
+    /// #
+    /// let org = Org::parse("* a\n:PROPERTIES:\n:UNNUMBERED: t\n:END:\n** b\n* c\n");
+    ///
+    /// let unnumbered: Vec<_> = org.headlines().map(|h| h.is_unnumbered(&org)).collect();
+    /// assert_eq!(unnumbered, vec![true, true, false]);
+    /// ```
+    pub fn is_unnumbered(self, org: &Org) -> bool {
+        self.unnumbered(org).is_some()
+    }
+
+    // value of the nearest non-nil `:UNNUMBERED:` property
+    pub(crate) fn unnumbered<'b>(self, org: &'b Org) -> Option<&'b str> {
+        let mut headline = Some(self);
+        while let Some(hdl) = headline {
+            let value = hdl.title(org).properties.get("UNNUMBERED").map(str::trim);
+            match value {
+                Some(value) if !value.is_empty() && value != "nil" => return Some(value),
+                _ => headline = hdl.parent(org),
+            }
+        }
+        None
+    }
+
+    /// Returns the value of `:ALT_TITLE:` property, which replaces the title
+    /// in the table of contents.
+    pub fn alt_title<'b>(self, org: &'b Org) -> Option<&'b str> {
+        non_empty_property(self.title(org), "ALT_TITLE")
+    }
+
+    /// Returns the value of `:EXPORT_FILE_NAME:` property, which names the
+    /// output when exporting this subtree.
+    pub fn export_file_name<'b>(self, org: &'b Org) -> Option<&'b str> {
+        non_empty_property(self.title(org), "EXPORT_FILE_NAME")
+    }
+
     /// Returns an iterator of this headline's children.
     ///
     /// ```rust
@@ -882,6 +1648,154 @@ impl Headline {
         })
     }
 
+    /// Returns the previous sibling of this headline, same as `previous`.
+    pub fn previous_sibling(self, org: &Org) -> Option<Headline> {
+        self.previous(org)
+    }
+
+    /// Returns the next sibling of this headline, same as `next`.
+    pub fn next_sibling(self, org: &Org) -> Option<Headline> {
+        self.next(org)
+    }
+
+    /// Returns the previous headline with the same level, like
+    /// `org-backward-heading-same-level` in Emacs.
+    ///
+    /// Deeper siblings are skipped, and it stops at a shallower one, so it
+    /// never leaves the parent headline.
+    ///
+    /// ```rust
+    /// # use orgize::Org;
+    /// #
+    /// let org = Org::parse(
+    ///     r#"
+    /// * h1
+    /// *** h1_1
+    /// ** h1_2
+    /// **** h1_3
+    /// ** h1_4
+    /// "#,
+    ///     );
+    ///
+    /// let h1_1 = org.headlines().nth(1).unwrap();
+    /// let h1_2 = org.headlines().nth(2).unwrap();
+    /// let h1_4 = org.headlines().nth(4).unwrap();
+    ///
+    /// assert_eq!(h1_4.previous_same_level(&org).unwrap().title(&org).raw, "h1_2");
+    ///
+    /// assert!(h1_2.previous_same_level(&org).is_none());
+    /// // h1_2 is shallower than h1_1
+    /// assert!(h1_1.next_same_level(&org).is_none());
+    /// ```
+    pub fn previous_same_level(self, org: &Org) -> Option<Headline> {
+        successors(self.previous(org), |hdl| hdl.previous(org))
+            .take_while(|hdl| hdl.lvl >= self.lvl)
+            .find(|hdl| hdl.lvl == self.lvl)
+    }
+
+    /// Returns the next headline with the same level, like
+    /// `org-forward-heading-same-level` in Emacs.
+    ///
+    /// Deeper siblings are skipped, and it stops at a shallower one, so it
+    /// never leaves the parent headline.
+    ///
+    /// ```rust
+    /// # use orgize::Org;
+    /// #
+    /// let org = Org::parse(
+    ///     r#"
+    /// * h1
+    /// ** h1_1
+    /// **** h1_2
+    /// ** h1_3
+    /// * h2
+    /// "#,
+    ///     );
+    ///
+    /// let h1 = org.headlines().nth(0).unwrap();
+    /// let h1_1 = org.headlines().nth(1).unwrap();
+    /// let h1_3 = org.headlines().nth(3).unwrap();
+    ///
+    /// assert_eq!(h1.next_same_level(&org).unwrap().title(&org).raw, "h2");
+    /// assert_eq!(h1_1.next_same_level(&org).unwrap().title(&org).raw, "h1_3");
+    ///
+    /// // last one in its parent
+    /// assert!(h1_3.next_same_level(&org).is_none());
+    /// ```
+    pub fn next_same_level(self, org: &Org) -> Option<Headline> {
+        successors(self.next(org), |hdl| hdl.next(org))
+            .take_while(|hdl| hdl.lvl >= self.lvl)
+            .find(|hdl| hdl.lvl == self.lvl)
+    }
+
+    /// Returns an iterator of this headline's ancestors, starting from its
+    /// parent.
+    ///
+    /// ```rust
+    /// # use orgize::Org;
+    /// #
+    /// let org = Org::parse(
+    ///     r#"
+    /// * h1
+    /// ** h1_1
+    /// *** h1_1_1
+    /// "#,
+    ///     );
+    ///
+    /// let h1 = org.headlines().nth(0).unwrap();
+    /// let h1_1_1 = org.headlines().nth(2).unwrap();
+    ///
+    /// let titles: Vec<_> = h1_1_1
+    ///     .ancestors(&org)
+    ///     .map(|hdl| hdl.title(&org).raw.as_ref())
+    ///     .collect();
+    /// assert_eq!(titles, ["h1_1", "h1"]);
+    ///
+    /// assert!(h1.ancestors(&org).next().is_none());
+    /// ```
+    pub fn ancestors<'a>(self, org: &'a Org) -> impl Iterator<Item = Headline> + 'a {
+        successors(self.parent(org), move |hdl| hdl.parent(org))
+    }
+
+    /// Returns an iterator of this headline's descendants in document order,
+    /// not including itself.
+    ///
+    /// ```rust
+    /// # use orgize::Org;
+    /// #
+    /// let org = Org::parse(
+    ///     r#"
+    /// * h1
+    /// ** h1_1
+    /// *** h1_1_1
+    /// ** h1_2
+    /// * h2
+    /// "#,
+    ///     );
+    ///
+    /// let h1 = org.headlines().nth(0).unwrap();
+    /// let h2 = org.headlines().nth(4).unwrap();
+    ///
+    /// let titles: Vec<_> = h1
+    ///     .descendants(&org)
+    ///     .map(|hdl| hdl.title(&org).raw.as_ref())
+    ///     .collect();
+    /// assert_eq!(titles, ["h1_1", "h1_1_1", "h1_2"]);
+    ///
+    /// assert!(h2.descendants(&org).next().is_none());
+    /// ```
+    pub fn descendants<'a>(self, org: &'a Org) -> impl Iterator<Item = Headline> + 'a {
+        let mut stack: Vec<Headline> = self.children(org).collect();
+        stack.reverse();
+        from_fn(move || {
+            let hdl = stack.pop()?;
+            let len = stack.len();
+            stack.extend(hdl.children(org));
+            stack[len..].reverse();
+            Some(hdl)
+        })
+    }
+
     /// Detaches this headline from arena.
     ///
     /// ```rust
@@ -915,6 +1829,7 @@ impl Headline {
     /// ```
     pub fn detach(self, org: &mut Org) {
         self.hdl_n.detach(&mut org.arena);
+        org.tree_changed();
     }
 
     /// Returns `true` if this headline is detached.
@@ -983,7 +1898,7 @@ impl Headline {
 
         self.hdl_n.append(hdl.hdl_n, &mut org.arena);
 
-        org.debug_validate();
+        org.tree_changed();
 
         Ok(())
     }
@@ -1037,7 +1952,7 @@ impl Headline {
             .unwrap_or(self.ttl_n)
             .insert_after(hdl.hdl_n, &mut org.arena);
 
-        org.debug_validate();
+        org.tree_changed();
 
         Ok(())
     }
@@ -1105,7 +2020,7 @@ impl Headline {
 
         self.hdl_n.insert_before(hdl.hdl_n, &mut org.arena);
 
-        org.debug_validate();
+        org.tree_changed();
 
         Ok(())
     }
@@ -1175,7 +2090,7 @@ impl Headline {
 
         self.hdl_n.insert_after(hdl.hdl_n, &mut org.arena);
 
-        org.debug_validate();
+        org.tree_changed();
 
         Ok(())
     }
@@ -1206,6 +2121,15 @@ impl Org<'_> {
         Document::from_org(self)
     }
 
+    /// Returns the `ZerothSection`, the content before the first headline.
+    pub fn zeroth_section(&self) -> ZerothSection {
+        let document = self.document();
+        ZerothSection {
+            doc_n: document.doc_n,
+            sec_n: document.sec_n,
+        }
+    }
+
     /// Returns an iterator of `Headline`s.
     pub fn headlines(&self) -> impl Iterator<Item = Headline> + '_ {
         self.root
@@ -1216,4 +2140,93 @@ impl Org<'_> {
                 _ => None,
             })
     }
+
+    /// Returns the category of each headline, in document order.
+    ///
+    /// Same as calling `Headline::category` on each headline, but
+    /// inherited categories are looked up only once.
+    pub fn categories<'b>(&'b self, default: &'b str) -> Vec<(Headline, Cow<'b, str>)> {
+        let default = keyword_category(self).unwrap_or(default);
+        let mut inherited = HashMap::new();
+
+        self.headlines()
+            .map(|hdl| {
+                let category =
+                    non_empty_property(hdl.title(self), "CATEGORY").unwrap_or_else(|| {
+                        self.arena[hdl.hdl_n]
+                            .parent()
+                            .and_then(|parent| inherited.get(&parent).copied())
+                            .unwrap_or(default)
+                    });
+                inherited.insert(hdl.hdl_n, category);
+                (hdl, Cow::Borrowed(category))
+            })
+            .collect()
+    }
+}
+
+// writes the contents of `node` back to text
+pub(crate) fn node_contents(node: NodeId, org: &Org) -> String {
+    let mut writer = Vec::new();
+    let mut handler = DefaultOrgHandler::default();
+    for edge in node.traverse(&org.arena) {
+        match edge {
+            NodeEdge::Start(n) if n != node => handler.start_node(&mut writer, n, &org[n]).unwrap(),
+            NodeEdge::End(n) if n != node => handler.end_node(&mut writer, n, &org[n]).unwrap(),
+            _ => (),
+        }
+    }
+    String::from_utf8_lossy(&writer).into_owned()
+}
+
+// adds `delta` to the levels of headline `node` and its subheadlines
+fn shift_levels(node: NodeId, delta: isize, org: &mut Org) {
+    let nodes: Vec<_> = node.descendants(&org.arena).collect();
+    for n in nodes {
+        match &mut org[n] {
+            Element::Headline { level } => *level = (*level as isize + delta) as usize,
+            Element::Title(title) => title.level = (title.level as isize + delta) as usize,
+            _ => (),
+        }
+    }
+}
+
+// `now` as an inactive timestamp, ranges are reduced to their starts
+#[cfg(feature = "chrono")]
+fn inactive(now: &Timestamp) -> Timestamp<'static> {
+    match now {
+        Timestamp::Active { start, .. }
+        | Timestamp::Inactive { start, .. }
+        | Timestamp::ActiveRange { start, .. }
+        | Timestamp::InactiveRange { start, .. } => Timestamp::Inactive {
+            start: start.clone().into_owned(),
+            repeater: None,
+            delay: None,
+            raw: None,
+        },
+        Timestamp::Diary { .. } => now.clone().into_owned(),
+    }
+}
+
+fn non_empty_property<'b>(title: &'b Title, key: &str) -> Option<&'b str> {
+    title
+        .properties
+        .get(key)
+        .map(str::trim)
+        .filter(|value| !value.is_empty())
+}
+
+// only keywords in the zeroth section are looked up, so it's cheap to call
+// for each headline
+fn keyword_category<'b>(org: &'b Org) -> Option<&'b str> {
+    let section = org.document().section_node()?;
+    section
+        .children(&org.arena)
+        .filter_map(|node| match &org[node] {
+            Element::Keyword(kw) if kw.key.eq_ignore_ascii_case("CATEGORY") => {
+                Some(kw.value.trim())
+            }
+            _ => None,
+        })
+        .rfind(|value| !value.is_empty())
 }
@@ -0,0 +1,269 @@
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+
+use crate::{
+    elements::{timestamp::parse_timestamp, Element, Timestamp},
+    headline::node_contents,
+    timestamps::cmp_start,
+    Headline, Org,
+};
+
+#[cfg(feature = "chrono")]
+use crate::duration::OrgDuration;
+
+/// Todo keyword change of a headline, see `Headline::state_history`
+#[derive(Debug, Clone, PartialEq)]
+pub struct StateChange {
+    /// Keyword before the change, `None` if the headline had no keyword or
+    /// was just created
+    pub from: Option<String>,
+    /// Keyword after the change, `None` if it was removed
+    pub to: Option<String>,
+    /// Time of the change, `None` if it's unknown
+    pub timestamp: Option<Timestamp<'static>>,
+}
+
+impl Headline {
+    /// Returns todo keyword changes of this headline, oldest first.
+    ///
+    /// Changes are read from `- State "DONE" from "TODO" [timestamp]` list
+    /// items, which are placed in a drawer like `LOGBOOK` or directly in the
+    /// section. They're sorted by their timestamps, an entry without
+    /// timestamp stays after the entry written below it, as Emacs adds
+    /// newer entries on top.
+    ///
+    /// + The first change is the creation of the headline, if it has a
+    ///   `CREATED` property holding a timestamp, or any timestamp. It's dated
+    ///   at the former, or the earliest one of the latter. It's not added if
+    ///   the first recorded change is from no keyword.
+    /// + The last change is to the current keyword, without timestamp, if the
+    ///   last recorded change is to another keyword.
+    ///
+    /// ```rust
+    /// use orgize::Org;
+    ///
+    /// let org = Org::parse(
+    ///     r#"* DONE task
+    /// :LOGBOOK:
+    /// - State "DONE"       from "TODO"       [2024-03-08 Fri 17:00]
+    /// - State "TODO"       from              [2024-03-01 Fri 09:00]
+    /// :END:
+    /// "#,
+    /// );
+    ///
+    /// let task = org.headlines().next().unwrap();
+    /// let changes: Vec<_> = task
+    ///     .state_history(&org)
+    ///     .into_iter()
+    ///     .map(|change| (change.from, change.to))
+    ///     .collect();
+    /// assert_eq!(
+    ///     changes,
+    ///     [
+    ///         (None, Some("TODO".into())),
+    ///         (Some("TODO".into()), Some("DONE".into())),
+    ///     ]
+    /// );
+    /// ```
+    pub fn state_history(self, org: &Org) -> Vec<StateChange> {
+        let mut changes: Vec<StateChange> = self
+            .section_node()
+            .into_iter()
+            .flat_map(|sec_n| sec_n.descendants(&org.arena))
+            .filter(|&node| {
+                matches!(org[node], Element::ListItem(_))
+                    && org.arena[node]
+                        .parent()
+                        .and_then(|list| org.arena[list].parent())
+                        .is_some_and(|parent| {
+                            matches!(org[parent], Element::Section | Element::Drawer(_))
+                        })
+            })
+            .filter_map(|item| parse_state_change(&node_contents(item, org)))
+            .collect();
+
+        // newer entries come first, and entries without timestamp are sorted
+        // along with the entry before them
+        changes.reverse();
+        let mut keys = Vec::with_capacity(changes.len());
+        for change in &changes {
+            let key = change
+                .timestamp
+                .clone()
+                .or_else(|| keys.last().cloned().flatten());
+            keys.push(key);
+        }
+        let mut keyed: Vec<_> = keys.into_iter().zip(changes).collect();
+        keyed.sort_by(|(a, _), (b, _)| match (a, b) {
+            (Some(a), Some(b)) => cmp_start(a, b),
+            (None, Some(_)) => Ordering::Less,
+            (Some(_), None) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        });
+        let mut changes: Vec<_> = keyed.into_iter().map(|(_, change)| change).collect();
+
+        let current = self.title(org).keyword.as_deref().map(String::from);
+
+        // a first change from no keyword is the creation already
+        if changes.first().map_or(true, |first| first.from.is_some()) {
+            if let Some(created) = self.created(org) {
+                let to = match changes.first() {
+                    Some(first) => first.from.clone(),
+                    None => current.clone(),
+                };
+                changes.insert(
+                    0,
+                    StateChange {
+                        from: None,
+                        to,
+                        timestamp: Some(created),
+                    },
+                );
+            }
+        }
+
+        if changes
+            .last()
+            .map_or(current.is_some(), |last| last.to != current)
+        {
+            changes.push(StateChange {
+                from: changes.last().and_then(|last| last.to.clone()),
+                to: current,
+                timestamp: None,
+            });
+        }
+
+        changes
+    }
+
+    /// Returns the total time this headline spent in todo keyword `keyword`,
+    /// see `Headline::state_history`.
+    ///
+    /// Each change with timestamp starts a period, which ends at the next
+    /// change with timestamp. The last period ends at `now`, if its keyword
+    /// is the current keyword of this headline.
+    ///
+    /// ```rust
+    /// use orgize::{elements::Timestamp, Org};
+    /// use std::convert::TryFrom;
+    ///
+    /// let org = Org::parse(
+    ///     r#"* DONE task
+    /// - State "DONE"       from "TODO"       [2024-03-02 Sat 12:00]
+    /// - State "TODO"       from              [2024-03-01 Fri 09:00]
+    /// "#,
+    /// );
+    ///
+    /// let task = org.headlines().next().unwrap();
+    /// let now = Timestamp::try_from("[2024-03-02 Sat 13:30]").unwrap();
+    /// assert_eq!(task.time_in_state("TODO", &now, &org).to_string(), "27:00");
+    /// assert_eq!(task.time_in_state("DONE", &now, &org).to_string(), "1:30");
+    /// ```
+    #[cfg(feature = "chrono")]
+    pub fn time_in_state(self, keyword: &str, now: &Timestamp, org: &Org) -> OrgDuration {
+        let current = self.title(org).keyword.as_deref().map(String::from);
+        let timed: Vec<_> = self
+            .state_history(org)
+            .into_iter()
+            .filter_map(|change| Some((change.timestamp.as_ref()?.start_datetime()?, change.to)))
+            .collect();
+
+        let mut total = OrgDuration::default();
+        for (i, (start, to)) in timed.iter().enumerate() {
+            if to.as_deref() != Some(keyword) {
+                continue;
+            }
+            let end = match timed.get(i + 1) {
+                Some((end, _)) => Some(*end),
+                None if *to == current => now.start_datetime(),
+                None => None,
+            };
+            if let Some(minutes) =
+                end.and_then(|end| u64::try_from((end - *start).num_minutes()).ok())
+            {
+                total = total + OrgDuration::from_minutes(minutes);
+            }
+        }
+        total
+    }
+
+    // value of `CREATED` property, or the earliest timestamp of this headline
+    fn created(self, org: &Org) -> Option<Timestamp<'static>> {
+        if let Some(created) = self
+            .title(org)
+            .properties
+            .get("CREATED")
+            .and_then(|value| Timestamp::try_from(value.trim()).ok())
+        {
+            return Some(created.into_owned());
+        }
+
+        org.timestamps()
+            .filter(|entry| {
+                entry
+                    .context
+                    .headline()
+                    .is_some_and(|hdl| hdl.headline_node() == self.headline_node())
+            })
+            .map(|entry| entry.timestamp)
+            .filter(|timestamp| !matches!(**timestamp, Timestamp::Diary { .. }))
+            .min_by(|a, b| cmp_start(a, b))
+            .map(|timestamp| timestamp.into_owned().into_owned())
+    }
+}
+
+// parses `State "DONE" from "TODO" [timestamp]`
+fn parse_state_change(text: &str) -> Option<StateChange> {
+    let rest = text.trim_start().strip_prefix("State")?.trim_start();
+    let (to, rest) = quoted(rest)?;
+    let rest = rest.trim_start().strip_prefix("from")?.trim_start();
+    let (from, rest) = match quoted(rest) {
+        Some((from, rest)) => (Some(from), rest.trim_start()),
+        None => (None, rest),
+    };
+    let timestamp = parse_timestamp(rest)
+        .ok()
+        .map(|(_, timestamp)| timestamp.into_owned());
+
+    Some(StateChange {
+        from: from.filter(|from| !from.is_empty()).map(String::from),
+        to: Some(to).filter(|to| !to.is_empty()).map(String::from),
+        timestamp,
+    })
+}
+
+// `"TODO"` to `TODO`
+fn quoted(input: &str) -> Option<(&str, &str)> {
+    let input = input.strip_prefix('"')?;
+    let end = input.find('"')?;
+    Some((&input[..end], &input[end + 1..]))
+}
+
+#[test]
+fn parse() {
+    let change =
+        parse_state_change("State \"DONE\"       from \"TODO\"       [2024-03-08 Fri 17:00]\n")
+            .unwrap();
+    assert_eq!(change.from.as_deref(), Some("TODO"));
+    assert_eq!(change.to.as_deref(), Some("DONE"));
+    assert_eq!(
+        change.timestamp.unwrap().to_string(),
+        "[2024-03-08 Fri 17:00]"
+    );
+
+    let change = parse_state_change(
+        "State \"TODO\"       from              [2024-03-08 Fri 17:00] \\\\\n  note\n",
+    )
+    .unwrap();
+    assert_eq!(change.from, None);
+    assert!(change.timestamp.is_some());
+
+    let change = parse_state_change("State \"WAIT\" from \"TODO\"").unwrap();
+    assert_eq!(change.timestamp, None);
+
+    assert_eq!(
+        parse_state_change("Note taken on [2024-03-08 Fri 17:00]"),
+        None
+    );
+    assert_eq!(parse_state_change("State DONE from TODO"), None);
+}
@@ -0,0 +1,279 @@
+use std::collections::BTreeMap;
+
+use indextree::NodeId;
+
+use crate::{
+    elements::{Element, SourceBlock},
+    export::{format_parts, FormatPart},
+    noweb::unindented_contents,
+    LineIndex, Org,
+};
+
+/// Usage of a language in source code, see `Org::languages`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct LanguageUsage {
+    /// Language, e.g. `rust`
+    pub language: String,
+    /// Number of source blocks
+    pub blocks: usize,
+    /// Number of lines of contents of source blocks
+    pub lines: usize,
+    /// Number of inline source blocks, e.g. `src_rust{1 + 1}`
+    pub inline: usize,
+}
+
+/// Options of `Org::extract_language`
+#[derive(Debug, Clone)]
+pub struct ExtractConfig {
+    /// Line written before the contents of each block, default is
+    /// `"# ==> %f:%l"`
+    ///
+    /// It's written as-is, except for `%f` replaced with `file_name`, `%l`
+    /// with the line of the first line of contents in the parsed text,
+    /// starting from 1, and `%%` with `%`. `%l` is replaced with nothing
+    /// for blocks created after parsing.
+    pub separator: String,
+    /// Replacement of `%f` in `separator`, default is empty
+    pub file_name: String,
+}
+
+impl Default for ExtractConfig {
+    fn default() -> Self {
+        ExtractConfig {
+            separator: String::from("# ==> %f:%l"),
+            file_name: String::new(),
+        }
+    }
+}
+
+/// Source blocks of a language joined into one text, see
+/// `Org::extract_language`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Extracted {
+    /// Joined contents, each block preceded by a separator line
+    pub code: String,
+    /// Blocks in `code`, in document order
+    pub blocks: Vec<ExtractedBlock>,
+}
+
+/// Source block in `Extracted::code`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExtractedBlock {
+    /// Node of the source block
+    pub node: NodeId,
+    /// Line of `Extracted::code` where contents of the block start,
+    /// starting from 0
+    pub line: usize,
+    /// Number of lines of contents
+    pub lines: usize,
+    /// Line of the parsed text where contents of the block start, starting
+    /// from 0, `None` if the block was created after parsing
+    pub source_line: Option<usize>,
+}
+
+impl Extracted {
+    /// Returns the block containing `line` of `code`, and the line inside
+    /// its contents, both starting from 0. It's `None` for separator lines.
+    pub fn origin(&self, line: usize) -> Option<(&ExtractedBlock, usize)> {
+        let i = self.blocks.partition_point(|block| block.line <= line);
+        let block = self.blocks.get(i.checked_sub(1)?)?;
+        let offset = line - block.line;
+        if offset < block.lines {
+            Some((block, offset))
+        } else {
+            None
+        }
+    }
+
+    /// Returns the line of the parsed text which `line` of `code` comes
+    /// from, both starting from 0. It's `None` for separator lines and
+    /// lines of blocks created after parsing.
+    pub fn source_line(&self, line: usize) -> Option<usize> {
+        let (block, offset) = self.origin(line)?;
+        Some(block.source_line? + offset)
+    }
+}
+
+impl Org<'_> {
+    /// Returns usage of each language in source blocks and inline source
+    /// blocks, sorted by language.
+    ///
+    /// ```rust
+    /// use orgize::Org;
+    ///
+    /// let org = Org::parse(
+    ///     "#+BEGIN_SRC rust\nfn main() {\n}\n#+END_SRC\n\
+    ///      Run src_sh{cargo run} or src_rust{main()}.\n",
+    /// );
+    ///
+    /// let usage: Vec<_> = org
+    ///     .languages()
+    ///     .into_iter()
+    ///     .map(|usage| (usage.language, usage.blocks, usage.lines, usage.inline))
+    ///     .collect();
+    /// assert_eq!(
+    ///     usage,
+    ///     [("rust".into(), 1, 2, 1), ("sh".into(), 0, 0, 1)]
+    /// );
+    /// ```
+    pub fn languages(&self) -> Vec<LanguageUsage> {
+        let mut languages = BTreeMap::new();
+        for node in self.root.descendants(&self.arena) {
+            let (language, lines, inline) = match &self[node] {
+                Element::SourceBlock(block) => (&block.language, block.contents.lines().count(), 0),
+                Element::InlineSrc(inline_src) => (&inline_src.lang, 0, 1),
+                _ => continue,
+            };
+            if language.is_empty() {
+                continue;
+            }
+            let usage = languages
+                .entry(language.as_ref())
+                .or_insert_with(|| LanguageUsage {
+                    language: language.to_string(),
+                    ..Default::default()
+                });
+            usage.blocks += 1 - inline;
+            usage.lines += lines;
+            usage.inline += inline;
+        }
+        languages.into_values().collect()
+    }
+
+    /// Joins contents of source blocks of `language`, e.g. for checking
+    /// them with external linters.
+    ///
+    /// Contents are unindented like `SourceBlock::expand_noweb`, and each
+    /// block is preceded by the separator line of `config`, so lines of the
+    /// result can be mapped back to blocks with `Extracted::origin`. Fixed
+    /// contents are written back with `SourceBlock::set_contents`.
+    ///
+    /// ```rust
+    /// use orgize::{ExtractConfig, Org};
+    ///
+    /// let org = Org::parse(
+    ///     "* Code\n#+BEGIN_SRC rust\nlet a = 1;\n#+END_SRC\n\
+    ///      #+BEGIN_SRC rust\nlet b = 2;\n#+END_SRC\n",
+    /// );
+    ///
+    /// let extracted = org.extract_language(
+    ///     "rust",
+    ///     &ExtractConfig {
+    ///         separator: String::from("// %f:%l"),
+    ///         file_name: String::from("notes.org"),
+    ///     },
+    /// );
+    /// assert_eq!(
+    ///     extracted.code,
+    ///     "// notes.org:3\nlet a = 1;\n// notes.org:6\nlet b = 2;\n"
+    /// );
+    /// assert_eq!(extracted.source_line(3), Some(5));
+    /// assert_eq!(extracted.source_line(2), None);
+    /// ```
+    pub fn extract_language(&self, language: &str, config: &ExtractConfig) -> Extracted {
+        let index = LineIndex::new(&self.source);
+        let mut extracted = Extracted::default();
+        let mut line = 0;
+
+        for node in self.root.descendants(&self.arena) {
+            let block = match &self[node] {
+                Element::SourceBlock(block) if block.language == language => block,
+                _ => continue,
+            };
+            // contents start on the line after `#+BEGIN_SRC`
+            let source_line = self
+                .span(node)
+                .and_then(|span| index.line_col(span.start))
+                .map(|(line, _)| line + 1);
+
+            write_separator(&mut extracted.code, config, source_line);
+            let contents = unindented_contents(block);
+            extracted.code.push_str(&contents);
+            if !contents.is_empty() && !contents.ends_with('\n') {
+                extracted.code.push('\n');
+            }
+
+            let lines = contents.lines().count();
+            extracted.blocks.push(ExtractedBlock {
+                node,
+                line: line + 1,
+                lines,
+                source_line,
+            });
+            line += 1 + lines;
+        }
+
+        extracted
+    }
+}
+
+fn write_separator(code: &mut String, config: &ExtractConfig, source_line: Option<usize>) {
+    for part in format_parts(&config.separator) {
+        match part {
+            FormatPart::Text(text) => code.push_str(text),
+            FormatPart::Spec('f') => code.push_str(&config.file_name),
+            FormatPart::Spec('l') => {
+                if let Some(line) = source_line {
+                    code.push_str(&(line + 1).to_string());
+                }
+            }
+            FormatPart::Spec(spec) => {
+                code.push('%');
+                code.push(spec);
+            }
+        }
+    }
+    code.push('\n');
+}
+
+impl SourceBlock<'_> {
+    /// Replaces contents of this block with `contents`, e.g. code fixed by
+    /// a formatter, see `Org::extract_language`.
+    ///
+    /// Lines are indented like the current contents, or like the block if
+    /// it's empty, unless the block has a `-i` switch, so unindented code can
+    /// be written back. A newline is added to non-empty contents without one.
+    ///
+    /// ```rust
+    /// use orgize::{Element, Org};
+    ///
+    /// let mut org = Org::parse("- item\n  #+BEGIN_SRC rust\n  let a=1;\n  #+END_SRC\n");
+    /// let node = org
+    ///     .events()
+    ///     .find(|event| matches!(event.event.element(), Element::SourceBlock(_)))
+    ///     .unwrap()
+    ///     .node;
+    /// if let Element::SourceBlock(block) = &mut org[node] {
+    ///     block.set_contents("let a = 1;\n\nlet b = 2;");
+    /// }
+    ///
+    /// let mut writer = Vec::new();
+    /// org.write_org(&mut writer).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(writer).unwrap(),
+    ///     "- item\n  #+BEGIN_SRC rust\n  let a = 1;\n\n  let b = 2;\n  #+END_SRC\n"
+    /// );
+    /// ```
+    pub fn set_contents(&mut self, contents: &str) {
+        let indent = if self.parse_switches().preserve_indent() {
+            0
+        } else {
+            self.contents
+                .lines()
+                .filter(|line| !line.trim().is_empty())
+                .map(|line| line.len() - line.trim_start().len())
+                .min()
+                .unwrap_or(self.indent)
+        };
+
+        let mut new = String::with_capacity(contents.len());
+        for line in contents.lines() {
+            if !line.trim().is_empty() {
+                new.extend(std::iter::repeat(' ').take(indent));
+            }
+            new.push_str(line);
+            new.push('\n');
+        }
+        self.contents = new.into();
+    }
+}
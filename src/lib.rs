@@ -23,11 +23,11 @@
 //!
 //! Org::parse_custom(
 //!     "* TASK Title 1",
-//!     &ParseConfig {
+//!     &ParseConfig::builder()
 //!         // custom todo keywords
-//!         todo_keywords: (vec!["TASK".to_string()], vec![]),
-//!         ..Default::default()
-//!     },
+//!         .todo_keywords(["TASK"], Vec::<String>::new())
+//!         .build()
+//!         .unwrap(),
 //! );
 //! ```
 //!
@@ -90,8 +90,8 @@
 //! use std::io::{Error as IOError, Write};
 //! use std::string::FromUtf8Error;
 //!
-//! use orgize::export::{DefaultHtmlHandler, HtmlHandler};
-//! use orgize::{Element, Org};
+//! use orgize::export::{DefaultHtmlHandler, ExportError, HtmlHandler};
+//! use orgize::{indextree::NodeId, Element, Org};
 //! use slugify::slugify;
 //!
 //! #[derive(Debug)]
@@ -114,10 +114,23 @@
 //!     }
 //! }
 //!
+//! // errors returned by exporters are wrapped in ExportError, which also
+//! // tells the element being written
+//! impl From<ExportError<MyError>> for MyError {
+//!     fn from(err: ExportError<MyError>) -> Self {
+//!         err.into_inner()
+//!     }
+//! }
+//!
 //! #[derive(Default)]
 //! struct MyHtmlHandler(DefaultHtmlHandler);
 //!
 //! impl HtmlHandler<MyError> for MyHtmlHandler {
+//!     // lets the default handler resolve links
+//!     fn prepare(&mut self, org: &Org) {
+//!         self.0.prepare(org);
+//!     }
+//!
 //!     fn start<W: Write>(&mut self, mut w: W, element: &Element) -> Result<(), MyError> {
 //!         if let Element::Title(title) = element {
 //!             if title.level > 6 {
@@ -145,6 +158,36 @@
 //!         }
 //!         Ok(())
 //!     }
+//!
+//!     // lets the default handler look up state of nodes, e.g. footnote sections
+//!     fn start_node<W: Write>(
+//!         &mut self,
+//!         w: W,
+//!         node: NodeId,
+//!         element: &Element,
+//!     ) -> Result<(), MyError> {
+//!         match element {
+//!             Element::Title(_) => self.start(w, element),
+//!             _ => Ok(self.0.start_node(w, node, element)?),
+//!         }
+//!     }
+//!
+//!     fn end_node<W: Write>(
+//!         &mut self,
+//!         w: W,
+//!         node: NodeId,
+//!         element: &Element,
+//!     ) -> Result<(), MyError> {
+//!         match element {
+//!             Element::Title(_) => self.end(w, element),
+//!             _ => Ok(self.0.end_node(w, node, element)?),
+//!         }
+//!     }
+//!
+//!     // lets the default handler write footnote definitions
+//!     fn trailing_nodes(&mut self, org: &Org, root: NodeId) -> Vec<NodeId> {
+//!         self.0.trailing_nodes(org, root)
+//!     }
 //! }
 //!
 //! fn main() -> Result<(), MyError> {
@@ -182,22 +225,22 @@
 //! println!("{}", to_string(&org).unwrap());
 //!
 //! // {
-//! //     "type": "document",
+//! //     "type": "org-data",
 //! //     "children": [{
 //! //         "type": "section",
 //! //         "children": [{
 //! //             "type": "paragraph",
 //! //             "children":[{
-//! //                 "type": "text",
+//! //                 "type": "plain-text",
 //! //                 "value":"I 'm "
 //! //             }, {
 //! //                 "type": "bold",
 //! //                 "children":[{
-//! //                     "type": "text",
+//! //                     "type": "plain-text",
 //! //                     "value": "bold"
 //! //                 }]
 //! //             }, {
-//! //                 "type":"text",
+//! //                 "type":"plain-text",
 //! //                 "value":"."
 //! //             }]
 //! //         }]
@@ -207,38 +250,95 @@
 //!
 //! # Features
 //!
-//! By now, orgize provides three features:
+//! By now, orgize provides six features:
 //!
 //! + `ser`: adds the ability to serialize `Org` and other elements using `serde`, enabled by default.
 //!
-//! + `chrono`: adds the ability to convert `Datetime` into `chrono` structs, disabled by default.
+//! + `chrono`: adds the ability to convert `Datetime` and `Timestamp` from and into `chrono` structs, disabled by default.
 //!
 //! + `syntect`: provides [`SyntectHtmlHandler`] for highlighting code block, disabled by default.
 //!
+//! + `regex`: provides `Org::autolink` for turning text matching a regular expression into links, disabled by default.
+//!
+//! + `fs`: provides `ImageConfig::with_files` for embedding images read from the file system in html, disabled by default.
+//!
+//! + `tokio`: provides `Org::write_html_async` for streaming html to a `tokio::io::AsyncWrite`, disabled by default.
+//!
 //! [`SyntectHtmlHandler`]: export/struct.SyntectHtmlHandler.html
 //!
 //! # License
 //!
 //! MIT
 
+mod clocktable;
+mod columns;
 mod config;
+mod convert;
+mod diagnostic;
+mod diff;
+pub mod duration;
 pub mod elements;
 pub mod export;
+mod formula;
 mod headline;
+mod history;
+mod languages;
+mod links;
+mod meta;
+mod noweb;
+mod order;
 mod org;
-mod parse;
+pub mod parse;
 mod parsers;
+mod preview;
+mod progress;
+mod query;
+mod replace;
+mod results;
+mod span;
+mod stats;
+mod tags;
+mod timestamps;
+mod toc;
 mod validate;
 
 // Re-export of the indextree crate.
 pub use indextree;
+#[cfg(feature = "regex")]
+pub use regex;
 #[cfg(feature = "syntect")]
 pub use syntect;
+#[cfg(feature = "tokio")]
+pub use tokio;
 
-pub use config::ParseConfig;
+pub use clocktable::{ClocktableBlock, ClocktableError, ClocktableParams, ClocktableScope};
+pub use columns::{
+    ColumnRow, ColumnSpec, ColumnSummary, ColumnviewError, ColumnviewParams, ColumnviewScope,
+};
+pub use config::{
+    ConfigError, CustomParser, DrawerPolicy, ParseConfig, ParseConfigBuilder, Startup, Strictness,
+    TodoKeyword, TodoLog,
+};
+pub use convert::ListConversion;
+pub use diagnostic::ParseError;
+pub use diff::{diff, HeadlineChange, TreeEdit};
 pub use elements::Element;
-pub use headline::{Document, Headline};
-pub use org::{Event, Org};
+pub use formula::{FormulaError, FormulaErrorKind};
+pub use headline::{Document, Headline, ZerothSection};
+pub use history::StateChange;
+pub use languages::{ExtractConfig, Extracted, ExtractedBlock, LanguageUsage};
+pub use links::{LinkIssue, LinkIssueKind};
+pub use meta::{DocumentDate, DocumentMeta, ExportTags};
+pub use noweb::NowebError;
+pub use org::{Event, NodeEvent, Org};
+pub use progress::{Progress, ProgressConfig};
+pub use query::{MatchError, MatchErrorKind, Matcher};
+pub use results::ResultsValue;
+pub use span::LineIndex;
+pub use stats::DocumentStats;
+pub use tags::{TagConflict, TagDefinition, TagDefinitions, TagGroup};
+pub use timestamps::{PlanningKind, TimestampContext, TimestampEntry};
+pub use toc::TocEntry;
 pub use validate::ValidationError;
 
 #[cfg(feature = "wasm")]
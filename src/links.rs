@@ -0,0 +1,312 @@
+use indextree::NodeId;
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::ops::Range;
+
+use crate::{
+    elements::{link::has_scheme, Element, SearchOption},
+    Org,
+};
+
+/// Issue found by `Org::check_links`
+#[derive(Debug, Clone, PartialEq)]
+pub struct LinkIssue {
+    /// The offending node
+    pub node: NodeId,
+    /// Span of the offending node, see `Org::span`
+    pub span: Option<Range<usize>>,
+    /// Issue kind
+    pub kind: LinkIssueKind,
+    /// Human-readable message
+    pub message: String,
+}
+
+/// Kind of `LinkIssue`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LinkIssueKind {
+    /// Fuzzy link like `[[target]]` which matches no target, element name
+    /// or headline title
+    UnresolvedFuzzy(String),
+    /// Headline link like `[[*Title]]` which matches no headline
+    UnresolvedHeadline(String),
+    /// `[[#custom-id]]` link without matching `CUSTOM_ID` property
+    UnresolvedCustomId(String),
+    /// `[[id:...]]` link without matching `ID` property
+    UnresolvedId(String),
+    /// `file:` link without path
+    InvalidFileLink(String),
+    /// `<<target>>` defined more than once
+    DuplicateTarget(String),
+    /// `CUSTOM_ID` property defined more than once
+    DuplicateCustomId(String),
+    /// Footnote reference without definition
+    MissingFootnoteDefinition(String),
+    /// Footnote definition which is never referenced
+    UnusedFootnoteDefinition(String),
+}
+
+impl fmt::Display for LinkIssueKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            LinkIssueKind::UnresolvedFuzzy(path) => {
+                write!(f, "link `{}` matches no target, name or headline", path)
+            }
+            LinkIssueKind::UnresolvedHeadline(title) => {
+                write!(f, "link to headline `{}` matches no headline", title)
+            }
+            LinkIssueKind::UnresolvedCustomId(id) => {
+                write!(f, "no headline has CUSTOM_ID `{}`", id)
+            }
+            LinkIssueKind::UnresolvedId(id) => write!(f, "no headline has ID `{}`", id),
+            LinkIssueKind::InvalidFileLink(path) => {
+                write!(f, "file link `{}` has no path", path)
+            }
+            LinkIssueKind::DuplicateTarget(target) => {
+                write!(f, "target `{}` is defined more than once", target)
+            }
+            LinkIssueKind::DuplicateCustomId(id) => {
+                write!(f, "CUSTOM_ID `{}` is defined more than once", id)
+            }
+            LinkIssueKind::MissingFootnoteDefinition(label) => {
+                write!(f, "footnote `{}` has no definition", label)
+            }
+            LinkIssueKind::UnusedFootnoteDefinition(label) => {
+                write!(f, "footnote `{}` is never referenced", label)
+            }
+        }
+    }
+}
+
+impl Org<'_> {
+    /// Checks links, targets and footnotes of this document.
+    ///
+    /// Reports links that resolve to nothing, duplicate `<<targets>>` and
+    /// `CUSTOM_ID`s, and footnote references and definitions without their
+    /// counterparts, in document order. Links to other files are checked
+    /// for syntax only.
+    ///
+    /// ```rust
+    /// use orgize::{LinkIssueKind, Org};
+    ///
+    /// let org = Org::parse("* Title\n[[Title]] [[#intro]] [fn:1]\n");
+    ///
+    /// let issues: Vec<_> = org.check_links().into_iter().map(|i| i.kind).collect();
+    /// assert_eq!(
+    ///     issues,
+    ///     vec![
+    ///         LinkIssueKind::UnresolvedCustomId("intro".into()),
+    ///         LinkIssueKind::MissingFootnoteDefinition("1".into()),
+    ///     ]
+    /// );
+    /// ```
+    pub fn check_links(&self) -> Vec<LinkIssue> {
+        let mut targets = HashSet::new();
+        let mut titles = HashSet::new();
+        let mut custom_ids = HashSet::new();
+        let mut ids = HashSet::new();
+        let mut fn_refs = HashSet::new();
+        let mut fn_defs = HashSet::new();
+
+        for node in self.root.descendants(&self.arena) {
+            match &self[node] {
+                Element::Target(target) => {
+                    targets.insert(normalize(&target.target));
+                }
+                Element::Keyword(keyword) if keyword.key.eq_ignore_ascii_case("NAME") => {
+                    targets.insert(normalize(&keyword.value));
+                }
+                Element::Title(title) => {
+                    titles.insert(normalize(&title.raw));
+                    for (key, value) in title.properties.iter() {
+                        if key.eq_ignore_ascii_case("CUSTOM_ID") {
+                            custom_ids.insert(&**value);
+                        } else if key.eq_ignore_ascii_case("ID") {
+                            ids.insert(&**value);
+                        }
+                    }
+                }
+                Element::FnRef(fn_ref) if !fn_ref.normalized_label().is_empty() => {
+                    fn_refs.insert(fn_ref.normalized_label());
+                    if fn_ref.definition.is_some() {
+                        fn_defs.insert(fn_ref.normalized_label());
+                    }
+                }
+                Element::FnDef(fn_def) => {
+                    fn_defs.insert(fn_def.normalized_label());
+                }
+                _ => (),
+            }
+        }
+
+        let mut issues = Vec::new();
+        let mut seen_targets = HashMap::new();
+        let mut seen_custom_ids = HashSet::new();
+
+        for node in self.root.descendants(&self.arena) {
+            let kind = match &self[node] {
+                Element::Link(link) => check_link(&link.path, &targets, &titles, &custom_ids, &ids),
+                Element::Target(target) => {
+                    let count = seen_targets.entry(normalize(&target.target)).or_insert(0);
+                    *count += 1;
+                    Some(LinkIssueKind::DuplicateTarget(target.target.to_string()))
+                        .filter(|_| *count > 1)
+                }
+                Element::Title(title) => title
+                    .properties
+                    .iter()
+                    .find(|(key, value)| {
+                        key.eq_ignore_ascii_case("CUSTOM_ID") && !seen_custom_ids.insert(&**value)
+                    })
+                    .map(|(_, value)| LinkIssueKind::DuplicateCustomId(value.to_string())),
+                Element::FnRef(fn_ref)
+                    if !fn_ref.normalized_label().is_empty()
+                        && !fn_defs.contains(fn_ref.normalized_label()) =>
+                {
+                    Some(LinkIssueKind::MissingFootnoteDefinition(
+                        fn_ref.normalized_label().to_string(),
+                    ))
+                }
+                Element::FnDef(fn_def) if !fn_refs.contains(fn_def.normalized_label()) => Some(
+                    LinkIssueKind::UnusedFootnoteDefinition(fn_def.normalized_label().to_string()),
+                ),
+                _ => None,
+            };
+
+            if let Some(kind) = kind {
+                issues.push(LinkIssue {
+                    node,
+                    span: self.span(node),
+                    message: kind.to_string(),
+                    kind,
+                });
+            }
+        }
+
+        issues
+    }
+}
+
+impl Org<'_> {
+    /// Resolves a search option in this document, returns the matched
+    /// node.
+    ///
+    /// Headline titles and custom ids resolve to headline nodes, targets and
+    /// radio targets to their nodes and names to the named elements. Regular expressions
+    /// and line numbers need the source text, so they're never resolved.
+    ///
+    /// ```rust
+    /// use orgize::{elements::SearchOption, Element, Org};
+    ///
+    /// let org = Org::parse("* Install\n<<setup>> Run it.\n");
+    ///
+    /// let node = org.resolve_search(&SearchOption::Heading("Install")).unwrap();
+    /// assert!(matches!(org[node], Element::Headline { .. }));
+    ///
+    /// let node = org.resolve_search(&SearchOption::Fuzzy("setup")).unwrap();
+    /// assert!(matches!(org[node], Element::Target(_)));
+    ///
+    /// assert!(org.resolve_search(&SearchOption::Fuzzy("missing")).is_none());
+    /// ```
+    pub fn resolve_search(&self, option: &SearchOption) -> Option<NodeId> {
+        match *option {
+            SearchOption::Heading(title) => self.find_headline(title),
+            SearchOption::CustomId(id) => self.headlines().find_map(|hdl| {
+                hdl.title(self)
+                    .properties
+                    .get("CUSTOM_ID")
+                    .filter(|value| *value == id)
+                    .map(|_| hdl.headline_node())
+            }),
+            SearchOption::Fuzzy(text) => {
+                let text = normalize(text);
+                self.root
+                    .descendants(&self.arena)
+                    .find_map(|node| match &self[node] {
+                        Element::Target(target) if normalize(&target.target) == text => Some(node),
+                        Element::RadioTarget if normalize(&self.text_content(node)) == text => {
+                            Some(node)
+                        }
+                        Element::Keyword(keyword)
+                            if keyword.key.eq_ignore_ascii_case("NAME")
+                                && normalize(&keyword.value) == text =>
+                        {
+                            // the element following the affiliated keywords
+                            Some(
+                                node.following_siblings(&self.arena)
+                                    .find(|&n| !matches!(self[n], Element::Keyword(_)))
+                                    .unwrap_or(node),
+                            )
+                        }
+                        _ => None,
+                    })
+                    .or_else(|| self.find_headline(&text))
+            }
+            SearchOption::Regex(_) | SearchOption::LineNumber(_) => None,
+        }
+    }
+
+    // text of objects inside `node`, e.g. contents of a radio target
+    pub(crate) fn text_content(&self, node: NodeId) -> String {
+        node.descendants(&self.arena)
+            .filter_map(|node| match &self[node] {
+                Element::Text { value } | Element::Code { value } | Element::Verbatim { value } => {
+                    Some(&**value)
+                }
+                _ => None,
+            })
+            .collect()
+    }
+
+    fn find_headline(&self, title: &str) -> Option<NodeId> {
+        let title = normalize(title);
+        self.headlines()
+            .find(|hdl| normalize(&hdl.title(self).raw) == title)
+            .map(|hdl| hdl.headline_node())
+    }
+}
+
+fn check_link(
+    path: &str,
+    targets: &HashSet<String>,
+    titles: &HashSet<String>,
+    custom_ids: &HashSet<&str>,
+    ids: &HashSet<&str>,
+) -> Option<LinkIssueKind> {
+    if let Some(id) = path.strip_prefix('#') {
+        Some(LinkIssueKind::UnresolvedCustomId(id.into())).filter(|_| !custom_ids.contains(id))
+    } else if let Some(title) = path.strip_prefix('*') {
+        Some(LinkIssueKind::UnresolvedHeadline(title.into()))
+            .filter(|_| !titles.contains(&normalize(title)))
+    } else if let Some(id) = path.strip_prefix("id:") {
+        Some(LinkIssueKind::UnresolvedId(id.into())).filter(|_| !ids.contains(id))
+    } else if let Some(file) = path.strip_prefix("file:") {
+        let file = file.split("::").next().unwrap_or_default();
+        Some(LinkIssueKind::InvalidFileLink(path.into())).filter(|_| file.trim().is_empty())
+    } else if has_scheme(path)
+        || path.starts_with('(')
+        || ["/", "./", "../", "~/"]
+            .iter()
+            .any(|prefix| path.starts_with(prefix))
+    {
+        // external links, code references and file paths
+        None
+    } else {
+        let path = normalize(path);
+        Some(LinkIssueKind::UnresolvedFuzzy(path.clone()))
+            .filter(|_| !targets.contains(&path) && !titles.contains(&path))
+    }
+}
+
+// collapses whitespaces, like org-mode does when matching fuzzy links
+fn normalize(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+#[test]
+fn scheme() {
+    assert!(has_scheme("https://example.com"));
+    assert!(has_scheme("mailto:a@b.c"));
+    assert!(!has_scheme("target"));
+    assert!(!has_scheme("a b:c"));
+    assert!(!has_scheme(":c"));
+}
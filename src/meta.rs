@@ -0,0 +1,270 @@
+use std::borrow::Cow;
+
+use crate::{
+    elements::{timestamp::parse_timestamp, PropertiesMap, Timestamp},
+    Org,
+};
+
+/// Document metadata from export keywords, see `Org::metadata`
+#[derive(Debug, Clone, Default)]
+pub struct DocumentMeta<'b> {
+    /// `#+TITLE:`, multiple occurrences are joined with a space
+    pub title: Option<Cow<'b, str>>,
+    /// `#+SUBTITLE:`, multiple occurrences are joined with a space
+    pub subtitle: Option<Cow<'b, str>>,
+    /// `#+DESCRIPTION:`, multiple occurrences are joined with a space
+    pub description: Option<Cow<'b, str>>,
+    /// `#+AUTHOR:`, the first occurrence wins
+    pub author: Option<Cow<'b, str>>,
+    /// `#+EMAIL:`, the first occurrence wins
+    pub email: Option<Cow<'b, str>>,
+    /// `#+DATE:`, the first occurrence wins
+    pub date: Option<DocumentDate<'b>>,
+    /// `#+LANGUAGE:`, the last occurrence wins
+    pub language: Option<Cow<'b, str>>,
+    /// Tags from all `#+FILETAGS:`, e.g. `:a:b:`
+    pub filetags: Vec<Cow<'b, str>>,
+}
+
+/// Tags selecting and excluding subtrees from export, see
+/// `Org::export_tags`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ExportTags<'b> {
+    /// `#+SELECT_TAGS:`, default is `["export"]`
+    pub select: Vec<&'b str>,
+    /// `#+EXCLUDE_TAGS:`, default is `["noexport"]`
+    pub exclude: Vec<&'b str>,
+}
+
+impl Default for ExportTags<'_> {
+    fn default() -> Self {
+        ExportTags {
+            select: vec!["export"],
+            exclude: vec!["noexport"],
+        }
+    }
+}
+
+/// Value of `#+DATE:` keyword
+#[derive(Debug, Clone)]
+pub enum DocumentDate<'b> {
+    /// The value is a single timestamp
+    Timestamp(Timestamp<'b>),
+    /// Any other value, kept as-is
+    Raw(Cow<'b, str>),
+}
+
+impl DocumentDate<'_> {
+    fn parse(value: &str) -> DocumentDate<'_> {
+        match parse_timestamp(value) {
+            Ok((tail, timestamp)) if tail.trim().is_empty() => DocumentDate::Timestamp(timestamp),
+            _ => DocumentDate::Raw(Cow::Borrowed(value)),
+        }
+    }
+}
+
+impl Org<'_> {
+    /// Collects document metadata from `#+TITLE:`, `#+SUBTITLE:`,
+    /// `#+DESCRIPTION:`, `#+AUTHOR:`, `#+EMAIL:`, `#+DATE:`, `#+LANGUAGE:`
+    /// and `#+FILETAGS:` keywords. Keywords with empty values are ignored.
+    ///
+    /// ```rust
+    /// use orgize::{DocumentDate, Org};
+    ///
+    /// let org = Org::parse(
+    ///     "#+TITLE: A long\n\
+    ///      #+TITLE: title\n\
+    ///      #+AUTHOR: Jane\n\
+    ///      #+DATE: <2024-03-08 Fri>\n\
+    ///      #+FILETAGS: :work:notes:\n",
+    /// );
+    /// let meta = org.metadata();
+    ///
+    /// assert_eq!(meta.title.as_deref(), Some("A long title"));
+    /// assert_eq!(meta.author.as_deref(), Some("Jane"));
+    /// assert!(matches!(meta.date, Some(DocumentDate::Timestamp(_))));
+    /// assert_eq!(meta.filetags, ["work", "notes"]);
+    /// ```
+    pub fn metadata(&self) -> DocumentMeta<'_> {
+        let mut meta = DocumentMeta::default();
+
+        for keyword in self.keywords() {
+            let value = keyword.value.trim();
+            if value.is_empty() {
+                continue;
+            }
+
+            let key = &*keyword.key;
+            if key.eq_ignore_ascii_case("TITLE") {
+                append(&mut meta.title, value);
+            } else if key.eq_ignore_ascii_case("SUBTITLE") {
+                append(&mut meta.subtitle, value);
+            } else if key.eq_ignore_ascii_case("DESCRIPTION") {
+                append(&mut meta.description, value);
+            } else if key.eq_ignore_ascii_case("AUTHOR") {
+                meta.author.get_or_insert(Cow::Borrowed(value));
+            } else if key.eq_ignore_ascii_case("EMAIL") {
+                meta.email.get_or_insert(Cow::Borrowed(value));
+            } else if key.eq_ignore_ascii_case("DATE") {
+                meta.date.get_or_insert_with(|| DocumentDate::parse(value));
+            } else if key.eq_ignore_ascii_case("LANGUAGE") {
+                meta.language = Some(Cow::Borrowed(value));
+            } else if key.eq_ignore_ascii_case("FILETAGS") {
+                meta.filetags.extend(
+                    value
+                        .split(|c: char| c == ':' || c.is_whitespace())
+                        .filter(|tag| !tag.is_empty())
+                        .map(Cow::Borrowed),
+                );
+            }
+        }
+
+        meta
+    }
+
+    /// Returns export options of all `#+OPTIONS:` keywords, e.g. `("toc",
+    /// "nil")` for `toc:nil`, in order of their first appearance.
+    ///
+    /// Like Emacs, options of every keyword apply, and an option given more
+    /// than once takes its last value. Items which aren't `key:value`, such
+    /// as a trailing `\` continuing the options on the next line, are
+    /// ignored. Keywords are left as-is in the tree.
+    ///
+    /// ```rust
+    /// use orgize::Org;
+    ///
+    /// let org = Org::parse("#+OPTIONS: toc:nil num:t \\\n#+OPTIONS: num:nil\n");
+    ///
+    /// assert_eq!(org.export_options(), [("toc", "nil"), ("num", "nil")]);
+    /// ```
+    pub fn export_options(&self) -> Vec<(&str, &str)> {
+        let mut options: Vec<(&str, &str)> = Vec::new();
+
+        for keyword in self.keywords() {
+            if !keyword.key.eq_ignore_ascii_case("OPTIONS") {
+                continue;
+            }
+            for (key, value) in keyword
+                .value
+                .split_whitespace()
+                .filter_map(|option| option.split_once(':'))
+            {
+                match options.iter_mut().find(|(k, _)| *k == key) {
+                    Some((_, old)) => *old = value,
+                    None => options.push((key, value)),
+                }
+            }
+        }
+
+        options
+    }
+
+    /// Returns tags of `#+SELECT_TAGS:` and `#+EXCLUDE_TAGS:` keywords,
+    /// which html export and `DefaultOrgHandler::clean` use to leave out
+    /// subtrees.
+    ///
+    /// Values are whitespace-separated, and tags of every keyword apply.
+    /// Keywords replace the default tags, so an empty `#+EXCLUDE_TAGS:`
+    /// excludes nothing. Keywords are left as-is in the tree.
+    ///
+    /// ```rust
+    /// use orgize::Org;
+    ///
+    /// let org = Org::parse("#+EXCLUDE_TAGS: noexport draft\n#+EXCLUDE_TAGS: old\n");
+    /// let tags = org.export_tags();
+    ///
+    /// assert_eq!(tags.select, ["export"]);
+    /// assert_eq!(tags.exclude, ["noexport", "draft", "old"]);
+    ///
+    /// let org = Org::parse("#+EXCLUDE_TAGS:\n");
+    /// assert!(org.export_tags().exclude.is_empty());
+    /// ```
+    pub fn export_tags(&self) -> ExportTags<'_> {
+        let mut tags = ExportTags::default();
+        let (mut select, mut exclude) = (None, None);
+
+        for keyword in self.keywords() {
+            let list = if keyword.key.eq_ignore_ascii_case("SELECT_TAGS") {
+                &mut select
+            } else if keyword.key.eq_ignore_ascii_case("EXCLUDE_TAGS") {
+                &mut exclude
+            } else {
+                continue;
+            };
+            list.get_or_insert_with(Vec::new)
+                .extend(keyword.value.split_whitespace());
+        }
+
+        if let Some(select) = select {
+            tags.select = select;
+        }
+        if let Some(exclude) = exclude {
+            tags.exclude = exclude;
+        }
+        tags
+    }
+
+    /// Returns properties set by `#+PROPERTY:` keywords, which apply to the
+    /// whole document.
+    ///
+    /// Like in property drawers, `NAME+` keywords add to the value of
+    /// `NAME`, see `PropertiesMap::get_accumulated`, while a later `NAME`
+    /// keyword replaces it. A trailing `\`, which
+    /// some writers use to continue the value on the next keyword, is
+    /// dropped. Keywords are left as-is in the tree.
+    ///
+    /// ```rust
+    /// use orgize::Org;
+    ///
+    /// let org = Org::parse(
+    ///     "#+PROPERTY: header-args :results silent \\\n\
+    ///      #+PROPERTY: header-args+ :exports both\n\
+    ///      #+PROPERTY: Effort_ALL 0 0:10 0:30\n",
+    /// );
+    /// let properties = org.document_properties();
+    ///
+    /// assert_eq!(
+    ///     properties.get_accumulated("header-args").as_deref(),
+    ///     Some(":results silent :exports both")
+    /// );
+    /// assert_eq!(properties.get("EFFORT_ALL"), Some("0 0:10 0:30"));
+    /// ```
+    pub fn document_properties(&self) -> PropertiesMap<'_> {
+        let mut properties = PropertiesMap::new();
+
+        for keyword in self.keywords() {
+            if !keyword.key.eq_ignore_ascii_case("PROPERTY") {
+                continue;
+            }
+            let value = keyword.value.trim();
+            let (name, value) = value
+                .split_once(|c: char| c.is_ascii_whitespace())
+                .unwrap_or((value, ""));
+            if name.is_empty() {
+                continue;
+            }
+            let value = value.trim();
+            let value = value.strip_suffix('\\').unwrap_or(value).trim_end();
+            // a later keyword replaces the property, like Emacs
+            if !name.ends_with('+') {
+                properties.remove(name);
+            }
+            properties
+                .pairs
+                .push((Cow::Borrowed(name), Cow::Borrowed(value)));
+        }
+
+        properties
+    }
+}
+
+fn append<'b>(field: &mut Option<Cow<'b, str>>, value: &'b str) {
+    match field {
+        Some(existing) => {
+            let existing = existing.to_mut();
+            existing.push(' ');
+            existing.push_str(value);
+        }
+        None => *field = Some(Cow::Borrowed(value)),
+    }
+}
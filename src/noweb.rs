@@ -0,0 +1,261 @@
+use std::borrow::Cow;
+use std::error::Error;
+use std::fmt;
+
+use crate::{
+    elements::{Element, SourceBlock},
+    Org,
+};
+
+/// Error returned by `SourceBlock::expand_noweb`
+#[derive(Debug, Clone, PartialEq)]
+pub struct NowebError {
+    /// References forming the cycle, e.g. `["a", "b", "a"]`
+    pub cycle: Vec<String>,
+}
+
+impl fmt::Display for NowebError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "noweb reference cycle: {}", self.cycle.join(" -> "))
+    }
+}
+
+impl Error for NowebError {}
+
+impl SourceBlock<'_> {
+    /// Returns contents of this block with noweb references expanded.
+    ///
+    /// `<<name>>` and `<<name(args)>>` are replaced with the contents of
+    /// source blocks named `name` by `#+NAME:` keyword or `:noweb-ref` header
+    /// argument, arguments are ignored. Contents of multiple blocks are
+    /// joined by their `:noweb-sep` header argument, default is a newline.
+    /// Each expanded line is prefixed with the text before the reference.
+    /// References are expanded recursively, unresolved ones are left
+    /// untouched.
+    ///
+    /// Like contents of the block, contents of referenced blocks are
+    /// unindented unless they have a `-i` switch.
+    ///
+    /// Returns an error if references form a cycle.
+    ///
+    /// ```rust
+    /// use orgize::{elements::SourceBlock, Element, Org};
+    ///
+    /// let org = Org::parse(
+    ///     "#+NAME: body\n\
+    ///      #+BEGIN_SRC rust\n\
+    ///      println!(\"Hello\");\n\
+    ///      #+END_SRC\n\
+    ///      #+BEGIN_SRC rust\n\
+    ///      fn main() {\n    <<body>>\n}\n\
+    ///      #+END_SRC\n",
+    /// );
+    ///
+    /// let block = org
+    ///     .arena()
+    ///     .iter()
+    ///     .filter_map(|node| match node.get() {
+    ///         Element::SourceBlock(block) => Some(block),
+    ///         _ => None,
+    ///     })
+    ///     .last()
+    ///     .unwrap();
+    ///
+    /// assert_eq!(
+    ///     block.expand_noweb(&org).unwrap(),
+    ///     "fn main() {\n    println!(\"Hello\");\n}\n"
+    /// );
+    /// ```
+    pub fn expand_noweb(&self, org: &Org) -> Result<Cow<'_, str>, NowebError> {
+        let contents = unindented_contents(self);
+        match expand(&contents, org, &mut Vec::new())? {
+            Cow::Borrowed(_) => Ok(contents),
+            Cow::Owned(expanded) => Ok(expanded.into()),
+        }
+    }
+}
+
+pub(crate) fn unindented_contents<'b>(block: &'b SourceBlock) -> Cow<'b, str> {
+    if block.parse_switches().preserve_indent() {
+        return Cow::Borrowed(&block.contents);
+    }
+
+    let indent = block
+        .contents
+        .lines()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+
+    if indent == 0 {
+        return Cow::Borrowed(&block.contents);
+    }
+
+    block
+        .contents
+        .split_inclusive('\n')
+        .map(|line| {
+            line.get(indent..)
+                .unwrap_or_else(|| line.trim_start_matches(' '))
+        })
+        .collect::<String>()
+        .into()
+}
+
+// expands references in `text`, `stack` contains references being expanded
+fn expand<'b>(
+    text: &'b str,
+    org: &Org,
+    stack: &mut Vec<String>,
+) -> Result<Cow<'b, str>, NowebError> {
+    if !text.contains("<<") {
+        return Ok(Cow::Borrowed(text));
+    }
+
+    let mut output = String::with_capacity(text.len());
+    let mut expanded = false;
+
+    for line in text.split_inclusive('\n') {
+        let mut rest = line;
+        while let Some((start, end, name)) = find_reference(rest) {
+            let prefix = &line[..line.len() - rest.len() + start];
+            output.push_str(&rest[..start]);
+            match resolve(name, org, stack)? {
+                Some(contents) => {
+                    expanded = true;
+                    let mut lines = contents.split('\n');
+                    if let Some(first) = lines.next() {
+                        output.push_str(first);
+                    }
+                    for line in lines {
+                        output.push('\n');
+                        output.push_str(prefix);
+                        output.push_str(line);
+                    }
+                }
+                None => output.push_str(&rest[start..end]),
+            }
+            rest = &rest[end..];
+        }
+        output.push_str(rest);
+    }
+
+    if expanded {
+        Ok(Cow::Owned(output))
+    } else {
+        Ok(Cow::Borrowed(text))
+    }
+}
+
+/// Finds the first noweb reference in `line`, returns its start, end and
+/// block name.
+fn find_reference(line: &str) -> Option<(usize, usize, &str)> {
+    let mut offset = 0;
+    while let Some(start) = line[offset..].find("<<").map(|i| offset + i) {
+        let inner = &line[start + 2..];
+        if let Some(len) = inner.find(">>") {
+            let reference = &inner[..len];
+            if !reference.is_empty()
+                && !reference.starts_with(char::is_whitespace)
+                && !reference.ends_with(char::is_whitespace)
+                && !reference.contains('\n')
+            {
+                let name = match reference.find('(') {
+                    Some(i) if reference.ends_with(')') => &reference[..i],
+                    _ => reference,
+                };
+                return Some((start, start + len + 4, name));
+            }
+        }
+        offset = start + 2;
+    }
+    None
+}
+
+/// Returns expanded contents of blocks referenced by `name`, `None` if no
+/// blocks are found.
+fn resolve(name: &str, org: &Org, stack: &mut Vec<String>) -> Result<Option<String>, NowebError> {
+    if stack.iter().any(|n| n == name) {
+        let mut cycle = stack.clone();
+        cycle.push(name.into());
+        return Err(NowebError { cycle });
+    }
+
+    let blocks = org
+        .root
+        .descendants(&org.arena)
+        .filter_map(|node| match &org[node] {
+            Element::SourceBlock(block)
+                if org.name(node) == Some(name)
+                    || block.header_args().get("noweb-ref") == Some(name) =>
+            {
+                Some(block)
+            }
+            _ => None,
+        });
+
+    stack.push(name.into());
+
+    let mut output: Option<String> = None;
+    let mut separator = String::new();
+    for block in blocks {
+        let contents = unindented_contents(block);
+        let contents = expand(&contents, org, stack)?;
+        let contents = contents.strip_suffix('\n').unwrap_or(&contents);
+
+        let output = output.get_or_insert_with(String::new);
+        output.push_str(&separator);
+        output.push_str(contents);
+
+        separator = block
+            .header_args()
+            .get("noweb-sep")
+            .map(unquote)
+            .unwrap_or_else(|| "\n".into());
+    }
+
+    stack.pop();
+
+    Ok(output)
+}
+
+// unquotes a lisp string, e.g. `"\n\n"`
+fn unquote(value: &str) -> String {
+    let value = match value.strip_prefix('"').and_then(|v| v.strip_suffix('"')) {
+        Some(value) => value,
+        None => return value.into(),
+    };
+
+    let mut output = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            output.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n') => output.push('\n'),
+            Some('t') => output.push('\t'),
+            Some(c) => output.push(c),
+            None => output.push('\\'),
+        }
+    }
+    output
+}
+
+#[test]
+fn find() {
+    assert_eq!(find_reference("  <<a>> x"), Some((2, 7, "a")));
+    assert_eq!(find_reference("<<a(x=1)>>"), Some((0, 10, "a")));
+    assert_eq!(find_reference("x << 1 >> 2"), None);
+    assert_eq!(find_reference("<< <<b>>"), Some((3, 8, "b")));
+    assert_eq!(find_reference("<<>>"), None);
+}
+
+#[test]
+fn unquote_() {
+    assert_eq!(unquote("\"\\n\\n\""), "\n\n");
+    assert_eq!(unquote("\" \\\"x\\\" \""), " \"x\" ");
+    assert_eq!(unquote(","), ",");
+}
@@ -0,0 +1,104 @@
+use indextree::NodeId;
+use std::cmp::Ordering;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+use crate::Org;
+
+/// Nodes of the document in pre-order, built on demand
+pub(crate) struct DocumentOrder {
+    nodes: Vec<NodeId>,
+    positions: HashMap<NodeId, usize>,
+}
+
+impl DocumentOrder {
+    fn new(org: &Org) -> DocumentOrder {
+        let nodes: Vec<_> = org.root.descendants(&org.arena).collect();
+        let positions = nodes.iter().enumerate().map(|(i, &n)| (n, i)).collect();
+        DocumentOrder { nodes, positions }
+    }
+}
+
+impl Org<'_> {
+    fn document_order(&self) -> &DocumentOrder {
+        self.document_order.get_or_init(|| DocumentOrder::new(self))
+    }
+
+    // position of `node` in the document, panics if it's detached
+    fn position(&self, node: NodeId) -> usize {
+        match self.document_order().positions.get(&node) {
+            Some(&i) => i,
+            None => panic!("{:?} isn't a node of the document", node),
+        }
+    }
+
+    /// Compares positions of nodes `a` and `b` in the document, i.e. a
+    /// node is before its descendants, which are before its next sibling.
+    ///
+    /// Positions are computed once, and again after the tree is changed,
+    /// so comparing many nodes is cheap.
+    ///
+    /// Panics if either node isn't in the document, e.g. it's detached.
+    ///
+    /// ```rust
+    /// use std::cmp::Ordering;
+    /// use orgize::Org;
+    ///
+    /// let org = Org::parse("* a\ntext\n* b\n");
+    /// let a = org.headlines().next().unwrap();
+    /// let b = org.headlines().nth(1).unwrap();
+    ///
+    /// assert_eq!(
+    ///     org.cmp_document_order(b.headline_node(), a.section_node().unwrap()),
+    ///     Ordering::Greater
+    /// );
+    /// ```
+    pub fn cmp_document_order(&self, a: NodeId, b: NodeId) -> Ordering {
+        self.position(a).cmp(&self.position(b))
+    }
+
+    /// Returns an iterator of nodes from `a` to `b` in document order, both
+    /// included, see `Org::cmp_document_order`. It's empty if `b` is
+    /// before `a`.
+    ///
+    /// Descendants of `b` aren't included, since they come after it.
+    ///
+    /// Panics if either node isn't in the document, e.g. it's detached.
+    ///
+    /// ```rust
+    /// use orgize::{Element, Org};
+    ///
+    /// let org = Org::parse("* a\n* b\ntext\n* c\n");
+    /// let a = org.headlines().next().unwrap();
+    /// let c = org.headlines().nth(2).unwrap();
+    ///
+    /// let texts: Vec<_> = org
+    ///     .range_iter(a.headline_node(), c.headline_node())
+    ///     .filter_map(|node| match &org[node] {
+    ///         Element::Text { value } => Some(value.as_ref()),
+    ///         _ => None,
+    ///     })
+    ///     .collect();
+    /// assert_eq!(texts, ["a", "b", "text"]);
+    /// ```
+    pub fn range_iter(&self, a: NodeId, b: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        let (start, end) = (self.position(a), self.position(b));
+        self.document_order()
+            .nodes
+            .get(start..=end)
+            .unwrap_or_default()
+            .iter()
+            .copied()
+    }
+
+    /// Drops positions computed by `Org::cmp_document_order` and spans of
+    /// removed nodes, called after changing the structure of the tree, and
+    /// validates it in debug builds.
+    pub(crate) fn tree_changed(&mut self) {
+        self.document_order = OnceLock::new();
+        self.span_table = OnceLock::new();
+        let arena = &self.arena;
+        self.spans.retain(|node, _| !node.is_removed(arena));
+        self.debug_validate();
+    }
+}
@@ -1,17 +1,35 @@
-use indextree::{Arena, NodeEdge, NodeId};
+use indextree::{Arena, NodeEdge, NodeId, Traverse};
+use std::borrow::Cow;
+use std::collections::{HashMap, HashSet};
 use std::io::{Error, Write};
 use std::ops::{Index, IndexMut};
+use std::sync::OnceLock;
 
 use crate::{
-    config::{ParseConfig, DEFAULT_CONFIG},
-    elements::{Element, Keyword},
-    export::{DefaultHtmlHandler, DefaultOrgHandler, HtmlHandler, OrgHandler},
-    parsers::{blank_lines_count, parse_container, Container, OwnedArena},
+    config::{ParseConfig, Startup, Strictness, DEFAULT_CONFIG},
+    diagnostic::{Diagnostics, ParseError},
+    elements::{Element, Keyword, TableRow},
+    export::{
+        DefaultHtmlHandler, DefaultOrgHandler, ExportError, FormatConfig, FormatOrgHandler,
+        HtmlHandler, OrgHandler,
+    },
+    meta::ExportTags,
+    order::DocumentOrder,
+    parsers::{blank_lines_count, parse_container_with_diagnostics, Container, OwnedArena},
+    span::{SpanTable, Spans},
+    Headline,
 };
 
 pub struct Org<'a> {
     pub(crate) arena: Arena<Element<'a>>,
     pub(crate) root: NodeId,
+    pub(crate) diagnostics: Vec<ParseError>,
+    pub(crate) spans: HashMap<NodeId, (usize, usize)>,
+    pub(crate) span_table: OnceLock<SpanTable>,
+    pub(crate) document_order: OnceLock<DocumentOrder>,
+    /// Parsed text, which spans are relative to
+    pub(crate) source: Cow<'a, str>,
+    pub(crate) config: ParseConfig,
 }
 
 #[derive(Debug)]
@@ -20,12 +38,46 @@ pub enum Event<'a, 'b> {
     End(&'b Element<'a>),
 }
 
+impl<'a, 'b> Event<'a, 'b> {
+    /// Returns the element this event starts or ends.
+    pub fn element(&self) -> &'b Element<'a> {
+        match self {
+            Event::Start(element) | Event::End(element) => element,
+        }
+    }
+
+    /// Returns `true` if the element is a container, see
+    /// `Element::is_container`.
+    pub fn is_container(&self) -> bool {
+        self.element().is_container()
+    }
+}
+
+/// `Event` along with its node and depth, see `Org::events`
+#[derive(Debug)]
+pub struct NodeEvent<'a, 'b> {
+    pub event: Event<'a, 'b>,
+    /// Node of the element
+    pub node: NodeId,
+    /// Number of ancestors of the node, `0` for the document
+    pub depth: usize,
+}
+
 impl<'a> Org<'a> {
     /// Creates a new empty `Org` struct.
     pub fn new() -> Org<'static> {
         let mut arena = Arena::new();
         let root = arena.new_node(Element::Document { pre_blank: 0 });
-        Org { arena, root }
+        Org {
+            arena,
+            root,
+            diagnostics: Vec::new(),
+            spans: HashMap::new(),
+            span_table: OnceLock::new(),
+            document_order: OnceLock::new(),
+            source: Cow::Borrowed(""),
+            config: DEFAULT_CONFIG.clone(),
+        }
     }
 
     /// Parses string `text` into `Org` struct.
@@ -40,46 +92,172 @@ impl<'a> Org<'a> {
 
     /// Parses string `text` into `Org` struct with custom `ParseConfig`.
     pub fn parse_custom(text: &'a str, config: &ParseConfig) -> Org<'a> {
+        // a byte order mark is skipped, content stays a slice of `text`
+        let content = text.strip_prefix('\u{feff}').unwrap_or(text);
+        let config = config.with_buffer_settings(content).into_owned();
         let mut arena = Arena::new();
-        let (text, pre_blank) = blank_lines_count(text);
+        let mut diagnostics = Diagnostics::new(text, config.strictness);
+        let mut spans = Spans::new(text);
+        let (content, pre_blank) = blank_lines_count(content);
         let root = arena.new_node(Element::Document { pre_blank });
-        let mut org = Org { arena, root };
+        spans.insert(root, text);
+        let mut org = Org {
+            arena,
+            root,
+            diagnostics: Vec::new(),
+            spans: HashMap::new(),
+            span_table: OnceLock::new(),
+            document_order: OnceLock::new(),
+            source: Cow::Borrowed(text),
+            config,
+        };
 
-        parse_container(
+        parse_container_with_diagnostics(
             &mut org.arena,
             Container::Document {
-                content: text,
+                content,
                 node: org.root,
             },
-            config,
+            &org.config,
+            &mut diagnostics,
+            &mut spans,
         );
 
+        org.diagnostics = diagnostics.into_errors();
+        org.spans = spans.into_spans();
+
         org.debug_validate();
 
         org
     }
 
+    /// Likes `parse_custom`, but returns the first malformed construct as an
+    /// error if `config.strictness` is `Strictness::Strict`.
+    ///
+    /// ```rust
+    /// use orgize::{Org, ParseConfig, ParseError, Strictness};
+    ///
+    /// let config = ParseConfig {
+    ///     strictness: Strictness::Strict,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert!(Org::try_parse_custom("#+BEGIN_SRC\n#+END_SRC", &config).is_ok());
+    /// assert_eq!(
+    ///     Org::try_parse_custom("text\n#+BEGIN_SRC\n", &config).err(),
+    ///     Some(ParseError::UnclosedBlock {
+    ///         name: "SRC".into(),
+    ///         offset: 5
+    ///     })
+    /// );
+    /// ```
+    pub fn try_parse_custom(text: &'a str, config: &ParseConfig) -> Result<Org<'a>, ParseError> {
+        Org::parse_custom(text, config).into_result(config)
+    }
+
     /// Likes `parse_custom`, but accepts `String`.
     pub fn parse_string_custom(text: String, config: &ParseConfig) -> Org<'static> {
+        // a byte order mark is skipped, content stays a slice of `text`
+        let content = text.strip_prefix('\u{feff}').unwrap_or(&text);
+        let config = config.with_buffer_settings(content).into_owned();
         let mut arena = Arena::new();
-        let (text, pre_blank) = blank_lines_count(&text);
+        let mut diagnostics = Diagnostics::new(&text, config.strictness);
+        let mut spans = Spans::new(&text);
+        let (content, pre_blank) = blank_lines_count(content);
         let root = arena.new_node(Element::Document { pre_blank });
-        let mut org = Org { arena, root };
+        spans.insert(root, &text);
+        let mut org = Org {
+            arena,
+            root,
+            diagnostics: Vec::new(),
+            spans: HashMap::new(),
+            span_table: OnceLock::new(),
+            document_order: OnceLock::new(),
+            source: Cow::Borrowed(""),
+            config,
+        };
 
-        parse_container(
+        parse_container_with_diagnostics(
             &mut OwnedArena::new(&mut org.arena),
             Container::Document {
-                content: text,
+                content,
                 node: org.root,
             },
-            config,
+            &org.config,
+            &mut diagnostics,
+            &mut spans,
         );
 
+        org.diagnostics = diagnostics.into_errors();
+        org.spans = spans.into_spans();
+        org.source = Cow::Owned(text);
+
         org.debug_validate();
 
         org
     }
 
+    /// Returns `true` if the parsed text starts with a byte order mark.
+    ///
+    /// The mark is skipped by the parser, and written back by org export
+    /// only if `DefaultOrgHandler::keep_bom` is set.
+    ///
+    /// ```rust
+    /// use orgize::Org;
+    ///
+    /// let org = Org::parse("\u{feff}* Title\n");
+    /// assert!(org.has_bom());
+    /// assert_eq!(org.headlines().next().unwrap().title(&org).raw, "Title");
+    /// ```
+    pub fn has_bom(&self) -> bool {
+        self.source.starts_with('\u{feff}')
+    }
+
+    /// Likes `try_parse_custom`, but accepts `String`.
+    pub fn try_parse_string_custom(
+        text: String,
+        config: &ParseConfig,
+    ) -> Result<Org<'static>, ParseError> {
+        Org::parse_string_custom(text, config).into_result(config)
+    }
+
+    fn into_result(self, config: &ParseConfig) -> Result<Self, ParseError> {
+        match (config.strictness, self.diagnostics.first()) {
+            (Strictness::Strict, Some(error)) => Err(error.clone()),
+            _ => Ok(self),
+        }
+    }
+
+    /// Returns the configuration this `Org` was parsed with, including todo
+    /// keywords set by `#+TODO:`, `#+SEQ_TODO:` and `#+TYP_TODO:` in the
+    /// document.
+    ///
+    /// ```rust
+    /// use orgize::Org;
+    ///
+    /// let org = Org::parse("#+TODO: TODO NEXT | DONE CANCELLED\n* CANCELLED a\n");
+    ///
+    /// assert_eq!(org.config().todo_keywords.1, vec!["DONE", "CANCELLED"]);
+    /// assert!(org.headlines().next().unwrap().is_done(&org));
+    /// ```
+    pub fn config(&self) -> &ParseConfig {
+        &self.config
+    }
+
+    /// Returns `#+STARTUP:` options of this document, on top of the ones of
+    /// the parse configuration.
+    pub fn startup(&self) -> &Startup {
+        &self.config.startup
+    }
+
+    /// Returns malformed constructs found while parsing, sorted by offset.
+    ///
+    /// It's always empty unless the `Org` was parsed with `Strictness::Warn`
+    /// or `Strictness::Strict`.
+    pub fn diagnostics(&self) -> &[ParseError] {
+        &self.diagnostics
+    }
+
     /// Returns a reference to the underlay arena.
     pub fn arena(&self) -> &Arena<Element<'a>> {
         &self.arena
@@ -87,6 +265,9 @@ impl<'a> Org<'a> {
 
     /// Returns a mutual reference to the underlay arena.
     pub fn arena_mut(&mut self) -> &mut Arena<Element<'a>> {
+        // the tree may be changed
+        self.document_order = OnceLock::new();
+        self.span_table = OnceLock::new();
         &mut self.arena
     }
 
@@ -98,6 +279,59 @@ impl<'a> Org<'a> {
         })
     }
 
+    /// Returns an iterator of `NodeEvent`s, which are `Event`s along with
+    /// their nodes and depths.
+    ///
+    /// Like `iter`, every element yields a `Start` event and an `End` event,
+    /// in LIFO order, whether it's a container or not. The `End` event has
+    /// the same depth as its `Start` event.
+    ///
+    /// ```rust
+    /// use orgize::{Event, Org};
+    ///
+    /// let org = Org::parse("* title\ntext");
+    ///
+    /// let starts: Vec<_> = org
+    ///     .events()
+    ///     .filter(|event| matches!(event.event, Event::Start(_)))
+    ///     .map(|event| (event.depth, event.event.element().name()))
+    ///     .collect();
+    ///
+    /// assert_eq!(
+    ///     starts,
+    ///     vec![
+    ///         (0, "org-data"),
+    ///         (1, "headline"),
+    ///         (2, "title"),
+    ///         (3, "plain-text"),
+    ///         (2, "section"),
+    ///         (3, "paragraph"),
+    ///         (4, "plain-text"),
+    ///     ]
+    /// );
+    /// ```
+    pub fn events<'b>(&'b self) -> impl Iterator<Item = NodeEvent<'a, 'b>> + 'b {
+        let mut depth = 0;
+        self.root.traverse(&self.arena).map(move |edge| match edge {
+            NodeEdge::Start(node) => {
+                depth += 1;
+                NodeEvent {
+                    event: Event::Start(&self[node]),
+                    node,
+                    depth: depth - 1,
+                }
+            }
+            NodeEdge::End(node) => {
+                depth -= 1;
+                NodeEvent {
+                    event: Event::End(&self[node]),
+                    node,
+                    depth,
+                }
+            }
+        })
+    }
+
     /// Returns an iterator of `Keyword`s.
     pub fn keywords(&self) -> impl Iterator<Item = &Keyword<'_>> {
         self.root
@@ -109,55 +343,423 @@ impl<'a> Org<'a> {
             })
     }
 
+    /// Returns an iterator of header rows of table `node`, which are the rows
+    /// before the first horizontal rule.
+    ///
+    /// It's empty if the table has no horizontal rule.
+    pub fn header_rows(&self, node: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        node.children(&self.arena)
+            .filter(move |&child| matches!(self[child], Element::TableRow(TableRow::Header)))
+    }
+
+    /// Returns an iterator of body rows of table `node`, horizontal rules are
+    /// not included.
+    pub fn body_rows(&self, node: NodeId) -> impl Iterator<Item = NodeId> + '_ {
+        node.children(&self.arena)
+            .filter(move |&child| matches!(self[child], Element::TableRow(TableRow::Body)))
+    }
+
+    /// Returns the `#+RESULTS:` keyword of source block or babel call `node`,
+    /// the results element itself is the keyword's next sibling.
+    ///
+    /// It's the keyword right after `node`, or the keyword whose value is
+    /// the name given by `node`'s `#+NAME:` keyword. A `:RESULTS:` drawer
+    /// right after `node` is returned as well, if it's recognized as a
+    /// drawer, see `ParseConfig::drawer_names`.
+    pub fn results(&self, node: NodeId) -> Option<NodeId> {
+        let is_results = |node: NodeId| matches!(&self[node], Element::Keyword(kw) if kw.key.eq_ignore_ascii_case("RESULTS"));
+
+        if let Some(next) = node.following_siblings(&self.arena).nth(1) {
+            match &self[next] {
+                Element::Drawer(drawer) if drawer.name.eq_ignore_ascii_case("RESULTS") => {
+                    return Some(next)
+                }
+                _ if is_results(next) => return Some(next),
+                _ => (),
+            }
+        }
+
+        let name = self.name(node)?;
+
+        self.root.descendants(&self.arena).find(|&node| {
+            is_results(node) && matches!(&self[node], Element::Keyword(kw) if kw.value == name)
+        })
+    }
+
+    /// Returns the name of element `node`, given by its `#+NAME:` keyword.
+    pub(crate) fn name(&self, node: NodeId) -> Option<&str> {
+        self.affiliated(node)
+            .find(|kw| kw.key.eq_ignore_ascii_case("NAME"))
+            .map(|kw| &*kw.value)
+    }
+
+    /// Returns the caption of element `node`, given by its `#+CAPTION:`
+    /// keywords, which are joined with a space.
+    pub(crate) fn caption(&self, node: NodeId) -> Option<String> {
+        let mut captions: Vec<_> = self
+            .affiliated(node)
+            .filter(|kw| kw.key.eq_ignore_ascii_case("CAPTION"))
+            .map(|kw| &*kw.value)
+            .collect();
+        if captions.is_empty() {
+            return None;
+        }
+        captions.reverse();
+        Some(captions.join(" "))
+    }
+
+    // keywords right before element `node`, nearest first
+    fn affiliated(&self, node: NodeId) -> impl Iterator<Item = &Keyword<'a>> {
+        node.preceding_siblings(&self.arena)
+            .skip(1)
+            .map_while(move |node| match &self[node] {
+                Element::Keyword(kw) => Some(kw),
+                _ => None,
+            })
+    }
+
+    /// Returns nodes which are excluded from html export by `:exports`
+    /// header arguments, and headlines left out by `Org::export_tags`.
+    pub(crate) fn unexported(&self) -> HashSet<NodeId> {
+        let mut nodes = self.unselected_headlines(&self.export_tags());
+
+        for node in self.root.descendants(&self.arena) {
+            let (exports, results) = match &self[node] {
+                Element::SourceBlock(block) => (block.exports(), self.results(node)),
+                Element::BabelCall(call) => (call.exports(), self.results(node)),
+                Element::InlineSrc(src) => (src.exports(), self.inline_results(node)),
+                Element::InlineCall(call) => (call.exports(), self.inline_results(node)),
+                _ => continue,
+            };
+
+            if !exports.code() {
+                nodes.insert(node);
+            }
+            if let (false, Some(results)) = (exports.results(), results) {
+                nodes.insert(results);
+                // results element follows the keyword directly
+                if let Element::Keyword(Keyword { post_blank: 0, .. }) = &self[results] {
+                    nodes.extend(results.following_siblings(&self.arena).nth(1));
+                }
+            }
+        }
+
+        nodes
+    }
+
+    /// Returns headlines tagged with one of the exclude tags, and, if some
+    /// headline is tagged with one of the select tags, headlines which
+    /// neither are in such a subtree nor contain one.
+    pub(crate) fn unselected_headlines(&self, tags: &ExportTags) -> HashSet<NodeId> {
+        let tagged = |headline: &Headline, list: &[&str]| {
+            headline
+                .title(self)
+                .tags
+                .iter()
+                .any(|tag| list.contains(&&**tag))
+        };
+
+        let selected: HashSet<_> = self
+            .headlines()
+            .filter(|headline| tagged(headline, &tags.select))
+            .map(|headline| headline.headline_node())
+            .collect();
+        // selected headlines and their ancestors
+        let kept: HashSet<_> = selected
+            .iter()
+            .flat_map(|node| node.ancestors(&self.arena))
+            .collect();
+
+        self.headlines()
+            .filter(|headline| {
+                let node = headline.headline_node();
+                tagged(headline, &tags.exclude)
+                    || (!selected.is_empty()
+                        && !kept.contains(&node)
+                        && !node.ancestors(&self.arena).any(|n| selected.contains(&n)))
+            })
+            .map(|headline| headline.headline_node())
+            .collect()
+    }
+
+    /// Returns the `{{{results(...)}}}` macro following inline source block
+    /// or inline babel call `node`.
+    fn inline_results(&self, node: NodeId) -> Option<NodeId> {
+        node.following_siblings(&self.arena)
+            .skip(1)
+            .find(|&node| !matches!(&self[node], Element::Text { value } if value.trim().is_empty()))
+            .filter(|&node| {
+                matches!(&self[node], Element::Macros(macros) if macros.name.eq_ignore_ascii_case("results"))
+            })
+    }
+
     /// Writes an `Org` struct as html format.
-    pub fn write_html<W>(&self, writer: W) -> Result<(), Error>
+    pub fn write_html<W>(&self, writer: W) -> Result<(), ExportError<Error>>
     where
         W: Write,
     {
-        self.write_html_custom(writer, &mut DefaultHtmlHandler)
+        self.write_html_custom(writer, &mut DefaultHtmlHandler::default())
     }
 
     /// Writes an `Org` struct as html format with custom `HtmlHandler`.
-    pub fn write_html_custom<W, H, E>(&self, mut writer: W, handler: &mut H) -> Result<(), E>
+    ///
+    /// Source blocks, babel calls and their results are exported according
+    /// to their `:exports` header arguments, and subtrees according to the
+    /// tags of `Org::export_tags`.
+    ///
+    /// Errors returned by the handler are wrapped in an `ExportError` along
+    /// with the element being written.
+    pub fn write_html_custom<W, H, E>(
+        &self,
+        mut writer: W,
+        handler: &mut H,
+    ) -> Result<(), ExportError<E>>
     where
         W: Write,
         E: From<Error>,
         H: HtmlHandler<E>,
     {
-        for event in self.iter() {
-            match event {
-                Event::Start(element) => handler.start(&mut writer, element)?,
-                Event::End(element) => handler.end(&mut writer, element)?,
-            }
-        }
+        self.write_html_node(self.root, &mut writer, handler)
+    }
 
+    /// Writes `headline` and its subheadlines as html format with custom
+    /// `HtmlHandler`.
+    pub fn write_subtree_html_custom<W, H, E>(
+        &self,
+        headline: Headline,
+        mut writer: W,
+        handler: &mut H,
+    ) -> Result<(), ExportError<E>>
+    where
+        W: Write,
+        E: From<Error>,
+        H: HtmlHandler<E>,
+    {
+        self.write_html_node(headline.headline_node(), &mut writer, handler)
+    }
+
+    /// Exports `headline` and its subheadlines as html format, returns its
+    /// `:EXPORT_FILE_NAME:` property along with the output.
+    ///
+    /// ```rust
+    /// use orgize::Org;
+    ///
+    /// let org = Org::parse("* Post\n:PROPERTIES:\n:EXPORT_FILE_NAME: post\n:END:\ntext\n");
+    /// let headline = org.headlines().next().unwrap();
+    ///
+    /// let (file_name, html) = org.export_subtree_html(headline).unwrap();
+    /// assert_eq!(file_name, Some("post"));
+    /// assert_eq!(html, "<h1>Post</h1><section><p>text</p></section>");
+    /// ```
+    pub fn export_subtree_html(
+        &self,
+        headline: Headline,
+    ) -> Result<(Option<&str>, String), ExportError<Error>> {
+        let mut writer = Vec::new();
+        self.write_subtree_html_custom(headline, &mut writer, &mut DefaultHtmlHandler::default())?;
+        Ok((
+            headline.export_file_name(self),
+            String::from_utf8_lossy(&writer).into_owned(),
+        ))
+    }
+
+    pub(crate) fn write_html_node<W, H, E>(
+        &self,
+        node: NodeId,
+        mut writer: W,
+        handler: &mut H,
+    ) -> Result<(), ExportError<E>>
+    where
+        W: Write,
+        E: From<Error>,
+        H: HtmlHandler<E>,
+    {
+        handler.prepare(self);
+        self.write_html_edges(node, &mut writer, handler, true)
+    }
+
+    // writes `node` without preparing `handler`, and nodes returned by
+    // `HtmlHandler::trailing_nodes` before its end if `trailing` is `true`
+    pub(crate) fn write_html_edges<W, H, E>(
+        &self,
+        node: NodeId,
+        writer: &mut W,
+        handler: &mut H,
+        trailing: bool,
+    ) -> Result<(), ExportError<E>>
+    where
+        W: Write,
+        E: From<Error>,
+        H: HtmlHandler<E>,
+    {
+        let mut edges = HtmlEdges::new(self, node, trailing);
+        while edges.write_next(writer, handler)?.is_some() {}
         Ok(())
     }
 
+    // writes nodes returned by `HtmlHandler::trailing_nodes` of `root`
+    pub(crate) fn write_html_trailing<W, H, E>(
+        &self,
+        root: NodeId,
+        writer: &mut W,
+        handler: &mut H,
+    ) -> Result<(), ExportError<E>>
+    where
+        W: Write,
+        E: From<Error>,
+        H: HtmlHandler<E>,
+    {
+        loop {
+            let nodes = handler.trailing_nodes(self, root);
+            if nodes.is_empty() {
+                return Ok(());
+            }
+            for node in nodes {
+                self.write_html_edges(node, writer, handler, false)?;
+            }
+        }
+    }
+
     /// Writes an `Org` struct as org format.
-    pub fn write_org<W>(&self, writer: W) -> Result<(), Error>
+    pub fn write_org<W>(&self, writer: W) -> Result<(), ExportError<Error>>
     where
         W: Write,
     {
-        self.write_org_custom(writer, &mut DefaultOrgHandler)
+        self.write_org_custom(writer, &mut DefaultOrgHandler::default())
     }
 
     /// Writes an `Org` struct as org format with custom `OrgHandler`.
-    pub fn write_org_custom<W, H, E>(&self, mut writer: W, handler: &mut H) -> Result<(), E>
+    ///
+    /// Errors returned by the handler are wrapped in an `ExportError` along
+    /// with the element being written.
+    pub fn write_org_custom<W, H, E>(
+        &self,
+        mut writer: W,
+        handler: &mut H,
+    ) -> Result<(), ExportError<E>>
     where
         W: Write,
         E: From<Error>,
         H: OrgHandler<E>,
     {
-        for event in self.iter() {
-            match event {
-                Event::Start(element) => handler.start(&mut writer, element)?,
-                Event::End(element) => handler.end(&mut writer, element)?,
+        handler.prepare(self);
+
+        for edge in self.root.traverse(&self.arena) {
+            match edge {
+                NodeEdge::Start(node) => handler
+                    .start_node(&mut writer, node, &self[node])
+                    .map_err(|err| self.export_error(node, err))?,
+                NodeEdge::End(node) => handler
+                    .end_node(&mut writer, node, &self[node])
+                    .map_err(|err| self.export_error(node, err))?,
             }
         }
 
         Ok(())
     }
+
+    /// Wraps an error occurred while writing `node`.
+    pub(crate) fn export_error<E>(&self, node: NodeId, error: E) -> ExportError<E> {
+        ExportError {
+            element: self[node].kind(),
+            node,
+            span: self.span(node),
+            error,
+        }
+    }
+
+    /// Formats an `Org` struct as a normalized org document.
+    ///
+    /// See [`FormatOrgHandler`] for what gets normalized.
+    ///
+    /// [`FormatOrgHandler`]: export/struct.FormatOrgHandler.html
+    ///
+    /// ```rust
+    /// use orgize::{export::FormatConfig, Org};
+    ///
+    /// let org = Org::parse("*   TODO  Title  :tag:\n  text  \n");
+    ///
+    /// let config = FormatConfig {
+    ///     tags_column: 20,
+    ///     ..Default::default()
+    /// };
+    ///
+    /// assert_eq!(org.format(&config), "* TODO Title        :tag:\ntext\n");
+    /// ```
+    pub fn format(&self, config: &FormatConfig) -> String {
+        let mut writer = Vec::new();
+        self.write_org_custom(&mut writer, &mut FormatOrgHandler::new(config.clone()))
+            .unwrap();
+        String::from_utf8(writer).unwrap()
+    }
+}
+
+// steps through the edges written by `Org::write_html_edges`, so that
+// `write_html_async_custom` can flush its writer after each of them
+pub(crate) struct HtmlEdges<'o, 'a> {
+    org: &'o Org<'a>,
+    root: NodeId,
+    trailing: bool,
+    unexported: HashSet<NodeId>,
+    traverse: Traverse<'o, Element<'a>>,
+}
+
+impl<'o, 'a> HtmlEdges<'o, 'a> {
+    pub(crate) fn new(org: &'o Org<'a>, root: NodeId, trailing: bool) -> Self {
+        HtmlEdges {
+            org,
+            root,
+            trailing,
+            unexported: org.unexported(),
+            traverse: root.traverse(&org.arena),
+        }
+    }
+
+    // writes the next edge, returns its node or `None` when all edges are
+    // written
+    pub(crate) fn write_next<W, H, E>(
+        &mut self,
+        writer: &mut W,
+        handler: &mut H,
+    ) -> Result<Option<NodeId>, ExportError<E>>
+    where
+        W: Write,
+        E: From<Error>,
+        H: HtmlHandler<E>,
+    {
+        let org = self.org;
+        while let Some(edge) = self.traverse.next() {
+            match edge {
+                // footnote definitions are written by `write_html_trailing`
+                NodeEdge::Start(node)
+                    if self.unexported.contains(&node)
+                        || (node != self.root && matches!(org[node], Element::FnDef(_))) =>
+                {
+                    for edge in &mut self.traverse {
+                        if matches!(edge, NodeEdge::End(end) if end == node) {
+                            break;
+                        }
+                    }
+                }
+                NodeEdge::Start(node) => {
+                    handler
+                        .start_node(&mut *writer, node, &org[node])
+                        .map_err(|err| org.export_error(node, err))?;
+                    return Ok(Some(node));
+                }
+                NodeEdge::End(node) => {
+                    if self.trailing && node == self.root {
+                        org.write_html_trailing(node, writer, handler)?;
+                    }
+                    handler
+                        .end_node(&mut *writer, node, &org[node])
+                        .map_err(|err| org.export_error(node, err))?;
+                    return Ok(Some(node));
+                }
+            }
+        }
+        Ok(None)
+    }
 }
 
 impl Default for Org<'static> {
@@ -3,12 +3,17 @@
 use memchr::memchr;
 use nom::{
     bytes::complete::take_while1,
-    combinator::verify,
+    character::complete::{char, digit1},
+    combinator::{recognize, verify},
     error::{make_error, ErrorKind},
+    sequence::separated_pair,
     Err, IResult,
 };
 
-// read until the first line_ending, if line_ending is not present, return the input directly
+/// Reads the first line, without its line ending, which can be `\n` or
+/// `\r\n`. Returns the whole input if it has no line ending.
+///
+/// Never fails, the line is empty if the input is.
 pub fn line(input: &str) -> IResult<&str, &str, ()> {
     if let Some(i) = memchr(b'\n', input.as_bytes()) {
         if i > 0 && input.as_bytes()[i - 1] == b'\r' {
@@ -21,6 +26,10 @@ pub fn line(input: &str) -> IResult<&str, &str, ()> {
     }
 }
 
+/// Reads lines until one of them matches `predicate`. Returns the lines
+/// before it, with their line endings, and the input after it.
+///
+/// Fails if no line matches.
 pub fn lines_till<F>(predicate: F) -> impl Fn(&str) -> IResult<&str, &str, ()>
 where
     F: Fn(&str) -> bool,
@@ -48,6 +57,10 @@ where
     }
 }
 
+/// Reads lines while they match `predicate`. Returns these lines, with their
+/// line endings, and the input starting at the first line not matching.
+///
+/// Never fails, the lines are empty if the first line doesn't match.
 pub fn lines_while<F>(predicate: F) -> impl Fn(&str) -> IResult<&str, &str, ()>
 where
     F: Fn(&str) -> bool,
@@ -89,16 +102,27 @@ fn test_lines_while() {
     );
 }
 
+/// Reads a line made of whitespace only, e.g. the rest of a line after a
+/// construct.
+///
+/// Fails if the line contains anything else.
 pub fn eol(input: &str) -> IResult<&str, &str, ()> {
     verify(line, |s: &str| {
         s.as_bytes().iter().all(u8::is_ascii_whitespace)
     })(input)
 }
 
+/// Reads characters until an ASCII whitespace.
+///
+/// Fails if the input is empty or starts with a whitespace.
 pub fn one_word(input: &str) -> IResult<&str, &str, ()> {
     take_while1(|c: char| !c.is_ascii_whitespace())(input)
 }
 
+/// Reads blank lines, i.e. lines made of whitespace only, and returns their
+/// number and the input after them.
+///
+/// Never fails.
 pub fn blank_lines_count(input: &str) -> IResult<&str, usize, ()> {
     let mut count = 0;
     let mut input = input;
@@ -122,6 +146,21 @@ pub fn blank_lines_count(input: &str) -> IResult<&str, usize, ()> {
     }
 }
 
+/// Reads a duration like `1:30` of clock lines, and returns it as-is.
+///
+/// Fails if the input doesn't start with digits, a colon and digits.
+pub fn duration(input: &str) -> IResult<&str, &str, ()> {
+    recognize(separated_pair(digit1, char(':'), digit1))(input)
+}
+
+#[test]
+fn test_duration() {
+    assert_eq!(duration("1:30 "), Ok((" ", "1:30")));
+    assert_eq!(duration("12:05"), Ok(("", "12:05")));
+    assert!(duration(":30").is_err());
+    assert!(duration("1:").is_err());
+}
+
 #[test]
 fn test_blank_lines_count() {
     assert_eq!(blank_lines_count("foo"), Ok(("foo", 0)));
@@ -1 +1,38 @@
-pub mod combinators;
+//! Parser combinators for third-party extensions, e.g. parsers of custom
+//! elements, see `ParseConfig::custom_parser`.
+//!
+//! They are [nom](https://docs.rs/nom) parsers with `()` as error type: each
+//! one takes the input, and returns the remaining input along with what it
+//! has read.
+//!
+//! ```rust
+//! use orgize::parse::{blank_lines_count, line, one_word};
+//!
+//! let (input, first) = line("roam: a b\r\n\n\nrest").unwrap();
+//! assert_eq!(first, "roam: a b");
+//! assert_eq!(one_word(first), Ok((" a b", "roam:")));
+//! assert_eq!(blank_lines_count(input), Ok(("rest", 2)));
+//! ```
+
+pub(crate) mod combinators;
+
+use nom::IResult;
+
+use crate::elements::{timestamp::parse_timestamp, Timestamp};
+
+pub use combinators::{blank_lines_count, duration, eol, line, lines_till, lines_while, one_word};
+
+/// Reads a timestamp or a timestamp range, active or inactive, like
+/// `<2024-03-08 Fri 10:00 +1w>` or `[2024-03-08]--[2024-03-09]`, and also
+/// diary timestamps like `<%%(diary-float t 4 2)>`.
+///
+/// ```rust
+/// use orgize::{elements::Timestamp, parse::timestamp};
+///
+/// let (input, ts) = timestamp("<2024-03-08 Fri> rest").unwrap();
+/// assert_eq!(input, " rest");
+/// assert!(matches!(ts, Timestamp::Active { .. }));
+/// ```
+pub fn timestamp(input: &str) -> IResult<&str, Timestamp<'_>, ()> {
+    parse_timestamp(input)
+}
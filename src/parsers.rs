@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::iter::once;
 use std::marker::PhantomData;
 
@@ -7,13 +8,16 @@ use memchr::{memchr, memchr_iter};
 use nom::bytes::complete::take_while1;
 
 use crate::config::ParseConfig;
+use crate::diagnostic::Diagnostics;
 use crate::elements::{
     block::RawBlock, emphasis::Emphasis, keyword::RawKeyword, radio_target::parse_radio_target,
-    timestamp::parse_timestamp, Clock, Comment, Cookie, Drawer, DynBlock, Element, FixedWidth,
-    FnDef, FnRef, InlineCall, InlineSrc, Link, List, ListItem, Macros, Rule, Snippet, Table,
-    TableCell, TableRow, Target, Title,
+    table::parse_special_row, timestamp::parse_timestamp, Clock, Comment, Cookie, Custom,
+    CustomSource, Drawer, DynBlock, Element, ElementKind, FixedWidth, FnDef, FnRef, InlineCall,
+    InlineSrc, Link, List, ListItem, Macros, Rule, Skipped, Snippet, Table, TableCell, TableRow,
+    Target, Title,
 };
-use crate::parse::combinators::lines_while;
+use crate::parse::combinators::{line, lines_while};
+use crate::span::Spans;
 
 pub trait ElementArena<'a> {
     fn append<T>(&mut self, element: T, parent: NodeId) -> NodeId
@@ -25,6 +29,8 @@ pub trait ElementArena<'a> {
     fn set<T>(&mut self, node: NodeId, element: T)
     where
         T: Into<Element<'a>>;
+    fn last_child(&self, parent: NodeId) -> Option<NodeId>;
+    fn node_count(&self) -> usize;
 }
 
 pub type BorrowedArena<'a> = Arena<Element<'a>>;
@@ -58,6 +64,14 @@ impl<'a> ElementArena<'a> for BorrowedArena<'a> {
     {
         *self[node].get_mut() = element.into();
     }
+
+    fn last_child(&self, parent: NodeId) -> Option<NodeId> {
+        self[parent].last_child()
+    }
+
+    fn node_count(&self) -> usize {
+        self.len()
+    }
 }
 
 pub struct OwnedArena<'a, 'b, 'c> {
@@ -96,6 +110,14 @@ impl<'a> ElementArena<'a> for OwnedArena<'a, '_, '_> {
     {
         self.arena.set(node, element.into().into_owned());
     }
+
+    fn last_child(&self, parent: NodeId) -> Option<NodeId> {
+        self.arena.last_child(parent)
+    }
+
+    fn node_count(&self) -> usize {
+        self.arena.len()
+    }
 }
 
 #[derive(Debug)]
@@ -114,38 +136,135 @@ pub fn parse_container<'a, T: ElementArena<'a>>(
     arena: &mut T,
     container: Container<'a>,
     config: &ParseConfig,
+) {
+    parse_container_with_diagnostics(
+        arena,
+        container,
+        config,
+        &mut Diagnostics::disabled(),
+        &mut Spans::disabled(),
+    );
+}
+
+pub fn parse_container_with_diagnostics<'a, T: ElementArena<'a>>(
+    arena: &mut T,
+    container: Container<'a>,
+    config: &ParseConfig,
+    diagnostics: &mut Diagnostics,
+    spans: &mut Spans,
 ) {
     let containers = &mut vec![container];
 
     while let Some(container) = containers.pop() {
+        let container = match config.max_nodes {
+            Some(limit) if arena.node_count() >= limit => {
+                match parse_plain_text(arena, container, limit, diagnostics, spans) {
+                    Some(container) => container,
+                    None => continue,
+                }
+            }
+            _ => container,
+        };
         match container {
             Container::Document { content, node } => {
-                parse_section_and_headlines(arena, content, node, containers);
+                parse_section_and_headlines(arena, content, node, containers, config, spans);
             }
             Container::Headline { content, node } => {
-                parse_headline_content(arena, content, node, containers, config);
+                parse_headline_content(
+                    arena,
+                    content,
+                    node,
+                    containers,
+                    config,
+                    diagnostics,
+                    spans,
+                );
             }
             Container::Block { content, node } => {
-                parse_blocks(arena, content, node, containers);
+                // spans of list items and other blocks found by their
+                // parent are recorded already
+                spans.insert(node, content);
+                parse_blocks(arena, content, node, containers, config, diagnostics, spans);
             }
             Container::Inline { content, node } => {
-                parse_inlines(arena, content, node, containers);
+                spans.insert(node, content);
+                parse_inlines(arena, content, node, containers, config, spans);
+            }
+        }
+    }
+}
+
+// parses contents of a block or an object as plain text, once the arena
+// reached `ParseConfig::max_nodes`, returns other containers unchanged
+fn parse_plain_text<'a, T: ElementArena<'a>>(
+    arena: &mut T,
+    container: Container<'a>,
+    limit: usize,
+    diagnostics: &mut Diagnostics,
+    spans: &mut Spans,
+) -> Option<Container<'a>> {
+    match container {
+        Container::Block { content, node } => {
+            spans.insert(node, content);
+            let content = blank_lines_count(content).0;
+            let (text, post_blank) = trailing_blank_lines(content);
+            let text = text.trim_end();
+            if !text.is_empty() {
+                diagnostics.check_node_limit(text, limit);
+                let paragraph = arena.append(Element::Paragraph { post_blank }, node);
+                spans.insert(paragraph, text);
+                let node = arena.append(Element::Text { value: text.into() }, paragraph);
+                spans.insert(node, text);
+            }
+            None
+        }
+        Container::Inline { content, node } => {
+            spans.insert(node, content);
+            if !content.is_empty() {
+                diagnostics.check_node_limit(content, limit);
+                let node = arena.append(
+                    Element::Text {
+                        value: content.into(),
+                    },
+                    node,
+                );
+                spans.insert(node, content);
             }
+            None
         }
+        _ => Some(container),
     }
 }
 
+// `ParseConfig::max_line_length` is exceeded by the first line of `input`
+fn is_long_line(input: &str, config: &ParseConfig) -> bool {
+    config.max_line_length.is_some_and(|max| {
+        input.len() > max && memchr(b'\n', input.as_bytes()).unwrap_or(input.len()) > max
+    })
+}
+
 pub fn parse_headline_content<'a, T: ElementArena<'a>>(
     arena: &mut T,
     content: &'a str,
     parent: NodeId,
     containers: &mut Vec<Container<'a>>,
     config: &ParseConfig,
+    diagnostics: &mut Diagnostics,
+    spans: &mut Spans,
 ) {
-    let (tail, (title, content)) = Title::parse(content, config).unwrap();
+    spans.insert(parent, content);
+    let (tail, (title, raw)) = Title::parse(content, config).unwrap();
+    diagnostics.check_title(content, config);
+    let after_title = line(content).map_or("", |(tail, _)| tail);
+    if title.planning.is_none() {
+        diagnostics.check_planning(after_title);
+    } else {
+        diagnostics.check_planning_keywords(after_title);
+    }
     let node = arena.append(title, parent);
-    containers.push(Container::Inline { content, node });
-    parse_section_and_headlines(arena, tail, parent, containers);
+    spans.insert_until(node, content, tail);
+    containers.push(Container::Inline { content: raw, node });
+    parse_section_and_headlines(arena, tail, parent, containers, config, spans);
 }
 
 pub fn parse_section_and_headlines<'a, T: ElementArena<'a>>(
@@ -153,6 +272,8 @@ pub fn parse_section_and_headlines<'a, T: ElementArena<'a>>(
     content: &'a str,
     parent: NodeId,
     containers: &mut Vec<Container<'a>>,
+    config: &ParseConfig,
+    spans: &mut Spans,
 ) {
     let content = blank_lines_count(content).0;
 
@@ -162,22 +283,27 @@ pub fn parse_section_and_headlines<'a, T: ElementArena<'a>>(
 
     let mut last_end = 0;
     for i in memchr_iter(b'\n', content.as_bytes()).chain(once(content.len())) {
-        if let Some((mut tail, (headline_content, level))) = parse_headline(&content[last_end..]) {
+        if let Some((mut tail, (headline_content, level))) =
+            parse_headline(&content[last_end..], config)
+        {
             if last_end != 0 {
                 let node = arena.append(Element::Section, parent);
                 let content = &content[0..last_end];
+                spans.insert(node, content);
                 containers.push(Container::Block { content, node });
             }
 
             let node = arena.append(Element::Headline { level }, parent);
+            spans.insert(node, headline_content);
             containers.push(Container::Headline {
                 content: headline_content,
                 node,
             });
 
-            while let Some((new_tail, (content, level))) = parse_headline(tail) {
+            while let Some((new_tail, (content, level))) = parse_headline(tail, config) {
                 debug_assert_ne!(tail, new_tail);
                 let node = arena.append(Element::Headline { level }, parent);
+                spans.insert(node, content);
                 containers.push(Container::Headline { content, node });
                 tail = new_tail;
             }
@@ -187,6 +313,7 @@ pub fn parse_section_and_headlines<'a, T: ElementArena<'a>>(
     }
 
     let node = arena.append(Element::Section, parent);
+    spans.insert(node, content);
     containers.push(Container::Block { content, node });
 }
 
@@ -195,10 +322,24 @@ pub fn parse_blocks<'a, T: ElementArena<'a>>(
     content: &'a str,
     parent: NodeId,
     containers: &mut Vec<Container<'a>>,
+    config: &ParseConfig,
+    diagnostics: &mut Diagnostics,
+    spans: &mut Spans,
 ) {
     let mut tail = blank_lines_count(content).0;
 
-    if let Some(new_tail) = parse_block(content, arena, parent, containers) {
+    if let Some(new_tail) = parse_block(
+        content,
+        arena,
+        parent,
+        containers,
+        config,
+        diagnostics,
+        spans,
+    ) {
+        if let Some(node) = arena.last_child(parent) {
+            spans.insert_until(node, content, new_tail);
+        }
         tail = blank_lines_count(new_tail).0;
     }
 
@@ -229,7 +370,13 @@ pub fn parse_blocks<'a, T: ElementArena<'a>>(
 
             pos = 0;
             text = tail;
-        } else if let Some(new_tail) = parse_block(tail, arena, parent, containers) {
+        } else if let Some(new_tail) =
+            parse_block(tail, arena, parent, containers, config, diagnostics, spans)
+        {
+            if let Some(node) = arena.last_child(parent) {
+                spans.insert_until(node, tail, new_tail);
+            }
+
             if pos != 0 {
                 let node =
                     arena.insert_before_last_child(Element::Paragraph { post_blank: 0 }, parent);
@@ -266,25 +413,42 @@ pub fn parse_block<'a, T: ElementArena<'a>>(
     arena: &mut T,
     parent: NodeId,
     containers: &mut Vec<Container<'a>>,
+    config: &ParseConfig,
+    diagnostics: &mut Diagnostics,
+    spans: &mut Spans,
 ) -> Option<&'a str> {
+    if is_long_line(contents, config) {
+        return None;
+    }
+
     match contents
         .as_bytes()
         .iter()
         .find(|c| !c.is_ascii_whitespace())?
     {
-        b'[' => {
-            let (tail, (fn_def, content)) = FnDef::parse(contents)?;
+        // footnote definitions must start at column 0
+        b'[' if contents.starts_with('[') => {
+            let (tail, (fn_def, content)) = FnDef::parse(contents, config)?;
+            if let Some(skipped) = skip(config, ElementKind::FnDef, contents, tail) {
+                arena.append(skipped, parent);
+                return Some(tail);
+            }
             let node = arena.append(fn_def, parent);
             containers.push(Container::Block { content, node });
             Some(tail)
         }
         b'0'..=b'9' | b'*' => {
-            let tail = parse_list(arena, contents, parent, containers)?;
-            Some(tail)
+            parse_list(arena, contents, parent, containers, spans).or_else(|| {
+                diagnostics.check_bullet(contents);
+                None
+            })
         }
         b'C' => {
             let (tail, clock) = Clock::parse(contents)?;
-            arena.append(clock, parent);
+            match skip(config, ElementKind::Clock, contents, tail) {
+                Some(skipped) => arena.append(skipped, parent),
+                None => arena.append(clock, parent),
+            };
             Some(tail)
         }
         b'\'' => {
@@ -296,36 +460,76 @@ pub fn parse_block<'a, T: ElementArena<'a>>(
                 arena.append(rule, parent);
                 Some(tail)
             } else {
-                let tail = parse_list(arena, contents, parent, containers)?;
-                Some(tail)
+                parse_list(arena, contents, parent, containers, spans).or_else(|| {
+                    diagnostics.check_bullet(contents);
+                    None
+                })
             }
         }
         b':' => {
-            if let Some((tail, (drawer, content))) = Drawer::parse(contents) {
+            if let Some((tail, (drawer, content))) = Drawer::parse(contents, config) {
+                diagnostics.add_drawer(&drawer.name, content);
+                if let Some(skipped) = skip(config, ElementKind::Drawer, contents, tail) {
+                    arena.append(skipped, parent);
+                    return Some(tail);
+                }
                 let node = arena.append(drawer, parent);
                 containers.push(Container::Block { content, node });
                 Some(tail)
-            } else {
-                let (tail, fixed_width) = FixedWidth::parse(contents)?;
-                arena.append(fixed_width, parent);
+            } else if let Some((tail, fixed_width)) = FixedWidth::parse(contents) {
+                match skip(config, ElementKind::FixedWidth, contents, tail) {
+                    Some(skipped) => arena.append(skipped, parent),
+                    None => arena.append(fixed_width, parent),
+                };
                 Some(tail)
+            } else {
+                diagnostics.check_drawer(contents, &config.drawer_names);
+                None
             }
         }
+        b'|' if config.skip_elements.contains(ElementKind::Table) => {
+            let (tail, _) = lines_while(|line| line.trim_start().starts_with('|'))(contents)
+                .unwrap_or((contents, ""));
+            let (tail, _) = parse_tblfm(tail);
+            let tail = blank_lines_count(tail).0;
+            arena.append(skip(config, ElementKind::Table, contents, tail)?, parent);
+            Some(tail)
+        }
         b'|' => {
             let tail = parse_org_table(arena, contents, containers, parent);
             Some(tail)
         }
         b'+' => {
             if let Some((tail, table)) = Table::parse_table_el(contents) {
-                arena.append(table, parent);
+                match skip(config, ElementKind::Table, contents, tail) {
+                    Some(skipped) => arena.append(skipped, parent),
+                    None => arena.append(table, parent),
+                };
                 Some(tail)
             } else {
-                let tail = parse_list(arena, contents, parent, containers)?;
-                Some(tail)
+                parse_list(arena, contents, parent, containers, spans).or_else(|| {
+                    diagnostics.check_bullet(contents);
+                    None
+                })
             }
         }
         b'#' => {
             if let Some((tail, block)) = RawBlock::parse(contents) {
+                if block.is_special() {
+                    let source = CustomSource::Block {
+                        name: block.name,
+                        parameters: block.arguments,
+                        contents: block.contents,
+                    };
+                    if let Some(custom) = parse_custom(config, &source, contents, tail) {
+                        arena.append(custom, parent);
+                        return Some(tail);
+                    }
+                }
+                if let Some(skipped) = skip(config, block.kind(), contents, tail) {
+                    arena.append(skipped, parent);
+                    return Some(tail);
+                }
                 let (element, content) = block.into_element();
                 // avoid use after free
                 let is_block_container = match element {
@@ -341,22 +545,89 @@ pub fn parse_block<'a, T: ElementArena<'a>>(
                 }
                 Some(tail)
             } else if let Some((tail, (dyn_block, content))) = DynBlock::parse(contents) {
+                if let Some(skipped) = skip(config, ElementKind::DynBlock, contents, tail) {
+                    arena.append(skipped, parent);
+                    return Some(tail);
+                }
                 let node = arena.append(dyn_block, parent);
                 containers.push(Container::Block { content, node });
                 Some(tail)
             } else if let Some((tail, keyword)) = RawKeyword::parse(contents) {
+                if !keyword.key.eq_ignore_ascii_case("CALL") {
+                    let source = CustomSource::Keyword {
+                        key: keyword.key,
+                        optional: keyword.optional,
+                        value: keyword.value,
+                    };
+                    if let Some(custom) = parse_custom(config, &source, contents, tail) {
+                        arena.append(custom, parent);
+                        return Some(tail);
+                    }
+                }
+                if keyword.key.eq_ignore_ascii_case("BEGIN") {
+                    diagnostics.check_dyn_block(contents);
+                }
                 arena.append(keyword.into_element(), parent);
                 Some(tail)
-            } else {
-                let (tail, comment) = Comment::parse(contents)?;
-                arena.append(comment, parent);
+            } else if let Some((tail, comment)) = Comment::parse(contents) {
+                match skip(config, ElementKind::Comment, contents, tail) {
+                    Some(skipped) => arena.append(skipped, parent),
+                    None => arena.append(comment, parent),
+                };
                 Some(tail)
+            } else {
+                diagnostics.check_block(contents);
+                None
             }
         }
-        _ => None,
+        _ => {
+            diagnostics.check_zero_width(contents);
+            None
+        }
     }
 }
 
+// an element of `kind` parsed from `contents`, leaving `tail`, as a skipped
+// element if the configuration says so
+fn skip<'a>(
+    config: &ParseConfig,
+    kind: ElementKind,
+    contents: &'a str,
+    tail: &'a str,
+) -> Option<Element<'a>> {
+    if !config.skip_elements.contains(kind) {
+        return None;
+    }
+    let (raw, post_blank) = trailing_blank_lines(&contents[..contents.len() - tail.len()]);
+    Some(Element::Skipped(Skipped {
+        kind,
+        raw: raw.into(),
+        post_blank,
+    }))
+}
+
+// calls the custom parser with `source`, which was parsed from `contents`,
+// leaving `tail`
+fn parse_custom<'a>(
+    config: &ParseConfig,
+    source: &CustomSource<'a>,
+    contents: &'a str,
+    tail: &'a str,
+) -> Option<Element<'a>> {
+    let payload = config.custom_parser.as_ref()?.parse(source)?;
+    let name = match *source {
+        CustomSource::Block { name, .. } => name,
+        CustomSource::Keyword { key, .. } => key,
+    };
+    let (raw, post_blank) = trailing_blank_lines(&contents[..contents.len() - tail.len()]);
+    Some(Element::Custom(Custom {
+        name: name.into(),
+        raw: raw.into(),
+        payload,
+        post_blank,
+    }))
+}
+
 struct InlinePositions<'a> {
     bytes: &'a [u8],
     pos: usize,
@@ -404,30 +675,59 @@ pub fn parse_inlines<'a, T: ElementArena<'a>>(
     content: &'a str,
     parent: NodeId,
     containers: &mut Vec<Container<'a>>,
+    config: &ParseConfig,
+    spans: &mut Spans,
 ) {
     let mut tail = content;
+    let max_objects = config.max_objects.unwrap_or(usize::MAX);
+    let mut objects = 0;
+
+    // paragraphs with a line too long are left as-is
+    let opaque = config
+        .max_line_length
+        .is_some_and(|max| content.len() > max && content.split('\n').any(|line| line.len() > max));
+    if opaque || max_objects == 0 {
+        if !tail.is_empty() {
+            let node = arena.append(Element::Text { value: tail.into() }, parent);
+            spans.insert(node, tail);
+        }
+        return;
+    }
 
-    if let Some(tail_) = parse_inline(tail, arena, containers, parent) {
+    if let Some(tail_) = parse_inline(tail, arena, containers, parent, config) {
+        if let Some(node) = arena.last_child(parent) {
+            spans.insert_until(node, tail, tail_);
+        }
         tail = tail_;
+        objects += 1;
     }
 
     while let Some((tail_, i)) = InlinePositions::new(tail.as_bytes())
-        .filter_map(|i| parse_inline(&tail[i..], arena, containers, parent).map(|tail| (tail, i)))
+        .take_while(|_| objects < max_objects)
+        .filter_map(|i| {
+            parse_inline(&tail[i..], arena, containers, parent, config).map(|tail| (tail, i))
+        })
         .next()
     {
+        objects += 1;
+        if let Some(node) = arena.last_child(parent) {
+            spans.insert_until(node, &tail[i..], tail_);
+        }
         if i != 0 {
-            arena.insert_before_last_child(
+            let node = arena.insert_before_last_child(
                 Element::Text {
                     value: tail[0..i].into(),
                 },
                 parent,
             );
+            spans.insert(node, &tail[0..i]);
         }
         tail = tail_;
     }
 
     if !tail.is_empty() {
-        arena.append(Element::Text { value: tail.into() }, parent);
+        let node = arena.append(Element::Text { value: tail.into() }, parent);
+        spans.insert(node, tail);
     }
 }
 
@@ -436,6 +736,7 @@ pub fn parse_inline<'a, T: ElementArena<'a>>(
     arena: &mut T,
     containers: &mut Vec<Container<'a>>,
     parent: NodeId,
+    config: &ParseConfig,
 ) -> Option<&'a str> {
     if contents.len() < 3 {
         return None;
@@ -455,8 +756,9 @@ pub fn parse_inline<'a, T: ElementArena<'a>>(
             Some(tail)
         }
         b'<' => {
-            if let Some((tail, _content)) = parse_radio_target(contents) {
-                arena.append(Element::RadioTarget, parent);
+            if let Some((tail, content)) = parse_radio_target(contents) {
+                let node = arena.append(Element::RadioTarget, parent);
+                containers.push(Container::Inline { content, node });
                 Some(tail)
             } else if let Some((tail, target)) = Target::parse(contents) {
                 arena.append(target, parent);
@@ -484,7 +786,7 @@ pub fn parse_inline<'a, T: ElementArena<'a>>(
             }
         }
         b'*' | b'+' | b'/' | b'_' | b'=' | b'~' => {
-            let (tail, emphasis) = Emphasis::parse(contents, byte)?;
+            let (tail, emphasis) = Emphasis::parse(contents, byte, config)?;
             let (element, content) = emphasis.into_element();
             let is_inline_container = match element {
                 Element::Bold | Element::Strike | Element::Italic | Element::Underline => true,
@@ -515,27 +817,41 @@ pub fn parse_list<'a, T: ElementArena<'a>>(
     contents: &'a str,
     parent: NodeId,
     containers: &mut Vec<Container<'a>>,
+    spans: &mut Spans,
 ) -> Option<&'a str> {
-    let (mut tail, (first_item, content)) = ListItem::parse(contents)?;
-    let first_item_indent = first_item.indent;
-    let first_item_ordered = first_item.ordered;
+    let (mut tail, (mut item, mut content)) = ListItem::parse(contents)?;
+    let first_item_indent = item.indent;
+    let first_item_ordered = item.ordered;
 
     let parent = arena.append(Element::Document { pre_blank: 0 }, parent); // placeholder
 
-    let node = arena.append(first_item, parent);
-    containers.push(Container::Block { content, node });
-
-    while let Some((tail_, (item, content))) = ListItem::parse(tail) {
-        if item.indent == first_item_indent {
-            let node = arena.append(item, parent);
-            containers.push(Container::Block { content, node });
-            debug_assert_ne!(tail, tail_);
-            tail = tail_;
-        } else {
-            break;
+    // two consecutive blank lines end the list
+    while trailing_blank_lines(content).1 < 2 {
+        match ListItem::parse(tail) {
+            Some((tail_, (next, content_))) if next.indent == first_item_indent => {
+                // blank lines between items belong to the previous item
+                let (item_content, blank_lines) = trailing_blank_lines(content);
+                item.post_blank = blank_lines;
+                let node = arena.append(item, parent);
+                spans.insert(node, content);
+                containers.push(Container::Block {
+                    content: item_content,
+                    node,
+                });
+                item = next;
+                content = content_;
+                debug_assert_ne!(tail, tail_);
+                tail = tail_;
+            }
+            _ => break,
         }
     }
 
+    // blank lines after the last item belong to the list
+    let (content, blank_lines) = trailing_blank_lines(content);
+    let node = arena.append(item, parent);
+    containers.push(Container::Block { content, node });
+
     let (tail, post_blank) = blank_lines_count(tail);
 
     arena.set(
@@ -543,13 +859,25 @@ pub fn parse_list<'a, T: ElementArena<'a>>(
         List {
             indent: first_item_indent,
             ordered: first_item_ordered,
-            post_blank,
+            post_blank: blank_lines + post_blank,
         },
     );
 
     Some(tail)
 }
 
+// splits `input` at the start of its trailing blank lines
+fn trailing_blank_lines(input: &str) -> (&str, usize) {
+    let trimmed = input.trim_end().len();
+    match memchr(b'\n', &input.as_bytes()[trimmed..]) {
+        Some(i) => {
+            let (content, blank) = input.split_at(trimmed + i + 1);
+            (content, blank_lines_count(blank).1)
+        }
+        None => (input, 0),
+    }
+}
+
 pub fn parse_org_table<'a, T: ElementArena<'a>>(
     arena: &mut T,
     contents: &'a str,
@@ -558,9 +886,22 @@ pub fn parse_org_table<'a, T: ElementArena<'a>>(
 ) -> &'a str {
     let (tail, contents) =
         lines_while(|line| line.trim_start().starts_with('|'))(contents).unwrap_or((contents, ""));
+    let (tail, tblfm) = parse_tblfm(tail);
     let (tail, post_blank) = blank_lines_count(tail);
 
-    let mut iter = contents.trim_end().lines().peekable();
+    let mut columns = vec![];
+
+    let mut iter = contents
+        .trim_end()
+        .lines()
+        .filter(|line| {
+            let line = line.trim_start();
+            line.starts_with("|-") || {
+                let fields: Vec<_> = table_fields(line).collect();
+                !parse_special_row(&fields, &mut columns)
+            }
+        })
+        .peekable();
 
     let mut lines = vec![];
 
@@ -587,13 +928,17 @@ pub fn parse_org_table<'a, T: ElementArena<'a>>(
 
     let parent = arena.append(
         Table::Org {
-            tblfm: None,
+            tblfm,
             post_blank,
             has_header,
+            columns: columns.clone(),
+            raw: Some(contents.into()),
         },
         parent,
     );
 
+    let alignment = |i: usize| columns.get(i).and_then(|column| column.alignment);
+
     for line in lines {
         if line.starts_with("|-") {
             if has_header {
@@ -605,21 +950,25 @@ pub fn parse_org_table<'a, T: ElementArena<'a>>(
         } else {
             if has_header {
                 let parent = arena.append(Element::TableRow(TableRow::Header), parent);
-                for content in line.split_terminator('|').skip(1) {
-                    let node = arena.append(Element::TableCell(TableCell::Header), parent);
-                    containers.push(Container::Inline {
-                        content: content.trim(),
-                        node,
-                    });
+                for (i, content) in table_fields(line).enumerate() {
+                    let node = arena.append(
+                        Element::TableCell(TableCell::Header {
+                            alignment: alignment(i),
+                        }),
+                        parent,
+                    );
+                    containers.push(Container::Inline { content, node });
                 }
             } else {
                 let parent = arena.append(Element::TableRow(TableRow::Body), parent);
-                for content in line.split_terminator('|').skip(1) {
-                    let node = arena.append(Element::TableCell(TableCell::Body), parent);
-                    containers.push(Container::Inline {
-                        content: content.trim(),
-                        node,
-                    });
+                for (i, content) in table_fields(line).enumerate() {
+                    let node = arena.append(
+                        Element::TableCell(TableCell::Body {
+                            alignment: alignment(i),
+                        }),
+                        parent,
+                    );
+                    containers.push(Container::Inline { content, node });
                 }
             }
         }
@@ -628,27 +977,67 @@ pub fn parse_org_table<'a, T: ElementArena<'a>>(
     tail
 }
 
+// formulas of consecutive `#+TBLFM:` lines, one line each
+fn parse_tblfm(input: &str) -> (&str, Option<Cow<'_, str>>) {
+    let mut tail = input;
+    let mut tblfm: Option<Cow<str>> = None;
+
+    while let Ok((input, line)) = line(tail) {
+        let line = line.trim();
+        match line.get(..8) {
+            Some(prefix) if prefix.eq_ignore_ascii_case("#+TBLFM:") => {
+                let formulas = line[8..].trim();
+                tblfm = Some(match tblfm {
+                    Some(tblfm) => format!("{}\n{}", tblfm, formulas).into(),
+                    None => formulas.into(),
+                });
+                tail = input;
+            }
+            _ => break,
+        }
+    }
+
+    (tail, tblfm)
+}
+
+fn table_fields(line: &str) -> impl Iterator<Item = &str> {
+    line.split_terminator('|').skip(1).map(str::trim)
+}
+
 pub fn blank_lines_count(input: &str) -> (&str, usize) {
     crate::parse::combinators::blank_lines_count(input).unwrap_or((input, 0))
 }
 
-pub fn parse_headline(input: &str) -> Option<(&str, (&str, usize))> {
-    let (input_, level) = parse_headline_level(input)?;
+pub fn parse_headline<'a>(
+    input: &'a str,
+    config: &ParseConfig,
+) -> Option<(&'a str, (&'a str, usize))> {
+    let (input_, stars) = parse_headline_level(input, config)?;
     let (input_, content) = lines_while(move |line| {
-        parse_headline_level(line)
-            .map(|(_, l)| l > level)
+        parse_headline_level(line, config)
+            .map(|(_, l)| l > stars)
             .unwrap_or(true)
     })(input_)
     .unwrap_or((input_, ""));
-    Some((input_, (&input[0..level + content.len()], level)))
+    Some((
+        input_,
+        (
+            &input[0..stars + content.len()],
+            config.startup.level(stars),
+        ),
+    ))
 }
 
-pub fn parse_headline_level(input: &str) -> Option<(&str, usize)> {
+// stars followed by a space, at most `max_headline_level` stars
+pub fn parse_headline_level<'a>(input: &'a str, config: &ParseConfig) -> Option<(&'a str, usize)> {
     let (input, stars) = take_while1::<_, _, ()>(|c: char| c == '*')(input).ok()?;
 
-    if input.starts_with(' ') || input.starts_with('\n') || input.is_empty() {
-        Some((input, stars.len()))
-    } else {
-        None
+    if !input.starts_with(' ') || is_long_line(input, config) {
+        return None;
+    }
+
+    match config.max_headline_level {
+        Some(max) if stars.len() > max => None,
+        _ => Some((input, stars.len())),
     }
 }
@@ -0,0 +1,234 @@
+use indextree::NodeEdge;
+use std::borrow::Cow;
+use std::collections::HashMap;
+
+use crate::{
+    elements::{Element, Objects},
+    Event, Headline, Org,
+};
+
+impl Headline {
+    /// Returns the beginning of the section of this headline as plain text,
+    /// e.g. for list views.
+    ///
+    /// Text of paragraphs, including those in lists and quote, center,
+    /// verse and special blocks, is joined with whitespace collapsed. Links
+    /// contribute their description, or their path if they have none, and
+    /// emphasis markers are left out. Radio targets contribute their text,
+    /// macros their expansion, see `object_text`, and targets nothing.
+    /// Drawers, blocks with raw contents, tables, keywords and footnotes
+    /// never appear in the preview.
+    ///
+    /// If the text is longer than `limit` chars, it's cut at the last word
+    /// or object boundary within `limit`, so that links, code and words
+    /// like `\alpha` are never split, and `ellipsis` is appended. The
+    /// ellipsis doesn't count toward `limit`.
+    ///
+    /// ```rust
+    /// use orgize::Org;
+    ///
+    /// let org = Org::parse("* Post\nSome *bold* text and [[https://example.com][a long link]].\n");
+    /// let post = org.headlines().next().unwrap();
+    ///
+    /// assert_eq!(post.preview(&org, 100, "…"), "Some bold text and a long link.");
+    /// assert_eq!(post.preview(&org, 25, "…"), "Some bold text and…");
+    /// ```
+    pub fn preview(self, org: &Org, limit: usize, ellipsis: &str) -> String {
+        let mut preview = Preview::default();
+        let definitions = macro_definitions(org);
+
+        if let Some(section) = self.section_node() {
+            let mut traverse = section.traverse(&org.arena);
+            while let Some(edge) = traverse.next() {
+                let node = match edge {
+                    NodeEdge::Start(node) => node,
+                    NodeEdge::End(node) => {
+                        if matches!(org[node], Element::Paragraph { .. }) {
+                            preview.push_break();
+                        }
+                        continue;
+                    }
+                };
+                match &org[node] {
+                    Element::Section
+                    | Element::Paragraph { .. }
+                    | Element::List(_)
+                    | Element::ListItem(_)
+                    | Element::QuoteBlock(_)
+                    | Element::CenterBlock(_)
+                    | Element::VerseBlock(_)
+                    | Element::SpecialBlock(_)
+                    | Element::Bold
+                    | Element::Italic
+                    | Element::Strike
+                    | Element::Underline
+                    | Element::RadioTarget => (),
+                    Element::Text { value } => preview.push_text(value),
+                    // expansions are text, which can be cut
+                    element @ Element::Macros(_) => {
+                        if let Some(text) = object_text(element, &definitions) {
+                            preview.push_text(&text);
+                        }
+                    }
+                    element if element.is_object() => {
+                        if let Some(text) = object_text(element, &definitions) {
+                            preview.push_atom(&text);
+                        }
+                    }
+                    // skips the whole subtree
+                    _ => {
+                        for edge in &mut traverse {
+                            if matches!(edge, NodeEdge::End(end) if end == node) {
+                                break;
+                            }
+                        }
+                    }
+                }
+            }
+        }
+
+        preview.finish(limit, ellipsis)
+    }
+}
+
+/// Returns the plain-text value of object `element`, shared by renderings
+/// of org text as plain text, so they don't drift apart.
+///
+/// Containers, e.g. emphasis and radio targets, have no value of their own,
+/// their children hold their text. Targets, footnote references, snippets
+/// and inline calls render nothing. Macros are expanded with their
+/// definition in `definitions`, see `macro_definitions`, or render their
+/// name if they have none.
+pub(crate) fn object_text<'e>(
+    element: &'e Element,
+    definitions: &HashMap<&str, &str>,
+) -> Option<Cow<'e, str>> {
+    let text = match element {
+        Element::Text { value } | Element::Code { value } | Element::Verbatim { value } => {
+            Cow::Borrowed(&**value)
+        }
+        Element::Link(link) => match &link.desc {
+            Some(desc) => Cow::Owned(plain_text(desc, definitions)),
+            None => Cow::Borrowed(&*link.path),
+        },
+        Element::Timestamp(timestamp) => Cow::Owned(timestamp.to_string()),
+        Element::Cookie(cookie) => Cow::Borrowed(&*cookie.value),
+        // macros in expansions aren't expanded, so recursive ones end
+        Element::Macros(macros) => match definitions.get(&*macros.name) {
+            Some(template) => Cow::Owned(plain_text(&macros.expand(template), &HashMap::new())),
+            None => Cow::Borrowed(&*macros.name),
+        },
+        _ => return None,
+    };
+    Some(text)
+}
+
+// plain text of objects in `text`, e.g. a link description
+fn plain_text(text: &str, definitions: &HashMap<&str, &str>) -> String {
+    Objects::parse(text)
+        .iter()
+        .filter_map(|event| match event {
+            Event::Start(element) => object_text(element, definitions),
+            _ => None,
+        })
+        .collect()
+}
+
+// templates of macros defined by `#+MACRO: name template` keywords, later
+// definitions replace earlier ones
+fn macro_definitions<'o>(org: &'o Org) -> HashMap<&'o str, &'o str> {
+    org.keywords()
+        .filter(|keyword| keyword.key.eq_ignore_ascii_case("MACRO"))
+        .map(|keyword| {
+            let value = keyword.value.trim();
+            let (name, template) = value.split_once(char::is_whitespace).unwrap_or((value, ""));
+            (name, template.trim_start())
+        })
+        .filter(|(name, _)| !name.is_empty())
+        .collect()
+}
+
+#[derive(Default)]
+struct Preview {
+    text: String,
+    // byte offsets where the text can be cut
+    breaks: Vec<usize>,
+}
+
+impl Preview {
+    fn push_text(&mut self, text: &str) {
+        for c in text.chars() {
+            if c.is_whitespace() {
+                self.push_break();
+            } else {
+                self.text.push(c);
+            }
+        }
+    }
+
+    // pushes text which is never cut
+    fn push_atom(&mut self, text: &str) {
+        let start = self.text.len();
+        for c in text.chars() {
+            if !c.is_whitespace() {
+                self.text.push(c);
+            } else if !self.text[start..].is_empty() && !self.text.ends_with(' ') {
+                self.text.push(' ');
+            }
+        }
+        let end = self.text.trim_end().len();
+        self.text.truncate(end);
+        self.breaks.push(self.text.len());
+    }
+
+    // collapses whitespace into a single space
+    fn push_break(&mut self) {
+        if !self.text.is_empty() && !self.text.ends_with(' ') {
+            self.breaks.push(self.text.len());
+            self.text.push(' ');
+        }
+    }
+
+    fn finish(mut self, limit: usize, ellipsis: &str) -> String {
+        let end = self.text.trim_end().len();
+        self.text.truncate(end);
+
+        let cut = match self.text.char_indices().nth(limit) {
+            Some((cut, _)) => cut,
+            None => return self.text,
+        };
+        let end = self
+            .breaks
+            .iter()
+            .copied()
+            .filter(|&i| i <= cut)
+            .max()
+            .unwrap_or(0);
+        let mut text = self.text[0..end].trim_end().to_string();
+        text.push_str(ellipsis);
+        text
+    }
+}
+
+#[test]
+fn truncate() {
+    let mut preview = Preview::default();
+    preview.push_text("one  two\n");
+    preview.push_atom("three four");
+    preview.push_text(".");
+    assert_eq!(preview.text, "one two three four.");
+
+    let finish = |limit| {
+        let mut preview = Preview::default();
+        preview.push_text("one two ");
+        preview.push_atom("three four");
+        preview.push_text(" five");
+        preview.finish(limit, "...")
+    };
+    assert_eq!(finish(100), "one two three four five");
+    assert_eq!(finish(23), "one two three four five");
+    assert_eq!(finish(22), "one two three four...");
+    assert_eq!(finish(17), "one two...");
+    assert_eq!(finish(7), "one two...");
+    assert_eq!(finish(2), "...");
+}
@@ -0,0 +1,122 @@
+use crate::{
+    duration::OrgDuration,
+    elements::{Checkbox, Element, ListItem},
+    Headline, Org,
+};
+
+/// Options of `Headline::progress`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ProgressConfig {
+    /// Counts checkboxes of list items in the subtree instead of todo
+    /// headlines, default is `false`
+    pub checkboxes: bool,
+    /// Weights each headline by its `Effort` property in minutes, default
+    /// is `false`
+    ///
+    /// Headlines without a valid effort weigh nothing. Ignored when
+    /// counting checkboxes.
+    pub effort: bool,
+}
+
+/// Progress of a subtree, see `Headline::progress`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Progress {
+    /// Number, or total effort in minutes, of done tasks
+    pub done: u64,
+    /// Number, or total effort in minutes, of all tasks
+    pub total: u64,
+    /// Percentage of done tasks, rounded down like statistics cookies, `0`
+    /// if there's no task
+    pub percent: u64,
+}
+
+impl Headline {
+    /// Returns the progress of tasks below this headline.
+    ///
+    /// Tasks are descendant headlines with a todo keyword, done if it's a
+    /// done keyword of the configuration this `Org` was parsed with. A
+    /// task with tasks below it is counted through them only, so that
+    /// nested tasks aren't counted twice.
+    ///
+    /// ```rust
+    /// use orgize::{Org, ProgressConfig};
+    ///
+    /// let org = Org::parse(
+    ///     "* Project\n** DONE a\n** TODO b\n*** DONE b1\n*** TODO b2\n** notes\n",
+    /// );
+    /// let project = org.headlines().next().unwrap();
+    ///
+    /// let progress = project.progress(&org, &ProgressConfig::default());
+    /// assert_eq!((progress.done, progress.total, progress.percent), (2, 3, 66));
+    /// ```
+    pub fn progress(self, org: &Org, config: &ProgressConfig) -> Progress {
+        let (mut done, mut total) = (0, 0);
+
+        if config.checkboxes {
+            for node in self.headline_node().descendants(&org.arena) {
+                let checkbox = match &org[node] {
+                    Element::ListItem(ListItem {
+                        checkbox: Some(checkbox),
+                        ..
+                    }) => *checkbox,
+                    _ => continue,
+                };
+                // items with checkboxes below are counted through them
+                let nested = node.descendants(&org.arena).skip(1).any(|n| {
+                    matches!(
+                        org[n],
+                        Element::ListItem(ListItem {
+                            checkbox: Some(_),
+                            ..
+                        })
+                    )
+                });
+                if !nested {
+                    total += 1;
+                    done += (checkbox == Checkbox::On) as u64;
+                }
+            }
+        } else {
+            count_tasks(self, org, config, &mut done, &mut total);
+        }
+
+        Progress {
+            done,
+            total,
+            percent: (done * 100).checked_div(total).unwrap_or(0),
+        }
+    }
+}
+
+// counts tasks below `headline`, returns `true` if there's one
+fn count_tasks(
+    headline: Headline,
+    org: &Org,
+    config: &ProgressConfig,
+    done: &mut u64,
+    total: &mut u64,
+) -> bool {
+    let mut found = false;
+    for child in headline.children(org) {
+        let title = child.title(org);
+        if count_tasks(child, org, config, done, total) {
+            found = true;
+        } else if let Some(keyword) = &title.keyword {
+            let weight = if config.effort {
+                title
+                    .properties
+                    .get("EFFORT")
+                    .and_then(|effort| effort.parse::<OrgDuration>().ok())
+                    .map_or(0, OrgDuration::minutes)
+            } else {
+                1
+            };
+            *total += weight;
+            if org.config.is_done_keyword(keyword) {
+                *done += weight;
+            }
+            found = true;
+        }
+    }
+    found
+}
@@ -0,0 +1,487 @@
+use std::borrow::Cow;
+use std::cmp::Ordering;
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{
+    duration::OrgDuration, elements::Timestamp, timestamps::cmp_start, Headline, Org,
+    TagDefinitions,
+};
+
+/// Headline matcher, compiled from an agenda match string
+///
+/// The syntax is the one of Org's tags and property searches:
+///
+/// + `work`, `+work` selects headlines tagged `work`, and `-work` rejects
+///   them. Tags are inherited from ancestors, and group tags of `#+TAGS:`
+///   also match their members, see `Org::tags_in_group`.
+/// + `work+urgent` or `work&urgent` requires both terms, and `work|home`
+///   requires either of them. `&` binds tighter than `|`.
+/// + `Effort<="0:30"` compares a property, with `=`, `<>` (or `!=`), `<`,
+///   `<=`, `>` or `>=`. An unquoted number is compared as a number, a
+///   quoted duration like `"0:30"` or `"2h"` as a duration, and other
+///   quoted values as strings. A missing property is an empty string.
+/// + `TODO`, `LEVEL`, `PRIORITY`, `CATEGORY` and `ITEM` are special
+///   properties, for the todo keyword, the level, the priority cookie, the
+///   category and the raw title. `SCHEDULED`, `DEADLINE` and `TIMESTAMP`
+///   are timestamps of the planning line, and the first active timestamp
+///   of the entry, see `Headline::first_active_timestamp`.
+/// + A quoted timestamp, e.g. `TIMESTAMP>="<2024-03-01>"`, is compared
+///   with the start of a timestamp. It never matches a missing one.
+/// + `/` starts the todo part, e.g. `work/NEXT|WAITING` or
+///   `work/-DONE`, matched against todo keywords. `/!` only selects
+///   headlines with an unfinished todo keyword.
+///
+/// Regular expressions, `{...}`, aren't supported.
+///
+/// ```rust
+/// use orgize::{Matcher, Org};
+///
+/// let org = Org::parse(
+///     r#"* TODO a :work:
+/// * NEXT b :work:urgent:
+/// * DONE c :work:
+/// "#,
+/// );
+///
+/// let matcher = Matcher::parse("+work-urgent/!").unwrap();
+/// let titles: Vec<_> = org
+///     .match_headlines(&matcher)
+///     .into_iter()
+///     .map(|hdl| hdl.title(&org).raw.to_string())
+///     .collect();
+/// assert_eq!(titles, ["a"]);
+/// ```
+#[derive(Debug, Clone, PartialEq)]
+pub struct Matcher {
+    // alternatives of terms required together, empty matches anything
+    alternatives: Vec<Vec<Term>>,
+    todo: Option<TodoMatch>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct TodoMatch {
+    unfinished: bool,
+    // alternatives of keyword terms, empty matches anything
+    alternatives: Vec<Vec<Term>>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Term {
+    negated: bool,
+    kind: TermKind,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum TermKind {
+    Tag(String),
+    Property(String, Op, Value),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Ne,
+    Lt,
+    Le,
+    Gt,
+    Ge,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Value {
+    Number(f64),
+    String(String),
+}
+
+/// Error returned by `Matcher::parse`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MatchError {
+    /// Byte offset of the error in the match string
+    pub offset: usize,
+    /// Error kind
+    pub kind: MatchErrorKind,
+}
+
+/// Kind of `MatchError`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatchErrorKind {
+    /// A tag, property or todo keyword is expected, e.g. after `|` or `+`
+    ExpectedTerm,
+    /// A property is compared with nothing, or with an invalid number
+    ExpectedValue,
+    /// A quoted value isn't closed
+    UnclosedQuote,
+    /// Regular expressions are used
+    Unsupported,
+}
+
+impl fmt::Display for MatchError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let reason = match self.kind {
+            MatchErrorKind::ExpectedTerm => "expected a tag, property or todo keyword",
+            MatchErrorKind::ExpectedValue => "expected a number or a quoted value",
+            MatchErrorKind::UnclosedQuote => "unclosed quote",
+            MatchErrorKind::Unsupported => "regular expressions are not supported",
+        };
+        write!(f, "{} at {}", reason, self.offset)
+    }
+}
+
+impl Error for MatchError {}
+
+impl Matcher {
+    /// Compiles a match string.
+    ///
+    /// ```rust
+    /// use orgize::{MatchErrorKind, Matcher};
+    ///
+    /// assert!(Matcher::parse("+work+Effort<=\"0:30\"/!TODO|NEXT").is_ok());
+    ///
+    /// let err = Matcher::parse("work|").unwrap_err();
+    /// assert_eq!(err.kind, MatchErrorKind::ExpectedTerm);
+    /// assert_eq!(err.offset, 5);
+    /// assert_eq!(err.to_string(), "expected a tag, property or todo keyword at 5");
+    /// ```
+    pub fn parse(input: &str) -> Result<Matcher, MatchError> {
+        let mut parser = Parser { input, pos: 0 };
+
+        let alternatives = parser.alternatives(false)?;
+        let todo = if parser.eat('/') {
+            let unfinished = parser.eat('!');
+            Some(TodoMatch {
+                unfinished,
+                alternatives: parser.alternatives(true)?,
+            })
+        } else {
+            None
+        };
+
+        Ok(Matcher { alternatives, todo })
+    }
+
+    /// Returns `true` if `headline` is selected by this matcher.
+    pub fn matches(&self, headline: Headline, org: &Org) -> bool {
+        self.matches_with(headline, org, &org.tag_definitions())
+    }
+
+    fn matches_with(&self, headline: Headline, org: &Org, definitions: &TagDefinitions) -> bool {
+        let title = headline.title(org);
+
+        if let Some(todo) = &self.todo {
+            let keyword = title.keyword.as_deref();
+            if todo.unfinished && keyword.map_or(true, |kw| org.config().is_done_keyword(kw)) {
+                return false;
+            }
+            let matched = any_alternative(&todo.alternatives, |term| match &term.kind {
+                TermKind::Tag(name) => keyword == Some(name.as_str()),
+                TermKind::Property(..) => false,
+            });
+            if !matched {
+                return false;
+            }
+        }
+
+        let tags: Vec<&str> = std::iter::once(headline)
+            .chain(headline.ancestors(org))
+            .flat_map(|hdl| hdl.title(org).tags.iter().map(|tag| &**tag))
+            .collect();
+
+        any_alternative(&self.alternatives, |term| match &term.kind {
+            TermKind::Tag(name) => {
+                tags.contains(&name.as_str())
+                    || definitions
+                        .members(name)
+                        .iter()
+                        .any(|member| tags.contains(member))
+            }
+            TermKind::Property(key, op, value) => {
+                compare(property(headline, org, key).as_deref(), *op, value)
+            }
+        })
+    }
+}
+
+impl FromStr for Matcher {
+    type Err = MatchError;
+
+    fn from_str(s: &str) -> Result<Matcher, MatchError> {
+        Matcher::parse(s)
+    }
+}
+
+impl Org<'_> {
+    /// Returns headlines selected by `matcher`, in document order, see
+    /// `Matcher`.
+    pub fn match_headlines(&self, matcher: &Matcher) -> Vec<Headline> {
+        let definitions = self.tag_definitions();
+        self.headlines()
+            .filter(|&hdl| matcher.matches_with(hdl, self, &definitions))
+            .collect()
+    }
+}
+
+fn any_alternative<F>(alternatives: &[Vec<Term>], mut term_matches: F) -> bool
+where
+    F: FnMut(&Term) -> bool,
+{
+    alternatives.is_empty()
+        || alternatives
+            .iter()
+            .any(|terms| terms.iter().all(|term| term_matches(term) != term.negated))
+}
+
+fn property<'b>(headline: Headline, org: &'b Org, key: &str) -> Option<Cow<'b, str>> {
+    let title = headline.title(org);
+    match &*key.to_ascii_uppercase() {
+        "TODO" => title.keyword.as_deref().map(Cow::Borrowed),
+        "LEVEL" => Some(Cow::Owned(headline.level().to_string())),
+        "PRIORITY" => title
+            .priority
+            .map(|priority| Cow::Owned(priority.to_string())),
+        "CATEGORY" => Some(headline.category(org, "")),
+        "ITEM" => Some(Cow::Borrowed(&title.raw)),
+        "SCHEDULED" => title.scheduled().map(|ts| Cow::Owned(ts.to_string())),
+        "DEADLINE" => title.deadline().map(|ts| Cow::Owned(ts.to_string())),
+        "TIMESTAMP" => headline
+            .first_active_timestamp(org)
+            .map(|ts| Cow::Owned(ts.to_string())),
+        _ => title
+            .properties
+            .get(key)
+            .map(|value| Cow::Borrowed(value.trim())),
+    }
+}
+
+fn compare(property: Option<&str>, op: Op, value: &Value) -> bool {
+    let timestamp = match value {
+        Value::String(string) if string.starts_with(['<', '[']) => {
+            Timestamp::try_from(string.as_str()).ok()
+        }
+        _ => None,
+    };
+    let property = property.unwrap_or_default();
+    let ordering = match (value, timestamp) {
+        (_, Some(timestamp)) => Timestamp::try_from(property)
+            .ok()
+            .map(|property| cmp_start(&property, &timestamp)),
+        (Value::Number(number), _) => property
+            .parse::<f64>()
+            .ok()
+            .and_then(|property| property.partial_cmp(number)),
+        (Value::String(string), _) => match string.parse::<OrgDuration>() {
+            // quoted plain numbers are compared as strings
+            Ok(duration) if !string.bytes().all(|c| c.is_ascii_digit()) => property
+                .parse::<OrgDuration>()
+                .ok()
+                .map(|property| property.cmp(&duration)),
+            _ => Some(property.cmp(string.as_str())),
+        },
+    };
+
+    ordering.is_some_and(|ordering| match op {
+        Op::Eq => ordering == Ordering::Equal,
+        Op::Ne => ordering != Ordering::Equal,
+        Op::Lt => ordering == Ordering::Less,
+        Op::Le => ordering != Ordering::Greater,
+        Op::Gt => ordering == Ordering::Greater,
+        Op::Ge => ordering != Ordering::Less,
+    })
+}
+
+struct Parser<'a> {
+    input: &'a str,
+    pos: usize,
+}
+
+impl Parser<'_> {
+    fn rest(&self) -> &str {
+        &self.input[self.pos..]
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.rest().chars().next()
+    }
+
+    fn eat(&mut self, c: char) -> bool {
+        if self.peek() == Some(c) {
+            self.pos += c.len_utf8();
+            true
+        } else {
+            false
+        }
+    }
+
+    fn error(&self, kind: MatchErrorKind) -> MatchError {
+        MatchError {
+            offset: self.pos,
+            kind,
+        }
+    }
+
+    // parses `a+b|c-d`, until `/` or the end of the tags part, or until the
+    // end of the todo part
+    fn alternatives(&mut self, todo: bool) -> Result<Vec<Vec<Term>>, MatchError> {
+        let mut alternatives = Vec::new();
+        let mut terms = Vec::new();
+        loop {
+            let trimmed = self.rest().trim_start();
+            self.pos = self.input.len() - trimmed.len();
+            match self.peek() {
+                None => break,
+                Some('/') if !todo => break,
+                Some('|') if !terms.is_empty() => {
+                    self.eat('|');
+                    alternatives.push(std::mem::take(&mut terms));
+                }
+                Some('&') if !terms.is_empty() => {
+                    self.eat('&');
+                    // a term is required after `&`
+                    terms.push(self.term(todo)?);
+                }
+                Some(_) => terms.push(self.term(todo)?),
+            }
+        }
+
+        if !terms.is_empty() {
+            alternatives.push(terms);
+        } else if !alternatives.is_empty() {
+            // trailing `|`
+            return Err(self.error(MatchErrorKind::ExpectedTerm));
+        }
+        Ok(alternatives)
+    }
+
+    fn term(&mut self, todo: bool) -> Result<Term, MatchError> {
+        let negated = if self.eat('-') {
+            true
+        } else {
+            self.eat('+');
+            false
+        };
+
+        if self.peek() == Some('{') {
+            return Err(self.error(MatchErrorKind::Unsupported));
+        }
+        let len = self
+            .rest()
+            .find(|c: char| !(c.is_alphanumeric() || matches!(c, '_' | '@' | '#' | '%')))
+            .unwrap_or(self.rest().len());
+        if len == 0 {
+            return Err(self.error(MatchErrorKind::ExpectedTerm));
+        }
+        let name = self.rest()[..len].to_string();
+        self.pos += len;
+
+        if todo {
+            return Ok(Term {
+                negated,
+                kind: TermKind::Tag(name),
+            });
+        }
+
+        let op = [
+            ("<=", Op::Le),
+            (">=", Op::Ge),
+            ("<>", Op::Ne),
+            ("!=", Op::Ne),
+            ("==", Op::Eq),
+            ("<", Op::Lt),
+            (">", Op::Gt),
+            ("=", Op::Eq),
+        ]
+        .iter()
+        .find(|(s, _)| self.rest().starts_with(s));
+        let kind = match op {
+            Some((s, op)) => {
+                self.pos += s.len();
+                TermKind::Property(name, *op, self.value()?)
+            }
+            None => TermKind::Tag(name),
+        };
+
+        Ok(Term { negated, kind })
+    }
+
+    fn value(&mut self) -> Result<Value, MatchError> {
+        match self.peek() {
+            Some('"') => {
+                let start = self.pos;
+                let end = self.rest()[1..].find('"').ok_or(MatchError {
+                    offset: start,
+                    kind: MatchErrorKind::UnclosedQuote,
+                })?;
+                let value = self.rest()[1..end + 1].to_string();
+                self.pos += end + 2;
+                Ok(Value::String(value))
+            }
+            Some('{') => Err(self.error(MatchErrorKind::Unsupported)),
+            _ => {
+                // a leading `-` is a sign, others start the next term
+                let sign = usize::from(self.rest().starts_with('-'));
+                let len = self.rest()[sign..]
+                    .find(|c: char| c.is_whitespace() || "+-&|/".contains(c))
+                    .map_or(self.rest().len(), |len| len + sign);
+                match self.rest()[..len].parse() {
+                    Ok(number) => {
+                        self.pos += len;
+                        Ok(Value::Number(number))
+                    }
+                    Err(_) => Err(self.error(MatchErrorKind::ExpectedValue)),
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn parse() {
+    let matcher = Matcher::parse("+work-boss|Effort<-1.5&LEVEL=\"2\"/!-DONE").unwrap();
+    assert_eq!(
+        matcher.alternatives,
+        [
+            vec![
+                Term {
+                    negated: false,
+                    kind: TermKind::Tag("work".into())
+                },
+                Term {
+                    negated: true,
+                    kind: TermKind::Tag("boss".into())
+                }
+            ],
+            vec![
+                Term {
+                    negated: false,
+                    kind: TermKind::Property("Effort".into(), Op::Lt, Value::Number(-1.5))
+                },
+                Term {
+                    negated: false,
+                    kind: TermKind::Property("LEVEL".into(), Op::Eq, Value::String("2".into()))
+                }
+            ]
+        ]
+    );
+    let todo = matcher.todo.unwrap();
+    assert!(todo.unfinished);
+    assert_eq!(todo.alternatives.len(), 1);
+    assert!(todo.alternatives[0][0].negated);
+
+    assert!(Matcher::parse("").unwrap().alternatives.is_empty());
+
+    let error = |input| Matcher::parse(input).unwrap_err();
+    assert_eq!(error("work&").kind, MatchErrorKind::ExpectedTerm);
+    assert_eq!(error("|work").offset, 0);
+    assert_eq!(error("a+Effort<").kind, MatchErrorKind::ExpectedValue);
+    assert_eq!(error("a+Effort<").offset, 9);
+    assert_eq!(
+        error("a+CATEGORY=\"dev").kind,
+        MatchErrorKind::UnclosedQuote
+    );
+    assert_eq!(error("a+CATEGORY=\"dev").offset, 11);
+    assert_eq!(error("With={Sarah}").kind, MatchErrorKind::Unsupported);
+    assert_eq!(error("a/TODO=1").offset, 6);
+    assert_eq!(error("a/b/c").offset, 3);
+}
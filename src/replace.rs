@@ -0,0 +1,183 @@
+use std::borrow::Cow;
+use std::ops::Range;
+
+use indextree::NodeId;
+
+use crate::{
+    elements::Element,
+    validate::{ValidationError, ValidationResult},
+    Org,
+};
+
+#[cfg(feature = "regex")]
+use crate::elements::Link;
+#[cfg(feature = "regex")]
+use regex::{Captures, Regex};
+
+impl<'a> Org<'a> {
+    /// Replaces bytes `range` of text node `node` with `objects`, and returns
+    /// the inserted nodes.
+    ///
+    /// The text node is split around `range`, and the objects are inserted
+    /// between both parts, which are removed if they're empty. Objects are
+    /// inserted as leaf nodes, children of containers like `Bold` must be
+    /// appended with `Org::arena_mut`.
+    ///
+    /// The `raw` title of a headline isn't updated when replacing text of
+    /// its title.
+    ///
+    /// Returns an error if `node` isn't a `Text`. Panics if `range` is out
+    /// of bounds, or isn't on char boundaries.
+    ///
+    /// ```rust
+    /// use orgize::{elements::Link, Element, Event, Org};
+    ///
+    /// let mut org = Org::parse("See #123.\n");
+    /// let text = org
+    ///     .events()
+    ///     .find(|event| matches!(event.event, Event::Start(Element::Text { .. })))
+    ///     .map(|event| event.node)
+    ///     .unwrap();
+    ///
+    /// org.replace_text(
+    ///     text,
+    ///     4..8,
+    ///     vec![Element::Link(Link {
+    ///         path: "https://example.com/issues/123".into(),
+    ///         desc: Some("#123".into()),
+    ///     })],
+    /// )
+    /// .unwrap();
+    ///
+    /// let mut writer = Vec::new();
+    /// org.write_org(&mut writer).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(writer).unwrap(),
+    ///     "See [[https://example.com/issues/123][#123]].\n"
+    /// );
+    /// ```
+    pub fn replace_text<I>(
+        &mut self,
+        node: NodeId,
+        range: Range<usize>,
+        objects: I,
+    ) -> ValidationResult<Vec<NodeId>>
+    where
+        I: IntoIterator<Item = Element<'a>>,
+    {
+        let (before, after) = match &self[node] {
+            Element::Text { value } => (
+                slice(value, 0..range.start),
+                slice(value, range.end..value.len()),
+            ),
+            _ => {
+                return Err(ValidationError::UnexpectedElement {
+                    expected: "Text",
+                    at: node,
+                })
+            }
+        };
+
+        let mut last = node;
+        let mut inserted = Vec::new();
+        for object in objects {
+            let object = self.arena.new_node(object);
+            last.insert_after(object, &mut self.arena);
+            inserted.push(object);
+            last = object;
+        }
+        if !after.is_empty() {
+            let after = self.arena.new_node(Element::Text { value: after });
+            last.insert_after(after, &mut self.arena);
+        }
+        if before.is_empty() {
+            node.remove(&mut self.arena);
+        } else {
+            self[node] = Element::Text { value: before };
+        }
+
+        self.tree_changed();
+
+        Ok(inserted)
+    }
+
+    /// Replaces each match of `regex` in text with the link returned by
+    /// `link`, and returns the number of inserted links.
+    ///
+    /// Contents of code, verbatim, blocks and links aren't text, so they're
+    /// never linked. Headline titles are skipped too, see
+    /// `Org::replace_text`.
+    ///
+    /// ```rust
+    /// use orgize::{elements::Link, regex::Regex, Org};
+    ///
+    /// let mut org = Org::parse("Fixed #12, see ~#34~.\n");
+    /// let regex = Regex::new(r"#(\d+)").unwrap();
+    ///
+    /// let count = org.autolink(&regex, |caps| Link {
+    ///     path: format!("https://example.com/issues/{}", &caps[1]).into(),
+    ///     desc: Some(caps[0].to_string().into()),
+    /// });
+    /// assert_eq!(count, 1);
+    ///
+    /// let mut writer = Vec::new();
+    /// org.write_org(&mut writer).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(writer).unwrap(),
+    ///     "Fixed [[https://example.com/issues/12][#12]], see ~#34~.\n"
+    /// );
+    /// ```
+    #[cfg(feature = "regex")]
+    pub fn autolink<F>(&mut self, regex: &Regex, mut link: F) -> usize
+    where
+        F: FnMut(&Captures) -> Link<'a>,
+    {
+        let texts: Vec<NodeId> = self
+            .root
+            .descendants(&self.arena)
+            .filter(|&node| matches!(self[node], Element::Text { .. }))
+            .filter(|&node| {
+                !node
+                    .ancestors(&self.arena)
+                    .any(|node| matches!(self[node], Element::Title(_)))
+            })
+            .collect();
+
+        let mut count = 0;
+        for node in texts {
+            let value = match &self[node] {
+                Element::Text { value } => value.to_string(),
+                _ => continue,
+            };
+            let links: Vec<_> = regex
+                .captures_iter(&value)
+                .filter_map(|caps| {
+                    let range = caps.get(0)?.range();
+                    if range.is_empty() {
+                        None
+                    } else {
+                        Some((range, link(&caps)))
+                    }
+                })
+                .collect();
+
+            // from the end, so that the text node keeps the text before
+            for (range, link) in links.into_iter().rev() {
+                if self
+                    .replace_text(node, range, Some(Element::Link(link)))
+                    .is_ok()
+                {
+                    count += 1;
+                }
+            }
+        }
+        count
+    }
+}
+
+fn slice<'a>(value: &Cow<'a, str>, range: Range<usize>) -> Cow<'a, str> {
+    match value {
+        Cow::Borrowed(value) => Cow::Borrowed(&value[range]),
+        Cow::Owned(value) => Cow::Owned(value[range].to_string()),
+    }
+}
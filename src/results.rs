@@ -0,0 +1,172 @@
+use indextree::NodeId;
+
+use crate::{
+    elements::{Element, Keyword, Table, TableRow},
+    formula::cell_text,
+    Org,
+};
+
+/// Results of a source block or babel call, see `Org::results_content`
+///
+/// Each variant is a shape babel writes results in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ResultsValue {
+    /// No results element, e.g. a `#+RESULTS:` keyword followed by a blank
+    /// line
+    Empty,
+    /// Fixed-width lines, e.g. `: 42`, without their `: ` prefix
+    FixedWidth(String),
+    /// Contents of an example block
+    Example(String),
+    /// Cells of each row of a table, horizontal rules are left out
+    Table(Vec<Vec<String>>),
+    /// Contents of a `:results:` drawer, as org text
+    Drawer(String),
+    /// A paragraph, e.g. the single line of `:results raw`, as org text
+    Raw(String),
+    /// Path of a link, e.g. `file:plot.png` of `:results file`
+    Link(String),
+}
+
+impl Org<'_> {
+    /// Returns the results of source block or babel call `node`, or `None`
+    /// if it has no `#+RESULTS:` keyword or drawer, see `Org::results`.
+    ///
+    /// The results element directly follows the keyword. Anything else
+    /// after it, including an element after a blank line, isn't part of
+    /// the results, which are `ResultsValue::Empty` then.
+    ///
+    /// ```rust
+    /// use orgize::{Org, ResultsValue};
+    ///
+    /// let org = Org::parse("#+BEGIN_SRC sh\necho 42\n#+END_SRC\n\n#+RESULTS:\n: 42\n");
+    /// let block = org.document().section_node().unwrap();
+    /// let block = org.arena()[block].first_child().unwrap();
+    ///
+    /// assert_eq!(
+    ///     org.results_content(block),
+    ///     Some(ResultsValue::FixedWidth("42\n".into()))
+    /// );
+    /// ```
+    pub fn results_content(&self, node: NodeId) -> Option<ResultsValue> {
+        let (_, element) = self.results_nodes(node)?;
+        let element = match element {
+            Some(element) => element,
+            None => return Some(ResultsValue::Empty),
+        };
+
+        let value = match &self[element] {
+            Element::FixedWidth(fixed_width) => ResultsValue::FixedWidth(
+                fixed_width
+                    .value
+                    .lines()
+                    .map(|line| {
+                        let line = line.trim_start();
+                        line.strip_prefix(": ").unwrap_or(&line[1..])
+                    })
+                    .flat_map(|line| [line, "\n"])
+                    .collect(),
+            ),
+            Element::ExampleBlock(block) => ResultsValue::Example(block.contents.to_string()),
+            Element::Table(_) => ResultsValue::Table(
+                element
+                    .children(&self.arena)
+                    .filter(|&row| {
+                        matches!(
+                            self[row],
+                            Element::TableRow(TableRow::Header) | Element::TableRow(TableRow::Body)
+                        )
+                    })
+                    .map(|row| {
+                        row.children(&self.arena)
+                            .map(|cell| cell_text(self, cell).trim().to_string())
+                            .collect()
+                    })
+                    .collect(),
+            ),
+            Element::Drawer(_) => ResultsValue::Drawer(cell_text(self, element)),
+            Element::Paragraph { .. } => {
+                let mut objects = element.children(&self.arena).filter(|&child| {
+                    !matches!(&self[child], Element::Text { value } if value.trim().is_empty())
+                });
+                match (objects.next().map(|child| &self[child]), objects.next()) {
+                    (Some(Element::Link(link)), None) => ResultsValue::Link(link.path.to_string()),
+                    _ => ResultsValue::Raw(cell_text(self, element)),
+                }
+            }
+            _ => unreachable!(),
+        };
+        Some(value)
+    }
+
+    /// Removes the results of source block or babel call `node`, returns
+    /// `false` if it has none.
+    ///
+    /// The `#+RESULTS:` keyword is removed along with the results element
+    /// of `Org::results_content`, so nothing else after it is removed. The
+    /// element before them keeps its blank lines, or the ones after the
+    /// results if there are more.
+    ///
+    /// ```rust
+    /// use orgize::Org;
+    ///
+    /// let mut org = Org::parse(
+    ///     "#+BEGIN_SRC sh\necho 42\n#+END_SRC\n\n#+RESULTS:\n: 42\n\ntext\n",
+    /// );
+    /// let block = org.document().section_node().unwrap();
+    /// let block = org.arena()[block].first_child().unwrap();
+    ///
+    /// assert!(org.remove_results(block));
+    /// assert!(!org.remove_results(block));
+    ///
+    /// let mut writer = Vec::new();
+    /// org.write_org(&mut writer).unwrap();
+    /// assert_eq!(
+    ///     String::from_utf8(writer).unwrap(),
+    ///     "#+BEGIN_SRC sh\necho 42\n#+END_SRC\n\ntext\n"
+    /// );
+    /// ```
+    pub fn remove_results(&mut self, node: NodeId) -> bool {
+        let (results, element) = match self.results_nodes(node) {
+            Some(nodes) => nodes,
+            None => return false,
+        };
+        let previous = results.preceding_siblings(&self.arena).nth(1);
+
+        let mut post_blank = 0;
+        for removed in std::iter::once(results).chain(element.filter(|&e| e != results)) {
+            post_blank = self[removed].post_blank();
+            removed.detach(&mut self.arena);
+        }
+        if let Some(previous) = previous {
+            let blank_lines = self[previous].post_blank().max(post_blank);
+            self[previous].set_post_blank(blank_lines);
+        }
+
+        self.tree_changed();
+
+        true
+    }
+
+    // `#+RESULTS:` keyword or drawer of `node`, and the results element
+    fn results_nodes(&self, node: NodeId) -> Option<(NodeId, Option<NodeId>)> {
+        let results = self.results(node)?;
+        let element = match &self[results] {
+            Element::Keyword(Keyword { post_blank: 0, .. }) => results
+                .following_siblings(&self.arena)
+                .nth(1)
+                .filter(|&next| match &self[next] {
+                    Element::FixedWidth(_)
+                    | Element::ExampleBlock(_)
+                    | Element::Drawer(_)
+                    | Element::Paragraph { .. } => true,
+                    Element::Table(table) => matches!(table, Table::Org { .. }),
+                    _ => false,
+                }),
+            // a drawer without keyword is both
+            Element::Drawer(_) => Some(results),
+            _ => None,
+        };
+        Some((results, element))
+    }
+}
@@ -0,0 +1,296 @@
+use indextree::NodeId;
+use std::collections::HashMap;
+use std::ops::Range;
+
+use crate::{elements::Element, Org};
+
+/// Collects byte spans of nodes while parsing
+pub(crate) struct Spans {
+    base: usize,
+    len: usize,
+    enabled: bool,
+    spans: HashMap<NodeId, (usize, usize)>,
+}
+
+impl Spans {
+    /// Creates a collector, offsets are relative to the start of `text`.
+    pub fn new(text: &str) -> Spans {
+        Spans {
+            base: text.as_ptr() as usize,
+            len: text.len(),
+            enabled: true,
+            spans: HashMap::new(),
+        }
+    }
+
+    /// Creates a collector which ignores everything.
+    pub fn disabled() -> Spans {
+        Spans {
+            base: 0,
+            len: 0,
+            enabled: false,
+            spans: HashMap::new(),
+        }
+    }
+
+    // returns `None` if `input` isn't a slice of the source text
+    fn offset(&self, input: &str) -> Option<usize> {
+        let offset = (input.as_ptr() as usize).checked_sub(self.base)?;
+        (self.enabled && offset + input.len() <= self.len).then_some(offset)
+    }
+
+    /// Records `input` as the span of `node`, unless it has one already.
+    pub fn insert(&mut self, node: NodeId, input: &str) {
+        if let Some(start) = self.offset(input) {
+            self.spans
+                .entry(node)
+                .or_insert((start, start + input.len()));
+        }
+    }
+
+    /// Records the text between `input` and `tail` as the span of `node`,
+    /// unless it has one already.
+    pub fn insert_until(&mut self, node: NodeId, input: &str, tail: &str) {
+        let start = match self.offset(input) {
+            Some(start) => start,
+            None => return,
+        };
+        // parsers may return an empty string literal if input is consumed
+        let end = match self.offset(tail) {
+            _ if tail.is_empty() => start + input.len(),
+            Some(end) => end,
+            None => return,
+        };
+        self.spans.entry(node).or_insert((start, end));
+    }
+
+    pub fn into_spans(self) -> HashMap<NodeId, (usize, usize)> {
+        self.spans
+    }
+}
+
+/// Spans of nodes in the tree sorted by start, used by `Org::element_at` and
+/// friends
+pub(crate) struct SpanTable {
+    // (start, end, node), sorted by start, then by end descending, then by
+    // node, so that parents come before their children
+    spans: Vec<(usize, usize, NodeId)>,
+}
+
+impl SpanTable {
+    // detached nodes are left out
+    fn new(org: &Org) -> SpanTable {
+        let mut spans: Vec<_> = org
+            .root
+            .descendants(&org.arena)
+            .filter_map(|node| {
+                let &(start, end) = org.spans.get(&node)?;
+                Some((start, end, node))
+            })
+            .collect();
+        spans.sort_by(|a, b| a.0.cmp(&b.0).then(b.1.cmp(&a.1)).then(a.2.cmp(&b.2)));
+        SpanTable { spans }
+    }
+}
+
+impl Org<'_> {
+    /// Returns the byte span of `node` in the parsed text.
+    ///
+    /// Spans always refer to the originally parsed text, they aren't updated
+    /// when the tree is changed: it's `None` if `node` was created or
+    /// reparsed after parsing, or was removed. The
+    /// span of a container element covers its contents, but not always its
+    /// markup, e.g. the span of a list item starts after its bullet.
+    pub fn span(&self, node: NodeId) -> Option<Range<usize>> {
+        self.spans.get(&node).map(|&(start, end)| start..end)
+    }
+
+    /// Returns the source text of `node`, i.e. the slice of the parsed text
+    /// given by `Org::span`, or `None` if `node` has no span.
+    ///
+    /// Sources of blocks, drawers, lists, tables and other elements include
+    /// their markup, e.g. begin and end lines, and blank lines after them,
+    /// i.e. their `post_blank`. The source of a headline is its subtree,
+    /// including blank lines before the next headline. Paragraphs don't
+    /// include blank lines after them, list items start after their bullet,
+    /// and table rows have no source.
+    ///
+    /// ```rust
+    /// use orgize::Org;
+    ///
+    /// let org = Org::parse("#+BEGIN_EXAMPLE\nx\n#+END_EXAMPLE\n\ntext\n");
+    /// // document, section, then the block
+    /// let block = org.events().find(|event| event.depth == 2).unwrap().node;
+    ///
+    /// assert_eq!(
+    ///     org.raw_source(block),
+    ///     Some("#+BEGIN_EXAMPLE\nx\n#+END_EXAMPLE\n\n")
+    /// );
+    /// ```
+    pub fn raw_source(&self, node: NodeId) -> Option<&str> {
+        let (start, end) = *self.spans.get(&node)?;
+        self.source.get(start..end)
+    }
+
+    /// Returns the deepest node whose span contains `offset`.
+    fn node_at(&self, offset: usize) -> Option<NodeId> {
+        let table = self.span_table.get_or_init(|| SpanTable::new(self));
+
+        // the last span starting before offset, then walks up to the first
+        // ancestor containing offset
+        let i = table
+            .spans
+            .partition_point(|&(start, _, _)| start <= offset);
+        let (_, _, node) = *table.spans.get(i.checked_sub(1)?)?;
+
+        node.ancestors(&self.arena).find(|node| {
+            self.spans
+                .get(node)
+                .is_some_and(|&(start, end)| start <= offset && offset < end)
+        })
+    }
+
+    /// Returns the innermost headline containing byte `offset`.
+    ///
+    /// ```rust
+    /// use orgize::{Element, Org};
+    ///
+    /// let org = Org::parse("* a\n** b\ntext\n* c\n");
+    ///
+    /// let headline = org.headline_at(10).unwrap();
+    /// assert!(matches!(org[headline], Element::Headline { level: 2 }));
+    /// assert!(org.headline_at(0).is_some());
+    /// ```
+    pub fn headline_at(&self, offset: usize) -> Option<NodeId> {
+        self.node_at(offset)?
+            .ancestors(&self.arena)
+            .find(|&node| matches!(self[node], Element::Headline { .. }))
+    }
+
+    /// Returns the deepest element whose span contains byte `offset`,
+    /// objects like text and links are skipped.
+    pub fn element_at(&self, offset: usize) -> Option<NodeId> {
+        self.node_at(offset)?
+            .ancestors(&self.arena)
+            .find(|&node| !self[node].is_object())
+    }
+
+    /// Returns the deepest object whose span contains byte `offset`, `None`
+    /// if `offset` isn't inside an object.
+    pub fn object_at(&self, offset: usize) -> Option<NodeId> {
+        self.node_at(offset).filter(|&node| self[node].is_object())
+    }
+}
+
+/// Converts between byte offsets and line and column pairs
+///
+/// Lines and columns start from 0. Columns are counted in bytes by
+/// `line_col` and `offset`, and in UTF-16 code units by `line_col_utf16`
+/// and `offset_utf16`, like LSP positions.
+///
+/// ```rust
+/// use orgize::LineIndex;
+///
+/// let index = LineIndex::new("* 标题\ntext\n");
+///
+/// assert_eq!(index.line_col(9), Some((1, 0)));
+/// assert_eq!(index.line_col_utf16(8), Some((0, 4)));
+/// assert_eq!(index.offset(1, 2), Some(11));
+/// assert_eq!(index.offset_utf16(0, 3), Some(5));
+/// ```
+#[derive(Debug, Clone)]
+pub struct LineIndex<'a> {
+    text: &'a str,
+    // offset of the first byte of each line
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(text: &'a str) -> LineIndex<'a> {
+        let line_starts = std::iter::once(0)
+            .chain(memchr::memchr_iter(b'\n', text.as_bytes()).map(|i| i + 1))
+            .collect();
+        LineIndex { text, line_starts }
+    }
+
+    /// Returns the line and byte column of `offset`, `None` if `offset` is
+    /// beyond the end of text.
+    pub fn line_col(&self, offset: usize) -> Option<(usize, usize)> {
+        if offset > self.text.len() {
+            return None;
+        }
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        Some((line, offset - self.line_starts[line]))
+    }
+
+    /// Returns the line and UTF-16 column of `offset`, `None` if `offset`
+    /// is beyond the end of text or not at a char boundary.
+    pub fn line_col_utf16(&self, offset: usize) -> Option<(usize, usize)> {
+        let (line, _) = self.line_col(offset)?;
+        let column = self
+            .text
+            .get(self.line_starts[line]..offset)?
+            .encode_utf16()
+            .count();
+        Some((line, column))
+    }
+
+    /// Returns the byte offset of `line` and byte `column`, `None` if it's
+    /// beyond the end of line.
+    pub fn offset(&self, line: usize, column: usize) -> Option<usize> {
+        let range = self.line_range(line)?;
+        if column > range.len() {
+            return None;
+        }
+        Some(range.start + column)
+    }
+
+    /// Returns the byte offset of `line` and UTF-16 `column`, `None` if it's
+    /// beyond the end of line.
+    pub fn offset_utf16(&self, line: usize, column: usize) -> Option<usize> {
+        let range = self.line_range(line)?;
+        let mut units = 0;
+        for (i, c) in self.text[range.clone()].char_indices() {
+            if units >= column {
+                return (units == column).then_some(range.start + i);
+            }
+            units += c.len_utf16();
+        }
+        (units == column).then_some(range.end)
+    }
+
+    // byte range of line, without the trailing newline
+    fn line_range(&self, line: usize) -> Option<Range<usize>> {
+        let start = *self.line_starts.get(line)?;
+        let end = self
+            .line_starts
+            .get(line + 1)
+            .map_or(self.text.len(), |next| next - 1);
+        Some(start..end)
+    }
+}
+
+#[test]
+fn line_index() {
+    let index = LineIndex::new("ab\n\nc");
+    assert_eq!(index.line_col(0), Some((0, 0)));
+    assert_eq!(index.line_col(2), Some((0, 2)));
+    assert_eq!(index.line_col(3), Some((1, 0)));
+    assert_eq!(index.line_col(4), Some((2, 0)));
+    assert_eq!(index.line_col(5), Some((2, 1)));
+    assert_eq!(index.line_col(6), None);
+
+    assert_eq!(index.offset(0, 2), Some(2));
+    assert_eq!(index.offset(0, 3), None);
+    assert_eq!(index.offset(1, 0), Some(3));
+    assert_eq!(index.offset(2, 1), Some(5));
+    assert_eq!(index.offset(3, 0), None);
+
+    let index = LineIndex::new("a😀b\n");
+    assert_eq!(index.line_col_utf16(5), Some((0, 3)));
+    assert_eq!(index.line_col_utf16(2), None);
+    assert_eq!(index.offset_utf16(0, 3), Some(5));
+    assert_eq!(index.offset_utf16(0, 2), None);
+    assert_eq!(index.offset_utf16(0, 4), Some(6));
+    assert_eq!(index.offset_utf16(0, 5), None);
+}
@@ -0,0 +1,77 @@
+use std::collections::BTreeMap;
+
+use crate::{config::keyword_name, elements::Element, Org};
+
+/// Outline statistics of a document, see `Org::stats`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct DocumentStats {
+    /// Number of headlines of each level
+    pub headlines: BTreeMap<usize, usize>,
+    /// Number of headlines with each todo keyword, including keywords that
+    /// are never used
+    pub todo_keywords: BTreeMap<String, usize>,
+    /// Number of headlines with a done keyword
+    pub done: usize,
+    /// Number of headlines with a todo keyword that isn't a done keyword
+    pub not_done: usize,
+    /// Number of elements of each type, keyed by `Element::name`
+    pub elements: BTreeMap<&'static str, usize>,
+    /// Number of nodes in the arena, including detached ones
+    pub arena_size: usize,
+}
+
+impl Org<'_> {
+    /// Collects outline statistics in one traversal.
+    ///
+    /// Todo keywords come from the configuration this `Org` was parsed with,
+    /// see `Org::config`.
+    ///
+    /// ```rust
+    /// use orgize::Org;
+    ///
+    /// let org = Org::parse("* TODO a\n** DONE b\n** c\ntext\n");
+    /// let stats = org.stats();
+    ///
+    /// assert_eq!(stats.headlines[&1], 1);
+    /// assert_eq!(stats.headlines[&2], 2);
+    /// assert_eq!(stats.todo_keywords["TODO"], 1);
+    /// assert_eq!((stats.done, stats.not_done), (1, 1));
+    /// assert_eq!(stats.elements["paragraph"], 1);
+    /// ```
+    pub fn stats(&self) -> DocumentStats {
+        let config = self.config();
+        let mut stats = DocumentStats {
+            todo_keywords: config
+                .todo_keywords
+                .0
+                .iter()
+                .chain(config.todo_keywords.1.iter())
+                .map(|s| (keyword_name(s).to_string(), 0))
+                .collect(),
+            arena_size: self.arena.len(),
+            ..Default::default()
+        };
+
+        for node in self.root.descendants(&self.arena) {
+            let element = &self[node];
+            *stats.elements.entry(element.name()).or_insert(0) += 1;
+
+            match element {
+                Element::Headline { level } => *stats.headlines.entry(*level).or_insert(0) += 1,
+                Element::Title(title) => {
+                    if let Some(keyword) = &title.keyword {
+                        *stats.todo_keywords.entry(keyword.to_string()).or_insert(0) += 1;
+                        if config.is_done_keyword(keyword) {
+                            stats.done += 1;
+                        } else {
+                            stats.not_done += 1;
+                        }
+                    }
+                }
+                _ => (),
+            }
+        }
+
+        stats
+    }
+}
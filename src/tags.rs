@@ -0,0 +1,280 @@
+use std::fmt;
+
+use crate::Org;
+
+/// Tags defined by `#+TAGS:` keywords, see `Org::tag_definitions`
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TagDefinitions {
+    /// Tags outside of any group, e.g. `laptop(l)` of
+    /// `#+TAGS: { @work @home } laptop(l)`
+    pub tags: Vec<TagDefinition>,
+    /// Groups, in order of appearance
+    pub groups: Vec<TagGroup>,
+}
+
+/// Tag and its fast selection key, e.g. `@work(w)`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagDefinition {
+    /// Tag name, e.g. `@work`
+    pub name: String,
+    /// Fast selection key, e.g. `w`
+    pub key: Option<char>,
+}
+
+/// Group of tags
+///
+/// + `{ @work @home }` is a group of mutually exclusive tags.
+/// + `{ Context : @work @home }` is the same, with a group tag.
+/// + `[ Project : ProjA ProjB ]` is a group tag, whose members aren't
+///   exclusive. Members can be group tags of other groups, which makes a
+///   tag hierarchy.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagGroup {
+    /// Group tag, the tag before `:`
+    pub tag: Option<TagDefinition>,
+    /// Member tags
+    pub members: Vec<TagDefinition>,
+    /// `true` if the group uses `{ }`, i.e. at most one of its members can
+    /// be applied to a headline
+    pub exclusive: bool,
+}
+
+/// Tags of an exclusive group applied to the same headline, returned by
+/// `Headline::set_tags`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TagConflict {
+    /// Group tag, if the group has one
+    pub group: Option<String>,
+    /// Applied tags of the group, in the order they're given
+    pub tags: Vec<String>,
+}
+
+impl fmt::Display for TagConflict {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "mutually exclusive tags")?;
+        if let Some(group) = &self.group {
+            write!(f, " of group `{}`", group)?;
+        }
+        write!(f, ": {}", self.tags.join(", "))
+    }
+}
+
+impl TagDefinitions {
+    /// Parses the value of a `#+TAGS:` keyword, and appends its tags and
+    /// groups.
+    ///
+    /// Line breaks of the fast selection interface, i.e. `\n`, are ignored.
+    pub fn push_str(&mut self, input: &str) {
+        let tokens = input
+            .split(|c: char| c.is_whitespace())
+            .flat_map(split_braces)
+            .filter(|token| !token.is_empty() && *token != "\\n");
+
+        let mut group: Option<TagGroup> = None;
+        for token in tokens {
+            match (token, &mut group) {
+                ("{", None) | ("[", None) => {
+                    group = Some(TagGroup {
+                        tag: None,
+                        members: Vec::new(),
+                        exclusive: token == "{",
+                    });
+                }
+                ("}", Some(_)) | ("]", Some(_)) => {
+                    self.groups.extend(group.take());
+                }
+                // the only tag before `:` is the group tag
+                (":", Some(group)) => {
+                    if group.tag.is_none() && group.members.len() == 1 {
+                        group.tag = group.members.pop();
+                    }
+                }
+                (_, Some(group)) => group.members.extend(TagDefinition::parse(token)),
+                (_, None) => self.tags.extend(TagDefinition::parse(token)),
+            }
+        }
+        // an unclosed group ends with the keyword
+        self.groups.extend(group);
+    }
+
+    /// Returns `true` if `name` is defined, in or out of a group.
+    pub fn contains(&self, name: &str) -> bool {
+        self.tags
+            .iter()
+            .chain(
+                self.groups
+                    .iter()
+                    .flat_map(|group| group.tag.iter().chain(group.members.iter())),
+            )
+            .any(|tag| tag.name == name)
+    }
+
+    /// Returns members of group tag `group`, including members of its member
+    /// groups, without duplicates.
+    pub fn members(&self, group: &str) -> Vec<&str> {
+        let mut members = Vec::new();
+        self.collect_members(group, &mut members);
+        // the group itself is found again in cyclic hierarchies
+        members.retain(|member| *member != group);
+        members
+    }
+
+    fn collect_members<'a>(&'a self, group: &str, members: &mut Vec<&'a str>) {
+        for tag in self
+            .groups
+            .iter()
+            .filter(|g| g.tag.as_ref().is_some_and(|tag| tag.name == group))
+            .flat_map(|g| g.members.iter())
+        {
+            // cycles are stopped by the members found so far
+            if !members.contains(&tag.name.as_str()) {
+                members.push(&tag.name);
+                self.collect_members(&tag.name, members);
+            }
+        }
+    }
+
+    /// Returns conflicts of exclusive groups in `tags`.
+    pub fn conflicts<S: AsRef<str>>(&self, tags: &[S]) -> Vec<TagConflict> {
+        self.groups
+            .iter()
+            .filter(|group| group.exclusive)
+            .filter_map(|group| {
+                let applied: Vec<String> = tags
+                    .iter()
+                    .map(AsRef::as_ref)
+                    .filter(|tag| group.members.iter().any(|member| member.name == *tag))
+                    .map(String::from)
+                    .collect();
+                if applied.len() > 1 {
+                    Some(TagConflict {
+                        group: group.tag.as_ref().map(|tag| tag.name.clone()),
+                        tags: applied,
+                    })
+                } else {
+                    None
+                }
+            })
+            .collect()
+    }
+}
+
+impl TagDefinition {
+    // parses `name` or `name(k)`
+    fn parse(token: &str) -> Option<TagDefinition> {
+        let (name, key) = match token.strip_suffix(')').and_then(|t| t.split_once('(')) {
+            Some((name, key)) => {
+                let mut chars = key.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(key), None) => (name, Some(key)),
+                    _ => return None,
+                }
+            }
+            None => (token, None),
+        };
+        if name.is_empty() || name.contains(['(', ')']) {
+            return None;
+        }
+        Some(TagDefinition {
+            name: name.to_string(),
+            key,
+        })
+    }
+}
+
+// splits braces, brackets and colons from tags, e.g. `{@work` to `{` and
+// `@work`, tags can't contain colons
+fn split_braces(word: &str) -> impl Iterator<Item = &str> {
+    let mut rest = word;
+    std::iter::from_fn(move || {
+        if rest.is_empty() {
+            return None;
+        }
+        let end = match rest.find(['{', '}', '[', ']', ':']) {
+            Some(0) => 1,
+            Some(i) => i,
+            None => rest.len(),
+        };
+        let (token, tail) = rest.split_at(end);
+        rest = tail;
+        Some(token)
+    })
+}
+
+impl Org<'_> {
+    /// Returns tags defined by all `#+TAGS:` keywords.
+    ///
+    /// ```rust
+    /// use orgize::Org;
+    ///
+    /// let org = Org::parse("#+TAGS: { @work(w) @home(h) } laptop(l)\n");
+    /// let definitions = org.tag_definitions();
+    ///
+    /// assert_eq!(definitions.tags[0].name, "laptop");
+    /// assert_eq!(definitions.tags[0].key, Some('l'));
+    /// assert!(definitions.groups[0].exclusive);
+    /// assert_eq!(definitions.groups[0].members[1].name, "@home");
+    /// ```
+    pub fn tag_definitions(&self) -> TagDefinitions {
+        let mut definitions = TagDefinitions::default();
+        for keyword in self.keywords() {
+            if keyword.key.eq_ignore_ascii_case("TAGS") {
+                definitions.push_str(&keyword.value);
+            }
+        }
+        definitions
+    }
+
+    /// Returns members of group tag `group` defined by `#+TAGS:` keywords,
+    /// including members of its member groups, see
+    /// `TagDefinitions::members`.
+    ///
+    /// ```rust
+    /// use orgize::Org;
+    ///
+    /// let org = Org::parse(
+    ///     "#+TAGS: [ Project : ProjA ProjB ]\n\
+    ///      #+TAGS: [ ProjA : ProjA1 ]\n",
+    /// );
+    ///
+    /// assert_eq!(org.tags_in_group("Project"), ["ProjA", "ProjA1", "ProjB"]);
+    /// assert!(org.tags_in_group("ProjB").is_empty());
+    /// ```
+    pub fn tags_in_group(&self, group: &str) -> Vec<String> {
+        self.tag_definitions()
+            .members(group)
+            .into_iter()
+            .map(String::from)
+            .collect()
+    }
+}
+
+#[test]
+fn parse() {
+    let mut definitions = TagDefinitions::default();
+    definitions.push_str("{ Context : @work(w) @home(h) } laptop(l) \\n [Project: a b] c(bad)");
+
+    assert_eq!(
+        definitions.tags,
+        [TagDefinition {
+            name: "laptop".into(),
+            key: Some('l')
+        }]
+    );
+    assert_eq!(definitions.groups.len(), 2);
+    assert_eq!(
+        definitions.groups[0].tag,
+        Some(TagDefinition {
+            name: "Context".into(),
+            key: None
+        })
+    );
+    assert_eq!(definitions.groups[0].members.len(), 2);
+    assert_eq!(definitions.groups[0].members[0].key, Some('w'));
+    assert!(definitions.groups[0].exclusive);
+
+    assert_eq!(definitions.groups[1].tag.as_ref().unwrap().name, "Project");
+    assert!(!definitions.groups[1].exclusive);
+    assert!(definitions.contains("@home"));
+    assert!(!definitions.contains("c"));
+}
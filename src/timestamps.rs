@@ -0,0 +1,219 @@
+use indextree::{NodeEdge, NodeId};
+use std::borrow::Cow;
+use std::cmp::Ordering;
+
+use crate::{
+    elements::{Datetime, Element, Timestamp},
+    Headline, Org,
+};
+
+/// Timestamp found by `Org::timestamps`
+#[derive(Debug, Clone)]
+pub struct TimestampEntry<'a, 'b> {
+    /// The timestamp, owned if it's constructed from a clock
+    pub timestamp: Cow<'b, Timestamp<'a>>,
+    /// Where the timestamp is found
+    pub context: TimestampContext,
+}
+
+/// Where a timestamp is found
+#[derive(Debug, Clone, Copy)]
+pub enum TimestampContext {
+    /// Planning line of a headline
+    Planning {
+        kind: PlanningKind,
+        headline: Headline,
+    },
+    /// Clock line
+    Clock {
+        /// The clock element
+        node: NodeId,
+        /// Headline containing the clock, `None` if it's in the zeroth section
+        headline: Option<Headline>,
+    },
+    /// Timestamp object in a title, paragraph, table and so on
+    Inline {
+        /// The timestamp object
+        node: NodeId,
+        /// The element containing the timestamp object, e.g. a paragraph or
+        /// a title
+        element: NodeId,
+        /// Headline containing the timestamp, `None` if it's in the zeroth
+        /// section
+        headline: Option<Headline>,
+    },
+}
+
+impl TimestampContext {
+    /// Returns the headline containing the timestamp.
+    pub fn headline(&self) -> Option<Headline> {
+        match *self {
+            TimestampContext::Planning { headline, .. } => Some(headline),
+            TimestampContext::Clock { headline, .. }
+            | TimestampContext::Inline { headline, .. } => headline,
+        }
+    }
+}
+
+/// Planning keyword
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PlanningKind {
+    /// `DEADLINE:`
+    Deadline,
+    /// `SCHEDULED:`
+    Scheduled,
+    /// `CLOSED:`
+    Closed,
+}
+
+impl TimestampEntry<'_, '_> {
+    /// Returns `true` if the timestamp is active, diary timestamps are
+    /// always active.
+    pub fn is_active(&self) -> bool {
+        matches!(
+            *self.timestamp,
+            Timestamp::Active { .. } | Timestamp::ActiveRange { .. } | Timestamp::Diary { .. }
+        )
+    }
+
+    /// Returns `true` if the timestamp is an active timestamp object, which
+    /// Emacs lists in the agenda as a plain timestamp.
+    ///
+    /// A `DEADLINE:` or `SCHEDULED:` that isn't in the planning line, e.g.
+    /// at the end of a title, is plain too.
+    ///
+    /// ```rust
+    /// use orgize::Org;
+    ///
+    /// let org = Org::parse(
+    ///     "* TODO Pay rent DEADLINE: <2024-03-01 Fri>\nSCHEDULED: <2024-02-28 Wed>\n",
+    /// );
+    /// let plain: Vec<_> = org.timestamps().map(|entry| entry.is_plain()).collect();
+    ///
+    /// assert_eq!(plain, [true, false]);
+    /// ```
+    pub fn is_plain(&self) -> bool {
+        matches!(self.context, TimestampContext::Inline { .. }) && self.is_active()
+    }
+}
+
+impl<'a> Org<'a> {
+    /// Returns an iterator of all timestamps in this document, in document
+    /// order.
+    ///
+    /// Timestamps in planning lines, clocks and timestamp objects are
+    /// included, so are timestamps in titles. Timestamps of one planning
+    /// line are yielded in the order of deadline, scheduled and closed,
+    /// after timestamps of the title.
+    ///
+    /// ```rust
+    /// use orgize::{Org, PlanningKind, TimestampContext};
+    ///
+    /// let org = Org::parse("* TODO a\nSCHEDULED: <2024-03-05 Tue>\n[2024-03-01 Fri]\n");
+    ///
+    /// let timestamps: Vec<_> = org.timestamps().collect();
+    ///
+    /// assert!(matches!(
+    ///     timestamps[0].context,
+    ///     TimestampContext::Planning { kind: PlanningKind::Scheduled, .. }
+    /// ));
+    /// assert!(timestamps[0].is_active());
+    /// assert!(matches!(timestamps[1].context, TimestampContext::Inline { .. }));
+    /// assert!(!timestamps[1].is_active());
+    /// ```
+    pub fn timestamps<'b>(&'b self) -> impl Iterator<Item = TimestampEntry<'a, 'b>> + 'b {
+        self.root
+            .traverse(&self.arena)
+            .flat_map(move |edge| self.timestamps_at(edge))
+    }
+
+    /// Returns all timestamps in this document, sorted by their start.
+    ///
+    /// Timestamps without time come before timestamps with time on the same
+    /// day, diary timestamps come last. Timestamps with the same start are
+    /// kept in document order.
+    pub fn timestamps_sorted<'b>(&'b self) -> Vec<TimestampEntry<'a, 'b>> {
+        let mut timestamps: Vec<_> = self.timestamps().collect();
+        timestamps.sort_by(|a, b| cmp_start(&a.timestamp, &b.timestamp));
+        timestamps
+    }
+
+    fn timestamps_at<'b>(&'b self, edge: NodeEdge) -> Vec<TimestampEntry<'a, 'b>> {
+        match edge {
+            NodeEdge::Start(node) => match &self[node] {
+                Element::Timestamp(timestamp) => vec![TimestampEntry {
+                    timestamp: Cow::Borrowed(timestamp),
+                    context: TimestampContext::Inline {
+                        node,
+                        element: node
+                            .ancestors(&self.arena)
+                            .find(|&n| !self[n].is_object())
+                            .unwrap_or(node),
+                        headline: self.headline_of(node),
+                    },
+                }],
+                Element::Clock(clock) => vec![TimestampEntry {
+                    timestamp: Cow::Owned(clock.value().into_owned()),
+                    context: TimestampContext::Clock {
+                        node,
+                        headline: self.headline_of(node),
+                    },
+                }],
+                _ => Vec::new(),
+            },
+            // planning line comes after title contents
+            NodeEdge::End(node) => match &self[node] {
+                Element::Title(title) => {
+                    let (planning, headline) = match (&title.planning, self.headline_of(node)) {
+                        (Some(planning), Some(headline)) => (planning, headline),
+                        _ => return Vec::new(),
+                    };
+                    [
+                        (PlanningKind::Deadline, &planning.deadline),
+                        (PlanningKind::Scheduled, &planning.scheduled),
+                        (PlanningKind::Closed, &planning.closed),
+                    ]
+                    .iter()
+                    .filter_map(|(kind, timestamp)| {
+                        Some(TimestampEntry {
+                            timestamp: Cow::Borrowed(timestamp.as_ref()?),
+                            context: TimestampContext::Planning {
+                                kind: *kind,
+                                headline,
+                            },
+                        })
+                    })
+                    .collect()
+                }
+                _ => Vec::new(),
+            },
+        }
+    }
+
+    fn headline_of(&self, node: NodeId) -> Option<Headline> {
+        node.ancestors(&self.arena).find_map(|n| match self[n] {
+            Element::Headline { level } => Some(Headline::from_node(n, level, self)),
+            _ => None,
+        })
+    }
+}
+
+pub(crate) fn cmp_start(a: &Timestamp, b: &Timestamp) -> Ordering {
+    fn start<'c>(timestamp: &'c Timestamp) -> Option<&'c Datetime<'c>> {
+        match timestamp {
+            Timestamp::Active { start, .. }
+            | Timestamp::Inactive { start, .. }
+            | Timestamp::ActiveRange { start, .. }
+            | Timestamp::InactiveRange { start, .. } => Some(start),
+            Timestamp::Diary { .. } => None,
+        }
+    }
+
+    match (start(a), start(b)) {
+        (Some(a), Some(b)) => (a.year, a.month, a.day, a.hour, a.minute)
+            .cmp(&(b.year, b.month, b.day, b.hour, b.minute)),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
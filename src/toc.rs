@@ -0,0 +1,104 @@
+use std::borrow::Cow;
+
+use crate::{Headline, Org};
+
+/// Entry of the table of contents, see `Org::toc`
+#[derive(Debug, Clone)]
+pub struct TocEntry<'b> {
+    pub headline: Headline,
+    /// Headline level
+    pub level: usize,
+    /// Section number, e.g. `[1, 2]` for section 1.2, `None` if the headline
+    /// is unnumbered
+    pub number: Option<Vec<usize>>,
+    /// Text of this entry, the `:ALT_TITLE:` property or the raw title
+    pub title: Cow<'b, str>,
+}
+
+impl TocEntry<'_> {
+    /// Returns the section number joined by dots, e.g. `"1.2"`.
+    pub fn number_string(&self) -> Option<String> {
+        let number = self.number.as_ref()?;
+        Some(
+            number
+                .iter()
+                .map(ToString::to_string)
+                .collect::<Vec<_>>()
+                .join("."),
+        )
+    }
+}
+
+impl Org<'_> {
+    /// Returns the table of contents of this document, in document order.
+    ///
+    /// Headlines with `:UNNUMBERED:` property, or under such headlines, are
+    /// included without number and don't consume numbers. They are left out
+    /// if the property is `notoc`. `:ALT_TITLE:` property replaces the title
+    /// of an entry.
+    ///
+    /// ```rust
+    /// use orgize::Org;
+    ///
+    /// let org = Org::parse(
+    ///     "* Intro\n\
+    ///      :PROPERTIES:\n\
+    ///      :UNNUMBERED: t\n\
+    ///      :END:\n\
+    ///      * Usage\n\
+    ///      ** A very long title\n\
+    ///      :PROPERTIES:\n\
+    ///      :ALT_TITLE: Short\n\
+    ///      :END:\n",
+    /// );
+    ///
+    /// let toc: Vec<_> = org
+    ///     .toc()
+    ///     .iter()
+    ///     .map(|entry| (entry.number_string(), entry.title.to_string()))
+    ///     .collect();
+    ///
+    /// assert_eq!(
+    ///     toc,
+    ///     vec![
+    ///         (None, "Intro".into()),
+    ///         (Some("1".into()), "Usage".into()),
+    ///         (Some("1.1".into()), "Short".into()),
+    ///     ]
+    /// );
+    /// ```
+    pub fn toc(&self) -> Vec<TocEntry<'_>> {
+        let mut counters: Vec<usize> = Vec::new();
+        let mut toc = Vec::new();
+
+        for headline in self.headlines() {
+            let level = headline.level();
+            let unnumbered = headline.unnumbered(self);
+
+            let number = if unnumbered.is_none() {
+                counters.resize(level, 0);
+                counters[level - 1] += 1;
+                Some(counters.clone())
+            } else {
+                None
+            };
+
+            if unnumbered.is_some_and(|value| value.eq_ignore_ascii_case("notoc")) {
+                continue;
+            }
+
+            let title = headline
+                .alt_title(self)
+                .unwrap_or(&headline.title(self).raw);
+
+            toc.push(TocEntry {
+                headline,
+                level,
+                number,
+                title: Cow::Borrowed(title),
+            });
+        }
+
+        toc
+    }
+}
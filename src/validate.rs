@@ -127,7 +127,7 @@ impl Org<'_> {
                         expect_element!(
                             child,
                             "TableCell::Header",
-                            Element::TableCell(TableCell::Header)
+                            Element::TableCell(TableCell::Header { .. })
                         );
                     }
                 }
@@ -136,7 +136,7 @@ impl Org<'_> {
                         expect_element!(
                             child,
                             "TableCell::Body",
-                            Element::TableCell(TableCell::Body)
+                            Element::TableCell(TableCell::Body { .. })
                         );
                     }
                 }
@@ -151,19 +151,19 @@ impl Org<'_> {
                 | Element::InlineCall(_)
                 | Element::Link(_)
                 | Element::Macros(_)
-                | Element::RadioTarget
                 | Element::Snippet(_)
                 | Element::Target(_)
                 | Element::Text { .. }
                 | Element::Timestamp(_)
                 | Element::Verbatim { .. }
-                | Element::FnDef(_)
                 | Element::Clock(_)
                 | Element::Comment { .. }
                 | Element::FixedWidth { .. }
                 | Element::Keyword(_)
                 | Element::Rule(_)
                 | Element::Cookie(_)
+                | Element::Custom(_)
+                | Element::Skipped(_)
                 | Element::TableRow(TableRow::BodyRule)
                 | Element::TableRow(TableRow::HeaderRule) => {
                     if node.first_child().is_some() {
@@ -180,11 +180,13 @@ impl Org<'_> {
                 | Element::Italic
                 | Element::Underline
                 | Element::Strike
-                | Element::DynBlock(_) => {
+                | Element::RadioTarget => {
                     expect_children!(node_id);
                 }
                 Element::ListItem(_)
+                | Element::FnDef(_)
                 | Element::Drawer(_)
+                | Element::DynBlock(_)
                 | Element::TableCell(_)
                 | Element::Table(_) => (),
             }
@@ -0,0 +1,74 @@
+#![cfg(feature = "tokio")]
+
+use std::{cell::Cell, sync::Arc};
+
+use orgize::Org;
+use pretty_assertions::assert_eq;
+use tokio::io::{duplex, AsyncReadExt};
+
+#[tokio::test]
+async fn streams_chunks() {
+    let text: String = (1..=50)
+        .map(|i| format!("* Headline {}\nParagraph *{}*.\n", i, i))
+        .collect();
+    let org = Org::parse(&text);
+
+    let mut expected = Vec::new();
+    org.write_html(&mut expected).unwrap();
+
+    // the writer waits whenever 64 bytes are left unread
+    let (writer, mut reader) = duplex(64);
+    let done = Cell::new(false);
+
+    let export = async {
+        org.write_html_async(writer).await.unwrap();
+        done.set(true);
+    };
+    let read = async {
+        let mut chunks = Vec::new();
+        let mut before_done = 0;
+        let mut buffer = [0; 32];
+        loop {
+            let n = reader.read(&mut buffer).await.unwrap();
+            if n == 0 {
+                break;
+            }
+            if !done.get() {
+                before_done += 1;
+            }
+            chunks.push(buffer[..n].to_vec());
+        }
+        (chunks, before_done)
+    };
+    let ((), (chunks, before_done)) = tokio::join!(export, read);
+
+    assert!(before_done > 10, "{} chunks before done", before_done);
+    assert!(chunks.iter().all(|chunk| chunk.len() <= 32));
+    assert_eq!(
+        String::from_utf8(chunks.concat()).unwrap(),
+        String::from_utf8(expected).unwrap()
+    );
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn spawned_export() {
+    let org = Arc::new(Org::parse_string(String::from(
+        "* Title\n*text*[fn:1]\n\n[fn:1] Note.\n",
+    )));
+
+    let mut expected = Vec::new();
+    org.write_html(&mut expected).unwrap();
+
+    let export = tokio::spawn({
+        let org = org.clone();
+        async move {
+            let mut writer = Vec::new();
+            org.write_html_async(&mut writer).await.unwrap();
+            writer
+        }
+    });
+    assert_eq!(
+        String::from_utf8(export.await.unwrap()).unwrap(),
+        String::from_utf8(expected).unwrap()
+    );
+}
@@ -0,0 +1,72 @@
+use orgize::{
+    elements::Element,
+    export::{Backend, DefaultHtmlHandler, HtmlConfig, HtmlHandler},
+    Org,
+};
+use pretty_assertions::assert_eq;
+use std::io::{Error, Write};
+
+const TEXT: &str = r#"a@@html:<br>@@@@latex:\\@@@@beamer:\pause@@@@html5:<wbr>@@b
+
+#+BEGIN_EXPORT html
+<hr>
+#+END_EXPORT
+
+#+BEGIN_EXPORT latex
+\newpage
+#+END_EXPORT
+"#;
+
+// minimal latex exporter which only writes text and raw contents
+struct LatexHandler(Backend);
+
+impl Default for LatexHandler {
+    fn default() -> Self {
+        LatexHandler(Backend::latex())
+    }
+}
+
+impl HtmlHandler<Error> for LatexHandler {
+    fn start<W: Write>(&mut self, mut w: W, element: &Element) -> Result<(), Error> {
+        if let Some(raw) = self.0.raw_contents(element) {
+            write!(w, "{}", raw)?;
+        } else if let Element::Text { value } = element {
+            write!(w, "{}", value)?;
+        }
+        Ok(())
+    }
+
+    fn end<W: Write>(&mut self, _: W, _: &Element) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+fn export<H: HtmlHandler<Error>>(mut handler: H) -> String {
+    let mut writer = Vec::new();
+    Org::parse(TEXT)
+        .write_html_custom(&mut writer, &mut handler)
+        .unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+#[test]
+fn routing() {
+    assert_eq!(
+        export(DefaultHtmlHandler::default()),
+        "<main><section><p>a<br>b</p><hr>\n</section></main>"
+    );
+
+    assert_eq!(export(LatexHandler::default()), "a\\\\\\pauseb\\newpage\n");
+}
+
+#[test]
+fn extended() {
+    let handler = DefaultHtmlHandler::new(HtmlConfig {
+        backend: Backend::html().with("html5"),
+        ..Default::default()
+    });
+    assert_eq!(
+        export(handler),
+        "<main><section><p>a<br><wbr>b</p><hr>\n</section></main>"
+    );
+}
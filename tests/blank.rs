@@ -1,4 +1,4 @@
-use orgize::Org;
+use orgize::{elements::Element, Org};
 
 const ORG_STR: &str = r#"
 
@@ -82,3 +82,74 @@ fn blank() {
 
     assert_eq!(String::from_utf8(writer).unwrap(), ORG_STR);
 }
+
+const ELEMENTS: &[&str] = &[
+    "paragraph\n",
+    "- list item\n",
+    "#+BEGIN_SRC rust\ncode\n#+END_SRC\n",
+    "#+BEGIN_QUOTE\nquote\n#+END_QUOTE\n",
+    "#+BEGIN: clocktable\ncontents\n#+END:\n",
+    "#+KEYWORD: value\n",
+    "#+CAPTION: caption\n",
+    "# comment\n",
+    ": fixed width\n",
+    ":DRAWER:\ncontents\n:END:\n",
+    "CLOCK: [2019-10-28 Mon 08:53]\n",
+    "-----\n",
+    "| table |\n",
+    "[fn:1] definition\n",
+    "+---+\n| a |\n+---+\n",
+    "* headline\n",
+];
+
+#[test]
+fn blank_lines_between_elements() {
+    for first in ELEMENTS {
+        for second in ELEMENTS {
+            for blank in 0..3 {
+                for text in &[
+                    format!("{}{}{}", first, "\n".repeat(blank), second),
+                    // inside a section
+                    format!("* h\n\n{}{}{}", first, "\n".repeat(blank), second),
+                ] {
+                    let mut writer = Vec::new();
+                    Org::parse(text).write_org(&mut writer).unwrap();
+
+                    assert_eq!(&String::from_utf8(writer).unwrap(), text);
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn paragraph_ends_at_element() {
+    for element in &ELEMENTS[1..ELEMENTS.len() - 1] {
+        let text = format!("paragraph\n{}", element);
+        let org = Org::parse(&text);
+
+        let document = org.document().section_node().unwrap();
+        let children: Vec<_> = document.children(org.arena()).collect();
+        assert_eq!(children.len(), 2, "{:?}", text);
+        assert!(
+            matches!(org[children[0]], Element::Paragraph { post_blank: 0 }),
+            "{:?}",
+            text
+        );
+        assert!(
+            !matches!(org[children[1]], Element::Paragraph { .. }),
+            "{:?}",
+            text
+        );
+    }
+}
+
+#[test]
+fn indented_footnote_definition() {
+    let org = Org::parse("paragraph\n  [fn:1] text\n");
+
+    let document = org.document().section_node().unwrap();
+    let children: Vec<_> = document.children(org.arena()).collect();
+    assert_eq!(children.len(), 1);
+    assert!(matches!(org[children[0]], Element::Paragraph { .. }));
+}
@@ -0,0 +1,98 @@
+use orgize::{export::DefaultOrgHandler, Org, ParseConfig, ParseError, Strictness};
+use pretty_assertions::assert_eq;
+
+const BOM: &str = "\u{feff}";
+
+fn html(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_html(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+fn org(org: &Org, keep_bom: bool) -> String {
+    let mut writer = Vec::new();
+    let mut handler = DefaultOrgHandler::default();
+    handler.keep_bom = keep_bom;
+    org.write_org_custom(&mut writer, &mut handler).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+fn warn() -> ParseConfig {
+    ParseConfig {
+        strictness: Strictness::Warn,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn headline_first() {
+    let text = "* TODO Title :tag:\ntext\n";
+    let with_bom = format!("{}{}", BOM, text);
+
+    let plain = Org::parse(text);
+    let org = Org::parse_custom(&with_bom, &warn());
+    assert!(org.has_bom());
+    assert!(!plain.has_bom());
+
+    let title = org.headlines().next().unwrap().title(&org);
+    assert_eq!(title.raw, "Title");
+    assert_eq!(title.keyword.as_deref(), Some("TODO"));
+    assert_eq!(html(&org), html(&plain));
+    assert_eq!(org.diagnostics(), []);
+
+    let org = Org::parse_string(with_bom.clone());
+    assert_eq!(org.headlines().count(), 1);
+    assert_eq!(html(&org), html(&plain));
+}
+
+#[test]
+fn keyword_first() {
+    let text = "#+TITLE: Plants\n#+STARTUP: odd\n* Cactus\n";
+    let with_bom = format!("{}{}", BOM, text);
+
+    let plain = Org::parse(text);
+    let org = Org::parse(&with_bom);
+    assert_eq!(org.metadata().title.as_deref(), Some("Plants"));
+    assert!(org.startup().odd_levels);
+    assert_eq!(html(&org), html(&plain));
+}
+
+#[test]
+fn org_export() {
+    let text = format!("{}\n* Title\n", BOM);
+    let parsed = Org::parse(&text);
+
+    assert_eq!(org(&parsed, false), "\n* Title\n");
+    assert_eq!(org(&parsed, true), text);
+    assert_eq!(org(&Org::parse("* Title\n"), true), "* Title\n");
+}
+
+#[test]
+fn zero_width_characters() {
+    let text = "\u{200b}* Not a headline\n\u{2060}#+TITLE: Not a keyword\n\u{200b}text\n";
+
+    let org = Org::parse_custom(text, &warn());
+    assert_eq!(org.headlines().count(), 0);
+    assert_eq!(
+        org.diagnostics(),
+        [
+            ParseError::UnicodeWhitespace {
+                character: '\u{200b}',
+                offset: 0,
+            },
+            ParseError::UnicodeWhitespace {
+                character: '\u{2060}',
+                offset: text.find('\u{2060}').unwrap(),
+            },
+        ]
+    );
+
+    assert_eq!(
+        Org::parse_custom("-\u{200b}item\n", &warn()).diagnostics(),
+        [ParseError::UnicodeWhitespace {
+            character: '\u{200b}',
+            offset: 1,
+        }]
+    );
+    assert_eq!(Org::parse("\u{200b}* Not a headline\n").diagnostics(), []);
+}
@@ -0,0 +1,77 @@
+use orgize::Org;
+use pretty_assertions::assert_eq;
+
+fn categories(text: &str) -> Vec<(String, String)> {
+    let org = Org::parse(text);
+    let categories: Vec<_> = org
+        .categories("file")
+        .into_iter()
+        .map(|(hdl, category)| (hdl.title(&org).raw.to_string(), category.into_owned()))
+        .collect();
+
+    // same as calling `Headline::category` on each headline
+    for ((_, category), hdl) in categories.iter().zip(org.headlines()) {
+        assert_eq!(hdl.category(&org, "file"), *category);
+    }
+
+    categories
+}
+
+fn pairs(pairs: &[(&str, &str)]) -> Vec<(String, String)> {
+    pairs
+        .iter()
+        .map(|(title, category)| (title.to_string(), category.to_string()))
+        .collect()
+}
+
+#[test]
+fn default() {
+    assert_eq!(
+        categories("* a\n** b\n"),
+        pairs(&[("a", "file"), ("b", "file")])
+    );
+}
+
+#[test]
+fn keyword() {
+    assert_eq!(
+        categories("#+CATEGORY: old\n#+CATEGORY: work\n* a\n** b\n"),
+        pairs(&[("a", "work"), ("b", "work")])
+    );
+
+    // keywords after the first headline are ignored
+    assert_eq!(
+        categories("* a\n#+CATEGORY: work\n"),
+        pairs(&[("a", "file")])
+    );
+}
+
+#[test]
+fn property() {
+    assert_eq!(
+        categories(
+            r#"#+CATEGORY: work
+* a
+:PROPERTIES:
+:CATEGORY: home
+:END:
+** b
+** c
+:PROPERTIES:
+:CATEGORY: garden
+:END:
+*** d
+** e
+* f
+"#
+        ),
+        pairs(&[
+            ("a", "home"),
+            ("b", "home"),
+            ("c", "garden"),
+            ("d", "garden"),
+            ("e", "home"),
+            ("f", "work"),
+        ])
+    );
+}
@@ -0,0 +1,94 @@
+use orgize::{
+    elements::{Checkbox, ListItem},
+    indextree::NodeId,
+    Element, Org,
+};
+use pretty_assertions::assert_eq;
+
+fn to_org(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+fn list_item(org: &Org, text: &str) -> NodeId {
+    org.arena()
+        .iter()
+        .filter(|node| matches!(node.get(), Element::ListItem(_)))
+        .filter_map(|node| org.arena().get_node_id(node))
+        .find(|item| {
+            let paragraph = item.children(org.arena()).next().unwrap();
+            paragraph
+                .descendants(org.arena())
+                .any(|node| matches!(&org[node], Element::Text { value } if value.trim() == text))
+        })
+        .unwrap()
+}
+
+#[test]
+fn nested_cookies() {
+    let mut org = Org::parse(
+        "- tasks [/]\n\
+         \x20 - [ ] write [%]\n\
+         \x20   - [X] draft\n\
+         \x20   - [ ] review\n\
+         \x20 - [X] read\n\
+         \x20 - note without checkbox\n",
+    );
+
+    ListItem::update_cookie(&mut org);
+    assert_eq!(
+        to_org(&org),
+        "- tasks [1/2]\n\
+         \x20 - [ ] write [50%]\n\
+         \x20   - [X] draft\n\
+         \x20   - [ ] review\n\
+         \x20 - [X] read\n\
+         \x20 - note without checkbox\n"
+    );
+
+    let review = list_item(&org, "review");
+    ListItem::set_checkbox(&mut org, review, Some(Checkbox::On));
+    ListItem::update_cookie(&mut org);
+
+    assert_eq!(
+        to_org(&org),
+        "- tasks [1/2]\n\
+         \x20 - [ ] write [100%]\n\
+         \x20   - [X] draft\n\
+         \x20   - [X] review\n\
+         \x20 - [X] read\n\
+         \x20 - note without checkbox\n"
+    );
+}
+
+#[test]
+fn cookie_outside_first_line() {
+    let mut org = Org::parse("- tasks\n  see [1/1]\n  - [ ] a\n");
+
+    ListItem::update_cookie(&mut org);
+    assert_eq!(to_org(&org), "- tasks\n  see [1/1]\n  - [ ] a\n");
+}
+
+#[test]
+fn separator_after_checkbox() {
+    let mut org = Org::parse("- [/]\n  - [X]\tdone\n  - [ ]  todo\n");
+
+    ListItem::update_cookie(&mut org);
+    assert_eq!(to_org(&org), "- [1/2]\n  - [X]\tdone\n  - [ ]  todo\n");
+}
+
+#[test]
+fn html() {
+    let org = Org::parse("- [X] done\n- [-] partial\n");
+
+    let mut writer = Vec::new();
+    org.write_html(&mut writer).unwrap();
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        "<main><section><ul>\
+         <li><code>[X]</code> <p>done</p></li>\
+         <li><code>[-]</code> <p>partial</p></li>\
+         </ul></section></main>"
+    );
+}
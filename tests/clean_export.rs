@@ -0,0 +1,72 @@
+use orgize::{export::DefaultOrgHandler, Org};
+use pretty_assertions::assert_eq;
+
+const TEXT: &str = r#"#+TITLE: Working file
+# a comment line
+* Published
+Text.
+#+BEGIN_COMMENT
+private
+#+END_COMMENT
+** COMMENT Draft
+Not ready.
+*** Nested
+* Internal :noexport:
+Secrets.
+** Child
+* Old :ARCHIVE:
+Archived.
+* Kept :public:
+# another comment
+| a | b |
+"#;
+
+fn org(handler: &mut DefaultOrgHandler) -> String {
+    let mut writer = Vec::new();
+    Org::parse(TEXT)
+        .write_org_custom(&mut writer, handler)
+        .unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+#[test]
+fn clean() {
+    let mut handler = DefaultOrgHandler::default();
+    handler.clean = true;
+    assert_eq!(
+        org(&mut handler),
+        r#"#+TITLE: Working file
+* Published
+Text.
+* Old :ARCHIVE:
+Archived.
+* Kept :public:
+| a | b |
+"#
+    );
+
+    let mut handler = DefaultOrgHandler::default();
+    handler.clean = true;
+    handler.exclude_archived = true;
+    handler.exclude_tags = Some(vec!["public".into()]);
+    assert_eq!(
+        org(&mut handler),
+        r#"#+TITLE: Working file
+* Published
+Text.
+* Internal :noexport:
+Secrets.
+** Child
+"#
+    );
+}
+
+#[test]
+fn fidelity() {
+    assert_eq!(org(&mut DefaultOrgHandler::default()), TEXT);
+
+    // options other than `clean` don't drop anything by themselves
+    let mut handler = DefaultOrgHandler::default();
+    handler.exclude_archived = true;
+    assert_eq!(org(&mut handler), TEXT);
+}
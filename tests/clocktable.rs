@@ -0,0 +1,211 @@
+use orgize::{
+    duration::OrgDuration, elements::Datetime, ClocktableError, ClocktableParams, Element, Org,
+};
+use pretty_assertions::assert_eq;
+use std::convert::TryFrom;
+
+const TEXT: &str = r#"#+BEGIN: clocktable :scope file :maxlevel 2
+#+CAPTION: Clock summary at [2024-03-08 Fri 10:00]
+| Headline     | Time      |      |
+|--------------+-----------+------|
+| *Total time* | *1d 3:15* |      |
+|--------------+-----------+------|
+| a            | 1d 2:30   |      |
+| \_  b        |           | 0:45 |
+#+END:
+
+* a
+:LOGBOOK:
+CLOCK: [2024-03-01 Fri 09:00]--[2024-03-02 Sat 10:30] => 25:30
+CLOCK: [2024-03-03 Sun 09:00]--[2024-03-03 Sun 10:00] =>  1:00
+:END:
+** b
+CLOCK: [2024-03-04 Mon 09:00]--[2024-03-04 Mon 09:45] =>  0:45
+"#;
+
+#[test]
+fn clocktable_total() {
+    let org = Org::parse(TEXT);
+
+    let block = org
+        .document()
+        .section_node()
+        .unwrap()
+        .children(org.arena())
+        .find(|&node| matches!(org[node], Element::DynBlock(_)))
+        .unwrap();
+    let total = org.clocktable_total(block).unwrap();
+    assert_eq!(total, "1d 3:15".parse().unwrap());
+    assert_eq!(total.to_string(), "27:15");
+
+    let clocks: OrgDuration = org
+        .arena()
+        .iter()
+        .filter_map(|node| match node.get() {
+            Element::Clock(clock) => clock.duration(),
+            _ => None,
+        })
+        .sum();
+    assert_eq!(clocks, total);
+
+    // clock durations are written back as they were read
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    let written = String::from_utf8(writer).unwrap();
+    assert!(written.contains("=> 25:30\n"));
+    assert!(written.contains("=> 0:45\n"));
+
+    // only clocktables have a total
+    assert_eq!(
+        org.clocktable_total(org.document().section_node().unwrap()),
+        None
+    );
+}
+
+const FIXTURE: &str = r#"#+BEGIN: clocktable :scope file :maxlevel 2
+old
+#+END:
+
+* Project :work:
+** Design
+:LOGBOOK:
+CLOCK: [2024-03-04 Mon 09:00]--[2024-03-04 Mon 11:00] =>  2:00
+CLOCK: [2024-02-28 Wed 23:00]--[2024-03-01 Fri 01:00] => 26:00
+:END:
+*** Sketches
+CLOCK: [2024-03-05 Tue 13:00]--[2024-03-05 Tue 13:20] =>  0:20
+** Meeting :meeting:
+CLOCK: [2024-03-06 Wed 10:00]--[2024-03-06 Wed 10:45] =>  0:45
+#+BEGIN: clocktable :scope subtree
+#+END:
+* Home
+CLOCK: [2024-03-08 Fri 20:00]--[2024-03-08 Fri 21:30] =>  1:30
+CLOCK: [2024-03-08 Fri 22:00]
+* Idle
+"#;
+
+fn generate(params: &str, now: Option<&str>, block: usize) -> String {
+    let mut org = Org::parse(FIXTURE);
+    let node = std::iter::once(org.document().section_node())
+        .chain(org.headlines().map(|headline| headline.section_node()))
+        .flatten()
+        .flat_map(|section| section.children(org.arena()))
+        .filter(|&node| matches!(org[node], Element::DynBlock(_)))
+        .nth(block)
+        .unwrap();
+    let mut params = ClocktableParams::parse(params);
+    params.now = now.map(|now| Datetime::try_from(now).unwrap().into_owned());
+    org.write_clocktable(node, &params).unwrap();
+    assert!(org.validate().is_empty());
+
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    let text = String::from_utf8(writer).unwrap();
+    let start = text.match_indices("#+BEGIN:").nth(block).unwrap().0;
+    let end = start + text[start..].find("#+END:").unwrap();
+    text[start..end]
+        .lines()
+        .skip(1)
+        .map(|line| format!("{}\n", line))
+        .collect()
+}
+
+#[test]
+fn generate_file() {
+    assert_eq!(
+        generate(":scope file :maxlevel 2", None, 0),
+        r#"| Headline     | Time    |       |
+|--------------+---------+-------|
+| *Total time* | *30:35* |       |
+|--------------+---------+-------|
+| Project      | 29:05   |       |
+| \_  Design   |         | 28:20 |
+| \_  Meeting  |         | 0:45  |
+| Home         | 1:30    |       |
+"#
+    );
+}
+
+#[test]
+fn generate_block() {
+    // the clock across february and march is cut at march's start
+    assert_eq!(
+        generate(":block 2024-03", None, 0),
+        r#"| Headline       | Time   |      |      |
+|----------------+--------+------+------|
+| *Total time*   | *5:35* |      |      |
+|----------------+--------+------+------|
+| Project        | 4:05   |      |      |
+| \_  Design     |        | 3:20 |      |
+| \_    Sketches |        |      | 0:20 |
+| \_  Meeting    |        | 0:45 |      |
+| Home           | 1:30   |      |      |
+"#
+    );
+
+    assert_eq!(
+        generate(
+            ":block thisweek :maxlevel 1",
+            Some("2024-03-08 Fri 10:00"),
+            0
+        ),
+        r#"#+CAPTION: Clock summary at [2024-03-08 Fri 10:00]
+| Headline     | Time   |
+|--------------+--------|
+| *Total time* | *4:35* |
+|--------------+--------|
+| Project      | 3:05   |
+| Home         | 1:30   |
+"#
+    );
+
+    assert_eq!(
+        generate(":block 2023", None, 0),
+        r#"| Headline     | Time   |
+|--------------+--------|
+| *Total time* | *0:00* |
+"#
+    );
+}
+
+#[test]
+fn generate_tags_and_subtree() {
+    assert_eq!(
+        generate(":tags work-meeting", None, 0),
+        r#"| Headline       | Time    |       |      |
+|----------------+---------+-------+------|
+| *Total time*   | *28:20* |       |      |
+|----------------+---------+-------+------|
+| Project        | 28:20   |       |      |
+| \_  Design     |         | 28:20 |      |
+| \_    Sketches |         |       | 0:20 |
+"#
+    );
+
+    assert_eq!(
+        generate(":scope subtree", None, 1),
+        r#"| Headline     | Time   |      |
+|--------------+--------+------|
+| *Total time* | *0:45* |      |
+|--------------+--------+------|
+| \_  Meeting  |        | 0:45 |
+"#
+    );
+}
+
+#[test]
+fn generate_errors() {
+    let mut org = Org::parse(FIXTURE);
+    let section = org.document().section_node().unwrap();
+    let block = section.children(org.arena()).next().unwrap();
+
+    let params = ClocktableParams::parse(":block today");
+    assert_eq!(
+        org.write_clocktable(block, &params),
+        Err(ClocktableError::MissingNow)
+    );
+    assert_eq!(
+        org.write_clocktable(section, &ClocktableParams::default()),
+        Err(ClocktableError::NotADynamicBlock)
+    );
+}
@@ -0,0 +1,246 @@
+use orgize::{ColumnSpec, ColumnSummary, ColumnviewError, ColumnviewParams, Element, Org};
+use pretty_assertions::assert_eq;
+
+const TEXT: &str = include_str!("fixtures/columns.org");
+
+fn values(org: &Org, specs: &[ColumnSpec]) -> Vec<Vec<Option<String>>> {
+    org.column_view(specs)
+        .into_iter()
+        .map(|row| row.values)
+        .collect()
+}
+
+fn row(values: &[Option<&str>]) -> Vec<Option<String>> {
+    values.iter().map(|value| value.map(String::from)).collect()
+}
+
+#[test]
+fn specs() {
+    let org = Org::parse(TEXT);
+    let specs = org.column_specs();
+    assert_eq!(
+        specs[3],
+        ColumnSpec {
+            width: None,
+            property: "Effort".into(),
+            title: Some("Est".into()),
+            summary: Some(ColumnSummary::Time),
+        }
+    );
+    assert_eq!(specs[4].summary, Some(ColumnSummary::CheckboxCount));
+    assert_eq!(
+        specs
+            .iter()
+            .map(|spec| (spec.width, &*spec.property))
+            .collect::<Vec<_>>(),
+        [
+            (Some(25), "ITEM"),
+            (None, "TODO"),
+            (Some(3), "PRIORITY"),
+            (None, "Effort"),
+            (None, "DONE"),
+            (None, "Points"),
+        ]
+    );
+
+    assert_eq!(
+        Org::parse("* a\n").column_specs(),
+        ColumnSpec::parse_all("%25ITEM %TODO %3PRIORITY %TAGS")
+    );
+    assert_eq!(
+        ColumnSpec::parse_all("% %x{unknown} %y{+;%.1f}")[0].summary,
+        None
+    );
+    assert_eq!(
+        ColumnSpec::parse_all("%y{+;%.1f}")[0].summary,
+        Some(ColumnSummary::Sum)
+    );
+}
+
+#[test]
+fn effort_and_checkbox_summaries() {
+    let org = Org::parse(TEXT);
+    let specs = org.column_specs();
+
+    assert_eq!(
+        values(&org, &specs),
+        [
+            row(&[
+                Some("Release"),
+                None,
+                None,
+                Some("27:15"),
+                Some("[1/2]"),
+                Some("9.5")
+            ]),
+            row(&[
+                Some("Parser"),
+                Some("TODO"),
+                Some("A"),
+                Some("2:45"),
+                Some("[1/2]"),
+                Some("3")
+            ]),
+            row(&[
+                Some("Blocks"),
+                Some("DONE"),
+                None,
+                Some("0:45"),
+                Some("[X]"),
+                None
+            ]),
+            row(&[
+                Some("Objects"),
+                Some("TODO"),
+                None,
+                Some("2:00"),
+                Some("[ ]"),
+                None
+            ]),
+            row(&[
+                Some("Exporter"),
+                Some("DONE"),
+                None,
+                Some("1d 0:30"),
+                Some("[X]"),
+                Some("5")
+            ]),
+            row(&[Some("Notes"), None, None, None, None, Some("1.5")]),
+            row(&[Some("Backlog"), None, None, None, None, None]),
+        ]
+    );
+
+    // completed counts are checked in their parent
+    let text = TEXT.replace(":DONE: [ ]", ":DONE: [X]");
+    let org = Org::parse(&text);
+    let done = values(&org, &specs)
+        .into_iter()
+        .map(|row| row[4].clone())
+        .collect::<Vec<_>>();
+    assert_eq!(done[..2], row(&[Some("[2/2]"), Some("[2/2]")])[..]);
+
+    let percent = ColumnSpec::parse_all("%DONE{X%} %DONE{X}");
+    assert_eq!(
+        values(&Org::parse(TEXT), &percent)[..2],
+        [
+            row(&[Some("[50%]"), Some("[ ]")]),
+            row(&[Some("[50%]"), Some("[ ]")])
+        ]
+    );
+}
+
+#[test]
+fn special_properties() {
+    let org = Org::parse(TEXT);
+    let specs = ColumnSpec::parse_all("%TAGS %CLOCKSUM{:} %Points{min} %Points{max} %Points{mean}");
+
+    let rows = values(&org, &specs);
+    assert_eq!(
+        rows[0],
+        row(&[
+            Some(":work:"),
+            None,
+            Some("1.5"),
+            Some("5"),
+            Some("3.1666666666666665")
+        ])
+    );
+    assert_eq!(rows[6], row(&[None, Some("1:30"), None, None, None]));
+
+    let org = Org::parse("* a\nSCHEDULED: <2024-03-08 Fri> DEADLINE: <2024-03-10 Sun>\n");
+    assert_eq!(
+        values(&org, &ColumnSpec::parse_all("%SCHEDULED %DEADLINE")),
+        [row(&[Some("<2024-03-08 Fri>"), Some("<2024-03-10 Sun>")])]
+    );
+}
+
+fn columnview(text: &str) -> String {
+    let mut org = Org::parse(text);
+    let blocks: Vec<_> = org
+        .arena()
+        .iter()
+        .filter_map(|node| match node.get() {
+            Element::DynBlock(block) if block.block_name == "columnview" => Some((
+                org.arena().get_node_id(node)?,
+                ColumnviewParams::from_args(&block.parameters()),
+            )),
+            _ => None,
+        })
+        .collect();
+    for (node, params) in blocks {
+        org.write_columnview(node, &params).unwrap();
+    }
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+#[test]
+fn write_columnview() {
+    let text = format!(
+        "#+BEGIN: columnview :id global :hlines 1 :indent t :maxlevel 2\nold\n#+END:\n\n{}",
+        TEXT.replacen(
+            "* Release :work:\n",
+            "* Release :work:\n#+BEGIN: columnview :id local :format \"%ITEM %Effort{:} %Points{+}\"\n#+END:\n",
+            1
+        )
+    );
+
+    let output = columnview(&text);
+    assert!(output.starts_with(
+        "#+BEGIN: columnview :id global :hlines 1 :indent t :maxlevel 2\n\
+         | ITEM         | TODO | PRIORITY | Est     | Done  | Points |\n\
+         |--------------+------+----------+---------+-------+--------|\n\
+         | Release      |      |          | 27:15   | [1/2] | 9.5    |\n\
+         | \\_  Parser   | TODO | A        | 2:45    | [1/2] | 3      |\n\
+         | \\_  Exporter | DONE |          | 1d 0:30 | [X]   | 5      |\n\
+         | \\_  Notes    |      |          |         |       | 1.5    |\n\
+         |--------------+------+----------+---------+-------+--------|\n\
+         | Backlog      |      |          |         |       |        |\n\
+         #+END:\n\n"
+    ));
+    assert!(output.contains(
+        "* Release :work:\n\
+         #+BEGIN: columnview :id local :format \"%ITEM %Effort{:} %Points{+}\"\n\
+         | ITEM     | Effort  | Points |\n\
+         |----------+---------+--------|\n\
+         | Release  | 27:15   | 9.5    |\n\
+         | Parser   | 2:45    | 3      |\n\
+         | Blocks   | 0:45    |        |\n\
+         | Objects  | 2:00    |        |\n\
+         | Exporter | 1d 0:30 | 5      |\n\
+         | Notes    |         | 1.5    |\n\
+         #+END:\n** TODO [#A] Parser\n"
+    ));
+    // regenerating gives the same table
+    assert_eq!(columnview(&output), output);
+}
+
+#[test]
+fn columnview_by_id() {
+    let text = "#+BEGIN: columnview :id \"b\"\n#+END:\n\
+                * a\n\
+                * b | c\n:PROPERTIES:\n:CUSTOM_ID: b\n:END:\n\
+                ** d\n";
+    assert!(columnview(text).starts_with(
+        "#+BEGIN: columnview :id \"b\"\n\
+         | ITEM        | TODO | PRIORITY | TAGS |\n\
+         |-------------+------+----------+------|\n\
+         | b \\vert{} c |      |          |      |\n\
+         | d           |      |          |      |\n\
+         #+END:\n"
+    ));
+
+    let mut org = Org::parse("#+BEGIN: columnview :id missing\n#+END:\n* a\n");
+    let block = org.document().section_node().unwrap();
+    let block = org.arena()[block].first_child().unwrap();
+    assert_eq!(
+        org.write_columnview(block, &ColumnviewParams::parse(":id missing")),
+        Err(ColumnviewError::HeadlineNotFound("missing".into()))
+    );
+    let headline = org.headlines().next().unwrap().headline_node();
+    assert_eq!(
+        org.write_columnview(headline, &ColumnviewParams::default()),
+        Err(ColumnviewError::NotADynamicBlock)
+    );
+}
@@ -0,0 +1,121 @@
+use orgize::{elements::ListItem, indextree::NodeId, Element, Event, ListConversion, Org};
+use pretty_assertions::assert_eq;
+
+fn write(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+// list item whose first line starts with `line`
+fn item(org: &Org, line: &str) -> NodeId {
+    let text = org
+        .events()
+        .find(|event| matches!(&event.event, Event::Start(Element::Text { value }) if value.starts_with(line)))
+        .unwrap()
+        .node;
+    text.ancestors(org.arena())
+        .find(|&node| matches!(org[node], Element::ListItem(_)))
+        .unwrap()
+}
+
+const LIST: &str = "* Tasks
+- [ ] write
+  draft first
+  - [X] outline
+  - [ ] chapters
+- [X] plan
+";
+
+#[test]
+fn round_trip() {
+    let mut org = Org::parse(LIST);
+    let node = item(&org, "write");
+    let headline = ListItem::into_headline(&mut org, node, 2, &ListConversion::default()).unwrap();
+    assert_eq!(
+        write(&org),
+        "* Tasks
+- [X] plan
+** TODO write
+draft first
+*** DONE outline
+*** TODO chapters
+"
+    );
+
+    headline.into_list_item(&mut org, &ListConversion::default());
+    assert_eq!(
+        write(&org),
+        "* Tasks
+- [X] plan
+- [ ] write
+  draft first
+  - [X] outline
+  - [ ] chapters
+"
+    );
+}
+
+#[test]
+fn keep_nested_list() {
+    let mut org = Org::parse(LIST);
+    let node = item(&org, "write");
+    let conversion = ListConversion {
+        nested_items: false,
+        checkboxes: false,
+    };
+    ListItem::into_headline(&mut org, node, 1, &conversion).unwrap();
+    assert_eq!(
+        write(&org),
+        "* Tasks
+- [X] plan
+* write
+draft first
+- [X] outline
+- [ ] chapters
+"
+    );
+}
+
+#[test]
+fn renumber() {
+    let mut org = Org::parse("1. a\n2. b\n3. c\n");
+    let node = item(&org, "b");
+    ListItem::into_headline(&mut org, node, 1, &ListConversion::default()).unwrap();
+    assert_eq!(write(&org), "1. a\n2. c\n* b\n");
+
+    // not a list item
+    let headline = org.headlines().next().unwrap().headline_node();
+    assert!(ListItem::into_headline(&mut org, headline, 1, &ListConversion::default()).is_err());
+
+    // deeper than the following headline
+    let mut org = Org::parse("* a\n- b\n** c\n");
+    let node = item(&org, "b");
+    assert!(ListItem::into_headline(&mut org, node, 3, &ListConversion::default()).is_err());
+    assert_eq!(write(&org), "* a\n- b\n** c\n");
+}
+
+#[test]
+fn no_leftover_nodes() {
+    let live = |org: &Org| org.arena().iter().filter(|node| !node.is_removed()).count();
+
+    let mut org = Org::parse("* Tasks\n- [ ] a\n** Sub\n");
+    let node = item(&org, "a");
+    let before = live(&org);
+    // deeper than the following headline
+    assert!(ListItem::into_headline(&mut org, node, 3, &ListConversion::default()).is_err());
+    assert_eq!(live(&org), before);
+    assert_eq!(write(&org), "* Tasks\n- [ ] a\n** Sub\n");
+
+    let headline = ListItem::into_headline(&mut org, node, 2, &ListConversion::default()).unwrap();
+    headline.into_list_item(&mut org, &ListConversion::default());
+    assert_eq!(write(&org), "* Tasks\n- [ ] a\n** Sub\n");
+    assert_eq!(live(&org), before);
+    assert_eq!(
+        org.arena()
+            .iter()
+            .filter(|node| !node.is_removed() && matches!(node.get(), Element::Document { .. }))
+            .count(),
+        1
+    );
+}
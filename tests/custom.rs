@@ -0,0 +1,107 @@
+use orgize::{
+    elements::{Custom, CustomSource},
+    CustomParser, Element, Org, ParseConfig,
+};
+use pretty_assertions::assert_eq;
+
+#[derive(Debug, PartialEq)]
+struct Roam {
+    parameters: String,
+    contents: String,
+}
+
+fn config() -> ParseConfig {
+    ParseConfig::builder()
+        .custom_parser(CustomParser::new(|source| match *source {
+            CustomSource::Block {
+                name,
+                parameters,
+                contents,
+            } if name.eq_ignore_ascii_case("roam") => Some(Box::new(Roam {
+                parameters: parameters.to_string(),
+                contents: contents.to_string(),
+            })),
+            CustomSource::Keyword { key, value, .. } if key.eq_ignore_ascii_case("ROAM_ALIAS") => {
+                Some(Box::new(value.to_string()))
+            }
+            _ => None,
+        }))
+        .build()
+        .unwrap()
+}
+
+fn customs<'a>(org: &'a Org) -> Vec<&'a Custom<'a>> {
+    org.arena()
+        .iter()
+        .filter_map(|node| match node.get() {
+            Element::Custom(custom) => Some(custom),
+            _ => None,
+        })
+        .collect()
+}
+
+const TEXT: &str = "#+ROAM_ALIAS: alias\n#+TITLE: title\n\n#+BEGIN_roam a b\nline 1\nline 2\n#+END_roam\n\n#+BEGIN_other\nx\n#+END_other\ntext\n";
+
+#[test]
+fn custom_elements() {
+    let config = config();
+    let org = Org::parse_custom(TEXT, &config);
+
+    let customs = customs(&org);
+    assert_eq!(customs.len(), 2);
+
+    assert_eq!(customs[0].name, "ROAM_ALIAS");
+    assert_eq!(customs[0].raw, "#+ROAM_ALIAS: alias\n");
+    assert_eq!(customs[0].payload::<String>().unwrap(), "alias");
+    assert_eq!(customs[0].payload::<Roam>(), None);
+
+    assert_eq!(customs[1].name, "roam");
+    assert_eq!(
+        customs[1].raw,
+        "#+BEGIN_roam a b\nline 1\nline 2\n#+END_roam\n"
+    );
+    assert_eq!(customs[1].post_blank, 1);
+    assert_eq!(
+        customs[1].payload::<Roam>().unwrap(),
+        &Roam {
+            parameters: "a b".into(),
+            contents: "line 1\nline 2\n".into(),
+        }
+    );
+
+    // elements rejected by the parser are parsed as usual
+    assert!(org
+        .arena()
+        .iter()
+        .any(|node| matches!(node.get(), Element::Keyword(k) if k.key == "TITLE")));
+    assert!(org
+        .arena()
+        .iter()
+        .any(|node| matches!(node.get(), Element::SpecialBlock(b) if b.name == "other")));
+
+    assert!(org.validate().is_empty());
+
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    assert_eq!(String::from_utf8(writer).unwrap(), TEXT);
+
+    let mut writer = Vec::new();
+    org.write_html(&mut writer).unwrap();
+    let html = String::from_utf8(writer).unwrap();
+    assert!(!html.contains("roam"));
+    assert!(!html.contains("line 1"));
+}
+
+#[test]
+fn without_custom_parser() {
+    let org = Org::parse(TEXT);
+    assert!(customs(&org).is_empty());
+}
+
+#[test]
+fn send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+
+    assert_send_sync::<Org<'static>>();
+    assert_send_sync::<Custom<'static>>();
+}
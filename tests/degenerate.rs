@@ -0,0 +1,95 @@
+use orgize::{
+    elements::Title,
+    export::{FormatConfig, HtmlDocumentConfig},
+    Headline, Org,
+};
+use pretty_assertions::assert_eq;
+
+// inputs which consist of nothing, or nearly nothing, along with their org
+// and html outputs
+const CASES: &[(&str, &str, &str)] = &[
+    ("", "", "<main></main>"),
+    ("\n", "\n", "<main></main>"),
+    ("\n\n\n", "\n\n\n", "<main></main>"),
+    ("   ", "\n", "<main></main>"),
+    (" \t\n \n", "\n\n", "<main></main>"),
+    ("\r\n", "\n", "<main></main>"),
+    (
+        "SCHEDULED: <2024-03-04 Mon>",
+        "SCHEDULED: <2024-03-04 Mon>\n",
+        "<main><section><p>SCHEDULED: <span class=\"timestamp-wrapper\">\
+         <span class=\"timestamp\">&lt;2024-03-04 Mon&gt;</span></span></p></section></main>",
+    ),
+    ("#+TITLE:", "#+TITLE: \n", "<main></main>"),
+    (
+        "#+TITLE: a\n#+AUTHOR:\n",
+        "#+TITLE: a\n#+AUTHOR: \n",
+        "<main></main>",
+    ),
+    (
+        ":PROPERTIES:\n:END:\n",
+        ":PROPERTIES:\n:END:\n",
+        "<main></main>",
+    ),
+    ("# comment", "# comment\n", "<main></main>"),
+    ("#+", "#+\n", "<main><section><p>#+</p></section></main>"),
+    ("*", "*\n", "<main><section><p>*</p></section></main>"),
+    ("* ", "* \n", "<main><h1></h1></main>"),
+    ("**\n", "**\n", "<main><section><p>**</p></section></main>"),
+    (
+        "#+BEGIN_SRC",
+        "#+BEGIN_SRC\n",
+        "<main><section><p>#+BEGIN_SRC</p></section></main>",
+    ),
+    (
+        "[fn:1]",
+        "[fn:1] \n",
+        // unreferenced definitions aren't exported
+        "<main></main>",
+    ),
+];
+
+fn to_org(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+fn to_html(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_html(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+#[test]
+fn degenerate_inputs() {
+    for &(input, org_output, html_output) in CASES {
+        let mut org = Org::parse(input);
+        assert!(org.validate().is_empty(), "{:?}", input);
+
+        assert_eq!(to_org(&org), org_output, "{:?}", input);
+        assert_eq!(to_html(&org), html_output, "{:?}", input);
+        org.write_html_document(Vec::new(), &HtmlDocumentConfig::default())
+            .unwrap();
+        org.format(&FormatConfig::default());
+        serde_json::to_string(&org).unwrap();
+        org.metadata();
+        org.toc();
+
+        let headline = Headline::new(
+            Title {
+                raw: "new".into(),
+                ..Default::default()
+            },
+            &mut org,
+        );
+        org.document().append(headline, &mut org).unwrap();
+        assert!(org.validate().is_empty(), "{:?}", input);
+        assert_eq!(
+            to_org(&org),
+            format!("{}* new\n", org_output),
+            "{:?}",
+            input
+        );
+    }
+}
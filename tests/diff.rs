@@ -0,0 +1,110 @@
+use orgize::{diff, indextree::NodeId, HeadlineChange, Org, TreeEdit};
+use pretty_assertions::assert_eq;
+use std::collections::HashMap;
+
+const OLD: &str = include_str!("fixtures/diff_old.org");
+const NEW: &str = include_str!("fixtures/diff_new.org");
+
+fn titles(org: &Org) -> HashMap<NodeId, String> {
+    org.headlines()
+        .map(|headline| {
+            (
+                headline.headline_node(),
+                headline.title(org).raw.to_string(),
+            )
+        })
+        .collect()
+}
+
+// renders edits with headline titles instead of node ids
+fn describe(old: &Org, new: &Org, edits: &[TreeEdit]) -> Vec<String> {
+    let (old, new) = (titles(old), titles(new));
+    let parent = |parent: &Option<NodeId>| match parent {
+        Some(node) => new[node].clone(),
+        None => String::from("document"),
+    };
+
+    edits
+        .iter()
+        .map(|edit| match edit {
+            TreeEdit::Removed { old: node } => format!("removed {}", old[node]),
+            TreeEdit::Added {
+                new: node,
+                parent: p,
+                index,
+            } => format!("added {} to {} at {}", new[node], parent(p), index),
+            TreeEdit::Moved {
+                old: old_node,
+                new: node,
+                parent: p,
+                index,
+            } => {
+                assert_eq!(old[old_node], new[node]);
+                format!("moved {} to {} at {}", new[node], parent(p), index)
+            }
+            TreeEdit::Changed {
+                new: node,
+                change: HeadlineChange::Planning { keyword, from, to },
+                ..
+            } => format!(
+                "changed {}: {} {:?} -> {:?}",
+                new[node],
+                keyword,
+                from.as_ref().map(ToString::to_string),
+                to.as_ref().map(ToString::to_string),
+            ),
+            TreeEdit::Changed {
+                new: node, change, ..
+            } => format!("changed {}: {:?}", new[node], change),
+        })
+        .collect()
+}
+
+#[test]
+fn refile_and_complete() {
+    let old = Org::parse(OLD);
+    let new = Org::parse(NEW);
+
+    let edits = diff(&old, &new);
+    assert_eq!(
+        describe(&old, &new, &edits),
+        vec![
+            r#"changed Call the plumber: Keyword { from: Some("TODO"), to: Some("DONE") }"#
+                .to_string(),
+            r#"changed Call the plumber: CLOSED None -> Some("[2024-03-13 Wed 10:02]")"#
+                .to_string(),
+            r#"changed Orgize: Property { key: "CATEGORY", from: None, to: Some("dev") }"#
+                .to_string(),
+            r#"changed Release orgize v0.9: Title { from: "Release v0.9", to: "Release orgize v0.9" }"#
+                .to_string(),
+            "moved Read about tree diffs to Ideas at 0".to_string(),
+            "changed Read about tree diffs: Level { from: 2, to: 3 }".to_string(),
+        ]
+    );
+
+    // nothing changes between identical documents
+    assert_eq!(diff(&old, &Org::parse(OLD)), vec![]);
+}
+
+#[test]
+fn added_and_removed() {
+    let old = Org::parse("* a\n** b\n* c\ntext\n");
+    let new = Org::parse("* c\ntext\n** d\n* a\n");
+
+    let edits = diff(&old, &new);
+    assert_eq!(
+        describe(&old, &new, &edits),
+        vec!["removed b", "moved c to document at 0", "added d to c at 0",]
+    );
+    assert!(matches!(
+        edits.last(),
+        Some(TreeEdit::Added { index: 0, .. })
+    ));
+
+    let old = Org::parse("* a\nold\n");
+    let new = Org::parse("* a\nnew\n");
+    assert_eq!(
+        describe(&old, &new, &diff(&old, &new)),
+        vec![format!("changed a: {:?}", HeadlineChange::Section)]
+    );
+}
@@ -0,0 +1,118 @@
+use orgize::{elements::Timestamp, DocumentDate, Org};
+use pretty_assertions::assert_eq;
+
+const TEXT: &str = r#"#+TITLE: Parsing org-mode
+#+title: in Rust
+#+SUBTITLE: A tour
+#+DESCRIPTION: How orgize
+#+DESCRIPTION: parses documents
+#+AUTHOR: Jane Doe
+#+AUTHOR: John Doe
+#+EMAIL: jane@example.com
+#+DATE: <2024-03-08 Fri 10:00>
+#+LANGUAGE: en
+#+LANGUAGE: fr
+#+FILETAGS: :rust:org:
+#+FILETAGS: parsing
+#+KEYWORDS: ignored
+
+* Headline
+#+TITLE:
+"#;
+
+#[test]
+fn metadata() {
+    let org = Org::parse(TEXT);
+    let meta = org.metadata();
+
+    assert_eq!(meta.title.as_deref(), Some("Parsing org-mode in Rust"));
+    assert_eq!(meta.subtitle.as_deref(), Some("A tour"));
+    assert_eq!(
+        meta.description.as_deref(),
+        Some("How orgize parses documents")
+    );
+    assert_eq!(meta.author.as_deref(), Some("Jane Doe"));
+    assert_eq!(meta.email.as_deref(), Some("jane@example.com"));
+    assert_eq!(meta.language.as_deref(), Some("fr"));
+    assert_eq!(meta.filetags, ["rust", "org", "parsing"]);
+
+    match meta.date {
+        Some(DocumentDate::Timestamp(Timestamp::Active { start, .. })) => {
+            assert_eq!((start.year, start.month, start.day), (2024, 3, 8));
+            assert_eq!((start.hour, start.minute), (Some(10), Some(0)));
+        }
+        date => panic!("unexpected date {:?}", date),
+    }
+}
+
+#[test]
+fn raw_date() {
+    let org = Org::parse("#+DATE: Spring 2024\n#+DATE: <2024-03-08 Fri>\n");
+    match org.metadata().date {
+        Some(DocumentDate::Raw(date)) => assert_eq!(date, "Spring 2024"),
+        date => panic!("unexpected date {:?}", date),
+    }
+
+    let org = Org::parse("#+DATE: <2024-03-08 Fri> or later\n");
+    assert!(matches!(org.metadata().date, Some(DocumentDate::Raw(_))));
+}
+
+#[test]
+fn empty() {
+    let org = Org::parse("* Headline\n");
+    let meta = org.metadata();
+    assert_eq!(meta.title, None);
+    assert!(meta.date.is_none());
+    assert!(meta.filetags.is_empty());
+}
+
+#[test]
+fn export_options() {
+    let org = Org::parse(
+        "#+OPTIONS: todo:nil toc:2 \\\n\
+         #+OPTIONS: pri:nil\n\
+         * Notes\n\
+         #+OPTIONS: tags:nil toc:nil\n\
+         * TODO [#A] Task :work:\n",
+    );
+
+    assert_eq!(
+        org.export_options(),
+        [
+            ("todo", "nil"),
+            ("toc", "nil"),
+            ("pri", "nil"),
+            ("tags", "nil")
+        ]
+    );
+
+    // flags of all three lines apply
+    let mut writer = Vec::new();
+    org.write_html(&mut writer).unwrap();
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        "<main><h1>Notes</h1><h1>Task</h1></main>"
+    );
+
+    // keywords are kept in the tree
+    assert_eq!(org.keywords().count(), 3);
+}
+
+#[test]
+fn document_properties() {
+    let org = Org::parse(
+        "#+PROPERTY: header-args :results silent \\\n\
+         #+PROPERTY: header-args+ :exports both\n\
+         #+PROPERTY: LOCATION home\n\
+         #+PROPERTY: location office\n\
+         #+PROPERTY:\n",
+    );
+    let properties = org.document_properties();
+
+    assert_eq!(
+        properties.get_accumulated("HEADER-ARGS").as_deref(),
+        Some(":results silent :exports both")
+    );
+    assert_eq!(properties.get("LOCATION"), Some("office"));
+    assert_eq!(properties.iter().count(), 3);
+}
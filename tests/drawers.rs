@@ -0,0 +1,74 @@
+use orgize::{indextree::NodeId, DrawerPolicy, Element, Org, ParseConfig, Strictness};
+use pretty_assertions::assert_eq;
+
+fn drawer_names(org: &Org) -> Vec<String> {
+    org.arena()
+        .iter()
+        .filter_map(|node| match node.get() {
+            Element::Drawer(drawer) => Some(drawer.name.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn source_block(org: &Org) -> NodeId {
+    let section = org.document().section_node().unwrap();
+    section
+        .children(org.arena())
+        .find(|&node| matches!(org[node], Element::SourceBlock(_)))
+        .unwrap()
+}
+
+fn parse(text: &str, policy: DrawerPolicy) -> Org<'_> {
+    let config = ParseConfig::builder()
+        .drawer_names(policy)
+        .strictness(Strictness::Warn)
+        .build()
+        .unwrap();
+    Org::parse_custom(text, &config)
+}
+
+#[test]
+fn time_like_line() {
+    let text = ":0830:\nstand-up\n:END:\n:LOGBOOK:\n- note\n:END:\n";
+
+    let org = parse(text, DrawerPolicy::Any);
+    assert_eq!(drawer_names(&org), ["0830", "LOGBOOK"]);
+
+    let org = parse(text, DrawerPolicy::OnlyKnown(vec!["logbook".into()]));
+    assert_eq!(drawer_names(&org), ["LOGBOOK"]);
+    assert!(org.diagnostics().is_empty());
+
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    assert_eq!(String::from_utf8(writer).unwrap(), text);
+
+    let org = parse(text, DrawerPolicy::KnownPlus(vec!["RESULTS".into()]));
+    assert_eq!(drawer_names(&org), ["LOGBOOK"]);
+
+    let org = parse(text, DrawerPolicy::OnlyKnown(vec![]));
+    assert_eq!(drawer_names(&org), Vec::<String>::new());
+}
+
+#[test]
+fn results_drawer() {
+    let text = "#+BEGIN_SRC sh :exports code\necho hi\n#+END_SRC\n:RESULTS:\nhi\n:END:\n";
+
+    let org = parse(text, DrawerPolicy::KnownPlus(vec!["RESULTS".into()]));
+    let block = source_block(&org);
+    let results = org.results(block).unwrap();
+    assert!(matches!(&org[results], Element::Drawer(drawer) if drawer.name == "RESULTS"));
+
+    let mut writer = Vec::new();
+    org.write_html(&mut writer).unwrap();
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        "<main><section><div class=\"org-src-container\"><pre><code class=\"language-bash\">\
+         echo hi\n</code></pre></div></section></main>"
+    );
+
+    // not a drawer, so not the results either
+    let org = parse(text, DrawerPolicy::OnlyKnown(vec!["LOGBOOK".into()]));
+    let block = source_block(&org);
+    assert_eq!(org.results(block), None);
+}
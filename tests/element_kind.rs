@@ -0,0 +1,61 @@
+use orgize::{elements::ElementKind, Event, Org};
+use pretty_assertions::assert_eq;
+use serde_json::Value;
+
+#[test]
+fn display_and_from_str() {
+    for &kind in ElementKind::ALL {
+        let name = kind.to_string();
+        assert_eq!(name, kind.name());
+        assert_eq!(name.parse(), Ok(kind));
+    }
+
+    for (name, kind) in [
+        ("property-drawer", ElementKind::Drawer),
+        ("source-block", ElementKind::SourceBlock),
+        ("fn-def", ElementKind::FnDef),
+        ("list-item", ElementKind::ListItem),
+        ("macros", ElementKind::Macros),
+        ("text", ElementKind::Text),
+    ] {
+        assert_eq!(name.parse(), Ok(kind));
+        assert_ne!(kind.to_string(), name);
+    }
+    assert_eq!(ElementKind::SourceBlock.to_string(), "src-block");
+    assert_eq!(ElementKind::ListItem.to_string(), "item");
+    assert!("".parse::<ElementKind>().is_err());
+    assert!("Bold".parse::<ElementKind>().is_err());
+}
+
+fn json_types(value: &Value, types: &mut Vec<String>) {
+    if let Some(kind) = value.get("type").and_then(Value::as_str) {
+        types.push(kind.to_string());
+    }
+    if let Some(children) = value.get("children").and_then(Value::as_array) {
+        for child in children {
+            json_types(child, types);
+        }
+    }
+}
+
+#[test]
+fn json_types_are_names() {
+    let org = Org::parse(include_str!("fixtures/elements.org"));
+
+    let kinds: Vec<ElementKind> = org
+        .iter()
+        .filter_map(|event| match event {
+            Event::Start(element) => Some(element.kind()),
+            _ => None,
+        })
+        .collect();
+    assert!(kinds.len() > 20);
+
+    let mut types = Vec::new();
+    json_types(&serde_json::to_value(&org).unwrap(), &mut types);
+    assert_eq!(
+        types,
+        kinds.iter().map(ElementKind::to_string).collect::<Vec<_>>()
+    );
+    assert!(types.iter().all(|name| name.parse::<ElementKind>().is_ok()));
+}
@@ -0,0 +1,58 @@
+use orgize::{Org, ParseConfig};
+use pretty_assertions::assert_eq;
+
+// pasted from a markdown document, where `*` also marks footnotes and
+// bullets of lists which aren't org lists
+const PASTED: &str = "Orders over $50 ship *free, for the whole order rather than per item,
+excluding gift cards and parcels shipped abroad*.
+
+*Requirements:
+• an account in good standing*
+
+The *real* deadline is Friday.
+";
+
+fn html(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_html(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+#[test]
+fn pasted_markdown() {
+    assert_eq!(
+        html(&Org::parse(PASTED)),
+        "<main><section>\
+         <p>Orders over $50 ship <b>free, for the whole order rather than per item,\n\
+         excluding gift cards and parcels shipped abroad</b>.</p>\
+         <p><b>Requirements:\n• an account in good standing</b></p>\
+         <p>The <b>real</b> deadline is Friday.</p>\
+         </section></main>"
+    );
+
+    let config = ParseConfig::builder()
+        .conservative_emphasis(40)
+        .build()
+        .unwrap();
+    assert_eq!(
+        html(&Org::parse_custom(PASTED, &config)),
+        "<main><section>\
+         <p>Orders over $50 ship *free, for the whole order rather than per item,\n\
+         excluding gift cards and parcels shipped abroad*.</p>\
+         <p>*Requirements:\n• an account in good standing*</p>\
+         <p>The <b>real</b> deadline is Friday.</p>\
+         </section></main>"
+    );
+}
+
+#[test]
+fn verbatim_unaffected() {
+    let config = ParseConfig::builder()
+        .conservative_emphasis(3)
+        .build()
+        .unwrap();
+    assert_eq!(
+        html(&Org::parse_custom("=verbatim= and *bold*\n", &config)),
+        "<main><section><p><code>verbatim</code> and *bold*</p></section></main>"
+    );
+}
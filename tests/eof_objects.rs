@@ -0,0 +1,65 @@
+use orgize::{Element, Org};
+
+// a check for the parsed element
+type Check = fn(&Element) -> bool;
+
+// every object type, with its check
+const OBJECTS: &[(&str, Check)] = &[
+    ("*a*", |e| matches!(e, Element::Bold)),
+    ("/a/", |e| matches!(e, Element::Italic)),
+    ("_a_", |e| matches!(e, Element::Underline)),
+    ("+a+", |e| matches!(e, Element::Strike)),
+    ("=x=", |e| matches!(e, Element::Verbatim { .. })),
+    ("~y~", |e| matches!(e, Element::Code { .. })),
+    ("*bold*", |e| matches!(e, Element::Bold)),
+    ("<<t>>", |e| matches!(e, Element::Target(_))),
+    ("<<<r>>>", |e| matches!(e, Element::RadioTarget)),
+    ("<2024-03-08 Fri>", |e| matches!(e, Element::Timestamp(_))),
+    ("[2024-03-08 Fri]", |e| matches!(e, Element::Timestamp(_))),
+    ("[[l]]", |e| matches!(e, Element::Link(_))),
+    ("[[l][d]]", |e| matches!(e, Element::Link(_))),
+    ("[fn:1]", |e| matches!(e, Element::FnRef(_))),
+    ("[1/2]", |e| matches!(e, Element::Cookie(_))),
+    ("[%]", |e| matches!(e, Element::Cookie(_))),
+    ("{{{m}}}", |e| matches!(e, Element::Macros(_))),
+    ("@@html:x@@", |e| matches!(e, Element::Snippet(_))),
+    ("src_sh{ls}", |e| matches!(e, Element::InlineSrc(_))),
+    ("call_f()", |e| matches!(e, Element::InlineCall(_))),
+];
+
+// returns the last object of the last paragraph, ignoring a trailing quote
+fn last_object<'a>(org: &'a Org) -> Option<&'a Element<'a>> {
+    let paragraph = org
+        .arena()
+        .iter()
+        .rfind(|node| matches!(node.get(), Element::Paragraph { .. }))?;
+    let last = paragraph.last_child()?;
+    match &org[last] {
+        Element::Text { value } if value == "\"" => {
+            Some(&org[org.arena()[last].previous_sibling()?])
+        }
+        element => Some(element),
+    }
+}
+
+#[test]
+fn objects_at_eof() {
+    for (object, check) in OBJECTS {
+        for text in [
+            object.to_string(),
+            format!("{}\n", object),
+            format!("text {}", object),
+            format!("text {}\n", object),
+            format!("* a\nline\ntext {}", object),
+            format!("text \"{}\"", object),
+        ] {
+            // footnote references at the beginning of a line are definitions
+            if text.starts_with("[fn:") {
+                continue;
+            }
+            let org = Org::parse(&text);
+            let last = last_object(&org);
+            assert!(last.is_some_and(check), "{:?} parsed as {:?}", text, last);
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use std::fs;
+
+use orgize::{Event, Org};
+
+fn fixtures() -> Vec<(String, String)> {
+    let mut fixtures: Vec<_> = fs::read_dir("tests/fixtures")
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "org"))
+        .map(|path| {
+            let text = fs::read_to_string(&path).unwrap();
+            (path.display().to_string(), text)
+        })
+        .collect();
+    fixtures.sort();
+    assert!(!fixtures.is_empty());
+    fixtures
+}
+
+#[test]
+fn paired_events() {
+    for (path, text) in fixtures() {
+        let org = Org::parse(&text);
+        let mut stack = Vec::new();
+        let mut count = 0;
+
+        for event in org.events() {
+            match event.event {
+                Event::Start(element) => {
+                    assert_eq!(event.depth, stack.len(), "{}: {:?}", path, element);
+                    if let Some(&parent) = stack.last() {
+                        assert!(
+                            org[parent].is_container(),
+                            "{}: {:?} has children but isn't a container",
+                            path,
+                            org[parent]
+                        );
+                    }
+                    stack.push(event.node);
+                    count += 1;
+                }
+                Event::End(element) => {
+                    assert_eq!(stack.pop(), Some(event.node), "{}: {:?}", path, element);
+                    assert_eq!(event.depth, stack.len(), "{}: {:?}", path, element);
+                }
+            }
+            assert!(std::ptr::eq(event.event.element(), &org[event.node]));
+        }
+
+        assert!(stack.is_empty(), "{}", path);
+        assert_eq!(count, org.arena().len(), "{}", path);
+
+        // `iter` yields the same events
+        assert!(org.iter().zip(org.events()).all(|(a, b)| std::ptr::eq(
+            a.element(),
+            b.event.element()
+        ) && matches!(
+            (a, b.event),
+            (Event::Start(_), Event::Start(_)) | (Event::End(_), Event::End(_))
+        )));
+    }
+}
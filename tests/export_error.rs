@@ -0,0 +1,118 @@
+use orgize::{
+    elements::{Element, ElementKind},
+    export::{DefaultHtmlHandler, DefaultOrgHandler, ExportError, HtmlHandler, OrgHandler},
+    Org,
+};
+use pretty_assertions::assert_eq;
+use std::io::{Error, ErrorKind, Write};
+
+const TEXT: &str = r#"* Code
+
+text
+
+#+BEGIN_SRC rust
+fn main() {}
+#+END_SRC
+"#;
+
+// fails on every source block
+#[derive(Default)]
+struct NoSourceHandler<H>(H);
+
+fn unsupported() -> Error {
+    Error::new(ErrorKind::Unsupported, "source blocks are not supported")
+}
+
+impl HtmlHandler<Error> for NoSourceHandler<DefaultHtmlHandler> {
+    fn start<W: Write>(&mut self, w: W, element: &Element) -> Result<(), Error> {
+        match element {
+            Element::SourceBlock(_) => Err(unsupported()),
+            _ => self.0.start(w, element),
+        }
+    }
+
+    fn end<W: Write>(&mut self, w: W, element: &Element) -> Result<(), Error> {
+        self.0.end(w, element)
+    }
+}
+
+impl OrgHandler<Error> for NoSourceHandler<DefaultOrgHandler> {
+    fn start<W: Write>(&mut self, w: W, element: &Element) -> Result<(), Error> {
+        match element {
+            Element::SourceBlock(_) => Err(unsupported()),
+            _ => self.0.start(w, element),
+        }
+    }
+
+    fn end<W: Write>(&mut self, w: W, element: &Element) -> Result<(), Error> {
+        self.0.end(w, element)
+    }
+}
+
+fn assert_source_block_error(org: &Org, err: &ExportError<Error>) {
+    assert_eq!(err.element, ElementKind::SourceBlock);
+    assert!(matches!(org[err.node], Element::SourceBlock(_)));
+
+    let span = err.span.clone().unwrap();
+    assert_eq!(span.start, TEXT.find("#+BEGIN_SRC").unwrap());
+    assert!(TEXT[span].ends_with("#+END_SRC\n"));
+
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "failed to export src-block at {}..{}: source blocks are not supported",
+            TEXT.find("#+BEGIN_SRC").unwrap(),
+            TEXT.len(),
+        )
+    );
+    assert_eq!(err.error.kind(), ErrorKind::Unsupported);
+}
+
+#[test]
+fn failing_html_handler() {
+    let org = Org::parse(TEXT);
+
+    let mut writer = Vec::new();
+    let err = org
+        .write_html_custom(
+            &mut writer,
+            &mut NoSourceHandler::<DefaultHtmlHandler>::default(),
+        )
+        .unwrap_err();
+
+    assert_source_block_error(&org, &err);
+    // everything before the source block is still written
+    assert!(String::from_utf8(writer).unwrap().ends_with("<p>text</p>"));
+}
+
+#[test]
+fn failing_org_handler() {
+    let org = Org::parse(TEXT);
+
+    let mut writer = Vec::new();
+    let err = org
+        .write_org_custom(
+            &mut writer,
+            &mut NoSourceHandler::<DefaultOrgHandler>::default(),
+        )
+        .unwrap_err();
+
+    assert_source_block_error(&org, &err);
+}
+
+#[test]
+fn into_io_error() {
+    let org = Org::parse(TEXT);
+
+    let mut writer = Vec::new();
+    let err: Error = org
+        .write_html_custom(
+            &mut writer,
+            &mut NoSourceHandler::<DefaultHtmlHandler>::default(),
+        )
+        .unwrap_err()
+        .into();
+
+    assert_eq!(err.kind(), ErrorKind::Unsupported);
+    assert!(err.to_string().starts_with("failed to export src-block"));
+}
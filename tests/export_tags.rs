@@ -0,0 +1,86 @@
+use orgize::{export::DefaultOrgHandler, Org};
+use pretty_assertions::assert_eq;
+
+fn html(text: &str) -> String {
+    let mut writer = Vec::new();
+    Org::parse(text).write_html(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+#[test]
+fn exclude_tags() {
+    let text = "* Kept\n* Draft :draft:\nunfinished\n* Private :noexport:\nsecret\n";
+
+    assert_eq!(
+        html(text),
+        "<main><h1>Kept</h1><h1>Draft</h1><section><p>unfinished</p></section></main>"
+    );
+    assert_eq!(
+        html(&format!("#+EXCLUDE_TAGS: old draft\n{}", text)),
+        "<main><h1>Kept</h1><h1>Private</h1><section><p>secret</p></section></main>"
+    );
+    // an empty keyword excludes nothing
+    assert_eq!(
+        html(&format!("#+EXCLUDE_TAGS:\n{}", text)),
+        "<main><h1>Kept</h1><h1>Draft</h1><section><p>unfinished</p></section>\
+         <h1>Private</h1><section><p>secret</p></section></main>"
+    );
+}
+
+#[test]
+fn select_tags() {
+    let text = "#+SELECT_TAGS: publish\n\
+                * Blog\nintro\n** Post :publish:\n*** Part\n** Idea\n\
+                * Notes\n* Old :export:\n";
+
+    assert_eq!(Org::parse(text).export_tags().select, ["publish"]);
+    // selected subtrees and their ancestors
+    assert_eq!(
+        html(text),
+        "<main><h1>Blog</h1><section><p>intro</p></section><h2>Post</h2><h3>Part</h3></main>"
+    );
+
+    // exclude tags win
+    let text = format!(
+        "#+EXCLUDE_TAGS: part\n{}",
+        text.replace("Part", "Part :part:")
+    );
+    assert_eq!(
+        html(&text),
+        "<main><h1>Blog</h1><section><p>intro</p></section><h2>Post</h2></main>"
+    );
+
+    // without selected headlines, everything is exported
+    assert_eq!(
+        html("* a :draft:\n* b\n"),
+        "<main><h1>a</h1><h1>b</h1></main>"
+    );
+}
+
+#[test]
+fn clean_org() {
+    let clean = |text: &str| {
+        let mut handler = DefaultOrgHandler::default();
+        handler.clean = true;
+        let mut writer = Vec::new();
+        Org::parse(text)
+            .write_org_custom(&mut writer, &mut handler)
+            .unwrap();
+        String::from_utf8(writer).unwrap()
+    };
+    let text = "* Kept\n* Draft :draft:\n* Private :noexport:\n";
+
+    assert_eq!(clean(text), "* Kept\n* Draft :draft:\n");
+    assert_eq!(
+        clean(&format!("#+EXCLUDE_TAGS: draft\n{}", text)),
+        "#+EXCLUDE_TAGS: draft\n* Kept\n* Private :noexport:\n"
+    );
+    assert_eq!(
+        clean(&format!("#+EXCLUDE_TAGS:\n{}", text)),
+        format!("#+EXCLUDE_TAGS: \n{}", text)
+    );
+    assert_eq!(
+        clean("#+SELECT_TAGS: publish\n* Blog\n** Post :publish:\n** Idea\n* Notes\n"),
+        "#+SELECT_TAGS: publish\n* Blog\n** Post :publish:\n"
+    );
+}
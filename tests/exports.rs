@@ -0,0 +1,115 @@
+use orgize::Org;
+use pretty_assertions::assert_eq;
+
+fn to_html(text: &str) -> String {
+    let mut writer = Vec::new();
+    Org::parse(text).write_html(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+fn source_block(exports: &str) -> String {
+    to_html(&format!(
+        "#+NAME: square\n\
+         #+BEGIN_SRC python{}\n\
+         return [[1, 1], [2, 4]]\n\
+         #+END_SRC\n\
+         \n\
+         #+RESULTS: square\n\
+         | 1 | 1 |\n\
+         | 2 | 4 |\n\
+         \n\
+         text\n",
+        exports
+    ))
+}
+
+const CODE: &str = "<div class=\"org-src-container\">\
+                    <pre><code class=\"language-python\">return [[1, 1], [2, 4]]\n</code></pre></div>";
+const RESULTS: &str = "<table><tbody>\
+                       <tr><td>1</td><td>1</td></tr><tr><td>2</td><td>4</td></tr>\
+                       </tbody></table>";
+const TEXT: &str = "<p>text</p>";
+
+#[test]
+fn exports_code() {
+    let expected = format!("<main><section>{}{}</section></main>", CODE, TEXT);
+    assert_eq!(source_block(""), expected);
+    assert_eq!(source_block(" :exports code"), expected);
+}
+
+#[test]
+fn exports_results() {
+    assert_eq!(
+        source_block(" :exports results"),
+        format!("<main><section>{}{}</section></main>", RESULTS, TEXT)
+    );
+}
+
+#[test]
+fn exports_both() {
+    assert_eq!(
+        source_block(" :exports both"),
+        format!(
+            "<main><section>{}{}{}</section></main>",
+            CODE, RESULTS, TEXT
+        )
+    );
+}
+
+#[test]
+fn exports_none() {
+    assert_eq!(
+        source_block(" :results table :exports none"),
+        format!("<main><section>{}</section></main>", TEXT)
+    );
+}
+
+#[test]
+fn named_results() {
+    // results are found by name if they don't follow the block
+    assert_eq!(
+        to_html(
+            "#+NAME: square\n\
+             #+BEGIN_SRC python :exports code\n\
+             return 4\n\
+             #+END_SRC\n\
+             text\n\
+             #+RESULTS: square\n\
+             : 4\n"
+        ),
+        "<main><section>\
+         <div class=\"org-src-container\"><pre><code class=\"language-python\">return 4\n</code></pre></div>\
+         <p>text</p>\
+         </section></main>"
+    );
+}
+
+#[test]
+fn babel_call() {
+    let text = "#+CALL: square(x=2){}\n\
+                #+RESULTS:\n\
+                | 4 |\n";
+    let results =
+        "<main><section><table><tbody><tr><td>4</td></tr></tbody></table></section></main>";
+
+    assert_eq!(to_html(&text.replace("{}", "")), results);
+    assert_eq!(to_html(&text.replace("{}", " :exports both")), results);
+    assert_eq!(
+        to_html(&text.replace("{}", " :exports code")),
+        "<main></main>"
+    );
+}
+
+#[test]
+fn inline() {
+    assert_eq!(
+        to_html(
+            "a src_sh[:exports none]{echo 1} {{{results(=1=)}}} \
+             b src_sh{echo 2} \
+             c src_sh[:exports results]{echo 3}\n"
+        ),
+        "<main><section><p>a   \
+         b <code class=\"language-bash\">echo 2</code> \
+         c </p></section></main>"
+    );
+}
@@ -0,0 +1,70 @@
+use orgize::{
+    elements::Element,
+    export::{DefaultHtmlHandler, FilterHandler, FormatOrgHandler},
+    Org,
+};
+use pretty_assertions::assert_eq;
+
+const TEXT: &str = r#"See [[https://example.com][example]].
+
+#+BEGIN_SRC rust
+fn main() {}
+#+END_SRC
+"#;
+
+#[test]
+fn html() {
+    let mut handler = FilterHandler::new(DefaultHtmlHandler::default())
+        .filter("link", |element, html| match element {
+            Element::Link(link) if link.path.starts_with("https:") => {
+                html.replacen("<a ", "<a rel=\"external\" ", 1)
+            }
+            _ => html,
+        })
+        .filter("source-block", |element, html| match element {
+            Element::SourceBlock(block) => {
+                format!("<figure data-lang=\"{}\">{}</figure>", block.language, html)
+            }
+            _ => html,
+        })
+        // applied after the link filter
+        .filter("paragraph", |_, html| {
+            html.replace("<p>", "<p class=\"x\">")
+        })
+        .output_filter(|html| html.replace("<main>", "<main class=\"doc\">"));
+
+    let mut writer = Vec::new();
+    Org::parse(TEXT)
+        .write_html_custom(&mut writer, &mut handler)
+        .unwrap();
+
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        "<main class=\"doc\"><section><p class=\"x\">See <a rel=\"external\" href=\"https://example.com\">example</a>.</p>\
+         <figure data-lang=\"rust\"><div class=\"org-src-container\"><pre><code class=\"language-rust\">fn main() {}\n</code></pre></div></figure>\
+         </section></main>"
+    );
+}
+
+#[test]
+fn org() {
+    let mut handler = FilterHandler::new(FormatOrgHandler::default())
+        .filter("link", |_, org| org.to_uppercase())
+        .filter("link", |_, org| format!("{} (link)", org))
+        .output_filter(|org| format!("# formatted\n{}", org));
+
+    let mut writer = Vec::new();
+    Org::parse(TEXT)
+        .write_org_custom(&mut writer, &mut handler)
+        .unwrap();
+
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        "# formatted\n\
+         See [[HTTPS://EXAMPLE.COM][EXAMPLE]] (link).\n\
+         \n\
+         #+BEGIN_SRC rust\n\
+         fn main() {}\n\
+         #+END_SRC\n"
+    );
+}
@@ -0,0 +1,299 @@
+use orgize::{
+    elements::Element,
+    export::{DefaultHtmlHandler, FootnoteOrder, HtmlConfig, HtmlHandler},
+    indextree::NodeId,
+    Org, ParseConfig,
+};
+use pretty_assertions::assert_eq;
+use std::io::{Error, Write};
+
+const TEXT: &str = r#"Text[fn:1] and[fn:note_v1.2] and[fn: Note ] again[fn:1].
+
+[fn:1] Numeric.
+
+[fn:note_v1.2] Underscores and dots.
+
+[fn:Note] Named.
+"#;
+
+fn html(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_html(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+fn fn_ref(label: &str, suffix: &str) -> String {
+    format!(
+        "<sup><a id=\"fnr.{0}{1}\" class=\"footref\" href=\"#fn.{0}\" \
+         role=\"doc-backlink\">{0}</a></sup>",
+        label, suffix
+    )
+}
+
+fn fn_def(label: &str, contents: &str) -> String {
+    format!(
+        "<div class=\"footdef\"><sup><a id=\"fn.{0}\" class=\"footnum\" \
+         href=\"#fnr.{0}\" role=\"doc-backlink\">{0}</a></sup> \
+         <div class=\"footpara\" role=\"doc-footnote\"><p>{1}</p></div></div>",
+        label, contents
+    )
+}
+
+fn footnotes(definitions: &[String]) -> String {
+    format!(
+        "<div id=\"footnotes\"><h2 class=\"footnotes\">Footnotes</h2>{}</div>",
+        definitions.concat()
+    )
+}
+
+#[test]
+fn labels() {
+    let org = Org::parse(TEXT);
+
+    let refs: Vec<_> = org
+        .iter()
+        .filter_map(|event| match event {
+            orgize::Event::Start(Element::FnRef(fn_ref)) => Some(fn_ref.normalized_label()),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(refs, ["1", "note_v1.2", "Note", "1"]);
+
+    assert!(org.check_links().is_empty());
+}
+
+#[test]
+fn spaced_labels_round_trip() {
+    let text = "a[fn: Note ] b[fn: 2 :inline]\n\n[fn: Note ] Named.\n";
+    let org = Org::parse(text);
+
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    assert_eq!(String::from_utf8(writer).unwrap(), text);
+}
+
+#[test]
+fn html_export() {
+    assert_eq!(
+        html(&Org::parse(TEXT)),
+        format!(
+            "<main><section><p>Text{} and{} and{} again{}.</p></section>{}</main>",
+            fn_ref("1", ""),
+            fn_ref("note_v1.2", ""),
+            fn_ref("Note", ""),
+            fn_ref("1", ".2"),
+            footnotes(&[
+                fn_def("1", "Numeric."),
+                fn_def("note_v1.2", "Underscores and dots."),
+                fn_def("Note", "Named."),
+            ]),
+        )
+    );
+}
+
+#[test]
+fn case_sensitive() {
+    let org = Org::parse("a[fn:note]\n\n[fn:Note] b\n");
+    assert_eq!(
+        org.check_links()
+            .into_iter()
+            .map(|issue| issue.message)
+            .collect::<Vec<_>>(),
+        [
+            "footnote `note` has no definition",
+            "footnote `Note` is never referenced"
+        ]
+    );
+}
+
+#[test]
+fn legacy_definitions() {
+    let text = "a[fn:1] b[fn:note]\n\n[1] Legacy.\n\n[fn:note] Named.\n";
+
+    let org = Org::parse(text);
+    assert_eq!(org.check_links().len(), 1);
+
+    let config = ParseConfig::builder()
+        .legacy_footnote_definitions(true)
+        .build()
+        .unwrap();
+    let org = Org::parse_custom(text, &config);
+    assert!(org.check_links().is_empty());
+    assert_eq!(
+        html(&org),
+        format!(
+            "<main><section><p>a{} b{}</p></section>{}</main>",
+            fn_ref("1", ""),
+            fn_ref("note", ""),
+            footnotes(&[fn_def("1", "Legacy."), fn_def("note", "Named.")]),
+        )
+    );
+}
+
+const SCATTERED: &str = r#"First[fn:c], then[fn:a] and[fn:b].
+
+[fn:a] In the zeroth section.
+
+* Chapter
+Text.
+
+[fn:b] Under a headline.
+
+* Footnotes
+[fn:c] In the footnote section.
+
+[fn:unused] Never referenced.
+"#;
+
+fn html_with(org: &Org, config: HtmlConfig) -> String {
+    let mut writer = Vec::new();
+    org.write_html_custom(&mut writer, &mut DefaultHtmlHandler::new(config))
+        .unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+#[test]
+fn scattered_definitions() {
+    let org = Org::parse(SCATTERED);
+    let body = format!(
+        "<main><section><p>First{}, then{} and{}.</p></section>\
+         <h1>Chapter</h1><section><p>Text.</p></section>",
+        fn_ref("c", ""),
+        fn_ref("a", ""),
+        fn_ref("b", ""),
+    );
+    let a = fn_def("a", "In the zeroth section.");
+    let b = fn_def("b", "Under a headline.");
+    let c = fn_def("c", "In the footnote section.");
+    let unused = fn_def("unused", "Never referenced.");
+
+    // the footnote section headline isn't written
+    assert_eq!(
+        html(&org),
+        format!(
+            "{}{}</main>",
+            body,
+            footnotes(&[c.clone(), a.clone(), b.clone()])
+        )
+    );
+
+    assert_eq!(
+        html_with(
+            &org,
+            HtmlConfig {
+                footnote_order: FootnoteOrder::Document,
+                ..Default::default()
+            }
+        ),
+        format!(
+            "{}{}</main>",
+            body,
+            footnotes(&[a.clone(), b.clone(), c.clone()])
+        )
+    );
+
+    assert_eq!(
+        html_with(
+            &org,
+            HtmlConfig {
+                unreferenced_footnotes: true,
+                ..Default::default()
+            }
+        ),
+        format!("{}{}</main>", body, footnotes(&[c, a, b, unused]))
+    );
+}
+
+#[test]
+fn inline_definitions() {
+    let org =
+        Org::parse("a[fn:n: Inline *text* & more] b[fn:: Anonymous] c[fn:1]\n\n[fn:1] One.\n");
+    assert_eq!(
+        html(&org),
+        format!(
+            "<main><section><p>a{} b{} c{}</p></section>{}</main>",
+            fn_ref("n", ""),
+            fn_ref("2", ""),
+            fn_ref("1", ""),
+            footnotes(&[
+                fn_def("n", "Inline *text* &amp; more"),
+                fn_def("2", "Anonymous"),
+                fn_def("1", "One."),
+            ]),
+        )
+    );
+}
+
+#[test]
+fn nested_references() {
+    let org = Org::parse("a[fn:1]\n\n[fn:1] See[fn:2].\n\n[fn:2] Nested.\n");
+    assert_eq!(
+        html(&org),
+        format!(
+            "<main><section><p>a{}</p></section>{}</main>",
+            fn_ref("1", ""),
+            footnotes(&[
+                fn_def("1", &format!("See{}.", fn_ref("2", ""))),
+                fn_def("2", "Nested."),
+            ]),
+        )
+    );
+}
+
+// writes bold text as `<strong>`
+#[derive(Default)]
+struct StrongHandler(DefaultHtmlHandler);
+
+impl HtmlHandler<Error> for StrongHandler {
+    fn prepare(&mut self, org: &Org) {
+        self.0.prepare(org);
+    }
+
+    fn start<W: Write>(&mut self, mut w: W, element: &Element) -> Result<(), Error> {
+        match element {
+            Element::Bold => write!(w, "<strong>"),
+            _ => self.0.start(w, element),
+        }
+    }
+
+    fn end<W: Write>(&mut self, mut w: W, element: &Element) -> Result<(), Error> {
+        match element {
+            Element::Bold => write!(w, "</strong>"),
+            _ => self.0.end(w, element),
+        }
+    }
+
+    fn start_node<W: Write>(&mut self, w: W, node: NodeId, element: &Element) -> Result<(), Error> {
+        match element {
+            Element::Bold => self.start(w, element),
+            _ => self.0.start_node(w, node, element),
+        }
+    }
+
+    fn end_node<W: Write>(&mut self, w: W, node: NodeId, element: &Element) -> Result<(), Error> {
+        match element {
+            Element::Bold => self.end(w, element),
+            _ => self.0.end_node(w, node, element),
+        }
+    }
+
+    fn trailing_nodes(&mut self, org: &Org, root: NodeId) -> Vec<NodeId> {
+        self.0.trailing_nodes(org, root)
+    }
+}
+
+#[test]
+fn definitions_through_outer_handler() {
+    let org = Org::parse("a[fn:1]\n\n[fn:1] *Bold*.\n");
+    let mut writer = Vec::new();
+    org.write_html_custom(&mut writer, &mut StrongHandler::default())
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        format!(
+            "<main><section><p>a{}</p></section>{}</main>",
+            fn_ref("1", ""),
+            footnotes(&[fn_def("1", "<strong>Bold</strong>.")]),
+        )
+    );
+}
@@ -0,0 +1,124 @@
+use orgize::{export::FormatConfig, Org};
+use pretty_assertions::assert_eq;
+
+fn format(text: &str, config: &FormatConfig) -> String {
+    let formatted = Org::parse(text).format(config);
+    // formatting must be idempotent
+    assert_eq!(Org::parse(&formatted).format(config), formatted);
+    formatted
+}
+
+macro_rules! test_suite {
+    ($name:ident, $content:expr, $expected:expr) => {
+        #[test]
+        fn $name() {
+            assert_eq!(format($content, &FormatConfig::default()), $expected);
+        }
+    };
+}
+
+test_suite!(
+    headline,
+    "*   TODO   [#A]  Title   :tag1:tag2:\n\
+     \n\
+     DEADLINE: <2019-10-28 Mon>\n\
+     text\n\
+     **   Child\n\
+     * DONE  Without section\n",
+    "* TODO [#A] Title                                                 :tag1:tag2:\n\
+     \n\
+     DEADLINE: <2019-10-28 Mon>\n\
+     text\n\
+     \n\
+     ** Child\n\
+     \n\
+     * DONE Without section\n"
+);
+
+test_suite!(
+    planning_and_properties,
+    "* Title\n\
+     SCHEDULED: <2019-10-28 Mon>\n\
+     :PROPERTIES:\n\
+     :ID: id\n\
+     :END:\n\
+     text\n",
+    "* Title\n  \
+     SCHEDULED: <2019-10-28 Mon>\n  \
+     :PROPERTIES:\n  \
+     :ID: id\n  \
+     :END:\n\
+     text\n"
+);
+
+test_suite!(
+    list,
+    "- item 1   \n    continued\n      - nested\n          continued\n- item 2\n\n\n\nparagraph",
+    "- item 1\n  continued\n  - nested\n    continued\n- item 2\n\nparagraph\n"
+);
+
+test_suite!(
+    blank_lines,
+    "\n\n#+TITLE: title\n#+AUTHOR: author\nparagraph 1\n\n\nparagraph 2\n#+BEGIN_QUOTE\nquote\n#+END_QUOTE\n\n\n",
+    "#+TITLE: title\n#+AUTHOR: author\nparagraph 1\n\nparagraph 2\n\n#+BEGIN_QUOTE\nquote\n#+END_QUOTE\n"
+);
+
+test_suite!(
+    table,
+    "| a | *b* |\n|---+----|\n| 1 | [[https://example.com][2]] |\n#+TBLFM: $2=$1\n",
+    "| a | *b*                        |\n\
+     |---+----------------------------|\n\
+     | 1 | [[https://example.com][2]] |\n\
+     #+TBLFM: $2=$1\n"
+);
+
+test_suite!(
+    raw_blocks,
+    "- item\n  #+BEGIN_SRC rust :results silent\n      fn main() {}   \n\n  #+END_SRC\n\
+     #+BEGIN_EXAMPLE\n  example  \n#+END_EXAMPLE\n\
+     #+BEGIN_EXPORT html\n  <br>  \n#+END_EXPORT\n\
+     #+BEGIN_VERSE\n  verse\n#+END_VERSE\n",
+    "- item\n  #+BEGIN_SRC rust :results silent\n      fn main() {}   \n\n  #+END_SRC\n\
+     \n\
+     #+BEGIN_EXAMPLE\n  example  \n#+END_EXAMPLE\n\
+     \n\
+     #+BEGIN_EXPORT html\n  <br>  \n#+END_EXPORT\n\
+     \n\
+     #+BEGIN_VERSE\n  verse\n#+END_VERSE\n"
+);
+
+#[test]
+fn config() {
+    let config = FormatConfig {
+        blank_lines: 0,
+        tags_column: 20,
+        list_indent: 4,
+        align_tables: false,
+    };
+
+    assert_eq!(
+        format(
+            "* Title :a:\n\nparagraph\n\n- item\n  - nested\n* A very long title :b:\n|a|bc|\n",
+            &config
+        ),
+        "* Title             :a:\nparagraph\n- item\n    - nested\n* A very long title :b:\n|a|bc|\n"
+    );
+}
+
+#[test]
+fn idempotent() {
+    for text in &[
+        "",
+        "\n\n\n",
+        "* ",
+        "*bold* /italic/ _underline_ +strike+ =verbatim= ~code~\n",
+        "#+CAPTION: caption\n#+NAME: name\n| a |\n",
+        "1. item\n   a. text\n   2. nested\n\n\n   not in list",
+        "* :tag:\n* Read https://foo:8080/  :a:b: :web:\n",
+        "* Title [1/2] :tag:\n:LOGBOOK:\nCLOCK: [2019-10-28 Mon 08:53]--[2019-10-28 Mon 09:53] =>  1:00\n:END:\n",
+        "{{{macro(arg)}}} <<target>> src_rust{1} call_f(1) @@html:<br>@@ [fn:1]\n",
+        "text\n-----\n: fixed\n# comment\n",
+    ] {
+        format(text, &FormatConfig::default());
+    }
+}
@@ -0,0 +1,98 @@
+use orgize::{
+    elements::{Repeater, RepeaterMark, TimeUnit, Timestamp},
+    Org,
+};
+use pretty_assertions::assert_eq;
+use std::convert::TryFrom;
+
+const HABITS: &str = r#"* TODO Run
+SCHEDULED: <2024-01-01 Mon .+2d/4d>
+:PROPERTIES:
+:STYLE:    habit
+:END:
+* TODO Water plants
+SCHEDULED: <2024-01-01 Mon ++1w/10d>
+:PROPERTIES:
+:STYLE: Habit
+:END:
+* TODO Pay rent
+SCHEDULED: <2024-01-01 Mon +1m>
+"#;
+
+fn repeater(ts: &str) -> Repeater {
+    match Timestamp::try_from(ts).unwrap() {
+        Timestamp::Active { repeater, .. } => repeater.unwrap(),
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn parse_habit_repeater() {
+    assert_eq!(
+        repeater("<2024-01-01 Mon .+2d/4d>"),
+        Repeater {
+            mark: RepeaterMark::Restart,
+            value: 2,
+            unit: TimeUnit::Day,
+            max: Some((4, TimeUnit::Day)),
+        }
+    );
+    assert_eq!(
+        repeater("<2024-01-01 Mon ++1w/10d>").max,
+        Some((10, TimeUnit::Day))
+    );
+    assert_eq!(repeater("<2024-01-01 Mon +1m>").max, None);
+    assert_eq!(
+        Repeater::try_from(".+2d/4d").unwrap().to_string(),
+        ".+2d/4d"
+    );
+
+    let org = Org::parse(HABITS);
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    assert_eq!(String::from_utf8(writer).unwrap(), HABITS);
+
+    let habits: Vec<_> = org.headlines().map(|h| h.is_habit(&org)).collect();
+    assert_eq!(habits, [true, true, false]);
+}
+
+#[test]
+#[cfg(feature = "chrono")]
+fn habit_next_and_status() {
+    use chrono::NaiveDate;
+    use orgize::elements::HabitStatus;
+
+    let date = |month, day| NaiveDate::from_ymd_opt(2024, month, day).unwrap();
+
+    // `.+` restarts from the completion date
+    let restart = Timestamp::try_from("<2024-01-01 Mon .+2d/4d>").unwrap();
+    assert_eq!(restart.next_after(date(1, 5)), Some(date(1, 7)));
+
+    // `++` keeps the weekday
+    let catch_up = Timestamp::try_from("<2024-01-01 Mon ++1w/10d>").unwrap();
+    assert_eq!(catch_up.next_after(date(1, 10)), Some(date(1, 15)));
+
+    assert_eq!(restart.habit_status(date(1, 1)), Some(HabitStatus::Due));
+    assert_eq!(restart.habit_status(date(1, 3)), Some(HabitStatus::Due));
+    assert_eq!(restart.habit_status(date(1, 4)), Some(HabitStatus::Overdue));
+    assert_eq!(
+        Timestamp::try_from("<2024-01-07 Sun .+2d/4d>")
+            .unwrap()
+            .habit_status(date(1, 5)),
+        Some(HabitStatus::NotDue)
+    );
+
+    assert_eq!(catch_up.habit_status(date(1, 4)), Some(HabitStatus::Due));
+    assert_eq!(
+        catch_up.habit_status(date(1, 5)),
+        Some(HabitStatus::Overdue)
+    );
+
+    // without a maximum, it's only due on the scheduled date
+    let rent = Timestamp::try_from("<2024-01-01 Mon +1m>").unwrap();
+    assert_eq!(rent.habit_status(date(1, 1)), Some(HabitStatus::Due));
+    assert_eq!(rent.habit_status(date(1, 2)), Some(HabitStatus::Overdue));
+
+    let once = Timestamp::try_from("<2024-01-01 Mon>").unwrap();
+    assert_eq!(once.habit_status(date(1, 1)), None);
+}
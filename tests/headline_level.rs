@@ -0,0 +1,53 @@
+use orgize::{Org, ParseConfig};
+use pretty_assertions::assert_eq;
+
+fn levels(org: &Org) -> Vec<usize> {
+    org.headlines().map(|headline| headline.level()).collect()
+}
+
+#[test]
+fn stars_without_space() {
+    let text = "*****\n*no-space\n* a\n*****\ntext\n**\n";
+    let org = Org::parse(text);
+    assert_eq!(levels(&org), [1]);
+
+    let section = org.document().section_node().unwrap();
+    assert_eq!(
+        org.span(section).map(|span| &text[span]),
+        Some("*****\n*no-space\n")
+    );
+    let a = org.headlines().next().unwrap();
+    let section = a.section_node().unwrap();
+    assert_eq!(
+        org.span(section).map(|span| &text[span]),
+        Some("*****\ntext\n**\n")
+    );
+}
+
+#[test]
+fn max_level() {
+    let stars = "*".repeat(40);
+    let text = format!("* a\n** b\n{} c\n*** d\n", stars);
+
+    let org = Org::parse(&text);
+    assert_eq!(levels(&org), [1, 2, 40, 3]);
+
+    let config = ParseConfig::builder()
+        .max_headline_level(3)
+        .build()
+        .unwrap();
+    let org = Org::parse_custom(&text, &config);
+    assert_eq!(levels(&org), [1, 2, 3]);
+    let b = org.headlines().nth(1).unwrap();
+    let section = b.section_node().unwrap();
+    assert_eq!(
+        org.span(section).map(|span| &text[span]),
+        Some(&*format!("{} c\n", stars))
+    );
+
+    let config = ParseConfig::builder()
+        .max_headline_level(40)
+        .build()
+        .unwrap();
+    assert_eq!(levels(&Org::parse_custom(&text, &config)), [1, 2, 40, 3]);
+}
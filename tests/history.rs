@@ -0,0 +1,138 @@
+use orgize::{Org, ParseConfig, StateChange};
+use pretty_assertions::assert_eq;
+
+const TEXT: &str = r#"* DONE Ship release
+CLOSED: [2024-03-08 Fri 17:00]
+:PROPERTIES:
+:CREATED:  [2024-03-01 Fri 09:00]
+:END:
+:LOGBOOK:
+- State "DONE"       from "TODO"       [2024-03-08 Fri 17:00]
+- State "TODO"       from "WAIT"       [2024-03-06 Wed 10:00]
+- State "WAIT"       from "TODO"       [2024-03-04 Mon 09:00] \\
+  Waiting for review
+CLOCK: [2024-03-04 Mon 08:00]--[2024-03-04 Mon 09:00] =>  1:00
+:END:
+Notes.
+"#;
+
+fn config() -> ParseConfig {
+    ParseConfig {
+        todo_keywords: (vec!["TODO".into(), "WAIT".into()], vec!["DONE".into()]),
+        ..Default::default()
+    }
+}
+
+fn chain(changes: &[StateChange]) -> Vec<String> {
+    changes
+        .iter()
+        .map(|change| {
+            format!(
+                "{} -> {} at {}",
+                change.from.as_deref().unwrap_or("-"),
+                change.to.as_deref().unwrap_or("-"),
+                change
+                    .timestamp
+                    .as_ref()
+                    .map(ToString::to_string)
+                    .unwrap_or_default()
+            )
+        })
+        .collect()
+}
+
+#[test]
+fn full_chain() {
+    let org = Org::parse_custom(TEXT, &config());
+    let headline = org.headlines().next().unwrap();
+
+    assert_eq!(
+        chain(&headline.state_history(&org)),
+        [
+            "- -> TODO at [2024-03-01 Fri 09:00]",
+            "TODO -> WAIT at [2024-03-04 Mon 09:00]",
+            "WAIT -> TODO at [2024-03-06 Wed 10:00]",
+            "TODO -> DONE at [2024-03-08 Fri 17:00]",
+        ]
+    );
+}
+
+#[test]
+fn outside_of_drawer() {
+    // out of order, and one entry without timestamp
+    let org = Org::parse_custom(
+        r#"* WAIT Review
+- State "TODO"       from "WAIT"       [2024-03-06 Wed 10:00]
+- State "WAIT"       from "TODO"
+- State "TODO"       from              [2024-03-01 Fri 09:00]
+- State "WAIT"       from "TODO"       [2024-03-07 Thu 10:00]
+- an unrelated item
+** Sub
+- State "DONE"       from "TODO"       [2024-02-01 Thu 10:00]
+"#,
+        &config(),
+    );
+    let headline = org.headlines().next().unwrap();
+
+    assert_eq!(
+        chain(&headline.state_history(&org)),
+        [
+            "- -> TODO at [2024-03-01 Fri 09:00]",
+            "TODO -> WAIT at ",
+            "WAIT -> TODO at [2024-03-06 Wed 10:00]",
+            "TODO -> WAIT at [2024-03-07 Thu 10:00]",
+        ]
+    );
+}
+
+#[test]
+fn current_state() {
+    let org = Org::parse_custom(
+        "* TODO Plain\n* DONE Changed\n- State \"WAIT\" from \"TODO\" [2024-03-04 Mon 09:00]\n* Untracked\n",
+        &config(),
+    );
+    let headlines: Vec<_> = org.headlines().collect();
+
+    // no history, only the current state
+    assert_eq!(chain(&headlines[0].state_history(&org)), ["- -> TODO at "]);
+    // the earliest timestamp is the creation
+    assert_eq!(
+        chain(&headlines[1].state_history(&org)),
+        [
+            "- -> TODO at [2024-03-04 Mon 09:00]",
+            "TODO -> WAIT at [2024-03-04 Mon 09:00]",
+            "WAIT -> DONE at ",
+        ]
+    );
+    assert!(headlines[2].state_history(&org).is_empty());
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn time_in_state() {
+    use orgize::elements::Timestamp;
+    use std::convert::TryFrom;
+
+    let org = Org::parse_custom(TEXT, &config());
+    let headline = org.headlines().next().unwrap();
+    let now = Timestamp::try_from("[2024-03-10 Sun 17:00]").unwrap();
+
+    // 03-01 09:00 to 03-04 09:00, and 03-06 10:00 to 03-08 17:00
+    assert_eq!(
+        headline.time_in_state("TODO", &now, &org).to_string(),
+        "127:00"
+    );
+    assert_eq!(
+        headline.time_in_state("WAIT", &now, &org).to_string(),
+        "49:00"
+    );
+    // until now
+    assert_eq!(
+        headline.time_in_state("DONE", &now, &org).to_string(),
+        "48:00"
+    );
+    assert_eq!(
+        headline.time_in_state("NEXT", &now, &org).to_string(),
+        "0:00"
+    );
+}
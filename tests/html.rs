@@ -0,0 +1,574 @@
+use orgize::{
+    export::{DefaultHtmlHandler, EntityOutput, HtmlConfig, HtmlHandler, LinkConfig},
+    indextree::NodeId,
+    Element, Org,
+};
+use pretty_assertions::assert_eq;
+use std::io::{Error, Write};
+
+const TEXT: &str = r#"* TODO Meeting
+DEADLINE: <2024-03-08 Fri> SCHEDULED: <2024-03-04 Mon>
+:PROPERTIES:
+:ID: 4f1c
+:END:
+:LOGBOOK:
+CLOCK: [2024-03-02 Sat 09:00]--[2024-03-02 Sat 10:30] =>  1:30
+:END:
+:NOTES:
+Some *notes*.
+:END:
+Body
+"#;
+
+const PLANNING: &str = "<p class=\"planning\">\
+    <span class=\"timestamp-wrapper\"><span class=\"timestamp-kwd\">DEADLINE:</span> \
+    <span class=\"timestamp\">&lt;2024-03-08 Fri&gt;</span></span> \
+    <span class=\"timestamp-wrapper\"><span class=\"timestamp-kwd\">SCHEDULED:</span> \
+    <span class=\"timestamp\">&lt;2024-03-04 Mon&gt;</span></span></p>";
+
+const PROPERTIES: &str = "<table class=\"properties\"><tr><th>ID</th><td>4f1c</td></tr></table>";
+
+const LOGBOOK: &str =
+    "<p><span class=\"timestamp-wrapper\"><span class=\"timestamp-kwd\">CLOCK:</span> \
+    <span class=\"timestamp\">[2024-03-02 Sat 09:00-10:30]</span></span> (1:30)</p>";
+
+const NOTES: &str = "<p>Some <b>notes</b>.</p>";
+
+fn html(config: HtmlConfig) -> String {
+    let mut writer = Vec::new();
+    Org::parse(TEXT)
+        .write_html_custom(&mut writer, &mut DefaultHtmlHandler::new(config))
+        .unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+fn expected(planning: &str, properties: &str, logbook: &str, notes: &str) -> String {
+    format!(
+        "<main><h1>Meeting</h1>{}{}<section>{}{}<p>Body</p></section></main>",
+        planning, properties, logbook, notes
+    )
+}
+
+#[test]
+fn default() {
+    assert_eq!(
+        html(HtmlConfig::default()),
+        expected(PLANNING, "", "", NOTES)
+    );
+}
+
+#[test]
+fn properties() {
+    assert_eq!(
+        html(HtmlConfig {
+            properties: true,
+            ..Default::default()
+        }),
+        expected(PLANNING, PROPERTIES, "", NOTES)
+    );
+}
+
+#[test]
+fn logbook() {
+    assert_eq!(
+        html(HtmlConfig {
+            logbook: true,
+            ..Default::default()
+        }),
+        expected(PLANNING, "", LOGBOOK, NOTES)
+    );
+}
+
+#[test]
+fn drawers() {
+    assert_eq!(
+        html(HtmlConfig {
+            drawers: false,
+            ..Default::default()
+        }),
+        expected(PLANNING, "", "", "")
+    );
+}
+
+#[test]
+fn planning() {
+    assert_eq!(
+        html(HtmlConfig {
+            planning: false,
+            ..Default::default()
+        }),
+        expected("", "", "", NOTES)
+    );
+}
+
+fn to_html(text: &str, config: HtmlConfig) -> String {
+    let mut writer = Vec::new();
+    Org::parse(text)
+        .write_html_custom(&mut writer, &mut DefaultHtmlHandler::new(config))
+        .unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+#[test]
+fn src_block() {
+    let text = "#+BEGIN_SRC rust\n    fn main() {\n\n\tlet a = 1;\n        if a < 2 {}\n    }\n#+END_SRC\n";
+    // tabs and spaces don't share any indentation
+    assert_eq!(
+        to_html(text, HtmlConfig::default()),
+        "<main><section><div class=\"org-src-container\"><pre><code class=\"language-rust\">    \
+         fn main() {\n\n\tlet a = 1;\n        if a &lt; 2 {}\n    }\n\
+         </code></pre></div></section></main>"
+    );
+
+    let text = "#+BEGIN_SRC rust\n    fn main() {\n        let a = 1;\n\n    }\n#+END_SRC\n";
+    assert_eq!(
+        to_html(text, HtmlConfig::default()),
+        "<main><section><div class=\"org-src-container\"><pre><code class=\"language-rust\">\
+         fn main() {\n    let a = 1;\n\n}\n\
+         </code></pre></div></section></main>"
+    );
+}
+
+#[test]
+fn src_language_alias() {
+    let text = "#+BEGIN_SRC elisp\n(message \"hi\")\n#+END_SRC\nsrc_elisp{(+ 1 2)}\n";
+    assert_eq!(
+        to_html(text, HtmlConfig::default()),
+        "<main><section><div class=\"org-src-container\"><pre><code class=\"language-lisp\">\
+         (message &quot;hi&quot;)\n</code></pre></div>\
+         <p><code class=\"language-lisp\">(+ 1 2)</code></p></section></main>"
+    );
+
+    assert_eq!(
+        to_html(
+            text,
+            HtmlConfig {
+                src_class_prefix: String::from("src-"),
+                ..Default::default()
+            }
+        ),
+        "<main><section><div class=\"org-src-container\"><pre><code class=\"src-lisp\">\
+         (message &quot;hi&quot;)\n</code></pre></div>\
+         <p><code class=\"src-lisp\">(+ 1 2)</code></p></section></main>"
+    );
+}
+
+#[test]
+fn cited_quote() {
+    let text = "#+ATTR_HTML: :cite https://example.com/source :class epigraph\n\
+                #+BEGIN_QUOTE\n\
+                Simple as possible.\n\
+                #+END_QUOTE\n";
+    assert_eq!(
+        to_html(text, HtmlConfig::default()),
+        "<main><section><blockquote cite=\"https://example.com/source\" class=\"epigraph\">\
+         <p>Simple as possible.</p></blockquote></section></main>"
+    );
+
+    let text = "#+CAPTION: Albert Einstein\n\
+                #+BEGIN_QUOTE\n\
+                Simple as possible.\n\
+                #+END_QUOTE\n";
+    assert_eq!(
+        to_html(text, HtmlConfig::default()),
+        "<main><section><figure><blockquote><p>Simple as possible.</p></blockquote>\
+         <figcaption>Albert Einstein</figcaption></figure></section></main>"
+    );
+
+    // not affiliated if separated by a blank line
+    let text = "#+ATTR_HTML: :cite https://example.com\n\n\
+                #+BEGIN_QUOTE\n\
+                text\n\
+                #+END_QUOTE\n";
+    assert_eq!(
+        to_html(text, HtmlConfig::default()),
+        "<main><section><blockquote><p>text</p></blockquote></section></main>"
+    );
+}
+
+#[test]
+fn centered_paragraph() {
+    let text = "#+CAPTION: A cat & a dog\n\
+                #+ATTR_HTML: :class pets :style \"width: 50%\"\n\
+                #+BEGIN_CENTER\n\
+                [[file:cat.png]]\n\
+                #+END_CENTER\n";
+    assert_eq!(
+        to_html(text, HtmlConfig::default()),
+        "<main><section><figure><div class=\"org-center pets\" style=\"width: 50%\">\
+         <p><a href=\"file:cat.png\">file:cat.png</a></p></div>\
+         <figcaption>A cat &amp; a dog</figcaption></figure></section></main>"
+    );
+    assert_eq!(
+        to_html(
+            text,
+            HtmlConfig {
+                center_style: true,
+                ..Default::default()
+            }
+        ),
+        "<main><section><figure><div style=\"text-align:center;width: 50%\" class=\"pets\">\
+         <p><a href=\"file:cat.png\">file:cat.png</a></p></div>\
+         <figcaption>A cat &amp; a dog</figcaption></figure></section></main>"
+    );
+}
+
+#[test]
+fn multi_line_caption() {
+    let text = "#+CAPTION[Quote]: From *Albert*\n\
+                #+CAPTION: see [[https://example.com][the source]]\n\
+                #+BEGIN_QUOTE\n\
+                Simple as possible.\n\
+                #+END_QUOTE\n";
+    assert_eq!(
+        to_html(text, HtmlConfig::default()),
+        "<main><section><figure><blockquote><p>Simple as possible.</p></blockquote>\
+         <figcaption>From <b>Albert</b> see <a href=\"https://example.com\">the source</a>\
+         </figcaption></figure></section></main>"
+    );
+}
+
+#[test]
+fn numbered_captions() {
+    let text = "See [[prices]], [[sales]] and [[logo]], or [[sales][the sales]].\n\n\
+                #+CAPTION: Prices\n\
+                #+NAME: prices\n\
+                | a | 1 |\n\n\
+                #+NAME: logo\n\
+                #+CAPTION: The logo\n\
+                [[file:logo.png]]\n\n\
+                #+NAME: sales\n\
+                #+CAPTION: Sales\n\
+                | b | 2 |\n\n\
+                | not | numbered |\n";
+    assert_eq!(
+        to_html(text, HtmlConfig::default()),
+        "<main><section><p>See <a href=\"#prices\">Table 1</a>, <a href=\"#sales\">Table 2</a> \
+         and <a href=\"#logo\">Figure 1</a>, or <a href=\"#sales\">the sales</a>.</p>\
+         <table id=\"prices\"><caption><span class=\"table-number\">Table 1:</span> Prices</caption>\
+         <tbody><tr><td>a</td><td>1</td></tr></tbody></table>\
+         <figure id=\"logo\"><p><a href=\"file:logo.png\">file:logo.png</a></p>\
+         <figcaption><span class=\"figure-number\">Figure 1:</span> The logo</figcaption></figure>\
+         <table id=\"sales\"><caption><span class=\"table-number\">Table 2:</span> Sales</caption>\
+         <tbody><tr><td>b</td><td>2</td></tr></tbody></table>\
+         <table><tbody><tr><td>not</td><td>numbered</td></tr></tbody></table></section></main>"
+    );
+
+    // labels follow the language of the document
+    let text = "#+LANGUAGE: de\n\
+                #+CAPTION: Hallo\n\
+                #+BEGIN_SRC rust\n\
+                fn main() {}\n\
+                #+END_SRC\n";
+    assert_eq!(
+        to_html(text, HtmlConfig::default()),
+        "<main><section><figure><div class=\"org-src-container\"><pre>\
+         <code class=\"language-rust\">fn main() {}\n</code></pre></div>\
+         <figcaption><span class=\"listing-number\">Programmlisting 1:</span> Hallo\
+         </figcaption></figure></section></main>"
+    );
+}
+
+// passes copies of elements to the default handler
+#[derive(Default)]
+struct CopyingHandler(DefaultHtmlHandler);
+
+impl HtmlHandler<Error> for CopyingHandler {
+    fn prepare(&mut self, org: &Org) {
+        self.0.prepare(org);
+    }
+
+    fn start<W: Write>(&mut self, w: W, element: &Element) -> Result<(), Error> {
+        self.0.start(w, &element.clone())
+    }
+
+    fn end<W: Write>(&mut self, w: W, element: &Element) -> Result<(), Error> {
+        self.0.end(w, &element.clone())
+    }
+
+    fn start_node<W: Write>(&mut self, w: W, node: NodeId, element: &Element) -> Result<(), Error> {
+        self.0.start_node(w, node, &element.clone())
+    }
+
+    fn end_node<W: Write>(&mut self, w: W, node: NodeId, element: &Element) -> Result<(), Error> {
+        self.0.end_node(w, node, &element.clone())
+    }
+}
+
+#[test]
+fn state_of_nodes() {
+    let mut writer = Vec::new();
+    Org::parse("#+CAPTION: Prices\n| a |\n\n* Footnotes\n[fn:1] a\n")
+        .write_html_custom(&mut writer, &mut CopyingHandler::default())
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        "<main><section><table><caption><span class=\"table-number\">Table 1:</span> \
+         Prices</caption><tbody><tr><td>a</td></tr></tbody></table></section></main>"
+    );
+}
+
+fn publish() -> HtmlConfig {
+    HtmlConfig {
+        links: LinkConfig {
+            base: "https://example.com/notes/".into(),
+            org_extension: Some("html".into()),
+            ..Default::default()
+        },
+        ..Default::default()
+    }
+}
+
+#[test]
+fn rewrite_org_links() {
+    assert_eq!(
+        to_html(
+            "[[file:projects/orgize.org::*Release notes (v0.9)][notes]] \
+             [[file:../todo.org::#inbox]] [[file:/srv/index.org]] [[*Getting started]]",
+            publish()
+        ),
+        "<main><section><p>\
+         <a href=\"https://example.com/notes/projects/orgize.html#release-notes-v0-9\">notes</a> \
+         <a href=\"https://example.com/notes/../todo.html#inbox\">file:../todo.org::#inbox</a> \
+         <a href=\"/srv/index.html\">file:/srv/index.org</a> \
+         <a href=\"#getting-started\">*Getting started</a>\
+         </p></section></main>"
+    );
+
+    // links are written as-is by default
+    assert_eq!(
+        to_html("[[file:a.org::*B]]", HtmlConfig::default()),
+        "<main><section><p><a href=\"file:a.org::*B\">file:a.org::*B</a></p></section></main>"
+    );
+}
+
+#[test]
+fn non_ascii_file_links() {
+    assert_eq!(
+        to_html("[[file:a日本]] [[file:日本.org]]", publish()),
+        "<main><section><p>\
+         <a href=\"https://example.com/notes/a日本\">file:a日本</a> \
+         <a href=\"https://example.com/notes/日本.html\">file:日本.org</a>\
+         </p></section></main>"
+    );
+}
+
+#[test]
+fn absolute_urls_untouched() {
+    assert_eq!(
+        to_html(
+            "[[https://orgmode.org/manual.org][manual]] [[mailto:a@example.com][mail]]",
+            publish()
+        ),
+        "<main><section><p><a href=\"https://orgmode.org/manual.org\">manual</a> \
+         <a href=\"mailto:a@example.com\">mail</a></p></section></main>"
+    );
+}
+
+#[test]
+fn link_hook() {
+    let config = HtmlConfig {
+        links: publish().links.with_hook(|link| {
+            let id = link.path.strip_prefix("id:")?;
+            Some(format!("/by-id/{}", id))
+        }),
+        ..Default::default()
+    };
+    assert_eq!(
+        to_html("[[id:4f1c][meeting]] [[./img/cat.png]]", config),
+        "<main><section><p><a href=\"/by-id/4f1c\">meeting</a> \
+         <a href=\"https://example.com/notes/img/cat.png\">./img/cat.png</a></p></section></main>"
+    );
+}
+
+#[test]
+fn target_anchors() {
+    let text = "* Setup\n\
+                Run the <<installer>> first, then read <<<the manual>>>.\n\
+                * Usage\n\
+                See [[installer]] and [[the manual]].\n\
+                Another <<installer>>.\n";
+    assert_eq!(
+        to_html(text, HtmlConfig::default()),
+        "<main><h1>Setup</h1><section><p>Run the <a id=\"installer\"></a> first, then read \
+         <a id=\"the-manual\">the manual</a>.</p></section>\
+         <h1>Usage</h1><section><p>See <a href=\"#installer\">installer</a> and \
+         <a href=\"#the-manual\">the manual</a>.\nAnother <a id=\"installer-2\"></a>.</p>\
+         </section></main>"
+    );
+}
+
+#[test]
+fn heading_anchors() {
+    let text = "* Intro\n\
+                * Notes\n\
+                * Notes\n\
+                :PROPERTIES:\n\
+                :CUSTOM_ID: later\n\
+                :END:\n\
+                * Links\n\
+                [[*Intro]] [[Notes]] [[#later]]\n";
+    assert_eq!(
+        to_html(text, HtmlConfig::default()),
+        "<main><h1 id=\"intro\">Intro</h1><h1 id=\"notes\">Notes</h1>\
+         <h1 id=\"later\">Notes</h1><h1>Links</h1><section><p>\
+         <a href=\"#intro\">*Intro</a> <a href=\"#notes\">Notes</a> \
+         <a href=\"#later\">#later</a></p></section></main>"
+    );
+}
+
+#[test]
+fn heading_keywords_priorities_and_tags() {
+    let text = "* TODO [#A] Write report :work:q@1:\n* DONE Ship\n";
+
+    assert_eq!(
+        to_html(text, HtmlConfig::default()),
+        "<main><h1>Write report</h1><h1>Ship</h1></main>"
+    );
+
+    let heading = "<main><h1><span class=\"todo TODO\">TODO</span> \
+                   <span class=\"priority\">[#A]</span> Write report\
+                   &#xa0;&#xa0;&#xa0;<span class=\"tag\"><span class=\"work\">work</span>\
+                   &#xa0;<span class=\"q_1\">q@1</span></span></h1>\
+                   <h1><span class=\"done DONE\">DONE</span> Ship</h1></main>";
+    assert_eq!(
+        to_html(
+            text,
+            HtmlConfig {
+                todo_keywords: true,
+                priorities: true,
+                tags: true,
+                ..Default::default()
+            }
+        ),
+        heading
+    );
+    assert_eq!(
+        to_html(
+            &format!("#+OPTIONS: todo:t pri:t tags:t toc:nil\n{}", text),
+            HtmlConfig::default()
+        ),
+        heading
+    );
+
+    // options of the document win
+    assert_eq!(
+        to_html(
+            &format!("#+OPTIONS: todo:nil pri:nil\n#+OPTIONS: tags:nil\n{}", text),
+            HtmlConfig {
+                todo_keywords: true,
+                priorities: true,
+                tags: true,
+                ..Default::default()
+            }
+        ),
+        "<main><h1>Write report</h1><h1>Ship</h1></main>"
+    );
+}
+
+#[test]
+fn special_strings() {
+    let text = "Pages 1--2 --- or so... soft\\-hyphen, ---- <a> & =a--b=\n";
+
+    assert_eq!(
+        to_html(text, HtmlConfig::default()),
+        "<main><section><p>Pages 1--2 --- or so... soft\\-hyphen, ---- &lt;a&gt; &amp; \
+         <code>a--b</code></p></section></main>"
+    );
+
+    let html = |entity_output| {
+        to_html(
+            &format!("#+OPTIONS: -:t\n{}", text),
+            HtmlConfig {
+                entity_output,
+                ..Default::default()
+            },
+        )
+    };
+    assert_eq!(
+        html(EntityOutput::Utf8),
+        "<main><section><p>Pages 1\u{2013}2 \u{2014} or so\u{2026} soft\u{ad}hyphen, \
+         ---- &lt;a&gt; &amp; <code>a--b</code></p></section></main>"
+    );
+    assert_eq!(
+        html(EntityOutput::Named),
+        "<main><section><p>Pages 1&ndash;2 &mdash; or so&hellip; soft&shy;hyphen, \
+         ---- &lt;a&gt; &amp; <code>a--b</code></p></section></main>"
+    );
+    assert_eq!(
+        html(EntityOutput::Numeric),
+        "<main><section><p>Pages 1&#x2013;2 &#x2014; or so&#x2026; soft&#xad;hyphen, \
+         ---- &lt;a&gt; &amp; <code>a--b</code></p></section></main>"
+    );
+
+    let config = HtmlConfig {
+        special_strings: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        to_html("#+OPTIONS: -:nil\n1--2", config.clone()),
+        "<main><section><p>1--2</p></section></main>"
+    );
+    assert_eq!(
+        to_html("1--2", config.clone()),
+        "<main><section><p>1\u{2013}2</p></section></main>"
+    );
+
+    // only dashes between two other characters are converted
+    assert_eq!(
+        to_html("a----b a---b --c d--", config),
+        "<main><section><p>a----b a\u{2014}b \u{2013}c d--</p></section></main>"
+    );
+}
+
+// `html` without whitespace around tags
+fn structure(html: &str) -> String {
+    let mut structure = String::new();
+    for (i, part) in html.split('<').enumerate() {
+        if i != 0 {
+            structure.push('<');
+        }
+        match part.split_once('>') {
+            Some((tag, text)) => {
+                structure.push_str(tag);
+                structure.push('>');
+                structure.push_str(text.trim());
+            }
+            None => structure.push_str(part.trim()),
+        }
+    }
+    structure
+}
+
+#[test]
+fn ox_html_structure() {
+    let config = HtmlConfig {
+        ox_html_structure: true,
+        ..Default::default()
+    };
+
+    // captured from the content div of an export by Emacs
+    let html = to_html(include_str!("fixtures/outline.org"), config.clone());
+    assert_eq!(
+        structure(&html),
+        structure(include_str!("fixtures/outline.html"))
+    );
+
+    // ids are made from titles, unless there's a `CUSTOM_ID` property
+    let html = to_html(
+        "* Read me\n[[*Read me][again]] and [[Read me]]\n** Read me\n* Done\n",
+        config,
+    );
+    assert_eq!(
+        html,
+        "<div id=\"content\" class=\"content\">\
+         <div id=\"outline-container-read-me\" class=\"outline-2\">\
+         <h2 id=\"read-me\">Read me</h2>\
+         <div class=\"outline-text-2\" id=\"text-read-me\">\
+         <p><a href=\"#read-me\">again</a> and <a href=\"#read-me\">Read me</a></p></div>\
+         <div id=\"outline-container-read-me-2\" class=\"outline-3\">\
+         <h3 id=\"read-me-2\">Read me</h3></div></div>\
+         <div id=\"outline-container-done\" class=\"outline-2\">\
+         <h2 id=\"done\">Done</h2></div></div>"
+    );
+}
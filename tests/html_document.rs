@@ -0,0 +1,226 @@
+use orgize::{
+    export::{HtmlDocumentConfig, Translations},
+    Org,
+};
+use pretty_assertions::assert_eq;
+
+const TEXT: &str = r#"#+TITLE: Notes & <ideas>
+#+SUBTITLE: Draft
+#+AUTHOR: Jane "JD" Doe
+#+DESCRIPTION: Things to remember
+#+LANGUAGE: de
+* Intro
+Hello.
+** Details
+* Usage
+"#;
+
+fn document(config: &HtmlDocumentConfig) -> String {
+    let mut writer = Vec::new();
+    Org::parse(TEXT)
+        .write_html_document(&mut writer, config)
+        .unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+const VOID_ELEMENTS: &[&str] = &["br", "hr", "img", "input", "link", "meta"];
+
+// A lenient html parser, which checks that every non-void element is
+// closed in order, and returns names of elements in document order.
+fn parse_html(html: &str) -> Vec<String> {
+    let html = html
+        .strip_prefix("<!DOCTYPE html>")
+        .expect("missing doctype");
+    let mut stack: Vec<String> = Vec::new();
+    let mut elements = Vec::new();
+    let mut rest = html;
+
+    while let Some(start) = rest.find('<') {
+        let end = rest[start..].find('>').expect("unclosed tag") + start;
+        let tag = &rest[start + 1..end];
+        assert!(!tag.contains('<'), "malformed tag `{}`", tag);
+
+        if let Some(name) = tag.strip_prefix('/') {
+            assert_eq!(
+                stack.pop().as_deref(),
+                Some(name),
+                "mismatched `</{}>`",
+                name
+            );
+        } else {
+            let name = tag.split_whitespace().next().unwrap().to_ascii_lowercase();
+            assert_eq!(
+                tag.matches('"').count() % 2,
+                0,
+                "unbalanced quotes in `{}`",
+                tag
+            );
+            if !VOID_ELEMENTS.contains(&&*name) {
+                stack.push(name.clone());
+            }
+            elements.push(name);
+        }
+        rest = &rest[end + 1..];
+    }
+
+    assert!(stack.is_empty(), "unclosed elements {:?}", stack);
+    elements
+}
+
+#[test]
+fn golden() {
+    assert_eq!(
+        document(&HtmlDocumentConfig::default()),
+        r#"<!DOCTYPE html>
+<html lang="de">
+<head>
+<meta charset="utf-8">
+<title>Notes &amp; &lt;ideas&gt;</title>
+<meta name="author" content="Jane &quot;JD&quot; Doe">
+<meta name="description" content="Things to remember">
+</head>
+<body>
+<div id="content">
+<h1 class="title">Notes &amp; &lt;ideas&gt;<br><span class="subtitle">Draft</span></h1>
+<main><h2>Intro</h2><section><p>Hello.</p></section><h3>Details</h3><h2>Usage</h2></main>
+</div>
+</body>
+</html>
+"#
+    );
+}
+
+#[test]
+fn valid_document() {
+    let config = HtmlDocumentConfig {
+        title: Some("Override".into()),
+        language: Some("en".into()),
+        stylesheets: vec!["style.css?a=1&b=2".into()],
+        style: Some("body { color: black; }".into()),
+        head: "<script src=\"app.js\"></script>".into(),
+        container: None,
+        toc: true,
+        translations: Translations::builtin(),
+        preamble: Some("<nav>Home</nav>".into()),
+        postamble: Some("<p class=\"author\">%a</p>".into()),
+        creator: "orgize".into(),
+        export_time: None,
+    };
+    let html = document(&config);
+
+    assert_eq!(
+        parse_html(&html),
+        [
+            "html", "head", "meta", "title", "meta", "meta", "link", "style", "script", "body",
+            "div", "nav", "h1", "br", "span", "nav", "h2", "ul", "li", "a", "ul", "li", "a", "li",
+            "a", "main", "h2", "section", "p", "h3", "h2", "div", "p"
+        ]
+    );
+    assert!(html.contains("<html lang=\"en\">"));
+    assert!(html.contains("<title>Override</title>"));
+    assert!(html.contains("<link rel=\"stylesheet\" href=\"style.css?a=1&amp;b=2\">"));
+    assert!(html.contains(
+        "<nav id=\"table-of-contents\"><h2>Table of Contents</h2>\
+         <ul><li><a href=\"#intro\">1 Intro</a>\
+         <ul><li><a href=\"#details\">1.1 Details</a></li></ul></li>\
+         <li><a href=\"#usage\">2 Usage</a></li></ul></nav>"
+    ));
+    assert!(html.contains(
+        "<main><h2 id=\"intro\">Intro</h2><section><p>Hello.</p></section>\
+         <h3 id=\"details\">Details</h3><h2 id=\"usage\">Usage</h2></main>"
+    ));
+    assert_eq!(html.matches("<h1").count(), 1);
+    assert!(!html.contains("<div id=\"content\">"));
+}
+
+#[test]
+fn without_metadata() {
+    let mut writer = Vec::new();
+    Org::parse("text")
+        .write_html_document(&mut writer, &HtmlDocumentConfig::default())
+        .unwrap();
+    let html = String::from_utf8(writer).unwrap();
+
+    assert!(html.starts_with("<!DOCTYPE html>\n<html lang=\"en\">"));
+    assert!(!html.contains("<title>"));
+    assert_eq!(
+        parse_html(&html),
+        ["html", "head", "meta", "body", "div", "main", "section", "p"]
+    );
+}
+
+#[test]
+fn translated_headings() {
+    let org = Org::parse(
+        "#+LANGUAGE: de\n\
+         * Intro\n\
+         Text.[fn:1]\n\
+         * Footnotes\n\
+         [fn:1] Fußnote.\n",
+    );
+    let config = HtmlDocumentConfig {
+        toc: true,
+        ..Default::default()
+    };
+    let mut writer = Vec::new();
+    org.write_html_document(&mut writer, &config).unwrap();
+    let html = String::from_utf8(writer).unwrap();
+
+    assert!(html.contains("<nav id=\"table-of-contents\"><h2>Inhaltsverzeichnis</h2>"));
+    assert!(html.contains("<div id=\"footnotes\"><h2 class=\"footnotes\">Fußnoten</h2>"));
+    assert!(!html.contains("<h1>Footnotes"));
+
+    // user translations replace built-in ones
+    let mut config = config;
+    config.translations.insert(
+        "de".into(),
+        Translations {
+            table_of_contents: "Inhalt".into(),
+            ..Default::default()
+        },
+    );
+    let mut writer = Vec::new();
+    org.write_html_document(&mut writer, &config).unwrap();
+    assert!(String::from_utf8(writer)
+        .unwrap()
+        .contains("<h2>Inhalt</h2>"));
+}
+
+#[test]
+fn postamble() {
+    let config = HtmlDocumentConfig {
+        postamble: Some("<p>%a, %d, 100% %c%% at %T%x%</p>".into()),
+        creator: "orgize <test>".into(),
+        export_time: Some("2024-03-08 10:00".into()),
+        ..Default::default()
+    };
+    let mut writer = Vec::new();
+    Org::parse("#+AUTHOR: Jane\n#+DATE: <2024-03-01 Fri>\ntext\n")
+        .write_html_document(&mut writer, &config)
+        .unwrap();
+    let html = String::from_utf8(writer).unwrap();
+
+    assert!(html.ends_with(
+        "<main><section><p>text</p></section></main>\n\
+         </div>\n\
+         <div id=\"postamble\" class=\"status\">\
+         <p>Jane, &lt;2024-03-01 Fri&gt;, 100% orgize &lt;test&gt;% at 2024-03-08 10:00%x%</p>\
+         </div>\n\
+         </body>\n\
+         </html>\n"
+    ));
+
+    // placeholders without a value are removed
+    let config = HtmlDocumentConfig {
+        preamble: Some("by %a%T".into()),
+        container: None,
+        ..Default::default()
+    };
+    let mut writer = Vec::new();
+    Org::parse("text\n")
+        .write_html_document(&mut writer, &config)
+        .unwrap();
+    let html = String::from_utf8(writer).unwrap();
+    assert!(html.contains("<body>\n<div id=\"preamble\" class=\"status\">by </div>\n<main>"));
+    assert!(!html.contains("postamble"));
+}
@@ -0,0 +1,128 @@
+use orgize::{
+    export::{DefaultHtmlHandler, HtmlConfig, ImageConfig},
+    Org,
+};
+use pretty_assertions::assert_eq;
+
+fn html(text: &str, images: ImageConfig) -> String {
+    let config = HtmlConfig {
+        images,
+        ..Default::default()
+    };
+    let mut writer = Vec::new();
+    Org::parse(text)
+        .write_html_custom(&mut writer, &mut DefaultHtmlHandler::new(config))
+        .unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+// images kept in memory, e.g. attachments of a note
+fn resolver() -> ImageConfig {
+    ImageConfig::default().with_resolver(|path| match path {
+        "img/pixel.png" => Some((
+            String::from("image/png"),
+            vec![0x89, b'P', b'N', b'G', 0x0d, 0x0a, 0x1a, 0x0a],
+        )),
+        "logo.svg" => Some((String::from("image/svg+xml"), b"<svg/>".to_vec())),
+        _ => None,
+    })
+}
+
+#[test]
+fn data_uri() {
+    assert_eq!(
+        html(
+            "[[file:img/pixel.png]] [[./logo.svg]]\n",
+            ImageConfig {
+                max_size: 8,
+                ..resolver()
+            }
+        ),
+        "<main><section><p>\
+         <img src=\"data:image/png;base64,iVBORw0KGgo=\" alt=\"pixel.png\"> \
+         <img src=\"./logo.svg\" alt=\"logo.svg\">\
+         </p></section></main>"
+    );
+    assert_eq!(
+        html("[[logo.svg]]\n", resolver()),
+        "<main><section><p><a href=\"logo.svg\">logo.svg</a></p></section></main>"
+    );
+    assert_eq!(
+        html("[[file:logo.svg]]\n", resolver()),
+        "<main><section><p>\
+         <img src=\"data:image/svg+xml;base64,PHN2Zy8+\" alt=\"logo.svg\">\
+         </p></section></main>"
+    );
+}
+
+#[test]
+fn declined() {
+    // too large
+    assert_eq!(
+        html(
+            "[[file:logo.svg]]\n",
+            ImageConfig {
+                max_size: 4,
+                ..resolver()
+            }
+        ),
+        "<main><section><p><img src=\"logo.svg\" alt=\"logo.svg\"></p></section></main>"
+    );
+
+    // not an image link
+    assert_eq!(
+        html(
+            "[[file:img/pixel.png][pixel]] [[file:notes.org]]\n",
+            resolver()
+        ),
+        "<main><section><p><a href=\"file:img/pixel.png\">pixel</a> \
+         <a href=\"file:notes.org\">file:notes.org</a></p></section></main>"
+    );
+
+    // no resolver
+    assert_eq!(
+        html("[[file:img/pixel.png]]\n", ImageConfig::default()),
+        "<main><section><p><a href=\"file:img/pixel.png\">file:img/pixel.png</a></p></section></main>"
+    );
+}
+
+#[cfg(feature = "fs")]
+#[test]
+fn files() {
+    let dir = std::env::temp_dir().join("orgize-images");
+    std::fs::create_dir_all(&dir).unwrap();
+    std::fs::write(dir.join("dot.gif"), b"GIF89a").unwrap();
+
+    assert_eq!(
+        html(
+            "[[./dot.gif]] [[./missing.gif]]\n",
+            ImageConfig::default().with_files(&dir)
+        ),
+        "<main><section><p>\
+         <img src=\"data:image/gif;base64,R0lGODlh\" alt=\"dot.gif\"> \
+         <img src=\"./missing.gif\" alt=\"missing.gif\">\
+         </p></section></main>"
+    );
+
+    // files outside of the base directory are linked, not read
+    let base = dir.join("base");
+    std::fs::create_dir_all(&base).unwrap();
+    let absolute = dir.join("dot.gif");
+    assert_eq!(
+        html(
+            &format!(
+                "[[../dot.gif]] [[file:{}]] [[~/dot.gif]]\n",
+                absolute.display()
+            ),
+            ImageConfig::default().with_files(&base)
+        ),
+        format!(
+            "<main><section><p>\
+             <img src=\"../dot.gif\" alt=\"dot.gif\"> \
+             <img src=\"{0}\" alt=\"dot.gif\"> \
+             <img src=\"~/dot.gif\" alt=\"dot.gif\">\
+             </p></section></main>",
+            absolute.display()
+        )
+    );
+}
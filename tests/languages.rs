@@ -0,0 +1,107 @@
+use orgize::{Element, ExtractConfig, LanguageUsage, Org};
+use pretty_assertions::assert_eq;
+
+const TEXT: &str = include_str!("fixtures/languages.org");
+
+#[test]
+fn languages() {
+    let org = Org::parse(TEXT);
+    assert_eq!(
+        org.languages(),
+        [
+            LanguageUsage {
+                language: "python".into(),
+                blocks: 1,
+                lines: 1,
+                inline: 1,
+            },
+            LanguageUsage {
+                language: "rust".into(),
+                blocks: 3,
+                lines: 9,
+                inline: 1,
+            },
+        ]
+    );
+}
+
+#[test]
+fn extract_and_map_back() {
+    let mut org = Org::parse(TEXT);
+    let config = ExtractConfig {
+        separator: String::from("// ==> %f:%l"),
+        file_name: String::from("languages.org"),
+    };
+    let extracted = org.extract_language("rust", &config);
+    assert_eq!(
+        extracted.code,
+        "// ==> languages.org:6\n\
+         struct Point {\n    x: i32,\n}\n\
+         // ==> languages.org:16\n\
+         fn origin() -> Point {\n    Point { x : 0 }\n}\n\
+         // ==> languages.org:27\n\
+         fn main() {\n    let p = origin();\n}\n"
+    );
+    assert_eq!(extracted.blocks.len(), 3);
+
+    // a linter reports `x : 0` on line 7 of the extracted code, i.e. the
+    // second line of the second block
+    let (block, line) = extracted.origin(6).unwrap();
+    assert_eq!((block.node, line), (extracted.blocks[1].node, 1));
+    let source_line = extracted.source_line(6).unwrap();
+    assert_eq!(TEXT.lines().nth(source_line), Some("      Point { x : 0 }"));
+    assert_eq!(extracted.origin(4), None);
+
+    // writes fixed code back into the block
+    let fixed = extracted.code.replace("x : 0", "x: 0");
+    for block in &extracted.blocks {
+        let contents: String = fixed
+            .lines()
+            .skip(block.line)
+            .take(block.lines)
+            .map(|line| format!("{}\n", line))
+            .collect();
+        if let Element::SourceBlock(source_block) = &mut org[block.node] {
+            source_block.set_contents(&contents);
+        }
+    }
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        TEXT.replace("x : 0", "x: 0")
+    );
+    assert_eq!(org.extract_language("rust", &config).code, fixed);
+}
+
+#[test]
+fn default_separator() {
+    let org = Org::parse("#+BEGIN_SRC sh\necho 1\n#+END_SRC\n");
+    let extracted = org.extract_language("sh", &ExtractConfig::default());
+    assert_eq!(extracted.code, "# ==> :2\necho 1\n");
+    assert_eq!(extracted.source_line(1), Some(1));
+    assert!(org
+        .extract_language("rust", &ExtractConfig::default())
+        .blocks
+        .is_empty());
+}
+
+#[test]
+fn set_empty_contents() {
+    let mut org = Org::parse("- item\n  #+BEGIN_SRC sh\n  #+END_SRC\n");
+    let node = org
+        .events()
+        .find(|event| matches!(event.event.element(), Element::SourceBlock(_)))
+        .unwrap()
+        .node;
+    if let Element::SourceBlock(block) = &mut org[node] {
+        block.set_contents("echo 1\n");
+    }
+
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        "- item\n  #+BEGIN_SRC sh\n  echo 1\n  #+END_SRC\n"
+    );
+}
@@ -0,0 +1,136 @@
+use orgize::{Element, Event, Org, ParseConfig, ParseError, Strictness};
+use pretty_assertions::assert_eq;
+
+fn org(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+fn count(org: &Org, f: impl Fn(&Element) -> bool) -> usize {
+    org.iter()
+        .filter(|event| matches!(event, Event::Start(element) if f(element)))
+        .count()
+}
+
+#[test]
+fn long_lines() {
+    let long = format!("* {}", "*bold* ".repeat(2000));
+    let text = format!(
+        "* Title\n{}\n#+BEGIN_QUOTE {}\nquote\n#+END_QUOTE\n",
+        long, long
+    );
+    let config = ParseConfig::builder()
+        .max_line_length(1000)
+        .build()
+        .unwrap();
+
+    // by default, a headline and a block full of bold objects
+    let full = Org::parse(&text);
+    assert_eq!(full.headlines().count(), 2);
+    assert_eq!(count(&full, |e| matches!(e, Element::Bold)), 2000);
+
+    // long lines become text of the paragraph they're in
+    let limited = Org::parse_custom(&text, &config);
+    assert_eq!(limited.headlines().count(), 1);
+    assert_eq!(count(&limited, |e| matches!(e, Element::Bold)), 0);
+    assert_eq!(count(&limited, |e| matches!(e, Element::QuoteBlock(_))), 0);
+    assert_eq!(
+        count(&limited, |e| matches!(e, Element::Paragraph { .. })),
+        1
+    );
+    assert_eq!(org(&limited), text);
+
+    // short lines are parsed as usual
+    let text = "* Title\n*bold*\n#+BEGIN_QUOTE\nquote\n#+END_QUOTE\n";
+    assert_eq!(
+        org(&Org::parse_custom(text, &config)),
+        org(&Org::parse(text))
+    );
+}
+
+#[test]
+fn objects_per_paragraph() {
+    let text = format!("{}\n", "a *b* ".repeat(10_000).trim_end());
+    let config = ParseConfig::builder().max_objects(100).build().unwrap();
+
+    let parsed = Org::parse_custom(&text, &config);
+    assert_eq!(count(&parsed, |e| matches!(e, Element::Bold)), 100);
+    // text before each object, and the rest in one
+    assert_eq!(
+        count(&parsed, |e| matches!(e, Element::Text { .. })),
+        100 * 2 + 1
+    );
+    let last = parsed
+        .iter()
+        .filter_map(|event| match event {
+            Event::Start(Element::Text { value }) => Some(value.len()),
+            _ => None,
+        })
+        .last()
+        .unwrap();
+    assert_eq!(last, text.trim_end().len() - 100 * "a *b* ".len() + 1);
+    assert_eq!(org(&parsed), text);
+    assert!(parsed.arena().len() < 1_000);
+
+    // limits nested objects too
+    let nested = Org::parse_custom(
+        "*a =b= =c= =d=*\n",
+        &ParseConfig {
+            max_objects: Some(1),
+            ..Default::default()
+        },
+    );
+    assert_eq!(count(&nested, |e| matches!(e, Element::Bold)), 1);
+    assert_eq!(count(&nested, |e| matches!(e, Element::Verbatim { .. })), 1);
+}
+
+#[test]
+fn nodes_in_arena() {
+    let text: String = (0..1_000)
+        .map(|i| format!("* Headline {}\n- item *{}*\n- item\n\n", i, i))
+        .collect();
+    let limit = 2_000;
+
+    let full = Org::parse(&text);
+    assert!(full.arena().len() > 10_000);
+
+    let lenient = Org::parse_custom(
+        &text,
+        &ParseConfig {
+            max_nodes: Some(limit),
+            ..Default::default()
+        },
+    );
+    // each headline adds a title, a section, a paragraph and a text at most
+    assert!(lenient.arena().len() < limit + 5 * 1_000);
+    assert_eq!(lenient.headlines().count(), 1_000);
+    assert_eq!(lenient.diagnostics(), []);
+    assert_eq!(org(&lenient), text);
+
+    let strict = ParseConfig {
+        max_nodes: Some(limit),
+        strictness: Strictness::Strict,
+        ..Default::default()
+    };
+    let error = Org::try_parse_custom(&text, &strict).err().unwrap();
+    assert!(matches!(
+        error,
+        ParseError::TooManyNodes { limit: 2_000, .. }
+    ));
+    // reported once, at the first contents left unparsed: titles are parsed
+    // after every headline, so this is the title of the first one
+    assert_eq!(error.offset(), "* ".len());
+    let unparsed = Org::parse_custom(&text, &strict);
+    assert_eq!(unparsed.diagnostics(), [error]);
+    assert!(unparsed.arena().len() >= limit);
+
+    assert!(Org::try_parse_custom(
+        "* a\n",
+        &ParseConfig {
+            max_nodes: Some(limit),
+            ..strict
+        }
+    )
+    .is_ok());
+}
@@ -0,0 +1,70 @@
+use orgize::{LinkIssueKind, Org};
+use pretty_assertions::assert_eq;
+
+const TEXT: &str = r#"#+TITLE: Links
+
+* Introduction
+:PROPERTIES:
+:CUSTOM_ID: intro
+:ID: 4f1c
+:END:
+
+<<anchor>> and <<anchor>> again.
+
+See [[Introduction]], [[anchor]], [[Missing]] and [[*Nowhere]].
+
+* Details
+:PROPERTIES:
+:CUSTOM_ID: intro
+:END:
+
+#+NAME: table
+| a |
+
+[[table]] [[#intro]] [[#outro]] [[id:4f1c]] [[id:dead]]
+[[file:notes.org]] [[file:::search]] [[https://example.com]]
+
+Footnotes [fn:1] [fn:2] [fn:inline: defined here] [fn::anonymous].
+
+[fn:1] Defined.
+
+[fn:3] Unused.
+"#;
+
+#[test]
+fn issues() {
+    let org = Org::parse(TEXT);
+    let issues = org.check_links();
+
+    assert_eq!(
+        issues.iter().map(|i| i.kind.clone()).collect::<Vec<_>>(),
+        vec![
+            LinkIssueKind::DuplicateTarget("anchor".into()),
+            LinkIssueKind::UnresolvedFuzzy("Missing".into()),
+            LinkIssueKind::UnresolvedHeadline("Nowhere".into()),
+            LinkIssueKind::DuplicateCustomId("intro".into()),
+            LinkIssueKind::UnresolvedCustomId("outro".into()),
+            LinkIssueKind::UnresolvedId("dead".into()),
+            LinkIssueKind::InvalidFileLink("file:::search".into()),
+            LinkIssueKind::MissingFootnoteDefinition("2".into()),
+            LinkIssueKind::UnusedFootnoteDefinition("3".into()),
+        ]
+    );
+
+    assert_eq!(
+        issues[1].message,
+        "link `Missing` matches no target, name or headline"
+    );
+
+    let span = issues[1].span.clone().unwrap();
+    assert_eq!(&TEXT[span], "[[Missing]]");
+
+    let span = issues[0].span.clone().unwrap();
+    assert_eq!(span.start, TEXT.rfind("<<anchor>>").unwrap());
+}
+
+#[test]
+fn clean() {
+    let org = Org::parse("* A\n<<b>> [[A]] [[b]] [fn:1]\n\n[fn:1] c\n");
+    assert_eq!(org.check_links(), vec![]);
+}
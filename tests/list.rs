@@ -0,0 +1,253 @@
+use orgize::{
+    elements::{Element, List, ListItem},
+    export::DefaultOrgHandler,
+    Event, Org,
+};
+use pretty_assertions::assert_eq;
+
+// one line per block element, indented by depth
+fn outline(org: &Org) -> String {
+    let mut outline = String::new();
+    for event in org.events() {
+        let name = match event.event {
+            Event::Start(Element::List(list)) => format!("list {}", list.post_blank),
+            Event::Start(Element::ListItem(_)) => String::from("item"),
+            Event::Start(Element::Paragraph { post_blank }) => format!("paragraph {}", post_blank),
+            Event::Start(Element::SourceBlock(block)) => format!("src {}", block.post_blank),
+            Event::Start(Element::QuoteBlock(block)) => format!("quote {}", block.post_blank),
+            Event::Start(Element::Table(_)) => String::from("table"),
+            _ => continue,
+        };
+        outline.push_str(&"  ".repeat(event.depth - 2));
+        outline.push_str(&name);
+        outline.push('\n');
+    }
+    outline
+}
+
+fn round_trip(text: &str) {
+    let mut writer = Vec::new();
+    Org::parse(text).write_org(&mut writer).unwrap();
+    assert_eq!(String::from_utf8(writer).unwrap(), text);
+}
+
+#[test]
+fn item_with_code_block() {
+    let text = "- item\n  #+BEGIN_SRC rust\nfn main() {\n\n\n}\n  #+END_SRC\n- next\n";
+    let org = Org::parse(text);
+    assert_eq!(
+        outline(&org),
+        "list 0\n  item\n    paragraph 0\n    src 0\n  item\n    paragraph 0\n"
+    );
+}
+
+fn html(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_html(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+#[test]
+fn src_block_between_items() {
+    let text = "- item\n  #+BEGIN_SRC rust\n    fn main() {}\n  #+END_SRC\n- next\n";
+    let org = Org::parse(text);
+    assert_eq!(
+        outline(&org),
+        "list 0\n  item\n    paragraph 0\n    src 0\n  item\n    paragraph 0\n"
+    );
+    assert_eq!(
+        html(&org),
+        "<main><section><ul><li><p>item</p><div class=\"org-src-container\">\
+         <pre><code class=\"language-rust\">fn main() {}\n</code></pre></div></li>\
+         <li><p>next</p></li></ul></section></main>"
+    );
+    round_trip(text);
+}
+
+#[test]
+fn nested_list_after_block() {
+    let text = "1. one\n   #+BEGIN_QUOTE\n   quoted\n   #+END_QUOTE\n   - nested\n     | a |\n     |---|\n     | 1 |\n     #+TBLFM: @2$1=1\n   #+BEGIN_EXAMPLE\n     example\n   #+END_EXAMPLE\n2. two\n#+BEGIN_EXAMPLE\nafter\n#+END_EXAMPLE\n";
+    let org = Org::parse(text);
+    assert_eq!(
+        outline(&org),
+        "list 0\n  item\n    paragraph 0\n    quote 0\n      paragraph 0\n    list 0\n      item\n        paragraph 0\n        table\n  item\n    paragraph 0\n"
+    );
+    assert_eq!(
+        html(&org),
+        "<main><section><ol><li><p>one</p><blockquote><p>   quoted</p></blockquote>\
+         <ul><li><p>nested</p><table><thead><tr><th>a</th></tr></thead>\
+         <tbody><tr><td>1</td></tr></tbody></table></li></ul>\
+         <pre class=\"example\">example\n</pre></li><li><p>two</p></li></ol>\
+         <pre class=\"example\">after\n</pre></section></main>"
+    );
+    round_trip(text);
+}
+
+#[test]
+fn indented_paragraph_after_blank_line() {
+    let text = "- a\n\n  continued\n- b\n";
+    let org = Org::parse(text);
+    assert_eq!(
+        outline(&org),
+        "list 0\n  item\n    paragraph 1\n    paragraph 0\n  item\n    paragraph 0\n"
+    );
+    round_trip(text);
+}
+
+#[test]
+fn two_blank_lines_end_list() {
+    let text = "- a\n\n\ntext\n";
+    let org = Org::parse(text);
+    assert_eq!(
+        outline(&org),
+        "list 2\n  item\n    paragraph 0\nparagraph 0\n"
+    );
+    round_trip(text);
+
+    let text = "- a\n\n\n- b\n";
+    let org = Org::parse(text);
+    assert_eq!(
+        outline(&org),
+        "list 2\n  item\n    paragraph 0\nlist 0\n  item\n    paragraph 0\n"
+    );
+    round_trip(text);
+
+    let text = "- a\n  - b\n\n\n  - c\n";
+    let org = Org::parse(text);
+    assert_eq!(
+        outline(&org),
+        "list 2\n  item\n    paragraph 0\n    list 0\n      item\n        paragraph 0\nlist 0\n  item\n    paragraph 0\n"
+    );
+    round_trip(text);
+}
+
+#[test]
+fn less_indented_line_continues_ancestor() {
+    let text = "- a\n  - b\n\n  text\n- c\n";
+    let org = Org::parse(text);
+    assert_eq!(
+        outline(&org),
+        "list 0\n  item\n    paragraph 0\n    list 1\n      item\n        paragraph 0\n    paragraph 0\n  item\n    paragraph 0\n"
+    );
+    round_trip(text);
+}
+
+#[test]
+fn post_blank() {
+    let text = "- a\n\n- b\n\n\n\ntext\n";
+    let org = Org::parse(text);
+    assert_eq!(
+        outline(&org),
+        "list 3\n  item\n    paragraph 0\n  item\n    paragraph 0\nparagraph 0\n"
+    );
+    round_trip(text);
+
+    let item = org
+        .events()
+        .find(|event| matches!(event.event, Event::Start(Element::ListItem(_))))
+        .unwrap();
+    assert_eq!(item.event.element().post_blank(), 1);
+    assert_eq!(org.span(item.node).map(|span| &text[span]), Some("a\n\n"));
+}
+
+fn numbered_list() -> String {
+    let mut text = String::new();
+    for i in 1..=12 {
+        text += &format!("{}. item {}\n", i, i);
+        match i {
+            8 => text += "   more of 8\n",
+            9 => text += "   more of 9\n   + nested\n     more of nested\n\n   paragraph of 9\n",
+            10 => text += "    more of 10\n",
+            _ => (),
+        }
+    }
+    text
+}
+
+#[test]
+fn renumber_after_insert() {
+    let text = numbered_list();
+    let mut org = Org::parse(&text);
+
+    // inserts `new` before the fifth item
+    let items: Vec<_> = org
+        .events()
+        .filter(|event| matches!(event.event, Event::Start(Element::ListItem(_))))
+        .map(|event| event.node)
+        .collect();
+    let arena = org.arena_mut();
+    let item = arena.new_node(Element::ListItem(ListItem {
+        bullet: "1. ".into(),
+        indent: 0,
+        ordered: true,
+        counter: None,
+        checkbox: None,
+        checkbox_separator: None,
+        post_blank: 0,
+    }));
+    let paragraph = arena.new_node(Element::Paragraph { post_blank: 0 });
+    let text_node = arena.new_node(Element::Text {
+        value: "new".into(),
+    });
+    item.append(paragraph, arena);
+    paragraph.append(text_node, arena);
+    items[4].insert_before(item, arena);
+
+    List::renumber(&mut org);
+
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        "1. item 1\n2. item 2\n3. item 3\n4. item 4\n5. new\n6. item 5\n7. item 6\n\
+         8. item 7\n9. item 8\n   more of 8\n\
+         10. item 9\n    more of 9\n    - nested\n      more of nested\n\n    paragraph of 9\n\
+         11. item 10\n    more of 10\n12. item 11\n13. item 12\n"
+    );
+}
+
+#[test]
+fn renumber_counter_and_bullets() {
+    let text = "3. [@10] a\n   text\n1. b\n   text\n2. [@3] c\n+ x\n\n\n- y\n";
+    let mut org = Org::parse(text);
+    List::renumber_with_bullet(&mut org, '+');
+
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        "10. [@10] a\n    text\n11. b\n    text\n3. [@3] c\n+ x\n\n\n+ y\n"
+    );
+}
+
+#[test]
+fn renumber_on_export() {
+    let text = numbered_list();
+    let org = Org::parse(&text);
+
+    // off by default
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    assert_eq!(String::from_utf8(writer).unwrap(), text);
+
+    let text = "9. a\n   b\n   + c\n     d\n\n   e\n9. f\n+ g\n";
+    let org = Org::parse(text);
+    let mut handler = DefaultOrgHandler::default();
+    handler.renumber_lists = true;
+    let mut writer = Vec::new();
+    org.write_org_custom(&mut writer, &mut handler).unwrap();
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        "1. a\n   b\n   - c\n     d\n\n   e\n2. f\n- g\n"
+    );
+
+    let org = Org::parse("8. [@9] a\n   b\n   + c\n     d\n\n   e\n1. f\n   g\n");
+    let mut handler = DefaultOrgHandler::default();
+    handler.renumber_lists = true;
+    let mut writer = Vec::new();
+    org.write_org_custom(&mut writer, &mut handler).unwrap();
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        "9. [@9] a\n   b\n   - c\n     d\n\n   e\n10. f\n    g\n"
+    );
+}
@@ -0,0 +1,144 @@
+use orgize::{Element, Org, ParseConfig};
+use pretty_assertions::assert_eq;
+
+const DRAWER_FIRST: &str = r#"* TODO a
+:PROPERTIES:
+:ID: 1
+:END:
+SCHEDULED: <2024-03-04 Mon>
+text
+"#;
+
+const BLANK_LINES: &str = r#"* TODO a
+
+SCHEDULED: <2024-03-04 Mon>
+
+:PROPERTIES:
+:ID: 1
+:END:
+text
+"#;
+
+fn lenient() -> ParseConfig {
+    ParseConfig {
+        lenient_headline_metadata: Some(1),
+        ..Default::default()
+    }
+}
+
+fn summary(org: &Org) -> (bool, Vec<(String, String)>, Vec<String>) {
+    let title = org.headlines().next().unwrap().title(org);
+    let section: Vec<_> = org
+        .headlines()
+        .next()
+        .unwrap()
+        .section_node()
+        .map(|section| {
+            section
+                .children(org.arena())
+                .map(|node| match &org[node] {
+                    Element::Drawer(drawer) => format!("drawer {}", drawer.name),
+                    element => format!("{:?}", element)
+                        .split(|c: char| !c.is_alphanumeric())
+                        .next()
+                        .unwrap()
+                        .to_string(),
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    (
+        title.planning.is_some(),
+        title
+            .properties
+            .iter()
+            .map(|(k, v)| (k.to_string(), v.to_string()))
+            .collect(),
+        section,
+    )
+}
+
+fn to_org(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+#[test]
+fn drawer_before_planning() {
+    let org = Org::parse(DRAWER_FIRST);
+    assert_eq!(
+        summary(&org),
+        (
+            false,
+            vec![("ID".into(), "1".into())],
+            vec!["Paragraph".into()]
+        )
+    );
+
+    let org = Org::parse_custom(DRAWER_FIRST, &lenient());
+    assert_eq!(
+        summary(&org),
+        (
+            true,
+            vec![("ID".into(), "1".into())],
+            vec!["Paragraph".into()]
+        )
+    );
+    assert_eq!(to_org(&org), DRAWER_FIRST);
+}
+
+#[test]
+fn blank_lines() {
+    let org = Org::parse(BLANK_LINES);
+    assert_eq!(
+        summary(&org),
+        (
+            false,
+            vec![],
+            vec![
+                "Paragraph".into(),
+                "drawer PROPERTIES".into(),
+                "Paragraph".into()
+            ]
+        )
+    );
+
+    let org = Org::parse_custom(BLANK_LINES, &lenient());
+    assert_eq!(
+        summary(&org),
+        (
+            true,
+            vec![("ID".into(), "1".into())],
+            vec!["Paragraph".into()]
+        )
+    );
+    assert_eq!(to_org(&org), BLANK_LINES);
+
+    // too many blank lines
+    let input = BLANK_LINES.replacen("\n\n", "\n\n\n", 1);
+    let org = Org::parse_custom(&input, &lenient());
+    assert_eq!(summary(&org).0, false);
+}
+
+#[test]
+fn canonical_after_modification() {
+    let mut org = Org::parse_custom(BLANK_LINES, &lenient());
+    let headline = org.headlines().next().unwrap();
+    headline
+        .title_mut(&mut org)
+        .properties
+        .pairs
+        .push(("CATEGORY".into(), "work".into()));
+
+    assert_eq!(
+        to_org(&org),
+        "* TODO a\n\
+         SCHEDULED: <2024-03-04 Mon>\n\
+         :PROPERTIES:\n\
+         :ID: 1\n\
+         :CATEGORY: work\n\
+         :END:\n\
+         text\n"
+    );
+}
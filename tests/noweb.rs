@@ -0,0 +1,95 @@
+use orgize::{elements::SourceBlock, Element, NowebError, Org};
+use pretty_assertions::assert_eq;
+
+fn expand_last_block(text: &str) -> Result<String, NowebError> {
+    let org = Org::parse(text);
+    let block = org
+        .arena()
+        .iter()
+        .filter_map(|node| match node.get() {
+            Element::SourceBlock(block) => Some(block),
+            _ => None,
+        })
+        .find(|block: &&SourceBlock| block.contents.contains("main"))
+        .unwrap();
+    block.expand_noweb(&org).map(Into::into)
+}
+
+#[test]
+fn indentation_in_list() {
+    assert_eq!(
+        expand_last_block(
+            "- item\n\
+             \x20 - nested\n\
+             \x20   #+NAME: body\n\
+             \x20   #+BEGIN_SRC python\n\
+             \x20   x = 1\n\
+             \x20   if x:\n\
+             \x20       y = 2\n\
+             \x20   #+END_SRC\n\
+             \x20   #+BEGIN_SRC python\n\
+             \x20   def main():\n\
+             \x20       <<body>>\n\
+             \x20       return y\n\
+             \x20   #+END_SRC\n"
+        ),
+        Ok("def main():\n    x = 1\n    if x:\n        y = 2\n    return y\n".into())
+    );
+}
+
+#[test]
+fn concatenation() {
+    assert_eq!(
+        expand_last_block(
+            "#+BEGIN_SRC sh :noweb-ref setup :noweb-sep \"\\n\\n\"\n\
+             export A=1\n\
+             #+END_SRC\n\
+             #+BEGIN_SRC sh :noweb-ref setup\n\
+             export B=2\n\
+             #+END_SRC\n\
+             #+BEGIN_SRC sh :noweb yes\n\
+             # main\n\
+             <<setup>>\n\
+             <<missing>> <<setup(x=1)>>\n\
+             #+END_SRC\n"
+        ),
+        Ok("# main\n\
+            export A=1\n\
+            \n\
+            export B=2\n\
+            <<missing>> export A=1\n\
+            <<missing>> \n\
+            <<missing>> export B=2\n"
+            .into())
+    );
+}
+
+#[test]
+fn cycle() {
+    assert_eq!(
+        expand_last_block(
+            "#+NAME: a\n\
+             #+BEGIN_SRC sh\n\
+             <<b>>\n\
+             #+END_SRC\n\
+             #+NAME: b\n\
+             #+BEGIN_SRC sh\n\
+             <<a>>\n\
+             #+END_SRC\n\
+             #+BEGIN_SRC sh\n\
+             main <<a>>\n\
+             #+END_SRC\n"
+        ),
+        Err(NowebError {
+            cycle: vec!["a".into(), "b".into(), "a".into()]
+        })
+    );
+}
+
+#[test]
+fn untouched() {
+    assert_eq!(
+        expand_last_block("#+BEGIN_SRC c\nint main() { return 1 << 2 >> 1; }\n#+END_SRC\n"),
+        Ok("int main() { return 1 << 2 >> 1; }\n".into())
+    );
+}
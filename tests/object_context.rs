@@ -0,0 +1,106 @@
+use orgize::{
+    elements::{Keyword, ObjectContext, Objects},
+    export::{DefaultHtmlHandler, HtmlConfig},
+    Element, Event, Org,
+};
+use pretty_assertions::assert_eq;
+
+const LINK: &str = "[[https://orgmode.org][Org]]";
+
+fn links(objects: &Objects) -> usize {
+    objects
+        .iter()
+        .filter(|event| matches!(event, Event::Start(Element::Link(_))))
+        .count()
+}
+
+fn keyword<'a>(key: &'a str, value: &'a str) -> Keyword<'a> {
+    Keyword {
+        key: key.into(),
+        optional: None,
+        value: value.into(),
+        post_blank: 0,
+    }
+}
+
+#[test]
+fn property_value_stays_literal() {
+    let text = format!(
+        "#+PROPERTY: url {0}\n* Title\n:PROPERTIES:\n:URL: {0}\n:END:\n\n#+CAPTION: From {0}\n| a |\n",
+        LINK
+    );
+    let org = Org::parse(&text);
+
+    let title = org.headlines().next().unwrap().title(&org);
+    assert_eq!(title.properties.get("URL"), Some(LINK));
+    assert_eq!(org.document_properties().get("url"), Some(LINK));
+
+    let keywords: Vec<_> = org
+        .iter()
+        .filter_map(|event| match event {
+            Event::Start(Element::Keyword(keyword)) => Some(keyword),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(keywords.len(), 2);
+    assert_eq!(links(&keywords[0].value_objects()), 0);
+    assert_eq!(keywords[0].value_objects().iter().count(), 2);
+    assert_eq!(links(&keywords[1].value_objects()), 1);
+
+    // neither the property values nor the caption are in the tree
+    assert_eq!(
+        org.iter()
+            .filter(|event| matches!(event, Event::Start(Element::Link(_))))
+            .count(),
+        0
+    );
+
+    let mut writer = Vec::new();
+    let mut handler = DefaultHtmlHandler::new(HtmlConfig {
+        properties: true,
+        ..Default::default()
+    });
+    org.write_html_custom(&mut writer, &mut handler).unwrap();
+    let html = String::from_utf8(writer).unwrap();
+    assert!(html.contains("<td>[[https://orgmode.org][Org]]</td>"));
+    assert!(html.contains("</span> From <a href=\"https://orgmode.org\">Org</a></caption>"));
+}
+
+#[test]
+fn contexts() {
+    for (key, context) in [
+        ("caption", ObjectContext::ParsedKeyword),
+        ("TITLE", ObjectContext::ParsedKeyword),
+        ("AUTHOR", ObjectContext::ParsedKeyword),
+        ("DATE", ObjectContext::ParsedKeyword),
+        ("PROPERTY", ObjectContext::PropertyValue),
+        ("OPTIONS", ObjectContext::Keyword),
+        ("RESULTS", ObjectContext::Keyword),
+    ] {
+        assert_eq!(ObjectContext::of_keyword(key), context, "{}", key);
+        let expected = context.contains_objects() as usize;
+        assert_eq!(links(&keyword(key, LINK).value_objects()), expected);
+    }
+
+    let objects = Objects::parse_in("*bold* and /italic/", ObjectContext::Tags);
+    let events: Vec<_> = objects.iter().collect();
+    assert_eq!(events.len(), 2);
+    assert!(matches!(
+        events[0],
+        Event::Start(Element::Text { value }) if value == "*bold* and /italic/"
+    ));
+    assert_eq!(
+        Objects::parse_in("", ObjectContext::Keyword).iter().count(),
+        0
+    );
+
+    // cells and titles contain objects
+    let text = format!("* {0}\n| {0} |\n", LINK);
+    let org = Org::parse(&text);
+    assert_eq!(
+        org.iter()
+            .filter(|event| matches!(event, Event::Start(Element::Link(_))))
+            .count(),
+        2
+    );
+}
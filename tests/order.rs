@@ -0,0 +1,91 @@
+use orgize::{elements::Title, Element, Headline, Org};
+use pretty_assertions::assert_eq;
+use std::cmp::Ordering;
+
+fn titles(org: &Org, a: indextree::NodeId, b: indextree::NodeId) -> Vec<String> {
+    org.range_iter(a, b)
+        .filter_map(|node| match &org[node] {
+            Element::Title(title) => Some(title.raw.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+#[test]
+fn order() {
+    let org = Org::parse("* a\n** b\ntext\n* c\n");
+    let headlines: Vec<_> = org.headlines().map(|h| h.headline_node()).collect();
+
+    assert_eq!(
+        org.cmp_document_order(headlines[0], headlines[1]),
+        Ordering::Less
+    );
+    assert_eq!(
+        org.cmp_document_order(headlines[2], headlines[1]),
+        Ordering::Greater
+    );
+    assert_eq!(
+        org.cmp_document_order(headlines[1], headlines[1]),
+        Ordering::Equal
+    );
+
+    assert_eq!(titles(&org, headlines[0], headlines[2]), ["a", "b"]);
+    assert_eq!(
+        titles(&org, headlines[1], headlines[1]),
+        Vec::<String>::new()
+    );
+    assert_eq!(org.range_iter(headlines[2], headlines[0]).count(), 0);
+}
+
+#[test]
+fn invalidation() {
+    let mut org = Org::parse("* a\n* b\n* c\n");
+    let a = org.headlines().next().unwrap();
+    let c = org.headlines().nth(2).unwrap();
+    assert_eq!(
+        org.cmp_document_order(a.headline_node(), c.headline_node()),
+        Ordering::Less
+    );
+
+    // moves `a` after `c`
+    a.detach(&mut org);
+    c.insert_after(a, &mut org).unwrap();
+    assert_eq!(
+        org.cmp_document_order(a.headline_node(), c.headline_node()),
+        Ordering::Greater
+    );
+
+    let b = org.headlines().next().unwrap();
+    let new = Headline::new(
+        Title {
+            raw: "new".into(),
+            ..Default::default()
+        },
+        &mut org,
+    );
+    b.insert_before(new, &mut org).unwrap();
+    assert_eq!(
+        titles(&org, new.headline_node(), a.headline_node()),
+        ["new", "b", "c"]
+    );
+
+    // through the arena
+    let (b, c) = (b.headline_node(), c.headline_node());
+    c.detach(org.arena_mut());
+    b.insert_before(c, org.arena_mut());
+    assert_eq!(org.cmp_document_order(c, b), Ordering::Less);
+    assert_eq!(
+        titles(&org, new.headline_node(), a.headline_node()),
+        ["new", "c", "b"]
+    );
+}
+
+#[test]
+#[should_panic]
+fn detached() {
+    let mut org = Org::parse("* a\n* b\n");
+    let a = org.headlines().next().unwrap();
+    let b = org.headlines().nth(1).unwrap();
+    b.detach(&mut org);
+    org.cmp_document_order(a.headline_node(), b.headline_node());
+}
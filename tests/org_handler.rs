@@ -0,0 +1,121 @@
+use orgize::{
+    elements::{Element, Title},
+    export::{DefaultOrgHandler, OrgHandler},
+    Org, ParseConfig,
+};
+use pretty_assertions::assert_eq;
+use std::io::{Error, Write};
+
+const TEXT: &str = r#"#+STARTUP: odd
+* todo [#A] First :tag:
+SCHEDULED: <2024-03-08 Fri>
+:PROPERTIES:
+:ID: 1
+:END:
+text *bold*
+*** next Second
+- [ ] item
+* done Third
+
+| a | b |
+"#;
+
+// writes todo keywords in uppercase, and everything else like the default
+// handler
+#[derive(Default)]
+struct UppercaseKeywords(DefaultOrgHandler);
+
+impl OrgHandler<Error> for UppercaseKeywords {
+    fn prepare(&mut self, org: &Org) {
+        self.0.prepare(org);
+    }
+
+    fn start<W: Write>(&mut self, w: W, element: &Element) -> Result<(), Error> {
+        match element {
+            Element::Title(title) => {
+                let title = Title {
+                    keyword: title.keyword.as_ref().map(|k| k.to_uppercase().into()),
+                    ..title.clone()
+                };
+                self.0.start(w, &Element::Title(title))
+            }
+            _ => self.0.start(w, element),
+        }
+    }
+
+    fn end<W: Write>(&mut self, w: W, element: &Element) -> Result<(), Error> {
+        self.0.end(w, element)
+    }
+}
+
+fn parse(text: &str) -> Org<'_> {
+    let config = ParseConfig {
+        todo_keywords: (vec!["todo".into(), "next".into()], vec!["done".into()]),
+        ..Default::default()
+    };
+    Org::parse_custom(text, &config)
+}
+
+#[test]
+fn default_handler() {
+    let org = parse(TEXT);
+
+    let mut writer = Vec::new();
+    org.write_org_custom(&mut writer, &mut DefaultOrgHandler::default())
+        .unwrap();
+    assert_eq!(String::from_utf8(writer).unwrap(), TEXT);
+}
+
+#[test]
+fn custom_handler() {
+    let org = parse(TEXT);
+
+    let mut writer = Vec::new();
+    org.write_org_custom(&mut writer, &mut UppercaseKeywords::default())
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        TEXT.replace("* todo", "* TODO")
+            .replace("* next", "* NEXT")
+            .replace("* done", "* DONE")
+    );
+}
+
+#[test]
+fn headline_spacing() {
+    let text = "*  todo\t[#A]   Title\t\t:tag:  \n\
+                ***    Second   \n\
+                * done [#B]Third :a:b:\n\
+                *   \t:only:tags:\n";
+    let mut writer = Vec::new();
+    parse(text).write_org(&mut writer).unwrap();
+    assert_eq!(String::from_utf8(writer).unwrap(), text);
+
+    let mut writer = Vec::new();
+    parse(text)
+        .write_org_custom(&mut writer, &mut UppercaseKeywords::default())
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        "* TODO [#A] Title :tag:\n\
+         ***    Second   \n\
+         * DONE [#B] Third :a:b:\n\
+         *   \t:only:tags:\n"
+    );
+}
+
+#[test]
+fn modified_headline() {
+    let mut org = parse("*  todo   Title\t:tag:\n**   Child  \n");
+    let first = org.headlines().next().unwrap();
+    let mut child = first.first_child(&org).unwrap();
+    first.set_tags(["other"], &mut org);
+    child.set_level(3, &mut org).unwrap();
+
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        "* todo Title :other:\n*** Child\n"
+    );
+}
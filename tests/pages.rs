@@ -0,0 +1,144 @@
+use orgize::export::{DefaultHtmlHandler, HtmlConfig, HtmlDocumentConfig, Page};
+use orgize::Org;
+use pretty_assertions::assert_eq;
+
+const TEXT: &str = r#"#+TITLE: Site
+#+AUTHOR: Jane Doe
+#+LANGUAGE: fr
+Welcome.
+
+* Home
+:PROPERTIES:
+:EXPORT_FILE_NAME: home
+:END:
+Read [[*Setup details][the setup]], the [[#faq][FAQ]] or [[*Home][this page]].
+* Guide
+Intro.
+** Setup details
+<<install>>Install it.
+* FAQ
+:PROPERTIES:
+:CUSTOM_ID: faq
+:END:
+Back [[*Home][home]], see [[install][installing]] and [[https://example.com][elsewhere]].
+"#;
+
+fn pages(org: &Org) -> Vec<Page> {
+    org.export_pages(1, &HtmlDocumentConfig::default(), |links| {
+        DefaultHtmlHandler::new(HtmlConfig {
+            links,
+            ..Default::default()
+        })
+    })
+    .unwrap()
+}
+
+#[test]
+fn split() {
+    let org = Org::parse(TEXT);
+    let pages = pages(&org);
+
+    let files: Vec<_> = pages.iter().map(|page| page.file_name.as_str()).collect();
+    assert_eq!(files, ["home.html", "guide.html", "faq.html", "index.html"]);
+    let titles: Vec<_> = pages.iter().map(|page| page.title.as_str()).collect();
+    assert_eq!(titles, ["Home", "Guide", "FAQ", "Site"]);
+
+    assert_eq!(pages[1].headline.unwrap().title(&org).raw, "Guide");
+    assert!(pages[3].headline.is_none());
+
+    // metadata of the document, and only contents of the page
+    let guide = &pages[1].html;
+    assert!(guide.starts_with("<!DOCTYPE html>\n<html lang=\"fr\">"));
+    assert!(guide.contains("<title>Guide</title>"));
+    assert!(guide.contains("<meta name=\"author\" content=\"Jane Doe\">"));
+    assert!(guide.contains("<h2 id=\"setup-details\">Setup details</h2>"));
+    assert!(!guide.contains("Welcome."));
+    assert!(!guide.contains("Home"));
+}
+
+#[test]
+fn cross_page_links() {
+    let org = Org::parse(TEXT);
+    let pages = pages(&org);
+
+    let home = &pages[0].html;
+    assert!(home.contains("<a href=\"guide.html#setup-details\">the setup</a>"));
+    assert!(home.contains("<a href=\"faq.html\">FAQ</a>"));
+    assert!(home.contains("<a href=\"home.html\">this page</a>"));
+
+    let faq = &pages[2].html;
+    assert!(faq.contains("<a href=\"home.html\">home</a>"));
+    assert!(faq.contains("<a href=\"guide.html#install\">installing</a>"));
+    assert!(faq.contains("<a href=\"https://example.com\">elsewhere</a>"));
+
+    // the anchor exists in the linked page
+    assert!(pages[1].html.contains("<a id=\"install\"></a>"));
+}
+
+#[test]
+fn index() {
+    let org = Org::parse(TEXT);
+    let index = &pages(&org)[3].html;
+
+    assert!(index.contains("<title>Site</title>"));
+    assert!(index.contains("<p>Welcome.</p>"));
+    assert!(index.contains(
+        "<nav><ul><li><a href=\"home.html\">Home</a></li>\
+         <li><a href=\"guide.html\">Guide</a></li>\
+         <li><a href=\"faq.html\">FAQ</a></li></ul></nav>"
+    ));
+    assert!(!index.contains("Intro."));
+}
+
+#[test]
+fn duplicated_names() {
+    let org = Org::parse("* Index\n* Notes\n* Notes\n");
+    let pages = pages(&org);
+
+    let files: Vec<_> = pages.iter().map(|page| page.file_name.as_str()).collect();
+    assert_eq!(
+        files,
+        ["index-2.html", "notes.html", "notes-2.html", "index.html"]
+    );
+}
+
+#[test]
+fn shallower_headlines() {
+    let org = Org::parse("Welcome.\n* Top\ntop text\n** Page\nSee [[*Top]].\n*** Deep\n");
+    let pages = org
+        .export_pages(2, &HtmlDocumentConfig::default(), |links| {
+            DefaultHtmlHandler::new(HtmlConfig {
+                links,
+                ..Default::default()
+            })
+        })
+        .unwrap();
+
+    let files: Vec<_> = pages.iter().map(|page| page.file_name.as_str()).collect();
+    assert_eq!(files, ["page.html", "index.html"]);
+
+    // contents outside of pages are written on the index
+    assert!(pages[0]
+        .html
+        .contains("<a href=\"index.html#top\">*Top</a>"));
+    assert!(pages[0].html.contains("<h3>Deep</h3>"));
+    assert!(pages[1].html.contains(
+        "<p>Welcome.</p></section><h1 id=\"top\">Top</h1><section><p>top text</p></section>\
+         <nav><ul><li><a href=\"page.html\">Page</a></li></ul></nav>"
+    ));
+}
+
+#[test]
+fn anchors_match_heading_ids() {
+    let org = Org::parse(
+        "* Intro\n<<setup>>Here.\n* Guide\n** Setup\n* Links\n[[*Setup]] and [[setup]].\n",
+    );
+    let pages = pages(&org);
+
+    // the target takes the id first, the heading gets a deduplicated one
+    let links = &pages[2].html;
+    assert!(links.contains("<a href=\"guide.html#setup-2\">*Setup</a>"));
+    assert!(links.contains("<a href=\"intro.html#setup\">setup</a>"));
+    assert!(pages[1].html.contains("<h2 id=\"setup-2\">Setup</h2>"));
+    assert!(pages[0].html.contains("<a id=\"setup\"></a>"));
+}
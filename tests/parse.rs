@@ -170,3 +170,11 @@ test_suite!(
      <tbody><tr></tr></tbody>\
      </table></section></main>"
 );
+
+test_suite!(
+    brackets,
+    "[see note] [2024] [TODO] [1] [cite:@doe] [2024-03-08 Fri] [1/2] [50%] [[l]]",
+    "<main><section><p>[see note] [2024] [TODO] [1] [cite:@doe] \
+     <span class=\"timestamp-wrapper\"><span class=\"timestamp\">[2024-03-08 Fri]</span></span> \
+     <code>[1/2]</code> <code>[50%]</code> <a href=\"l\">l</a></p></section></main>"
+);
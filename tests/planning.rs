@@ -0,0 +1,107 @@
+use orgize::{elements::Timestamp, Element, Org, ParseConfig, ParseError, Strictness};
+use pretty_assertions::assert_eq;
+
+const DEADLINE: &str = "DEADLINE: <2024-03-08 Fri>";
+const SCHEDULED: &str = "SCHEDULED: <2024-03-04 Mon>";
+const CLOSED: &str = "CLOSED: [2024-03-05 Tue 10:00]";
+
+fn planning(line: &str) -> Option<(String, String, String)> {
+    let org = Org::parse_string(format!("* TODO a\n{}\ntext\n", line));
+    let title = org.headlines().next().unwrap().title(&org);
+    title.planning.as_ref()?;
+    let show = |timestamp: Option<&Timestamp>| timestamp.map(|t| t.to_string()).unwrap_or_default();
+    Some((
+        show(title.deadline()),
+        show(title.scheduled()),
+        show(title.closed()),
+    ))
+}
+
+fn all() -> Option<(String, String, String)> {
+    Some((
+        "<2024-03-08 Fri>".into(),
+        "<2024-03-04 Mon>".into(),
+        "[2024-03-05 Tue 10:00]".into(),
+    ))
+}
+
+#[test]
+fn orderings() {
+    let orderings = [
+        [DEADLINE, SCHEDULED, CLOSED],
+        [DEADLINE, CLOSED, SCHEDULED],
+        [SCHEDULED, DEADLINE, CLOSED],
+        [SCHEDULED, CLOSED, DEADLINE],
+        [CLOSED, DEADLINE, SCHEDULED],
+        [CLOSED, SCHEDULED, DEADLINE],
+    ];
+    for ordering in orderings.iter() {
+        assert_eq!(planning(&ordering.join(" ")), all(), "{:?}", ordering);
+    }
+}
+
+#[test]
+fn whitespaces() {
+    assert_eq!(
+        planning(&format!("   {}\t {}   {}  ", DEADLINE, SCHEDULED, CLOSED)),
+        all()
+    );
+    assert_eq!(
+        planning("SCHEDULED:<2024-03-04 Mon>"),
+        Some((String::new(), "<2024-03-04 Mon>".into(), String::new()))
+    );
+}
+
+#[test]
+fn invalid() {
+    assert_eq!(planning(""), None);
+    assert_eq!(planning("SCHEDULED:"), None);
+    assert_eq!(planning("SCHEDULED: <2024-03-04 Mon> later"), None);
+    assert_eq!(planning("scheduled: <2024-03-04 Mon>"), None);
+}
+
+#[test]
+fn not_after_headline() {
+    let org = Org::parse("* a\ntext\nSCHEDULED: <2024-03-04 Mon>\n");
+    let title = org.headlines().next().unwrap().title(&org);
+    assert!(title.planning.is_none());
+    assert!(org
+        .arena()
+        .iter()
+        .any(|node| matches!(node.get(), Element::Paragraph { .. })));
+}
+
+#[test]
+fn duplicates_and_inactive() {
+    let text =
+        "* a\nSCHEDULED: [2024-03-04 Mon] DEADLINE: <2024-03-08 Fri> SCHEDULED: <2024-03-05 Tue>\n";
+    let org = Org::parse_custom(
+        text,
+        &ParseConfig {
+            strictness: Strictness::Warn,
+            ..Default::default()
+        },
+    );
+
+    // the first one wins
+    let title = org.headlines().next().unwrap().title(&org);
+    assert_eq!(title.scheduled().unwrap().to_string(), "[2024-03-04 Mon]");
+    assert_eq!(title.deadline().unwrap().to_string(), "<2024-03-08 Fri>");
+
+    assert_eq!(
+        org.diagnostics(),
+        &[
+            ParseError::InactivePlanningTimestamp {
+                keyword: "SCHEDULED:".into(),
+                offset: text.find("SCHEDULED:").unwrap(),
+            },
+            ParseError::DuplicatePlanningKeyword {
+                keyword: "SCHEDULED:".into(),
+                offset: text.rfind("SCHEDULED:").unwrap(),
+            },
+        ]
+    );
+
+    // nothing is reported in lenient mode
+    assert_eq!(Org::parse(text).diagnostics(), &[]);
+}
@@ -0,0 +1,74 @@
+use orgize::{Element, Event, Org};
+use pretty_assertions::assert_eq;
+
+const FIXTURES: &[&str] = &[
+    include_str!("fixtures/elements.org"),
+    include_str!("fixtures/inline.org"),
+    include_str!("fixtures/search.org"),
+    include_str!("fixtures/stats.org"),
+    include_str!("fixtures/tables.org"),
+    include_str!("fixtures/diff_old.org"),
+    include_str!("fixtures/diff_new.org"),
+    "\n\n#+TITLE: blank\n\n* a\n\n\n** b\n- 1\n\n- 2\n\n  + 2.1\n\n  + 2.2\n\n\n- 3\n\n\
+     #+BEGIN_QUOTE\n\nquote\n\n#+END_QUOTE\n\n:DRAWER:\n\ncontents\n\n:END:\n\n\
+     | table |\n\n-----\n\n# comment\n\n: fixed\n\n[fn:1] def\n\n\n* c\n\n",
+];
+
+fn to_org(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+#[test]
+fn lines_add_up() {
+    for text in FIXTURES {
+        let mut org = Org::parse(text);
+        // the org export may normalize indentation and spacing, but not lines
+        assert_eq!(to_org(&org).lines().count(), text.lines().count());
+
+        let post_blank: usize = org
+            .iter()
+            .filter_map(|event| match event {
+                Event::Start(element) => Some(element.post_blank()),
+                _ => None,
+            })
+            .sum();
+
+        // without blank lines after elements, only their own lines are left
+        for node in org.arena_mut().iter_mut() {
+            node.get_mut().set_post_blank(0);
+        }
+        let without = to_org(&org);
+
+        assert_eq!(
+            without.lines().count() + post_blank,
+            text.lines().count(),
+            "{}",
+            without
+        );
+    }
+}
+
+#[test]
+fn list_items() {
+    let org = Org::parse("- a\n\n- b\n  - c\n\n  - d\n\n- e\n\n\ntext\n");
+
+    let items: Vec<_> = org
+        .iter()
+        .filter_map(|event| match event {
+            Event::Start(Element::ListItem(item)) => Some(item.post_blank),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(items, [1, 1, 1, 0, 0]);
+
+    let lists: Vec<_> = org
+        .iter()
+        .filter_map(|event| match event {
+            Event::Start(Element::List(list)) => Some(list.post_blank),
+            _ => None,
+        })
+        .collect();
+    assert_eq!(lists, [2, 0]);
+}
@@ -0,0 +1,76 @@
+use orgize::Org;
+use pretty_assertions::assert_eq;
+
+const TEXT: &str = r#"* Post
+SCHEDULED: <2024-03-08 Fri>
+:PROPERTIES:
+:CUSTOM_ID: post
+:END:
+:LOGBOOK:
+- Note taken on [2024-03-01 Fri 10:00]
+:END:
+Read /the/ [[https://example.com/docs][*full* documentation]] first.
+
+#+BEGIN_SRC sh
+make
+#+END_SRC
+- then =run= it
+** Child
+Not in the preview.
+"#;
+
+#[test]
+fn preview() {
+    let org = Org::parse(TEXT);
+    let post = org.headlines().next().unwrap();
+
+    assert_eq!(
+        post.preview(&org, 100, "..."),
+        "Read the full documentation first. then run it"
+    );
+    // the link ends at 27
+    assert_eq!(
+        post.preview(&org, 27, "..."),
+        "Read the full documentation..."
+    );
+    assert_eq!(post.preview(&org, 26, "..."), "Read the...");
+    assert_eq!(post.preview(&org, 8, "..."), "Read the...");
+    assert_eq!(post.preview(&org, 7, "..."), "Read...");
+}
+
+#[test]
+fn empty() {
+    let org = Org::parse("* Empty\n:PROPERTIES:\n:ID: 1\n:END:\n** Child\ntext\n");
+    let empty = org.headlines().next().unwrap();
+    assert_eq!(empty.preview(&org, 10, "..."), "");
+}
+
+#[test]
+fn radio_targets_targets_and_macros() {
+    let org = Org::parse(
+        "* Glossary\n<<<Orgize>>> parses <<here>>{{{version}}} [1/2] with {{{kbd(C-c)}}}.\n",
+    );
+    let glossary = org.headlines().next().unwrap();
+    assert_eq!(
+        glossary.preview(&org, 100, "..."),
+        "Orgize parses version [1/2] with kbd."
+    );
+}
+
+#[test]
+fn macros() {
+    let org = Org::parse(
+        "#+MACRO: greet Hello, /$1/ and $2!\n\
+         #+MACRO: loop {{{loop}}} again\n\
+         * Post\n\
+         {{{greet(Alice\\, Bob,Carol)}}} {{{loop}}} {{{unknown(a)}}}\n",
+    );
+    let post = org.headlines().next().unwrap();
+
+    assert_eq!(
+        post.preview(&org, 100, "..."),
+        "Hello, Alice, Bob and Carol! loop again unknown"
+    );
+    // expansions are cut like text
+    assert_eq!(post.preview(&org, 12, "..."), "Hello,...");
+}
@@ -0,0 +1,87 @@
+use orgize::{Org, Progress, ProgressConfig};
+use pretty_assertions::assert_eq;
+
+const TEXT: &str = "* Release
+** DONE Changelog
+:PROPERTIES:
+:EFFORT: 0:30
+:END:
+** TODO Docs
+*** DONE Guide
+:PROPERTIES:
+:EFFORT: 2h
+:END:
+- [X] install
+- [ ] usage
+  - [X] cli
+  - [ ] library
+*** TODO API
+:PROPERTIES:
+:EFFORT: 1h
+:END:
+** Notes
+- [X] unrelated
+** TODO Announce
+* Other
+** TODO elsewhere
+";
+
+fn progress(config: &ProgressConfig) -> Progress {
+    let org = Org::parse(TEXT);
+    let release = org.headlines().next().unwrap();
+    release.progress(&org, config)
+}
+
+#[test]
+fn headlines() {
+    assert_eq!(
+        progress(&ProgressConfig::default()),
+        Progress {
+            done: 2,
+            total: 4,
+            percent: 50
+        }
+    );
+}
+
+#[test]
+fn checkboxes() {
+    let config = ProgressConfig {
+        checkboxes: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        progress(&config),
+        Progress {
+            done: 3,
+            total: 4,
+            percent: 75
+        }
+    );
+}
+
+#[test]
+fn effort() {
+    let config = ProgressConfig {
+        effort: true,
+        ..Default::default()
+    };
+    assert_eq!(
+        progress(&config),
+        Progress {
+            done: 150,
+            total: 210,
+            percent: 71
+        }
+    );
+}
+
+#[test]
+fn no_task() {
+    let org = Org::parse("* a\n** b\n");
+    let a = org.headlines().next().unwrap();
+    assert_eq!(
+        a.progress(&org, &ProgressConfig::default()),
+        Progress::default()
+    );
+}
@@ -0,0 +1,93 @@
+use orgize::Org;
+use pretty_assertions::assert_eq;
+
+const TEXT: &str = r#"* Build
+  :PROPERTIES:
+  :ID:       2c7e
+  :VAR:      target=x86
+  :VAR+:     opt=3
+  :EFFORT:   1:00
+  :END:
+Text.
+"#;
+
+fn write(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+#[test]
+fn round_trip() {
+    let org = Org::parse(TEXT);
+    assert_eq!(write(&org), TEXT);
+
+    let properties = &org.headlines().next().unwrap().title(&org).properties;
+    let keys: Vec<_> = properties.iter().map(|(key, _)| &**key).collect();
+    assert_eq!(keys, ["ID", "VAR", "VAR+", "EFFORT"]);
+    assert_eq!(
+        properties.get_accumulated("VAR").as_deref(),
+        Some("target=x86 opt=3")
+    );
+
+    let text = "* a\n:PROPERTIES:\n:ID: 1\n:CATEGORY: b\n:EMPTY:\n:END:\n";
+    assert_eq!(
+        write(&Org::parse(text)),
+        text.replace(":EMPTY:", ":EMPTY: ")
+    );
+}
+
+#[test]
+fn update_middle_property() {
+    let mut org = Org::parse(TEXT);
+    let headline = org.headlines().next().unwrap();
+
+    headline.set_property("effort", "2:30", &mut org);
+    headline.set_property("VAR", "target=arm", &mut org);
+
+    assert_eq!(
+        write(&org),
+        TEXT.replace("1:00", "2:30").replace("x86", "arm")
+    );
+}
+
+#[test]
+fn add_property() {
+    let mut org = Org::parse(TEXT);
+    let headline = org.headlines().next().unwrap();
+
+    headline.set_property("CATEGORY", "build", &mut org);
+    headline.set_property("LONG_PROPERTY_NAME", "v", &mut org);
+
+    assert_eq!(
+        write(&org),
+        TEXT.replace(
+            "  :END:",
+            "  :CATEGORY: build\n  :LONG_PROPERTY_NAME: v\n  :END:"
+        )
+    );
+
+    // creates the drawer
+    let mut org = Org::parse("* a\nb\n");
+    let headline = org.headlines().next().unwrap();
+    headline.set_property("ID", "1", &mut org);
+    assert_eq!(write(&org), "* a\n:PROPERTIES:\n:ID: 1\n:END:\nb\n");
+}
+
+#[test]
+fn remove_accumulated_property() {
+    let mut org = Org::parse(TEXT);
+    let headline = org.headlines().next().unwrap();
+
+    assert!(headline.remove_property("var", &mut org));
+    assert!(!headline.remove_property("VAR", &mut org));
+
+    assert_eq!(
+        write(&org),
+        TEXT.replace("  :VAR:      target=x86\n  :VAR+:     opt=3\n", "")
+    );
+
+    headline.remove_property("ID", &mut org);
+    headline.remove_property("EFFORT", &mut org);
+    assert_eq!(write(&org), "* Build\nText.\n");
+}
@@ -0,0 +1,135 @@
+use orgize::{Matcher, Org, ParseConfig};
+use pretty_assertions::assert_eq;
+
+const FIXTURE: &str = r#"#+TAGS: [ Project : ProjA ProjB ]
+* TODO Write report :work:boss:
+:PROPERTIES:
+:Effort: 2:00
+:Coffee: unlimited
+:END:
+* NEXT Call Sarah :work:
+:PROPERTIES:
+:Effort: 0:15
+:CATEGORY: dev
+:END:
+** WAITING Review patch
+:PROPERTIES:
+:Effort: 0:30
+:END:
+** DONE Merge branch :ProjA:
+* TODO [#A] Buy laptop :laptop:night:
+:PROPERTIES:
+:Price: 999
+:END:
+* Evening :night:
+*** TODO Read book
+"#;
+
+fn parse() -> Org<'static> {
+    Org::parse_custom(
+        FIXTURE,
+        &ParseConfig {
+            todo_keywords: (
+                vec!["TODO".into(), "NEXT".into(), "WAITING".into()],
+                vec!["DONE".into()],
+            ),
+            ..Default::default()
+        },
+    )
+}
+
+fn titles(input: &str) -> Vec<String> {
+    let org = parse();
+    let matcher = Matcher::parse(input).unwrap();
+    org.match_headlines(&matcher)
+        .into_iter()
+        .map(|hdl| hdl.title(&org).raw.to_string())
+        .collect()
+}
+
+#[test]
+fn tags() {
+    assert_eq!(
+        titles("work"),
+        ["Write report", "Call Sarah", "Review patch", "Merge branch"]
+    );
+    assert_eq!(titles("work&boss"), ["Write report"]);
+    assert_eq!(
+        titles("+work-boss"),
+        ["Call Sarah", "Review patch", "Merge branch"]
+    );
+    assert_eq!(
+        titles("work|laptop"),
+        [
+            "Write report",
+            "Call Sarah",
+            "Review patch",
+            "Merge branch",
+            "Buy laptop"
+        ]
+    );
+    assert_eq!(
+        titles("work|laptop+night"),
+        [
+            "Write report",
+            "Call Sarah",
+            "Review patch",
+            "Merge branch",
+            "Buy laptop"
+        ]
+    );
+    assert_eq!(titles("night-laptop"), ["Evening", "Read book"]);
+    assert_eq!(titles("Project"), ["Merge branch"]);
+}
+
+#[test]
+fn properties() {
+    assert_eq!(
+        titles("+work-boss+PRIORITY=\"A\"|+Coffee=\"unlimited\""),
+        ["Write report"]
+    );
+    assert_eq!(titles("PRIORITY=\"A\""), ["Buy laptop"]);
+    assert_eq!(
+        titles("+Effort<=\"0:30\"+CATEGORY=\"dev\""),
+        ["Call Sarah", "Review patch"]
+    );
+    assert_eq!(titles("Effort>\"1h\""), ["Write report"]);
+    assert_eq!(titles("Price>500"), ["Buy laptop"]);
+    assert_eq!(titles("Price<>999"), Vec::<String>::new());
+    assert_eq!(titles("+LEVEL=3+night"), ["Read book"]);
+    assert_eq!(titles("+work-TODO=\"DONE\"+LEVEL>1"), ["Review patch"]);
+}
+
+#[test]
+fn todo_keywords() {
+    assert_eq!(titles("work/WAITING"), ["Review patch"]);
+    assert_eq!(titles("work/!-WAITING-NEXT"), ["Write report"]);
+    assert_eq!(
+        titles("/!+TODO|+NEXT"),
+        ["Write report", "Call Sarah", "Buy laptop", "Read book"]
+    );
+    assert_eq!(
+        titles("work/!"),
+        ["Write report", "Call Sarah", "Review patch"]
+    );
+    assert_eq!(titles("work/DONE"), ["Merge branch"]);
+}
+
+#[test]
+fn errors() {
+    let error = |input: &str| Matcher::parse(input).unwrap_err().to_string();
+
+    assert_eq!(
+        error("work||boss"),
+        "expected a tag, property or todo keyword at 5"
+    );
+    assert_eq!(
+        error("+Effort<=0:30"),
+        "expected a number or a quoted value at 9"
+    );
+    assert_eq!(error("CATEGORY=\"dev"), "unclosed quote at 9");
+    assert_eq!(
+        error("+With={Sarah\\|Denny}"),
+        "regular expressions are not supported at 6"
+    );
+}
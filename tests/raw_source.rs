@@ -0,0 +1,86 @@
+use orgize::{elements::Title, Headline, Org};
+use pretty_assertions::assert_eq;
+
+const TEXT: &str = r#"#+TITLE: raw
+
+* Notes :tag:
+SCHEDULED: <2024-01-01 Mon>
+#+NAME: hello
+#+BEGIN_SRC rust :exports both
+fn main() {}
+#+END_SRC
+
+- a
+  - b
+- c
+
+
+text
+** Nested
+| a | b |
+* Next
+"#;
+
+fn find(org: &Org, name: &str) -> indextree::NodeId {
+    org.events()
+        .find(|event| event.event.element().name() == name)
+        .unwrap()
+        .node
+}
+
+fn slice(from: &str, to: &str) -> &'static str {
+    let start = TEXT.find(from).unwrap();
+    let end = TEXT.find(to).unwrap();
+    &TEXT[start..end]
+}
+
+#[test]
+fn raw_source() {
+    for org in [Org::parse(TEXT), Org::parse_string(TEXT.to_string())] {
+        assert_eq!(org.raw_source(find(&org, "org-data")), Some(TEXT));
+
+        // begin and end lines, and blank lines after the block
+        assert_eq!(
+            org.raw_source(find(&org, "src-block")),
+            Some(slice("#+BEGIN_SRC", "- a"))
+        );
+        assert_eq!(
+            org.raw_source(find(&org, "plain-list")),
+            Some(slice("- a", "text"))
+        );
+        assert_eq!(org.raw_source(find(&org, "paragraph")), Some("a"));
+
+        let headlines: Vec<_> = org.headlines().collect();
+        assert_eq!(
+            org.raw_source(headlines[0].headline_node()),
+            Some(slice("* Notes", "* Next"))
+        );
+        assert_eq!(
+            org.raw_source(headlines[0].title_node()),
+            Some("* Notes :tag:\nSCHEDULED: <2024-01-01 Mon>\n")
+        );
+        assert_eq!(
+            org.raw_source(headlines[1].headline_node()),
+            Some(slice("** Nested", "* Next"))
+        );
+    }
+}
+
+#[test]
+fn raw_source_of_new_nodes() {
+    let mut org = Org::parse(TEXT);
+    let headline = Headline::new(
+        Title {
+            raw: "new".into(),
+            level: 1,
+            ..Default::default()
+        },
+        &mut org,
+    );
+    org.document().append(headline, &mut org).unwrap();
+
+    assert_eq!(org.raw_source(headline.headline_node()), None);
+
+    let empty = Org::new();
+    assert_eq!(empty.raw_source(find(&empty, "org-data")), None);
+}
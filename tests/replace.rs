@@ -0,0 +1,148 @@
+use orgize::{elements::Link, Element, Event, Org};
+use pretty_assertions::assert_eq;
+
+fn to_org(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+fn to_html(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_html(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+fn first_text(org: &Org) -> indextree::NodeId {
+    org.events()
+        .find(|event| matches!(event.event, Event::Start(Element::Text { .. })))
+        .map(|event| event.node)
+        .unwrap()
+}
+
+#[test]
+fn replace_text() {
+    let mut org = Org::parse("foo bar baz\n");
+    let text = first_text(&org);
+
+    let inserted = org
+        .replace_text(
+            text,
+            4..7,
+            vec![
+                Element::Text {
+                    value: "see ".into(),
+                },
+                Element::Link(Link {
+                    path: "https://bar.com".into(),
+                    desc: None,
+                }),
+            ],
+        )
+        .unwrap();
+    assert_eq!(inserted.len(), 2);
+    assert!(org.validate().is_empty());
+    assert_eq!(to_org(&org), "foo see [[https://bar.com]] baz\n");
+    assert_eq!(
+        to_html(&org),
+        "<main><section><p>foo see <a href=\"https://bar.com\">https://bar.com</a> baz</p></section></main>"
+    );
+}
+
+#[test]
+fn replace_whole_text() {
+    let mut org = Org::parse("word\n");
+    let text = first_text(&org);
+
+    org.replace_text(
+        text,
+        0..4,
+        Some(Element::Link(Link {
+            path: "word".into(),
+            desc: None,
+        })),
+    )
+    .unwrap();
+    assert!(org.validate().is_empty());
+    assert_eq!(to_org(&org), "[[word]]\n");
+}
+
+#[test]
+fn replace_non_text() {
+    let mut org = Org::parse("~code~\n");
+    let code = org
+        .events()
+        .find(|event| matches!(event.event, Event::Start(Element::Code { .. })))
+        .map(|event| event.node)
+        .unwrap();
+
+    assert!(org.replace_text(code, 0..1, None).is_err());
+    assert_eq!(to_org(&org), "~code~\n");
+}
+
+#[cfg(feature = "regex")]
+#[test]
+fn autolink() {
+    use orgize::regex::Regex;
+
+    const TEXT: &str = r#"* Issue #1
+Fixes #12 and #13, see ~#34~ and =#56=.
+#+BEGIN_SRC sh
+echo #78
+#+END_SRC
+- *bold #90* src_sh{echo #91}
+"#;
+
+    let mut org = Org::parse(TEXT);
+    let regex = Regex::new(r"#(\d+)").unwrap();
+    let count = org.autolink(&regex, |caps| Link {
+        path: format!("https://example.com/issues/{}", &caps[1]).into(),
+        desc: Some(caps[0].to_string().into()),
+    });
+
+    assert_eq!(count, 3);
+    assert!(org.validate().is_empty());
+    assert_eq!(
+        to_org(&org),
+        r#"* Issue #1
+Fixes [[https://example.com/issues/12][#12]] and [[https://example.com/issues/13][#13]], see ~#34~ and =#56=.
+#+BEGIN_SRC sh
+echo #78
+#+END_SRC
+- *bold [[https://example.com/issues/90][#90]]* src_sh{echo #91}
+"#
+    );
+    assert_eq!(
+        to_html(&org),
+        "<main><h1>Issue #1</h1><section><p>Fixes \
+         <a href=\"https://example.com/issues/12\">#12</a> and \
+         <a href=\"https://example.com/issues/13\">#13</a>, see <code>#34</code> and \
+         <code>#56</code>.</p><div class=\"org-src-container\">\
+         <pre><code class=\"language-bash\">echo #78\n</code></pre></div><ul><li><p><b>bold \
+         <a href=\"https://example.com/issues/90\">#90</a></b> \
+         <code class=\"language-bash\">echo #91</code></p></li></ul></section></main>"
+    );
+}
+
+#[test]
+fn removed_text_spans() {
+    let mut org = Org::parse("Visit example.com now.\n");
+    let text = org.object_at(0).unwrap();
+    assert!(matches!(org[text], Element::Text { .. }));
+
+    // the whole text before `now.` is replaced, so its node is removed
+    let link = Element::Link(Link {
+        path: "https://example.com".into(),
+        desc: None,
+    });
+    org.replace_text(text, 0..18, [link]).unwrap();
+    assert!(text.is_removed(org.arena()));
+
+    // spans of removed nodes aren't looked up anymore
+    for offset in 0..22 {
+        if let Some(node) = org.object_at(offset) {
+            assert!(!node.is_removed(org.arena()), "{}", offset);
+        }
+    }
+    assert_eq!(org.object_at(0), None);
+}
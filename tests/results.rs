@@ -0,0 +1,109 @@
+use orgize::{Element, Org, ResultsValue};
+use pretty_assertions::assert_eq;
+
+const TEXT: &str = include_str!("fixtures/results.org");
+
+// source blocks and babel calls of each headline
+fn calls(org: &Org) -> Vec<(String, indextree::NodeId)> {
+    org.headlines()
+        .filter_map(|headline| {
+            let section = headline.section_node()?;
+            let node = section.children(org.arena()).find(|&node| {
+                matches!(org[node], Element::SourceBlock(_) | Element::BabelCall(_))
+            })?;
+            Some((headline.title(org).raw.to_string(), node))
+        })
+        .collect()
+}
+
+fn org_string(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+#[test]
+fn shapes() {
+    let org = Org::parse(TEXT);
+    let results: Vec<_> = calls(&org)
+        .into_iter()
+        .map(|(title, node)| (title, org.results_content(node).unwrap()))
+        .collect();
+
+    assert_eq!(
+        results,
+        [
+            (
+                "Fixed width".into(),
+                ResultsValue::FixedWidth("a\nb\n\nc\n".into())
+            ),
+            (
+                "Example".into(),
+                ResultsValue::Example((1..=12).map(|n| format!("{}\n", n)).collect::<String>())
+            ),
+            (
+                "Table".into(),
+                ResultsValue::Table(vec![
+                    vec!["1".into(), "1".into()],
+                    vec!["2".into(), "4".into()],
+                ])
+            ),
+            (
+                "Drawer".into(),
+                ResultsValue::Drawer("*bold* and text\n".into())
+            ),
+            ("Raw".into(), ResultsValue::Raw("42".into())),
+            ("Link".into(), ResultsValue::Link("file:plot.png".into())),
+            ("Empty".into(), ResultsValue::Empty),
+        ]
+    );
+
+    let org = Org::parse("* a\n#+BEGIN_SRC sh\ntrue\n#+END_SRC\n: not results\n");
+    assert_eq!(org.results_content(calls(&org)[0].1), None);
+}
+
+#[test]
+fn removal_keeps_unrelated_content() {
+    let mut org = Org::parse(TEXT);
+    for (_, node) in calls(&org) {
+        assert!(org.remove_results(node));
+        assert_eq!(org.results_content(node), None);
+        assert!(!org.remove_results(node));
+    }
+
+    let expected = TEXT
+        .replace("\n#+RESULTS:\n: a\n: b\n:\n: c\n", "")
+        .replace("\n#+RESULTS:\n#+begin_example\n", "")
+        .replace(
+            &(1..=12).map(|n| format!("{}\n", n)).collect::<String>(),
+            "",
+        )
+        .replace("#+end_example\n", "")
+        .replace("\n#+RESULTS: squares\n| 1 | 1 |\n| 2 | 4 |\n", "")
+        .replace("\n#+RESULTS:\n:results:\n*bold* and text\n:end:\n", "")
+        .replace("#+RESULTS:\n42\n", "")
+        .replace("\n#+RESULTS:\n[[file:plot.png]]\n", "")
+        .replace("\n#+RESULTS:\n\nNot results.", "\nNot results.");
+    assert_eq!(org_string(&org), expected);
+    assert!(expected.contains("Text after the results."));
+    assert!(expected.contains("| unrelated | table |"));
+    assert!(expected.contains("Not results."));
+}
+
+#[test]
+fn results_drawer_without_keyword() {
+    let text = "#+BEGIN_SRC sh\necho a\n#+END_SRC\n:RESULTS:\n: a\n:END:\n\ntext\n";
+    let mut org = Org::parse(text);
+    let section = org.document().section_node().unwrap();
+    let block = org.arena()[section].first_child().unwrap();
+
+    assert_eq!(
+        org.results_content(block),
+        Some(ResultsValue::Drawer(": a\n".into()))
+    );
+    assert!(org.remove_results(block));
+    assert_eq!(
+        org_string(&org),
+        "#+BEGIN_SRC sh\necho a\n#+END_SRC\n\ntext\n"
+    );
+}
@@ -0,0 +1,119 @@
+use orgize::{
+    elements::{Link, SearchOption},
+    export::DefaultHtmlHandler,
+    Element, Org,
+};
+use pretty_assertions::assert_eq;
+
+const TEXT: &str = include_str!("fixtures/search.org");
+
+fn link(path: &str) -> Link<'_> {
+    Link {
+        path: path.into(),
+        desc: None,
+    }
+}
+
+#[test]
+fn parse_search_options() {
+    assert_eq!(
+        link("file:manual.org::*Installation").search(),
+        Some(SearchOption::Heading("Installation"))
+    );
+    assert_eq!(
+        link("./manual.org::#install").search(),
+        Some(SearchOption::CustomId("install"))
+    );
+    assert_eq!(
+        link("file:manual.org::/v[0-9]+/").search(),
+        Some(SearchOption::Regex("v[0-9]+"))
+    );
+    assert_eq!(
+        link("file:manual.org::42").search(),
+        Some(SearchOption::LineNumber(42))
+    );
+    assert_eq!(
+        link("file:manual.org::the installer").search(),
+        Some(SearchOption::Fuzzy("the installer"))
+    );
+    assert_eq!(link("file:manual.org").search(), None);
+
+    // internal links
+    assert_eq!(
+        link("*Installation").search(),
+        Some(SearchOption::Heading("Installation"))
+    );
+    assert_eq!(
+        link("#install").search(),
+        Some(SearchOption::CustomId("install"))
+    );
+    assert_eq!(link("42").search(), Some(SearchOption::Fuzzy("42")));
+    assert_eq!(
+        link("My Target").search(),
+        Some(SearchOption::Fuzzy("My Target"))
+    );
+
+    // external links and code references
+    assert_eq!(link("https://orgmode.org").search(), None);
+    assert_eq!(link("id:4f1c").search(), None);
+    assert_eq!(link("(ref)").search(), None);
+}
+
+#[test]
+fn resolve_search_options() {
+    let org = Org::parse(TEXT);
+    let title = |node| match &org[node] {
+        Element::Headline { .. } => org
+            .headlines()
+            .find(|hdl| hdl.headline_node() == node)
+            .map(|hdl| hdl.title(&org).raw.to_string()),
+        _ => None,
+    };
+
+    let installation = org.resolve_search(&SearchOption::Heading("Installation"));
+    assert_eq!(installation.and_then(title), Some("Installation".into()));
+    assert_eq!(
+        org.resolve_search(&SearchOption::CustomId("install")),
+        installation
+    );
+    assert_eq!(
+        org.resolve_search(&SearchOption::Fuzzy("Usage"))
+            .and_then(title),
+        Some("Usage".into())
+    );
+
+    // whitespaces are collapsed
+    let target = org
+        .resolve_search(&SearchOption::Fuzzy("the\n installer"))
+        .unwrap();
+    assert!(matches!(&org[target], Element::Target(t) if t.target == "the installer"));
+
+    // names resolve to the named element
+    let table = org
+        .resolve_search(&SearchOption::Fuzzy("versions"))
+        .unwrap();
+    assert!(matches!(org[table], Element::Table(_)));
+
+    assert_eq!(org.resolve_search(&SearchOption::Heading("Missing")), None);
+    assert_eq!(org.resolve_search(&SearchOption::CustomId("usage")), None);
+    assert_eq!(org.resolve_search(&SearchOption::Regex("Run")), None);
+    assert_eq!(org.resolve_search(&SearchOption::LineNumber(3)), None);
+}
+
+#[test]
+fn same_document_links_in_html() {
+    let mut writer = Vec::new();
+    let org = Org::parse(TEXT);
+    let usage = org.headlines().nth(1).unwrap();
+    org.write_subtree_html_custom(usage, &mut writer, &mut DefaultHtmlHandler::default())
+        .unwrap();
+
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        "<h1 id=\"usage\">Usage</h1><section><p>See \
+         <a href=\"#install\">*Installation</a>, \
+         <a href=\"#the-installer\">the installer</a>, \
+         <a href=\"#usage\">Usage</a> and \
+         <a href=\"#install\">#install</a>.</p></section>"
+    );
+}
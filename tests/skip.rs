@@ -0,0 +1,164 @@
+use orgize::{
+    elements::{ElementKind, ElementTypeSet},
+    Element, Event, Org, ParseConfig,
+};
+use pretty_assertions::assert_eq;
+
+const TEXT: &str = r#"* DONE Water the plants
+CLOSED: [2024-05-02 Thu 08:10]
+:PROPERTIES:
+:ID: 8a3e
+:END:
+:LOGBOOK:
+- State "DONE"       from "TODO"       [2024-05-02 Thu 08:10]
+CLOCK: [2024-05-02 Thu 08:00]--[2024-05-02 Thu 08:10] =>  0:10
+:END:
+Remember the *cactus*.
+
+| plant  | liters |
+|--------+--------|
+| cactus |    0.1 |
+#+TBLFM: $2=$2
+
+CLOCK: [2024-05-03 Fri 08:00]--[2024-05-03 Fri 08:05] =>  0:05
+
+#+BEGIN_QUOTE
+Water [[https://example.com][gently]].
+#+END_QUOTE
+* Notes
+: fixed width
+"#;
+
+fn skipping(kinds: &[ElementKind]) -> ParseConfig {
+    ParseConfig {
+        skip_elements: kinds.iter().copied().collect(),
+        ..Default::default()
+    }
+}
+
+fn org(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+// whether an element is skipped
+type IsSkipped = fn(&Element) -> bool;
+
+// names of elements, with those matching `skipped` and their descendants
+// replaced by a single `skipped`
+fn names(org: &Org, skipped: impl Fn(&Element) -> bool) -> Vec<&'static str> {
+    let mut depth = 0;
+    let mut names = Vec::new();
+    for event in org.iter() {
+        match event {
+            Event::Start(_) if depth > 0 => depth += 1,
+            Event::End(_) if depth > 0 => depth -= 1,
+            Event::Start(element) if skipped(element) && element.is_container() => {
+                names.push("skipped");
+                depth = 1;
+            }
+            Event::Start(element) if skipped(element) => names.push("skipped"),
+            Event::Start(element) => names.push(element.name()),
+            Event::End(_) => (),
+        }
+    }
+    names
+}
+
+#[test]
+fn skipped_elements() {
+    let config = skipping(&[
+        ElementKind::Drawer,
+        ElementKind::Table,
+        ElementKind::Clock,
+        ElementKind::QuoteBlock,
+    ]);
+    let parsed = Org::parse_custom(TEXT, &config);
+
+    let skipped: Vec<_> = parsed
+        .iter()
+        .filter_map(|event| match event {
+            Event::Start(Element::Skipped(skipped)) => {
+                Some((skipped.kind, &*skipped.raw, skipped.post_blank))
+            }
+            _ => None,
+        })
+        .collect();
+    assert_eq!(
+        skipped,
+        [
+            (
+                ElementKind::Drawer,
+                ":LOGBOOK:\n\
+                 - State \"DONE\"       from \"TODO\"       [2024-05-02 Thu 08:10]\n\
+                 CLOCK: [2024-05-02 Thu 08:00]--[2024-05-02 Thu 08:10] =>  0:10\n\
+                 :END:\n",
+                0
+            ),
+            (
+                ElementKind::Table,
+                "| plant  | liters |\n|--------+--------|\n| cactus |    0.1 |\n#+TBLFM: $2=$2\n",
+                1
+            ),
+            (
+                ElementKind::Clock,
+                "CLOCK: [2024-05-03 Fri 08:00]--[2024-05-03 Fri 08:05] =>  0:05\n",
+                1
+            ),
+            (
+                ElementKind::QuoteBlock,
+                "#+BEGIN_QUOTE\nWater [[https://example.com][gently]].\n#+END_QUOTE\n",
+                0
+            ),
+        ]
+    );
+
+    // nothing inside skipped elements is parsed
+    assert!(!parsed
+        .iter()
+        .any(|event| matches!(event, Event::Start(Element::Link(_)))));
+    let title = parsed.headlines().next().unwrap().title(&parsed);
+    assert_eq!(title.properties.get("ID"), Some("8a3e"));
+
+    // org export writes them unchanged, even the spacing of clocks
+    assert_eq!(org(&parsed), TEXT);
+}
+
+#[test]
+fn other_elements_unchanged() {
+    let full = Org::parse(TEXT);
+
+    let cases: [(&[ElementKind], IsSkipped); 3] = [
+        (&[ElementKind::Drawer], |e| matches!(e, Element::Drawer(_))),
+        (&[ElementKind::QuoteBlock], |e| {
+            matches!(e, Element::QuoteBlock(_))
+        }),
+        (&[ElementKind::Clock, ElementKind::FixedWidth], |e| {
+            matches!(e, Element::Clock(_) | Element::FixedWidth(_))
+        }),
+    ];
+    for (kinds, skipped) in cases {
+        let parsed = Org::parse_custom(TEXT, &skipping(kinds));
+        assert_eq!(names(&parsed, |_| false), names(&full, skipped));
+    }
+}
+
+#[test]
+fn element_type_set() {
+    let mut set = ElementTypeSet::from([ElementKind::Drawer, ElementKind::Table]);
+    assert!(set.contains(ElementKind::Table));
+    set.remove(ElementKind::Table);
+    assert!(!set.contains(ElementKind::Table));
+    assert!(!set.is_empty());
+    assert!(ElementTypeSet::new().is_empty());
+
+    let config = ParseConfig::builder()
+        .skip_element(ElementKind::Drawer)
+        .build()
+        .unwrap();
+    assert_eq!(
+        config.skip_elements,
+        ElementTypeSet::new().with(ElementKind::Drawer)
+    );
+}
@@ -0,0 +1,120 @@
+use orgize::{Element, LineIndex, Org};
+use pretty_assertions::assert_eq;
+
+const TEXT: &str = "#+TITLE: doc\n\
+                    \n\
+                    * TODO Headline *bold*\n\
+                    paragraph with [[https://example.com][link]]\n\
+                    \n\
+                    - item\n\
+                    ** Child\n\
+                    | a | b |\n";
+
+fn kind(org: &Org, node: Option<orgize::indextree::NodeId>) -> String {
+    match node.map(|node| &org[node]) {
+        Some(Element::Headline { level }) => format!("headline {}", level),
+        Some(Element::Title(title)) => format!("title {}", title.raw),
+        Some(Element::Text { value }) => format!("text {:?}", value),
+        Some(element) => format!("{:?}", element)
+            .split(|c: char| !c.is_alphanumeric())
+            .next()
+            .unwrap()
+            .to_string(),
+        None => "none".into(),
+    }
+}
+
+fn offset(pattern: &str) -> usize {
+    TEXT.find(pattern).unwrap()
+}
+
+#[test]
+fn headline_at() {
+    let org = Org::parse(TEXT);
+
+    // before the first headline
+    assert_eq!(kind(&org, org.headline_at(0)), "none");
+    assert_eq!(kind(&org, org.headline_at(13)), "none");
+    // first byte of headlines
+    assert_eq!(kind(&org, org.headline_at(14)), "headline 1");
+    assert_eq!(kind(&org, org.headline_at(offset("- item"))), "headline 1");
+    assert_eq!(
+        kind(&org, org.headline_at(offset("** Child"))),
+        "headline 2"
+    );
+    // the last newline
+    assert_eq!(kind(&org, org.headline_at(TEXT.len() - 1)), "headline 2");
+    assert_eq!(kind(&org, org.headline_at(TEXT.len())), "none");
+}
+
+#[test]
+fn element_at() {
+    let org = Org::parse(TEXT);
+
+    assert_eq!(kind(&org, org.element_at(0)), "Keyword");
+    // blank lines belong to the preceding element
+    assert_eq!(kind(&org, org.element_at(13)), "Keyword");
+    assert_eq!(kind(&org, org.element_at(14)), "title Headline *bold*");
+    assert_eq!(kind(&org, org.element_at(36)), "title Headline *bold*");
+    // first byte of paragraph
+    assert_eq!(kind(&org, org.element_at(37)), "Paragraph");
+    // newline between paragraph and list
+    assert_eq!(kind(&org, org.element_at(offset("\n\n- item"))), "Section");
+    assert_eq!(kind(&org, org.element_at(offset("- item"))), "List");
+    assert_eq!(kind(&org, org.element_at(offset("item\n"))), "Paragraph");
+    assert_eq!(kind(&org, org.element_at(offset("| a"))), "Table");
+    assert_eq!(kind(&org, org.element_at(offset("a |"))), "TableRow");
+}
+
+#[test]
+fn object_at() {
+    let org = Org::parse(TEXT);
+
+    assert_eq!(kind(&org, org.object_at(0)), "none");
+    assert_eq!(kind(&org, org.object_at(offset("*bold"))), "Bold");
+    assert_eq!(kind(&org, org.object_at(offset("bold"))), "text \"bold\"");
+    assert_eq!(kind(&org, org.object_at(37)), "text \"paragraph with \"");
+    assert_eq!(kind(&org, org.object_at(offset("[["))), "Link");
+    assert_eq!(kind(&org, org.object_at(offset("\n\n- item"))), "none");
+    assert_eq!(kind(&org, org.object_at(offset("a |"))), "text \"a\"");
+
+    let link = org.object_at(offset("example")).unwrap();
+    assert_eq!(
+        &TEXT[org.span(link).unwrap()],
+        "[[https://example.com][link]]"
+    );
+}
+
+#[test]
+fn detached_nodes() {
+    let mut org = Org::parse(TEXT);
+
+    let table = org.element_at(offset("| a")).unwrap();
+    assert!(org.element_at(offset("- item")).is_some());
+    table.detach(org.arena_mut());
+
+    // the table's span is kept, but it isn't found anymore
+    assert!(org.span(table).is_some());
+    assert_eq!(kind(&org, org.element_at(offset("| a"))), "Section");
+    assert_eq!(kind(&org, org.object_at(offset("a |"))), "none");
+}
+
+#[test]
+fn parse_string() {
+    let org = Org::parse_string(TEXT.to_string());
+
+    assert_eq!(
+        kind(&org, org.headline_at(offset("** Child"))),
+        "headline 2"
+    );
+    assert_eq!(kind(&org, org.object_at(offset("[["))), "Link");
+}
+
+#[test]
+fn line_index() {
+    let index = LineIndex::new(TEXT);
+
+    assert_eq!(index.line_col(14), Some((2, 0)));
+    assert_eq!(index.offset(2, 0), Some(14));
+    assert_eq!(index.line_col(TEXT.len()), Some((8, 0)));
+}
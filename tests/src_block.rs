@@ -0,0 +1,90 @@
+use orgize::{
+    elements::{NumberLines, SourceBlock},
+    Element, Org,
+};
+use pretty_assertions::assert_eq;
+
+fn src_block<'a>(org: &'a Org) -> &'a SourceBlock<'a> {
+    org.iter()
+        .find_map(|event| match event.element() {
+            Element::SourceBlock(block) => Some(block),
+            _ => None,
+        })
+        .unwrap()
+}
+
+fn to_org(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+#[test]
+fn all_parts() {
+    const TEXT: &str =
+        "#+BEGIN_SRC emacs-lisp -n 10 -r :exports both :results silent\n(+ 1 2)\n#+END_SRC\n";
+
+    let org = Org::parse(TEXT);
+    let block = src_block(&org);
+    assert_eq!(block.language, "emacs-lisp");
+    assert_eq!(block.switches, "-n 10 -r");
+    assert_eq!(block.arguments, ":exports both :results silent");
+    assert_eq!(block.header_args().get("results"), Some("silent"));
+
+    let switches = block.parse_switches();
+    assert_eq!(switches.number_lines(), Some(NumberLines::New(10)));
+    assert!(!switches.retain_labels());
+
+    assert_eq!(to_org(&org), TEXT);
+}
+
+#[test]
+fn header_args_only() {
+    const TEXT: &str = "#+BEGIN_SRC rust :tangle main.rs\nfn main() {}\n#+END_SRC\n";
+
+    let org = Org::parse(TEXT);
+    let block = src_block(&org);
+    assert_eq!(block.language, "rust");
+    assert_eq!(block.switches, "");
+    assert_eq!(block.arguments, ":tangle main.rs");
+    assert!(block.parse_switches().is_empty());
+
+    assert_eq!(to_org(&org), TEXT);
+}
+
+#[test]
+fn quoted_label_format() {
+    const TEXT: &str =
+        "#+BEGIN_SRC python -l \"(ref: %s)\" -i :noweb yes\nx = 1 (ref: one)\n#+END_SRC\n";
+
+    let org = Org::parse(TEXT);
+    let block = src_block(&org);
+    assert_eq!(block.language, "python");
+    assert_eq!(block.switches, "-l \"(ref: %s)\" -i");
+    assert_eq!(block.arguments, ":noweb yes");
+
+    let switches = block.parse_switches();
+    assert_eq!(switches.label_fmt(), Some("(ref: %s)"));
+    assert!(switches.preserve_indent());
+    assert_eq!(switches.number_lines(), None);
+
+    assert_eq!(to_org(&org), TEXT);
+}
+
+#[test]
+fn example_block() {
+    const TEXT: &str = "#+BEGIN_EXAMPLE +n 5 -k\ntext\n#+END_EXAMPLE\n";
+
+    let org = Org::parse(TEXT);
+    let switches = org
+        .iter()
+        .find_map(|event| match event.element() {
+            Element::ExampleBlock(block) => Some(block.switches()),
+            _ => None,
+        })
+        .unwrap();
+    assert_eq!(switches.number_lines(), Some(NumberLines::Continued(5)));
+    assert!(!switches.use_labels());
+
+    assert_eq!(to_org(&org), TEXT);
+}
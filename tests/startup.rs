@@ -0,0 +1,98 @@
+use orgize::{Org, ParseConfig, TodoLog};
+use pretty_assertions::assert_eq;
+
+const TEXT: &str = "#+STARTUP: odd logdone hideblocks\n\
+                    * TODO Project\n\
+                    *** TODO Task\n\
+                    ***** Step\n\
+                    *** Other task\n";
+
+fn to_org(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+#[test]
+fn startup_options() {
+    let org = Org::parse(TEXT);
+    let startup = org.startup();
+    assert!(startup.odd_levels);
+    assert_eq!(startup.log_done, Some(TodoLog::Timestamp));
+    assert!(!startup.custom_time);
+    assert_eq!(startup.other, ["hideblocks"]);
+
+    // later words and keywords win
+    let org = Org::parse("#+STARTUP: odd nologdone\n#+startup: oddeven customtime fold\n");
+    let startup = org.startup();
+    assert!(!startup.odd_levels);
+    assert_eq!(startup.log_done, None);
+    assert!(startup.custom_time);
+    assert_eq!(startup.other, ["fold"]);
+
+    // defaults
+    assert_eq!(
+        Org::parse("* a\n").startup(),
+        &ParseConfig::default().startup
+    );
+}
+
+#[test]
+fn odd_levels() {
+    let org = Org::parse(TEXT);
+    let levels: Vec<_> = org.headlines().map(|hdl| hdl.level()).collect();
+    assert_eq!(levels, [1, 2, 3, 2]);
+    assert_eq!(org.headlines().nth(1).unwrap().title(&org).level, 2);
+
+    // written back with the original stars
+    assert_eq!(to_org(&org), TEXT);
+
+    let mut html = Vec::new();
+    Org::parse("#+STARTUP: odd\n*** Task\n")
+        .write_html(&mut html)
+        .unwrap();
+    assert_eq!(
+        String::from_utf8(html).unwrap(),
+        "<main><h2>Task</h2></main>"
+    );
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn log_done() {
+    use orgize::elements::Timestamp;
+    use std::convert::TryFrom;
+
+    let now = Timestamp::try_from("[2024-03-05 Tue 14:00]").unwrap();
+
+    let mut org = Org::parse(TEXT);
+    let task = org.headlines().nth(1).unwrap();
+    task.complete(&now, &mut org);
+    assert_eq!(
+        to_org(&org),
+        "#+STARTUP: odd logdone hideblocks\n\
+         * TODO Project\n\
+         *** DONE Task\n\
+         CLOSED: [2024-03-05 Tue 14:00]\n\
+         ***** Step\n\
+         *** Other task\n"
+    );
+
+    let mut org = Org::parse("#+STARTUP: lognotedone\n* TODO a\n");
+    let task = org.headlines().next().unwrap();
+    task.complete(&now, &mut org);
+    assert_eq!(
+        to_org(&org),
+        "#+STARTUP: lognotedone\n\
+         * DONE a\n\
+         CLOSED: [2024-03-05 Tue 14:00]\n\
+         :LOGBOOK:\n\
+         - CLOSING NOTE [2024-03-05 Tue 14:00]\n\
+         :END:\n"
+    );
+
+    let mut org = Org::parse("#+STARTUP: nologdone\n* TODO a\n");
+    let task = org.headlines().next().unwrap();
+    task.complete(&now, &mut org);
+    assert_eq!(to_org(&org), "#+STARTUP: nologdone\n* DONE a\n");
+}
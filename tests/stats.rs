@@ -0,0 +1,64 @@
+use orgize::Org;
+use pretty_assertions::assert_eq;
+use std::collections::BTreeMap;
+
+const FIXTURE: &str = include_str!("fixtures/stats.org");
+
+#[test]
+fn fixture() {
+    let org = Org::parse(FIXTURE);
+    let stats = org.stats();
+
+    assert_eq!(
+        stats.headlines,
+        [(1, 3), (2, 4), (3, 3)]
+            .iter()
+            .copied()
+            .collect::<BTreeMap<_, _>>()
+    );
+    // keywords from `#+TODO:` are counted, unused ones included
+    assert_eq!(
+        stats.todo_keywords,
+        [
+            ("TODO", 2),
+            ("NEXT", 1),
+            ("WAIT", 1),
+            ("DONE", 2),
+            ("CANCELLED", 1)
+        ]
+        .iter()
+        .map(|&(k, v)| (k.to_string(), v))
+        .collect::<BTreeMap<_, _>>()
+    );
+    assert_eq!(stats.done, 3);
+    assert_eq!(stats.not_done, 4);
+
+    assert_eq!(stats.elements["org-data"], 1);
+    assert_eq!(stats.elements["headline"], 10);
+    assert_eq!(stats.elements["title"], 10);
+    assert_eq!(stats.elements["keyword"], 2);
+    assert_eq!(stats.elements["link"], 1);
+    assert_eq!(stats.elements["plain-list"], 1);
+    assert_eq!(stats.elements["item"], 2);
+    assert_eq!(stats.elements["table"], 1);
+    assert_eq!(stats.elements["table-row"], 3);
+    assert_eq!(stats.elements["table-cell"], 4);
+    assert!(!stats.elements.contains_key("src-block"));
+
+    assert_eq!(stats.arena_size, stats.elements.values().sum::<usize>());
+}
+
+#[test]
+fn default_keywords() {
+    let org = Org::parse("* TODO a\n* NEXT b\n* DONE c\n");
+    let stats = org.stats();
+
+    assert_eq!(
+        stats.todo_keywords,
+        [("TODO", 1), ("DONE", 1)]
+            .iter()
+            .map(|&(k, v)| (k.to_string(), v))
+            .collect::<BTreeMap<_, _>>()
+    );
+    assert_eq!((stats.done, stats.not_done), (1, 1));
+}
@@ -0,0 +1,126 @@
+use orgize::{Org, ParseConfig, ParseError, Strictness};
+
+const MALFORMED: &str = r#"#+TITLE: malformed
+:ID: not-in-drawer
+
+* Headline 1
+SCHEDULED: tomorrow
+#+BEGIN_QUOTE
+quote
+#+END_SRC
+
+* Headline 2
+:LOGBOOK:
+CLOCK: [2019-10-28 Mon 08:53]
+
+* Headline 3
+#+BEGIN_EXAMPLE
+example
+"#;
+
+fn config(strictness: Strictness) -> ParseConfig {
+    ParseConfig {
+        strictness,
+        ..Default::default()
+    }
+}
+
+fn expected_errors() -> Vec<ParseError> {
+    vec![
+        ParseError::PropertyOutsideDrawer {
+            name: "ID".into(),
+            offset: MALFORMED.find(":ID:").unwrap(),
+        },
+        ParseError::InvalidPlanning {
+            offset: MALFORMED.find("SCHEDULED:").unwrap(),
+        },
+        ParseError::MismatchedBlockEnd {
+            begin: "QUOTE".into(),
+            end: "SRC".into(),
+            offset: MALFORMED.find("#+BEGIN_QUOTE").unwrap(),
+        },
+        ParseError::UnclosedDrawer {
+            name: "LOGBOOK".into(),
+            offset: MALFORMED.find(":LOGBOOK:").unwrap(),
+        },
+        ParseError::UnclosedBlock {
+            name: "EXAMPLE".into(),
+            offset: MALFORMED.find("#+BEGIN_EXAMPLE").unwrap(),
+        },
+    ]
+}
+
+fn to_html(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_html(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+#[test]
+fn lenient() {
+    let config = config(Strictness::Lenient);
+
+    let org = Org::try_parse_custom(MALFORMED, &config).unwrap();
+    assert!(org.diagnostics().is_empty());
+    assert_eq!(to_html(&org), to_html(&Org::parse(MALFORMED)));
+}
+
+#[test]
+fn warn() {
+    let config = config(Strictness::Warn);
+
+    let org = Org::try_parse_custom(MALFORMED, &config).unwrap();
+    assert_eq!(org.diagnostics(), &expected_errors()[..]);
+    // the tree is the same as lenient mode
+    assert_eq!(to_html(&org), to_html(&Org::parse(MALFORMED)));
+
+    let org = Org::parse_string_custom(MALFORMED.to_string(), &config);
+    assert_eq!(org.diagnostics(), &expected_errors()[..]);
+}
+
+#[test]
+fn strict() {
+    let config = config(Strictness::Strict);
+
+    assert_eq!(
+        Org::try_parse_custom(MALFORMED, &config).err(),
+        expected_errors().into_iter().next()
+    );
+    assert_eq!(
+        Org::try_parse_string_custom(MALFORMED.to_string(), &config).err(),
+        expected_errors().into_iter().next()
+    );
+
+    // `parse_custom` never fails
+    let org = Org::parse_custom(MALFORMED, &config);
+    assert_eq!(org.diagnostics(), &expected_errors()[..]);
+}
+
+#[test]
+fn well_formed() {
+    let org = Org::try_parse_custom(
+        r#"
+:PROPERTIES:
+:ID: top-level
+:END:
+
+* TODO Headline
+DEADLINE: <2019-10-28 Mon>
+:PROPERTIES:
+:ID: headline
+:END:
+:LOGBOOK:
+CLOCK: [2019-10-28 Mon 08:53]
+:END:
+#+BEGIN_src rust
+: fixed width
+#+END_SRC
+: fixed width
+:END:
+"#,
+        &config(Strictness::Strict),
+    )
+    .unwrap();
+
+    assert!(org.diagnostics().is_empty());
+}
@@ -0,0 +1,127 @@
+use orgize::{Element, Org, ValidationError};
+use pretty_assertions::assert_eq;
+
+const TEXT: &str = r#"#+TITLE: subtree
+
+* TODO h1 :tag:
+SCHEDULED: <2019-10-28 Mon>
+:PROPERTIES:
+:ID: h1
+:END:
+text
+
+| a | b |
+|---+---|
+| 1 | 2 |
+
+** h1_1
+#+BEGIN_SRC rust
+fn main() {}
+#+END_SRC
+* h2
+"#;
+
+fn to_org(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+#[test]
+fn round_trip() {
+    let mut org = Org::parse(TEXT);
+    let h1 = org.headlines().next().unwrap();
+
+    let subtree = h1.to_org_string(&org);
+    assert_eq!(
+        subtree,
+        &TEXT[TEXT.find("* TODO").unwrap()..TEXT.find("* h2").unwrap()]
+    );
+
+    let h1 = h1.parse_replace(&subtree, false, &mut org).unwrap();
+    assert_eq!(h1.title(&org).raw, "h1");
+    assert_eq!(to_org(&org), TEXT);
+}
+
+#[test]
+fn add_child() {
+    let mut org = Org::parse(TEXT);
+    let h1 = org.headlines().next().unwrap();
+
+    let edited = h1.to_org_string(&org) + "** h1_2\nnew child\n";
+    let h1 = h1.parse_replace(&edited, false, &mut org).unwrap();
+
+    let children: Vec<_> = h1
+        .children(&org)
+        .map(|hdl| hdl.title(&org).raw.to_string())
+        .collect();
+    assert_eq!(children, ["h1_1", "h1_2"]);
+    assert_eq!(
+        org.headlines().last().unwrap().title(&org).raw,
+        "h2",
+        "following headlines are kept"
+    );
+    assert_eq!(
+        to_org(&org),
+        TEXT.replace("* h2\n", "** h1_2\nnew child\n* h2\n")
+    );
+}
+
+#[test]
+fn level_mismatch() {
+    let mut org = Org::parse("* h1\n** h1_1\n** h1_2\n* h2\n");
+    let h1_2 = org.headlines().nth(2).unwrap();
+
+    // h1_2 must be deeper than h1
+    match h1_2.parse_replace("* new\n", false, &mut org) {
+        Err(ValidationError::HeadlineLevelMismatch { range, .. }) => {
+            assert_eq!(range, 2..=2)
+        }
+        _ => panic!("expected level mismatch"),
+    }
+    assert_eq!(to_org(&org), "* h1\n** h1_1\n** h1_2\n* h2\n");
+
+    let new = h1_2
+        .parse_replace("*** new\n**** child\n", true, &mut org)
+        .unwrap();
+    assert_eq!(new.level(), 2);
+    assert_eq!(to_org(&org), "* h1\n** h1_1\n** new\n*** child\n* h2\n");
+}
+
+#[test]
+fn invalid_subtree() {
+    let mut org = Org::parse("* h1\n* h2\n");
+    let h1 = org.headlines().next().unwrap();
+
+    assert!(h1.parse_replace("", false, &mut org).is_err());
+    assert!(h1.parse_replace("text\n* new\n", false, &mut org).is_err());
+    assert!(h1
+        .parse_replace("* new\n* another\n", false, &mut org)
+        .is_err());
+    assert_eq!(to_org(&org), "* h1\n* h2\n");
+}
+
+#[test]
+fn no_leftover_nodes() {
+    let live = |org: &Org| org.arena().iter().filter(|node| !node.is_removed()).count();
+
+    let mut org = Org::parse("* h1\n* h2\n");
+    let h1 = org.headlines().next().unwrap();
+    let before = live(&org);
+    assert!(h1.parse_replace("text\n* new\n", false, &mut org).is_err());
+    assert!(h1.parse_replace("** new\n", false, &mut org).is_ok());
+    assert!(h1
+        .parse_replace("* new\n* another\n", false, &mut org)
+        .is_err());
+
+    // the new headline, its title and text are added, the replaced one is
+    // left detached, and parsed documents are freed
+    assert_eq!(live(&org), before + 3);
+    assert_eq!(
+        org.arena()
+            .iter()
+            .filter(|node| !node.is_removed() && matches!(node.get(), Element::Document { .. }))
+            .count(),
+        1
+    );
+}
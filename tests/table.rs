@@ -0,0 +1,207 @@
+use orgize::{
+    elements::{Table, TableAlignment, TableCell},
+    export::DefaultOrgHandler,
+    indextree::NodeId,
+    Element, Org,
+};
+use pretty_assertions::assert_eq;
+
+fn table_node(org: &Org) -> NodeId {
+    org.arena()
+        .iter()
+        .find(|node| matches!(node.get(), Element::Table(_)))
+        .and_then(|node| org.arena().get_node_id(node))
+        .unwrap()
+}
+
+fn to_html(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_html(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+fn to_org(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+fn to_aligned_org(org: &Org) -> String {
+    let mut writer = Vec::new();
+    let mut handler = DefaultOrgHandler::default();
+    handler.align_tables = true;
+    org.write_org_custom(&mut writer, &mut handler).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+#[test]
+fn alignment() {
+    let org = Org::parse("| <l> | <c> | <r10> |\n| Name | Kind | Count |\n|-\n| a | b | 1 |\n");
+    let table = table_node(&org);
+
+    match &org[table] {
+        Element::Table(Table::Org { columns, .. }) => {
+            assert_eq!(
+                columns.iter().map(|c| c.alignment).collect::<Vec<_>>(),
+                vec![
+                    Some(TableAlignment::Left),
+                    Some(TableAlignment::Center),
+                    Some(TableAlignment::Right)
+                ]
+            );
+            assert_eq!(columns[2].width, Some(10));
+        }
+        _ => unreachable!(),
+    }
+
+    // the alignment row isn't part of the table
+    assert_eq!(org.header_rows(table).count(), 1);
+    assert_eq!(org.body_rows(table).count(), 1);
+
+    let cell = org
+        .body_rows(table)
+        .flat_map(|row| row.children(org.arena()))
+        .last()
+        .unwrap();
+    assert!(matches!(
+        org[cell],
+        Element::TableCell(TableCell::Body {
+            alignment: Some(TableAlignment::Right)
+        })
+    ));
+
+    assert_eq!(
+        to_html(&org),
+        "<main><section><table><thead>\
+         <tr><th class=\"org-left\">Name</th><th class=\"org-center\">Kind</th><th class=\"org-right\">Count</th></tr>\
+         </thead><tbody>\
+         <tr><td class=\"org-left\">a</td><td class=\"org-center\">b</td><td class=\"org-right\">1</td></tr>\
+         </tbody></table></section></main>"
+    );
+
+    assert_eq!(
+        to_org(&org),
+        "| <l> | <c> | <r10> |\n| Name | Kind | Count |\n|-\n| a | b | 1 |\n"
+    );
+}
+
+#[test]
+fn column_groups() {
+    let org = Org::parse("| / | < | > |\n| a | b | c |\n");
+    let table = table_node(&org);
+
+    assert_eq!(org.body_rows(table).count(), 1);
+    assert_eq!(
+        to_html(&org),
+        "<main><section><table>\
+         <colgroup><col></colgroup><colgroup><col><col></colgroup>\
+         <tbody><tr><td>a</td><td>b</td><td>c</td></tr></tbody>\
+         </table></section></main>"
+    );
+    assert_eq!(to_org(&org), "| / | < | > |\n| a | b | c |\n");
+}
+
+#[test]
+fn objects_in_cells() {
+    let org = Org::parse("| *bold* | [[https://example.com][link]] |\n|-\n| /italic/ | =code= |\n");
+
+    assert_eq!(
+        to_html(&org),
+        "<main><section><table><thead>\
+         <tr><th><b>bold</b></th><th><a href=\"https://example.com\">link</a></th></tr>\
+         </thead><tbody>\
+         <tr><td><i>italic</i></td><td><code>code</code></td></tr>\
+         </tbody></table></section></main>"
+    );
+}
+
+#[test]
+fn no_rule() {
+    let org = Org::parse("| a | b |\n| c | d |\n");
+    let table = table_node(&org);
+
+    assert_eq!(org.header_rows(table).count(), 0);
+    assert_eq!(org.body_rows(table).count(), 2);
+    assert_eq!(
+        to_html(&org),
+        "<main><section><table><tbody>\
+         <tr><td>a</td><td>b</td></tr><tr><td>c</td><td>d</td></tr>\
+         </tbody></table></section></main>"
+    );
+}
+
+#[test]
+fn realign() {
+    let org = Org::parse("|name|qty|\n|-\n|apple|3|\n|banana|12|\n|cherry||\n");
+
+    assert_eq!(
+        to_aligned_org(&org),
+        "| name   | qty |\n\
+         |--------+-----|\n\
+         | apple  |   3 |\n\
+         | banana |  12 |\n\
+         | cherry |     |\n"
+    );
+}
+
+#[test]
+fn realign_wide_characters() {
+    let org = Org::parse("| 名前 | a |\n|-\n| りんご | bc |\n| x |\n");
+
+    assert_eq!(
+        to_aligned_org(&org),
+        "| 名前   | a  |\n\
+         |--------+----|\n\
+         | りんご | bc |\n\
+         | x      |    |\n"
+    );
+}
+
+#[test]
+fn realign_width_cookie() {
+    let org = Org::parse("| <6> | <c> |\n| a very long cell | b |\n| short | ccc |\n");
+
+    assert_eq!(
+        to_aligned_org(&org),
+        "| <6>    | <c> |\n\
+         | a ve=> |  b  |\n\
+         | short  | ccc |\n"
+    );
+}
+
+#[test]
+fn untouched() {
+    let text = "  |  a|b  |\n  |--+--|\n  | 1 |  2|\n  |-\n#+TBLFM: $2=$1\n";
+    let org = Org::parse(text);
+    assert_eq!(to_org(&org), text);
+
+    let mut org = Org::parse(text);
+    orgize::elements::Table::recalculate(&mut org);
+    assert_eq!(
+        to_org(&org),
+        "| a | b |\n|--+--|\n| 1 | 1 |\n|-\n#+TBLFM: $2=$1\n"
+    );
+}
+
+#[test]
+fn special_rows_in_place() {
+    let text = "| a | b |\n|-\n| / | < |\n| <l> |  |\n| 1 | 2 |\n#+TBLFM: $2=$1\n";
+    let org = Org::parse(text);
+    assert_eq!(to_org(&org), text);
+    assert_eq!(
+        to_aligned_org(&org),
+        "| a   | b |\n\
+         |-----+---|\n\
+         | /   | < |\n\
+         | <l> |   |\n\
+         | 1   | 2 |\n\
+         #+TBLFM: $2=$1\n"
+    );
+
+    let mut org = Org::parse(text);
+    Table::recalculate(&mut org);
+    assert_eq!(
+        to_org(&org),
+        "| a | b |\n|-\n| / | < |\n| <l> |  |\n| 1 | 1 |\n#+TBLFM: $2=$1\n"
+    );
+}
@@ -0,0 +1,88 @@
+use orgize::{Org, TagConflict};
+use pretty_assertions::assert_eq;
+
+const TEXT: &str = r#"#+TAGS: { @work(w) @home(h) @errand(e) } laptop(l)
+#+TAGS: { Energy : high low }
+#+TAGS: [ Project : Orgize Website ]
+#+TAGS: [ Orgize : Parser Export ]
+* Fix the parser :Parser:laptop:
+* Update the site :Website:
+* Groceries
+"#;
+
+#[test]
+fn exclusive_groups() {
+    let mut org = Org::parse(TEXT);
+    let headlines: Vec<_> = org.headlines().collect();
+
+    assert!(headlines[2]
+        .set_tags(vec!["@errand", "laptop", "low"], &mut org)
+        .is_empty());
+
+    assert_eq!(
+        headlines[2].set_tags(vec!["@work", "high", "@home", "low", "@errand"], &mut org),
+        [
+            TagConflict {
+                group: None,
+                tags: vec!["@work".into(), "@home".into(), "@errand".into()],
+            },
+            TagConflict {
+                group: Some("Energy".into()),
+                tags: vec!["high".into(), "low".into()],
+            },
+        ]
+    );
+    assert_eq!(
+        headlines[2].set_tags(vec!["high", "low"], &mut org)[0].to_string(),
+        "mutually exclusive tags of group `Energy`: high, low"
+    );
+
+    // tags are set anyway
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    assert!(String::from_utf8(writer)
+        .unwrap()
+        .ends_with("* Groceries :high:low:\n"));
+
+    // non-exclusive groups never conflict
+    assert!(headlines[2]
+        .set_tags(vec!["Parser", "Export", "Website"], &mut org)
+        .is_empty());
+}
+
+#[test]
+fn hierarchy() {
+    let org = Org::parse(TEXT);
+    let headlines: Vec<_> = org.headlines().collect();
+
+    assert_eq!(
+        org.tags_in_group("Project"),
+        ["Orgize", "Parser", "Export", "Website"]
+    );
+    assert_eq!(org.tags_in_group("Orgize"), ["Parser", "Export"]);
+    assert_eq!(org.tags_in_group("Energy"), ["high", "low"]);
+    assert!(org.tags_in_group("laptop").is_empty());
+
+    assert!(headlines[0].has_tag("Parser", &org));
+    assert!(!headlines[0].has_tag("Project", &org));
+    assert!(headlines[0].has_tag_in_hierarchy("Project", &org));
+    assert!(headlines[0].has_tag_in_hierarchy("Orgize", &org));
+    assert!(headlines[1].has_tag_in_hierarchy("Project", &org));
+    assert!(!headlines[1].has_tag_in_hierarchy("Orgize", &org));
+    assert!(!headlines[2].has_tag_in_hierarchy("Project", &org));
+
+    let definitions = org.tag_definitions();
+    assert_eq!(definitions.tags.len(), 1);
+    assert_eq!(definitions.groups.len(), 4);
+    assert_eq!(definitions.groups[0].members[2].key, Some('e'));
+}
+
+#[test]
+fn cyclic_hierarchy() {
+    let org = Org::parse("#+TAGS: [ A : B ] [ B : A C ]\n* h :C:\n");
+    let headline = org.headlines().next().unwrap();
+
+    assert_eq!(org.tags_in_group("A"), ["B", "C"]);
+    assert_eq!(org.tags_in_group("B"), ["A", "C"]);
+    assert!(headline.has_tag_in_hierarchy("A", &org));
+}
@@ -0,0 +1,232 @@
+use orgize::{elements::Table, FormulaError, FormulaErrorKind, Org};
+use pretty_assertions::assert_eq;
+
+fn recalculate(text: &str) -> (String, Vec<FormulaError>) {
+    let mut org = Org::parse(text);
+    let errors = Table::recalculate(&mut org);
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    (String::from_utf8(writer).unwrap(), errors)
+}
+
+#[test]
+fn column_formula() {
+    assert_eq!(
+        recalculate(
+            "| a | b | sum | avg |\n\
+             |---+---+-----+-----|\n\
+             | 1 | 2 |     |     |\n\
+             | 3 | 4 |     |     |\n\
+             | 7 | 0 |     |     |\n\
+             #+TBLFM: $3=$1+$2::$4=($1+$2)/2\n"
+        ),
+        (
+            "| a | b | sum | avg |\n\
+             |---+---+-----+-----|\n\
+             | 1 | 2 | 3 | 1.5 |\n\
+             | 3 | 4 | 7 | 3.5 |\n\
+             | 7 | 0 | 7 | 3.5 |\n\
+             #+TBLFM: $3=$1+$2::$4=($1+$2)/2\n"
+                .into(),
+            vec![]
+        )
+    );
+}
+
+#[test]
+fn multiple_lines() {
+    assert_eq!(
+        recalculate(
+            "| 1 |  |  |\n\
+             #+TBLFM: $2=$1*2\n\
+             #+TBLFM: $3=$1*3\n"
+        ),
+        (
+            "| 1 | 2 | 3 |\n\
+             #+TBLFM: $2=$1*2\n\
+             #+TBLFM: $3=$1*3\n"
+                .into(),
+            vec![]
+        )
+    );
+}
+
+#[test]
+fn field_formula() {
+    assert_eq!(
+        recalculate(
+            "| 2 | 0 |\n\
+             | 5 | 0 |\n\
+             #+TBLFM: $2=$1*10::@2$2=@2$1*2\n"
+        ),
+        (
+            "| 2 | 20 |\n\
+             | 5 | 10 |\n\
+             #+TBLFM: $2=$1*10::@2$2=@2$1*2\n"
+                .into(),
+            vec![]
+        )
+    );
+}
+
+#[test]
+fn range_sum() {
+    assert_eq!(
+        recalculate(
+            "| item | count |\n\
+             |------+-------|\n\
+             | a    |     1 |\n\
+             | b    |       |\n\
+             | c    |   2.5 |\n\
+             | sum  |       |\n\
+             #+TBLFM: @5$2=vsum(@2..@4)\n"
+        ),
+        (
+            "| item | count |\n\
+             |------+-------|\n\
+             | a | 1 |\n\
+             | b |  |\n\
+             | c | 2.5 |\n\
+             | sum | 3.5 |\n\
+             #+TBLFM: @5$2=vsum(@2..@4)\n"
+                .into(),
+            vec![]
+        )
+    );
+}
+
+#[test]
+fn format_specifier() {
+    assert_eq!(
+        recalculate(
+            "| 10  |  |\n\
+             | 7.5 |  |\n\
+             | 1   |  |\n\
+             #+TBLFM: $2=$1*0.2;%.2f\n"
+        ),
+        (
+            "| 10 | 2.00 |\n\
+             | 7.5 | 1.50 |\n\
+             | 1 | 0.20 |\n\
+             #+TBLFM: $2=$1*0.2;%.2f\n"
+                .into(),
+            vec![]
+        )
+    );
+}
+
+#[test]
+fn missing_cells() {
+    assert_eq!(
+        recalculate("| 1 |\n| 2 |\n#+TBLFM: $3=$1*3\n"),
+        (
+            "| 1 |  | 3 |\n| 2 |  | 6 |\n#+TBLFM: $3=$1*3\n".into(),
+            vec![]
+        )
+    );
+}
+
+#[test]
+fn errors() {
+    let (output, errors) = recalculate(
+        "| 1 | x |\n\
+         | a |   |\n\
+         #+TBLFM: $2='(+ $1 1)::@2$2=$1+1::@1$2=remote(tbl,@1$1)::@1$1=1/0\n",
+    );
+
+    // fields are left untouched
+    assert_eq!(
+        output,
+        "| 1 | x |\n\
+         | a |   |\n\
+         #+TBLFM: $2='(+ $1 1)::@2$2=$1+1::@1$2=remote(tbl,@1$1)::@1$1=1/0\n"
+    );
+    assert_eq!(
+        errors,
+        vec![
+            FormulaError {
+                formula: "$2='(+ $1 1)".into(),
+                field: None,
+                kind: FormulaErrorKind::Unsupported,
+            },
+            FormulaError {
+                formula: "@2$2=$1+1".into(),
+                field: Some((2, 2)),
+                kind: FormulaErrorKind::NotANumber,
+            },
+            FormulaError {
+                formula: "@1$2=remote(tbl,@1$1)".into(),
+                field: Some((1, 2)),
+                kind: FormulaErrorKind::Undefined,
+            },
+            FormulaError {
+                formula: "@1$1=1/0".into(),
+                field: Some((1, 1)),
+                kind: FormulaErrorKind::DivisionByZero,
+            },
+        ]
+    );
+}
+
+#[test]
+fn constants() {
+    assert_eq!(
+        recalculate(
+            "#+CONSTANTS: pi=3.14159\n\
+             #+CONSTANTS: c=299792458 g=9.81\n\
+             | 2 |  |\n\
+             #+TBLFM: $2=$pi*$1\n"
+        ),
+        (
+            "#+CONSTANTS: pi=3.14159\n\
+             #+CONSTANTS: c=299792458 g=9.81\n\
+             | 2 | 6.28318 |\n\
+             #+TBLFM: $2=$pi*$1\n"
+                .into(),
+            vec![]
+        )
+    );
+
+    let (output, errors) = recalculate(
+        "#+CONSTANTS: pi=3.14159 e=euler\n\
+         | 1 | x | y |\n\
+         #+TBLFM: $2=$nope*$1::$3=$e\n",
+    );
+    assert!(output.contains("| 1 | x | y |"));
+    assert_eq!(
+        errors,
+        vec![
+            FormulaError {
+                formula: "$2=$nope*$1".into(),
+                field: Some((1, 2)),
+                kind: FormulaErrorKind::Undefined,
+            },
+            FormulaError {
+                formula: "$3=$e".into(),
+                field: Some((1, 3)),
+                kind: FormulaErrorKind::NotANumber,
+            },
+        ]
+    );
+}
+
+#[test]
+fn remote() {
+    assert_eq!(
+        recalculate(
+            "#+NAME: prices\n\
+             | apple | 3 |\n\
+             | pear  | 5 |\n\
+             \n\
+             | total |  |\n\
+             #+TBLFM: $2=vsum(remote(prices, @1$2..@>$2))\n"
+        )
+        .0,
+        "#+NAME: prices\n\
+         | apple | 3 |\n\
+         | pear  | 5 |\n\
+         \n\
+         | total | 8 |\n\
+         #+TBLFM: $2=vsum(remote(prices, @1$2..@>$2))\n"
+    );
+}
@@ -0,0 +1,100 @@
+use std::convert::TryFrom;
+
+use orgize::{elements::Timestamp, Element, Event, Org, ParseConfig, ParseError, Strictness};
+use pretty_assertions::assert_eq;
+
+fn timestamps(org: &Org) -> Vec<String> {
+    org.iter()
+        .filter_map(|event| match event {
+            Event::Start(Element::Timestamp(timestamp)) => Some(timestamp.to_string()),
+            _ => None,
+        })
+        .collect()
+}
+
+fn to_org(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+#[test]
+fn single_digit_hours() {
+    const TEXT: &str = "* Call <2024-01-01 Mon 9:05>\n\
+                        SCHEDULED: <2024-01-01 Mon 9:05-9:30>\n\
+                        From [2024-01-01 Mon 9:05]--[2024-01-02 Tue 8:00].\n";
+
+    let org = Org::parse(TEXT);
+    // raw forms are kept
+    assert_eq!(to_org(&org), TEXT);
+    // canonical forms are zero-padded
+    assert_eq!(
+        timestamps(&org),
+        [
+            "<2024-01-01 Mon 09:05>",
+            "[2024-01-01 Mon 09:05]--[2024-01-02 Tue 08:00]"
+        ]
+    );
+
+    let headline = org.headlines().next().unwrap();
+    let scheduled = headline.title(&org).scheduled().unwrap();
+    assert_eq!(scheduled.to_org_string(), "<2024-01-01 Mon 09:05-09:30>");
+}
+
+#[test]
+fn crossing_midnight() {
+    let ts = Timestamp::try_from("<2024-01-01 Mon 23:30-01:00>").unwrap();
+    assert_eq!(ts.raw(), Some("<2024-01-01 Mon 23:30-01:00>"));
+    assert_eq!(ts.to_org_string(), "<2024-01-01 Mon 23:30-01:00>");
+
+    #[cfg(feature = "chrono")]
+    {
+        use chrono::NaiveDate;
+
+        assert_eq!(
+            ts.end_datetime(),
+            NaiveDate::from_ymd_opt(2024, 1, 2).and_then(|d| d.and_hms_opt(1, 0, 0))
+        );
+        assert_eq!(ts.duration().unwrap().to_string(), "1:30");
+
+        let ts = Timestamp::try_from("[2024-01-01 Mon 9:05-17:00]").unwrap();
+        assert_eq!(ts.duration().unwrap().to_string(), "7:55");
+        assert!(Timestamp::try_from("[2024-01-01 Mon 9:05]")
+            .unwrap()
+            .duration()
+            .is_none());
+    }
+}
+
+#[test]
+fn invalid_time() {
+    assert!(Timestamp::try_from("<2024-01-01 Mon 25:00>").is_err());
+    assert!(Timestamp::try_from("<2024-01-01 Mon 10:60>").is_err());
+    assert!(Timestamp::try_from("<2024-01-01 Mon 10:00-24:30>").is_err());
+
+    const TEXT: &str = "* Task\nSCHEDULED: <2024-01-01 Mon 25:00>\nAt <2024-01-01 Mon 25:00>.\n";
+
+    // degrades to text
+    let org = Org::parse(TEXT);
+    assert!(timestamps(&org).is_empty());
+    assert!(org
+        .headlines()
+        .next()
+        .unwrap()
+        .title(&org)
+        .planning
+        .is_none());
+    assert_eq!(to_org(&org), TEXT);
+
+    let config = ParseConfig {
+        strictness: Strictness::Warn,
+        ..Default::default()
+    };
+    let org = Org::parse_custom(TEXT, &config);
+    assert_eq!(
+        org.diagnostics(),
+        [ParseError::InvalidPlanning {
+            offset: TEXT.find("SCHEDULED:").unwrap()
+        }]
+    );
+}
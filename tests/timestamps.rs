@@ -0,0 +1,172 @@
+use orgize::{Matcher, Org, PlanningKind, TimestampContext};
+use pretty_assertions::assert_eq;
+
+const TEXT: &str = r#"Kickoff on <2024-03-01 Fri>.
+
+* TODO Meeting <2024-03-04 Mon 10:00>
+DEADLINE: <2024-03-08 Fri> SCHEDULED: <2024-03-04 Mon>
+:LOGBOOK:
+CLOCK: [2024-03-02 Sat 09:00]--[2024-03-02 Sat 10:30] =>  1:30
+:END:
+:NOTES:
+Moved from [2024-02-28 Wed].
+:END:
+
+* DONE Review
+CLOSED: [2024-03-03 Sun 18:00]
+| date | <%%(diary-float t 4 2)> |
+"#;
+
+fn describe(org: &Org, context: &TimestampContext) -> String {
+    let headline = context
+        .headline()
+        .map(|hdl| hdl.title(org).raw.to_string())
+        .unwrap_or_default();
+    match context {
+        TimestampContext::Planning { kind, .. } => format!("planning {:?} {}", kind, headline),
+        TimestampContext::Clock { .. } => format!("clock {}", headline),
+        TimestampContext::Inline { element, .. } => {
+            let element = format!("{:?}", org[*element]);
+            let element = element
+                .split(|c: char| !c.is_alphanumeric())
+                .next()
+                .unwrap();
+            format!("inline {} {}", element, headline)
+        }
+    }
+}
+
+#[test]
+fn document_order() {
+    let org = Org::parse(TEXT);
+
+    let timestamps: Vec<_> = org
+        .timestamps()
+        .map(|ts| {
+            format!(
+                "{} {} {}",
+                ts.timestamp,
+                if ts.is_active() { "active" } else { "inactive" },
+                describe(&org, &ts.context)
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        timestamps,
+        vec![
+            "<2024-03-01 Fri> active inline Paragraph ",
+            "<2024-03-04 Mon 10:00> active inline Title Meeting <2024-03-04 Mon 10:00>",
+            "<2024-03-08 Fri> active planning Deadline Meeting <2024-03-04 Mon 10:00>",
+            "<2024-03-04 Mon> active planning Scheduled Meeting <2024-03-04 Mon 10:00>",
+            "[2024-03-02 Sat 09:00-10:30] inactive clock Meeting <2024-03-04 Mon 10:00>",
+            "[2024-02-28 Wed] inactive inline Paragraph Meeting <2024-03-04 Mon 10:00>",
+            "[2024-03-03 Sun 18:00] inactive planning Closed Review",
+            "<%%(diary-float t 4 2)> active inline TableRow Review",
+        ]
+    );
+}
+
+#[test]
+fn chronological_order() {
+    let org = Org::parse(TEXT);
+
+    let timestamps: Vec<_> = org
+        .timestamps_sorted()
+        .into_iter()
+        .map(|ts| ts.timestamp.to_string())
+        .collect();
+
+    assert_eq!(
+        timestamps,
+        vec![
+            "[2024-02-28 Wed]",
+            "<2024-03-01 Fri>",
+            "[2024-03-02 Sat 09:00-10:30]",
+            "[2024-03-03 Sun 18:00]",
+            "<2024-03-04 Mon>",
+            "<2024-03-04 Mon 10:00>",
+            "<2024-03-08 Fri>",
+            "<%%(diary-float t 4 2)>",
+        ]
+    );
+
+    assert!(org.timestamps().any(|ts| matches!(
+        ts.context,
+        TimestampContext::Planning {
+            kind: PlanningKind::Closed,
+            ..
+        }
+    )));
+}
+
+#[test]
+fn plain_timestamps() {
+    let text = "* TODO Call Bob <2024-03-05 Tue>\n\
+                * TODO Pay rent DEADLINE: <2024-03-01 Fri>\n\
+                * TODO Renew passport\n:PROPERTIES:\n:ID: passport\n:END:\n\
+                DEADLINE: <2024-04-10 Wed>\n\
+                * TODO Planned\nSCHEDULED: <2024-03-06 Wed>\n\
+                * Notes [2024-03-05 Tue]\n";
+    let org = Org::parse(text);
+
+    // agenda entries: planning lines and plain timestamps, by date
+    let agenda: Vec<_> = org
+        .timestamps_sorted()
+        .into_iter()
+        .filter(|ts| ts.is_active())
+        .map(|ts| {
+            let title = ts.context.headline().unwrap().title(&org).raw.to_string();
+            (ts.timestamp.to_string(), ts.is_plain(), title)
+        })
+        .collect();
+    assert_eq!(
+        agenda,
+        [
+            (
+                "<2024-03-01 Fri>".into(),
+                true,
+                "Pay rent DEADLINE: <2024-03-01 Fri>".into()
+            ),
+            (
+                "<2024-03-05 Tue>".into(),
+                true,
+                "Call Bob <2024-03-05 Tue>".into()
+            ),
+            ("<2024-03-06 Wed>".into(), false, "Planned".into()),
+            ("<2024-04-10 Wed>".into(), true, "Renew passport".into()),
+        ]
+    );
+
+    let first: Vec<_> = org
+        .headlines()
+        .map(|hdl| hdl.first_active_timestamp(&org).map(|ts| ts.to_string()))
+        .collect();
+    assert_eq!(
+        first,
+        [
+            Some("<2024-03-05 Tue>".into()),
+            Some("<2024-03-01 Fri>".into()),
+            Some("<2024-04-10 Wed>".into()),
+            None,
+            None,
+        ]
+    );
+
+    let matched = |query: &str| -> Vec<String> {
+        org.match_headlines(&Matcher::parse(query).unwrap())
+            .into_iter()
+            .map(|hdl| hdl.title(&org).raw.to_string())
+            .collect()
+    };
+    assert_eq!(
+        matched("TIMESTAMP>=\"<2024-03-02>\"&TIMESTAMP<\"<2024-04-01>\""),
+        ["Call Bob <2024-03-05 Tue>"]
+    );
+    assert_eq!(
+        matched("TIMESTAMP=\"<2024-03-01>\""),
+        ["Pay rent DEADLINE: <2024-03-01 Fri>"]
+    );
+    assert_eq!(matched("SCHEDULED<=\"<2024-03-06 Wed>\""), ["Planned"]);
+    assert_eq!(matched("DEADLINE>\"<2024-01-01>\""), Vec::<String>::new());
+}
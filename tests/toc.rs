@@ -0,0 +1,88 @@
+use orgize::Org;
+use pretty_assertions::assert_eq;
+
+const TEXT: &str = r#"* Preface
+:PROPERTIES:
+:UNNUMBERED: t
+:END:
+** Thanks
+* Getting started
+** Installing orgize on your machine
+:PROPERTIES:
+:ALT_TITLE: Installation
+:END:
+** Usage
+* Hidden
+:PROPERTIES:
+:UNNUMBERED: notoc
+:END:
+* Reference
+:PROPERTIES:
+:UNNUMBERED: nil
+:EXPORT_FILE_NAME: reference
+:END:
+Text
+** API
+"#;
+
+#[test]
+fn toc() {
+    let org = Org::parse(TEXT);
+
+    let toc: Vec<_> = org
+        .toc()
+        .iter()
+        .map(|entry| {
+            format!(
+                "{}{} {}",
+                " ".repeat(entry.level - 1),
+                entry.number_string().unwrap_or_else(|| "-".into()),
+                entry.title
+            )
+        })
+        .collect();
+
+    assert_eq!(
+        toc,
+        vec![
+            "- Preface",
+            " - Thanks",
+            "1 Getting started",
+            " 1.1 Installation",
+            " 1.2 Usage",
+            "2 Reference",
+            " 2.1 API",
+        ]
+    );
+}
+
+#[test]
+fn accessors() {
+    let org = Org::parse(TEXT);
+    let headlines: Vec<_> = org.headlines().collect();
+
+    assert!(headlines[0].is_unnumbered(&org));
+    assert!(headlines[1].is_unnumbered(&org));
+    assert!(!headlines[2].is_unnumbered(&org));
+    assert!(!headlines[6].is_unnumbered(&org));
+
+    assert_eq!(headlines[3].alt_title(&org), Some("Installation"));
+    assert_eq!(headlines[2].alt_title(&org), None);
+
+    assert_eq!(headlines[6].export_file_name(&org), Some("reference"));
+    assert_eq!(headlines[0].export_file_name(&org), None);
+}
+
+#[test]
+fn subtree_export() {
+    let org = Org::parse(TEXT);
+    let reference = org.headlines().nth(6).unwrap();
+
+    let (file_name, html) = org.export_subtree_html(reference).unwrap();
+
+    assert_eq!(file_name, Some("reference"));
+    assert_eq!(
+        html,
+        "<h1>Reference</h1><section><p>Text</p></section><h2>API</h2>"
+    );
+}
@@ -0,0 +1,213 @@
+use orgize::{Org, ParseConfig, TodoLog};
+use pretty_assertions::assert_eq;
+
+fn keywords(org: &Org) -> Vec<Option<String>> {
+    org.headlines()
+        .map(|headline| {
+            headline
+                .title(org)
+                .keyword
+                .as_ref()
+                .map(ToString::to_string)
+        })
+        .collect()
+}
+
+const HEADLINES: &str = "* TODO a\n* WAIT b\n* DONE c\n* TODO(t!) d\n* NEXT e\n";
+
+#[test]
+fn config() {
+    let config = ParseConfig {
+        todo_keywords: (
+            vec!["TODO(t!)".into(), "WAIT(w@/!)".into()],
+            vec!["DONE(d)".into()],
+        ),
+        ..Default::default()
+    };
+
+    let org = Org::parse_custom(HEADLINES, &config);
+    assert_eq!(
+        keywords(&org),
+        vec![
+            Some("TODO".into()),
+            Some("WAIT".into()),
+            Some("DONE".into()),
+            None,
+            None
+        ]
+    );
+
+    let todo = config.todo_keyword("TODO").unwrap();
+    assert_eq!(todo.key, Some('t'));
+    assert_eq!(todo.on_entry_log, Some(TodoLog::Timestamp));
+    assert_eq!(todo.on_exit_log, None);
+
+    let wait = config.todo_keyword("WAIT").unwrap();
+    assert_eq!(wait.on_entry_log, Some(TodoLog::Note));
+    assert_eq!(wait.on_exit_log, Some(TodoLog::Timestamp));
+
+    let done = config.todo_keyword("DONE").unwrap();
+    assert_eq!(done.key, Some('d'));
+    assert_eq!(done.on_entry_log, None);
+}
+
+#[test]
+fn in_buffer() {
+    let input = format!(
+        "#+TODO: TODO(t!) WAIT(w@/!) | DONE(d)\n#+seq_todo: NEXT\n{}",
+        HEADLINES
+    );
+    let org = Org::parse(&input);
+    assert_eq!(
+        keywords(&org),
+        vec![
+            Some("TODO".into()),
+            Some("WAIT".into()),
+            Some("DONE".into()),
+            None,
+            Some("NEXT".into())
+        ]
+    );
+
+    // in-buffer keywords are added to the configured ones
+    let org = Org::parse("#+TODO: TASK | FINISHED\n* TODO a\n* TASK b\n* FINISHED c\n");
+    assert_eq!(
+        keywords(&org),
+        vec![
+            Some("TODO".into()),
+            Some("TASK".into()),
+            Some("FINISHED".into())
+        ]
+    );
+
+    // keywords in source and example blocks are ignored
+    let org = Org::parse(
+        "#+BEGIN_SRC org\n#+TODO: TASK\n#+END_SRC\n\
+         #+begin_example\n#+TODO: TASK\n#+end_example\n\
+         * TASK a\n",
+    );
+    assert_eq!(keywords(&org), vec![None]);
+}
+
+#[test]
+fn effective_config() {
+    let org =
+        Org::parse("#+TODO: TODO(t) | DONE(d)\n#+TYP_TODO: KILL\n* KILL a\n* DONE b\n* TODO c\n");
+    assert_eq!(
+        org.config().todo_keywords,
+        (
+            vec!["TODO(t)".to_string()],
+            vec!["DONE(d)".to_string(), "KILL".to_string()]
+        )
+    );
+    assert_eq!(
+        org.headlines().map(|h| h.is_done(&org)).collect::<Vec<_>>(),
+        vec![true, true, false]
+    );
+
+    let config = ParseConfig::gtd();
+    let org = Org::parse_custom("* CANCELLED a\n* WAIT b\n", &config);
+    assert_eq!(org.config(), &config);
+    assert_eq!(
+        org.headlines().map(|h| h.is_done(&org)).collect::<Vec<_>>(),
+        vec![true, false]
+    );
+
+    assert_eq!(Org::new().config(), &ParseConfig::default());
+}
+
+#[cfg(feature = "chrono")]
+fn complete(text: &str, config: &ParseConfig) -> String {
+    use orgize::elements::Timestamp;
+    use std::convert::TryFrom;
+
+    let mut org = Org::parse_custom(text, config);
+    let now = Timestamp::try_from("<2024-03-13 Wed 09:30>").unwrap();
+    let headline = org.headlines().next().unwrap();
+    headline.complete(&now, &mut org);
+
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+#[test]
+#[cfg(feature = "chrono")]
+fn complete_task() {
+    let config = ParseConfig::default();
+    assert_eq!(
+        complete("* TODO task\nSCHEDULED: <2024-03-12 Tue>\ntext\n", &config),
+        "* DONE task\nSCHEDULED: <2024-03-12 Tue> CLOSED: [2024-03-13 Wed 09:30]\ntext\n"
+    );
+    // done headlines are left unchanged
+    assert_eq!(complete("* DONE task\n", &config), "* DONE task\n");
+    // keywords of the document apply
+    assert_eq!(
+        complete("#+TODO: TASK | FINISHED\n* TASK task\n", &config),
+        "#+TODO: TASK | FINISHED\n* DONE task\nCLOSED: [2024-03-13 Wed 09:30]\n"
+    );
+
+    let config = ParseConfig::gtd();
+    assert_eq!(
+        complete("* NEXT task\ntext\n", &config),
+        "* DONE task\nCLOSED: [2024-03-13 Wed 09:30]\n:LOGBOOK:\n\
+         - State \"DONE\"       from \"NEXT\"       [2024-03-13 Wed 09:30]\n\
+         :END:\ntext\n"
+    );
+    assert_eq!(
+        complete(
+            "* WAIT task\n:LOGBOOK:\n- State \"WAIT\"       from \"TODO\"       [2024-03-01 Fri]\n:END:\n",
+            &config
+        ),
+        "* DONE task\nCLOSED: [2024-03-13 Wed 09:30]\n:LOGBOOK:\n\
+         - State \"DONE\"       from \"WAIT\"       [2024-03-13 Wed 09:30]\n\
+         - State \"WAIT\"       from \"TODO\"       [2024-03-01 Fri]\n\
+         :END:\n"
+    );
+}
+
+#[test]
+#[cfg(feature = "chrono")]
+fn complete_repeating_task() {
+    let config = ParseConfig::default();
+    assert_eq!(
+        complete(
+            "* TODO review\nSCHEDULED: <2024-03-11 Mon 10:00 +1w> DEADLINE: <2024-03-15 Fri>\n",
+            &config
+        ),
+        "* TODO review\nSCHEDULED: <2024-03-18 Mon 10:00 +1w> DEADLINE: <2024-03-15 Fri>\n\
+         :PROPERTIES:\n:LAST_REPEAT: [2024-03-13 Wed 09:30]\n:END:\n"
+    );
+
+    let config = ParseConfig::gtd();
+    assert_eq!(
+        complete(
+            "* WAIT review\nDEADLINE: <2024-03-04 Mon ++1w>\n\
+             :PROPERTIES:\n:LAST_REPEAT: [2024-03-04 Mon 18:00]\n:END:\n",
+            &config
+        ),
+        "* WAIT review\nDEADLINE: <2024-03-18 Mon ++1w>\n\
+         :PROPERTIES:\n:LAST_REPEAT: [2024-03-13 Wed 09:30]\n:END:\n\
+         :LOGBOOK:\n- State \"DONE\"       from \"WAIT\"       [2024-03-13 Wed 09:30]\n:END:\n"
+    );
+}
+
+#[test]
+#[cfg(feature = "chrono")]
+fn reopen() {
+    let config = ParseConfig::gtd();
+    let mut org = Org::parse_custom(
+        "* CANCELLED task\nCLOSED: [2024-03-13 Wed 09:30] DEADLINE: <2024-03-15 Fri>\n* NEXT other\n",
+        &config,
+    );
+    for headline in org.headlines().collect::<Vec<_>>() {
+        headline.reopen(&mut org);
+    }
+
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    assert_eq!(
+        String::from_utf8(writer).unwrap(),
+        "* TODO task\nDEADLINE: <2024-03-15 Fri>\n* NEXT other\n"
+    );
+}
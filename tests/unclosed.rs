@@ -0,0 +1,100 @@
+use orgize::{Element, Org, ParseConfig, ParseError, Strictness};
+use pretty_assertions::assert_eq;
+
+fn warn() -> ParseConfig {
+    ParseConfig {
+        strictness: Strictness::Warn,
+        ..Default::default()
+    }
+}
+
+fn outline(org: &Org) -> Vec<String> {
+    org.headlines()
+        .map(|headline| headline.title(org).raw.to_string())
+        .collect()
+}
+
+fn to_org(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+#[test]
+fn unclosed_src_block() {
+    const TEXT: &str = r#"#+BEGIN_SRC rust
+fn main() {}
+
+* Headline 1
+** Headline 2
+* Headline 3
+"#;
+
+    let org = Org::parse_custom(TEXT, &warn());
+
+    assert_eq!(outline(&org), ["Headline 1", "Headline 2", "Headline 3"]);
+    assert_eq!(
+        org.diagnostics(),
+        [ParseError::UnclosedBlock {
+            name: "SRC".into(),
+            offset: 0,
+        }]
+    );
+    // the begin line is kept as a paragraph
+    assert!(org
+        .iter()
+        .all(|event| !matches!(event.element(), Element::SourceBlock(_))));
+    assert_eq!(to_org(&org), TEXT);
+}
+
+#[test]
+fn unclosed_drawer() {
+    const TEXT: &str = r#"* Headline 1
+:LOGBOOK:
+CLOCK: [2019-10-28 Mon 08:53]
+* Headline 2
+* Headline 3
+"#;
+
+    let org = Org::parse_custom(TEXT, &warn());
+
+    assert_eq!(outline(&org), ["Headline 1", "Headline 2", "Headline 3"]);
+    assert_eq!(
+        org.diagnostics(),
+        [ParseError::UnclosedDrawer {
+            name: "LOGBOOK".into(),
+            offset: TEXT.find(":LOGBOOK:").unwrap(),
+        }]
+    );
+    assert_eq!(to_org(&org), TEXT);
+}
+
+#[test]
+fn unclosed_dynamic_block() {
+    const TEXT: &str = r#"* Headline 1
+#+BEGIN: clocktable :scope file
+| Headline | Time |
+* Headline 2
+* Headline 3
+"#;
+
+    let org = Org::parse_custom(TEXT, &warn());
+
+    assert_eq!(outline(&org), ["Headline 1", "Headline 2", "Headline 3"]);
+    assert_eq!(
+        org.diagnostics(),
+        [ParseError::UnclosedDynamicBlock {
+            name: "clocktable".into(),
+            offset: TEXT.find("#+BEGIN:").unwrap(),
+        }]
+    );
+    assert_eq!(
+        org.diagnostics()[0].to_string(),
+        "unclosed dynamic block `clocktable` at 13"
+    );
+    assert_eq!(to_org(&org), TEXT);
+
+    // other keywords named `BEGIN` aren't reported
+    let org = Org::parse_custom("#+BEGIN: 42\n", &warn());
+    assert!(org.diagnostics().is_empty());
+}
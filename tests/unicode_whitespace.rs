@@ -0,0 +1,123 @@
+use orgize::{Org, ParseConfig, ParseError, Strictness};
+use pretty_assertions::assert_eq;
+
+const NBSP: char = '\u{a0}';
+
+fn config(strictness: Strictness) -> ParseConfig {
+    ParseConfig {
+        strictness,
+        ..Default::default()
+    }
+}
+
+fn html(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_html(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+fn nbsp_at(text: &str, before: &str) -> ParseError {
+    ParseError::UnicodeWhitespace {
+        character: NBSP,
+        offset: text.find(before).unwrap() - NBSP.len_utf8(),
+    }
+}
+
+#[test]
+fn after_headline_stars() {
+    let text = "*\u{a0}Not a headline\n";
+
+    // like Emacs, it's a paragraph
+    let org = Org::parse(text);
+    assert_eq!(org.headlines().count(), 0);
+    assert_eq!(
+        html(&org),
+        "<main><section><p>*\u{a0}Not a headline</p></section></main>"
+    );
+    assert_eq!(org.diagnostics(), []);
+
+    let org = Org::parse_custom(text, &config(Strictness::Warn));
+    assert_eq!(org.headlines().count(), 0);
+    assert_eq!(org.diagnostics(), [nbsp_at(text, "Not")]);
+
+    // list bullets too
+    let text = "-\u{a0}a\n1.\u{a0}b\n";
+    let org = Org::parse_custom(text, &config(Strictness::Warn));
+    assert_eq!(
+        html(&org),
+        "<main><section><p>-\u{a0}a\n1.\u{a0}b</p></section></main>"
+    );
+    assert_eq!(org.diagnostics(), [nbsp_at(text, "a"), nbsp_at(text, "b")]);
+}
+
+#[test]
+fn inside_planning_line() {
+    let text = "* Task\nSCHEDULED: <2024-06-03 Mon>\u{a0}DEADLINE: <2024-06-07 Fri>\n";
+
+    // not a planning line, like Emacs
+    for strictness in [Strictness::Lenient, Strictness::Warn] {
+        let org = Org::parse_custom(text, &config(strictness));
+        let task = org.headlines().next().unwrap();
+        assert!(task.title(&org).planning.is_none());
+        assert!(task.section_node().is_some());
+    }
+
+    let org = Org::parse(text);
+    assert_eq!(org.diagnostics(), []);
+
+    // reported instead of an invalid planning line
+    let org = Org::parse_custom(text, &config(Strictness::Warn));
+    assert_eq!(org.diagnostics(), [nbsp_at(text, "DEADLINE")]);
+
+    let text = "* Task\nSCHEDULED:\u{a0}<2024-06-03 Mon>\n";
+    let org = Org::parse_custom(text, &config(Strictness::Strict));
+    assert_eq!(org.diagnostics(), [nbsp_at(text, "<2024")]);
+    assert_eq!(
+        Org::try_parse_custom(text, &config(Strictness::Strict)).err(),
+        Some(nbsp_at(text, "<2024"))
+    );
+}
+
+#[test]
+fn around_tags() {
+    for text in [
+        "* Title\u{a0}:work:\n",
+        "* Title :work:\u{a0}\n",
+        "* TODO\u{a0}Title :work:\n",
+    ] {
+        let org = Org::parse(text);
+        let title = org.headlines().next().unwrap().title(&org);
+        assert!(title.keyword.is_none());
+        assert_eq!(org.diagnostics(), []);
+
+        let org = Org::parse_custom(text, &config(Strictness::Warn));
+        assert_eq!(org.diagnostics().len(), 1);
+    }
+
+    // tags stay in the title, like Emacs
+    let text = "* Title\u{a0}:work:\n";
+    let org = Org::parse_custom(text, &config(Strictness::Warn));
+    let title = org.headlines().next().unwrap().title(&org);
+    assert_eq!(title.raw, "Title\u{a0}:work:");
+    assert!(title.tags.is_empty());
+    assert_eq!(org.diagnostics(), [nbsp_at(text, ":work:")]);
+
+    let text = "* Title :work:\u{a0}\n";
+    let org = Org::parse_custom(text, &config(Strictness::Warn));
+    assert!(org.headlines().next().unwrap().title(&org).tags.is_empty());
+    assert_eq!(org.diagnostics(), [nbsp_at(text, "\n")]);
+
+    // the keyword isn't recognized, but the tags are
+    let text = "* TODO\u{a0}Title :work:\n";
+    let org = Org::parse_custom(text, &config(Strictness::Warn));
+    let title = org.headlines().next().unwrap().title(&org);
+    assert_eq!(title.raw, "TODO\u{a0}Title");
+    assert_eq!(title.tags, ["work"]);
+    assert_eq!(org.diagnostics(), [nbsp_at(text, "Title")]);
+
+    // elsewhere in the title, it's just text
+    let text = "* 10\u{a0}km run :sport:\n";
+    let org = Org::parse_custom(text, &config(Strictness::Warn));
+    assert_eq!(org.headlines().next().unwrap().title(&org).tags, ["sport"]);
+    assert_eq!(org.diagnostics(), []);
+}
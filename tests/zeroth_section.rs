@@ -0,0 +1,105 @@
+use orgize::{
+    elements::{Element, Title},
+    export::{FormatConfig, HtmlDocumentConfig},
+    Headline, Org,
+};
+use pretty_assertions::assert_eq;
+
+const TEXT: &str =
+    "# comment\n:PROPERTIES:\n:ID: doc\n:CATEGORY: notes\n:END:\n#+TITLE: Notes\n\ntext\n";
+
+fn org_string(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_org(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+fn html_string(org: &Org) -> String {
+    let mut writer = Vec::new();
+    org.write_html(&mut writer).unwrap();
+    String::from_utf8(writer).unwrap()
+}
+
+#[test]
+fn accessors() {
+    let org = Org::parse(TEXT);
+    let section = org.zeroth_section();
+
+    assert_eq!(section.section_node(), org.document().section_node());
+    assert_eq!(section.elements(&org).count(), 4);
+    assert_eq!(
+        section
+            .keywords(&org)
+            .map(|kw| &*kw.key)
+            .collect::<Vec<_>>(),
+        ["TITLE"]
+    );
+
+    let drawer = section.property_drawer_node(&org).unwrap();
+    assert!(matches!(&org[drawer], Element::Drawer(drawer) if drawer.name == "PROPERTIES"));
+
+    let properties = section.properties(&org);
+    assert_eq!(properties.get("ID"), Some("doc"));
+    assert_eq!(properties.get("CATEGORY"), Some("notes"));
+
+    // only at the beginning of the section
+    let org = Org::parse("text\n:PROPERTIES:\n:ID: doc\n:END:\n");
+    assert_eq!(org.zeroth_section().property_drawer_node(&org), None);
+    assert!(org.zeroth_section().properties(&org).is_empty());
+}
+
+#[test]
+fn empty_document() {
+    let mut org = Org::parse("");
+    let mut section = org.zeroth_section();
+
+    assert_eq!(section.section_node(), None);
+    assert_eq!(section.elements(&org).count(), 0);
+    assert_eq!(section.keywords(&org).count(), 0);
+    assert!(section.properties(&org).is_empty());
+
+    section.set_content("#+TITLE: t\n", &mut org);
+    assert!(section.section_node().is_some());
+    assert_eq!(section.keywords(&org).count(), 1);
+    assert_eq!(org_string(&org), "#+TITLE: t\n");
+}
+
+#[test]
+fn edit_and_export() {
+    let mut org = Org::parse(TEXT);
+    assert_eq!(org_string(&org), TEXT);
+    assert_eq!(
+        html_string(&org),
+        "<main><section><p>text</p></section></main>"
+    );
+    assert!(org.format(&FormatConfig::default()).ends_with("\ntext\n"));
+    assert!(org.toc().is_empty());
+
+    let mut section = org.zeroth_section();
+    section.set_content("#+TITLE: Edited\n\n*new* text\n", &mut org);
+    assert_eq!(org_string(&org), "#+TITLE: Edited\n\n*new* text\n");
+    assert_eq!(
+        html_string(&org),
+        "<main><section><p><b>new</b> text</p></section></main>"
+    );
+    assert_eq!(org.metadata().title.as_deref(), Some("Edited"));
+
+    let mut writer = Vec::new();
+    org.write_html_document(&mut writer, &HtmlDocumentConfig::default())
+        .unwrap();
+    let html = String::from_utf8(writer).unwrap();
+    assert!(html.contains("<title>Edited</title>"));
+    assert!(html.contains("<p><b>new</b> text</p>"));
+
+    // adds the first headline
+    let headline = Headline::new(
+        Title {
+            raw: "h".into(),
+            ..Default::default()
+        },
+        &mut org,
+    );
+    org.document().append(headline, &mut org).unwrap();
+    assert_eq!(org_string(&org), "#+TITLE: Edited\n\n*new* text\n* h\n");
+    assert_eq!(org.zeroth_section().elements(&org).count(), 2);
+}